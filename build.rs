@@ -1,3 +1,33 @@
+use std::process::Command;
+
 fn main() {
-    embuild::espidf::sysenv::output();
+    // 主机上只编译库目标运行单元测试，不链接ESP-IDF
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("espidf") {
+        embuild::espidf::sysenv::output();
+    }
+
+    // 关于界面显示的git提交，不在git仓库中构建时不设置
+    let hash = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok());
+    if let Some(hash) = hash {
+        println!("cargo:rustc-env=GIT_HASH={}", hash.trim());
+    }
+    // WiFi凭据可以在构建时通过环境变量提供（见wifi.env.example）
+    for var in [
+        "WIFI_SSID",
+        "WIFI_PASS",
+        "WIFI_EAP_METHOD",
+        "WIFI_EAP_IDENTITY",
+        "WIFI_EAP_USERNAME",
+        "WIFI_EAP_PASSWORD",
+    ] {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
 }
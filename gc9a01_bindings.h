@@ -0,0 +1,3 @@
+#include "esp_lcd_panel_vendor.h"
+#include "esp_lcd_io_spi.h"
+#include "esp_lcd_gc9a01.h"
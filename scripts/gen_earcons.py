@@ -0,0 +1,48 @@
+#!/usr/bin/env python3
+"""生成内置提示音（16kHz、16位小端、单声道原始PCM），输出到assets/earcons/"""
+
+import math
+import os
+import struct
+
+SAMPLE_RATE = 16000
+FADE_MS = 5
+OUT_DIR = os.path.join(os.path.dirname(__file__), "..", "assets", "earcons")
+
+
+def tone(freqs, duration_ms, amplitude):
+    """多个频率叠加的音调，首尾淡入淡出"""
+    total = SAMPLE_RATE * duration_ms // 1000
+    fade = SAMPLE_RATE * FADE_MS // 1000
+    samples = []
+    for i in range(total):
+        envelope = min(1.0, min(i, total - 1 - i) / fade)
+        value = sum(math.sin(2 * math.pi * f * i / SAMPLE_RATE) for f in freqs) / len(freqs)
+        samples.append(int(value * amplitude * envelope))
+    return samples
+
+
+def silence(duration_ms):
+    return [0] * (SAMPLE_RATE * duration_ms // 1000)
+
+
+EARCONS = {
+    # 开始聆听：上扬的两个音
+    "listen_start.pcm": tone([880], 60, 10000) + silence(10) + tone([1320], 60, 10000),
+    # 结束聆听：下降的两个音
+    "listen_end.pcm": tone([1320], 60, 10000) + silence(10) + tone([880], 60, 10000),
+    # 错误：低沉的蜂鸣
+    "error.pcm": tone([200, 400, 600], 140, 12000),
+}
+
+
+def main():
+    os.makedirs(OUT_DIR, exist_ok=True)
+    for name, samples in EARCONS.items():
+        with open(os.path.join(OUT_DIR, name), "wb") as f:
+            f.write(struct.pack("<%dh" % len(samples), *samples))
+        print("%s: %d samples" % (name, len(samples)))
+
+
+if __name__ == "__main__":
+    main()
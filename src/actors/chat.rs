@@ -0,0 +1,472 @@
+use std::sync::mpsc::{Receiver, Sender};
+use std::time::Instant;
+
+use anyhow::Result;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use super::spawn;
+use crate::api::{
+    client::ApiClient,
+    endpoints::EndpointConfig,
+    pacing::UploadPacer,
+    pcm_client::{PcmClient, PcmClientConfig},
+    persona::Persona,
+    request::{CancelToken, ChatInput, RequestId, RequestOptions, CHAT_REQUEST_TIMEOUT},
+    types::{ApiError, DeviceCommand, ModelInfo},
+    ApiConfig,
+};
+use crate::error::{Error, ErrorKind};
+use crate::peripherals::resample::Resampler;
+
+/// 语音输入时发送的消息，告诉服务端使用刚上传的语音作为本轮输入
+const VOICE_MESSAGE: &str = "[voice]";
+
+/// 按键说话录音的采样率
+const VOICE_SAMPLE_RATE: u32 = 16000;
+
+/// 语音上传进度每增加这么多个百分点通知一次界面
+const UPLOAD_PROGRESS_STEP: u8 = 5;
+
+#[derive(Debug, Clone)]
+pub enum ChatCommand {
+    /// 发送提示，附带本次请求的编号与取消令牌
+    Prompt(RequestId, ChatInput, CancelToken),
+    /// 获取可用模型列表
+    ListModels,
+    /// 切换模型，None表示使用服务端默认模型
+    SetModel(Option<String>),
+    /// 切换角色
+    SetPersona(Persona),
+    /// 修改服务器地址，pcm_url为None时语音上传使用base_url
+    SetEndpoints {
+        base_url: String,
+        pcm_url: Option<String>,
+    },
+    /// 结束当前会话，下次提示时重新创建
+    NewSession,
+    /// 压缩并上传日志文本
+    UploadLogs(Vec<u8>),
+    /// 上传文件（截屏、调试录音），随下一条消息作为附件发送
+    Attach { path: String, mime: &'static str },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ChatEvent {
+    /// 收到流式回复的一个片段，之后仍会发送完整的`Reply`
+    ReplyDelta(RequestId, String),
+    /// 收到回复
+    Reply(RequestId, String),
+    /// 请求失败
+    Failed(RequestId, Error),
+    /// 超过截止时间未收到回复
+    TimedOut(RequestId),
+    /// 请求被取消
+    Cancelled(RequestId),
+    /// 可用模型列表
+    Models(Vec<ModelInfo>),
+    /// 获取模型列表失败
+    ModelsFailed(Error),
+    /// 服务端从对话中识别出的设备命令（例如设置闹钟）
+    Command(RequestId, DeviceCommand),
+    /// 日志已上传
+    LogsUploaded,
+    /// 上传日志失败
+    LogsUploadFailed(Error),
+}
+
+impl ChatEvent {
+    /// 事件所属的对话请求，与请求无关的事件（模型列表、日志上传）返回None
+    pub fn request_id(&self) -> Option<RequestId> {
+        match self {
+            ChatEvent::ReplyDelta(id, _)
+            | ChatEvent::Reply(id, _)
+            | ChatEvent::Failed(id, _)
+            | ChatEvent::TimedOut(id)
+            | ChatEvent::Cancelled(id)
+            | ChatEvent::Command(id, _) => Some(*id),
+            ChatEvent::Models(_)
+            | ChatEvent::ModelsFailed(_)
+            | ChatEvent::LogsUploaded
+            | ChatEvent::LogsUploadFailed(_) => None,
+        }
+    }
+}
+
+/// 语音上传客户端的配置，未单独设置语音上传地址时使用对话API地址
+fn pcm_client_config(config: &ApiConfig) -> PcmClientConfig {
+    PcmClientConfig {
+        base_url: config
+            .pcm_url
+            .clone()
+            .unwrap_or_else(|| config.base_url.clone()),
+        session_id: String::new(),
+        timeout_secs: config.timeout_secs,
+        sample_rate: config.upload_sample_rate,
+    }
+}
+
+/// 对话actor
+///
+/// 在独立线程中执行阻塞的HTTP请求，会话在第一次发送提示时创建。
+pub struct ChatActor {
+    /// 修改服务器地址时按新地址重新创建客户端
+    config: ApiConfig,
+    client: ApiClient,
+    /// 语音上传客户端
+    pcm_client: PcmClient,
+    /// 语音上传的分块大小与网络状态，在多次上传之间保留
+    pacer: UploadPacer,
+    session_id: Option<String>,
+    /// 创建会话时使用的模型
+    model: Option<String>,
+    /// 创建会话时设置的角色
+    persona: Persona,
+    /// 已上传、等待随下一条消息发送的文件ID
+    attachments: Vec<String>,
+    command_receiver: Receiver<ChatCommand>,
+    app_event_sender: crate::events::EventSender,
+}
+
+impl ChatActor {
+    pub fn new(
+        config: ApiConfig,
+        model: Option<String>,
+        persona: Persona,
+        command_receiver: Receiver<ChatCommand>,
+        app_event_sender: crate::events::EventSender,
+    ) -> Self {
+        Self {
+            client: ApiClient::new(config.clone()),
+            pcm_client: PcmClient::new(pcm_client_config(&config)),
+            config,
+            pacer: UploadPacer::default(),
+            session_id: None,
+            model,
+            persona,
+            attachments: Vec::new(),
+            command_receiver,
+            app_event_sender,
+        }
+    }
+
+    pub fn run(&mut self) {
+        info!("Chat actor started");
+
+        while let Ok(command) = self.command_receiver.recv() {
+            match command {
+                ChatCommand::Prompt(id, input, cancel) => {
+                    let options = RequestOptions::default()
+                        .with_timeout(CHAT_REQUEST_TIMEOUT)
+                        .with_cancel(cancel);
+                    let event = match self.prompt(id, &input, &options) {
+                        Ok(reply) => ChatEvent::Reply(id, reply),
+                        Err(e) => match e.downcast_ref::<ApiError>() {
+                            Some(ApiError::Timeout) => ChatEvent::TimedOut(id),
+                            Some(ApiError::Cancelled) => ChatEvent::Cancelled(id),
+                            _ => {
+                                warn!("Chat request failed: {}", e);
+                                ChatEvent::Failed(id, Error::classify(&e, ErrorKind::Api))
+                            }
+                        },
+                    };
+                    let _ = crate::events::send_chat_event(&self.app_event_sender, event);
+                }
+                ChatCommand::ListModels => {
+                    let event = match self.client.list_models() {
+                        Ok(models) => ChatEvent::Models(models),
+                        Err(e) => {
+                            warn!("List models failed: {}", e);
+                            ChatEvent::ModelsFailed(Error::classify(&e, ErrorKind::Api))
+                        }
+                    };
+                    let _ = crate::events::send_chat_event(&self.app_event_sender, event);
+                }
+                ChatCommand::SetModel(model) => {
+                    // 会话与模型绑定，切换模型后下次提示时重新创建会话
+                    if self.model != model {
+                        info!("Chat model changed: {:?}", model);
+                        self.model = model;
+                        self.session_id = None;
+                    }
+                }
+                ChatCommand::SetPersona(persona) => {
+                    // 系统提示只在创建会话时发送，切换角色后重新创建会话
+                    if self.persona != persona {
+                        info!("Chat persona changed: {}", persona.id());
+                        self.persona = persona;
+                        self.session_id = None;
+                    }
+                }
+                ChatCommand::NewSession => {
+                    info!("Chat session reset");
+                    self.session_id = None;
+                }
+                ChatCommand::UploadLogs(logs) => {
+                    let event = match self.client.upload_logs(&logs) {
+                        Ok(()) => ChatEvent::LogsUploaded,
+                        Err(e) => {
+                            warn!("Upload logs failed: {}", e);
+                            ChatEvent::LogsUploadFailed(Error::classify(&e, ErrorKind::Api))
+                        }
+                    };
+                    let _ = crate::events::send_chat_event(&self.app_event_sender, event);
+                }
+                ChatCommand::SetEndpoints { base_url, pcm_url } => {
+                    // 会话只在原来的服务器上有效
+                    info!("Chat endpoints changed: {} (pcm: {:?})", base_url, pcm_url);
+                    self.config.base_url = base_url;
+                    self.config.pcm_url = pcm_url;
+                    self.client = ApiClient::new(self.config.clone());
+                    self.pcm_client = PcmClient::new(pcm_client_config(&self.config));
+                    self.session_id = None;
+                    // 文件只在上传到的服务器上有效
+                    self.attachments.clear();
+                }
+                ChatCommand::Attach { path, mime } => match self.client.upload_file(&path, mime) {
+                    Ok(file_id) => {
+                        info!("Attachment uploaded: {} -> {}", path, file_id);
+                        self.attachments.push(file_id);
+                    }
+                    Err(e) => warn!("Upload attachment {} failed: {}", path, e),
+                },
+            }
+        }
+
+        info!("Chat actor command channel disconnected, shutting down");
+    }
+
+    fn prompt(
+        &mut self,
+        request: RequestId,
+        input: &ChatInput,
+        options: &RequestOptions,
+    ) -> Result<String> {
+        let session_id = match &self.session_id {
+            Some(id) => id.clone(),
+            None => {
+                let id = self.client.create_session(self.model.as_deref(), options)?;
+                info!("Chat session created: {}", id);
+                self.client.set_persona(&id, self.persona, options)?;
+                self.session_id = Some(id.clone());
+                id
+            }
+        };
+
+        let message = match input {
+            ChatInput::Text(message) => message.as_str(),
+            ChatInput::Voice(samples) => {
+                self.upload_voice(&session_id, samples, options)?;
+                VOICE_MESSAGE
+            }
+        };
+
+        options.check()?;
+        let app_event_sender = self.app_event_sender.clone();
+        let files = (!self.attachments.is_empty()).then(|| self.attachments.clone());
+        let result = self.client.prompt_stream(
+            &session_id,
+            message,
+            files,
+            options,
+            |stage| {
+                let _ = crate::events::send_chat_progress_event(&app_event_sender, stage);
+            },
+            |command| {
+                let _ = crate::events::send_chat_event(
+                    &app_event_sender,
+                    ChatEvent::Command(request, command),
+                );
+            },
+            |delta| {
+                let _ = crate::events::send_chat_event(
+                    &app_event_sender,
+                    ChatEvent::ReplyDelta(request, delta.to_string()),
+                );
+            },
+        );
+
+        // 附件发送成功后清空，失败时留给重试的请求
+        if result.is_ok() {
+            self.attachments.clear();
+        }
+
+        // 会话在服务端失效时，下次请求重新创建
+        if let Err(e) = &result {
+            if matches!(
+                e.downcast_ref::<ApiError>(),
+                Some(ApiError::SessionNotFound)
+            ) {
+                self.session_id = None;
+            }
+        }
+        result
+    }
+
+    /// 通过PcmClient上传语音
+    ///
+    /// 整段语音在一个分块传输的请求中发送，连接在多次上传之间复用。
+    /// 分块大小按每块的写入耗时调整（见`UploadPacer`），跟不上实时速度时
+    /// 发送`AppEvent::NetworkDegraded`提示用户；上传进度通过`AppEvent::UploadProgress`显示在思考界面。
+    /// 每块之间检查取消与超时，服务端要求的采样率与录音不同时逐块重采样。
+    fn upload_voice(
+        &mut self,
+        session_id: &str,
+        samples: &[i16],
+        options: &RequestOptions,
+    ) -> Result<()> {
+        self.pcm_client.set_session_id(session_id.to_string());
+        let sample_rate = self.pcm_client.sample_rate();
+        let mut resampler = Resampler::new(VOICE_SAMPLE_RATE, sample_rate);
+        let mut resampled = Vec::new();
+        let mut upload = self.pcm_client.begin_upload()?;
+        let mut remaining = samples;
+        let mut reported = 0;
+        while !remaining.is_empty() {
+            options.check()?;
+            let (chunk, rest) = remaining.split_at(self.pacer.chunk_samples().min(remaining.len()));
+            remaining = rest;
+            resampled.clear();
+            resampler.process(chunk, &mut resampled);
+
+            let start = Instant::now();
+            upload.write(bytemuck::cast_slice(&resampled))?;
+            if let Some(degraded) =
+                self.pacer
+                    .observe(chunk.len(), VOICE_SAMPLE_RATE, start.elapsed())
+            {
+                warn!("Voice upload network degraded: {}", degraded);
+                let _ =
+                    crate::events::send_network_degraded_event(&self.app_event_sender, degraded);
+            }
+
+            let percent = ((samples.len() - remaining.len()) * 100 / samples.len()) as u8;
+            if percent >= reported + UPLOAD_PROGRESS_STEP || remaining.is_empty() {
+                reported = percent;
+                let _ = crate::events::send_upload_progress_event(&self.app_event_sender, percent);
+            }
+        }
+        options.check()?;
+        upload.finish()?;
+        info!(
+            "Voice uploaded: {} samples at {} Hz",
+            samples.len(),
+            sample_rate
+        );
+        Ok(())
+    }
+}
+
+pub struct ChatActorManager {
+    command_sender: Sender<ChatCommand>,
+    /// 当前请求的取消令牌
+    current: Option<CancelToken>,
+    /// 上一次分配的请求编号
+    last_request: RequestId,
+}
+
+impl ChatActorManager {
+    /// 启动对话线程
+    ///
+    /// # 参数
+    /// * `config` - API配置
+    /// * `model` - 首选模型，None表示使用服务端默认模型
+    /// * `persona` - 对话角色
+    /// * `app_event_sender` - 应用事件发送器
+    pub fn new(
+        config: ApiConfig,
+        model: Option<String>,
+        persona: Persona,
+        app_event_sender: crate::events::EventSender,
+    ) -> Result<Self> {
+        let (command_sender, command_receiver) = std::sync::mpsc::channel::<ChatCommand>();
+
+        spawn::CHAT.spawn(move || {
+            ChatActor::new(config, model, persona, command_receiver, app_event_sender).run();
+        })?;
+
+        Ok(Self {
+            command_sender,
+            current: None,
+            last_request: RequestId::default(),
+        })
+    }
+
+    /// 发送提示，之前未完成的请求会被取消
+    ///
+    /// # 返回值
+    /// 本次请求的编号，结果事件带有同一编号
+    pub fn prompt(&mut self, input: ChatInput) -> Result<RequestId> {
+        self.cancel();
+        let id = RequestId(self.last_request.0.wrapping_add(1));
+        let token = CancelToken::new();
+        self.command_sender
+            .send(ChatCommand::Prompt(id, input, token.clone()))?;
+        self.last_request = id;
+        self.current = Some(token);
+        Ok(id)
+    }
+
+    /// 取消当前请求
+    pub fn cancel(&mut self) {
+        if let Some(token) = self.current.take() {
+            token.cancel();
+        }
+    }
+
+    /// 请求可用模型列表，结果通过`ChatEvent::Models`返回
+    pub fn list_models(&self) -> Result<()> {
+        self.command_sender.send(ChatCommand::ListModels)?;
+        Ok(())
+    }
+
+    /// 切换对话模型
+    pub fn set_model(&self, model: Option<String>) -> Result<()> {
+        self.command_sender.send(ChatCommand::SetModel(model))?;
+        Ok(())
+    }
+
+    /// 切换对话角色
+    pub fn set_persona(&self, persona: Persona) -> Result<()> {
+        self.command_sender.send(ChatCommand::SetPersona(persona))?;
+        Ok(())
+    }
+
+    /// 结束当前会话
+    pub fn new_session(&self) -> Result<()> {
+        self.command_sender.send(ChatCommand::NewSession)?;
+        Ok(())
+    }
+
+    /// 上传日志，结果通过`ChatEvent::LogsUploaded`或`ChatEvent::LogsUploadFailed`返回
+    ///
+    /// # 参数
+    /// * `logs` - 日志文本，通常来自`logring::snapshot`
+    pub fn upload_logs(&self, logs: Vec<u8>) -> Result<()> {
+        self.command_sender.send(ChatCommand::UploadLogs(logs))?;
+        Ok(())
+    }
+
+    /// 上传文件，作为附件随下一条消息发送
+    ///
+    /// # 参数
+    /// * `path` - 文件完整路径
+    /// * `mime` - MIME类型，例如`MIME_BMP`
+    pub fn attach(&self, path: String, mime: &'static str) -> Result<()> {
+        self.command_sender
+            .send(ChatCommand::Attach { path, mime })?;
+        Ok(())
+    }
+
+    /// 修改服务器地址，当前会话随之结束
+    ///
+    /// # 参数
+    /// * `endpoints` - 新的服务器地址
+    pub fn set_endpoints(&self, endpoints: &EndpointConfig) -> Result<()> {
+        self.command_sender.send(ChatCommand::SetEndpoints {
+            base_url: endpoints.base_url().to_string(),
+            pcm_url: endpoints.pcm_url.clone(),
+        })?;
+        Ok(())
+    }
+}
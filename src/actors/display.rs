@@ -0,0 +1,282 @@
+// 显示刷新actor
+//
+// 主线程只在共享帧缓冲区上绘制，刷新时向显示线程发送一条命令后立即返回；
+// 显示线程等待TE信号后取出脏区域，分块复制到DMA缓冲区发送到LCD。
+// 命令队列容量为1：显示线程仍在发送上一帧时不再排队，本次刷新直接跳过，
+// 脏区域在帧缓冲区中继续累积，由下一次刷新一起发送。SPI传输再慢也不会阻塞事件处理。
+// 传输持续失败时也由显示线程重新初始化面板（策略见`PanelRecovery`），复位等待不占用主循环。
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use esp_idf_hal::gpio::AnyInputPin;
+use log::{info, warn};
+
+use super::spawn;
+use crate::boards::SPEC;
+use crate::graphics::{
+    framebuffer::SharedFrameBuffer,
+    panel_recovery::{PanelRecovery, RecoveryAction},
+    primitives::FrameFlusher,
+};
+use crate::hal::{BitmapSink, DisplayDevice, PanelReinit, TransferTicket};
+use crate::metrics;
+use crate::peripherals::st77916::{dma_buffer::DmaBuffer, lcd::TeSync};
+
+/// 每块传输的最大行数
+const FLUSH_CHUNK_ROWS: usize = 20;
+
+/// 等待显示线程空闲的最长时间，远大于整屏刷新时间
+const IDLE_WAIT_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy)]
+pub enum DisplayCommand {
+    /// 把帧缓冲区的脏区域发送到LCD
+    Flush,
+}
+
+/// 显示线程与管理器共享的状态
+#[derive(Default)]
+struct FlushState {
+    /// 已有刷新命令排队或正在发送
+    busy: AtomicBool,
+    /// 因显示线程忙而跳过的刷新次数
+    skipped: AtomicU32,
+    /// 多次重新初始化面板仍未恢复，等待主线程取走并上报
+    panel_failed: AtomicBool,
+}
+
+/// 显示刷新actor
+///
+/// 独占屏幕的位图传输。两个分块缓冲区交替使用：CPU复制一块的同时DMA发送另一块。
+pub struct DisplayActor {
+    port: Box<dyn BitmapSink>,
+    panel: Box<dyn PanelReinit>,
+    recovery: PanelRecovery,
+    framebuffer: SharedFrameBuffer,
+    te_sync: Option<TeSync>,
+    state: Arc<FlushState>,
+    command_receiver: Receiver<DisplayCommand>,
+    chunk_buffers: [DmaBuffer; 2],
+    /// 每个分块缓冲区最近一次传输的凭据
+    chunk_tickets: [Option<TransferTicket>; 2],
+    next_chunk: usize,
+}
+
+impl DisplayActor {
+    fn new(
+        port: Box<dyn BitmapSink>,
+        panel: Box<dyn PanelReinit>,
+        framebuffer: SharedFrameBuffer,
+        te_sync: Option<TeSync>,
+        state: Arc<FlushState>,
+        command_receiver: Receiver<DisplayCommand>,
+    ) -> Result<Self> {
+        // 按长边分配，旋转后不需要重新分配
+        let chunk_len = SPEC.display_width.max(SPEC.display_height) as usize * FLUSH_CHUNK_ROWS;
+        Ok(Self {
+            port,
+            panel,
+            recovery: PanelRecovery::default(),
+            framebuffer,
+            te_sync,
+            state,
+            command_receiver,
+            chunk_buffers: [DmaBuffer::new(chunk_len)?, DmaBuffer::new(chunk_len)?],
+            chunk_tickets: [None, None],
+            next_chunk: 0,
+        })
+    }
+
+    pub fn run(&mut self) {
+        info!("Display actor started");
+
+        while let Ok(command) = self.command_receiver.recv() {
+            match command {
+                DisplayCommand::Flush => {
+                    if let Err(e) = self.flush() {
+                        warn!("LCD刷新失败: {}", e);
+                    }
+                    self.check_panel();
+                    self.state.busy.store(false, Ordering::Release);
+                }
+            }
+        }
+
+        info!("Display actor command channel disconnected, shutting down");
+    }
+
+    /// 检查位图传输是否持续失败，必要时重新初始化面板并标记整屏重绘
+    fn check_panel(&mut self) {
+        let errors = self.port.transfer_errors();
+        match self.recovery.check(errors) {
+            RecoveryAction::None => {}
+            RecoveryAction::Reinit => {
+                warn!(
+                    "LCD连续{}次传输失败，第{}次重新初始化面板",
+                    errors,
+                    self.recovery.attempts()
+                );
+                metrics::increment(metrics::LCD_REINITS, 1);
+                match self.panel.reinit() {
+                    Ok(()) => self.framebuffer.lock().invalidate(),
+                    Err(e) => warn!("LCD重新初始化失败: {}", e),
+                }
+            }
+            RecoveryAction::Report => self.state.panel_failed.store(true, Ordering::Release),
+        }
+    }
+
+    /// 发送脏区域
+    ///
+    /// 最后一块传输可能在返回后仍在进行，下一次使用该缓冲区前会等待。
+    fn flush(&mut self) -> Result<()> {
+        // 启用TE同步时等到垂直消隐期再开始传输，避免画面撕裂
+        if let Some(te) = self.te_sync.as_mut() {
+            te.wait()?;
+        }
+
+        let Some(area) = self.framebuffer.lock().take_dirty() else {
+            return Ok(());
+        };
+
+        let region_width = area.size.width as usize;
+        let rows_per_chunk = (self.chunk_buffers[0].len() / region_width).max(1);
+        let end_y = area.top_left.y + area.size.height as i32;
+
+        let mut y = area.top_left.y;
+        while y < end_y {
+            let rows = rows_per_chunk.min((end_y - y) as usize);
+            let index = self.next_chunk;
+            self.next_chunk = (self.next_chunk + 1) % 2;
+
+            // 缓冲区可能仍在传输上一块数据
+            if let Some(ticket) = self.chunk_tickets[index].take() {
+                self.port.wait_transfer(ticket)?;
+            }
+            let chunk = &mut self.chunk_buffers[index][..rows * region_width];
+            // 只在复制期间持有锁，主线程可以在传输时继续绘制
+            self.framebuffer.lock().copy_rows(&area, y, chunk);
+
+            self.chunk_tickets[index] = Some(self.port.draw_bitmap_async(
+                area.top_left.x,
+                y,
+                area.top_left.x + region_width as i32,
+                y + rows as i32,
+                chunk,
+            )?);
+
+            y += rows as i32;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct DisplayActorManager {
+    command_sender: SyncSender<DisplayCommand>,
+    state: Arc<FlushState>,
+    port: Box<dyn BitmapSink>,
+}
+
+impl DisplayActorManager {
+    /// 启动显示线程（核心与优先级见`spawn::DISPLAY`）
+    ///
+    /// # 参数
+    /// * `device` - 屏幕，显示线程与管理器各持有一个位图传输端口
+    /// * `framebuffer` - 共享帧缓冲区
+    /// * `te_pin` - 面板TE引脚，为None时不做TE同步
+    pub fn new(
+        device: &dyn DisplayDevice,
+        framebuffer: SharedFrameBuffer,
+        te_pin: Option<AnyInputPin>,
+    ) -> Result<Self> {
+        let (command_sender, command_receiver) = mpsc::sync_channel::<DisplayCommand>(1);
+        let state = Arc::new(FlushState::default());
+
+        let actor_port = device.bitmap_sink();
+        let actor_panel = device.panel_reinit();
+        let actor_state = state.clone();
+        spawn::DISPLAY.spawn(move || {
+            // TE中断通知发送给创建它的任务，必须在显示线程中注册
+            let te_sync = te_pin.and_then(|pin| match TeSync::new(pin) {
+                Ok(te) => Some(te),
+                Err(e) => {
+                    warn!("LCD TE同步启用失败: {}", e);
+                    None
+                }
+            });
+            match DisplayActor::new(
+                actor_port,
+                actor_panel,
+                framebuffer,
+                te_sync,
+                actor_state,
+                command_receiver,
+            ) {
+                Ok(mut actor) => actor.run(),
+                Err(e) => warn!("显示线程初始化失败: {}", e),
+            }
+        })?;
+
+        Ok(Self {
+            command_sender,
+            state,
+            port: device.bitmap_sink(),
+        })
+    }
+}
+
+impl FrameFlusher for DisplayActorManager {
+    /// 请求刷新，立即返回
+    ///
+    /// 显示线程仍在发送上一帧时跳过本次请求，脏区域留到下一次刷新。
+    fn request_flush(&self) -> Result<()> {
+        if self.state.busy.swap(true, Ordering::AcqRel) {
+            self.state.skipped.fetch_add(1, Ordering::Relaxed);
+            return Ok(());
+        }
+        match self.command_sender.try_send(DisplayCommand::Flush) {
+            Ok(()) => Ok(()),
+            // busy标记保证队列中最多只有一条命令
+            Err(TrySendError::Full(_)) => Ok(()),
+            Err(TrySendError::Disconnected(_)) => {
+                self.state.busy.store(false, Ordering::Release);
+                anyhow::bail!("显示线程已退出")
+            }
+        }
+    }
+
+    /// 等待显示线程发送完已请求的刷新
+    ///
+    /// 发送面板命令（如修改方向）前调用，避免命令插在像素传输中间。
+    fn wait_idle(&self) -> Result<()> {
+        let start = Instant::now();
+        while self.state.busy.load(Ordering::Acquire) {
+            if start.elapsed() > IDLE_WAIT_TIMEOUT {
+                anyhow::bail!("等待显示线程空闲超时");
+            }
+            thread::yield_now();
+        }
+        self.port.wait_idle()
+    }
+
+    /// 显示线程是否仍在发送上一帧
+    fn is_busy(&self) -> bool {
+        self.state.busy.load(Ordering::Acquire)
+    }
+
+    /// 取出跳过的刷新次数并清零
+    fn take_skipped_frames(&self) -> u32 {
+        self.state.skipped.swap(0, Ordering::Relaxed)
+    }
+
+    /// 多次重新初始化面板后传输是否仍然失败，取出后清除，同一次故障只返回一次true
+    fn take_panel_failure(&self) -> bool {
+        self.state.panel_failed.swap(false, Ordering::AcqRel)
+    }
+}
@@ -0,0 +1,278 @@
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use esp_idf_svc::mdns::{EspMdns, QueryResult};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use super::spawn;
+use crate::api::intercom::{
+    self, IntercomPeer, FRAME_SAMPLES, INTERCOM_PORT, SERVICE_PROTO, SERVICE_TYPE,
+};
+use crate::events::{self, EventSender};
+use crate::peripherals::microphone::tap::AudioTap;
+
+/// 接收超时，也是处理命令与发送麦克风数据的间隔
+const RECEIVE_TIMEOUT: Duration = Duration::from_millis(20);
+
+/// 收到的样本攒够这么多（16kHz下100ms）再交给App播放，吸收网络抖动
+const JITTER_SAMPLES: usize = 1600;
+
+/// 超过这段时间没有新数据时，把攒下的样本立即交给App
+const JITTER_FLUSH: Duration = Duration::from_millis(60);
+
+/// 对讲时最多缓存的麦克风数据块
+const TAP_CAPACITY: usize = 16;
+
+/// 两次查询其他设备的间隔
+const DISCOVERY_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 单次mDNS查询的等待时间
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 单次查询最多返回的设备数
+const MAX_PEERS: usize = 8;
+
+/// 发送给对讲线程的命令
+#[derive(Debug)]
+pub enum IntercomCommand {
+    /// 开始把麦克风数据发送到指定地址
+    StartTalking(SocketAddr),
+    /// 停止发送
+    StopTalking,
+}
+
+/// 对讲线程发给App的事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum IntercomEvent {
+    /// 局域网中的其他设备（不含本机），每次查询后变化时发送
+    Peers(Vec<IntercomPeer>),
+    /// 收到的一段语音
+    Audio(Vec<i16>),
+}
+
+/// 正在进行的发送
+struct Talking {
+    peer: SocketAddr,
+    samples: Receiver<Vec<i16>>,
+    pending: Vec<i16>,
+    seq: u16,
+}
+
+/// 对讲Actor
+///
+/// 在独立线程中收发对讲语音：接收到的数据按序号去掉乱序的旧包，
+/// 攒够`JITTER_SAMPLES`后通过`AppEvent::Intercom`交给App，由扬声器播放；
+/// 按住说话期间从音频分流中取出麦克风数据，按帧发送给对方。
+pub struct IntercomActor {
+    socket: UdpSocket,
+    /// 唤醒词线程分出的麦克风数据
+    tap: AudioTap,
+    command_receiver: Receiver<IntercomCommand>,
+    app_event_sender: EventSender,
+}
+
+impl IntercomActor {
+    pub fn new(
+        tap: AudioTap,
+        command_receiver: Receiver<IntercomCommand>,
+        app_event_sender: EventSender,
+    ) -> Result<Self> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, INTERCOM_PORT))?;
+        socket.set_read_timeout(Some(RECEIVE_TIMEOUT))?;
+        Ok(Self {
+            socket,
+            tap,
+            command_receiver,
+            app_event_sender,
+        })
+    }
+
+    pub fn run(&mut self) {
+        info!("Intercom actor started on port {}", INTERCOM_PORT);
+
+        let mut talking: Option<Talking> = None;
+        let mut incoming = Vec::with_capacity(JITTER_SAMPLES * 2);
+        let mut last_seq: Option<u16> = None;
+        let mut last_packet = Instant::now();
+        let mut packet = vec![0u8; 2048];
+
+        loop {
+            match self.command_receiver.try_recv() {
+                Ok(IntercomCommand::StartTalking(peer)) => {
+                    info!("对讲: 开始发送到{}", peer);
+                    talking = Some(Talking {
+                        peer,
+                        samples: self.tap.attach(TAP_CAPACITY),
+                        pending: Vec::with_capacity(FRAME_SAMPLES * 2),
+                        seq: 0,
+                    });
+                }
+                Ok(IntercomCommand::StopTalking) => {
+                    self.tap.detach();
+                    talking = None;
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => break,
+            }
+
+            if let Some(talking) = talking.as_mut() {
+                self.send_pending(talking);
+            }
+
+            match self.socket.recv_from(&mut packet) {
+                Ok((len, _)) => {
+                    if let Some((seq, samples)) = intercom::decode_packet(&packet[..len]) {
+                        // 对方重新开始说话时序号从0开始，停顿后不再比较序号
+                        let restarted = last_packet.elapsed() >= JITTER_FLUSH;
+                        if restarted || last_seq.map_or(true, |last| intercom::is_newer(seq, last))
+                        {
+                            incoming.extend_from_slice(&samples);
+                            last_seq = Some(seq);
+                        }
+                        last_packet = Instant::now();
+                    }
+                }
+                // 超时是正常情况
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                    ) => {}
+                Err(e) => warn!("对讲接收失败: {}", e),
+            }
+
+            let flush = incoming.len() >= JITTER_SAMPLES
+                || (!incoming.is_empty() && last_packet.elapsed() >= JITTER_FLUSH);
+            if flush {
+                let audio = std::mem::take(&mut incoming);
+                let _ = events::send_intercom_event(
+                    &self.app_event_sender,
+                    IntercomEvent::Audio(audio),
+                );
+            }
+        }
+
+        self.tap.detach();
+        info!("Intercom actor command channel disconnected, shutting down");
+    }
+
+    /// 取出分流的麦克风数据，按帧发送
+    fn send_pending(&self, talking: &mut Talking) {
+        while let Ok(samples) = talking.samples.try_recv() {
+            talking.pending.extend_from_slice(&samples);
+        }
+        let frames = talking.pending.len() / FRAME_SAMPLES;
+        for frame in talking.pending.chunks_exact(FRAME_SAMPLES).take(frames) {
+            let packet = intercom::encode_packet(talking.seq, frame);
+            if let Err(e) = self.socket.send_to(&packet, talking.peer) {
+                warn!("对讲发送失败: {}", e);
+            }
+            talking.seq = talking.seq.wrapping_add(1);
+        }
+        talking.pending.drain(..frames * FRAME_SAMPLES);
+    }
+}
+
+/// 在局域网中广播本机并定期查询其他设备，直到App不再接收事件
+///
+/// # 参数
+/// * `name` - 本机名称，同时作为mDNS主机名与服务实例名
+/// * `app_event_sender` - 应用事件发送器
+fn discovery_loop(name: String, app_event_sender: EventSender) -> Result<()> {
+    let mut mdns = EspMdns::take()?;
+    mdns.set_hostname(&name)?;
+    mdns.set_instance_name(&name)?;
+    mdns.add_service(Some(&name), SERVICE_TYPE, SERVICE_PROTO, INTERCOM_PORT, &[])?;
+    info!("对讲: 已广播为{}", name);
+
+    let mut results = vec![QueryResult::default(); MAX_PEERS];
+    let mut known: Option<Vec<IntercomPeer>> = None;
+    loop {
+        let peers = match mdns.query_ptr(
+            SERVICE_TYPE,
+            SERVICE_PROTO,
+            DISCOVERY_TIMEOUT,
+            MAX_PEERS,
+            &mut results,
+        ) {
+            Ok(count) => peers_from_results(&results[..count], &name),
+            Err(e) => {
+                warn!("对讲: 查询其他设备失败: {}", e);
+                Vec::new()
+            }
+        };
+
+        if known.as_ref() != Some(&peers) {
+            info!("对讲: 发现{}台设备", peers.len());
+            known = Some(peers.clone());
+            if events::send_intercom_event(&app_event_sender, IntercomEvent::Peers(peers)).is_err()
+            {
+                return Ok(());
+            }
+        }
+        std::thread::sleep(DISCOVERY_INTERVAL);
+    }
+}
+
+/// 从mDNS查询结果中取出其他设备的IPv4地址
+fn peers_from_results(results: &[QueryResult], own_name: &str) -> Vec<IntercomPeer> {
+    let mut peers: Vec<IntercomPeer> = results
+        .iter()
+        .filter_map(|result| {
+            let name = result.instance_name.clone()?;
+            let ip = result.addr.iter().find(|addr| addr.is_ipv4())?;
+            (name != own_name).then(|| IntercomPeer {
+                name,
+                addr: SocketAddr::new(*ip, result.port),
+            })
+        })
+        .collect();
+    peers.sort_by(|a, b| a.name.cmp(&b.name));
+    peers.dedup_by(|a, b| a.name == b.name);
+    peers
+}
+
+/// 对讲Actor管理器
+///
+/// 创建时在后台启动收发线程与设备发现线程，WiFi连接后创建。
+pub struct IntercomActorManager {
+    command_sender: Sender<IntercomCommand>,
+}
+
+impl IntercomActorManager {
+    /// 启动对讲
+    ///
+    /// # 参数
+    /// * `name` - 本机在局域网中显示的名称
+    /// * `tap` - 唤醒词线程分出的麦克风数据
+    /// * `app_event_sender` - 应用事件发送器，发现设备与收到语音时发送`AppEvent::Intercom`
+    pub fn new(name: String, tap: AudioTap, app_event_sender: EventSender) -> Result<Self> {
+        let (command_sender, command_receiver) = mpsc::channel();
+        let mut actor = IntercomActor::new(tap, command_receiver, app_event_sender.clone())?;
+
+        spawn::INTERCOM.spawn(move || actor.run())?;
+        spawn::INTERCOM_DISCOVERY.spawn(move || {
+            if let Err(e) = discovery_loop(name, app_event_sender) {
+                warn!("对讲: 设备发现已停止: {}", e);
+            }
+        })?;
+
+        Ok(Self { command_sender })
+    }
+
+    /// 开始把麦克风数据发送给指定设备
+    pub fn start_talking(&self, peer: &IntercomPeer) -> Result<()> {
+        self.command_sender
+            .send(IntercomCommand::StartTalking(peer.addr))?;
+        Ok(())
+    }
+
+    /// 停止发送
+    pub fn stop_talking(&self) -> Result<()> {
+        self.command_sender.send(IntercomCommand::StopTalking)?;
+        Ok(())
+    }
+}
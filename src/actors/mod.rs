@@ -1,2 +1,8 @@
+pub mod chat;
+pub mod display;
+pub mod intercom;
 pub mod motion;
+pub mod spawn;
+pub mod wakeword;
+pub mod weather;
 pub mod wifi;
@@ -1,10 +1,14 @@
-use std::thread;
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    mpsc::{self, Receiver, Sender, TryRecvError},
+    Arc,
+};
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use esp_idf_hal::delay::FreeRtos;
-use esp_idf_hal::gpio::{Gpio10, Gpio11};
-use esp_idf_hal::i2c::I2C0;
 use esp_idf_sys::esp_timer_get_time;
+use serde::{Deserialize, Serialize};
 
 /// 心跳间隔时间（微秒）
 ///
@@ -12,22 +16,61 @@ use esp_idf_sys::esp_timer_get_time;
 /// 设置为5秒（5,000,000微秒）以保持与应用程序的连接活跃。
 const HEARTBEAT_INTERVAL_US: i64 = 5_000_000;
 
+/// 传感器采样间隔（毫秒），计步需要约20Hz的采样率
+const SAMPLE_INTERVAL_MS: u32 = 50;
+
+/// 传感器采样频率（Hz）
+const SAMPLE_RATE_HZ: u32 = 1000 / SAMPLE_INTERVAL_MS;
+
+/// 每隔多少个样本检测一次运动状态（500ms）
+const DETECT_EVERY_SAMPLES: u32 = 10;
+
+/// 自动校准的采样时长
+pub const CALIBRATION_DURATION: Duration = Duration::from_secs(3);
+
+/// 自动校准的采样间隔（毫秒）
+const CALIBRATION_SAMPLE_INTERVAL_MS: u32 = 20;
+
+use super::spawn;
+use crate::api::imu_stream::{ImuSample, ImuStreamConfig, ImuStreamSink};
+use crate::clock;
+use crate::error::{Error, ErrorKind};
+use crate::hal::{MotionSensor, SensorData};
 use crate::peripherals::qmi8658::{
-    driver::QMI8658Driver,
-    motion_detector::{MotionDetector, MotionState},
-    QMI8658_ADDRESS_HIGH,
+    calibration::NoiseCalibrator,
+    motion_detector::{MotionDetector, MotionState, MotionThresholds},
+    pedometer::Pedometer,
 };
 
+/// 运动检测命令
+pub enum MotionCommand {
+    /// 使用新的检测阈值
+    SetThresholds(MotionThresholds),
+    /// 采样静止噪声并自动推算阈值，结果通过`AppEvent::MotionCalibration`返回
+    Calibrate,
+    /// 开始或停止（None）输出原始传感器数据
+    SetStream(Option<ImuStreamSink>),
+}
+
+/// 自动校准结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MotionCalibrationEvent {
+    /// 校准完成，新阈值已生效
+    Finished(MotionThresholds),
+    /// 校准失败（例如校准时设备被移动），原阈值不变
+    Failed(Error),
+}
+
 /// 运动传感器Actor
 ///
 /// 负责在独立线程中运行运动检测逻辑，包括：
-/// - 读取QMI8658传感器数据
+/// - 读取运动传感器数据
 /// - 检测运动状态变化
 /// - 发送运动事件到应用程序事件总线
 /// - 管理心跳机制确保连接活跃
-pub struct MotionActor<'a> {
-    /// QMI8658传感器驱动器实例
-    qmi8658: QMI8658Driver<'a>,
+pub struct MotionActor {
+    /// 运动传感器
+    sensor: Box<dyn MotionSensor>,
     /// 运动检测器，用于分析传感器数据并识别运动模式
     motion_detector: MotionDetector,
     /// 应用程序事件发送器，用于发送运动事件到主事件总线
@@ -36,103 +79,228 @@ pub struct MotionActor<'a> {
     last_state: Option<MotionState>,
     /// 上次发送事件的时间戳（微秒），用于心跳机制
     last_sent_time: i64,
+    /// 命令接收器
+    command_receiver: Receiver<MotionCommand>,
+    /// 计步器
+    pedometer: Pedometer,
+    /// 计步所属的本地日期，时间同步前为None
+    step_day: Option<(i32, u8, u8)>,
+    /// 今日步数，与管理器共享
+    steps_today: Arc<AtomicU32>,
+    /// 调试用原始数据出口
+    stream: Option<ImuStreamSink>,
 }
 
-impl<'a> MotionActor<'a> {
+impl MotionActor {
     /// 创建新的运动传感器Actor实例
     ///
     /// # 参数
-    /// * `i2c` - I2C0外设实例，用于与QMI8658传感器通信
-    /// * `sda` - I2C数据线GPIO引脚（GPIO11）
-    /// * `scl` - I2C时钟线GPIO引脚（GPIO10）
+    /// * `sensor` - 已初始化的运动传感器
     /// * `app_event_sender` - 应用程序事件发送器，用于发送运动事件
-    ///
-    /// # 返回值
-    /// * `Result<Self>` - 成功时返回MotionActor实例，失败时返回错误
-    ///
-    /// # 错误
-    /// 如果QMI8658传感器初始化失败，将返回相应的错误信息
+    /// * `command_receiver` - 命令接收器
+    /// * `steps_today` - 今日步数，由actor更新
     pub fn new(
-        i2c: I2C0,
-        sda: Gpio11,
-        scl: Gpio10,
+        sensor: Box<dyn MotionSensor>,
         app_event_sender: crate::events::EventSender,
-    ) -> Result<Self> {
-        let qmi8658 = QMI8658Driver::new(i2c, sda, scl, QMI8658_ADDRESS_HIGH)?;
+        command_receiver: Receiver<MotionCommand>,
+        steps_today: Arc<AtomicU32>,
+    ) -> Self {
         let motion_detector = MotionDetector::new();
 
-        Ok(Self {
-            qmi8658,
+        Self {
+            sensor,
             motion_detector,
             app_event_sender,
             last_state: None,
             last_sent_time: 0,
-        })
+            command_receiver,
+            pedometer: Pedometer::new(),
+            step_day: None,
+            steps_today,
+            stream: None,
+        }
     }
 
     /// 运行运动检测主循环
     ///
     /// 这是运动传感器Actor的核心方法，在独立线程中运行。
     /// 负责：
-    /// - 定期读取QMI8658传感器数据
+    /// - 定期读取运动传感器数据
+    /// - 计步
     /// - 检测运动状态变化
     /// - 发送运动事件到应用程序事件总线
     /// - 管理心跳机制
     ///
     /// # 循环逻辑
-    /// 1. 读取传感器数据
-    /// 2. 检测运动状态
+    /// 1. 每50ms读取一次传感器数据，输入计步器、跌落与扣放检测，启用数据流时按频率输出
+    /// 2. 每500ms检测一次运动状态（检测器的计数阈值按500ms轮询设计）
     /// 3. 判断是否需要发送事件（状态变化或心跳超时）
     /// 4. 发送事件到应用程序
-    /// 5. 延迟500ms后重复
     ///
     /// # 注意
     /// 此方法包含无限循环，应在独立线程中调用
     pub fn run(&mut self) {
+        let mut tick = 0u32;
         loop {
-            // 读取传感器数据并检测运动
-            match self.qmi8658.read_sensor_data() {
-                Ok(sensor_data) => {
-                    let motion_state = self.motion_detector.detect_motion(&sensor_data);
-
-                    let time = unsafe { esp_timer_get_time() };
-
-                    let should_send = self.last_state != Some(motion_state)
-                        || (time - self.last_sent_time) >= HEARTBEAT_INTERVAL_US;
-
-                    if should_send {
-                        self.last_state = Some(motion_state);
-                        self.last_sent_time = time;
+            match self.command_receiver.try_recv() {
+                Ok(command) => self.handle_command(command),
+                Err(TryRecvError::Empty) => {}
+                // 管理器被丢弃后不再接收命令，检测照常进行
+                Err(TryRecvError::Disconnected) => {}
+            }
 
-                        // 发送运动事件到主事件总线
-                        if let Err(e) =
-                            crate::events::send_motion_event(&self.app_event_sender, motion_state)
-                        {
-                            log::info!("Failed to send motion event: {}", e);
+            // 读取传感器数据，计步并检测运动
+            match self.sensor.read_sensor_data() {
+                Ok(sensor_data) => {
+                    self.count_steps(&sensor_data);
+                    self.detect_drop(&sensor_data);
+                    self.detect_face_down(&sensor_data);
+                    if let Some(stream) = &self.stream {
+                        if tick % stream.decimation == 0 {
+                            stream.push(ImuSample {
+                                t_ms: (unsafe { esp_timer_get_time() } / 1000) as u64,
+                                data: sensor_data,
+                            });
                         }
                     }
+                    if tick % DETECT_EVERY_SAMPLES == 0 {
+                        self.detect(&sensor_data);
+                    }
                 }
                 Err(e) => {
                     log::info!("Sensor read error: {}", e);
                 }
             }
 
-            FreeRtos::delay_ms(500);
+            tick = tick.wrapping_add(1);
+            FreeRtos::delay_ms(SAMPLE_INTERVAL_MS);
+        }
+    }
+
+    /// 检测运动状态，状态变化或心跳超时时发送事件
+    fn detect(&mut self, sensor_data: &SensorData) {
+        let motion_state = self.motion_detector.detect_motion(sensor_data);
+
+        let time = unsafe { esp_timer_get_time() };
+
+        let should_send = self.last_state != Some(motion_state)
+            || (time - self.last_sent_time) >= HEARTBEAT_INTERVAL_US;
+
+        if should_send {
+            self.last_state = Some(motion_state);
+            self.last_sent_time = time;
+
+            // 发送运动事件到主事件总线
+            if let Err(e) = crate::events::send_motion_event(&self.app_event_sender, motion_state) {
+                log::info!("Failed to send motion event: {}", e);
+            }
+        }
+    }
+
+    /// 跌落检测，需要每个样本都调用
+    fn detect_drop(&mut self, sensor_data: &SensorData) {
+        let timestamp_ms = (unsafe { esp_timer_get_time() } / 1000) as u64;
+        if let Some(fall_ms) = self.motion_detector.detect_drop(sensor_data, timestamp_ms) {
+            log::warn!("Drop detected, free fall {}ms", fall_ms);
+            if let Err(e) = crate::events::send_dropped_event(&self.app_event_sender, fall_ms) {
+                log::info!("Failed to send drop event: {}", e);
+            }
+        }
+    }
+
+    /// 扣放检测，需要每个样本都调用
+    fn detect_face_down(&mut self, sensor_data: &SensorData) {
+        let timestamp_ms = (unsafe { esp_timer_get_time() } / 1000) as u64;
+        if let Some(face_down) = self
+            .motion_detector
+            .detect_face_down(sensor_data, timestamp_ms)
+        {
+            log::info!("Face down: {}", face_down);
+            if let Err(e) = crate::events::send_face_down_event(&self.app_event_sender, face_down) {
+                log::info!("Failed to send face down event: {}", e);
+            }
+        }
+    }
+
+    /// 计步，本地日期变化时清零
+    fn count_steps(&mut self, sensor_data: &SensorData) {
+        if let Some(today) = clock::now().map(|t| (t.year, t.month, t.day)) {
+            if self.step_day != Some(today) {
+                if self.step_day.is_some() {
+                    log::info!("Steps yesterday: {}", self.pedometer.steps());
+                    self.pedometer.reset_steps();
+                    self.steps_today.store(0, Ordering::Relaxed);
+                }
+                self.step_day = Some(today);
+            }
+        }
+
+        let magnitude = (sensor_data.accel_x.powi(2)
+            + sensor_data.accel_y.powi(2)
+            + sensor_data.accel_z.powi(2))
+        .sqrt();
+        let now_ms = (unsafe { esp_timer_get_time() } / 1000) as u64;
+        if self.pedometer.update(magnitude, now_ms) {
+            self.steps_today
+                .store(self.pedometer.steps(), Ordering::Relaxed);
+        }
+    }
+
+    fn handle_command(&mut self, command: MotionCommand) {
+        match command {
+            MotionCommand::SetThresholds(thresholds) => {
+                if let Err(e) = self.motion_detector.set_all_thresholds(thresholds) {
+                    log::warn!("Invalid motion thresholds {:?}: {}", thresholds, e);
+                }
+            }
+            MotionCommand::Calibrate => {
+                let event = match self.calibrate() {
+                    Ok(thresholds) => {
+                        log::info!("Motion calibration finished: {:?}", thresholds);
+                        MotionCalibrationEvent::Finished(thresholds)
+                    }
+                    Err(e) => {
+                        log::warn!("Motion calibration failed: {}", e);
+                        MotionCalibrationEvent::Failed(Error::classify(&e, ErrorKind::Sensor))
+                    }
+                };
+                let _ = crate::events::send_motion_calibration_event(&self.app_event_sender, event);
+            }
+            MotionCommand::SetStream(sink) => self.stream = sink,
+        }
+    }
+
+    /// 静止采样`CALIBRATION_DURATION`并应用推算出的阈值
+    fn calibrate(&mut self) -> Result<MotionThresholds> {
+        let mut calibrator = NoiseCalibrator::new();
+        let start = Instant::now();
+        while start.elapsed() < CALIBRATION_DURATION {
+            match self.sensor.read_sensor_data() {
+                Ok(sensor_data) => calibrator.add(&sensor_data),
+                Err(e) => log::info!("Sensor read error: {}", e),
+            }
+            FreeRtos::delay_ms(CALIBRATION_SAMPLE_INTERVAL_MS);
         }
+
+        let thresholds = calibrator.finish()?;
+        self.motion_detector.set_all_thresholds(thresholds)?;
+        self.motion_detector.reset();
+        Ok(thresholds)
     }
 }
 
 /// 运动传感器Actor管理器
 ///
 /// 负责创建和管理运动传感器Actor的生命周期。
-/// 这是一个简化版本，只负责启动后台线程，不提供命令通道。
 ///
 /// # 特点
 /// - 创建时自动启动独立线程运行MotionActor
-/// - 不提供停止或控制机制（适合嵌入式系统的简单需求）
+/// - 通过命令通道调整检测阈值或启动自动校准
 /// - 线程一旦启动将持续运行直到程序结束
 pub struct MotionActorManager {
-    // 简化版本不需要命令通道，只是启动后台线程
+    command_sender: Sender<MotionCommand>,
+    /// 今日步数
+    steps_today: Arc<AtomicU32>,
 }
 
 impl MotionActorManager {
@@ -141,34 +309,69 @@ impl MotionActorManager {
     /// 此方法会立即创建MotionActor实例并在新线程中启动运行。
     ///
     /// # 参数
-    /// * `i2c` - I2C0外设实例，用于与QMI8658传感器通信
-    /// * `sda` - I2C数据线GPIO引脚（GPIO11）
-    /// * `scl` - I2C时钟线GPIO引脚（GPIO10）
+    /// * `sensor` - 已初始化的运动传感器
     /// * `app_event_sender` - 应用程序事件发送器，用于发送运动事件
     ///
     /// # 返回值
     /// * `Result<Self>` - 成功时返回MotionActorManager实例，失败时返回错误
     ///
     /// # 错误
-    /// 如果MotionActor创建失败（通常是传感器初始化失败），将返回相应的错误信息
+    /// 线程创建失败时返回错误
     ///
     /// # 注意
     /// - 此方法会立即启动后台线程
     /// - 线程将持续运行直到程序结束
     /// - 调用者无需手动管理线程生命周期
     pub fn new(
-        i2c: I2C0,
-        sda: Gpio11,
-        scl: Gpio10,
+        sensor: Box<dyn MotionSensor>,
         app_event_sender: crate::events::EventSender,
     ) -> Result<Self> {
         // 先在当前线程创建actor，这样生命周期明确
-        let mut actor = MotionActor::new(i2c, sda, scl, app_event_sender)?;
+        let (command_sender, command_receiver) = mpsc::channel::<MotionCommand>();
+        let steps_today = Arc::new(AtomicU32::new(0));
+        let mut actor = MotionActor::new(
+            sensor,
+            app_event_sender,
+            command_receiver,
+            steps_today.clone(),
+        );
 
-        thread::spawn(move || {
+        spawn::MOTION.spawn(move || {
             actor.run();
-        });
+        })?;
+
+        Ok(Self {
+            command_sender,
+            steps_today,
+        })
+    }
+
+    /// 今日步数（本地时间零点清零）
+    pub fn steps_today(&self) -> u32 {
+        self.steps_today.load(Ordering::Relaxed)
+    }
+
+    /// 按配置开始或停止输出原始传感器数据（调试用）
+    pub fn set_streaming(&self, config: &ImuStreamConfig) -> Result<()> {
+        let sink = if config.enabled {
+            Some(ImuStreamSink::start(config.clone(), SAMPLE_RATE_HZ)?)
+        } else {
+            None
+        };
+        self.command_sender.send(MotionCommand::SetStream(sink))?;
+        Ok(())
+    }
+
+    /// 使用新的检测阈值
+    pub fn set_thresholds(&self, thresholds: MotionThresholds) -> Result<()> {
+        self.command_sender
+            .send(MotionCommand::SetThresholds(thresholds))?;
+        Ok(())
+    }
 
-        Ok(Self {})
+    /// 开始自动校准，设备需静止放置约3秒
+    pub fn calibrate(&self) -> Result<()> {
+        self.command_sender.send(MotionCommand::Calibrate)?;
+        Ok(())
     }
 }
@@ -0,0 +1,146 @@
+// 线程创建配置
+//
+// 各actor线程的栈大小、优先级与所在核心集中在这里定义：
+// - 音频采集与唤醒词检测固定在核心1，优先级最高，不与SPI刷新争抢CPU
+// - 显示刷新固定在核心0，与主循环在同一核心
+// - 其余网络类线程不绑定核心，由调度器分配
+
+use std::thread::{self, JoinHandle};
+
+use anyhow::Result;
+use esp_idf_hal::cpu::Core;
+use esp_idf_hal::task::thread::ThreadSpawnConfiguration;
+
+/// 线程创建配置
+#[derive(Debug, Clone, Copy)]
+pub struct ThreadSpawnConfig {
+    /// 任务名，必须以`\0`结尾
+    pub name: &'static [u8],
+    /// 栈大小（字节）
+    pub stack_size: usize,
+    /// FreeRTOS优先级，数值越大越优先（主任务为1，默认为5）
+    pub priority: u8,
+    /// 绑定的核心，为None时不绑定
+    pub core: Option<Core>,
+}
+
+/// 音频采集：高于所有其他线程，避免I2S溢出
+pub const AUDIO_CAPTURE: ThreadSpawnConfig = ThreadSpawnConfig {
+    name: b"audio_capture\0",
+    stack_size: 4 * 1024,
+    priority: 20,
+    core: Some(Core::Core1),
+};
+
+/// 唤醒词检测：与音频采集在同一核心，消费采集缓冲区
+pub const WAKEWORD: ThreadSpawnConfig = ThreadSpawnConfig {
+    name: b"wakeword_actor\0",
+    stack_size: 16 * 1024,
+    priority: 10,
+    core: Some(Core::Core1),
+};
+
+/// 显示刷新：高于主循环，保证请求的刷新尽快发送
+pub const DISPLAY: ThreadSpawnConfig = ThreadSpawnConfig {
+    name: b"display_actor\0",
+    stack_size: 8 * 1024,
+    priority: 6,
+    core: Some(Core::Core0),
+};
+
+/// 运动检测：按固定频率采样
+pub const MOTION: ThreadSpawnConfig = ThreadSpawnConfig {
+    name: b"motion_actor\0",
+    stack_size: 8 * 1024,
+    priority: 6,
+    core: None,
+};
+
+/// 按键扫描
+pub const BUTTON: ThreadSpawnConfig = ThreadSpawnConfig {
+    name: b"button_actor\0",
+    stack_size: 6 * 1024,
+    priority: 6,
+    core: None,
+};
+
+/// 对话请求
+pub const CHAT: ThreadSpawnConfig = ThreadSpawnConfig {
+    name: b"chat_actor\0",
+    stack_size: 32 * 1024,
+    priority: 5,
+    core: None,
+};
+
+/// WiFi连接与扫描
+pub const WIFI: ThreadSpawnConfig = ThreadSpawnConfig {
+    name: b"wifi_actor\0",
+    stack_size: 64 * 1024,
+    priority: 5,
+    core: None,
+};
+
+/// 天气获取
+pub const WEATHER: ThreadSpawnConfig = ThreadSpawnConfig {
+    name: b"weather_actor\0",
+    stack_size: 16 * 1024,
+    priority: 5,
+    core: None,
+};
+
+/// 对讲收发：每20ms收发一次，高于其他网络线程
+pub const INTERCOM: ThreadSpawnConfig = ThreadSpawnConfig {
+    name: b"intercom_actor\0",
+    stack_size: 8 * 1024,
+    priority: 7,
+    core: None,
+};
+
+/// 对讲设备发现（mDNS）
+pub const INTERCOM_DISCOVERY: ThreadSpawnConfig = ThreadSpawnConfig {
+    name: b"intercom_mdns\0",
+    stack_size: 8 * 1024,
+    priority: 4,
+    core: None,
+};
+
+/// 事件回放（`event-trace`特性），大部分时间在等待下一个事件
+pub const EVENT_REPLAY: ThreadSpawnConfig = ThreadSpawnConfig {
+    name: b"event_replay\0",
+    stack_size: 8 * 1024,
+    priority: 4,
+    core: None,
+};
+
+/// 屏幕镜像（`display-mirror`特性），每秒只编码发送几帧
+pub const DISPLAY_MIRROR: ThreadSpawnConfig = ThreadSpawnConfig {
+    name: b"display_mirror\0",
+    stack_size: 8 * 1024,
+    priority: 3,
+    core: None,
+};
+
+impl ThreadSpawnConfig {
+    /// 按配置创建线程
+    ///
+    /// 创建后恢复默认配置，避免影响之后用`thread::Builder`创建的线程。
+    pub fn spawn<F, T>(&self, f: F) -> Result<JoinHandle<T>>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        ThreadSpawnConfiguration {
+            name: Some(self.name),
+            stack_size: self.stack_size,
+            priority: self.priority,
+            pin_to_core: self.core,
+            ..Default::default()
+        }
+        .set()?;
+
+        let spawned = thread::Builder::new().stack_size(self.stack_size).spawn(f);
+
+        ThreadSpawnConfiguration::default().set()?;
+        Ok(spawned?)
+    }
+}
@@ -0,0 +1,570 @@
+use std::ffi::CStr;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::{self, Receiver, Sender, TryRecvError},
+    Arc, Mutex,
+};
+use std::time::Duration;
+
+use anyhow::Result;
+use esp_idf_sys::sr::{
+    afe_config_free, afe_config_init, afe_mode_t_AFE_MODE_HIGH_PERF,
+    afe_ns_mode_t_AFE_NS_MODE_WEBRTC, afe_type_t_AFE_TYPE_SR, esp_afe_handle_from_config,
+    esp_afe_sr_data_t, esp_afe_sr_iface_t, esp_srmodel_init, srmodel_list_t,
+    wakenet_state_t_WAKENET_DETECTED,
+};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use super::spawn;
+use crate::events::{self, EventSender};
+use crate::metrics;
+use crate::peripherals::microphone::{
+    activity::{NoiseFloor, SoundActivity},
+    dsp::{self, RmsComparison},
+    preroll::PreRoll,
+    recorder::AudioRecorder,
+    ring_buffer::RingConsumer,
+    tap::AudioTap,
+    utterance::UtteranceBuffer,
+};
+use crate::peripherals::speaker::i2s_speaker::PlaybackReference;
+
+/// 等待一个feed块的最长时间
+const FEED_READ_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// 处理前后电平对比的统计窗口（16kHz下1秒）
+const LEVEL_WINDOW_SAMPLES: usize = 16000;
+
+/// 唤醒词预录缓冲的长度（16kHz下1.5秒）
+const PRE_ROLL_SAMPLES: usize = 24000;
+
+/// 唤醒词模型名称的前缀（esp-sr约定，如"wn9_hiesp"）
+const WAKENET_PREFIX: &str = "wn";
+
+/// 检测阈值的可调范围，低于下限误唤醒过多，esp-sr不接受1.0
+pub const MIN_WAKE_THRESHOLD: f32 = 0.4;
+pub const MAX_WAKE_THRESHOLD: f32 = 0.9999;
+
+/// 从模型默认阈值开始调节时的起点
+pub const DEFAULT_WAKE_THRESHOLD: f32 = 0.5;
+
+/// 唤醒词设置
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WakeWordConfig {
+    /// 唤醒词模型名称（如"wn9_hiesp"），None表示使用模型分区中的第一个
+    pub model: Option<String>,
+    /// 检测阈值，越高越不容易误唤醒，None表示使用模型自带的阈值
+    pub threshold: Option<f32>,
+}
+
+/// 唤醒词的显示名称，去掉模型名称中的版本前缀："wn9_hiesp" → "hiesp"
+pub fn keyword_name(model: &str) -> &str {
+    model.split_once('_').map_or(model, |(_, keyword)| keyword)
+}
+
+/// 从模型分区的模型名称中筛选唤醒词模型
+pub fn wakenet_models<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    names
+        .into_iter()
+        .filter(|name| name.starts_with(WAKENET_PREFIX))
+        .map(str::to_string)
+        .collect()
+}
+
+/// 设置界面中的唤醒词选项，第一项为默认（None，使用模型分区中的第一个），之后为各个模型
+///
+/// # 参数
+/// * `models` - 可用的唤醒词模型
+pub fn model_choices(models: &[String]) -> Vec<Option<String>> {
+    std::iter::once(None)
+        .chain(models.iter().cloned().map(Some))
+        .collect()
+}
+
+/// 发送给唤醒词检测线程的命令
+#[derive(Debug)]
+pub enum WakeWordCommand {
+    /// 切换模型或阈值，模型变化时重新创建AFE
+    Configure(WakeWordConfig),
+}
+
+/// 运行中的AFE实例，释放时销毁
+struct AfePipeline {
+    handle: *const esp_afe_sr_iface_t,
+    data: *mut esp_afe_sr_data_t,
+    feed_size: usize,
+    feed_nch: usize,
+}
+
+impl AfePipeline {
+    /// 按设置创建AFE
+    ///
+    /// # 参数
+    /// * `models` - 模型分区中的模型列表
+    /// * `config` - 唤醒词设置，找不到指定模型时使用默认模型
+    /// * `aec` - 是否启用回声消除（输入包含播放参考通道）
+    /// * `noise_suppression` - 是否启用降噪
+    unsafe fn new(
+        models: *mut srmodel_list_t,
+        config: &WakeWordConfig,
+        aec: bool,
+        noise_suppression: bool,
+    ) -> Result<Self> {
+        // M: 麦克风通道，R: 播放参考通道
+        let input_format = if aec { c"MR" } else { c"M" };
+        let cfg = afe_config_init(
+            input_format.as_ptr(),
+            models,
+            afe_type_t_AFE_TYPE_SR,
+            afe_mode_t_AFE_MODE_HIGH_PERF,
+        );
+        if cfg.is_null() {
+            anyhow::bail!("AFE配置初始化失败");
+        }
+        (*cfg).aec_init = aec;
+        // WebRTC降噪不需要额外的模型分区
+        (*cfg).ns_init = noise_suppression;
+        (*cfg).afe_ns_mode = afe_ns_mode_t_AFE_NS_MODE_WEBRTC;
+
+        // 模型名称指向模型列表中的字符串，与列表的生命周期相同
+        if let Some(model) = &config.model {
+            match find_model(models, model) {
+                Some(name) => (*cfg).wakenet_model_name = name,
+                None => log::warn!("模型分区中没有唤醒词模型{}，使用默认模型", model),
+            }
+        }
+        let model_name = if (*cfg).wakenet_model_name.is_null() {
+            "<无>".to_string()
+        } else {
+            CStr::from_ptr((*cfg).wakenet_model_name)
+                .to_string_lossy()
+                .into_owned()
+        };
+        info!(
+            "AFE: 唤醒词{}, 回声消除{}, 降噪{}",
+            model_name,
+            if aec { "开" } else { "关" },
+            if noise_suppression { "开" } else { "关" }
+        );
+
+        let handle = esp_afe_handle_from_config(cfg);
+        let data = (*handle).create_from_config.unwrap()(cfg);
+        afe_config_free(cfg);
+        if data.is_null() {
+            anyhow::bail!("AFE创建失败");
+        }
+        let feed_size = (*handle).get_feed_chunksize.unwrap()(data);
+        let fetch_size = (*handle).get_fetch_chunksize.unwrap()(data);
+        let feed_nch = (*handle).get_feed_channel_num.unwrap()(data);
+        println!(
+            "Starting Wakeup. feed_nch: {}, feed_size: {}, fetch_size: {}, buffer_size: {}",
+            feed_nch,
+            feed_size,
+            fetch_size,
+            feed_size * feed_nch
+        );
+
+        let pipeline = Self {
+            handle,
+            data,
+            feed_size: feed_size as usize,
+            feed_nch: feed_nch as usize,
+        };
+        pipeline.set_threshold(config.threshold);
+        Ok(pipeline)
+    }
+
+    /// 设置第一个唤醒词模型的检测阈值，None恢复模型自带的阈值
+    unsafe fn set_threshold(&self, threshold: Option<f32>) {
+        // esp-sr中唤醒词模型的序号从1开始
+        let result = match threshold {
+            Some(threshold) => (*self.handle).set_wakenet_threshold.unwrap()(
+                self.data,
+                1,
+                threshold.clamp(MIN_WAKE_THRESHOLD, MAX_WAKE_THRESHOLD),
+            ),
+            None => (*self.handle).reset_wakenet_threshold.unwrap()(self.data, 1),
+        };
+        if result < 0 {
+            log::warn!("设置唤醒词阈值失败: {}", result);
+        } else {
+            info!("唤醒词阈值: {:?}", threshold);
+        }
+    }
+}
+
+impl Drop for AfePipeline {
+    fn drop(&mut self) {
+        unsafe {
+            if let Some(destroy) = (*self.handle).destroy {
+                destroy(self.data);
+            }
+        }
+    }
+}
+
+/// 模型列表中的所有模型名称
+unsafe fn model_names(models: *mut srmodel_list_t) -> Vec<String> {
+    if models.is_null() {
+        return Vec::new();
+    }
+    (0..(*models).num as isize)
+        .filter_map(|i| {
+            let name = *(*models).model_name.offset(i);
+            (!name.is_null()).then(|| CStr::from_ptr(name).to_string_lossy().into_owned())
+        })
+        .collect()
+}
+
+/// 在模型列表中查找指定名称，返回列表中的字符串指针
+unsafe fn find_model(models: *mut srmodel_list_t, model: &str) -> Option<*mut core::ffi::c_char> {
+    if models.is_null() {
+        return None;
+    }
+    (0..(*models).num as isize)
+        .map(|i| *(*models).model_name.offset(i))
+        .find(|name| !name.is_null() && CStr::from_ptr(*name).to_bytes() == model.as_bytes())
+}
+
+/// 唤醒词检测Actor
+///
+/// 负责在独立线程中运行esp-sr AFE唤醒词检测，音频样本来自采集任务的环形缓冲区。
+/// AFE处理是长时间阻塞操作，不能放在主循环中执行。
+///
+/// 提供播放参考信号时，AFE以"MR"（麦克风 + 参考）格式工作并启用回声消除，
+/// 扬声器播放期间检测到唤醒词会打断播放（barge-in），并通知App开始聆听。
+///
+/// 启用降噪时，调试录音与按键说话收到的是AFE输出（回声消除与降噪之后），
+/// 否则是原始麦克风数据。处理前后的电平作为运行指标定期上报。
+///
+/// 同样的数据还写入预录缓冲，检测到唤醒词时交给语音缓冲，
+/// 唤醒后上传的语音从唤醒词之前开始，不会丢失紧跟唤醒词的指令开头。
+///
+/// 唤醒词被禁用（免打扰）时AFE照常运行，只忽略检测结果。
+/// 切换唤醒词模型时在检测线程中销毁并重新创建AFE，只修改阈值时直接生效。
+pub struct WakeWordActor {
+    /// 采集任务环形缓冲区的消费者端
+    capture: RingConsumer,
+    /// 扬声器播放参考信号，用于回声消除
+    reference: Option<PlaybackReference>,
+    /// 调试录音器，录音时同时把麦克风数据写入WAV文件
+    recorder: AudioRecorder,
+    /// 按键说话的语音缓冲
+    utterance: UtteranceBuffer,
+    /// 音频分流，对讲时发送给其他设备、频谱界面显示
+    taps: Vec<AudioTap>,
+    /// 环境声音检测结果，用于判断附近是否有人
+    activity: SoundActivity,
+    /// 是否启用AFE降噪并录制降噪后的音频
+    noise_suppression: bool,
+    /// 是否响应唤醒词，由管理器修改
+    enabled: Arc<AtomicBool>,
+    /// 唤醒词模型与阈值
+    config: WakeWordConfig,
+    /// 命令接收端
+    command_receiver: Receiver<WakeWordCommand>,
+    /// 模型分区中的唤醒词模型，启动后填入，与管理器共享
+    models: Arc<Mutex<Vec<String>>>,
+    /// 应用事件发送器
+    app_event_sender: EventSender,
+}
+
+impl WakeWordActor {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        capture: RingConsumer,
+        reference: Option<PlaybackReference>,
+        recorder: AudioRecorder,
+        utterance: UtteranceBuffer,
+        taps: Vec<AudioTap>,
+        activity: SoundActivity,
+        noise_suppression: bool,
+        enabled: Arc<AtomicBool>,
+        config: WakeWordConfig,
+        command_receiver: Receiver<WakeWordCommand>,
+        models: Arc<Mutex<Vec<String>>>,
+        app_event_sender: EventSender,
+    ) -> Self {
+        Self {
+            capture,
+            reference,
+            recorder,
+            utterance,
+            taps,
+            activity,
+            noise_suppression,
+            enabled,
+            config,
+            command_receiver,
+            models,
+            app_event_sender,
+        }
+    }
+
+    /// 处理管理器发来的命令
+    ///
+    /// # 返回值
+    /// 需要重新创建AFE时返回true
+    fn handle_command(&mut self, command: WakeWordCommand, afe: &AfePipeline) -> bool {
+        match command {
+            WakeWordCommand::Configure(config) => {
+                let rebuild = config.model != self.config.model;
+                if !rebuild && config.threshold != self.config.threshold {
+                    unsafe { afe.set_threshold(config.threshold) };
+                }
+                self.config = config;
+                rebuild
+            }
+        }
+    }
+
+    /// 运行唤醒词检测
+    ///
+    /// 同时作为采集数据的唯一消费者，把样本分发给调试录音器、按键说话缓冲和音频分流，
+    /// 并根据原始麦克风电平检测环境声音。
+    ///
+    /// # 注意
+    /// 此方法不会返回，应在独立线程中调用
+    pub fn run(&mut self) -> Result<()> {
+        let models = unsafe { esp_srmodel_init(c"model".as_ptr()) };
+        let names = unsafe { model_names(models) };
+        for (i, name) in names.iter().enumerate() {
+            println!("Model {}: {}", i, name);
+        }
+        *self.models.lock().unwrap() = wakenet_models(names.iter().map(String::as_str));
+
+        let aec = self.reference.is_some();
+        let mut afe =
+            unsafe { AfePipeline::new(models, &self.config, aec, self.noise_suppression)? };
+        let mut feed_buffer = vec![0i16; afe.feed_size * afe.feed_nch];
+        let mut mic_buffer = vec![0i16; afe.feed_size];
+        let mut reference_buffer = vec![0i16; afe.feed_size];
+        let mut levels = RmsComparison::new(LEVEL_WINDOW_SAMPLES);
+        let mut noise_floor = NoiseFloor::default();
+        let mut pre_roll = PreRoll::new(PRE_ROLL_SAMPLES);
+
+        // 丢弃启动前积压的旧数据
+        self.capture.clear();
+
+        loop {
+            let rebuild = match self.command_receiver.try_recv() {
+                Ok(command) => self.handle_command(command, &afe),
+                Err(TryRecvError::Empty) => false,
+                // 管理器被丢弃后不再接收命令，检测照常进行
+                Err(TryRecvError::Disconnected) => false,
+            };
+            if rebuild {
+                // 先销毁旧实例再创建，模型占用的内存较大
+                drop(afe);
+                afe =
+                    unsafe { AfePipeline::new(models, &self.config, aec, self.noise_suppression)? };
+                feed_buffer = vec![0i16; afe.feed_size * afe.feed_nch];
+                mic_buffer = vec![0i16; afe.feed_size];
+                reference_buffer = vec![0i16; afe.feed_size];
+                // 重新创建期间积压的数据已经过时
+                self.capture.clear();
+                pre_roll.clear();
+            }
+
+            let feed_size = afe.feed_size;
+            let feed_nch = afe.feed_nch;
+            if self.capture.read_exact(&mut mic_buffer, FEED_READ_TIMEOUT) < feed_size {
+                log::warn!("采集数据不足一个feed块，跳过");
+                continue;
+            }
+            if !self.noise_suppression {
+                self.recorder.feed(&mic_buffer);
+                self.utterance.feed(&mic_buffer);
+                for tap in &self.taps {
+                    tap.feed(&mic_buffer);
+                }
+                pre_roll.push(&mic_buffer);
+            }
+            if noise_floor.update(dsp::rms(&mic_buffer)) {
+                self.activity.report();
+            }
+
+            // 按AFE要求交错排列：[mic, ref, mic, ref, ...]
+            match &self.reference {
+                Some(reference) => reference.read_into(&mut reference_buffer),
+                None => reference_buffer.fill(0),
+            }
+            for (i, &sample) in mic_buffer.iter().enumerate() {
+                feed_buffer[i * feed_nch] = sample;
+                if feed_nch > 1 {
+                    feed_buffer[i * feed_nch + 1] = reference_buffer[i];
+                }
+            }
+
+            unsafe {
+                (*afe.handle).feed.unwrap()(afe.data, feed_buffer.as_ptr());
+                let res = (*afe.handle).fetch.unwrap()(afe.data);
+                if res.is_null() || (*res).data.is_null() {
+                    continue;
+                }
+
+                let processed = std::slice::from_raw_parts(
+                    (*res).data,
+                    (*res).data_size as usize / std::mem::size_of::<i16>(),
+                );
+                if self.noise_suppression {
+                    self.recorder.feed(processed);
+                    self.utterance.feed(processed);
+                    for tap in &self.taps {
+                        tap.feed(processed);
+                    }
+                    pre_roll.push(processed);
+                }
+                if let Some(report) = levels.feed(&mic_buffer, processed) {
+                    metrics::set_gauge(metrics::MIC_RMS_RAW, report.raw as f64);
+                    metrics::set_gauge(metrics::MIC_RMS_PROCESSED, report.processed as f64);
+                    metrics::set_gauge(metrics::MIC_REDUCTION_DB, report.reduction_db() as f64);
+                }
+
+                if (*res).wakeup_state == wakenet_state_t_WAKENET_DETECTED {
+                    if !self.enabled.load(Ordering::Relaxed) {
+                        info!("检测到唤醒词，唤醒已禁用，忽略");
+                        continue;
+                    }
+                    info!("检测到唤醒词");
+                    let playing = self
+                        .reference
+                        .as_ref()
+                        .is_some_and(|reference| reference.is_playing());
+                    // 播放中的原始麦克风数据混有扬声器的声音，只保留唤醒之后的部分
+                    if playing && !self.noise_suppression {
+                        self.utterance.arm(Vec::new());
+                    } else {
+                        self.utterance.arm(pre_roll.snapshot());
+                    }
+                    if playing {
+                        info!("播放中检测到唤醒词，打断播放");
+                        if let Some(reference) = &self.reference {
+                            reference.request_interrupt();
+                        }
+                        if let Err(e) = events::send_barge_in_event(&self.app_event_sender) {
+                            log::warn!("发送插话事件失败: {}", e);
+                        }
+                    } else if let Err(e) = events::send_wake_word_event(&self.app_event_sender) {
+                        log::warn!("发送唤醒词事件失败: {}", e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 唤醒词检测Actor管理器
+///
+/// 创建时立即在后台线程中启动唤醒词检测。
+pub struct WakeWordActorManager {
+    /// 与检测线程共享的启用标志
+    enabled: Arc<AtomicBool>,
+    /// 命令发送端
+    command_sender: Sender<WakeWordCommand>,
+    /// 模型分区中的唤醒词模型
+    models: Arc<Mutex<Vec<String>>>,
+}
+
+impl WakeWordActorManager {
+    /// 启动唤醒词检测线程
+    ///
+    /// # 参数
+    /// * `capture` - 采集任务环形缓冲区的消费者端，所有权转移到后台线程
+    /// * `reference` - 扬声器播放参考信号，提供时启用回声消除
+    /// * `recorder` - 调试录音器
+    /// * `utterance` - 按键说话的语音缓冲
+    /// * `taps` - 音频分流，每个消费者一路，互不影响
+    /// * `activity` - 环境声音检测结果
+    /// * `noise_suppression` - 是否启用AFE降噪并录制降噪后的音频
+    /// * `enabled` - 是否响应唤醒词
+    /// * `config` - 唤醒词模型与阈值
+    /// * `app_event_sender` - 应用事件发送器，播放中被唤醒时发送`AppEvent::BargeIn`，
+    ///   否则发送`AppEvent::WakeWord`
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        capture: RingConsumer,
+        reference: Option<PlaybackReference>,
+        recorder: AudioRecorder,
+        utterance: UtteranceBuffer,
+        taps: Vec<AudioTap>,
+        activity: SoundActivity,
+        noise_suppression: bool,
+        enabled: bool,
+        config: WakeWordConfig,
+        app_event_sender: EventSender,
+    ) -> Result<Self> {
+        let enabled = Arc::new(AtomicBool::new(enabled));
+        let (command_sender, command_receiver) = mpsc::channel();
+        let models = Arc::new(Mutex::new(Vec::new()));
+        let mut actor = WakeWordActor::new(
+            capture,
+            reference,
+            recorder,
+            utterance,
+            taps,
+            activity,
+            noise_suppression,
+            enabled.clone(),
+            config,
+            command_receiver,
+            models.clone(),
+            app_event_sender,
+        );
+
+        spawn::WAKEWORD.spawn(move || {
+            if let Err(e) = actor.run() {
+                info!("Wake word actor stopped: {}", e);
+            }
+        })?;
+
+        Ok(Self {
+            enabled,
+            command_sender,
+            models,
+        })
+    }
+
+    /// 启用或禁用唤醒词，立即生效
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// 切换唤醒词模型与阈值，检测线程在下一个feed块前生效
+    pub fn configure(&self, config: WakeWordConfig) -> Result<()> {
+        self.command_sender
+            .send(WakeWordCommand::Configure(config))?;
+        Ok(())
+    }
+
+    /// 模型分区中的唤醒词模型，检测线程启动前为空
+    pub fn models(&self) -> Vec<String> {
+        self.models.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wakenet_models_and_choices() {
+        let models = wakenet_models(["mn7_cn", "wn9_hiesp", "nsnet1", "wn9_alexa"]);
+        assert_eq!(models, vec!["wn9_hiesp", "wn9_alexa"]);
+        assert_eq!(keyword_name(&models[0]), "hiesp");
+        assert_eq!(keyword_name("custom"), "custom");
+
+        // 默认在前，之后为各个模型
+        assert_eq!(
+            model_choices(&models),
+            vec![
+                None,
+                Some("wn9_hiesp".to_string()),
+                Some("wn9_alexa".to_string())
+            ]
+        );
+        assert_eq!(model_choices(&[]), vec![None]);
+    }
+}
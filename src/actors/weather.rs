@@ -0,0 +1,124 @@
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use log::{info, warn};
+
+use super::spawn;
+use crate::api::weather::{WeatherClient, WeatherConfig};
+
+/// 成功获取天气后的刷新间隔
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// 获取失败后的首次重试间隔，之后每次失败加倍
+const RETRY_INITIAL: Duration = Duration::from_secs(30);
+
+/// 重试间隔上限
+const RETRY_MAX: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Clone, Copy)]
+pub enum WeatherCommand {
+    /// 立即获取天气，并开始按小时刷新
+    Refresh,
+}
+
+/// 天气actor
+///
+/// 收到第一个`Refresh`命令（WiFi连接后）开始获取天气，成功后每小时刷新一次，
+/// 失败时按指数退避重试。结果通过`AppEvent::Weather`发送。
+pub struct WeatherActor {
+    client: WeatherClient,
+    command_receiver: Receiver<WeatherCommand>,
+    app_event_sender: crate::events::EventSender,
+}
+
+impl WeatherActor {
+    pub fn new(
+        config: WeatherConfig,
+        command_receiver: Receiver<WeatherCommand>,
+        app_event_sender: crate::events::EventSender,
+    ) -> Self {
+        Self {
+            client: WeatherClient::new(config),
+            command_receiver,
+            app_event_sender,
+        }
+    }
+
+    pub fn run(&mut self) {
+        info!("Weather actor started");
+
+        // None表示尚未开始（等待WiFi连接）
+        let mut next_fetch: Option<Instant> = None;
+        let mut retry_delay = RETRY_INITIAL;
+
+        loop {
+            let command = match next_fetch {
+                None => self
+                    .command_receiver
+                    .recv()
+                    .map_err(|_| RecvTimeoutError::Disconnected),
+                Some(at) => self
+                    .command_receiver
+                    .recv_timeout(at.saturating_duration_since(Instant::now())),
+            };
+
+            match command {
+                Ok(WeatherCommand::Refresh) => next_fetch = Some(Instant::now()),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            if next_fetch.is_some_and(|at| Instant::now() >= at) {
+                match self.client.current() {
+                    Ok(weather) => {
+                        info!(
+                            "Weather updated: {} {:.1}C {}",
+                            weather.city, weather.temperature, weather.description
+                        );
+                        let _ = crate::events::send_weather_event(&self.app_event_sender, weather);
+                        next_fetch = Some(Instant::now() + REFRESH_INTERVAL);
+                        retry_delay = RETRY_INITIAL;
+                    }
+                    Err(e) => {
+                        warn!("Weather fetch failed, retry in {:?}: {}", retry_delay, e);
+                        next_fetch = Some(Instant::now() + retry_delay);
+                        retry_delay = (retry_delay * 2).min(RETRY_MAX);
+                    }
+                }
+            }
+        }
+
+        info!("Weather actor command channel disconnected, shutting down");
+    }
+}
+
+pub struct WeatherActorManager {
+    command_sender: Sender<WeatherCommand>,
+}
+
+impl WeatherActorManager {
+    /// 启动天气线程
+    ///
+    /// # 参数
+    /// * `config` - 天气服务配置
+    /// * `app_event_sender` - 应用事件发送器
+    pub fn new(
+        config: WeatherConfig,
+        app_event_sender: crate::events::EventSender,
+    ) -> Result<Self> {
+        let (command_sender, command_receiver) = std::sync::mpsc::channel::<WeatherCommand>();
+
+        spawn::WEATHER.spawn(move || {
+            WeatherActor::new(config, command_receiver, app_event_sender).run();
+        })?;
+
+        Ok(Self { command_sender })
+    }
+
+    /// 立即刷新天气，WiFi连接后调用
+    pub fn refresh(&self) -> Result<()> {
+        self.command_sender.send(WeatherCommand::Refresh)?;
+        Ok(())
+    }
+}
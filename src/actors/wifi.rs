@@ -1,43 +1,64 @@
 use std::sync::mpsc::{Receiver, Sender};
-use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use esp_idf_hal::modem::Modem;
 use esp_idf_svc::{eventloop::EspSystemEventLoop, nvs::EspDefaultNvsPartition};
 use log::info;
+use serde::{Deserialize, Serialize};
 
-use crate::peripherals::wifi::{WifiConfig, WifiManager};
+use super::spawn;
+use crate::error::Error;
+use crate::peripherals::wifi::{rssi_to_level, WifiConfig, WifiManager};
+
+/// RSSI采样间隔
+const RSSI_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Clone)]
 pub enum WifiCommand {
     Connect(WifiConfig),
     Disconnect,
     GetStatus,
-    // Scan,
+    Scan,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum WifiEvent {
     Connected(String), // IP address
     Disconnected,
-    ConnectionFailed(String), // Error message
+    ConnectionFailed(Error),
     StatusUpdate(WifiStatus),
-    // ScanResult(Vec<String>), // Network names
+    ScanResult(Vec<String>), // Network names
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum WifiStatus {
-    Connected,
+    Connected { rssi: Option<i8> }, // 信号强度(dBm)，未采样时为None
     Disconnected,
     Connecting,
     Scanning,
-    Error(String),
+    Error(Error),
 }
 
 impl WifiStatus {
     pub fn is_connected(&self) -> bool {
-        matches!(self, WifiStatus::Connected)
+        matches!(self, WifiStatus::Connected { .. })
+    }
+
+    /// 获取信号强度（dBm），未连接或未采样时返回None
+    pub fn rssi(&self) -> Option<i8> {
+        match self {
+            WifiStatus::Connected { rssi } => *rssi,
+            _ => None,
+        }
+    }
+
+    /// 获取0-4格的信号强度，未连接时返回None
+    pub fn signal_level(&self) -> Option<u8> {
+        match self {
+            WifiStatus::Connected { rssi } => Some(rssi.map(rssi_to_level).unwrap_or(0)),
+            _ => None,
+        }
     }
 }
 
@@ -47,6 +68,8 @@ pub struct WifiActor {
     event_sender: Sender<WifiEvent>,
     current_status: WifiStatus,
     app_event_sender: crate::events::EventSender,
+    last_rssi_sample: Instant,
+    last_signal_level: Option<u8>,
 }
 
 impl WifiActor {
@@ -66,6 +89,8 @@ impl WifiActor {
             event_sender,
             current_status: WifiStatus::Disconnected,
             app_event_sender,
+            last_rssi_sample: Instant::now(),
+            last_signal_level: None,
         })
     }
 
@@ -80,16 +105,17 @@ impl WifiActor {
             {
                 Ok(command) => {
                     if let Err(e) = self.handle_command(command) {
-                        let error_msg = format!("WiFi command failed: {}", e);
-                        self.current_status = WifiStatus::Error(error_msg.clone());
+                        let error = Error::Network(format!("WiFi command failed: {}", e));
+                        self.current_status = WifiStatus::Error(error.clone());
                         let _ = self
                             .event_sender
-                            .send(WifiEvent::StatusUpdate(WifiStatus::Error(error_msg)));
+                            .send(WifiEvent::StatusUpdate(WifiStatus::Error(error)));
                     }
                 }
                 Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
                     // Periodic status check
                     self.check_connection_status();
+                    self.sample_rssi();
                 }
                 Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
                     info!("WiFi actor command channel disconnected, shutting down");
@@ -111,7 +137,8 @@ impl WifiActor {
                 match self.wifi_manager.connect_with_config(&config) {
                     Ok(_) => {
                         info!("WiFi connected successfully");
-                        self.current_status = WifiStatus::Connected;
+                        let status = self.connected_status();
+                        self.current_status = status.clone();
 
                         if let Ok(ip) = self.wifi_manager.get_ip_info() {
                             let ip_str = format!("{}", ip);
@@ -132,22 +159,22 @@ impl WifiActor {
 
                         let _ = self
                             .event_sender
-                            .send(WifiEvent::StatusUpdate(WifiStatus::Connected));
+                            .send(WifiEvent::StatusUpdate(status.clone()));
                         let _ = crate::events::send_wifi_event(
                             &self.app_event_sender,
-                            WifiEvent::StatusUpdate(WifiStatus::Connected),
+                            WifiEvent::StatusUpdate(status),
                         );
                     }
                     Err(e) => {
-                        let error_msg = format!("WiFi connection failed: {}", e);
-                        info!("{}", error_msg);
-                        self.current_status = WifiStatus::Error(error_msg.clone());
+                        let error = Error::Network(format!("WiFi connection failed: {}", e));
+                        info!("{}", error);
+                        self.current_status = WifiStatus::Error(error.clone());
                         let _ = self
                             .event_sender
-                            .send(WifiEvent::ConnectionFailed(error_msg.clone()));
+                            .send(WifiEvent::ConnectionFailed(error.clone()));
                         let _ = crate::events::send_wifi_event(
                             &self.app_event_sender,
-                            WifiEvent::ConnectionFailed(error_msg),
+                            WifiEvent::ConnectionFailed(error),
                         );
                         let _ = self
                             .event_sender
@@ -178,11 +205,11 @@ impl WifiActor {
                         );
                     }
                     Err(e) => {
-                        let error_msg = format!("WiFi disconnect failed: {}", e);
-                        self.current_status = WifiStatus::Error(error_msg.clone());
+                        let error = Error::Network(format!("WiFi disconnect failed: {}", e));
+                        self.current_status = WifiStatus::Error(error.clone());
                         let _ = self
                             .event_sender
-                            .send(WifiEvent::StatusUpdate(WifiStatus::Error(error_msg)));
+                            .send(WifiEvent::StatusUpdate(WifiStatus::Error(error)));
                     }
                 }
             }
@@ -190,45 +217,51 @@ impl WifiActor {
                 let _ = self
                     .event_sender
                     .send(WifiEvent::StatusUpdate(self.current_status.clone()));
-            } // WifiCommand::Scan => {
-              //     info!("Scanning for WiFi networks");
-              //     self.current_status = WifiStatus::Scanning;
-              //     let _ = self
-              //         .event_sender
-              //         .send(WifiEvent::StatusUpdate(WifiStatus::Scanning));
-
-              //     match self.wifi_manager.scan_networks() {
-              //         Ok(networks) => {
-              //             let network_names: Vec<String> =
-              //                 networks.into_iter().map(|ap| ap.ssid.to_string()).collect();
-              //             let _ = self.event_sender.send(WifiEvent::ScanResult(network_names));
-
-              //             // Restore previous status after scan
-              //             let status = if self.wifi_manager.is_connected() {
-              //                 WifiStatus::Connected
-              //             } else {
-              //                 WifiStatus::Disconnected
-              //             };
-              //             self.current_status = status.clone();
-              //             let _ = self.event_sender.send(WifiEvent::StatusUpdate(status));
-              //         }
-              //         Err(e) => {
-              //             let error_msg = format!("WiFi scan failed: {}", e);
-              //             let _ = self
-              //                 .event_sender
-              //                 .send(WifiEvent::StatusUpdate(WifiStatus::Error(error_msg)));
-
-              //             // Restore previous status
-              //             let status = if self.wifi_manager.is_connected() {
-              //                 WifiStatus::Connected
-              //             } else {
-              //                 WifiStatus::Disconnected
-              //             };
-              //             self.current_status = status.clone();
-              //             let _ = self.event_sender.send(WifiEvent::StatusUpdate(status));
-              //         }
-              //     }
-              // }
+            }
+            WifiCommand::Scan => {
+                info!("Scanning for WiFi networks");
+                let previous = self.current_status.clone();
+                self.current_status = WifiStatus::Scanning;
+                let _ = self
+                    .event_sender
+                    .send(WifiEvent::StatusUpdate(WifiStatus::Scanning));
+
+                let result = self.wifi_manager.scan_networks();
+                self.current_status = previous;
+                match result {
+                    Ok(networks) => {
+                        let network_names: Vec<String> =
+                            networks.into_iter().map(|ap| ap.ssid.to_string()).collect();
+                        let _ = self
+                            .event_sender
+                            .send(WifiEvent::ScanResult(network_names.clone()));
+                        let _ = crate::events::send_wifi_event(
+                            &self.app_event_sender,
+                            WifiEvent::ScanResult(network_names),
+                        );
+                    }
+                    Err(e) => {
+                        let error = Error::Network(format!("WiFi scan failed: {}", e));
+                        let _ = self
+                            .event_sender
+                            .send(WifiEvent::StatusUpdate(WifiStatus::Error(error.clone())));
+                        let _ = crate::events::send_wifi_event(
+                            &self.app_event_sender,
+                            WifiEvent::StatusUpdate(WifiStatus::Error(error)),
+                        );
+                    }
+                }
+
+                // 恢复扫描前的状态
+                let status = self.current_status.clone();
+                let _ = self
+                    .event_sender
+                    .send(WifiEvent::StatusUpdate(status.clone()));
+                let _ = crate::events::send_wifi_event(
+                    &self.app_event_sender,
+                    WifiEvent::StatusUpdate(status),
+                );
+            }
         }
         Ok(())
     }
@@ -237,7 +270,7 @@ impl WifiActor {
         let is_connected = self.wifi_manager.is_connected();
 
         match (&self.current_status, is_connected) {
-            (WifiStatus::Connected, false) => {
+            (WifiStatus::Connected { .. }, false) => {
                 info!("WiFi connection lost");
                 self.current_status = WifiStatus::Disconnected;
                 let _ = self.event_sender.send(WifiEvent::Disconnected);
@@ -247,18 +280,59 @@ impl WifiActor {
             }
             (WifiStatus::Disconnected, true) => {
                 info!("WiFi connection restored");
-                self.current_status = WifiStatus::Connected;
+                let status = self.connected_status();
+                self.current_status = status.clone();
                 if let Ok(ip) = self.wifi_manager.get_ip_info() {
                     let ip_str = format!("{}", ip);
                     let _ = self.event_sender.send(WifiEvent::Connected(ip_str));
                 }
-                let _ = self
-                    .event_sender
-                    .send(WifiEvent::StatusUpdate(WifiStatus::Connected));
+                let _ = self.event_sender.send(WifiEvent::StatusUpdate(status));
             }
             _ => {} // No status change
         }
     }
+
+    /// 读取当前RSSI并构造已连接状态
+    fn connected_status(&mut self) -> WifiStatus {
+        let rssi = self.wifi_manager.get_rssi().ok();
+        self.last_rssi_sample = Instant::now();
+        self.last_signal_level = rssi.map(rssi_to_level);
+        WifiStatus::Connected { rssi }
+    }
+
+    /// 周期性采样RSSI，信号格数变化时上报状态
+    fn sample_rssi(&mut self) {
+        if !self.current_status.is_connected()
+            || self.last_rssi_sample.elapsed() < RSSI_SAMPLE_INTERVAL
+        {
+            return;
+        }
+        self.last_rssi_sample = Instant::now();
+
+        let rssi = match self.wifi_manager.get_rssi() {
+            Ok(rssi) => rssi,
+            Err(e) => {
+                info!("Failed to read RSSI: {}", e);
+                return;
+            }
+        };
+
+        self.current_status = WifiStatus::Connected { rssi: Some(rssi) };
+
+        // 只在信号格数变化时上报，避免事件风暴
+        let level = rssi_to_level(rssi);
+        if self.last_signal_level == Some(level) {
+            return;
+        }
+        self.last_signal_level = Some(level);
+
+        let status = self.current_status.clone();
+        let _ = self
+            .event_sender
+            .send(WifiEvent::StatusUpdate(status.clone()));
+        let _ =
+            crate::events::send_wifi_event(&self.app_event_sender, WifiEvent::StatusUpdate(status));
+    }
 }
 
 pub struct WifiActorManager {
@@ -278,9 +352,7 @@ impl WifiActorManager {
 
         let event_sender_clone = event_sender.clone();
 
-        thread::Builder::new()
-            .stack_size(64 * 1024)
-            .name("wifi_actor".to_string())
+        spawn::WIFI
             .spawn(move || {
                 match WifiActor::new(
                     modem,
@@ -294,9 +366,9 @@ impl WifiActorManager {
                         actor.run();
                     }
                     Err(e) => {
-                        let error_msg = format!("Failed to create WiFi actor: {}", e);
+                        let error = Error::Network(format!("Failed to create WiFi actor: {}", e));
                         let _ = event_sender_clone
-                            .send(WifiEvent::StatusUpdate(WifiStatus::Error(error_msg)));
+                            .send(WifiEvent::StatusUpdate(WifiStatus::Error(error)));
                     }
                 }
             })
@@ -323,10 +395,11 @@ impl WifiActorManager {
         Ok(())
     }
 
-    // pub fn scan_networks(&self) -> Result<()> {
-    //     self.command_sender.send(WifiCommand::Scan)?;
-    //     Ok(())
-    // }
+    /// 扫描附近的网络，结果通过`WifiEvent::ScanResult`返回
+    pub fn scan_networks(&self) -> Result<()> {
+        self.command_sender.send(WifiCommand::Scan)?;
+        Ok(())
+    }
 
     pub fn try_recv_event(&self) -> Result<WifiEvent, std::sync::mpsc::TryRecvError> {
         self.event_receiver.try_recv()
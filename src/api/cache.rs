@@ -0,0 +1,156 @@
+// GET响应缓存
+//
+// 模型列表、天气等很少变化的数据缓存在内存中，TTL内直接返回，界面不必等待网络。
+// 缓存键由URL与设备指纹组成，切换服务端或设备身份后不会读到别人的数据。
+// 过期的条目继续保留，请求失败（网络断开、超时）时作为兜底返回。
+// 只缓存200响应；所有线程共用一个缓存，条目数有上限，满了之后淘汰最早写入的。
+
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::metrics;
+
+/// 模型列表的缓存时间
+pub const MODELS_TTL: Duration = Duration::from_secs(10 * 60);
+/// 天气的缓存时间，WiFi反复重连时不会重复查询
+pub const WEATHER_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// 最多缓存的条目数
+const MAX_ENTRIES: usize = 8;
+
+struct CacheEntry {
+    key: String,
+    body: String,
+    stored_at: Instant,
+}
+
+/// 按键保存响应体的缓存
+pub struct ResponseCache {
+    /// 按写入时间排序，最早的在前
+    entries: Vec<CacheEntry>,
+}
+
+impl ResponseCache {
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// 查询未过期的响应体
+    ///
+    /// # 参数
+    /// * `key` - 缓存键，见`cache_key`
+    /// * `ttl` - 缓存时间
+    /// * `now` - 当前时间
+    pub fn get(&self, key: &str, ttl: Duration, now: Instant) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|entry| entry.key == key)
+            .filter(|entry| now.saturating_duration_since(entry.stored_at) < ttl)
+            .map(|entry| entry.body.as_str())
+    }
+
+    /// 查询响应体，不论是否过期
+    pub fn get_stale(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|entry| entry.key == key)
+            .map(|entry| entry.body.as_str())
+    }
+
+    /// 写入响应体，替换同一个键的旧条目
+    pub fn insert(&mut self, key: &str, body: String, now: Instant) {
+        self.entries.retain(|entry| entry.key != key);
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.entries.push(CacheEntry {
+            key: key.to_string(),
+            body,
+            stored_at: now,
+        });
+    }
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static CACHE: Mutex<ResponseCache> = Mutex::new(ResponseCache::new());
+
+fn with_cache<T>(f: impl FnOnce(&mut ResponseCache) -> T) -> T {
+    f(&mut CACHE.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// 缓存键
+///
+/// # 参数
+/// * `url` - 完整的请求URL（包括查询参数）
+/// * `fingerprint` - 设备指纹，不区分设备的接口传空字符串
+pub fn cache_key(url: &str, fingerprint: &str) -> String {
+    format!("{}|{}", fingerprint, url)
+}
+
+/// 查询全局缓存中未过期的响应体
+pub fn lookup(key: &str, ttl: Duration) -> Option<String> {
+    let body = with_cache(|cache| cache.get(key, ttl, Instant::now()).map(str::to_string));
+    if body.is_some() {
+        metrics::increment(metrics::HTTP_CACHE_HITS, 1);
+    }
+    body
+}
+
+/// 查询全局缓存中的响应体，不论是否过期，用于请求失败时兜底
+pub fn lookup_stale(key: &str) -> Option<String> {
+    with_cache(|cache| cache.get_stale(key).map(str::to_string))
+}
+
+/// 写入全局缓存
+pub fn store(key: &str, body: &str) {
+    with_cache(|cache| cache.insert(key, body.to_string(), Instant::now()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_expire_but_stay_as_fallback() {
+        let start = Instant::now();
+        let mut cache = ResponseCache::new();
+        let key = cache_key("http://host/api/chat/models", "dev-1");
+        cache.insert(&key, "[]".to_string(), start);
+
+        let ttl = Duration::from_secs(60);
+        assert_eq!(
+            cache.get(&key, ttl, start + Duration::from_secs(59)),
+            Some("[]")
+        );
+        assert_eq!(cache.get(&key, ttl, start + ttl), None);
+        assert_eq!(cache.get_stale(&key), Some("[]"));
+
+        // 不同设备指纹互不影响
+        let other = cache_key("http://host/api/chat/models", "dev-2");
+        assert_eq!(cache.get_stale(&other), None);
+    }
+
+    #[test]
+    fn test_oldest_entry_evicted() {
+        let now = Instant::now();
+        let mut cache = ResponseCache::new();
+        for i in 0..MAX_ENTRIES {
+            cache.insert(&i.to_string(), i.to_string(), now);
+        }
+        // 覆盖已有的键不会淘汰其他条目
+        cache.insert("0", "new".to_string(), now);
+        assert_eq!(cache.get_stale("1"), Some("1"));
+
+        cache.insert("extra", String::new(), now);
+        assert_eq!(cache.get_stale("1"), None);
+        assert_eq!(cache.get_stale("0"), Some("new"));
+        assert_eq!(cache.entries.len(), MAX_ENTRIES);
+    }
+}
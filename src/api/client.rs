@@ -1,13 +1,29 @@
-use super::{types::*, ApiConfig};
+use super::{
+    cache, persona::Persona, request::RequestOptions, signing, sse::SseParser, tls, types::*,
+    ApiConfig,
+};
 use anyhow::Result;
 use embedded_svc::{
     http::{client::Client as HttpClient, Method},
     io::Write,
-    utils::io,
 };
 use esp_idf_svc::http::client::EspHttpConnection;
-use log::{error, info};
-use std::time::Duration;
+use log::{error, info, warn};
+use std::time::{Duration, Instant};
+
+use crate::blocking::{self, HTTP_REQUEST_SLACK};
+use crate::{logring, metrics};
+
+/// WAV音频的MIME类型
+pub const MIME_WAV: &str = "audio/wav";
+/// BMP图片的MIME类型
+pub const MIME_BMP: &str = "image/bmp";
+
+/// multipart请求的分隔符
+const MULTIPART_BOUNDARY: &str = "----esp32-aichat-upload";
+
+/// 上传时每次写入的字节数
+const UPLOAD_CHUNK_SIZE: usize = 4096;
 
 /// HTTP API客户端，用于与聊天服务进行通信
 pub struct ApiClient {
@@ -21,54 +37,117 @@ impl ApiClient {
     }
 
     /// 构建HTTP请求头
-    fn build_headers(&self) -> Vec<(&str, &str)> {
-        vec![
-            ("X-Fingerprint", &self.config.fingerprint),
+    ///
+    /// # 参数
+    /// * `signature` - `signing::sign_request`生成的签名头
+    fn build_headers<'a>(
+        &'a self,
+        signature: &'a [(&'static str, String)],
+    ) -> Vec<(&'a str, &'a str)> {
+        let mut headers = vec![
+            ("X-Fingerprint", self.config.fingerprint.as_str()),
             ("Content-Type", "application/json"),
-        ]
+        ];
+        headers.extend(
+            signature
+                .iter()
+                .map(|(name, value)| (*name, value.as_str())),
+        );
+        headers
     }
 
     /// 创建HTTP客户端连接
-    fn create_client(&self) -> Result<HttpClient<EspHttpConnection>> {
-        let http_config = esp_idf_svc::http::client::Configuration {
-            timeout: Some(Duration::from_secs(self.config.timeout_secs)),
+    ///
+    /// 套接字超时取配置超时与请求剩余时间中较小的一个，保证单次阻塞读不会越过截止时间太多。
+    fn create_client(&self, options: &RequestOptions) -> Result<HttpClient<EspHttpConnection>> {
+        let mut timeout = Duration::from_secs(self.config.timeout_secs);
+        if let Some(remaining) = options.remaining() {
+            timeout = timeout.min(remaining.max(Duration::from_secs(1)));
+        }
+
+        let mut http_config = esp_idf_svc::http::client::Configuration {
+            timeout: Some(timeout),
             ..Default::default()
         };
+        tls::apply(&mut http_config);
 
         let connection = EspHttpConnection::new(&http_config)?;
         Ok(HttpClient::wrap(connection))
     }
 
-    /// 读取HTTP响应体内容
-    fn read_response_body<R>(mut reader: R) -> Result<String>
+    /// 分块读取HTTP响应体，每读到一块数据调用一次回调
+    ///
+    /// 每次读取之间检查取消与超时，累计长度超过`max_bytes`时返回`ApiError::ResponseTooLarge`。
+    ///
+    /// # 参数
+    /// * `reader` - 响应体读取器
+    /// * `options` - 请求控制选项
+    /// * `max_bytes` - 响应体最大字节数
+    /// * `on_chunk` - 数据块回调
+    ///
+    /// # 返回值
+    /// 读取的总字节数
+    pub fn read_body_chunked<R, F>(
+        mut reader: R,
+        options: &RequestOptions,
+        max_bytes: usize,
+        mut on_chunk: F,
+    ) -> Result<usize>
     where
         R: embedded_svc::io::Read,
+        F: FnMut(&[u8]) -> Result<()>,
     {
         let mut buf = [0u8; 1024];
-        let bytes_read = io::try_read_full(&mut reader, &mut buf)
-            .map_err(|e| anyhow::anyhow!("Failed to read response: {:?}", e.0))?;
-
-        match std::str::from_utf8(&buf[0..bytes_read]) {
-            Ok(response_text) => Ok(response_text.to_string()),
-            Err(e) => {
-                error!("Error decoding response body: {}", e);
-                Err(anyhow::anyhow!("UTF-8 decoding error: {}", e))
+        let mut total = 0;
+        loop {
+            options.check()?;
+            let n = reader
+                .read(&mut buf)
+                .map_err(|e| anyhow::anyhow!("Failed to read response: {:?}", e))?;
+            if n == 0 {
+                break;
+            }
+            total += n;
+            if total > max_bytes {
+                return Err(ApiError::ResponseTooLarge { limit: max_bytes }.into());
             }
+            on_chunk(&buf[..n])?;
         }
+        Ok(total)
+    }
+
+    /// 读取完整的HTTP响应体内容
+    fn read_response_body<R>(
+        reader: R,
+        options: &RequestOptions,
+        max_bytes: usize,
+    ) -> Result<String>
+    where
+        R: embedded_svc::io::Read,
+    {
+        let mut body = Vec::new();
+        Self::read_body_chunked(reader, options, max_bytes, |chunk| {
+            body.extend_from_slice(chunk);
+            Ok(())
+        })?;
+
+        // 按完整响应解码，避免多字节字符被分块边界截断
+        String::from_utf8(body).map_err(|e| {
+            error!("Error decoding response body: {}", e);
+            anyhow::anyhow!("UTF-8 decoding error: {}", e)
+        })
     }
 
     /// 创建API错误信息
     fn create_api_error(status: u16, response_text: &str) -> anyhow::Error {
-        match serde_json::from_str::<ApiResponse<serde_json::Value>>(response_text) {
-            Ok(error_response) => anyhow::anyhow!(
-                "API error {}: {}",
-                status,
-                error_response
-                    .message
-                    .unwrap_or_else(|| "Unknown error".to_string())
-            ),
-            Err(_) => anyhow::anyhow!("API error {}: {}", status, response_text),
-        }
+        metrics::increment(metrics::HTTP_ERRORS, 1);
+        let message = match serde_json::from_str::<ApiResponse<serde_json::Value>>(response_text) {
+            Ok(error_response) => error_response
+                .message
+                .unwrap_or_else(|| "Unknown error".to_string()),
+            Err(_) => response_text.to_string(),
+        };
+        ApiError::Api { status, message }.into()
     }
 
     /// 处理API响应，返回反序列化的数据
@@ -93,57 +172,437 @@ impl ApiClient {
         }
     }
 
+    /// 单次请求的阻塞预算：请求超时加上固定余量
+    fn request_budget(&self) -> Duration {
+        Duration::from_secs(self.config.timeout_secs) + HTTP_REQUEST_SLACK
+    }
+
     /// 执行GET请求
-    fn execute_get_request(&self, url: &str) -> Result<(u16, String)> {
-        let mut client = self.create_client()?;
-        let headers = self.build_headers();
+    fn execute_get_request(&self, url: &str, options: &RequestOptions) -> Result<(u16, String)> {
+        blocking::assert_off_main_thread("http_get");
+        let start = Instant::now();
+        options.check()?;
+
+        let mut client = self.create_client(options)?;
+        let signature = signing::sign_request("GET", url, Some(&[]));
+        let headers = self.build_headers(&signature);
         info!("-> GET {}", url);
-        let request = client.request(Method::Get, url, &headers)?;
+        let request = client
+            .request(Method::Get, url, &headers)
+            .map_err(|e| tls::connect_error(url, e))?;
         let response = request.submit()?;
+        signing::observe_response(&response);
+        options.check()?;
 
         let status = response.status();
         info!("<- {}", status);
-        let response_text = Self::read_response_body(response)?;
+        let response_text =
+            Self::read_response_body(response, options, self.config.max_response_bytes)?;
 
+        blocking::check_budget("http_get", self.request_budget(), start.elapsed());
+        metrics::observe_duration(metrics::HTTP_LATENCY_MS, start.elapsed());
         Ok((status, response_text))
     }
 
+    /// 执行可缓存的GET请求
+    ///
+    /// 缓存未过期时直接返回，不访问网络；请求失败时返回过期的缓存（如果有）。
+    /// 只有200响应会写入缓存。
+    ///
+    /// # 参数
+    /// * `url` - 请求URL
+    /// * `ttl` - 缓存时间
+    /// * `options` - 请求控制选项
+    fn execute_cached_get_request(
+        &self,
+        url: &str,
+        ttl: Duration,
+        options: &RequestOptions,
+    ) -> Result<(u16, String)> {
+        let key = cache::cache_key(url, &self.config.fingerprint);
+        if let Some(body) = cache::lookup(&key, ttl) {
+            info!("-> GET {} (cached)", url);
+            return Ok((200, body));
+        }
+
+        match self.execute_get_request(url, options) {
+            Ok((status, body)) => {
+                if status == 200 {
+                    cache::store(&key, &body);
+                }
+                Ok((status, body))
+            }
+            Err(e) => match cache::lookup_stale(&key) {
+                Some(body) => {
+                    warn!("GET {} failed, using stale cache: {}", url, e);
+                    Ok((200, body))
+                }
+                None => Err(e),
+            },
+        }
+    }
+
     /// 执行POST请求
-    fn execute_post_request(&self, url: &str, body: &str) -> Result<(u16, String)> {
-        let mut client = self.create_client()?;
-        let headers = self.build_headers();
+    fn execute_post_request(
+        &self,
+        url: &str,
+        body: &str,
+        options: &RequestOptions,
+    ) -> Result<(u16, String)> {
+        blocking::assert_off_main_thread("http_post");
+        let start = Instant::now();
+        options.check()?;
+
+        let mut client = self.create_client(options)?;
+        let signature = signing::sign_request("POST", url, Some(body.as_bytes()));
+        let headers = self.build_headers(&signature);
 
         info!("-> POST {}", url);
-        let mut request = client.request(Method::Post, url, &headers)?;
+        let mut request = client
+            .request(Method::Post, url, &headers)
+            .map_err(|e| tls::connect_error(url, e))?;
         request.write_all(body.as_bytes())?;
         request.flush()?;
+        options.check()?;
 
         let response = request.submit()?;
+        signing::observe_response(&response);
+        options.check()?;
         let status = response.status();
         info!("<- {}", status);
-        let response_text = Self::read_response_body(response)?;
+        let response_text =
+            Self::read_response_body(response, options, self.config.max_response_bytes)?;
 
+        blocking::check_budget("http_post", self.request_budget(), start.elapsed());
+        metrics::observe_duration(metrics::HTTP_LATENCY_MS, start.elapsed());
         Ok((status, response_text))
     }
 
+    /// 以流式方式发送提示，服务端通过SSE推送处理进度和回复内容
+    ///
+    /// 事件格式见`SseEvent`：`status`事件的`content`为处理阶段，`message`事件的`content`
+    /// 为回复片段，`command`事件的`content`为设备命令JSON（见`DeviceCommand`），
+    /// `heartbeat`事件只用于保持连接，`error`事件表示请求失败，`done`事件结束。
+    ///
+    /// # 参数
+    /// - `session_id`: 会话ID
+    /// - `message`: 提示消息
+    /// - `files`: 可选的文件列表
+    /// - `options`: 请求控制选项
+    /// - `on_stage`: 收到处理阶段时的回调
+    /// - `on_command`: 收到设备命令时的回调
+    /// - `on_message`: 收到回复片段时的回调，用于边接收边显示
+    ///
+    /// # 返回
+    /// 拼接完整的回复
+    #[allow(clippy::too_many_arguments)]
+    pub fn prompt_stream<F, C, M>(
+        &self,
+        session_id: &str,
+        message: &str,
+        files: Option<Vec<String>>,
+        options: &RequestOptions,
+        mut on_stage: F,
+        mut on_command: C,
+        mut on_message: M,
+    ) -> Result<String>
+    where
+        F: FnMut(ChatStage),
+        C: FnMut(DeviceCommand),
+        M: FnMut(&str),
+    {
+        blocking::assert_off_main_thread("http_stream");
+        let url = format!("{}/chat/prompt/{}/stream", self.config.base_url, session_id);
+        let body_json = serde_json::to_string(&MessageRequest {
+            message: message.to_string(),
+            files,
+        })?;
+        options.check()?;
+
+        let mut client = self.create_client(options)?;
+        let signature = signing::sign_request("POST", &url, Some(body_json.as_bytes()));
+        let mut headers = self.build_headers(&signature);
+        headers.push(("Accept", "text/event-stream"));
+
+        info!("-> POST {} (stream)", url);
+        let mut request = client
+            .request(Method::Post, &url, &headers)
+            .map_err(|e| tls::connect_error(&url, e))?;
+        request.write_all(body_json.as_bytes())?;
+        request.flush()?;
+        options.check()?;
+
+        let response = request.submit()?;
+        signing::observe_response(&response);
+        options.check()?;
+        let status = response.status();
+        info!("<- {}", status);
+        if status != 200 {
+            let response_text =
+                Self::read_response_body(response, options, self.config.max_response_bytes)?;
+            return Err(Self::create_api_error(status, &response_text));
+        }
+
+        let mut parser = SseParser::new();
+        let mut reply = String::new();
+        let mut done = false;
+        Self::read_body_chunked(response, options, self.config.max_response_bytes, |chunk| {
+            parser.feed(chunk, |data| {
+                let event: SseEvent = serde_json::from_str(data)?;
+                match event.event_type.as_str() {
+                    "status" => {
+                        let stage = event.content.as_deref().and_then(|content| {
+                            serde_json::from_value(serde_json::Value::from(content)).ok()
+                        });
+                        match stage {
+                            Some(stage) => on_stage(stage),
+                            None => warn!("Unknown chat stage: {:?}", event.content),
+                        }
+                    }
+                    "message" => {
+                        let content = event.content.as_deref().unwrap_or_default();
+                        if !content.is_empty() {
+                            on_message(content);
+                            reply.push_str(content);
+                        }
+                    }
+                    "command" => {
+                        let content = event.content.as_deref().unwrap_or_default();
+                        match serde_json::from_str(content) {
+                            Ok(command) => on_command(command),
+                            Err(e) => warn!("Unknown device command {:?}: {}", content, e),
+                        }
+                    }
+                    "error" => {
+                        return Err(ApiError::Api {
+                            status,
+                            message: event.content.unwrap_or_default(),
+                        }
+                        .into())
+                    }
+                    "done" => done = true,
+                    _ => {}
+                }
+                Ok(())
+            })
+        })?;
+
+        if !done && reply.is_empty() {
+            anyhow::bail!("Stream closed before reply");
+        }
+        Ok(reply)
+    }
+
+    /// 生成multipart请求体中文件数据前后的部分
+    ///
+    /// # 返回值
+    /// (文件数据之前的头部, 文件数据之后的结尾)
+    fn multipart_envelope(filename: &str, mime: &str) -> (String, String) {
+        let head = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+            MULTIPART_BOUNDARY, filename, mime
+        );
+        let tail = format!("\r\n--{}--\r\n", MULTIPART_BOUNDARY);
+        (head, tail)
+    }
+
+    /// 以multipart/form-data格式流式上传文件
+    ///
+    /// 文件内容分块写入请求，不需要把整个文件读入内存。
+    fn execute_upload<R>(
+        &self,
+        filename: &str,
+        mime: &str,
+        len: usize,
+        mut reader: R,
+    ) -> Result<(u16, String)>
+    where
+        R: std::io::Read,
+    {
+        blocking::assert_off_main_thread("http_upload");
+        let start = Instant::now();
+        let options = RequestOptions::default();
+
+        let url = format!("{}/files/upload", self.config.base_url);
+        let (head, tail) = Self::multipart_envelope(filename, mime);
+        let content_type = format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY);
+        let content_length = (head.len() + len + tail.len()).to_string();
+        let signature = signing::sign_request("POST", &url, None);
+        let mut headers = vec![
+            ("X-Fingerprint", self.config.fingerprint.as_str()),
+            ("Content-Type", content_type.as_str()),
+            ("Content-Length", content_length.as_str()),
+        ];
+        headers.extend(
+            signature
+                .iter()
+                .map(|(name, value)| (*name, value.as_str())),
+        );
+
+        let mut client = self.create_client(&options)?;
+        info!("-> POST {} ({}, {} bytes)", url, filename, len);
+        let mut request = client
+            .request(Method::Post, &url, &headers)
+            .map_err(|e| tls::connect_error(&url, e))?;
+        request.write_all(head.as_bytes())?;
+
+        let mut buf = vec![0u8; UPLOAD_CHUNK_SIZE];
+        let mut remaining = len;
+        while remaining > 0 {
+            let n = reader.read(&mut buf[..remaining.min(UPLOAD_CHUNK_SIZE)])?;
+            if n == 0 {
+                anyhow::bail!("上传文件提前结束: 还剩 {} 字节", remaining);
+            }
+            request.write_all(&buf[..n])?;
+            remaining -= n;
+        }
+
+        request.write_all(tail.as_bytes())?;
+        request.flush()?;
+
+        let response = request.submit()?;
+        signing::observe_response(&response);
+        let status = response.status();
+        info!("<- {}", status);
+        let response_text =
+            Self::read_response_body(response, &options, self.config.max_response_bytes)?;
+
+        blocking::check_budget("http_upload", self.request_budget(), start.elapsed());
+        metrics::observe_duration(metrics::HTTP_LATENCY_MS, start.elapsed());
+        Ok((status, response_text))
+    }
+
+    /// 上传文件系统中的文件（录音WAV、截图BMP等）
+    ///
+    /// # 参数
+    /// - `path`: 完整路径，例如`/sdcard/mic_debug.wav`
+    /// - `mime`: MIME类型
+    ///
+    /// # 返回
+    /// 文件ID，可放入`prompt_sync`的`files`参数中
+    ///
+    /// # 示例
+    /// ```rust,no_run
+    /// let file_id = client.upload_file("/sdcard/mic_debug.wav", MIME_WAV)?;
+    /// let reply = client.prompt_sync(&session_id, "这段录音里说了什么？", Some(vec![file_id]))?;
+    /// ```
+    pub fn upload_file(&self, path: &str, mime: &str) -> Result<String> {
+        let file = std::fs::File::open(path)?;
+        let len = file.metadata()?.len() as usize;
+        let filename = path.rsplit('/').next().unwrap_or(path);
+
+        let (status, response_text) = self.execute_upload(filename, mime, len, file)?;
+        let uploaded: UploadedFile = self.handle_response(status, &response_text)?;
+        Ok(uploaded.file_id)
+    }
+
+    /// 上传设备日志，供维护人员排查现场设备的问题
+    ///
+    /// 日志先经zlib压缩，以`Content-Encoding: deflate`发送，签名按压缩后的请求体计算。
+    ///
+    /// # 参数
+    /// - `logs`: 日志文本，通常来自`logring::snapshot`
+    pub fn upload_logs(&self, logs: &[u8]) -> Result<()> {
+        blocking::assert_off_main_thread("http_upload_logs");
+        let start = Instant::now();
+        let options = RequestOptions::default();
+
+        let url = format!("{}/device/logs", self.config.base_url);
+        let body = logring::compress(logs);
+        let content_length = body.len().to_string();
+        let signature = signing::sign_request("POST", &url, Some(&body));
+        let mut headers = vec![
+            ("X-Fingerprint", self.config.fingerprint.as_str()),
+            ("Content-Type", "text/plain; charset=utf-8"),
+            ("Content-Encoding", "deflate"),
+            ("Content-Length", content_length.as_str()),
+        ];
+        headers.extend(
+            signature
+                .iter()
+                .map(|(name, value)| (*name, value.as_str())),
+        );
+
+        let mut client = self.create_client(&options)?;
+        info!(
+            "-> POST {} ({} bytes, {} compressed)",
+            url,
+            logs.len(),
+            body.len()
+        );
+        let mut request = client
+            .request(Method::Post, &url, &headers)
+            .map_err(|e| tls::connect_error(&url, e))?;
+        request.write_all(&body)?;
+        request.flush()?;
+
+        let response = request.submit()?;
+        signing::observe_response(&response);
+        let status = response.status();
+        info!("<- {}", status);
+        let response_text =
+            Self::read_response_body(response, &options, self.config.max_response_bytes)?;
+
+        blocking::check_budget("http_upload_logs", self.request_budget(), start.elapsed());
+        metrics::observe_duration(metrics::HTTP_LATENCY_MS, start.elapsed());
+        self.handle_response_unit(status, &response_text)
+    }
+
     /// 创建聊天会话
     ///
     /// # 参数
     /// - `model`: 可选的模型名称
+    /// - `options`: 超时与取消，通常与随后发送的提示共用
     ///
     /// # 返回
     /// 会话ID字符串
-    pub fn create_session(&self, model: Option<&str>) -> Result<String> {
+    pub fn create_session(&self, model: Option<&str>, options: &RequestOptions) -> Result<String> {
         let mut url = format!("{}/chat/create", self.config.base_url);
         if let Some(model) = model {
             url.push_str(&format!("?model={}", model));
         }
 
-        let (status, response_text) = self.execute_get_request(&url)?;
+        let (status, response_text) = self.execute_get_request(&url, options)?;
         let session_info: SessionInfo = self.handle_response(status, &response_text)?;
         Ok(session_info.session_id)
     }
 
+    /// 获取服务端可用的模型列表
+    ///
+    /// 结果缓存`MODELS_TTL`，期间不再访问网络。
+    ///
+    /// # 返回
+    /// 模型信息列表，`ModelInfo::id`可直接传给`create_session`
+    pub fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let url = format!("{}/chat/models", self.config.base_url);
+        let (status, response_text) =
+            self.execute_cached_get_request(&url, cache::MODELS_TTL, &RequestOptions::default())?;
+        self.handle_response(status, &response_text)
+    }
+
+    /// 设置会话的角色（系统提示）
+    ///
+    /// # 参数
+    /// - `session_id`: 会话ID
+    /// - `persona`: 内置角色
+    /// - `options`: 超时与取消
+    pub fn set_persona(
+        &self,
+        session_id: &str,
+        persona: Persona,
+        options: &RequestOptions,
+    ) -> Result<()> {
+        let url = format!("{}/chat/persona/{}", self.config.base_url, session_id);
+        let request_body = PersonaRequest {
+            persona: persona.id().to_string(),
+            system_prompt: persona.system_prompt().to_string(),
+        };
+        let body_json = serde_json::to_string(&request_body)?;
+
+        let (status, response_text) = self.execute_post_request(&url, &body_json, options)?;
+        self.handle_response_unit(status, &response_text)
+    }
+
     /// 发送消息到聊天会话
     ///
     /// # 参数
@@ -163,7 +622,8 @@ impl ApiClient {
         };
         let body_json = serde_json::to_string(&request_body)?;
 
-        let (status, response_text) = self.execute_post_request(&url, &body_json)?;
+        let (status, response_text) =
+            self.execute_post_request(&url, &body_json, &RequestOptions::default())?;
         self.handle_response_unit(status, &response_text)
     }
 
@@ -181,6 +641,26 @@ impl ApiClient {
         session_id: &str,
         message: &str,
         files: Option<Vec<String>>,
+    ) -> Result<String> {
+        self.prompt_sync_with_options(session_id, message, files, &RequestOptions::default())
+    }
+
+    /// 同步发送提示并获取响应，支持截止时间与取消
+    ///
+    /// # 参数
+    /// - `session_id`: 会话ID
+    /// - `message`: 提示消息
+    /// - `files`: 可选的文件列表
+    /// - `options`: 请求控制选项
+    ///
+    /// # 返回
+    /// 聊天响应字符串；超时返回`ApiError::Timeout`，取消返回`ApiError::Cancelled`
+    pub fn prompt_sync_with_options(
+        &self,
+        session_id: &str,
+        message: &str,
+        files: Option<Vec<String>>,
+        options: &RequestOptions,
     ) -> Result<String> {
         let url = format!("{}/chat/prompt/{}", self.config.base_url, session_id);
         let request_body = MessageRequest {
@@ -189,7 +669,36 @@ impl ApiClient {
         };
         let body_json = serde_json::to_string(&request_body)?;
 
-        let (status, response_text) = self.execute_post_request(&url, &body_json)?;
+        let (status, response_text) = self.execute_post_request(&url, &body_json, options)?;
         self.handle_response(status, &response_text)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_body_larger_than_chunk() {
+        let data = vec![b'a'; 3000];
+        let body =
+            ApiClient::read_response_body(&data[..], &RequestOptions::default(), 4096).unwrap();
+        assert_eq!(body.len(), 3000);
+
+        let err =
+            ApiClient::read_response_body(&data[..], &RequestOptions::default(), 2048).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ApiError>(),
+            Some(ApiError::ResponseTooLarge { limit: 2048 })
+        ));
+    }
+
+    #[test]
+    fn test_multipart_envelope() {
+        let (head, tail) = ApiClient::multipart_envelope("a.wav", MIME_WAV);
+        assert!(head.starts_with(&format!("--{}\r\n", MULTIPART_BOUNDARY)));
+        assert!(head.contains("filename=\"a.wav\""));
+        assert!(head.ends_with("Content-Type: audio/wav\r\n\r\n"));
+        assert_eq!(tail, format!("\r\n--{}--\r\n", MULTIPART_BOUNDARY));
+    }
+}
@@ -0,0 +1,162 @@
+// 服务器地址
+//
+// 对话API与语音上传的地址可以在设备上修改（设置→网络→对话地址/上传地址），保存在`DeviceConfig`中。
+// 未修改时使用编译时的默认地址；语音上传地址未单独设置时与对话API相同。
+
+use serde::{Deserialize, Serialize};
+
+/// 默认的对话API地址
+pub const DEFAULT_API_BASE_URL: &str = "http://111.230.48.137:3001/api";
+
+/// 地址最大长度，屏幕上三行以内能显示完
+pub const MAX_URL_LEN: usize = 96;
+
+/// 可修改的服务器地址
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EndpointConfig {
+    /// 对话API地址，None表示使用`DEFAULT_API_BASE_URL`
+    pub base_url: Option<String>,
+    /// 语音上传地址，None表示与对话API相同
+    pub pcm_url: Option<String>,
+}
+
+impl EndpointConfig {
+    /// 实际使用的对话API地址
+    pub fn base_url(&self) -> &str {
+        self.base_url.as_deref().unwrap_or(DEFAULT_API_BASE_URL)
+    }
+
+    /// 实际使用的语音上传地址
+    pub fn pcm_url(&self) -> &str {
+        self.pcm_url.as_deref().unwrap_or(self.base_url())
+    }
+
+    /// 当前地址
+    pub fn get(&self, field: EndpointField) -> &str {
+        match field {
+            EndpointField::BaseUrl => self.base_url(),
+            EndpointField::PcmUrl => self.pcm_url(),
+        }
+    }
+
+    /// 修改地址，与默认值相同时恢复为未设置
+    ///
+    /// # 参数
+    /// * `field` - 要修改的地址
+    /// * `url` - 经过`validate_url`检查的地址
+    pub fn set(&mut self, field: EndpointField, url: String) {
+        match field {
+            EndpointField::BaseUrl => {
+                self.base_url = (url != DEFAULT_API_BASE_URL).then_some(url);
+            }
+            EndpointField::PcmUrl => {
+                self.pcm_url = (url != self.base_url()).then_some(url);
+            }
+        }
+    }
+}
+
+/// 可修改的地址项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndpointField {
+    /// 对话API
+    BaseUrl,
+    /// 语音上传
+    PcmUrl,
+}
+
+impl EndpointField {
+    /// 界面上显示的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::BaseUrl => "服务器地址",
+            Self::PcmUrl => "语音上传地址",
+        }
+    }
+}
+
+/// 检查并规范化服务器地址
+///
+/// 只接受http/https地址，主机名不能为空，不能包含空白或非ASCII字符；
+/// 末尾的`/`会被去掉，因为请求路径以`/`开头拼接在地址后面。
+///
+/// # 返回值
+/// 规范化后的地址，不合法时返回说明原因的错误
+pub fn validate_url(url: &str) -> anyhow::Result<String> {
+    let url = url.trim().trim_end_matches('/');
+    if url.len() > MAX_URL_LEN {
+        anyhow::bail!("地址太长");
+    }
+    if !url.chars().all(|c| c.is_ascii_graphic()) {
+        anyhow::bail!("地址包含无效字符");
+    }
+    let Some(rest) = url
+        .strip_prefix("http://")
+        .or_else(|| url.strip_prefix("https://"))
+    else {
+        anyhow::bail!("地址须以http://或https://开头");
+    };
+    let authority = rest.split('/').next().unwrap_or_default();
+    // IPv6地址中的冒号不是端口分隔符
+    let host = match authority
+        .rsplit_once(':')
+        .filter(|(_, port)| !port.ends_with(']'))
+    {
+        Some((host, port)) => {
+            if port.parse::<u16>().map_or(true, |port| port == 0) {
+                anyhow::bail!("端口无效");
+            }
+            host
+        }
+        None => authority,
+    };
+    if host.is_empty() {
+        anyhow::bail!("缺少主机名");
+    }
+    Ok(url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_url() {
+        assert_eq!(
+            validate_url(" http://10.0.0.2:3001/api/ ").unwrap(),
+            "http://10.0.0.2:3001/api"
+        );
+        assert_eq!(
+            validate_url("https://chat.example.com").unwrap(),
+            "https://chat.example.com"
+        );
+        assert!(validate_url("ftp://example.com").is_err());
+        assert!(validate_url("http://").is_err());
+        assert!(validate_url("http://:3001/api").is_err());
+        assert!(validate_url("http://[fe80::1]:8080").is_ok());
+        assert!(validate_url("http://host:0").is_err());
+        assert!(validate_url("http://host:99999").is_err());
+        assert!(validate_url("http://exa mple.com").is_err());
+        assert!(validate_url(&format!("http://{}", "a".repeat(MAX_URL_LEN))).is_err());
+    }
+
+    #[test]
+    fn test_defaults_and_fallback() {
+        let mut endpoints = EndpointConfig::default();
+        assert_eq!(endpoints.base_url(), DEFAULT_API_BASE_URL);
+        assert_eq!(endpoints.pcm_url(), DEFAULT_API_BASE_URL);
+
+        endpoints.set(EndpointField::BaseUrl, "http://10.0.0.2/api".to_string());
+        assert_eq!(endpoints.pcm_url(), "http://10.0.0.2/api");
+
+        endpoints.set(EndpointField::PcmUrl, "http://10.0.0.3".to_string());
+        assert_eq!(endpoints.get(EndpointField::PcmUrl), "http://10.0.0.3");
+
+        // 设为默认值时恢复为未设置
+        endpoints.set(EndpointField::BaseUrl, DEFAULT_API_BASE_URL.to_string());
+        assert_eq!(endpoints.base_url, None);
+        endpoints.set(EndpointField::PcmUrl, DEFAULT_API_BASE_URL.to_string());
+        assert_eq!(endpoints, EndpointConfig::default());
+    }
+}
@@ -0,0 +1,221 @@
+// IMU原始数据流
+//
+// 调试用：运动actor把原始传感器数据按选定的频率交给上传线程，
+// 上传线程每秒把攒下的样本以NDJSON（每行一个JSON对象）POST到配置的地址，
+// 便于在电脑上回放真实数据、调整运动检测阈值。
+// 上传失败时丢弃该批数据，不影响运动检测。
+
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use embedded_svc::http::{client::Client as HttpClient, Method};
+use embedded_svc::io::Write as EmbeddedWrite;
+use esp_idf_svc::http::client::{Configuration, EspHttpConnection};
+use serde::{Deserialize, Serialize};
+
+use crate::blocking::{self, HTTP_REQUEST_SLACK};
+use crate::hal::SensorData;
+
+/// 上传间隔
+const UPLOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 请求超时
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 队列可缓存的上传间隔数，上传较慢时超出部分直接丢弃
+const QUEUE_INTERVALS: usize = 4;
+
+/// IMU数据流配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImuStreamConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 接收NDJSON的地址
+    pub url: String,
+    /// 采样频率（Hz），不超过运动actor的采样频率
+    pub rate_hz: u32,
+}
+
+impl Default for ImuStreamConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: "http://192.168.1.100:8080/imu".to_string(),
+            rate_hz: 20,
+        }
+    }
+}
+
+impl ImuStreamConfig {
+    /// 每隔多少个传感器样本取一个
+    ///
+    /// # 参数
+    /// * `sample_rate_hz` - 传感器采样频率
+    pub fn decimation(&self, sample_rate_hz: u32) -> u32 {
+        (sample_rate_hz / self.rate_hz.clamp(1, sample_rate_hz.max(1))).max(1)
+    }
+}
+
+/// 一条IMU样本
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ImuSample {
+    /// 设备启动后的时间（毫秒）
+    pub t_ms: u64,
+    #[serde(flatten)]
+    pub data: SensorData,
+}
+
+/// 转换为NDJSON，每个样本一行
+pub fn to_ndjson(samples: &[ImuSample]) -> Result<String> {
+    let mut body = String::new();
+    for sample in samples {
+        body.push_str(&serde_json::to_string(sample)?);
+        body.push('\n');
+    }
+    Ok(body)
+}
+
+/// 运动actor持有的数据出口
+pub struct ImuStreamSink {
+    sender: SyncSender<ImuSample>,
+    /// 每隔多少个样本取一个
+    pub decimation: u32,
+}
+
+impl ImuStreamSink {
+    /// 启动上传线程
+    ///
+    /// 出口被丢弃后上传线程发送完剩余数据后退出。
+    ///
+    /// # 参数
+    /// * `config` - 数据流配置
+    /// * `sample_rate_hz` - 传感器采样频率
+    pub fn start(config: ImuStreamConfig, sample_rate_hz: u32) -> Result<Self> {
+        let decimation = config.decimation(sample_rate_hz);
+        let per_interval = (sample_rate_hz / decimation) as usize;
+        let (sender, receiver) = mpsc::sync_channel(per_interval * QUEUE_INTERVALS);
+
+        log::info!(
+            "IMU数据流已启用: {} ({}Hz)",
+            config.url,
+            sample_rate_hz / decimation
+        );
+        thread::Builder::new()
+            .stack_size(8 * 1024)
+            .name("imu_stream".to_string())
+            .spawn(move || upload_loop(&config.url, receiver))?;
+
+        Ok(Self { sender, decimation })
+    }
+
+    /// 提交一个样本，队列已满时丢弃
+    pub fn push(&self, sample: ImuSample) {
+        let _ = self.sender.try_send(sample);
+    }
+}
+
+/// 按`UPLOAD_INTERVAL`批量上传，直到出口被丢弃
+fn upload_loop(url: &str, receiver: Receiver<ImuSample>) {
+    let mut batch = Vec::new();
+    let mut last_upload = Instant::now();
+    let mut failed = false;
+
+    loop {
+        let disconnected = match receiver.recv_timeout(UPLOAD_INTERVAL) {
+            Ok(sample) => {
+                batch.push(sample);
+                false
+            }
+            Err(RecvTimeoutError::Timeout) => false,
+            Err(RecvTimeoutError::Disconnected) => true,
+        };
+
+        if !batch.is_empty() && (disconnected || last_upload.elapsed() >= UPLOAD_INTERVAL) {
+            // 连续失败只打印第一次，避免WiFi未连接时刷屏
+            match post_batch(url, &batch) {
+                Ok(()) => failed = false,
+                Err(e) if !failed => {
+                    log::warn!("IMU数据上传失败: {}", e);
+                    failed = true;
+                }
+                Err(_) => {}
+            }
+            batch.clear();
+            last_upload = Instant::now();
+        }
+
+        if disconnected {
+            log::info!("IMU数据流已停止");
+            return;
+        }
+    }
+}
+
+/// POST一批样本
+fn post_batch(url: &str, batch: &[ImuSample]) -> Result<()> {
+    blocking::assert_off_main_thread("imu_stream");
+    let start = Instant::now();
+    let body = to_ndjson(batch)?;
+
+    let connection = EspHttpConnection::new(&Configuration {
+        timeout: Some(UPLOAD_TIMEOUT),
+        ..Default::default()
+    })?;
+    let mut client = HttpClient::wrap(connection);
+
+    let content_length = body.len().to_string();
+    let headers = [
+        ("Content-Type", "application/x-ndjson"),
+        ("Content-Length", content_length.as_str()),
+    ];
+    let mut request = client.request(Method::Post, url, &headers)?;
+    request
+        .write_all(body.as_bytes())
+        .map_err(|e| anyhow::anyhow!("写入IMU数据失败: {:?}", e))?;
+    let status = request.submit()?.status();
+    blocking::check_budget(
+        "imu_stream",
+        UPLOAD_TIMEOUT + HTTP_REQUEST_SLACK,
+        start.elapsed(),
+    );
+
+    if !(200..300).contains(&status) {
+        anyhow::bail!("HTTP {}", status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ndjson_and_decimation() {
+        let sample = ImuSample {
+            t_ms: 1500,
+            data: SensorData {
+                accel_x: 0.0,
+                accel_y: 0.0,
+                accel_z: 1000.0,
+                gyro_x: 0.0,
+                gyro_y: 0.0,
+                gyro_z: 0.0,
+                temperature: 25.0,
+                timestamp: 7,
+            },
+        };
+        let body = to_ndjson(&[sample, sample]).unwrap();
+        assert_eq!(body.lines().count(), 2);
+        assert!(body.starts_with(r#"{"t_ms":1500,"accel_x":0.0"#));
+
+        let mut config = ImuStreamConfig::default();
+        assert_eq!(config.decimation(20), 1);
+        config.rate_hz = 5;
+        assert_eq!(config.decimation(20), 4);
+        config.rate_hz = 100;
+        assert_eq!(config.decimation(20), 1);
+    }
+}
@@ -0,0 +1,114 @@
+// 局域网对讲协议
+//
+// 设备通过mDNS广播`_aichat-talk._udp`服务并发现彼此，
+// 对讲时把16kHz单声道PCM按20ms一帧直接发到对方的UDP端口，不做编码。
+// 每个数据包为：4字节头（"AIC" + 版本号）、2字节序号（小端）、PCM样本（小端）。
+// 局域网内丢包很少，接收端只丢弃乱序到达的旧包，不做重传。
+
+use std::net::SocketAddr;
+
+use serde::{Deserialize, Serialize};
+
+/// 对讲使用的UDP端口
+pub const INTERCOM_PORT: u16 = 5004;
+
+/// mDNS服务类型与协议
+pub const SERVICE_TYPE: &str = "_aichat-talk";
+pub const SERVICE_PROTO: &str = "_udp";
+
+/// 对讲语音的采样率（Hz），与扬声器不同时播放前重采样
+pub const INTERCOM_SAMPLE_RATE: u32 = 16000;
+
+/// 每帧样本数（16kHz下20ms）
+pub const FRAME_SAMPLES: usize = 320;
+
+/// 数据包头
+const PACKET_MAGIC: [u8; 4] = *b"AIC\x01";
+
+/// 数据包头与序号的长度
+const HEADER_LEN: usize = PACKET_MAGIC.len() + 2;
+
+/// 发现的其他设备
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntercomPeer {
+    /// 设备名称（mDNS实例名）
+    pub name: String,
+    /// 对讲地址
+    pub addr: SocketAddr,
+}
+
+/// 由WiFi MAC地址生成设备名称，如"aichat-3f2a"
+pub fn device_name(mac: &[u8; 6]) -> String {
+    format!("aichat-{:02x}{:02x}", mac[4], mac[5])
+}
+
+/// 生成数据包
+///
+/// # 参数
+/// * `seq` - 序号，每帧加1，溢出后回绕
+/// * `samples` - 一帧PCM样本
+pub fn encode_packet(seq: u16, samples: &[i16]) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(HEADER_LEN + samples.len() * 2);
+    packet.extend_from_slice(&PACKET_MAGIC);
+    packet.extend_from_slice(&seq.to_le_bytes());
+    for sample in samples {
+        packet.extend_from_slice(&sample.to_le_bytes());
+    }
+    packet
+}
+
+/// 解析数据包
+///
+/// # 返回值
+/// 序号与PCM样本，不是对讲数据包时返回None
+pub fn decode_packet(packet: &[u8]) -> Option<(u16, Vec<i16>)> {
+    let payload = packet.strip_prefix(&PACKET_MAGIC)?;
+    if payload.len() < 2 || payload.len() % 2 != 0 {
+        return None;
+    }
+    let seq = u16::from_le_bytes([payload[0], payload[1]]);
+    let samples = payload[2..]
+        .chunks_exact(2)
+        .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+        .collect();
+    Some((seq, samples))
+}
+
+/// 序号`seq`是否比`last`新（考虑回绕）
+pub fn is_newer(seq: u16, last: u16) -> bool {
+    let delta = seq.wrapping_sub(last);
+    delta != 0 && delta < u16::MAX / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packet_round_trip() {
+        let samples = [0, 1, -1, i16::MAX, i16::MIN];
+        let packet = encode_packet(513, &samples);
+        assert_eq!(packet.len(), HEADER_LEN + samples.len() * 2);
+        assert_eq!(decode_packet(&packet), Some((513, samples.to_vec())));
+
+        assert_eq!(decode_packet(b"HTTP/1.1"), None);
+        assert_eq!(decode_packet(&packet[..packet.len() - 1]), None);
+    }
+
+    #[test]
+    fn test_sequence_wraps() {
+        assert!(is_newer(2, 1));
+        assert!(!is_newer(1, 2));
+        assert!(!is_newer(7, 7));
+        assert!(is_newer(0, u16::MAX));
+        assert!(!is_newer(u16::MAX, 0));
+    }
+
+    #[test]
+    fn test_device_name() {
+        assert_eq!(
+            device_name(&[0x24, 0x0a, 0xc4, 0x12, 0x3f, 0x2a]),
+            "aichat-3f2a"
+        );
+    }
+}
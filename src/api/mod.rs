@@ -1,20 +1,40 @@
+pub mod cache;
 pub mod client;
+pub mod endpoints;
+pub mod imu_stream;
+pub mod intercom;
+pub mod pacing;
 pub mod pcm_client;
+pub mod persona;
+pub mod request;
+pub mod signing;
+pub mod sse;
+pub mod tls;
 pub mod types;
+pub mod weather;
 
 #[derive(Debug, Clone)]
 pub struct ApiConfig {
     pub base_url: String,
+    /// 语音上传地址，None表示与`base_url`相同
+    pub pcm_url: Option<String>,
     pub fingerprint: String,
     pub timeout_secs: u64,
+    /// 响应体最大字节数，超过后返回`ApiError::ResponseTooLarge`而不是截断
+    pub max_response_bytes: usize,
+    /// 语音上传的采样率(Hz)，与麦克风不同时上传前重采样
+    pub upload_sample_rate: u32,
 }
 
 impl Default for ApiConfig {
     fn default() -> Self {
         Self {
             base_url: "http://localhost:3000/api".to_string(),
+            pcm_url: None,
             fingerprint: "esp32-device".to_string(),
             timeout_secs: 300,
+            max_response_bytes: 64 * 1024,
+            upload_sample_rate: 16000,
         }
     }
 }
@@ -0,0 +1,141 @@
+// 语音上传的自适应分块
+//
+// 每写入一块数据测量阻塞时间，与这块音频本身的时长相比得到负载：
+// 小于1表示发送比实时快，大于1表示网络跟不上录音。
+// 负载低时加大分块减少分块编码与系统调用的开销；负载高时减小分块，
+// 让每次阻塞更短，取消与超时检查更及时。
+// 平滑后的负载超过阈值时认为网络变差，降到较低阈值以下才恢复，避免状态来回跳变。
+
+use std::time::Duration;
+
+/// 最小分块（16kHz下32毫秒）
+pub const MIN_CHUNK_SAMPLES: usize = 512;
+/// 最大分块（16kHz下1秒）
+pub const MAX_CHUNK_SAMPLES: usize = 16000;
+/// 初始分块（16kHz下0.25秒）
+const INITIAL_CHUNK_SAMPLES: usize = 4000;
+
+/// 负载低于该值时加大分块
+const GROW_LOAD: f32 = 0.25;
+/// 负载高于该值时减小分块
+const SHRINK_LOAD: f32 = 0.75;
+/// 平滑负载高于该值时认为网络变差
+const DEGRADED_LOAD: f32 = 1.0;
+/// 平滑负载低于该值时认为网络恢复
+const RECOVERED_LOAD: f32 = 0.5;
+/// 参与平滑的负载上限，避免一次长时间阻塞之后很久才能恢复
+const MAX_LOAD: f32 = 4.0;
+/// 负载指数平滑系数
+const LOAD_SMOOTHING: f32 = 0.3;
+
+/// 上传节奏控制器
+#[derive(Debug, Clone)]
+pub struct UploadPacer {
+    chunk_samples: usize,
+    /// 平滑后的负载，尚无测量时为None
+    load: Option<f32>,
+    degraded: bool,
+}
+
+impl Default for UploadPacer {
+    fn default() -> Self {
+        Self {
+            chunk_samples: INITIAL_CHUNK_SAMPLES,
+            load: None,
+            degraded: false,
+        }
+    }
+}
+
+impl UploadPacer {
+    /// 下一块的样本数
+    pub fn chunk_samples(&self) -> usize {
+        self.chunk_samples
+    }
+
+    /// 网络是否处于变差状态
+    pub fn is_degraded(&self) -> bool {
+        self.degraded
+    }
+
+    /// 记录一次写入的耗时，并调整下一块的大小
+    ///
+    /// # 参数
+    /// * `samples` - 这块的样本数
+    /// * `sample_rate` - 样本的采样率(Hz)
+    /// * `elapsed` - 写入阻塞的时间
+    ///
+    /// # 返回值
+    /// 网络状态变化时返回新的状态（true表示变差）
+    pub fn observe(&mut self, samples: usize, sample_rate: u32, elapsed: Duration) -> Option<bool> {
+        if samples == 0 || sample_rate == 0 {
+            return None;
+        }
+        let audio_secs = samples as f32 / sample_rate as f32;
+        let load = (elapsed.as_secs_f32() / audio_secs).min(MAX_LOAD);
+
+        if load < GROW_LOAD {
+            self.chunk_samples = (self.chunk_samples * 2).min(MAX_CHUNK_SAMPLES);
+        } else if load > SHRINK_LOAD {
+            self.chunk_samples = (self.chunk_samples / 2).max(MIN_CHUNK_SAMPLES);
+        }
+
+        let smoothed = match self.load {
+            Some(previous) => previous + (load - previous) * LOAD_SMOOTHING,
+            None => load,
+        };
+        self.load = Some(smoothed);
+
+        let degraded = if self.degraded {
+            smoothed >= RECOVERED_LOAD
+        } else {
+            smoothed > DEGRADED_LOAD
+        };
+        (degraded != self.degraded).then(|| {
+            self.degraded = degraded;
+            degraded
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fast_network_grows_chunks() {
+        let mut pacer = UploadPacer::default();
+        for _ in 0..10 {
+            let samples = pacer.chunk_samples();
+            assert_eq!(
+                pacer.observe(samples, 16000, Duration::from_millis(5)),
+                None
+            );
+        }
+        assert_eq!(pacer.chunk_samples(), MAX_CHUNK_SAMPLES);
+        assert!(!pacer.is_degraded());
+    }
+
+    #[test]
+    fn test_slow_network_shrinks_and_reports_degraded() {
+        let mut pacer = UploadPacer::default();
+        // 写入时间是音频时长的两倍
+        let samples = pacer.chunk_samples();
+        assert_eq!(
+            pacer.observe(samples, 16000, Duration::from_millis(500)),
+            Some(true)
+        );
+        assert_eq!(pacer.chunk_samples(), INITIAL_CHUNK_SAMPLES / 2);
+        for _ in 0..10 {
+            let samples = pacer.chunk_samples();
+            pacer.observe(samples, 16000, Duration::from_secs(1));
+        }
+        assert_eq!(pacer.chunk_samples(), MIN_CHUNK_SAMPLES);
+
+        // 一次快速写入不足以恢复，持续变快后才恢复
+        assert_eq!(pacer.observe(512, 16000, Duration::ZERO), None);
+        let recovered = (0..10).find_map(|_| pacer.observe(512, 16000, Duration::ZERO));
+        assert_eq!(recovered, Some(false));
+        assert!(!pacer.is_degraded());
+    }
+}
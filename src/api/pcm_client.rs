@@ -1,9 +1,17 @@
 use anyhow::Result;
-use embedded_svc::http::{client::Client as HttpClient, Method};
-use embedded_svc::io::Write as EmbeddedWrite;
+use embedded_svc::http::client::{Connection, Request};
+use embedded_svc::http::Method;
+use embedded_svc::io::{Read, Write as EmbeddedWrite};
 use esp_idf_svc::http::client::EspHttpConnection;
-use log::{error, info};
-use std::time::Duration;
+use log::{error, info, warn};
+use std::time::{Duration, Instant};
+
+use crate::api::{signing, tls};
+use crate::blocking::{self, HTTP_REQUEST_SLACK};
+use crate::metrics;
+
+/// 读取并丢弃响应体时使用的缓冲区大小
+const DRAIN_BUFFER_SIZE: usize = 256;
 
 /// PCM音频数据上传配置
 pub struct PcmClientConfig {
@@ -13,6 +21,8 @@ pub struct PcmClientConfig {
     pub session_id: String,
     /// 请求超时时间（秒）
     pub timeout_secs: u64,
+    /// 服务端期望的采样率(Hz)，调用方按该采样率准备数据
+    pub sample_rate: u32,
 }
 
 impl Default for PcmClientConfig {
@@ -21,114 +31,185 @@ impl Default for PcmClientConfig {
             base_url: "http://192.168.1.100:8080".to_string(), // 替换为实际服务器地址
             session_id: "esp32_device_001".to_string(),
             timeout_secs: 30,
+            sample_rate: 16000,
         }
     }
 }
 
 /// PCM音频数据HTTP客户端
+///
+/// 保持一个HTTP连接在多次上传之间复用（HTTP/1.1 keep-alive），省去每次的TCP/TLS握手。
+/// 上传中途出错或被放弃时连接状态未知，下次上传前重新建立连接。
 pub struct PcmClient {
     config: PcmClientConfig,
+    /// 复用的HTTP连接，第一次上传时建立
+    connection: Option<EspHttpConnection>,
+    /// 上一次上传是否完整结束，否则连接不能复用
+    reusable: bool,
 }
 
 impl PcmClient {
     /// 创建新的PCM客户端实例
     pub fn new(config: PcmClientConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            connection: None,
+            reusable: false,
+        }
     }
 
-    /// 创建HTTP客户端连接
-    fn create_client(&self) -> Result<HttpClient<EspHttpConnection>> {
-        let http_config = esp_idf_svc::http::client::Configuration {
+    /// 创建HTTP连接
+    fn create_connection(&self) -> Result<EspHttpConnection> {
+        let mut http_config = esp_idf_svc::http::client::Configuration {
             timeout: Some(Duration::from_secs(self.config.timeout_secs)),
             buffer_size: Some(4096), // 增加缓冲区大小以支持音频流
             ..Default::default()
         };
+        tls::apply(&mut http_config);
+
+        Ok(EspHttpConnection::new(&http_config)?)
+    }
+
+    /// 开始一次流式上传
+    ///
+    /// 请求不带Content-Length，以分块传输编码（Transfer-Encoding: chunked）发送，
+    /// 调用方边录音/重采样边`write`，最后调用`finish`提交。
+    /// 复用上一次上传的连接，上一次没有正常结束时重新建立连接。
+    ///
+    /// # 返回
+    /// 上传句柄，未调用`finish`就丢弃时连接在下次上传前重建
+    pub fn begin_upload(&mut self) -> Result<PcmUpload<'_>> {
+        blocking::assert_off_main_thread("pcm_upload");
+        let start = Instant::now();
+        let url = format!("{}/pcm/{}", self.config.base_url, self.config.session_id);
+        info!("Starting PCM upload to {}", url);
+
+        let budget = Duration::from_secs(self.config.timeout_secs) + HTTP_REQUEST_SLACK;
+        let signature = signing::sign_request("POST", &url, None);
+        let mut headers = vec![("Content-Type", "application/octet-stream")];
+        headers.extend(
+            signature
+                .iter()
+                .map(|(name, value)| (*name, value.as_str())),
+        );
+        let (request, reusable) = self.open_request(&url, &headers)?;
 
-        let connection = EspHttpConnection::new(&http_config)?;
-        Ok(HttpClient::wrap(connection))
+        Ok(PcmUpload {
+            request,
+            reusable,
+            bytes: 0,
+            start,
+            budget,
+        })
+    }
+
+    /// 在复用的连接上发起POST请求
+    ///
+    /// 请求结束前连接标记为不可复用，直到`PcmUpload::finish`读完响应。
+    ///
+    /// # 返回
+    /// 请求与连接复用标记
+    fn open_request<'a>(
+        &'a mut self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<(Request<&'a mut EspHttpConnection>, &'a mut bool)> {
+        if !self.reusable && self.connection.take().is_some() {
+            warn!("PCM connection was left in an unknown state, reconnecting");
+        }
+        if self.connection.is_none() {
+            self.connection = Some(self.create_connection()?);
+        }
+
+        let Self {
+            connection,
+            reusable,
+            ..
+        } = self;
+        *reusable = false;
+        let Some(connection) = connection.as_mut() else {
+            unreachable!("connection was created above");
+        };
+        Connection::initiate_request(connection, Method::Post, url, headers)
+            .map_err(|e| tls::connect_error(url, e))?;
+        Ok((Request::wrap(connection), reusable))
     }
 
     /// 发送PCM音频数据块
     ///
+    /// 单独的一次上传（带Content-Length），同样复用保持的连接。
+    ///
     /// # 参数
-    /// - `pcm_data`: PCM音频数据（16位，16kHz，单声道）
+    /// - `pcm_data`: PCM音频数据（16位，单声道，采样率见`sample_rate`）
     ///
     /// # 返回
     /// 成功返回Ok(())，失败返回错误
-    pub fn send_pcm_chunk(&self, pcm_data: &[u8]) -> Result<()> {
+    pub fn send_pcm_chunk(&mut self, pcm_data: &[u8]) -> Result<()> {
+        blocking::assert_off_main_thread("pcm_upload");
+        let start = Instant::now();
         let url = format!("{}/pcm/{}", self.config.base_url, self.config.session_id);
-
         info!("Sending PCM chunk: {} bytes to {}", pcm_data.len(), url);
 
-        let mut client = self.create_client()?;
-
-        // 设置请求头
-        let headers = [
+        let content_length = pcm_data.len().to_string();
+        let signature = signing::sign_request("POST", &url, Some(pcm_data));
+        let mut headers = vec![
             ("Content-Type", "application/octet-stream"),
-            ("Content-Length", &pcm_data.len().to_string()),
+            ("Content-Length", content_length.as_str()),
         ];
+        headers.extend(
+            signature
+                .iter()
+                .map(|(name, value)| (*name, value.as_str())),
+        );
+        let budget = Duration::from_secs(self.config.timeout_secs) + HTTP_REQUEST_SLACK;
+        let (request, reusable) = self.open_request(&url, &headers)?;
 
-        // 创建POST请求
-        let mut request = client.request(Method::Post, &url, &headers)?;
-
-        // 发送PCM数据
-        request
-            .write_all(pcm_data)
-            .map_err(|e| anyhow::anyhow!("Failed to write PCM data: {:?}", e))?;
-        request
-            .flush()
-            .map_err(|e| anyhow::anyhow!("Failed to flush request: {:?}", e))?;
-
-        // 提交请求并获取响应
-        let response = request.submit()?;
-        let status = response.status();
-
-        if status == 200 {
-            info!("PCM chunk sent successfully");
-            Ok(())
-        } else {
-            error!("Failed to send PCM chunk: HTTP {}", status);
-            Err(anyhow::anyhow!("HTTP error: {}", status))
-        }
+        let mut upload = PcmUpload {
+            request,
+            reusable,
+            bytes: 0,
+            start,
+            budget,
+        };
+        upload.write(pcm_data)?;
+        upload.finish()?;
+        Ok(())
     }
 
     /// 发送PCM音频流
     ///
+    /// 整段音频在一个分块传输的请求中发送。
+    ///
     /// # 参数
     /// - `pcm_stream`: PCM音频数据迭代器
-    /// - `chunk_size`: 每次发送的数据块大小（字节）
+    /// - `chunk_size`: 每次写入的数据块大小（字节）
     ///
     /// # 返回
     /// 成功返回发送的总字节数，失败返回错误
-    pub fn send_pcm_stream<I>(&self, pcm_stream: I, chunk_size: usize) -> Result<usize>
+    pub fn send_pcm_stream<I>(&mut self, pcm_stream: I, chunk_size: usize) -> Result<usize>
     where
         I: Iterator<Item = Vec<u8>>,
     {
-        let mut total_bytes = 0;
+        let chunk_size = chunk_size.max(1);
+        let mut upload = self.begin_upload()?;
         let mut chunk_buffer = Vec::with_capacity(chunk_size);
 
         for data in pcm_stream {
             chunk_buffer.extend_from_slice(&data);
 
-            // 当缓冲区达到指定大小时发送
+            // 攒够一块再写，避免分块编码的开销超过数据本身
             while chunk_buffer.len() >= chunk_size {
-                let chunk: Vec<u8> = chunk_buffer.drain(..chunk_size).collect();
-                self.send_pcm_chunk(&chunk)?;
-                total_bytes += chunk.len();
-
-                // 添加小延迟防止过快发送
-                std::thread::sleep(Duration::from_millis(10));
+                upload.write(&chunk_buffer[..chunk_size])?;
+                chunk_buffer.drain(..chunk_size);
             }
         }
 
         // 发送剩余数据
         if !chunk_buffer.is_empty() {
-            self.send_pcm_chunk(&chunk_buffer)?;
-            total_bytes += chunk_buffer.len();
+            upload.write(&chunk_buffer)?;
         }
 
-        info!("PCM stream sent: {} total bytes", total_bytes);
-        Ok(total_bytes)
+        upload.finish()
     }
 
     /// 更新会话ID
@@ -136,12 +217,90 @@ impl PcmClient {
         self.config.session_id = session_id;
     }
 
+    /// 服务端期望的采样率(Hz)
+    pub fn sample_rate(&self) -> u32 {
+        self.config.sample_rate
+    }
+
     /// 获取当前会话ID
     pub fn session_id(&self) -> &str {
         &self.config.session_id
     }
 }
 
+/// 进行中的PCM上传
+///
+/// 由`PcmClient::begin_upload`创建，`write`写入的数据立即作为一个分块发出。
+pub struct PcmUpload<'a> {
+    request: Request<&'a mut EspHttpConnection>,
+    /// 指向`PcmClient`的连接复用标记，只有`finish`成功读完响应后才置位
+    reusable: &'a mut bool,
+    bytes: usize,
+    start: Instant,
+    /// 整次上传的阻塞预算
+    budget: Duration,
+}
+
+impl PcmUpload<'_> {
+    /// 写入一块PCM数据
+    pub fn write(&mut self, pcm_data: &[u8]) -> Result<()> {
+        if pcm_data.is_empty() {
+            return Ok(());
+        }
+        self.request
+            .write_all(pcm_data)
+            .map_err(|e| anyhow::anyhow!("Failed to write PCM data: {:?}", e))?;
+        self.bytes += pcm_data.len();
+        Ok(())
+    }
+
+    /// 已写入的字节数
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// 结束上传并检查响应
+    ///
+    /// 读完响应体后连接才能复用。
+    ///
+    /// # 返回
+    /// 成功返回上传的总字节数
+    pub fn finish(self) -> Result<usize> {
+        let Self {
+            mut request,
+            reusable,
+            bytes,
+            start,
+            budget,
+        } = self;
+
+        request
+            .flush()
+            .map_err(|e| anyhow::anyhow!("Failed to flush request: {:?}", e))?;
+        let mut response = request.submit()?;
+        signing::observe_response(&response);
+        let status = response.status();
+
+        // 读完响应体，否则下一次请求会读到残留数据
+        let mut buf = [0u8; DRAIN_BUFFER_SIZE];
+        while response.read(&mut buf)? > 0 {}
+
+        blocking::check_budget("pcm_upload", budget, start.elapsed());
+        metrics::observe_duration(metrics::HTTP_LATENCY_MS, start.elapsed());
+
+        if status == 200 {
+            *reusable = true;
+            info!("PCM upload finished: {} bytes", bytes);
+            metrics::record_audio_upload(bytes, start.elapsed());
+            Ok(bytes)
+        } else {
+            error!("Failed to upload PCM: HTTP {}", status);
+            metrics::increment(metrics::HTTP_ERRORS, 1);
+            Err(anyhow::anyhow!("HTTP error: {}", status))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
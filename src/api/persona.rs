@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+/// 内置的对话角色
+///
+/// 创建会话时把角色对应的系统提示发送给服务端，由设备决定助手的行为。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Persona {
+    /// 通用助手
+    #[default]
+    Assistant,
+    /// 儿童模式：语言简单、内容适合儿童
+    Kids,
+    /// 翻译：中英互译
+    Translator,
+}
+
+impl Persona {
+    /// 所有内置角色，按设置界面中的顺序排列
+    pub const ALL: [Persona; 3] = [Persona::Assistant, Persona::Kids, Persona::Translator];
+
+    /// 发送给服务端的角色ID
+    pub fn id(&self) -> &'static str {
+        match self {
+            Persona::Assistant => "assistant",
+            Persona::Kids => "kids",
+            Persona::Translator => "translator",
+        }
+    }
+
+    /// 界面显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Persona::Assistant => "助手",
+            Persona::Kids => "儿童",
+            Persona::Translator => "翻译",
+        }
+    }
+
+    /// 角色对应的系统提示
+    pub fn system_prompt(&self) -> &'static str {
+        match self {
+            Persona::Assistant => {
+                "你是一个运行在小型语音设备上的AI助手。回答要简短、口语化，适合朗读，避免使用表格和代码块。"
+            }
+            Persona::Kids => {
+                "你是一个陪伴小朋友的AI伙伴。用简单、友好、积极的语言回答，每次不超过三句话，不讨论任何不适合儿童的内容。"
+            }
+            Persona::Translator => {
+                "你是一个翻译。用户说中文时翻译成英文，说其他语言时翻译成中文。只输出译文，不要解释。"
+            }
+        }
+    }
+
+    /// 设置界面中的下一个角色（循环）
+    pub fn next(&self) -> Persona {
+        let index = Self::ALL.iter().position(|p| p == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
@@ -0,0 +1,93 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::types::ApiError;
+
+/// 单次对话请求的截止时间
+pub const CHAT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 对话请求的编号
+///
+/// 由`ChatActorManager::prompt`分配，请求的所有结果都带有该编号。
+/// 发送新提示后，之前请求迟到的结果（例如被取消时的`Cancelled`）按编号丢弃。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestId(pub u32);
+
+/// 对话输入
+#[derive(Debug, Clone)]
+pub enum ChatInput {
+    /// 文字提示
+    Text(String),
+    /// 按键说话录下的语音（16kHz、16位、单声道PCM）
+    Voice(Arc<[i16]>),
+}
+
+/// 请求取消令牌
+///
+/// 可在多个线程间共享，调用`cancel`后，正在进行的请求会在下一次检查点返回`ApiError::Cancelled`。
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 取消请求
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// 是否已取消
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// 单次请求的控制选项
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// 请求截止时间，超过后返回`ApiError::Timeout`
+    pub deadline: Option<Instant>,
+    /// 取消令牌
+    pub cancel: Option<CancelToken>,
+}
+
+impl RequestOptions {
+    /// 设置从现在开始计算的超时时间
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Some(Instant::now() + timeout);
+        self
+    }
+
+    /// 设置取消令牌
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// 距离截止时间的剩余时间，没有截止时间时返回None
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// 检查请求是否已被取消或超时
+    ///
+    /// 在连接、发送、读取的各个阶段之间调用。
+    pub fn check(&self) -> Result<()> {
+        if self.cancel.as_ref().is_some_and(|c| c.is_cancelled()) {
+            return Err(ApiError::Cancelled.into());
+        }
+        if self.remaining().is_some_and(|r| r.is_zero()) {
+            return Err(ApiError::Timeout.into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+// 请求签名
+//
+// X-Fingerprint只是设备自报的标识，任何人都能伪造。配置了设备密钥后，
+// 对话API与语音上传的每个请求都附带HMAC-SHA256签名，服务端用同一密钥校验：
+//
+//   签名 = HMAC-SHA256(密钥, "方法\n路径\n时间戳\n随机数\n正文SHA256")
+//
+// 时间戳与随机数用于拒绝重放。流式上传的正文在发起请求时未知，摘要写为`UNSIGNED-PAYLOAD`。
+// 设备时钟可能尚未同步（没有RTC、SNTP未完成），时间戳按服务端响应中的Date头校正。
+// 密钥保存在NVS命名空间`auth`的`device_secret`中（原始字节），没有时不签名。
+
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use embedded_svc::http::Headers;
+use esp_idf_svc::nvs::{EspDefaultNvsPartition, EspNvs};
+use hmac::{Hmac, Mac};
+use log::info;
+use sha2::{Digest, Sha256};
+
+/// NVS命名空间
+const AUTH_NAMESPACE: &str = "auth";
+/// 设备密钥在NVS中的键
+const SECRET_KEY: &str = "device_secret";
+
+/// 密钥最短长度（字节）
+const MIN_SECRET_LEN: usize = 16;
+
+/// 流式上传时代替正文摘要
+pub const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// 本地时间与服务端相差不超过该值（秒）时不校正，避免Date头的1秒精度引起抖动
+const CLOCK_SKEW_TOLERANCE_SECS: i64 = 5;
+
+/// 设备密钥，启动时设置一次
+static SECRET: OnceLock<Vec<u8>> = OnceLock::new();
+
+/// 服务端时间减去本地时间（秒）
+static CLOCK_OFFSET: AtomicI64 = AtomicI64::new(0);
+
+/// 从NVS加载设备密钥，之后的请求都附带签名
+///
+/// # 参数
+/// * `partition` - 默认NVS分区
+///
+/// # 返回值
+/// 是否启用了请求签名
+pub fn install(partition: EspDefaultNvsPartition) -> Result<bool> {
+    let nvs = EspNvs::new(partition, AUTH_NAMESPACE, true)?;
+    let Some(len) = nvs.blob_len(SECRET_KEY)? else {
+        return Ok(false);
+    };
+    let mut buf = vec![0u8; len];
+    let Some(secret) = nvs.get_blob(SECRET_KEY, &mut buf)? else {
+        return Ok(false);
+    };
+    if secret.len() < MIN_SECRET_LEN {
+        anyhow::bail!("设备密钥太短: {}字节", secret.len());
+    }
+    if SECRET.set(secret.to_vec()).is_err() {
+        anyhow::bail!("设备密钥已加载");
+    }
+    info!("已启用请求签名");
+    Ok(true)
+}
+
+/// 生成请求的签名头，没有配置设备密钥时返回空列表
+///
+/// # 参数
+/// * `method` - HTTP方法，如"POST"
+/// * `url` - 完整的请求地址
+/// * `body` - 请求正文，流式上传传None
+pub fn sign_request(method: &str, url: &str, body: Option<&[u8]>) -> Vec<(&'static str, String)> {
+    let Some(secret) = SECRET.get() else {
+        return Vec::new();
+    };
+    let body_hash = body.map_or(UNSIGNED_PAYLOAD.to_string(), |body| {
+        hex(&Sha256::digest(body))
+    });
+    let nonce = format!(
+        "{:08x}{:08x}",
+        unsafe { esp_idf_sys::esp_random() },
+        unsafe { esp_idf_sys::esp_random() }
+    );
+    signature_headers(secret, method, url, &body_hash, server_now(), &nonce)
+}
+
+/// 按服务端响应的Date头校正签名时间戳
+///
+/// # 参数
+/// * `date` - Date头的值（RFC 7231格式）
+pub fn observe_server_date(date: &str) {
+    let Some(server) = parse_http_date(date) else {
+        return;
+    };
+    let offset = server as i64 - local_now() as i64;
+    let offset = if offset.abs() > CLOCK_SKEW_TOLERANCE_SECS {
+        offset
+    } else {
+        0
+    };
+    if CLOCK_OFFSET.swap(offset, Ordering::Relaxed) != offset {
+        info!("签名时间按服务端校正: {:+}秒", offset);
+    }
+}
+
+/// 读取响应的Date头校正签名时间戳，签名被拒绝（时间戳过期）后下一次请求即可成功
+pub fn observe_response(response: &impl Headers) {
+    if let Some(date) = response.header("Date") {
+        observe_server_date(date);
+    }
+}
+
+/// 本地Unix时间（秒），系统时间未同步时从1970年开始计
+fn local_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// 按服务端校正后的Unix时间（秒）
+fn server_now() -> u64 {
+    (local_now() as i64 + CLOCK_OFFSET.load(Ordering::Relaxed)).max(0) as u64
+}
+
+/// 计算签名头
+fn signature_headers(
+    secret: &[u8],
+    method: &str,
+    url: &str,
+    body_hash: &str,
+    timestamp: u64,
+    nonce: &str,
+) -> Vec<(&'static str, String)> {
+    let canonical = format!(
+        "{}\n{}\n{}\n{}\n{}",
+        method,
+        request_path(url),
+        timestamp,
+        nonce,
+        body_hash
+    );
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC接受任意长度的密钥");
+    mac.update(canonical.as_bytes());
+    vec![
+        ("X-Timestamp", timestamp.to_string()),
+        ("X-Nonce", nonce.to_string()),
+        ("X-Content-SHA256", body_hash.to_string()),
+        ("X-Signature", hex(&mac.finalize().into_bytes())),
+    ]
+}
+
+/// 地址中的路径与查询部分，如"http://host:3001/api/chat?x=1"得到"/api/chat?x=1"
+fn request_path(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    match rest.find('/') {
+        Some(index) => &rest[index..],
+        None => "/",
+    }
+}
+
+/// 小写十六进制
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// 解析HTTP Date头（如"Sun, 06 Nov 1994 08:49:37 GMT"），返回Unix时间（秒）
+fn parse_http_date(date: &str) -> Option<u64> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let mut parts = date.split_whitespace().skip(1);
+    let day: u64 = parts.next()?.parse().ok()?;
+    let month_name = parts.next()?;
+    let month = MONTHS.iter().position(|&m| m == month_name)? as u64 + 1;
+    let year: u64 = parts.next()?.parse().ok()?;
+    let mut clock = parts
+        .next()?
+        .split(':')
+        .map(|part| part.parse::<u64>().ok());
+    let (hour, minute, second) = (clock.next()??, clock.next()??, clock.next()??);
+    if parts.next()? != "GMT" || year < 1970 || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // 按公历计算1970-01-01起的天数
+    let (y, m) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let days = 365 * y + y / 4 - y / 100 + y / 400 + (153 * m + 2) / 5 + day - 1 - 719_468;
+    Some(days * 86_400 + hour * 3_600 + minute * 60 + second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_headers() {
+        let headers = signature_headers(
+            b"0123456789abcdef",
+            "POST",
+            "http://10.0.0.2:3001/api/chat/create?model=x",
+            UNSIGNED_PAYLOAD,
+            1_700_000_000,
+            "00112233aabbccdd",
+        );
+        let names: Vec<_> = headers.iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            names,
+            ["X-Timestamp", "X-Nonce", "X-Content-SHA256", "X-Signature"]
+        );
+        assert_eq!(headers[0].1, "1700000000");
+
+        // 服务端按同样的规则计算
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"0123456789abcdef").unwrap();
+        mac.update(
+            b"POST\n/api/chat/create?model=x\n1700000000\n00112233aabbccdd\nUNSIGNED-PAYLOAD",
+        );
+        assert_eq!(headers[3].1, hex(&mac.finalize().into_bytes()));
+
+        // 任何一项不同签名都不同
+        let other = signature_headers(
+            b"0123456789abcdef",
+            "POST",
+            "http://10.0.0.2:3001/api/chat/create?model=y",
+            UNSIGNED_PAYLOAD,
+            1_700_000_000,
+            "00112233aabbccdd",
+        );
+        assert_ne!(headers[3].1, other[3].1);
+    }
+
+    #[test]
+    fn test_body_hash() {
+        assert_eq!(
+            hex(&Sha256::digest(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_request_path() {
+        assert_eq!(request_path("http://host:3001/api/chat"), "/api/chat");
+        assert_eq!(request_path("https://host"), "/");
+        assert_eq!(request_path("http://host/pcm/abc?x=1"), "/pcm/abc?x=1");
+    }
+
+    #[test]
+    fn test_parse_http_date() {
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(784_111_777)
+        );
+        assert_eq!(
+            parse_http_date("Thu, 29 Feb 2024 00:00:00 GMT"),
+            Some(1_709_164_800)
+        );
+        assert_eq!(parse_http_date("Thu, 01 Jan 1970 00:00:00 GMT"), Some(0));
+        assert_eq!(parse_http_date("garbage"), None);
+        assert_eq!(parse_http_date("Sun, 06 Foo 1994 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 PST"), None);
+    }
+}
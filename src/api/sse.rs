@@ -0,0 +1,75 @@
+//! Server-Sent Events解析
+//!
+//! 响应体按任意边界分块到达，解析器缓存不完整的行，
+//! 遇到空行时把累计的`data:`内容作为一个事件交给回调。
+
+use anyhow::Result;
+
+/// SSE解析器
+#[derive(Debug, Default)]
+pub struct SseParser {
+    /// 尚未遇到换行的不完整行
+    line: Vec<u8>,
+    /// 当前事件累计的data内容
+    data: String,
+}
+
+impl SseParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 输入一块响应数据，每解析出一个完整事件调用一次回调
+    ///
+    /// # 参数
+    /// * `bytes` - 响应体数据块
+    /// * `on_event` - 事件回调，参数为事件的data内容（多行data以换行连接）
+    pub fn feed<F>(&mut self, bytes: &[u8], mut on_event: F) -> Result<()>
+    where
+        F: FnMut(&str) -> Result<()>,
+    {
+        for &byte in bytes {
+            if byte != b'\n' {
+                self.line.push(byte);
+                continue;
+            }
+
+            let line = String::from_utf8(std::mem::take(&mut self.line))?;
+            let line = line.strip_suffix('\r').unwrap_or(&line);
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    on_event(&self.data)?;
+                    self.data.clear();
+                }
+            } else if let Some(value) = line.strip_prefix("data:") {
+                if !self.data.is_empty() {
+                    self.data.push('\n');
+                }
+                self.data.push_str(value.strip_prefix(' ').unwrap_or(value));
+            }
+            // 注释行（以`:`开头）和其他字段不需要处理
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_split_across_chunks() {
+        let mut parser = SseParser::new();
+        let mut events = Vec::new();
+        let stream = b": ping\r\ndata: {\"type\":\"status\"}\r\n\r\ndata: a\ndata: b\n\n";
+        for chunk in stream.chunks(5) {
+            parser
+                .feed(chunk, |data| {
+                    events.push(data.to_string());
+                    Ok(())
+                })
+                .unwrap();
+        }
+        assert_eq!(events, vec!["{\"type\":\"status\"}", "a\nb"]);
+    }
+}
@@ -12,6 +12,28 @@ pub struct SessionInfo {
     pub session_id: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelInfo {
+    /// 模型ID，创建会话时使用
+    pub id: String,
+    /// 显示名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl ModelInfo {
+    /// 界面显示用的名称，没有显示名称时使用模型ID
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.id)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadedFile {
+    /// 文件ID，放入`MessageRequest::files`中随消息发送
+    pub file_id: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MessageRequest {
     pub message: String,
@@ -19,6 +41,12 @@ pub struct MessageRequest {
     pub files: Option<Vec<String>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonaRequest {
+    pub persona: String,
+    pub system_prompt: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MessageHistory {
     pub role: String,
@@ -43,20 +71,96 @@ pub struct SseEvent {
     pub message_id: Option<String>,
 }
 
+/// 服务端处理对话请求的阶段，通过SSE `status`事件推送
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatStage {
+    /// 语音转文字
+    Transcribing,
+    /// 生成回复
+    Generating,
+    /// 合成语音
+    Speaking,
+}
+
+/// 服务端从对话中识别出的设备命令，通过SSE `command`事件推送，`content`为命令JSON
+///
+/// 例如：`{"command": "set_alarm", "hour": 7, "minute": 30, "label": "起床", "repeat": true}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum DeviceCommand {
+    /// 设置闹钟
+    SetAlarm {
+        hour: u8,
+        minute: u8,
+        #[serde(default)]
+        label: String,
+        /// 是否每天重复
+        #[serde(default)]
+        repeat: bool,
+    },
+    /// 设置倒计时
+    SetTimer {
+        seconds: u64,
+        #[serde(default)]
+        label: String,
+    },
+    /// 取消所有闹钟与倒计时
+    CancelAlarms,
+    /// 修改音量，`volume`、`delta`、`mute`必须且只能有一个
+    ///
+    /// 例如：`{"command": "set_volume", "delta": -10}`
+    SetVolume {
+        /// 音量（0-100）
+        #[serde(default)]
+        volume: Option<u8>,
+        /// 音量增量
+        #[serde(default)]
+        delta: Option<i8>,
+        #[serde(default)]
+        mute: Option<bool>,
+    },
+    /// 开启或关闭免打扰
+    SetDoNotDisturb { enabled: bool },
+    /// 上传设备日志，维护人员远程排查问题时使用
+    UploadLogs,
+    /// 修改儿童模式，省略的字段保持不变
+    ///
+    /// 例如：`{"command": "set_kids_mode", "enabled": true, "daily_limit_minutes": 45}`
+    SetKidsMode {
+        #[serde(default)]
+        enabled: Option<bool>,
+        /// 每天允许的互动时长（分钟），0表示不限制
+        #[serde(default)]
+        daily_limit_minutes: Option<u16>,
+    },
+}
+
 #[derive(Debug)]
 pub enum ApiError {
+    #[cfg(target_os = "espidf")]
     Http(esp_idf_svc::sys::EspError),
     Json(serde_json::Error),
     Utf8(std::string::FromUtf8Error),
-    Api { status: u16, message: String },
+    Api {
+        status: u16,
+        message: String,
+    },
     SessionNotFound,
     InvalidFingerprint,
     Timeout,
+    Cancelled,
+    ResponseTooLarge {
+        limit: usize,
+    },
+    /// 启用客户端证书后无法建立HTTPS连接（证书被拒绝或服务器证书无法校验）
+    TlsHandshake,
 }
 
 impl std::fmt::Display for ApiError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            #[cfg(target_os = "espidf")]
             ApiError::Http(e) => write!(f, "HTTP request failed: {}", e),
             ApiError::Json(e) => write!(f, "JSON parsing failed: {}", e),
             ApiError::Utf8(e) => write!(f, "UTF-8 conversion failed: {}", e),
@@ -64,12 +168,18 @@ impl std::fmt::Display for ApiError {
             ApiError::SessionNotFound => write!(f, "Session not found"),
             ApiError::InvalidFingerprint => write!(f, "Invalid fingerprint"),
             ApiError::Timeout => write!(f, "Timeout"),
+            ApiError::Cancelled => write!(f, "Cancelled"),
+            ApiError::ResponseTooLarge { limit } => {
+                write!(f, "Response body exceeds {} bytes", limit)
+            }
+            ApiError::TlsHandshake => write!(f, "TLS handshake failed"),
         }
     }
 }
 
 impl std::error::Error for ApiError {}
 
+#[cfg(target_os = "espidf")]
 impl From<esp_idf_svc::sys::EspError> for ApiError {
     fn from(error: esp_idf_svc::sys::EspError) -> Self {
         ApiError::Http(error)
@@ -0,0 +1,188 @@
+// 天气查询客户端
+//
+// 兼容OpenWeather的"当前天气"接口（/data/2.5/weather），
+// 地址与API密钥可在设备配置中修改，未配置密钥时不启用天气功能。
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use embedded_svc::http::{client::Client as HttpClient, Method};
+use esp_idf_svc::http::client::{Configuration, EspHttpConnection};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use super::{cache, client::ApiClient, request::RequestOptions};
+use crate::blocking::{self, HTTP_REQUEST_SLACK};
+
+/// 天气响应体最大字节数
+const MAX_WEATHER_RESPONSE_BYTES: usize = 8 * 1024;
+
+/// 天气服务配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeatherConfig {
+    /// 接口地址
+    pub url: String,
+    /// API密钥，为空时不启用天气功能
+    pub api_key: String,
+    /// 城市名称，例如"Shenzhen"
+    pub city: String,
+    /// 请求超时（秒）
+    pub timeout_secs: u64,
+}
+
+impl Default for WeatherConfig {
+    fn default() -> Self {
+        Self {
+            url: "https://api.openweathermap.org/data/2.5/weather".to_string(),
+            api_key: String::new(),
+            city: "Shenzhen".to_string(),
+            timeout_secs: 10,
+        }
+    }
+}
+
+impl WeatherConfig {
+    /// 是否已配置API密钥
+    pub fn is_enabled(&self) -> bool {
+        !self.api_key.is_empty()
+    }
+}
+
+/// 当前天气
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Weather {
+    /// 城市名称
+    pub city: String,
+    /// 气温（摄氏度）
+    pub temperature: f32,
+    /// 相对湿度（%）
+    pub humidity: u8,
+    /// 天气描述，例如"多云"
+    pub description: String,
+}
+
+/// OpenWeather响应中用到的字段
+#[derive(Debug, Deserialize)]
+struct OpenWeatherResponse {
+    #[serde(default)]
+    name: String,
+    main: OpenWeatherMain,
+    #[serde(default)]
+    weather: Vec<OpenWeatherCondition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenWeatherMain {
+    temp: f32,
+    #[serde(default)]
+    humidity: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenWeatherCondition {
+    #[serde(default)]
+    description: String,
+}
+
+/// 解析OpenWeather格式的当前天气响应
+pub fn parse_weather(json: &str) -> Result<Weather> {
+    let response: OpenWeatherResponse = serde_json::from_str(json)?;
+    Ok(Weather {
+        city: response.name,
+        temperature: response.main.temp,
+        humidity: response.main.humidity.clamp(0.0, 100.0) as u8,
+        description: response
+            .weather
+            .into_iter()
+            .next()
+            .map(|condition| condition.description)
+            .unwrap_or_default(),
+    })
+}
+
+/// 天气查询客户端
+pub struct WeatherClient {
+    config: WeatherConfig,
+}
+
+impl WeatherClient {
+    pub fn new(config: WeatherConfig) -> Self {
+        Self { config }
+    }
+
+    /// 查询当前天气（公制单位，中文描述）
+    ///
+    /// 结果缓存`WEATHER_TTL`，期间WiFi重连触发的刷新直接返回缓存。
+    pub fn current(&self) -> Result<Weather> {
+        let url = format!(
+            "{}?q={}&appid={}&units=metric&lang=zh_cn",
+            self.config.url, self.config.city, self.config.api_key
+        );
+        let key = cache::cache_key(&url, "");
+        if let Some(text) = cache::lookup(&key, cache::WEATHER_TTL) {
+            info!("-> GET {} (weather, cached)", self.config.url);
+            return parse_weather(&text);
+        }
+
+        let text = self.fetch(&url)?;
+        let weather = parse_weather(&text)?;
+        cache::store(&key, &text);
+        Ok(weather)
+    }
+
+    /// 请求天气接口，返回响应体
+    fn fetch(&self, url: &str) -> Result<String> {
+        blocking::assert_off_main_thread("weather_get");
+        let start = Instant::now();
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let options = RequestOptions::default().with_timeout(timeout);
+
+        // 公共天气服务一般只提供HTTPS，使用内置的根证书包校验服务器证书
+        let connection = EspHttpConnection::new(&Configuration {
+            timeout: Some(timeout),
+            crt_bundle_attach: Some(esp_idf_sys::esp_crt_bundle_attach),
+            ..Default::default()
+        })?;
+        let mut client = HttpClient::wrap(connection);
+
+        info!("-> GET {} (weather)", self.config.url);
+        let response = client
+            .request(Method::Get, url, &[("Accept", "application/json")])?
+            .submit()?;
+        let status = response.status();
+        info!("<- {}", status);
+
+        let mut body = Vec::new();
+        ApiClient::read_body_chunked(response, &options, MAX_WEATHER_RESPONSE_BYTES, |chunk| {
+            body.extend_from_slice(chunk);
+            Ok(())
+        })?;
+        blocking::check_budget("weather_get", timeout + HTTP_REQUEST_SLACK, start.elapsed());
+
+        let text = String::from_utf8(body)?;
+        if status != 200 {
+            anyhow::bail!("天气接口错误 {}: {}", status, text);
+        }
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_openweather_response() {
+        let json = r#"{
+            "weather": [{"id": 803, "main": "Clouds", "description": "多云", "icon": "04d"}],
+            "main": {"temp": 21.6, "feels_like": 21.2, "humidity": 64},
+            "name": "Shenzhen"
+        }"#;
+        let weather = parse_weather(json).unwrap();
+        assert_eq!(weather.city, "Shenzhen");
+        assert_eq!(weather.description, "多云");
+        assert_eq!(weather.humidity, 64);
+        assert!((weather.temperature - 21.6).abs() < f32::EPSILON);
+    }
+}
@@ -1,49 +1,1970 @@
-use std::ffi::CStr;
+pub mod alarms;
+pub mod brightness;
+pub mod chat_request;
+pub mod frame_pacing;
+pub mod intercom_playback;
+pub mod kids;
+pub mod push_to_talk;
+pub mod scheduler;
+pub mod selftest;
+pub mod url_editor;
+pub mod voice_guide;
 
 use crate::{
-    actors::wifi::WifiEvent,
-    api::pcm_client::{PcmClient, PcmClientConfig},
-    display::Display,
-    events::{AppEvent, EventHandler, SystemEvent},
+    actors::{
+        chat::{ChatActorManager, ChatEvent},
+        intercom::{IntercomActorManager, IntercomEvent},
+        motion::{MotionActorManager, MotionCalibrationEvent},
+        wakeword::{self, WakeWordActorManager, WakeWordConfig},
+        weather::WeatherActorManager,
+        wifi::{WifiActorManager, WifiEvent, WifiStatus},
+    },
+    api::{
+        client::{MIME_BMP, MIME_WAV},
+        endpoints::EndpointField,
+        intercom::{self, IntercomPeer},
+        persona::Persona,
+        request::ChatInput,
+        types::DeviceCommand,
+    },
+    clock,
+    config::{ConfigStore, DeviceConfig},
+    crash,
+    display::{Display, DisplayState},
+    error::{Error, ErrorCounts, ErrorKind},
+    events::{self, AppEvent, EventHandler, EventSender, SystemEvent, UserInputEvent},
+    graphics::{
+        screens::{
+            about::AboutInfo,
+            endpoint::EndpointEditView,
+            intercom::IntercomView,
+            settings::{BrightnessSetting, LogUploadStatus, SettingAction},
+            spectrum::SPECTRUM_BANDS,
+        },
+        theme::{self, ThemeConfig},
+    },
+    hal::{AmbientLightSensor, AudioInput, RealTimeClock},
+    logring, metrics,
     peripherals::{
-        microphone::{self, i2s_microphone::I2sMicrophone},
-        qmi8658::motion_detector::MotionState,
+        backlight::MIN_BRIGHTNESS,
+        battery::BatteryMonitor,
+        button::BOOT_BUTTON,
+        i2c_bus::SharedI2cBus,
+        microphone::{
+            activity::SoundActivity,
+            capture::{CaptureTask, DEFAULT_CAPTURE_BUFFER_SAMPLES},
+            dsp,
+            fft::{SpectrumAnalyzer, DEFAULT_FFT_SIZE},
+            recorder::AudioRecorder,
+            tap::AudioTap,
+            utterance::UtteranceBuffer,
+        },
+        neopixel::{effects::RingEffect, StatusRingManager},
+        qmi8658::motion_detector::{MotionState, MotionThresholds},
+        speaker::{
+            earcon::Earcon,
+            i2s_speaker::I2sSpeaker,
+            tone,
+            volume::{Volume, VolumeCommand, VOLUME_STEP},
+        },
+        storage::{Storage, StorageLocation},
     },
+    stats::{format_duration, StatsStore},
 };
 
+use std::ffi::CStr;
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+
 use anyhow::Result;
-use esp_idf_sys::sr::{
-    afe_config_init, afe_mode_t_AFE_MODE_HIGH_PERF, afe_type_t_AFE_TYPE_SR,
-    esp_afe_handle_from_config, esp_srmodel_init,
+use esp_idf_svc::sntp::EspSntp;
+use serde::Serialize;
+
+use self::{
+    alarms::AlarmManager,
+    brightness::AutoBrightness,
+    chat_request::ChatRequest,
+    frame_pacing::FramePacer,
+    intercom_playback::IntercomPlayback,
+    kids::{GestureLock, KidsModeConfig, KidsUsageStore, UnlockGesture},
+    push_to_talk::PushToTalkGate,
+    scheduler::Scheduler,
+    selftest::{SelfTestReport, SelfTestStep, TestOutcome},
+    url_editor::{EditorAction, UrlEditor},
+    voice_guide::{Announcement, VoiceGuide},
 };
 
+/// 调试录音文件名（有SD卡时写入SD卡，否则写入SPIFFS）
+const DEBUG_RECORDING_FILE: &str = "mic_debug.wav";
+
+/// 调试录音最长时间（秒）
+const DEBUG_RECORDING_SECONDS: u32 = 10;
+
+/// 截屏文件名前缀，后接序号
+const SCREENSHOT_PREFIX: &str = "screenshot_";
+
+/// 运行统计界面刷新存储空间信息的间隔
+const STORAGE_QUERY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 采集采样率（Hz）
+const SAMPLE_RATE: u32 = 16000;
+
+/// 按键说话最长录音时间（秒）
+const PUSH_TO_TALK_MAX_SECONDS: u32 = 15;
+
+/// 语音导航每帧播放的时长（毫秒），不超出主循环的阻塞预算
+const VOICE_SLICE_MS: u32 = 60;
+
+/// 短于该长度的语音视为误触，不上传
+const PUSH_TO_TALK_MIN_SAMPLES: usize = SAMPLE_RATE as usize * 3 / 10;
+
+/// 唤醒词插话后免按键聆听的时长，没有松开按键的动作，到时自动结束
+const BARGE_IN_LISTEN_DURATION: Duration = Duration::from_secs(6);
+
+/// 最后一段对讲语音之后，对讲界面继续显示"对方正在说话"的时间
+const INTERCOM_RECEIVE_HOLD: Duration = Duration::from_secs(1);
+
+/// 对讲语音每帧最多播放的时长（毫秒），略长于对讲线程每次交来的100ms，积压时能追上
+const INTERCOM_SLICE_MS: u32 = 120;
+
+/// 对讲语音最多积压的时长（毫秒），超出时丢弃最早的部分
+const INTERCOM_MAX_BACKLOG_MS: u32 = 500;
+
+/// 频谱界面最多缓存的麦克风数据块，界面卡顿时丢弃更新的数据
+const SPECTRUM_TAP_CAPACITY: usize = 8;
+
+/// 对话记录中语音提问显示的文字（语音没有转写文本）
+const VOICE_PROMPT_LABEL: &str = "(语音)";
+
+/// 自检时色条的显示时间
+const SELF_TEST_PATTERN_DURATION: Duration = Duration::from_millis(1500);
+
+/// 麦克风自检采集环境声音的时长
+const SELF_TEST_MIC_DURATION: Duration = Duration::from_millis(500);
+
+/// 主界面无操作超过该时间后进入待机表盘
+const STANDBY_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 主循环最长休眠时间，保证事件及时处理；界面按`DeviceConfig::ui_fps`刷新，
+/// 界面计时按实际经过的时间计算，与刷新频率无关
+pub const UI_REFRESH_PERIOD: Duration = Duration::from_millis(50);
+
+/// 状态栏、待机检查和运行统计的刷新周期
+const STATUS_REFRESH_PERIOD: Duration = Duration::from_secs(1);
+
+/// 遥测日志的输出周期
+const TELEMETRY_PERIOD: Duration = Duration::from_secs(60);
+
+/// 儿童模式状态，输出到遥测日志
+#[derive(Debug, Serialize)]
+struct KidsModeStatus {
+    enabled: bool,
+    daily_limit_minutes: u16,
+    /// 今天已用时长（秒）
+    used_secs: u32,
+    /// 今天剩余时长（秒），不限时为null
+    remaining_secs: Option<u32>,
+}
+
+/// 主循环的周期性任务
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AppJob {
+    /// 界面刷新与动画
+    Ui,
+    /// 状态栏数据、待机检查与运行统计
+    Status,
+    /// 输出运行状态与调度延迟
+    Telemetry,
+}
+
+/// 读取电池电量的间隔
+const BATTERY_READ_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 闹钟提示音的播放间隔
+const ALARM_BEEP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 闹钟无人关闭时自动停止的时间
+const ALARM_RING_TIMEOUT: Duration = Duration::from_secs(3 * 60);
+
+/// 免打扰时灯环的亮度系数
+const DND_RING_BRIGHTNESS: f32 = 0.15;
+
 pub struct App<'a> {
     display: Display<'a>,
     network_state: bool,
-    micphone: I2sMicrophone,
+    /// WiFi连接后获得的IP地址，关于界面显示
+    ip_address: Option<String>,
+    /// 麦克风，WiFi连接后移交给采集任务
+    micphone: Option<Box<dyn AudioInput>>,
+    /// 音频采集任务
+    capture: Option<CaptureTask>,
+    /// 麦克风调试录音器
+    recorder: AudioRecorder,
+    /// 扬声器，播放参考信号提供给唤醒词检测做回声消除
+    speaker: I2sSpeaker,
+    /// 可靠性统计
+    stats: StatsStore,
+    /// 文件系统存储
+    storage: Storage,
+    /// 上次刷新存储空间信息的时间
+    last_storage_query: Option<Instant>,
+    /// 设备配置
+    config: ConfigStore,
+    /// 对话actor
+    chat: ChatActorManager,
+    /// 按键说话的语音缓冲
+    utterance: UtteranceBuffer,
+    /// 按住BOOT键超过双击窗口才开始按键说话
+    push_to_talk: PushToTalkGate,
+    /// 等待中的对话请求与最近一次提示
+    chat_request: ChatRequest,
+    /// SNTP时间同步，WiFi连接后启动
+    sntp: Option<EspSntp<'static>>,
+    /// 电池电压检测，板子不支持时为None
+    battery: Option<BatteryMonitor>,
+    /// 上次读取电池电量的时间
+    last_battery_read: Option<Instant>,
+    /// 最近一次用户操作的时间，用于进入待机表盘
+    last_activity: Instant,
+    /// 最近一次检测到有人的时间，用于关闭屏幕
+    last_presence: Instant,
+    /// 环境声音检测结果，由唤醒词线程写入
+    sound: SoundActivity,
+    /// 天气actor，未配置天气服务时为None
+    weather: Option<WeatherActorManager>,
+    /// 闹钟与倒计时
+    alarms: AlarmManager,
+    /// 闹钟开始响铃的时间，未响铃时为None
+    ringing_since: Option<Instant>,
+    /// 上次播放闹钟提示音的时间
+    last_beep: Option<Instant>,
+    /// 预先生成的闹钟提示音
+    alarm_tone: Vec<i16>,
+    /// 状态灯环，没有灯环时为None
+    status_ring: Option<StatusRingManager>,
+    /// 环境光传感器，没有时为None
+    ambient_light: Option<Box<dyn AmbientLightSensor>>,
+    /// 自动亮度控制
+    auto_brightness: AutoBrightness,
+    /// 实时时钟，没有时为None
+    rtc: Option<Box<dyn RealTimeClock>>,
+    /// 运动检测actor
+    motion: MotionActorManager,
+    /// WiFi actor
+    wifi: WifiActorManager,
+    /// 共享I2C总线，自检时扫描设备
+    i2c: SharedI2cBus,
+    /// 进行中的硬件自检
+    self_test: Option<SelfTestReport>,
+    /// 主循环周期性任务调度器
+    scheduler: Scheduler<AppJob>,
+    /// 界面帧率控制与统计
+    frame_pacer: FramePacer,
+    /// 上次遥测以来按类别统计的错误次数
+    errors: ErrorCounts,
+    /// 唤醒词检测actor，WiFi连接后启动
+    wakeword: Option<WakeWordActorManager>,
+    /// 免打扰由扣放手势开启，翻回时自动关闭
+    dnd_by_flip: bool,
+    /// 上一次收到的运动状态，用于区分旋转手势与心跳重复
+    last_motion_state: Option<MotionState>,
+    /// 日志正在上传，期间忽略新的上传请求
+    uploading_logs: bool,
+    /// 儿童模式当天用量
+    kids_usage: KidsUsageStore,
+    /// 家长手势密码输入
+    unlock: GestureLock,
+    /// 本次进入设置是否已经通过验证，离开设置及其子界面后清除
+    settings_unlocked: bool,
+    /// 应用事件发送器，交给WiFi连接后启动的唤醒词线程
+    event_sender: EventSender,
+    /// 免按键聆听的结束时间（唤醒词插话），按键说话时为None
+    hands_free_until: Option<Instant>,
+    /// 局域网对讲，WiFi连接后启动
+    intercom: Option<IntercomActorManager>,
+    /// 对讲时分流麦克风数据，与唤醒词线程共享
+    intercom_tap: AudioTap,
+    /// 局域网中的其他设备
+    intercom_peers: Vec<IntercomPeer>,
+    /// 对讲界面选中的设备
+    intercom_selected: usize,
+    /// 是否正在对讲说话
+    intercom_talking: bool,
+    /// 上次收到对讲语音的时间
+    last_intercom_audio: Option<Instant>,
+    /// 等待播放的对讲语音
+    intercom_playback: IntercomPlayback,
+    /// 频谱界面分流麦克风数据，与唤醒词线程共享
+    spectrum_tap: AudioTap,
+    /// 频谱界面打开期间接收分流的麦克风数据
+    spectrum_samples: Option<Receiver<Vec<i16>>>,
+    /// 频谱界面的FFT与最近样本
+    spectrum: SpectrumAnalyzer,
+    /// 地址输入界面的编辑状态
+    url_editor: Option<UrlEditor>,
+    /// 语音导航的朗读状态
+    voice_guide: VoiceGuide,
 }
 
 impl<'a> App<'a> {
-    pub fn new(display: Display<'a>, micphone: I2sMicrophone) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        mut display: Display<'a>,
+        micphone: Box<dyn AudioInput>,
+        mut speaker: I2sSpeaker,
+        mut stats: StatsStore,
+        storage: Storage,
+        config: ConfigStore,
+        chat: ChatActorManager,
+        battery: Option<BatteryMonitor>,
+        weather: Option<WeatherActorManager>,
+        alarms: AlarmManager,
+        kids_usage: KidsUsageStore,
+        status_ring: Option<StatusRingManager>,
+        ambient_light: Option<Box<dyn AmbientLightSensor>>,
+        rtc: Option<Box<dyn RealTimeClock>>,
+        motion: MotionActorManager,
+        wifi: WifiActorManager,
+        i2c: SharedI2cBus,
+        event_sender: EventSender,
+    ) -> Self {
+        let volume = Volume::new(config.config().volume, config.config().muted);
+        speaker.set_volume(speaker_volume(volume, config.config().do_not_disturb));
+        display.set_volume(volume);
+        display.set_do_not_disturb(config.config().do_not_disturb);
+        display.set_voice_guide(config.config().voice_guide);
+        display.set_current_model(config.config().model.clone());
+        display.set_persona(config.config().active_persona());
+        display.set_kids_mode(config.config().kids_mode.clone());
+        display.set_wake_word(config.config().wake_word.clone());
+        if let Err(e) = display.set_theme(config.config().theme) {
+            log::warn!("应用主题失败: {}", e);
+        }
+        if let Err(e) = display.set_burn_in_config(config.config().burn_in) {
+            log::warn!("应用防烧屏设置失败: {}", e);
+        }
+        display.set_ui_fps(config.config().ui_fps);
+        display.set_sleep_minutes(config.config().display_sleep_minutes);
+        display.set_motion_thresholds(config.config().motion);
+        // 自动亮度在第一次读到环境光后接管
+        if let Err(e) = display.set_brightness(config.config().brightness) {
+            log::warn!("设置屏幕亮度失败: {}", e);
+        }
+        display
+            .set_brightness_setting(brightness_setting(config.config(), ambient_light.is_some()));
+        if let Err(e) = motion.set_thresholds(config.config().motion) {
+            log::warn!("应用运动检测阈值失败: {}", e);
+        }
+        if config.config().imu_stream.enabled {
+            if let Err(e) = motion.set_streaming(&config.config().imu_stream) {
+                log::warn!("启动IMU数据流失败: {}", e);
+            }
+        }
+
+        if let Some(crash) = stats.take_recovered_crash() {
+            let notice = format!("已从崩溃中恢复: {}", crash.reason);
+            if let Err(e) = display.enter_error(notice) {
+                log::warn!("显示崩溃提示失败: {}", e);
+            }
+        }
+
+        let alarm_tone = tone::alarm_beep(speaker.get_sample_rate());
+
+        let frame_pacer = FramePacer::new(config.config().ui_fps, Instant::now());
+        let mut scheduler = Scheduler::new();
+        scheduler.add(
+            AppJob::Ui,
+            frame_pacing::frame_period(frame_pacer.target_fps()),
+            2,
+        );
+        scheduler.add(AppJob::Status, STATUS_REFRESH_PERIOD, 1);
+        scheduler.add(AppJob::Telemetry, TELEMETRY_PERIOD, 0);
+
         Self {
             display,
             network_state: false,
-            micphone,
+            ip_address: None,
+            micphone: Some(micphone),
+            capture: None,
+            recorder: AudioRecorder::new(),
+            speaker,
+            stats,
+            storage,
+            last_storage_query: None,
+            config,
+            chat,
+            utterance: UtteranceBuffer::new(),
+            push_to_talk: PushToTalkGate::default(),
+            chat_request: ChatRequest::default(),
+            sntp: None,
+            battery,
+            last_battery_read: None,
+            last_activity: Instant::now(),
+            last_presence: Instant::now(),
+            sound: SoundActivity::new(),
+            weather,
+            alarms,
+            ringing_since: None,
+            last_beep: None,
+            alarm_tone,
+            status_ring,
+            ambient_light,
+            auto_brightness: AutoBrightness::default(),
+            rtc,
+            motion,
+            wifi,
+            i2c,
+            self_test: None,
+            scheduler,
+            frame_pacer,
+            errors: ErrorCounts::default(),
+            wakeword: None,
+            dnd_by_flip: false,
+            last_motion_state: None,
+            uploading_logs: false,
+            kids_usage,
+            unlock: GestureLock::default(),
+            settings_unlocked: false,
+            event_sender,
+            hands_free_until: None,
+            intercom: None,
+            intercom_tap: AudioTap::new(),
+            intercom_peers: Vec::new(),
+            intercom_selected: 0,
+            intercom_talking: false,
+            last_intercom_audio: None,
+            intercom_playback: IntercomPlayback::new(
+                (intercom::INTERCOM_SAMPLE_RATE * INTERCOM_MAX_BACKLOG_MS / 1000) as usize,
+            ),
+            spectrum_tap: AudioTap::new(),
+            spectrum_samples: None,
+            spectrum: SpectrumAnalyzer::new(DEFAULT_FFT_SIZE),
+            url_editor: None,
+            voice_guide: VoiceGuide::new(),
+        }
+    }
+
+    /// 显示错误界面并按类别计数
+    ///
+    /// # 参数
+    /// * `error` - 错误
+    /// * `retry` - 是否提示按键重试
+    fn show_error(&mut self, error: &Error, retry: bool) -> Result<()> {
+        log::warn!("{:?}错误: {}", error.kind(), error);
+        self.errors.record(error.kind());
+        if retry {
+            self.display.enter_error_with_retry(error.user_message())
+        } else {
+            self.display.enter_error(error.user_message())
         }
     }
 
     fn handle_motion(&mut self, motion_state: MotionState) -> Result<()> {
         let time = unsafe { esp_idf_sys::esp_timer_get_time() };
         println!("收到晃动事件: {:?}, time: {}", motion_state, time);
+
+        // 运动线程每隔一段时间重复发送当前状态作为心跳。保持旋转时滚动类界面继续滚动，
+        // 调音量和移动设置焦点只在进入旋转状态时处理一次，否则每次心跳都会再走一步
+        let repeated = self.last_motion_state.replace(motion_state) == Some(motion_state);
+
+        // 休息界面和家长验证界面中只接受手势密码
+        if matches!(
+            self.display.get_state(),
+            DisplayState::Break | DisplayState::KidsUnlock
+        ) {
+            if let Some(gesture) = UnlockGesture::from_motion(motion_state) {
+                self.push_unlock_gesture(gesture)?;
+            }
+            return Ok(());
+        }
+
+        // 对话界面中旋转手势用于滚动消息：顺时针看更新的，逆时针看更早的
+        if *self.display.get_state() == DisplayState::Conversation {
+            let delta = match motion_state {
+                MotionState::RotatingClockwise => 1,
+                MotionState::RotatingCounterClockwise => -1,
+                _ => 0,
+            };
+            if delta != 0 {
+                return self.display.scroll_conversation(delta);
+            }
+        }
+
+        // 模型选择界面中旋转手势用于切换模型
+        if *self.display.get_state() == DisplayState::ModelSelect {
+            let delta = match motion_state {
+                MotionState::RotatingClockwise => 1,
+                MotionState::RotatingCounterClockwise => -1,
+                _ => 0,
+            };
+            if delta != 0 {
+                if let Some(model) = self.display.move_model_selection(delta)? {
+                    self.announce(Announcement::Model(model.clone()));
+                    self.select_model(model)?;
+                }
+                return Ok(());
+            }
+        }
+
+        // 地址输入界面中旋转手势用于选择字符
+        if *self.display.get_state() == DisplayState::EndpointEdit {
+            let delta = match motion_state {
+                MotionState::RotatingClockwise => 1,
+                MotionState::RotatingCounterClockwise => -1,
+                _ => 0,
+            };
+            if delta != 0 {
+                if let Some(editor) = self.url_editor.as_mut() {
+                    editor.rotate(delta);
+                    let selected = editor.window(0)[0];
+                    self.announce(Announcement::Picker(selected));
+                }
+                return self.update_endpoint_view();
+            }
+        }
+
+        // 设置界面中旋转手势用于移动焦点或调节正在编辑的设置项
+        if *self.display.get_state() == DisplayState::Settings {
+            let delta = match motion_state {
+                MotionState::RotatingClockwise => 1,
+                MotionState::RotatingCounterClockwise => -1,
+                _ => 0,
+            };
+            if delta != 0 {
+                if repeated {
+                    return Ok(());
+                }
+                if let Some(action) = self.display.settings_rotate(delta)? {
+                    self.apply_setting(action)?;
+                }
+                return Ok(());
+            }
+        }
+
+        // 对讲界面中旋转手势用于选择设备
+        if *self.display.get_state() == DisplayState::Intercom {
+            let delta = match motion_state {
+                MotionState::RotatingClockwise => 1,
+                MotionState::RotatingCounterClockwise => -1,
+                _ => 0,
+            };
+            if delta != 0 {
+                return self.move_intercom_selection(delta);
+            }
+        }
+
+        // 旋转手势调节音量：顺时针调大，逆时针调小
+        match motion_state {
+            MotionState::RotatingClockwise if !repeated => self.adjust_volume(VOLUME_STEP)?,
+            MotionState::RotatingCounterClockwise if !repeated => {
+                self.adjust_volume(-VOLUME_STEP)?
+            }
+            _ => {}
+        }
+
         self.display.on_motion(motion_state)?;
         Ok(())
     }
 
-    pub fn update(&mut self) -> Result<()> {
-        self.display.update()?;
+    /// 设置音量，同步到扬声器与界面并保存到NVS
+    ///
+    /// 免打扰时扬声器保持静音，新音量在关闭免打扰后生效。
+    pub fn set_volume(&mut self, volume: Volume) -> Result<()> {
+        self.speaker
+            .set_volume(speaker_volume(volume, self.config.config().do_not_disturb));
+        self.display.set_volume(volume);
+        self.config.update(|config| {
+            config.volume = volume.level();
+            config.muted = volume.is_muted();
+        })
+    }
+
+    /// 保存的音量设置（不受免打扰影响）
+    fn volume(&self) -> Volume {
+        Volume::new(self.config.config().volume, self.config.config().muted)
+    }
+
+    /// 按增量调整音量
+    pub fn adjust_volume(&mut self, delta: i8) -> Result<()> {
+        let mut volume = self.volume();
+        volume.adjust(delta);
+        self.set_volume(volume)?;
+        self.display.show_volume()
+    }
+
+    /// 执行服务端推送的音量命令
+    fn handle_volume_command(&mut self, command: VolumeCommand) -> Result<()> {
+        let mut volume = self.volume();
+        command.apply_to(&mut volume);
+        self.set_volume(volume)
+    }
+
+    /// 开启或关闭免打扰并保存
+    ///
+    /// 免打扰时忽略唤醒词、扬声器静音、灯环调暗，状态栏显示月亮图标。
+    pub fn set_do_not_disturb(&mut self, enabled: bool) -> Result<()> {
+        self.dnd_by_flip = false;
+        self.config
+            .update(|config| config.do_not_disturb = enabled)?;
+        log::info!("免打扰: {}", if enabled { "开" } else { "关" });
+
+        self.speaker
+            .set_volume(speaker_volume(self.volume(), enabled));
+        if let Some(wakeword) = &self.wakeword {
+            wakeword.set_enabled(!enabled);
+        }
+        self.display.set_do_not_disturb(enabled);
+        self.update_status_ring();
+        Ok(())
+    }
+
+    /// 扣放开启免打扰，翻回时关闭由扣放开启的免打扰
+    fn handle_face_down(&mut self, face_down: bool) -> Result<()> {
+        if face_down {
+            if !self.config.config().do_not_disturb {
+                self.set_do_not_disturb(true)?;
+                self.dnd_by_flip = true;
+            }
+        } else if self.dnd_by_flip {
+            self.set_do_not_disturb(false)?;
+        }
+        Ok(())
+    }
+
+    /// 切换唤醒词模型与检测阈值并保存
+    ///
+    /// 检测线程已启动时立即生效：切换模型会重新创建AFE，只改阈值时直接设置。
+    pub fn set_wake_word(&mut self, config: WakeWordConfig) -> Result<()> {
+        if let Some(wakeword) = &self.wakeword {
+            wakeword.configure(config.clone())?;
+        }
+        self.display.set_wake_word(config.clone());
+        self.config.update(|device| device.wake_word = config)
+    }
+
+    /// 设置界面中选择唤醒词模型
+    ///
+    /// # 参数
+    /// * `index` - `wakeword::model_choices`中的序号，0为默认
+    fn select_wake_word_model(&mut self, index: usize) -> Result<()> {
+        let models = self
+            .wakeword
+            .as_ref()
+            .map(WakeWordActorManager::models)
+            .unwrap_or_default();
+        let Some(model) = wakeword::model_choices(&models).get(index).cloned() else {
+            return Ok(());
+        };
+        let mut config = self.config.config().wake_word.clone();
+        config.model = model;
+        self.set_wake_word(config)
+    }
+
+    /// 打开模型选择界面并向服务端请求模型列表（设置→其他→模型）
+    pub fn open_model_select(&mut self) -> Result<()> {
+        self.display.enter_model_select()?;
+        self.chat.list_models()
+    }
+
+    /// 切换对话模型并保存为首选模型
+    ///
+    /// # 参数
+    /// * `model` - 模型ID，None表示使用服务端默认模型
+    pub fn select_model(&mut self, model: Option<String>) -> Result<()> {
+        // 切换模型会重新创建会话，之前的对话记录不再属于当前会话
+        if self.config.config().model != model {
+            self.display.clear_conversation()?;
+        }
+        self.chat.set_model(model.clone())?;
+        self.display.set_current_model(model.clone());
+        self.config.update(|config| config.model = model)
+    }
+
+    /// 切换对话角色并保存
+    ///
+    /// 新角色的系统提示在下次创建会话时发送。儿童模式下只保存，关闭儿童模式后生效。
+    pub fn set_persona(&mut self, persona: Persona) -> Result<()> {
+        let previous = self.config.config().active_persona();
+        self.config.update(|config| config.persona = persona)?;
+        self.apply_persona(previous)
+    }
+
+    /// 把实际使用的角色同步到对话actor与界面，角色变化时清空对话记录
+    fn apply_persona(&mut self, previous: Persona) -> Result<()> {
+        let persona = self.config.config().active_persona();
+        if persona != previous {
+            self.display.clear_conversation()?;
+        }
+        self.chat.set_persona(persona)?;
+        self.display.set_persona(persona);
         Ok(())
     }
 
+    /// 设置界面中切换到下一个内置角色
+    pub fn cycle_persona(&mut self) -> Result<()> {
+        self.set_persona(self.config.config().persona.next())
+    }
+
+    /// 打开设置界面，儿童模式下先进行家长手势验证
+    ///
+    /// 所有进入设置界面的路径都经过这里。本次进入设置后已经验证过时（从子界面返回）直接进入；
+    /// 没有经过设置打开的子界面（启动时的硬件自检）返回时同样需要验证。
+    pub fn open_settings(&mut self) -> Result<()> {
+        if self.config.config().kids_mode.enabled && !self.settings_unlocked {
+            self.unlock.reset();
+            self.display.enter_kids_unlock()
+        } else {
+            self.settings_unlocked = true;
+            // 模型列表由检测线程启动后填充，每次进入设置时更新
+            if let Some(wakeword) = &self.wakeword {
+                self.display.set_wake_word_models(wakeword.models());
+            }
+            self.display.enter_settings()
+        }
+    }
+
+    /// 返回上一级：设置的子界面经过`open_settings`回到设置界面，其他界面见`Display::back`
+    fn back(&mut self) -> Result<()> {
+        if self.display.is_settings_subscreen() {
+            self.open_settings()
+        } else {
+            self.display.back()
+        }
+    }
+
+    /// 打开运行统计界面（设置→工具→统计），存储空间在下一次状态刷新时查询
+    pub fn open_stats(&mut self) -> Result<()> {
+        self.display.set_reliability_stats(self.stats.snapshot());
+        self.display.set_frame_stats(self.frame_pacer.stats());
+        self.last_storage_query = None;
+        self.display.enter_stats()
+    }
+
+    /// 打开关于界面（固件版本、构建信息与网络信息），设置→工具→关于
+    pub fn open_about(&mut self) -> Result<()> {
+        self.display.set_about_info(self.about_info());
+        self.display.enter_about()
+    }
+
+    /// 收集关于界面显示的设备信息
+    fn about_info(&self) -> AboutInfo {
+        let idf_version = unsafe { CStr::from_ptr(esp_idf_sys::esp_get_idf_version()) };
+        AboutInfo {
+            idf_version: idf_version.to_string_lossy().into_owned(),
+            mac: sta_mac(),
+            ip: self.ip_address.clone(),
+            free_heap: unsafe {
+                esp_idf_sys::heap_caps_get_free_size(esp_idf_sys::MALLOC_CAP_8BIT)
+            },
+        }
+    }
+
+    /// 打开对讲界面（设置→工具→对讲）
+    pub fn open_intercom(&mut self) -> Result<()> {
+        self.display.enter_intercom()?;
+        self.update_intercom_view()
+    }
+
+    /// 打开地址输入界面（设置→网络），从当前地址开始编辑
+    ///
+    /// # 参数
+    /// * `field` - 要修改的地址
+    pub fn open_endpoint_editor(&mut self, field: EndpointField) -> Result<()> {
+        let current = self.config.config().endpoints.get(field);
+        self.url_editor = Some(UrlEditor::new(field, current));
+        self.display.enter_endpoint_edit()?;
+        self.update_endpoint_view()
+    }
+
+    /// 显示屏幕镜像页面的二维码（设置→网络→镜像），手机扫码即可在浏览器中观看界面
+    #[cfg(feature = "display-mirror")]
+    pub fn open_mirror_qr(&mut self) -> Result<()> {
+        let Some(ip) = self.ip_address.clone() else {
+            return self.show_error(&Error::Network("WiFi未连接".to_string()), false);
+        };
+        let url = crate::mirror::page_url(&ip);
+        self.display.enter_pairing(&url, ip)
+    }
+
+    /// 同步地址输入界面
+    fn update_endpoint_view(&mut self) -> Result<()> {
+        let Some(editor) = &self.url_editor else {
+            return Ok(());
+        };
+        self.display.set_endpoint_edit_view(EndpointEditView {
+            title: editor.field().label().to_string(),
+            text: editor.text().to_string(),
+            picker: editor.window(2).into_iter().map(|e| e.label()).collect(),
+            error: editor.error().map(str::to_string),
+        })
+    }
+
+    /// 执行地址输入界面中选中的项，地址检查通过后保存并返回设置界面
+    fn confirm_endpoint_edit(&mut self) -> Result<()> {
+        let Some(editor) = self.url_editor.as_mut() else {
+            return Ok(());
+        };
+        let field = editor.field();
+        match editor.confirm() {
+            EditorAction::Edited => self.update_endpoint_view(),
+            EditorAction::Save(url) => {
+                log::info!("{}修改为{}", field.label(), url);
+                self.config
+                    .update(|config| config.endpoints.set(field, url))?;
+                self.chat.set_endpoints(&self.config.config().endpoints)?;
+                self.url_editor = None;
+                self.back()
+            }
+        }
+    }
+
+    /// 打开频谱界面（设置→工具→频谱），开始分流麦克风数据
+    pub fn open_spectrum(&mut self) -> Result<()> {
+        self.spectrum.clear();
+        self.spectrum_samples = Some(self.spectrum_tap.attach(SPECTRUM_TAP_CAPACITY));
+        self.display.enter_spectrum()
+    }
+
+    /// 取出分流的麦克风数据，计算各频段电平交给频谱界面；离开界面后停止分流
+    fn update_spectrum(&mut self) {
+        if *self.display.get_state() != DisplayState::Spectrum {
+            if self.spectrum_samples.take().is_some() {
+                self.spectrum_tap.detach();
+            }
+            return;
+        }
+        if let Some(samples) = &self.spectrum_samples {
+            while let Ok(chunk) = samples.try_recv() {
+                self.spectrum.push(&chunk);
+            }
+        }
+        let levels = self.spectrum.levels(SPECTRUM_BANDS, SAMPLE_RATE);
+        self.display.push_spectrum(&levels);
+    }
+
+    /// 同步对讲界面的设备列表与收发状态
+    fn update_intercom_view(&mut self) -> Result<()> {
+        let receiving = self
+            .last_intercom_audio
+            .is_some_and(|t| t.elapsed() < INTERCOM_RECEIVE_HOLD);
+        self.display.set_intercom_view(IntercomView {
+            peers: self.intercom_peers.iter().map(|p| p.name.clone()).collect(),
+            selected: self.intercom_selected,
+            talking: self.intercom_talking,
+            receiving,
+        })
+    }
+
+    /// 在对讲界面中切换选中的设备，说话期间不切换
+    fn move_intercom_selection(&mut self, delta: i32) -> Result<()> {
+        if self.intercom_talking || self.intercom_peers.is_empty() {
+            return Ok(());
+        }
+        let total = self.intercom_peers.len() as i32;
+        self.intercom_selected = (self.intercom_selected as i32 + delta).rem_euclid(total) as usize;
+        let name = self.intercom_peers[self.intercom_selected].name.clone();
+        self.announce(Announcement::Peer(name));
+        self.update_intercom_view()
+    }
+
+    /// 开始向选中的设备发送语音
+    fn start_intercom_talk(&mut self) -> Result<()> {
+        let (Some(intercom), Some(peer)) = (
+            self.intercom.as_ref(),
+            self.intercom_peers.get(self.intercom_selected),
+        ) else {
+            return Ok(());
+        };
+        intercom.start_talking(peer)?;
+        self.intercom_talking = true;
+        self.update_intercom_view()
+    }
+
+    /// 停止发送语音
+    fn stop_intercom_talk(&mut self) -> Result<()> {
+        if !std::mem::take(&mut self.intercom_talking) {
+            return Ok(());
+        }
+        if let Some(intercom) = &self.intercom {
+            intercom.stop_talking()?;
+        }
+        self.update_intercom_view()
+    }
+
+    fn handle_intercom(&mut self, event: IntercomEvent) -> Result<()> {
+        match event {
+            IntercomEvent::Peers(peers) => {
+                // 列表变化后尽量保持选中同一台设备
+                let selected = self
+                    .intercom_peers
+                    .get(self.intercom_selected)
+                    .and_then(|current| peers.iter().position(|p| p.name == current.name))
+                    .unwrap_or(0);
+                self.intercom_peers = peers;
+                self.intercom_selected = selected;
+                self.update_intercom_view()
+            }
+            IntercomEvent::Audio(samples) => {
+                // 只在对讲界面播放，避免在其他界面突然出声
+                if *self.display.get_state() != DisplayState::Intercom {
+                    return Ok(());
+                }
+                self.last_intercom_audio = Some(Instant::now());
+                self.intercom_playback.push(&samples);
+                self.update_intercom_view()
+            }
+        }
+    }
+
+    /// 播放一段等待中的对讲语音，每帧调用一次；离开对讲界面后放弃未播放的部分
+    fn update_intercom_playback(&mut self) {
+        if *self.display.get_state() != DisplayState::Intercom {
+            self.intercom_playback.clear();
+            return;
+        }
+        let slice_samples = (intercom::INTERCOM_SAMPLE_RATE * INTERCOM_SLICE_MS / 1000) as usize;
+        if let Some(slice) = self.intercom_playback.next_slice(slice_samples) {
+            if let Err(e) = self
+                .speaker
+                .play_at_rate(slice, intercom::INTERCOM_SAMPLE_RATE)
+            {
+                log::warn!("对讲语音播放失败: {}", e);
+                self.intercom_playback.clear();
+            }
+        }
+    }
+
+    /// 输入一步家长手势密码，正确后进入设置界面
+    fn push_unlock_gesture(&mut self, gesture: UnlockGesture) -> Result<()> {
+        let code = &self.config.config().kids_mode.unlock_code;
+        if self.unlock.push(code, gesture, Instant::now()) {
+            log::info!("家长验证通过");
+            self.settings_unlocked = true;
+            return self.open_settings();
+        }
+        self.display.set_unlock_progress(self.unlock.entered());
+        Ok(())
+    }
+
+    /// 修改儿童模式配置并保存，同步角色与休息界面
+    fn update_kids_mode(&mut self, f: impl FnOnce(&mut KidsModeConfig)) -> Result<()> {
+        let previous = self.config.config().active_persona();
+        self.config.update(|config| f(&mut config.kids_mode))?;
+        let kids_mode = self.config.config().kids_mode.clone();
+        log::info!(
+            "儿童模式: {}, 每天{}分钟",
+            if kids_mode.enabled { "开" } else { "关" },
+            kids_mode.daily_limit_minutes
+        );
+        self.display.set_kids_mode(kids_mode);
+        self.apply_persona(previous)?;
+        self.check_kids_quota()
+    }
+
+    /// 开启或关闭儿童模式（设置界面）
+    ///
+    /// 开启后角色固定为儿童角色，每天的互动时长受限，进入设置需要家长手势密码。
+    pub fn set_kids_mode(&mut self, enabled: bool) -> Result<()> {
+        self.update_kids_mode(|kids_mode| kids_mode.enabled = enabled)
+    }
+
+    /// 设置儿童模式每天允许的互动时长（设置界面）
+    ///
+    /// # 参数
+    /// * `minutes` - 分钟数，0表示不限制
+    pub fn set_kids_daily_limit(&mut self, minutes: u16) -> Result<()> {
+        self.update_kids_mode(|kids_mode| kids_mode.daily_limit_minutes = minutes)
+    }
+
+    /// 处理服务端推送的儿童模式命令（`DeviceCommand::SetKidsMode`）
+    ///
+    /// # 参数
+    /// * `enabled` - 是否开启，None保持不变
+    /// * `daily_limit_minutes` - 每天允许的互动时长，None保持不变
+    fn handle_kids_mode_command(
+        &mut self,
+        enabled: Option<bool>,
+        daily_limit_minutes: Option<u16>,
+    ) -> Result<()> {
+        self.update_kids_mode(|kids_mode| {
+            if let Some(enabled) = enabled {
+                kids_mode.enabled = enabled;
+            }
+            if let Some(minutes) = daily_limit_minutes {
+                kids_mode.daily_limit_minutes = minutes;
+            }
+        })
+    }
+
+    /// 当前儿童模式状态的JSON，输出到遥测日志
+    fn kids_mode_status_json(&self) -> Result<String> {
+        let kids_mode = &self.config.config().kids_mode;
+        let usage = self.kids_usage.usage();
+        Ok(serde_json::to_string(&KidsModeStatus {
+            enabled: kids_mode.enabled,
+            daily_limit_minutes: kids_mode.daily_limit_minutes,
+            used_secs: usage.used_secs,
+            remaining_secs: usage.remaining_secs(kids_mode.daily_limit_secs()),
+        })?)
+    }
+
+    /// 累计儿童模式的互动时间
+    ///
+    /// 录音、对话和主界面有操作时计时，待机表盘与其他界面不计时。
+    fn update_kids_usage(&mut self) -> Result<()> {
+        let active = self.config.config().kids_mode.enabled
+            && matches!(
+                self.display.get_state(),
+                DisplayState::Main
+                    | DisplayState::Conversation
+                    | DisplayState::Listening
+                    | DisplayState::Thinking
+                    | DisplayState::Reply(_)
+            )
+            && self.last_activity.elapsed() < STANDBY_IDLE_TIMEOUT;
+        // 关闭儿童模式时也检查日期，重新开启时不会沿用之前的用量
+        let today = clock::now().map(|time| kids::day_key(&time));
+        if let Err(e) = self.kids_usage.tick(today, active) {
+            log::warn!("保存儿童模式用量失败: {}", e);
+        }
+        self.check_kids_quota()
+    }
+
+    /// 当天时间用完后在空闲界面进入休息界面，不打断进行中的对话；
+    /// 跨天、关闭儿童模式或放宽限制后回到主界面
+    fn check_kids_quota(&mut self) -> Result<()> {
+        let kids_mode = &self.config.config().kids_mode;
+        let exhausted = kids_mode.enabled
+            && self
+                .kids_usage
+                .usage()
+                .is_exhausted(kids_mode.daily_limit_secs());
+        let state = self.display.get_state();
+
+        if exhausted
+            && matches!(
+                state,
+                DisplayState::Main
+                    | DisplayState::Conversation
+                    | DisplayState::Welcome
+                    | DisplayState::Standby
+            )
+            && !self.display.is_streaming_reply()
+        {
+            log::info!("儿童模式今天的时间已用完");
+            self.unlock.reset();
+            self.display.enter_break()
+        } else if !exhausted && *state == DisplayState::Break {
+            self.display.enter_main()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 开启或关闭防烧屏并保存（设置→屏幕）
+    ///
+    /// 关闭时立即恢复零偏移，位移间隔等其他参数保持不变。
+    pub fn set_burn_in(&mut self, enabled: bool) -> Result<()> {
+        let mut burn_in = self.config.config().burn_in;
+        burn_in.enabled = enabled;
+        self.display.set_burn_in_config(burn_in)?;
+        self.config.update(|config| config.burn_in = burn_in)
+    }
+
+    /// 修改界面目标帧率并保存（设置→屏幕→帧率）
+    ///
+    /// # 参数
+    /// * `fps` - 目标帧率，限制在`MIN_UI_FPS`到`MAX_UI_FPS`之间
+    pub fn set_ui_fps(&mut self, fps: u8) -> Result<()> {
+        let fps = self.frame_pacer.set_target_fps(fps);
+        self.scheduler
+            .set_period(AppJob::Ui, frame_pacing::frame_period(fps), Instant::now());
+        self.display.set_ui_fps(fps);
+        log::info!("界面目标帧率: {}fps", fps);
+        self.config.update(|config| config.ui_fps = fps)
+    }
+
+    /// 修改无人时关闭屏幕的等待时间并保存（设置→屏幕→自动关屏）
+    ///
+    /// # 参数
+    /// * `minutes` - 待机时无人多少分钟后关闭屏幕，0表示不关闭
+    pub fn set_display_sleep_minutes(&mut self, minutes: u16) -> Result<()> {
+        if minutes == 0 {
+            self.display.wake()?;
+        }
+        self.display.set_sleep_minutes(minutes);
+        self.config
+            .update(|config| config.display_sleep_minutes = minutes)
+    }
+
+    /// 手动设置屏幕亮度并保存，同时关闭自动亮度
+    ///
+    /// # 参数
+    /// * `percent` - 亮度，限制在`MIN_BRIGHTNESS`到100之间
+    pub fn set_brightness(&mut self, percent: u8) -> Result<()> {
+        let percent = percent.clamp(MIN_BRIGHTNESS, 100);
+        self.display.set_brightness(percent)?;
+        self.config.update(|config| {
+            config.brightness = percent;
+            config.auto_brightness = false;
+        })?;
+        self.display.set_brightness_setting(brightness_setting(
+            self.config.config(),
+            self.ambient_light.is_some(),
+        ));
+        Ok(())
+    }
+
+    /// 开启或关闭自动亮度并保存，关闭后恢复手动设置的亮度
+    pub fn set_auto_brightness(&mut self, enabled: bool) -> Result<()> {
+        self.auto_brightness.reset();
+        if !enabled {
+            self.display
+                .set_brightness(self.config.config().brightness)?;
+        }
+        self.config
+            .update(|config| config.auto_brightness = enabled)?;
+        self.display.set_brightness_setting(brightness_setting(
+            self.config.config(),
+            self.ambient_light.is_some(),
+        ));
+        Ok(())
+    }
+
+    /// 切换自动亮度
+    pub fn toggle_auto_brightness(&mut self) -> Result<()> {
+        self.set_auto_brightness(!self.config.config().auto_brightness)
+    }
+
+    /// 开启或关闭语音导航并保存
+    pub fn set_voice_guide(&mut self, enabled: bool) -> Result<()> {
+        if !enabled {
+            self.voice_guide.stop();
+        }
+        self.config.update(|config| config.voice_guide = enabled)?;
+        self.display.set_voice_guide(enabled);
+        log::info!("语音导航: {}", if enabled { "开" } else { "关" });
+        Ok(())
+    }
+
+    /// 高亮项变化时朗读（开启了语音导航时）
+    fn announce(&mut self, item: Announcement) {
+        if self.config.config().voice_guide {
+            self.voice_guide.highlight(item, Instant::now());
+        }
+    }
+
+    /// 开始朗读停留下来的高亮项，并播放当前片段的下一段
+    fn update_voice_guide(&mut self) {
+        let sample_rate = self.speaker.get_sample_rate();
+        if let Some(item) = self.voice_guide.take_due(Instant::now()) {
+            let clip = self.load_voice_clip(&item).unwrap_or_else(|| {
+                // 没有语音片段时用短提示音表示高亮项已变化
+                tone::sine_tone(1500, 30, 6000, sample_rate)
+            });
+            self.voice_guide.start(clip);
+        }
+
+        let slice_samples = (sample_rate * VOICE_SLICE_MS / 1000) as usize;
+        if let Some(slice) = self.voice_guide.next_slice(slice_samples) {
+            if let Err(e) = self.speaker.play(slice) {
+                log::warn!("语音导航播放失败: {}", e);
+                self.voice_guide.stop();
+            }
+        }
+    }
+
+    /// 读取高亮项的语音片段，有SD卡时优先读SD卡
+    fn load_voice_clip(&self, item: &Announcement) -> Option<Vec<i16>> {
+        let name = item.clip_name();
+        let bytes = [self.storage.bulk_location(), StorageLocation::Internal]
+            .into_iter()
+            .find_map(|location| self.storage.read(location, &name).ok());
+        let Some(bytes) = bytes else {
+            log::info!("没有语音片段: {}", name);
+            return None;
+        };
+        Some(voice_guide::decode_clip(
+            &bytes,
+            self.speaker.get_sample_rate(),
+        ))
+    }
+
+    /// 按环境光调整屏幕亮度（有传感器且开启了自动亮度时）
+    fn update_brightness(&mut self) -> Result<()> {
+        if !self.config.config().auto_brightness {
+            return Ok(());
+        }
+        let Some(sensor) = self.ambient_light.as_mut() else {
+            return Ok(());
+        };
+        let lux = sensor.read_lux()?;
+        if let Some(percent) = self.auto_brightness.update(lux) {
+            log::info!("环境光{:.0}lux，屏幕亮度调整为{}%", lux, percent);
+            self.display.set_brightness(percent)?;
+        }
+        Ok(())
+    }
+
+    /// 切换界面主题并保存（设置→显示→主题，旋转选择时立即生效）
+    pub fn set_theme(&mut self, theme: ThemeConfig) -> Result<()> {
+        self.display.set_theme(theme)?;
+        self.config.update(|config| config.theme = theme)
+    }
+
+    /// 添加闹钟（设置界面或语音命令）
+    ///
+    /// # 参数
+    /// * `hour` - 小时（0-23）
+    /// * `minute` - 分钟（0-59）
+    /// * `label` - 标签，为空时显示"闹钟"
+    /// * `daily` - 是否每天重复
+    pub fn add_alarm(&mut self, hour: u8, minute: u8, label: String, daily: bool) -> Result<()> {
+        let id = self.alarms.add_alarm(hour, minute, label, daily)?;
+        log::info!("已添加闹钟 #{} {:02}:{:02}", id, hour, minute);
+        Ok(())
+    }
+
+    /// 开始倒计时（设置界面或语音命令）
+    pub fn start_timer(&mut self, duration: Duration, label: String) -> Result<()> {
+        let id = self.alarms.start_timer(duration, label)?;
+        log::info!("已开始倒计时 #{} {:?}", id, duration);
+        Ok(())
+    }
+
+    /// 删除所有闹钟与倒计时
+    pub fn clear_alarms(&mut self) -> Result<()> {
+        self.alarms.clear()
+    }
+
+    /// 执行服务端从对话中识别出的设备命令
+    fn handle_device_command(&mut self, command: DeviceCommand) -> Result<()> {
+        log::info!("收到设备命令: {:?}", command);
+        match command {
+            DeviceCommand::SetAlarm {
+                hour,
+                minute,
+                label,
+                repeat,
+            } => self.add_alarm(hour, minute, label, repeat),
+            DeviceCommand::SetTimer { seconds, label } => {
+                self.start_timer(Duration::from_secs(seconds), label)
+            }
+            DeviceCommand::CancelAlarms => self.clear_alarms(),
+            DeviceCommand::SetVolume {
+                volume,
+                delta,
+                mute,
+            } => self.handle_volume_command(VolumeCommand::from_fields(volume, delta, mute)?),
+            DeviceCommand::SetDoNotDisturb { enabled } => self.set_do_not_disturb(enabled),
+            DeviceCommand::UploadLogs => self.upload_logs(),
+            DeviceCommand::SetKidsMode {
+                enabled,
+                daily_limit_minutes,
+            } => self.handle_kids_mode_command(enabled, daily_limit_minutes),
+        }
+    }
+
+    /// 闹钟到期：显示全屏提醒并开始响铃
+    fn ring_alarm(&mut self, alarm: alarms::Alarm) -> Result<()> {
+        // 正在进行的对话请求不再等待结果
+        if self.chat_request.finish() {
+            self.chat.cancel();
+            self.display.end_reply_stream();
+        }
+        self.ringing_since = Some(Instant::now());
+        self.last_beep = None;
+        self.display.enter_alarm(alarm.title().to_string())
+    }
+
+    /// 关闭闹钟提醒并返回主界面
+    fn dismiss_alarm(&mut self) -> Result<()> {
+        self.ringing_since = None;
+        self.last_beep = None;
+        self.display.enter_main()
+    }
+
+    /// 响铃中每隔`ALARM_BEEP_INTERVAL`播放一次提示音，超过`ALARM_RING_TIMEOUT`自动关闭
+    fn update_alarm(&mut self) -> Result<()> {
+        let Some(since) = self.ringing_since else {
+            return Ok(());
+        };
+        if !matches!(self.display.get_state(), DisplayState::Alarm(_)) {
+            self.ringing_since = None;
+            return Ok(());
+        }
+        if since.elapsed() >= ALARM_RING_TIMEOUT {
+            return self.dismiss_alarm();
+        }
+
+        let due = self
+            .last_beep
+            .map_or(true, |t| t.elapsed() >= ALARM_BEEP_INTERVAL);
+        if due {
+            self.last_beep = Some(Instant::now());
+            self.speaker.play(&self.alarm_tone)?;
+        }
+        Ok(())
+    }
+
+    /// 应用运动检测阈值并保存
+    pub fn set_motion_thresholds(&mut self, thresholds: MotionThresholds) -> Result<()> {
+        self.motion.set_thresholds(thresholds)?;
+        self.display.set_motion_thresholds(thresholds);
+        self.config.update(|config| config.motion = thresholds)
+    }
+
+    /// 应用设置界面中修改的设置项
+    fn apply_setting(&mut self, action: SettingAction) -> Result<()> {
+        match action {
+            SettingAction::Volume(level) => {
+                self.set_volume(Volume::new(level, self.volume().is_muted()))
+            }
+            SettingAction::Muted(muted) => {
+                self.set_volume(Volume::new(self.volume().level(), muted))
+            }
+            SettingAction::DoNotDisturb(enabled) => self.set_do_not_disturb(enabled),
+            SettingAction::VoiceGuide(enabled) => self.set_voice_guide(enabled),
+            SettingAction::Brightness(percent) => self.set_brightness(percent),
+            SettingAction::AutoBrightness(enabled) => self.set_auto_brightness(enabled),
+            SettingAction::Theme(theme) => self.set_theme(theme),
+            SettingAction::TestPattern => self.display.enter_test_pattern(),
+            SettingAction::BurnIn(enabled) => self.set_burn_in(enabled),
+            SettingAction::UiFps(fps) => self.set_ui_fps(fps),
+            SettingAction::SleepMinutes(minutes) => self.set_display_sleep_minutes(minutes),
+            SettingAction::MotionSensitivity(sensitivity) => {
+                self.set_motion_thresholds(MotionThresholds::preset(sensitivity))
+            }
+            SettingAction::CalibrateMotion => self.calibrate_motion(),
+            SettingAction::WakeThreshold(threshold) => {
+                let mut config = self.config.config().wake_word.clone();
+                config.threshold = Some(threshold);
+                self.set_wake_word(config)
+            }
+            SettingAction::WakeWordModel(index) => self.select_wake_word_model(index),
+            SettingAction::Persona(persona) => self.set_persona(persona),
+            SettingAction::ModelSelect => self.open_model_select(),
+            SettingAction::UploadLogs => self.upload_logs(),
+            SettingAction::DebugRecording => self.toggle_debug_recording(),
+            SettingAction::About => self.open_about(),
+            SettingAction::Intercom => self.open_intercom(),
+            SettingAction::Spectrum => self.open_spectrum(),
+            SettingAction::Stats => self.open_stats(),
+            SettingAction::EditEndpoint(field) => self.open_endpoint_editor(field),
+            #[cfg(feature = "display-mirror")]
+            SettingAction::MirrorQr => self.open_mirror_qr(),
+            SettingAction::KidsMode(enabled) => self.set_kids_mode(enabled),
+            SettingAction::KidsDailyLimit(minutes) => self.set_kids_daily_limit(minutes),
+        }
+    }
+
+    /// 上传内存中的日志供维护人员排查问题（设置界面，或服务端推送`upload_logs`命令）
+    ///
+    /// 上传在对话actor中进行，结果通过`ChatEvent::LogsUploaded`返回。正在上传时忽略。
+    pub fn upload_logs(&mut self) -> Result<()> {
+        if self.uploading_logs {
+            return Ok(());
+        }
+        let logs = logring::snapshot();
+        log::info!("上传日志: {} 字节", logs.len());
+        self.chat.upload_logs(logs)?;
+        self.uploading_logs = true;
+        self.display.set_log_upload(LogUploadStatus::Uploading);
+        Ok(())
+    }
+
+    /// 开始运动阈值自动校准（设置→灵敏度→校准动作），设备需静止放置约3秒
+    pub fn calibrate_motion(&mut self) -> Result<()> {
+        self.motion.calibrate()?;
+        self.display.enter_calibrating()
+    }
+
+    /// 开始硬件自检（设置界面，或启动时按住BOOT键）
+    pub fn start_self_test(&mut self) -> Result<()> {
+        let report = SelfTestReport::default();
+        self.display.set_self_test_report(report.clone());
+        self.self_test = Some(report);
+        self.display.enter_self_test()
+    }
+
+    /// 推进硬件自检，每帧调用一次
+    ///
+    /// 每帧最多执行一步，界面在两步之间刷新。离开自检界面后放弃剩余检测。
+    fn poll_self_test(&mut self) -> Result<()> {
+        let Some(mut report) = self.self_test.take() else {
+            return Ok(());
+        };
+        if *self.display.get_state() != DisplayState::SelfTest {
+            if report.current() == Some(SelfTestStep::Microphone) {
+                self.utterance.finish();
+            }
+            return Ok(());
+        }
+
+        if let Some(step) = report.current() {
+            let first = report.current_outcome() == Some(&TestOutcome::Pending);
+            let outcome = if !first && report.step_elapsed() > step.timeout() {
+                TestOutcome::Fail("超时".to_string())
+            } else {
+                self.run_self_test_step(step, first, report.step_elapsed())
+            };
+            report.set_outcome(outcome);
+        }
+
+        self.display.set_self_test_report(report.clone());
+        if !report.is_finished() {
+            self.self_test = Some(report);
+        }
+        Ok(())
+    }
+
+    /// 执行一步自检
+    ///
+    /// # 参数
+    /// * `step` - 当前检测项
+    /// * `first` - 是否第一次执行该项
+    /// * `elapsed` - 该项已进行的时间
+    fn run_self_test_step(
+        &mut self,
+        step: SelfTestStep,
+        first: bool,
+        elapsed: Duration,
+    ) -> TestOutcome {
+        let result = match step {
+            SelfTestStep::I2c => Ok(selftest::check_i2c(&self.i2c.scan())),
+            // 色条由自检界面绘制，需要目视确认
+            SelfTestStep::Lcd if elapsed < SELF_TEST_PATTERN_DURATION => Ok(TestOutcome::Running),
+            SelfTestStep::Lcd => Ok(TestOutcome::Pass("请目视检查色条".to_string())),
+            SelfTestStep::Microphone => self.self_test_microphone(first, elapsed),
+            SelfTestStep::Speaker => {
+                let samples = tone::sine_tone(1000, 150, 8000, self.speaker.get_sample_rate());
+                self.speaker
+                    .play(&samples)
+                    .map(|_| TestOutcome::Pass("1kHz".to_string()))
+            }
+            // 扫描结果通过WifiEvent::ScanResult返回
+            SelfTestStep::Wifi if first => self.wifi.scan_networks().map(|_| TestOutcome::Running),
+            SelfTestStep::Wifi => Ok(TestOutcome::Running),
+        };
+        result.unwrap_or_else(|e| TestOutcome::Fail(e.to_string()))
+    }
+
+    /// 麦克风自检：采集一段环境声音，检查幅度是否正常
+    ///
+    /// 采集任务已启动时借用按键说话的语音缓冲，否则直接读取麦克风。
+    fn self_test_microphone(&mut self, first: bool, elapsed: Duration) -> Result<TestOutcome> {
+        if let Some(micphone) = self.micphone.as_mut() {
+            let mut samples = vec![0i16; SAMPLE_RATE as usize / 10];
+            micphone.start_recording()?;
+            let read = micphone.read_samples(&mut samples);
+            micphone.stop_recording()?;
+            return Ok(selftest::check_mic_rms(dsp::rms(&samples[..read?])));
+        }
+        if self.capture.is_none() {
+            anyhow::bail!("麦克风不可用");
+        }
+        if first {
+            self.utterance.start(SAMPLE_RATE, 1);
+            return Ok(TestOutcome::Running);
+        }
+        if elapsed < SELF_TEST_MIC_DURATION {
+            return Ok(TestOutcome::Running);
+        }
+        let samples = self.utterance.finish().unwrap_or_default();
+        Ok(selftest::check_mic_rms(dsp::rms(&samples)))
+    }
+
+    /// 自检中收到WiFi扫描结果或扫描失败
+    fn finish_self_test_wifi(&mut self, outcome: TestOutcome) {
+        if let Some(report) = self.self_test.as_mut() {
+            if report.current() == Some(SelfTestStep::Wifi) {
+                report.set_outcome(outcome);
+            }
+        }
+    }
+
+    /// 处理自动校准结果：保存新阈值并返回设置界面
+    fn handle_motion_calibration(&mut self, event: MotionCalibrationEvent) -> Result<()> {
+        match event {
+            MotionCalibrationEvent::Finished(thresholds) => {
+                self.display.set_motion_thresholds(thresholds);
+                self.config.update(|config| config.motion = thresholds)?;
+                self.open_settings()
+            }
+            MotionCalibrationEvent::Failed(error) => self.show_error(&error, false),
+        }
+    }
+
+    /// 处理跌落：记录到统计数据，空闲时显示"好痛"表情
+    fn handle_drop(&mut self, fall_ms: u64) -> Result<()> {
+        log::warn!("设备跌落, 失重{}ms", fall_ms);
+        if let Err(e) = self.stats.record_drop() {
+            log::warn!("保存跌落次数失败: {}", e);
+        }
+
+        // 录音、对话和提醒过程中不打断
+        match self.display.get_state() {
+            DisplayState::Listening
+            | DisplayState::Thinking
+            | DisplayState::Alarm(_)
+            | DisplayState::Calibrating
+            | DisplayState::Pairing(_) => Ok(()),
+            _ => self.display.enter_ouch(),
+        }
+    }
+
+    /// 发送对话提示并进入思考界面
+    pub fn send_prompt(&mut self, input: ChatInput) -> Result<()> {
+        let request = self.chat.prompt(input.clone())?;
+        self.chat_request.start(request, input, Instant::now());
+        self.display.enter_thinking()
+    }
+
+    /// 重新发送最近一次的提示
+    ///
+    /// 只有错误界面提示可重试时才生效，返回是否已重试。
+    pub fn retry_last_prompt(&mut self) -> Result<bool> {
+        if !self.display.retry_available() {
+            return Ok(false);
+        }
+        match self.chat_request.last_prompt().cloned() {
+            Some(input) => {
+                self.send_prompt(input)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// 开始按键说话
+    ///
+    /// 只在主界面、对话界面、欢迎界面或回复界面且采集任务已启动时生效。
+    fn start_push_to_talk(&mut self) -> Result<()> {
+        let idle = matches!(
+            self.display.get_state(),
+            DisplayState::Main
+                | DisplayState::Conversation
+                | DisplayState::Welcome
+                | DisplayState::Reply(_)
+        );
+        // 回复还在流式显示时不开始新一轮
+        if !idle || self.capture.is_none() || self.display.is_streaming_reply() {
+            return Ok(());
+        }
+
+        // 先播放提示音再开始录音，避免提示音被录进语音
+        self.play_earcon(Earcon::ListenStart);
+        self.utterance.start(SAMPLE_RATE, PUSH_TO_TALK_MAX_SECONDS);
+        self.hands_free_until = None;
+        self.display.enter_listening()
+    }
+
+    /// 用户插话（说出唤醒词，或思考、回复过程中按键）
+    ///
+    /// 播放中的扬声器已由按键或唤醒词线程打断并淡出，这里取消未完成的回复并开始聆听。
+    ///
+    /// # 参数
+    /// * `hands_free` - 由唤醒词触发，没有松开按键的动作，聆听`BARGE_IN_LISTEN_DURATION`后自动结束
+    fn barge_in(&mut self, hands_free: bool) -> Result<()> {
+        if self.chat_request.finish() {
+            log::info!("插话，取消未完成的回复");
+            self.chat.cancel();
+            self.display.end_reply_stream();
+            self.display.enter_main()?;
+        }
+
+        // 按住按键说话时说出唤醒词不改变结束方式
+        let listening = *self.display.get_state() == DisplayState::Listening;
+        self.start_push_to_talk()?;
+        if hands_free && !listening && *self.display.get_state() == DisplayState::Listening {
+            self.hands_free_until = Some(Instant::now() + BARGE_IN_LISTEN_DURATION);
+        }
+        Ok(())
+    }
+
+    /// 结束按键说话，上传语音并进入思考界面
+    fn finish_push_to_talk(&mut self) -> Result<()> {
+        self.hands_free_until = None;
+        let Some(samples) = self.utterance.finish() else {
+            return Ok(());
+        };
+        self.play_earcon(Earcon::ListenEnd);
+
+        if samples.len() < PUSH_TO_TALK_MIN_SAMPLES {
+            log::info!("语音过短 ({} 样本)，忽略", samples.len());
+            return self.display.enter_main();
+        }
+
+        self.stats.record_speech(Duration::from_millis(
+            samples.len() as u64 * 1000 / SAMPLE_RATE as u64,
+        ));
+        self.send_prompt(ChatInput::Voice(samples.into()))
+    }
+
+    /// 播放提示音，失败只记录日志，不影响界面状态切换
+    fn play_earcon(&mut self, earcon: Earcon) {
+        if let Err(e) = self.speaker.play(&earcon.samples()) {
+            log::warn!("播放提示音{:?}失败: {}", earcon, e);
+        }
+    }
+
+    /// 结束当前对话：清空对话记录并让下次提问创建新会话
+    fn end_conversation(&mut self) -> Result<()> {
+        self.chat.new_session()?;
+        self.display.clear_conversation()
+    }
+
+    /// 取消正在进行的对话请求并返回主界面
+    pub fn cancel_prompt(&mut self) -> Result<()> {
+        self.chat.cancel();
+        self.chat_request.finish();
+        self.display.end_reply_stream();
+        self.display.enter_main()
+    }
+
+    /// 检查思考界面是否超时
+    ///
+    /// 正常情况下对话actor会先上报`ChatEvent::TimedOut`，
+    /// 这里兜底处理请求卡在底层阻塞调用中无法返回的情况。
+    /// 流式回复显示在对话界面，接收期间同样计时。
+    fn check_thinking_timeout(&mut self) -> Result<()> {
+        if !self.chat_request.is_pending() {
+            return Ok(());
+        }
+        if *self.display.get_state() != DisplayState::Thinking && !self.display.is_streaming_reply()
+        {
+            self.chat_request.finish();
+            return Ok(());
+        }
+        if self.chat_request.is_overdue(Instant::now()) {
+            self.chat.cancel();
+            self.chat_request.finish();
+            self.display.end_reply_stream();
+            self.errors.record(ErrorKind::Network);
+            self.play_earcon(Earcon::Error);
+            self.display
+                .enter_error_with_retry("请求超时".to_string())?;
+        }
+        Ok(())
+    }
+
+    /// 首次连接WiFi后的一次性启动工作（采集线程、唤醒词模型）是否已完成
+    pub fn startup_finished(&self) -> bool {
+        self.capture.is_some()
+    }
+
+    /// 执行已到期的周期性任务，主循环每次醒来时调用
+    ///
+    /// # 返回值
+    /// 距离下一个任务到期的时间，主循环据此休眠
+    pub fn tick(&mut self) -> Duration {
+        for job in self.scheduler.due(Instant::now()) {
+            let result = match job {
+                AppJob::Ui => self.update_ui(),
+                AppJob::Status => self.update_status(),
+                AppJob::Telemetry => {
+                    self.report_telemetry();
+                    Ok(())
+                }
+            };
+            if let Err(e) = result {
+                log::warn!("周期任务{:?}失败: {}", job, e);
+            }
+        }
+        self.scheduler.time_until_next(Instant::now())
+    }
+
+    /// 界面刷新（按目标帧率）：超时检查、动画与界面绘制
+    ///
+    /// 显示线程仍在发送上一帧时跳过绘制，见`FramePacer::begin_frame`。
+    fn update_ui(&mut self) -> Result<()> {
+        self.check_thinking_timeout()?;
+        self.poll_self_test()?;
+        self.update_alarm()?;
+        self.update_spectrum();
+        self.update_voice_guide();
+        self.update_intercom_playback();
+        if *self.display.get_state() != DisplayState::Settings
+            && !self.display.is_settings_subscreen()
+        {
+            self.settings_unlocked = false;
+        }
+
+        if self.push_to_talk.poll(Instant::now()) {
+            self.start_push_to_talk()?;
+        }
+        if *self.display.get_state() == DisplayState::Listening {
+            self.display.push_mic_level(self.utterance.level());
+            if self
+                .hands_free_until
+                .is_some_and(|until| Instant::now() >= until)
+            {
+                self.finish_push_to_talk()?;
+            }
+        }
+
+        let render_start = Instant::now();
+        if self
+            .frame_pacer
+            .begin_frame(self.display.is_flush_pending(), render_start)
+        {
+            self.display.update()?;
+            metrics::observe_duration(metrics::FRAME_RENDER_MS, render_start.elapsed());
+        }
+        self.update_status_ring();
+        Ok(())
+    }
+
+    /// 状态刷新（1Hz）：电量、步数、待机检查与运行统计
+    fn update_status(&mut self) -> Result<()> {
+        crash::heartbeat();
+        self.read_battery();
+        self.check_display();
+
+        // 主界面长时间无操作时切换到待机表盘
+        if matches!(
+            self.display.get_state(),
+            DisplayState::Main | DisplayState::Conversation
+        ) && self.last_activity.elapsed() >= STANDBY_IDLE_TIMEOUT
+        {
+            self.display.enter_standby()?;
+        }
+
+        self.check_presence()?;
+        if let Err(e) = self.update_brightness() {
+            log::warn!("自动亮度调整失败: {}", e);
+        }
+        if let (Some(sntp), Some(rtc)) = (&self.sntp, self.rtc.as_mut()) {
+            if let Err(e) = clock::save_to_rtc_if_synced(sntp, rtc.as_mut()) {
+                log::warn!("写入实时时钟失败: {}", e);
+            }
+        }
+
+        self.display.set_steps(Some(self.motion.steps_today()));
+        self.update_kids_usage()?;
+
+        if let Err(e) = self.stats.tick() {
+            log::warn!("保存运行统计失败: {}", e);
+        }
+        if *self.display.get_state() == DisplayState::About {
+            self.display.set_about_info(self.about_info());
+        }
+        // 录音到时自动结束，设置界面中的状态随之更新
+        self.sync_debug_recording();
+        if *self.display.get_state() == DisplayState::Intercom {
+            self.update_intercom_view()?;
+        }
+        if *self.display.get_state() == DisplayState::Stats {
+            self.display.set_reliability_stats(self.stats.snapshot());
+            self.display.set_frame_stats(self.frame_pacer.stats());
+
+            // 查询文件系统信息较慢，限制刷新频率
+            let stale = self
+                .last_storage_query
+                .map_or(true, |t| t.elapsed() >= STORAGE_QUERY_INTERVAL);
+            if stale {
+                self.display.set_storage_spaces(self.storage.all_spaces());
+                self.last_storage_query = Some(Instant::now());
+            }
+        }
+        Ok(())
+    }
+
+    /// 检测到有人（动作、按键、唤醒词），重新计算无人时间，屏幕已关闭时立即打开
+    fn note_presence(&mut self) -> Result<()> {
+        self.last_presence = Instant::now();
+        if self.display.is_asleep() {
+            log::info!("检测到有人，打开屏幕");
+            self.display.wake()?;
+        }
+        Ok(())
+    }
+
+    /// 待机表盘下长时间无人时关闭屏幕
+    ///
+    /// 听到明显高于噪声底的声音也算有人，但只推迟关闭，不会打开已关闭的屏幕。
+    fn check_presence(&mut self) -> Result<()> {
+        if self.sound.take_heard() {
+            self.last_presence = Instant::now();
+        }
+
+        let minutes = self.config.config().display_sleep_minutes;
+        if minutes == 0
+            || self.display.is_asleep()
+            || *self.display.get_state() != DisplayState::Standby
+        {
+            return Ok(());
+        }
+        if self.last_presence.elapsed() >= Duration::from_secs(minutes as u64 * 60) {
+            log::info!("{}分钟无人，关闭屏幕", minutes);
+            self.display.sleep()?;
+        }
+        Ok(())
+    }
+
+    /// 输出运行状态与各任务的调度延迟
+    fn report_telemetry(&mut self) {
+        let stats = self.stats.snapshot();
+        let free_heap =
+            unsafe { esp_idf_sys::heap_caps_get_free_size(esp_idf_sys::MALLOC_CAP_8BIT) };
+        log::info!(
+            "运行状态: 已运行{}秒, 可用内存{}字节, 界面{:?}, 免打扰{}",
+            stats.session_uptime_secs,
+            free_heap,
+            self.display.get_state(),
+            if self.config.config().do_not_disturb {
+                "开"
+            } else {
+                "关"
+            }
+        );
+        log::info!(
+            "累计统计: 启动{}次, 运行{}, 对话{}次, 说话{}",
+            stats.boot_count,
+            format_duration(stats.total_uptime_secs),
+            stats.conversations,
+            format_duration(stats.speaking_ms / 1000)
+        );
+        for (job, jitter, skipped) in self.scheduler.take_jitter() {
+            log::info!(
+                "任务{:?}: 执行{}次, 平均延迟{}ms, 最大延迟{}ms, 跳过{}次",
+                job,
+                jitter.runs,
+                jitter.mean_lateness().as_millis(),
+                jitter.max_lateness.as_millis(),
+                skipped
+            );
+        }
+        let frames = self.frame_pacer.stats();
+        log::info!(
+            "界面帧率: {:.1}/{}fps, 跳过绘制{}帧, 跳过刷新{}帧",
+            frames.measured_fps,
+            frames.target_fps,
+            frames.skipped,
+            self.display.take_skipped_frames()
+        );
+        metrics::set_gauge(metrics::UI_FPS, frames.measured_fps as f64);
+        for (kind, count) in self.errors.take() {
+            log::info!("{:?}错误: {}次", kind, count);
+        }
+        if self.config.config().kids_mode.enabled {
+            match self.kids_mode_status_json() {
+                Ok(json) => log::info!("儿童模式: {}", json),
+                Err(e) => log::warn!("儿童模式状态序列化失败: {}", e),
+            }
+        }
+        match serde_json::to_string(&metrics::take_snapshot()) {
+            Ok(json) => log::info!("运行指标: {}", json),
+            Err(e) => log::warn!("运行指标序列化失败: {}", e),
+        }
+    }
+
+    /// 按界面状态切换灯环效果：待机呼吸、思考旋转、说话录音时脉冲
+    fn update_status_ring(&mut self) {
+        let Some(ring) = self.status_ring.as_mut() else {
+            return;
+        };
+        let theme = theme::current();
+        let effect = match self.display.get_state() {
+            DisplayState::Listening => RingEffect::Pulse(theme.accent.into()),
+            DisplayState::Thinking => RingEffect::Spinning(theme.accent.into()),
+            DisplayState::Alarm(_) => RingEffect::Pulse(theme.warning.into()),
+            DisplayState::Error(_) => RingEffect::Solid(theme.error.into()),
+            _ => RingEffect::Breathing(theme.accent.into()),
+        };
+        let effect = if self.config.config().do_not_disturb {
+            effect.dimmed(DND_RING_BRIGHTNESS)
+        } else {
+            effect
+        };
+        if let Err(e) = ring.set_effect(effect) {
+            log::warn!("切换灯环效果失败: {}", e);
+        }
+    }
+
+    /// 按`BATTERY_READ_INTERVAL`间隔读取电池电量并更新界面
+    fn read_battery(&mut self) {
+        let Some(battery) = self.battery.as_mut() else {
+            return;
+        };
+        let due = self
+            .last_battery_read
+            .map_or(true, |t| t.elapsed() >= BATTERY_READ_INTERVAL);
+        if !due {
+            return;
+        }
+        self.last_battery_read = Some(Instant::now());
+
+        match battery.read() {
+            Ok(level) => self.display.set_battery_level(Some(level)),
+            Err(e) => log::warn!("读取电池电量失败: {}", e),
+        }
+    }
+
+    /// 检查LCD面板，显示线程多次重新初始化仍无效时作为硬件错误处理
+    fn check_display(&mut self) {
+        if let Err(e) = self.display.check_panel() {
+            let error = Error::new(ErrorKind::Display, e.to_string());
+            if let Err(e) =
+                events::send_system_event(&self.event_sender, SystemEvent::HardwareError(error))
+            {
+                log::warn!("发送硬件错误事件失败: {}", e);
+            }
+        }
+    }
+
+    /// 开始或结束麦克风调试录音（设置→其他→调试录音）
+    ///
+    /// 录音写入`DEBUG_RECORDING_FILE`，最长`DEBUG_RECORDING_SECONDS`秒，
+    /// 用于在没有服务器的情况下检查麦克风接线与音量；有服务器时随下一条消息发送。
+    pub fn toggle_debug_recording(&mut self) -> Result<()> {
+        if self.recorder.is_recording() {
+            self.recorder.stop()?;
+        } else {
+            let path = self
+                .storage
+                .path(self.storage.bulk_location(), DEBUG_RECORDING_FILE);
+            self.recorder
+                .start(&path, SAMPLE_RATE, DEBUG_RECORDING_SECONDS)?;
+        }
+        self.sync_debug_recording();
+        Ok(())
+    }
+
+    /// 把调试录音状态同步到设置界面，录音结束后作为附件随下一条消息发送
+    fn sync_debug_recording(&mut self) {
+        let recording = self.recorder.is_recording();
+        if self.display.debug_recording() && !recording {
+            let path = self
+                .storage
+                .path(self.storage.bulk_location(), DEBUG_RECORDING_FILE);
+            if let Err(e) = self.chat.attach(path, MIME_WAV) {
+                log::warn!("调试录音附件发送失败: {}", e);
+            }
+        }
+        self.display.set_debug_recording(recording);
+    }
+
+    /// 截屏保存到存储中，文件名序号取第一个未使用的，并作为附件随下一条消息发送
+    ///
+    /// # 返回值
+    /// 截屏文件路径
+    pub fn capture_screenshot(&mut self) -> Result<String> {
+        let location = self.storage.bulk_location();
+        let name = (0..1000)
+            .map(|index| format!("{}{:03}.bmp", SCREENSHOT_PREFIX, index))
+            .find(|name| !self.storage.exists(location, name))
+            .ok_or_else(|| anyhow::anyhow!("截屏文件数量已达上限"))?;
+
+        let path = self.storage.path(location, &name);
+        self.display.capture_screenshot(&path)?;
+        println!("截屏已保存: {}", path);
+        if let Err(e) = self.chat.attach(path.clone(), MIME_BMP) {
+            log::warn!("截屏附件发送失败: {}", e);
+        }
+        Ok(path)
+    }
+
     fn handle_wifi(&mut self, wifi_event: WifiEvent) -> Result<()> {
         match wifi_event {
             WifiEvent::Connected(ip) => {
@@ -60,84 +1981,83 @@ impl<'a> App<'a> {
 
                 // println!("创建会话成功，会话ID: {}", resp);
                 self.network_state = true;
+                self.ip_address = Some(ip);
+
+                // 待机表盘依赖同步后的系统时间
+                if self.sntp.is_none() {
+                    match clock::start_sntp() {
+                        Ok(sntp) => self.sntp = Some(sntp),
+                        Err(e) => log::warn!("启动SNTP失败: {}", e),
+                    }
+                }
+
+                if self.intercom.is_none() {
+                    let name =
+                        sta_mac().map_or("aichat".to_string(), |mac| intercom::device_name(&mac));
+                    match IntercomActorManager::new(
+                        name,
+                        self.intercom_tap.clone(),
+                        self.event_sender.clone(),
+                    ) {
+                        Ok(manager) => self.intercom = Some(manager),
+                        Err(e) => log::warn!("启动对讲失败: {}", e),
+                    }
+                }
 
-                let pcm_client = PcmClient::new(PcmClientConfig {
-                    base_url: "http://pcmtest.s7.tunnelfrp.com".to_string(),
-                    session_id: "session_id".to_string(),
-                    timeout_secs: 60,
-                });
-
-                unsafe {
-                    let models = esp_srmodel_init(c"model".as_ptr());
-                    let model_num = (*models).num;
-                    println!("模型数量: {}", model_num);
-                    for i in 0..model_num {
-                        // 获取第 i 个模型名称的 C
-                        // 字符串指针
-                        let model_name_ptr = (*models).model_name.offset(i as isize);
-                        if !model_name_ptr.is_null() && !(*model_name_ptr).is_null() {
-                            // 将 C 字符串转换为 Rust
-                            //字符串
-                            let c_str = CStr::from_ptr(*model_name_ptr);
-                            match c_str.to_str() {
-                                Ok(name) => println!("Model {}: {}", i, name),
-                                Err(_) => println!("Model {}: <invalid UTF-8>", i),
-                            }
-                        } else {
-                            println!("Model {}: <null>", i);
-                        }
+                // 每次重新连接都立即刷新天气
+                if let Some(weather) = &self.weather {
+                    if let Err(e) = weather.refresh() {
+                        log::warn!("刷新天气失败: {}", e);
                     }
+                }
 
-                    let cfg = afe_config_init(
-                        c"MMNR".as_ptr(),
-                        models,
-                        afe_type_t_AFE_TYPE_SR,
-                        afe_mode_t_AFE_MODE_HIGH_PERF,
-                    );
-
-                    let afe_handle = esp_afe_handle_from_config(cfg);
-                    let fetch_fn = (*afe_handle).fetch.unwrap();
-                    let feed_fn = (*afe_handle).feed.unwrap();
-                    let afe_data = (*afe_handle).create_from_config.unwrap()(cfg);
-                    let feed_size = (*afe_handle).get_feed_chunksize.unwrap()(afe_data);
-                    let fetch_size = (*afe_handle).get_fetch_chunksize.unwrap()(afe_data);
-                    let feed_nch = (*afe_handle).get_feed_channel_num.unwrap()(afe_data);
-                    let buffer_size = feed_size * feed_nch;
-
-                    println!(
-                        "Starting Wakeup. feed_nch: {}, feed_size: {}, fetch_size: {}, buffer_size: {}",
-                        feed_nch, feed_size, fetch_size, buffer_size
-                    );
-
-                    self.micphone.start_recording()?;
-                    self.micphone
-                        .record_with_callback(30, buffer_size as usize, move |buffer| {
-                            println!("buffer size: {}", buffer.len());
-
-                            let u8_buffer: &[u8] = bytemuck::cast_slice(buffer);
-                            // pcm_client.send_pcm_chunk(u8_buffer).unwrap();
-
-                            feed_fn(afe_data, buffer.as_ptr());
-                            let res = fetch_fn(afe_data);
-                            let state = (*res).wakeup_state;
-                            println!("Wake up State is: {:?}", state);
-                            return true;
-                        })
-                        .unwrap();
+                // 采集与唤醒词检测都在独立线程中执行，避免阻塞主循环
+                if let Some(micphone) = self.micphone.take() {
+                    let (capture, consumer) =
+                        CaptureTask::spawn(micphone, DEFAULT_CAPTURE_BUFFER_SAMPLES)?;
+                    self.capture = Some(capture);
+                    self.wakeword = Some(WakeWordActorManager::new(
+                        consumer,
+                        Some(self.speaker.reference()),
+                        self.recorder.clone(),
+                        self.utterance.clone(),
+                        vec![self.intercom_tap.clone(), self.spectrum_tap.clone()],
+                        self.sound.clone(),
+                        self.config.config().noise_suppression,
+                        !self.config.config().do_not_disturb,
+                        self.config.config().wake_word.clone(),
+                        self.event_sender.clone(),
+                    )?);
                 }
             }
             WifiEvent::Disconnected => {
+                // 只统计连接后的断开，避免重连失败时重复计数
+                if self.network_state {
+                    self.stats.record_wifi_disconnect()?;
+                }
                 self.network_state = false;
+                self.ip_address = None;
+                self.display.set_wifi_level(None);
             }
             WifiEvent::ConnectionFailed(error) => {
-                self.display
-                    .enter_error(format!("WiFi连接失败: {}", error))?;
+                self.show_error(&error, false)?;
             }
             WifiEvent::StatusUpdate(status) => {
+                if let WifiStatus::Error(error) = &status {
+                    self.finish_self_test_wifi(TestOutcome::Fail(error.to_string()));
+                }
                 self.network_state = status.is_connected();
-            } // WifiEvent::ScanResult(networks) => {
-              //     println!("扫描到的网络: {:?}", networks);
-              // }
+                self.display.set_wifi_level(status.signal_level());
+            }
+            WifiEvent::ScanResult(networks) => {
+                println!("扫描到的网络: {:?}", networks);
+                let outcome = if networks.is_empty() {
+                    TestOutcome::Fail("未发现网络".to_string())
+                } else {
+                    TestOutcome::Pass(format!("{}个网络", networks.len()))
+                };
+                self.finish_self_test_wifi(outcome);
+            }
         }
 
         Ok(())
@@ -152,7 +2072,7 @@ impl<'a> App<'a> {
                 self.display.enter_error("内存不足".to_string())?;
             }
             SystemEvent::HardwareError(error) => {
-                self.display.enter_error(format!("硬件错误: {}", error))?;
+                self.show_error(&error, false)?;
             }
             SystemEvent::Shutdown => {
                 println!("系统即将关闭");
@@ -161,14 +2081,319 @@ impl<'a> App<'a> {
 
         Ok(())
     }
+
+    fn handle_input(&mut self, input_event: UserInputEvent) -> Result<()> {
+        // 对讲说话期间界面被闹钟等打断时，松开按键同样结束发送；
+        // 还没开始的按键说话也不再开始
+        if input_event == UserInputEvent::ButtonRelease(BOOT_BUTTON) {
+            self.push_to_talk.release();
+            self.stop_intercom_talk()?;
+        }
+
+        // 闹钟响铃时任意按键关闭提醒
+        if matches!(self.display.get_state(), DisplayState::Alarm(_)) {
+            if matches!(
+                input_event,
+                UserInputEvent::ButtonPress(_) | UserInputEvent::Back
+            ) {
+                self.dismiss_alarm()?;
+            }
+            return Ok(());
+        }
+
+        // 待机时按键只用于唤醒，不触发其他操作
+        if *self.display.get_state() == DisplayState::Standby {
+            if matches!(
+                input_event,
+                UserInputEvent::ButtonPress(_) | UserInputEvent::Back
+            ) {
+                self.display.back()?;
+            }
+            return Ok(());
+        }
+
+        // 休息时按键不起作用，家长可以输入手势密码进入设置
+        if *self.display.get_state() == DisplayState::Break {
+            return Ok(());
+        }
+
+        // 地址输入界面：单击输入选中的字符，长按放弃修改
+        if *self.display.get_state() == DisplayState::EndpointEdit {
+            match input_event {
+                UserInputEvent::Confirm => self.confirm_endpoint_edit()?,
+                UserInputEvent::Back => {
+                    self.url_editor = None;
+                    self.back()?;
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // 设置界面：单击操作获得焦点的设置项，编辑时长按结束编辑
+        if *self.display.get_state() == DisplayState::Settings {
+            match input_event {
+                UserInputEvent::Confirm => {
+                    if let Some(action) = self.display.settings_activate() {
+                        self.apply_setting(action)?;
+                    }
+                }
+                UserInputEvent::Back => {
+                    if !self.display.settings_back() {
+                        self.display.enter_main()?;
+                    }
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // 对讲界面：按住BOOT键说话；说话时长按不返回
+        if *self.display.get_state() == DisplayState::Intercom {
+            match input_event {
+                UserInputEvent::ButtonPress(BOOT_BUTTON) => self.start_intercom_talk()?,
+                UserInputEvent::Back if !self.intercom_talking => self.back()?,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        match input_event {
+            UserInputEvent::ButtonPress(BOOT_BUTTON) => {
+                if self.retry_last_prompt()? {
+                    return Ok(());
+                }
+                // 思考或回复还在流式显示时按键即插话
+                if *self.display.get_state() == DisplayState::Thinking
+                    || self.display.is_streaming_reply()
+                {
+                    return self.barge_in(false);
+                }
+                // 按住超过双击窗口才开始录音（见`update_ui`），单击与双击不触发提示音
+                self.push_to_talk.press(Instant::now());
+            }
+            UserInputEvent::ButtonRelease(BOOT_BUTTON) => {
+                if *self.display.get_state() == DisplayState::Listening {
+                    self.finish_push_to_talk()?;
+                }
+            }
+            UserInputEvent::DoubleClick(BOOT_BUTTON) => {
+                // 双击BOOT键截屏，用于记录界面问题；第一下是按键说话时不算双击
+                if self.push_to_talk.accepts_double_click() {
+                    if let Err(e) = self.capture_screenshot() {
+                        println!("截屏失败: {}", e);
+                    }
+                }
+            }
+            UserInputEvent::ButtonPress(_)
+            | UserInputEvent::ButtonRelease(_)
+            | UserInputEvent::Click(_)
+            | UserInputEvent::DoubleClick(_)
+            | UserInputEvent::LongPress(_) => {}
+            UserInputEvent::Confirm => {
+                // 主界面单击打开设置，按住说话松开后的单击不算；单击先发送的`Click`不处理，
+                // 否则随后的`Confirm`会在刚打开的设置界面中激活获得焦点的设置项
+                if *self.display.get_state() == DisplayState::Main
+                    && self.push_to_talk.accepts_click()
+                {
+                    self.open_settings()?;
+                }
+            }
+            UserInputEvent::Back => {
+                if *self.display.get_state() == DisplayState::Thinking
+                    || self.display.is_streaming_reply()
+                {
+                    self.cancel_prompt()?;
+                } else if *self.display.get_state() == DisplayState::Conversation {
+                    self.end_conversation()?;
+                } else {
+                    self.back()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_chat(&mut self, chat_event: ChatEvent) -> Result<()> {
+        // 模型列表和设备命令与对话请求的结果无关，单独处理
+        match chat_event {
+            ChatEvent::Models(models) => return self.display.set_models(models),
+            ChatEvent::Command(request, command) => {
+                // 被取消或已被新提示取代的请求中的命令不再执行
+                if !self.chat_request.is_pending_for(request) {
+                    log::info!("丢弃过期请求中的设备命令: {:?}", command);
+                    return Ok(());
+                }
+                return self.handle_device_command(command);
+            }
+            ChatEvent::ModelsFailed(error) => return self.show_error(&error, false),
+            ChatEvent::LogsUploaded => {
+                log::info!("日志已上传");
+                self.uploading_logs = false;
+                self.display.set_log_upload(LogUploadStatus::Uploaded);
+                return Ok(());
+            }
+            ChatEvent::LogsUploadFailed(error) => {
+                self.uploading_logs = false;
+                self.display.set_log_upload(LogUploadStatus::Failed);
+                // 设置界面中显示失败即可，远程触发时不打断用户
+                log::warn!("上传日志失败: {}", error);
+                self.errors.record(error.kind());
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        // 已经离开思考界面（超时或取消）的请求结果，以及之前请求迟到的结果直接丢弃
+        let Some(request) = chat_event.request_id() else {
+            return Ok(());
+        };
+        if let ChatEvent::ReplyDelta(_, delta) = &chat_event {
+            if self.chat_request.is_pending_for(request) {
+                self.display
+                    .append_reply(prompt_label(self.chat_request.last_prompt()), delta)?;
+            }
+            return Ok(());
+        }
+        if !self.chat_request.finish_for(request) {
+            return Ok(());
+        }
+        let streamed = self.display.is_streaming_reply();
+        self.display.end_reply_stream();
+
+        match chat_event {
+            ChatEvent::Reply(_, reply) => {
+                println!("收到回复: {}", reply);
+                self.stats.record_conversation();
+                // 流式回复已经显示在对话界面中
+                if !streamed {
+                    self.display
+                        .push_exchange(prompt_label(self.chat_request.last_prompt()), &reply);
+                    self.display.enter_reply(reply)?;
+                }
+            }
+            ChatEvent::Failed(_, error) => {
+                self.play_earcon(Earcon::Error);
+                self.show_error(&error, true)?;
+            }
+            ChatEvent::TimedOut(_) => {
+                self.errors.record(ErrorKind::Network);
+                self.play_earcon(Earcon::Error);
+                self.display
+                    .enter_error_with_retry("请求超时".to_string())?;
+            }
+            ChatEvent::Cancelled(_) => {
+                self.display.enter_main()?;
+            }
+            ChatEvent::ReplyDelta(..)
+            | ChatEvent::Models(_)
+            | ChatEvent::ModelsFailed(_)
+            | ChatEvent::Command(..)
+            | ChatEvent::LogsUploaded
+            | ChatEvent::LogsUploadFailed(_) => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// 扬声器实际使用的音量，免打扰时静音
+fn speaker_volume(volume: Volume, do_not_disturb: bool) -> Volume {
+    let mut volume = volume;
+    if do_not_disturb {
+        volume.set_muted(true);
+    }
+    volume
+}
+
+/// WiFi STA的MAC地址，读取失败时返回None
+fn sta_mac() -> Option<[u8; 6]> {
+    let mut mac = [0u8; 6];
+    esp_idf_sys::esp!(unsafe {
+        esp_idf_sys::esp_read_mac(
+            mac.as_mut_ptr(),
+            esp_idf_sys::esp_mac_type_t_ESP_MAC_WIFI_STA,
+        )
+    })
+    .ok()?;
+    Some(mac)
+}
+
+/// 设置界面显示的亮度设置，自动亮度需要环境光传感器
+fn brightness_setting(config: &DeviceConfig, has_sensor: bool) -> BrightnessSetting {
+    BrightnessSetting {
+        percent: config.brightness,
+        auto: has_sensor.then_some(config.auto_brightness),
+    }
+}
+
+/// 提问在对话界面中显示的文字
+fn prompt_label(input: Option<&ChatInput>) -> &str {
+    match input {
+        Some(ChatInput::Text(text)) => text.as_str(),
+        Some(ChatInput::Voice(_)) | None => VOICE_PROMPT_LABEL,
+    }
 }
 
 impl<'a> EventHandler for App<'a> {
     fn handle_event(&mut self, event: AppEvent) -> Result<()> {
+        // 按键、动作和唤醒词说明有人在附近，屏幕关闭时立即打开
+        let present = matches!(
+            event,
+            AppEvent::Input(_)
+                | AppEvent::AlarmFired(_)
+                | AppEvent::Dropped(_)
+                | AppEvent::BargeIn
+                | AppEvent::WakeWord
+        ) || matches!(event, AppEvent::Motion(state) if state != MotionState::Still);
+        if present {
+            self.note_presence()?;
+        }
+
+        // 以上事件和对话结果都视为用户活动，重新计算待机时间
+        if present
+            || matches!(
+                event,
+                AppEvent::Chat(_) | AppEvent::ChatProgress(_) | AppEvent::UploadProgress(_)
+            )
+        {
+            self.last_activity = Instant::now();
+        }
+
         match event {
             AppEvent::Motion(motion_state) => self.handle_motion(motion_state),
             AppEvent::Wifi(wifi_event) => self.handle_wifi(wifi_event),
             AppEvent::System(system_event) => self.handle_system(system_event),
+            AppEvent::Chat(chat_event) => self.handle_chat(chat_event),
+            AppEvent::ChatProgress(stage) => {
+                // 只更新仍在等待的请求
+                if self.chat_request.is_pending() {
+                    self.display.set_chat_stage(stage);
+                }
+                Ok(())
+            }
+            AppEvent::UploadProgress(percent) => {
+                if self.chat_request.is_pending() {
+                    self.display.set_upload_progress(percent);
+                }
+                Ok(())
+            }
+            AppEvent::Input(input_event) => self.handle_input(input_event),
+            AppEvent::Weather(weather) => {
+                self.display.set_weather(weather);
+                Ok(())
+            }
+            AppEvent::AlarmFired(alarm) => self.ring_alarm(alarm),
+            AppEvent::MotionCalibration(event) => self.handle_motion_calibration(event),
+            AppEvent::Dropped(fall_ms) => self.handle_drop(fall_ms),
+            AppEvent::FaceDown(face_down) => self.handle_face_down(face_down),
+            AppEvent::BargeIn | AppEvent::WakeWord => self.barge_in(true),
+            AppEvent::NetworkDegraded(degraded) => {
+                self.display.set_network_degraded(degraded);
+                Ok(())
+            }
+            AppEvent::Intercom(event) => self.handle_intercom(event),
         }
     }
 }
@@ -0,0 +1,318 @@
+// src/app/alarms.rs
+//! 闹钟与倒计时
+//!
+//! 闹钟和倒计时以JSON形式保存在NVS中，重启后保持。后台线程每秒检查一次，
+//! 到时间后发送`AppEvent::AlarmFired`，由App显示全屏提醒并播放提示音。
+//! 单次闹钟和倒计时触发后自动删除，每日闹钟保留。
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use esp_idf_svc::nvs::{EspDefaultNvsPartition, EspNvs, NvsDefault};
+use serde::{Deserialize, Serialize};
+
+use crate::clock::{self, LocalTime};
+
+/// NVS命名空间
+const ALARMS_NAMESPACE: &str = "alarms";
+/// 闹钟列表JSON在NVS中的键
+const ALARMS_KEY: &str = "list";
+
+/// 最多保存的闹钟与倒计时数量
+pub const MAX_ALARMS: usize = 16;
+
+/// 检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 触发时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlarmSchedule {
+    /// 每天在指定时间触发
+    Daily { hour: u8, minute: u8 },
+    /// 下一次到达指定时间时触发一次
+    Once { hour: u8, minute: u8 },
+    /// 倒计时，在指定的Unix时间（秒）触发
+    Timer { fires_at: u64 },
+}
+
+/// 闹钟或倒计时
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Alarm {
+    pub id: u32,
+    /// 提醒界面显示的标签，可以为空
+    #[serde(default)]
+    pub label: String,
+    pub schedule: AlarmSchedule,
+}
+
+impl Alarm {
+    /// 在给定时间是否应该触发
+    ///
+    /// 按时刻触发的闹钟需要已同步的本地时间，同一分钟内由调用方保证只检查一次。
+    ///
+    /// # 参数
+    /// * `local` - 当前本地时间，尚未同步时为None
+    /// * `unix_secs` - 当前Unix时间（秒）
+    pub fn is_due(&self, local: Option<&LocalTime>, unix_secs: u64) -> bool {
+        match self.schedule {
+            AlarmSchedule::Daily { hour, minute } | AlarmSchedule::Once { hour, minute } => {
+                local.is_some_and(|time| time.hour == hour && time.minute == minute)
+            }
+            AlarmSchedule::Timer { fires_at } => unix_secs >= fires_at,
+        }
+    }
+
+    /// 触发后是否保留
+    fn repeats(&self) -> bool {
+        matches!(self.schedule, AlarmSchedule::Daily { .. })
+    }
+
+    /// 提醒界面显示的标题
+    pub fn title(&self) -> &str {
+        if !self.label.is_empty() {
+            return &self.label;
+        }
+        match self.schedule {
+            AlarmSchedule::Timer { .. } => "倒计时结束",
+            _ => "闹钟",
+        }
+    }
+}
+
+/// 保存在NVS中的闹钟列表
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AlarmList {
+    alarms: Vec<Alarm>,
+    next_id: u32,
+}
+
+/// 闹钟状态（NVS存储与检查线程共享）
+struct AlarmState {
+    nvs: EspNvs<NvsDefault>,
+    list: AlarmList,
+    /// 上次检查按时刻闹钟的分钟数（Unix时间/60），避免同一分钟内重复触发
+    last_checked_minute: u64,
+}
+
+impl AlarmState {
+    fn persist(&mut self) -> Result<()> {
+        let json = serde_json::to_string(&self.list)?;
+        self.nvs.set_str(ALARMS_KEY, &json)?;
+        Ok(())
+    }
+
+    fn add(&mut self, label: String, schedule: AlarmSchedule) -> Result<u32> {
+        if self.list.alarms.len() >= MAX_ALARMS {
+            anyhow::bail!("闹钟数量已达上限: {}", MAX_ALARMS);
+        }
+        let id = self.list.next_id;
+        self.list.next_id = self.list.next_id.wrapping_add(1);
+        self.list.alarms.push(Alarm {
+            id,
+            label,
+            schedule,
+        });
+        self.persist()?;
+        Ok(id)
+    }
+
+    /// 取出到期的闹钟，单次闹钟与倒计时从列表中删除
+    fn take_due(&mut self, unix_secs: u64) -> Result<Vec<Alarm>> {
+        let minute = unix_secs / 60;
+        let new_minute = minute != self.last_checked_minute;
+        self.last_checked_minute = minute;
+        let local = clock::now();
+
+        let due: Vec<Alarm> = self
+            .list
+            .alarms
+            .iter()
+            .filter(|alarm| match alarm.schedule {
+                AlarmSchedule::Timer { .. } => alarm.is_due(local.as_ref(), unix_secs),
+                _ => new_minute && alarm.is_due(local.as_ref(), unix_secs),
+            })
+            .cloned()
+            .collect();
+
+        if due.iter().any(|alarm| !alarm.repeats()) {
+            self.list
+                .alarms
+                .retain(|alarm| alarm.repeats() || !due.contains(alarm));
+            self.persist()?;
+        }
+        Ok(due)
+    }
+}
+
+/// 当前Unix时间（秒）
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 闹钟管理器
+///
+/// 可在设置界面或语音命令中添加、删除闹钟，到期检查在后台线程中进行。
+pub struct AlarmManager {
+    state: Arc<Mutex<AlarmState>>,
+}
+
+impl AlarmManager {
+    /// 从NVS加载闹钟并启动检查线程
+    ///
+    /// # 参数
+    /// * `partition` - 默认NVS分区
+    /// * `app_event_sender` - 应用事件发送器，到期时发送`AppEvent::AlarmFired`
+    pub fn new(
+        partition: EspDefaultNvsPartition,
+        app_event_sender: crate::events::EventSender,
+    ) -> Result<Self> {
+        let nvs = EspNvs::new(partition, ALARMS_NAMESPACE, true)?;
+        let list = match nvs.str_len(ALARMS_KEY)? {
+            Some(len) => {
+                let mut buf = vec![0u8; len];
+                match nvs.get_str(ALARMS_KEY, &mut buf)? {
+                    Some(json) => serde_json::from_str(json).unwrap_or_else(|e| {
+                        log::warn!("闹钟列表解析失败，已清空: {}", e);
+                        AlarmList::default()
+                    }),
+                    None => AlarmList::default(),
+                }
+            }
+            None => AlarmList::default(),
+        };
+        log::info!("已加载{}个闹钟", list.alarms.len());
+
+        let state = Arc::new(Mutex::new(AlarmState {
+            nvs,
+            list,
+            last_checked_minute: unix_now() / 60,
+        }));
+
+        let checker = state.clone();
+        thread::Builder::new()
+            .stack_size(8 * 1024)
+            .name("alarms".to_string())
+            .spawn(move || loop {
+                thread::sleep(CHECK_INTERVAL);
+                let due = match checker.lock() {
+                    Ok(mut state) => state.take_due(unix_now()),
+                    Err(_) => break,
+                };
+                match due {
+                    Ok(due) => {
+                        for alarm in due {
+                            log::info!("闹钟触发: {:?}", alarm);
+                            if crate::events::send_alarm_event(&app_event_sender, alarm).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Err(e) => log::warn!("保存闹钟列表失败: {}", e),
+                }
+            })?;
+
+        Ok(Self { state })
+    }
+
+    fn with_state<T>(&self, f: impl FnOnce(&mut AlarmState) -> Result<T>) -> Result<T> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("闹钟状态锁已损坏"))?;
+        f(&mut state)
+    }
+
+    /// 添加按时刻触发的闹钟
+    ///
+    /// # 参数
+    /// * `hour` - 小时（0-23）
+    /// * `minute` - 分钟（0-59）
+    /// * `label` - 标签
+    /// * `daily` - 是否每天重复
+    ///
+    /// # 返回值
+    /// 新闹钟的ID
+    pub fn add_alarm(&self, hour: u8, minute: u8, label: String, daily: bool) -> Result<u32> {
+        if hour > 23 || minute > 59 {
+            anyhow::bail!("无效的闹钟时间: {:02}:{:02}", hour, minute);
+        }
+        let schedule = if daily {
+            AlarmSchedule::Daily { hour, minute }
+        } else {
+            AlarmSchedule::Once { hour, minute }
+        };
+        self.with_state(|state| state.add(label, schedule))
+    }
+
+    /// 开始倒计时
+    ///
+    /// # 参数
+    /// * `duration` - 倒计时时长
+    /// * `label` - 标签
+    ///
+    /// # 返回值
+    /// 新倒计时的ID
+    pub fn start_timer(&self, duration: Duration, label: String) -> Result<u32> {
+        let fires_at = unix_now() + duration.as_secs().max(1);
+        self.with_state(|state| state.add(label, AlarmSchedule::Timer { fires_at }))
+    }
+
+    /// 删除闹钟或倒计时
+    pub fn remove(&self, id: u32) -> Result<()> {
+        self.with_state(|state| {
+            state.list.alarms.retain(|alarm| alarm.id != id);
+            state.persist()
+        })
+    }
+
+    /// 删除所有闹钟与倒计时
+    pub fn clear(&self) -> Result<()> {
+        self.with_state(|state| {
+            state.list.alarms.clear();
+            state.persist()
+        })
+    }
+
+    /// 当前所有闹钟与倒计时
+    pub fn alarms(&self) -> Vec<Alarm> {
+        self.state
+            .lock()
+            .map(|state| state.list.alarms.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alarm_is_due() {
+        let time = LocalTime::from_local_secs(7 * 3600 + 30 * 60);
+        let daily = Alarm {
+            id: 0,
+            label: String::new(),
+            schedule: AlarmSchedule::Daily {
+                hour: 7,
+                minute: 30,
+            },
+        };
+        assert!(daily.is_due(Some(&time), 0));
+        assert!(!daily.is_due(None, 0));
+
+        let timer = Alarm {
+            id: 1,
+            label: String::new(),
+            schedule: AlarmSchedule::Timer { fires_at: 100 },
+        };
+        assert!(!timer.is_due(None, 99));
+        assert!(timer.is_due(None, 100));
+        assert_eq!(timer.title(), "倒计时结束");
+    }
+}
@@ -0,0 +1,100 @@
+// src/app/brightness.rs
+//! 自动亮度
+//!
+//! 环境光照度按对数映射到背光亮度：1 lux以下为最低亮度，1000 lux以上为最高亮度，
+//! 与人眼对亮度的感知大致成比例。照度先做指数平滑，目标亮度与当前亮度相差
+//! 超过回差时才调整，避免灯光闪烁或手挡住传感器时亮度来回跳变。
+
+use crate::peripherals::backlight::MIN_BRIGHTNESS;
+
+/// 最高亮度
+const MAX_BRIGHTNESS: u8 = 100;
+/// 达到最高亮度的照度(lux)
+const FULL_BRIGHTNESS_LUX: f32 = 1000.0;
+/// 目标亮度与当前亮度相差超过该值（百分点）才调整
+const HYSTERESIS: u8 = 8;
+/// 照度指数平滑系数（每秒一次读数）
+const LUX_SMOOTHING: f32 = 0.3;
+
+/// 照度对应的目标亮度
+pub fn brightness_for_lux(lux: f32) -> u8 {
+    let position = (lux.max(1.0).log10() / FULL_BRIGHTNESS_LUX.log10()).min(1.0);
+    let range = (MAX_BRIGHTNESS - MIN_BRIGHTNESS) as f32;
+    MIN_BRIGHTNESS + (range * position).round() as u8
+}
+
+/// 自动亮度控制器
+#[derive(Debug, Clone, Default)]
+pub struct AutoBrightness {
+    /// 平滑后的照度，尚无读数时为None
+    lux: Option<f32>,
+    /// 当前亮度，尚未调整过时为None
+    brightness: Option<u8>,
+}
+
+impl AutoBrightness {
+    /// 输入一次照度读数
+    ///
+    /// # 返回值
+    /// 需要调整亮度时返回新的亮度
+    pub fn update(&mut self, lux: f32) -> Option<u8> {
+        let smoothed = match self.lux {
+            Some(previous) => previous + (lux - previous) * LUX_SMOOTHING,
+            None => lux,
+        };
+        self.lux = Some(smoothed);
+
+        let target = brightness_for_lux(smoothed);
+        let changed = match self.brightness {
+            Some(current) => current.abs_diff(target) > HYSTERESIS,
+            None => true,
+        };
+        changed.then(|| {
+            self.brightness = Some(target);
+            target
+        })
+    }
+
+    /// 丢弃之前的读数，重新启用自动亮度时立即按下一次读数调整
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brightness_for_lux() {
+        assert_eq!(brightness_for_lux(0.0), MIN_BRIGHTNESS);
+        assert_eq!(brightness_for_lux(1.0), MIN_BRIGHTNESS);
+        assert_eq!(brightness_for_lux(5000.0), MAX_BRIGHTNESS);
+        let indoor = brightness_for_lux(100.0);
+        assert!(indoor > MIN_BRIGHTNESS && indoor < MAX_BRIGHTNESS);
+    }
+
+    #[test]
+    fn test_first_reading_applies_immediately() {
+        let mut auto = AutoBrightness::default();
+        assert_eq!(auto.update(1000.0), Some(MAX_BRIGHTNESS));
+        assert_eq!(auto.update(1000.0), None);
+    }
+
+    #[test]
+    fn test_small_changes_ignored_large_changes_follow() {
+        let mut auto = AutoBrightness::default();
+        let start = auto.update(100.0).unwrap();
+        // 照度小幅波动不调整
+        for lux in [90.0, 110.0, 95.0, 105.0] {
+            assert_eq!(auto.update(lux), None);
+        }
+        // 关灯后亮度随平滑后的照度降低
+        let dimmed = (0..10).filter_map(|_| auto.update(0.0)).last();
+        assert!(dimmed.is_some_and(|level| level < start));
+
+        // 重置后下一次读数立即生效
+        auto.reset();
+        assert_eq!(auto.update(0.0), Some(MIN_BRIGHTNESS));
+    }
+}
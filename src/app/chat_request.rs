@@ -0,0 +1,135 @@
+//! 对话请求的等待状态
+//!
+//! 发送提示后进入等待，收到结果、取消、插话或超时后结束。等待期间才接受对话actor的进度与结果，
+//! 并且只接受当前请求编号的结果：结束后到达的结果，以及之前请求迟到的结果都直接丢弃。
+//! 最近一次提示在结束后仍然保留，供错误界面重试。
+
+use std::time::{Duration, Instant};
+
+use crate::api::request::{ChatInput, RequestId, CHAT_REQUEST_TIMEOUT};
+
+/// 思考界面在请求截止时间之后额外等待的时间，超过后由界面主动取消请求
+pub const THINKING_TIMEOUT_SLACK: Duration = Duration::from_secs(5);
+
+/// 对话请求的等待状态
+#[derive(Debug, Default)]
+pub struct ChatRequest {
+    /// 最近一次发送的提示，用于重试
+    last_prompt: Option<ChatInput>,
+    /// 最近一次发送的请求编号
+    request: RequestId,
+    /// 等待结果的截止时间，None表示没有在等待
+    deadline: Option<Instant>,
+}
+
+impl ChatRequest {
+    /// 发送提示后开始等待
+    ///
+    /// # 参数
+    /// * `request` - 对话actor分配的请求编号
+    /// * `input` - 本次提示
+    /// * `now` - 当前时间
+    pub fn start(&mut self, request: RequestId, input: ChatInput, now: Instant) {
+        self.request = request;
+        self.last_prompt = Some(input);
+        self.deadline = Some(now + CHAT_REQUEST_TIMEOUT + THINKING_TIMEOUT_SLACK);
+    }
+
+    /// 是否正在等待结果
+    pub fn is_pending(&self) -> bool {
+        self.deadline.is_some()
+    }
+
+    /// 结束等待
+    ///
+    /// # 返回值
+    /// 之前是否在等待，为false时说明请求已经结束，到达的结果应丢弃
+    pub fn finish(&mut self) -> bool {
+        self.deadline.take().is_some()
+    }
+
+    /// 是否正在等待指定请求的结果
+    pub fn is_pending_for(&self, request: RequestId) -> bool {
+        self.is_pending() && self.request == request
+    }
+
+    /// 收到指定请求的结果时结束等待
+    ///
+    /// # 返回值
+    /// 是否为正在等待的请求，为false时结果应丢弃
+    pub fn finish_for(&mut self, request: RequestId) -> bool {
+        self.is_pending_for(request) && self.finish()
+    }
+
+    /// 是否已超过截止时间仍未收到结果
+    pub fn is_overdue(&self, now: Instant) -> bool {
+        self.deadline.is_some_and(|deadline| now >= deadline)
+    }
+
+    /// 最近一次发送的提示
+    pub fn last_prompt(&self) -> Option<&ChatInput> {
+        self.last_prompt.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FakeClock;
+
+    fn text(prompt: &str) -> ChatInput {
+        ChatInput::Text(prompt.to_string())
+    }
+
+    #[test]
+    fn test_pending_until_finished() {
+        let mut clock = FakeClock::new();
+        let mut request = ChatRequest::default();
+        assert!(!request.is_pending());
+        assert!(!request.finish());
+
+        request.start(RequestId(1), text("你好"), clock.now());
+        assert!(request.is_pending());
+        clock.advance(CHAT_REQUEST_TIMEOUT);
+        assert!(!request.is_overdue(clock.now()));
+
+        // 第一个结果结束等待，之后到达的结果丢弃
+        assert!(request.finish());
+        assert!(!request.finish());
+        assert!(!request.is_overdue(clock.now() + THINKING_TIMEOUT_SLACK));
+        // 结束后仍可重试
+        assert!(matches!(request.last_prompt(), Some(ChatInput::Text(p)) if p == "你好"));
+    }
+
+    #[test]
+    fn test_overdue_after_slack() {
+        let mut clock = FakeClock::new();
+        let mut request = ChatRequest::default();
+        request.start(RequestId(1), text("一"), clock.now());
+        clock.advance(CHAT_REQUEST_TIMEOUT + THINKING_TIMEOUT_SLACK);
+        assert!(request.is_overdue(clock.now()));
+
+        // 重试重新计时并替换提示
+        request.start(RequestId(2), text("二"), clock.now());
+        assert!(!request.is_overdue(clock.now()));
+        assert!(matches!(request.last_prompt(), Some(ChatInput::Text(p)) if p == "二"));
+    }
+
+    #[test]
+    fn test_stale_results_ignored() {
+        let mut clock = FakeClock::new();
+        let mut request = ChatRequest::default();
+        request.start(RequestId(1), text("一"), clock.now());
+        // 插话后发送新提示，前一个请求被取消
+        clock.advance(Duration::from_secs(1));
+        request.start(RequestId(2), text("二"), clock.now());
+
+        // 前一个请求迟到的取消结果不会结束新请求
+        assert!(!request.is_pending_for(RequestId(1)));
+        assert!(!request.finish_for(RequestId(1)));
+        assert!(request.is_pending());
+
+        assert!(request.finish_for(RequestId(2)));
+        assert!(!request.finish_for(RequestId(2)));
+    }
+}
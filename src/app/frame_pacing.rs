@@ -0,0 +1,171 @@
+// src/app/frame_pacing.rs
+//! 界面帧率控制
+//!
+//! 界面刷新任务按目标帧率调度（见[`frame_period`]）。显示线程仍在通过SPI发送上一帧时
+//! 跳过本帧的绘制，避免在传输期间继续修改帧缓冲区、让主循环越积越慢；
+//! 动画按经过的时间计算（见`graphics::animation::EspInstant`），跳帧不会改变动画速度。
+//! 连续跳过的帧数有上限，保证SPI持续繁忙时界面仍然会更新。
+//!
+//! 每个统计窗口计算一次实际绘制的帧率，与目标帧率一起在运行统计界面和遥测中显示。
+
+use std::time::{Duration, Instant};
+
+/// 最低目标帧率
+pub const MIN_UI_FPS: u8 = 5;
+/// 最高目标帧率，再高时SPI传输跟不上整屏动画
+pub const MAX_UI_FPS: u8 = 30;
+/// 默认目标帧率
+pub const DEFAULT_UI_FPS: u8 = 20;
+
+/// 显示线程忙时最多连续跳过的帧数
+const MAX_CONSECUTIVE_SKIPS: u32 = 2;
+
+/// 帧率统计窗口
+const STATS_WINDOW: Duration = Duration::from_secs(1);
+
+/// 目标帧率对应的刷新周期，超出范围时按边界值处理
+pub fn frame_period(fps: u8) -> Duration {
+    Duration::from_secs(1) / fps.clamp(MIN_UI_FPS, MAX_UI_FPS) as u32
+}
+
+/// 帧率统计
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameStats {
+    /// 目标帧率
+    pub target_fps: u8,
+    /// 最近一个统计窗口实际绘制的帧率
+    pub measured_fps: f32,
+    /// 最近一个统计窗口因显示线程忙跳过的帧数
+    pub skipped: u32,
+}
+
+/// 帧率控制器
+pub struct FramePacer {
+    target_fps: u8,
+    window_start: Instant,
+    rendered: u32,
+    skipped: u32,
+    consecutive_skips: u32,
+    stats: FrameStats,
+}
+
+impl FramePacer {
+    /// 创建帧率控制器
+    ///
+    /// # 参数
+    /// * `target_fps` - 目标帧率，超出范围时按边界值处理
+    /// * `now` - 当前时间，作为第一个统计窗口的起点
+    pub fn new(target_fps: u8, now: Instant) -> Self {
+        let target_fps = target_fps.clamp(MIN_UI_FPS, MAX_UI_FPS);
+        Self {
+            target_fps,
+            window_start: now,
+            rendered: 0,
+            skipped: 0,
+            consecutive_skips: 0,
+            stats: FrameStats {
+                target_fps,
+                ..FrameStats::default()
+            },
+        }
+    }
+
+    /// 目标帧率
+    pub fn target_fps(&self) -> u8 {
+        self.target_fps
+    }
+
+    /// 修改目标帧率
+    ///
+    /// # 返回值
+    /// 实际使用的目标帧率（限制在`MIN_UI_FPS`到`MAX_UI_FPS`之间）
+    pub fn set_target_fps(&mut self, fps: u8) -> u8 {
+        self.target_fps = fps.clamp(MIN_UI_FPS, MAX_UI_FPS);
+        self.stats.target_fps = self.target_fps;
+        self.target_fps
+    }
+
+    /// 最近一个统计窗口的帧率
+    pub fn stats(&self) -> FrameStats {
+        self.stats
+    }
+
+    /// 决定本帧是否绘制
+    ///
+    /// # 参数
+    /// * `display_busy` - 显示线程是否仍在发送上一帧
+    /// * `now` - 当前时间
+    ///
+    /// # 返回值
+    /// 需要绘制时返回true
+    pub fn begin_frame(&mut self, display_busy: bool, now: Instant) -> bool {
+        self.roll_window(now);
+
+        if display_busy && self.consecutive_skips < MAX_CONSECUTIVE_SKIPS {
+            self.consecutive_skips += 1;
+            self.skipped += 1;
+            return false;
+        }
+        self.consecutive_skips = 0;
+        self.rendered += 1;
+        true
+    }
+
+    /// 统计窗口结束时计算帧率并开始新的窗口
+    fn roll_window(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed < STATS_WINDOW {
+            return;
+        }
+        self.stats = FrameStats {
+            target_fps: self.target_fps,
+            measured_fps: self.rendered as f32 / elapsed.as_secs_f32(),
+            skipped: self.skipped,
+        };
+        self.window_start = now;
+        self.rendered = 0;
+        self.skipped = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_period_is_clamped() {
+        assert_eq!(frame_period(20), Duration::from_millis(50));
+        assert_eq!(frame_period(0), Duration::from_millis(200));
+        assert_eq!(frame_period(120), frame_period(MAX_UI_FPS));
+    }
+
+    #[test]
+    fn test_measured_fps_and_skips() {
+        let start = Instant::now();
+        let mut pacer = FramePacer::new(20, start);
+        let period = frame_period(pacer.target_fps());
+
+        // 每4帧中有3帧遇到显示线程忙：最多连续跳过2帧
+        let mut drawn = 0;
+        for i in 0..20 {
+            if pacer.begin_frame(i % 4 != 0, start + period * i) {
+                drawn += 1;
+            }
+        }
+        assert_eq!(drawn, 10);
+
+        // 进入下一个窗口时结算上一个窗口
+        pacer.begin_frame(false, start + STATS_WINDOW);
+        let stats = pacer.stats();
+        assert_eq!(stats.target_fps, 20);
+        assert_eq!(stats.skipped, 10);
+        assert!((stats.measured_fps - 10.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_set_target_fps() {
+        let mut pacer = FramePacer::new(20, Instant::now());
+        assert_eq!(pacer.set_target_fps(60), MAX_UI_FPS);
+        assert_eq!(pacer.stats().target_fps, MAX_UI_FPS);
+    }
+}
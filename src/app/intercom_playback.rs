@@ -0,0 +1,84 @@
+// src/app/intercom_playback.rs
+//! 对讲语音的分段播放
+//!
+//! 收到的语音先放入队列，由主循环每帧播放一段（与语音导航相同），
+//! 不在收到时整段阻塞主循环。网络抖动或界面卡顿造成积压时丢弃最早的样本，
+//! 避免延迟越来越大。
+
+use std::collections::VecDeque;
+
+/// 对讲语音播放队列
+#[derive(Debug)]
+pub struct IntercomPlayback {
+    queue: VecDeque<i16>,
+    /// 本帧取出的样本，供扬声器播放
+    slice: Vec<i16>,
+    /// 队列最多保留的样本数
+    max_backlog: usize,
+}
+
+impl IntercomPlayback {
+    /// 创建播放队列
+    ///
+    /// # 参数
+    /// * `max_backlog` - 最多积压的样本数，超出时丢弃最早的样本
+    pub fn new(max_backlog: usize) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            slice: Vec::new(),
+            max_backlog,
+        }
+    }
+
+    /// 放入收到的一段语音
+    pub fn push(&mut self, samples: &[i16]) {
+        self.queue.extend(samples);
+        let excess = self.queue.len().saturating_sub(self.max_backlog);
+        if excess > 0 {
+            self.queue.drain(..excess);
+        }
+    }
+
+    /// 本帧要播放的一段，队列为空时返回None
+    ///
+    /// # 参数
+    /// * `max_samples` - 每帧最多播放的样本数
+    pub fn next_slice(&mut self, max_samples: usize) -> Option<&[i16]> {
+        if self.queue.is_empty() {
+            return None;
+        }
+        let len = max_samples.min(self.queue.len());
+        self.slice.clear();
+        self.slice.extend(self.queue.drain(..len));
+        Some(&self.slice)
+    }
+
+    /// 放弃未播放的语音（离开对讲界面或播放失败时）
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slices_and_backlog() {
+        let mut playback = IntercomPlayback::new(8);
+        playback.push(&[1, 2, 3, 4, 5]);
+        assert_eq!(playback.next_slice(3), Some(&[1, 2, 3][..]));
+
+        // 积压超出上限时丢弃最早的样本
+        playback.push(&[6, 7, 8, 9, 10, 11, 12]);
+        assert_eq!(
+            playback.next_slice(16),
+            Some(&[5, 6, 7, 8, 9, 10, 11, 12][..])
+        );
+        assert_eq!(playback.next_slice(16), None);
+
+        playback.push(&[1, 2]);
+        playback.clear();
+        assert_eq!(playback.next_slice(16), None);
+    }
+}
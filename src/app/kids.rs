@@ -0,0 +1,317 @@
+// src/app/kids.rs
+//! 儿童模式
+//!
+//! 开启后对话角色固定为儿童角色，每天的互动时长受限，用完后显示"休息一下"界面，
+//! 第二天自动恢复。进入设置界面前需要用旋转手势输入家长密码。
+//! 当天已用时长保存在NVS中，重启后继续累计。
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use esp_idf_svc::nvs::{EspDefaultNvsPartition, EspNvs, NvsDefault};
+use serde::{Deserialize, Serialize};
+
+use crate::{clock::LocalTime, peripherals::qmi8658::motion_detector::MotionState};
+
+/// NVS命名空间
+const KIDS_NAMESPACE: &str = "kids";
+/// 当天用量JSON在NVS中的键
+const USAGE_KEY: &str = "usage";
+
+/// 用量写入NVS的间隔，避免频繁擦写Flash
+const USAGE_PERSIST_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 两次手势之间超过该时间后重新输入密码
+const GESTURE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 手势密码中的一步
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnlockGesture {
+    /// 顺时针旋转
+    Clockwise,
+    /// 逆时针旋转
+    CounterClockwise,
+}
+
+impl UnlockGesture {
+    /// 从运动状态转换，只有旋转手势可以用于密码
+    pub fn from_motion(state: MotionState) -> Option<Self> {
+        match state {
+            MotionState::RotatingClockwise => Some(Self::Clockwise),
+            MotionState::RotatingCounterClockwise => Some(Self::CounterClockwise),
+            _ => None,
+        }
+    }
+}
+
+/// 儿童模式配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KidsModeConfig {
+    /// 是否开启
+    pub enabled: bool,
+    /// 每天允许的互动时长（分钟），0表示不限制
+    pub daily_limit_minutes: u16,
+    /// 进入设置界面的手势密码
+    pub unlock_code: Vec<UnlockGesture>,
+}
+
+impl Default for KidsModeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            daily_limit_minutes: 30,
+            unlock_code: vec![
+                UnlockGesture::Clockwise,
+                UnlockGesture::Clockwise,
+                UnlockGesture::CounterClockwise,
+                UnlockGesture::CounterClockwise,
+            ],
+        }
+    }
+}
+
+impl KidsModeConfig {
+    /// 每天允许的互动时长（秒），不限制时为None
+    pub fn daily_limit_secs(&self) -> Option<u32> {
+        (self.daily_limit_minutes > 0).then(|| self.daily_limit_minutes as u32 * 60)
+    }
+}
+
+/// 日期编号（yyyymmdd），用于判断是否跨天
+pub fn day_key(time: &LocalTime) -> u32 {
+    time.year.max(0) as u32 * 10000 + time.month as u32 * 100 + time.day as u32
+}
+
+/// 当天的互动用量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyUsage {
+    /// 用量所属日期（yyyymmdd），0表示未知
+    pub day: u32,
+    /// 已用时长（秒）
+    pub used_secs: u32,
+}
+
+impl DailyUsage {
+    /// 日期变化时清零
+    ///
+    /// 时间尚未同步时无法判断日期，继续累计到已记录的日期上。
+    ///
+    /// # 返回值
+    /// 是否已清零
+    pub fn roll_over(&mut self, today: Option<u32>) -> bool {
+        match today {
+            Some(day) if day != self.day => {
+                *self = Self { day, used_secs: 0 };
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 剩余时长（秒），不限制时为None
+    pub fn remaining_secs(&self, limit_secs: Option<u32>) -> Option<u32> {
+        limit_secs.map(|limit| limit.saturating_sub(self.used_secs))
+    }
+
+    /// 是否已用完当天的时长
+    pub fn is_exhausted(&self, limit_secs: Option<u32>) -> bool {
+        self.remaining_secs(limit_secs) == Some(0)
+    }
+}
+
+/// 手势密码输入
+///
+/// 只比较最近输入的几步，输错后继续输入即可，不需要先清除。
+#[derive(Debug, Default)]
+pub struct GestureLock {
+    entered: Vec<UnlockGesture>,
+    last_input: Option<Instant>,
+}
+
+impl GestureLock {
+    /// 清除已输入的手势
+    pub fn reset(&mut self) {
+        self.entered.clear();
+        self.last_input = None;
+    }
+
+    /// 已输入的步数（不超过密码长度）
+    pub fn entered(&self) -> usize {
+        self.entered.len()
+    }
+
+    /// 输入一步手势
+    ///
+    /// # 参数
+    /// * `code` - 正确的密码
+    /// * `gesture` - 输入的手势
+    /// * `now` - 当前时间，与上一步间隔超过`GESTURE_TIMEOUT`时重新开始
+    ///
+    /// # 返回值
+    /// 最近输入的手势与密码一致时返回true，并清除已输入的手势
+    pub fn push(&mut self, code: &[UnlockGesture], gesture: UnlockGesture, now: Instant) -> bool {
+        if self
+            .last_input
+            .is_some_and(|last| now.duration_since(last) > GESTURE_TIMEOUT)
+        {
+            self.entered.clear();
+        }
+        self.last_input = Some(now);
+        if code.is_empty() {
+            return true;
+        }
+
+        self.entered.push(gesture);
+        if self.entered.len() > code.len() {
+            self.entered.remove(0);
+        }
+        let unlocked = self.entered == code;
+        if unlocked {
+            self.reset();
+        }
+        unlocked
+    }
+}
+
+/// 儿童模式用量存储
+pub struct KidsUsageStore {
+    nvs: EspNvs<NvsDefault>,
+    usage: DailyUsage,
+    /// 不足一秒的互动时间，留到下次累计
+    carry: Duration,
+    last_tick: Instant,
+    last_persist: Instant,
+    /// 内存中的用量比NVS中的新
+    dirty: bool,
+}
+
+impl KidsUsageStore {
+    /// 从NVS加载当天用量，解析失败时从零开始
+    ///
+    /// # 参数
+    /// * `partition` - 默认NVS分区
+    pub fn new(partition: EspDefaultNvsPartition) -> Result<Self> {
+        let nvs = EspNvs::new(partition, KIDS_NAMESPACE, true)?;
+        let usage = match nvs.str_len(USAGE_KEY)? {
+            Some(len) => {
+                let mut buf = vec![0u8; len];
+                match nvs.get_str(USAGE_KEY, &mut buf)? {
+                    Some(json) => serde_json::from_str(json).unwrap_or_else(|e| {
+                        log::warn!("儿童模式用量解析失败，已清零: {}", e);
+                        DailyUsage::default()
+                    }),
+                    None => DailyUsage::default(),
+                }
+            }
+            None => DailyUsage::default(),
+        };
+
+        Ok(Self {
+            nvs,
+            usage,
+            carry: Duration::ZERO,
+            last_tick: Instant::now(),
+            last_persist: Instant::now(),
+            dirty: false,
+        })
+    }
+
+    /// 当天用量
+    pub fn usage(&self) -> DailyUsage {
+        self.usage
+    }
+
+    /// 定期调用，累计互动时间并按`USAGE_PERSIST_INTERVAL`间隔写入NVS
+    ///
+    /// # 参数
+    /// * `today` - 当天的日期编号，时间尚未同步时为None
+    /// * `active` - 距上次调用期间是否在互动
+    pub fn tick(&mut self, today: Option<u32>, active: bool) -> Result<()> {
+        let elapsed = self.last_tick.elapsed();
+        self.last_tick = Instant::now();
+
+        // 跨天立即保存，避免重启后把昨天的用量算到今天
+        if self.usage.roll_over(today) {
+            self.carry = Duration::ZERO;
+            return self.persist();
+        }
+
+        if active {
+            self.carry += elapsed;
+            let secs = self.carry.as_secs();
+            if secs > 0 {
+                self.carry -= Duration::from_secs(secs);
+                self.usage.used_secs = self.usage.used_secs.saturating_add(secs as u32);
+                self.dirty = true;
+            }
+        }
+
+        if self.dirty && self.last_persist.elapsed() >= USAGE_PERSIST_INTERVAL {
+            self.persist()?;
+        }
+        Ok(())
+    }
+
+    fn persist(&mut self) -> Result<()> {
+        self.nvs
+            .set_str(USAGE_KEY, &serde_json::to_string(&self.usage)?)?;
+        self.last_persist = Instant::now();
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_rolls_over_on_new_day() {
+        let limit = KidsModeConfig::default().daily_limit_secs();
+        let mut usage = DailyUsage {
+            day: 20240101,
+            used_secs: 30 * 60,
+        };
+        assert!(usage.is_exhausted(limit));
+        assert!(!usage.is_exhausted(None));
+
+        // 时间未同步或同一天不清零
+        assert!(!usage.roll_over(None));
+        assert!(!usage.roll_over(Some(20240101)));
+        assert_eq!(usage.remaining_secs(limit), Some(0));
+
+        assert!(usage.roll_over(Some(20240102)));
+        assert_eq!(usage.used_secs, 0);
+        assert_eq!(usage.remaining_secs(limit), Some(30 * 60));
+    }
+
+    #[test]
+    fn test_day_key() {
+        let time = LocalTime::from_local_secs(1_704_067_200);
+        assert_eq!(day_key(&time), 20240101);
+    }
+
+    #[test]
+    fn test_gesture_lock() {
+        use UnlockGesture::{Clockwise as Cw, CounterClockwise as Ccw};
+        let code = [Cw, Cw, Ccw];
+        let start = Instant::now();
+        let mut lock = GestureLock::default();
+
+        // 输错的步骤被挤出，最近三步正确即解锁
+        assert!(!lock.push(&code, Ccw, start));
+        assert!(!lock.push(&code, Cw, start));
+        assert!(!lock.push(&code, Cw, start));
+        assert!(lock.push(&code, Ccw, start));
+        assert_eq!(lock.entered(), 0);
+
+        // 间隔太久重新开始
+        assert!(!lock.push(&code, Cw, start));
+        assert!(!lock.push(&code, Cw, start));
+        let later = start + GESTURE_TIMEOUT + Duration::from_secs(1);
+        assert!(!lock.push(&code, Ccw, later));
+        assert_eq!(lock.entered(), 1);
+    }
+}
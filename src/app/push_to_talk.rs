@@ -0,0 +1,106 @@
+// src/app/push_to_talk.rs
+//! 按键说话的延迟启动
+//!
+//! BOOT键同时用于按键说话、单击和双击（截屏）。按下后立即录音会让每次单击、双击都播放
+//! 开始/结束提示音并走一遍录音流程，因此按住超过[`DOUBLE_CLICK_WINDOW`]才开始录音，
+//! 之前松开的按下只作为单击或双击处理；开始过录音的按下松开后上报的单击、双击则忽略。
+
+use std::time::Instant;
+
+use crate::peripherals::button::classifier::DOUBLE_CLICK_WINDOW;
+
+/// 按键说话的启动判定
+#[derive(Debug, Default)]
+pub struct PushToTalkGate {
+    /// 等待判定的按下时间
+    pressed_at: Option<Instant>,
+    /// 本次按下是否已经开始录音
+    current: bool,
+    /// 上一次按下是否开始过录音，双击的第一下可能是一次按键说话
+    previous: bool,
+}
+
+impl PushToTalkGate {
+    /// 按键按下，开始计时
+    pub fn press(&mut self, now: Instant) {
+        self.previous = self.current;
+        self.current = false;
+        self.pressed_at = Some(now);
+    }
+
+    /// 按键松开，之前没有开始录音时本次按下作为单击或双击处理
+    pub fn release(&mut self) {
+        self.pressed_at = None;
+    }
+
+    /// 检查按住时间
+    ///
+    /// # 返回值
+    /// 按住达到双击窗口时返回true，每次按下只返回一次，调用方此时开始录音
+    pub fn poll(&mut self, now: Instant) -> bool {
+        match self.pressed_at {
+            Some(pressed_at) if now.duration_since(pressed_at) >= DOUBLE_CLICK_WINDOW => {
+                self.pressed_at = None;
+                self.current = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 单击是否需要处理，开始过录音的按下松开后同样会上报单击
+    pub fn accepts_click(&self) -> bool {
+        !self.current
+    }
+
+    /// 双击是否需要处理，两次按下都没有开始录音时才是真正的双击
+    pub fn accepts_double_click(&self) -> bool {
+        !self.current && !self.previous
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FakeClock;
+    use std::time::Duration;
+
+    #[test]
+    fn test_short_press_is_click() {
+        let mut clock = FakeClock::new();
+        let mut gate = PushToTalkGate::default();
+        gate.press(clock.now());
+        clock.advance(Duration::from_millis(100));
+        assert!(!gate.poll(clock.now()));
+        gate.release();
+
+        // 松开后不再开始录音
+        clock.advance(DOUBLE_CLICK_WINDOW);
+        assert!(!gate.poll(clock.now()));
+        assert!(gate.accepts_click());
+
+        // 第二次短按组成双击
+        gate.press(clock.now());
+        gate.release();
+        assert!(gate.accepts_double_click());
+    }
+
+    #[test]
+    fn test_hold_starts_once_and_suppresses_clicks() {
+        let mut clock = FakeClock::new();
+        let mut gate = PushToTalkGate::default();
+        gate.press(clock.now());
+        clock.advance(DOUBLE_CLICK_WINDOW);
+        assert!(gate.poll(clock.now()));
+        clock.advance(Duration::from_millis(100));
+        assert!(!gate.poll(clock.now()));
+        gate.release();
+        assert!(!gate.accepts_click());
+
+        // 说完后立即短按一次，与上一次松开组成的双击不截屏
+        gate.press(clock.now());
+        gate.release();
+        assert!(gate.accepts_click());
+        assert!(!gate.accepts_double_click());
+    }
+}
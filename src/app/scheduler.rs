@@ -0,0 +1,199 @@
+// src/app/scheduler.rs
+//! 主循环调度器
+//!
+//! 主循环中的周期性工作（界面刷新、状态栏、遥测）以任务的形式注册，
+//! 每个任务有固定周期和优先级。主循环每次醒来时取出已到期的任务，
+//! 按优先级从高到低执行，然后休眠到最近的截止时间。
+//! 同时记录每个任务相对截止时间的延迟（抖动），便于发现阻塞主循环的调用。
+
+use std::cmp::Reverse;
+use std::time::{Duration, Instant};
+
+/// 任务延迟统计
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct JitterStats {
+    /// 统计期间的执行次数
+    pub runs: u32,
+    /// 最大延迟
+    pub max_lateness: Duration,
+    /// 累计延迟
+    pub total_lateness: Duration,
+}
+
+impl JitterStats {
+    /// 平均延迟
+    pub fn mean_lateness(&self) -> Duration {
+        if self.runs == 0 {
+            Duration::ZERO
+        } else {
+            self.total_lateness / self.runs
+        }
+    }
+
+    fn record(&mut self, lateness: Duration) {
+        self.runs += 1;
+        self.max_lateness = self.max_lateness.max(lateness);
+        self.total_lateness += lateness;
+    }
+}
+
+/// 周期性任务
+struct Task<J> {
+    job: J,
+    period: Duration,
+    priority: u8,
+    next_due: Instant,
+    /// 错过的周期数（执行太晚时直接跳过，不补执行）
+    skipped: u32,
+    jitter: JitterStats,
+}
+
+/// 协作式调度器
+///
+/// `J`通常是描述任务的枚举，由调用方根据返回的任务执行具体工作。
+pub struct Scheduler<J> {
+    tasks: Vec<Task<J>>,
+}
+
+impl<J: Copy> Scheduler<J> {
+    pub fn new() -> Self {
+        Self { tasks: Vec::new() }
+    }
+
+    /// 注册周期性任务，第一次在注册后立即到期
+    ///
+    /// # 参数
+    /// * `job` - 任务标识
+    /// * `period` - 执行周期
+    /// * `priority` - 优先级，同时到期时数值大的先执行
+    pub fn add(&mut self, job: J, period: Duration, priority: u8) {
+        self.tasks.push(Task {
+            job,
+            period,
+            priority,
+            next_due: Instant::now(),
+            skipped: 0,
+            jitter: JitterStats::default(),
+        });
+    }
+
+    /// 取出已到期的任务，按优先级从高到低排列
+    ///
+    /// 取出的任务记录延迟并安排下一次执行。下一次执行保持原来的节拍，
+    /// 若已错过一个或多个周期则跳过，避免阻塞后连续补执行。
+    pub fn due(&mut self, now: Instant) -> Vec<J> {
+        let mut due: Vec<&mut Task<J>> = self
+            .tasks
+            .iter_mut()
+            .filter(|task| task.next_due <= now)
+            .collect();
+        due.sort_by_key(|task| Reverse(task.priority));
+
+        due.into_iter()
+            .map(|task| {
+                task.jitter.record(now - task.next_due);
+                task.next_due += task.period;
+                while task.next_due <= now {
+                    task.next_due += task.period;
+                    task.skipped += 1;
+                }
+                task.job
+            })
+            .collect()
+    }
+
+    /// 距离最近一个任务到期的时间，已有任务到期时为0
+    pub fn time_until_next(&self, now: Instant) -> Duration {
+        self.tasks
+            .iter()
+            .map(|task| task.next_due.saturating_duration_since(now))
+            .min()
+            .unwrap_or(Duration::MAX)
+    }
+
+    /// 取出各任务的延迟统计与跳过的周期数并清零
+    pub fn take_jitter(&mut self) -> Vec<(J, JitterStats, u32)> {
+        self.tasks
+            .iter_mut()
+            .map(|task| {
+                let stats = std::mem::take(&mut task.jitter);
+                let skipped = std::mem::take(&mut task.skipped);
+                (task.job, stats, skipped)
+            })
+            .collect()
+    }
+}
+
+impl<J: Copy + PartialEq> Scheduler<J> {
+    /// 修改任务的执行周期
+    ///
+    /// 新周期比剩余等待时间短时提前到期，不需要等完旧的周期。
+    ///
+    /// # 参数
+    /// * `job` - 任务标识
+    /// * `period` - 新的执行周期
+    /// * `now` - 当前时间
+    pub fn set_period(&mut self, job: J, period: Duration, now: Instant) {
+        for task in self.tasks.iter_mut().filter(|task| task.job == job) {
+            task.period = period;
+            task.next_due = task.next_due.min(now + period);
+        }
+    }
+}
+
+impl<J: Copy> Default for Scheduler<J> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Job {
+        Fast,
+        Slow,
+    }
+
+    #[test]
+    fn test_due_order_and_skipping() {
+        let mut scheduler = Scheduler::new();
+        scheduler.add(Job::Slow, Duration::from_secs(1), 0);
+        scheduler.add(Job::Fast, Duration::from_millis(50), 1);
+        let start = Instant::now();
+
+        // 注册后立即到期，高优先级在前
+        assert_eq!(scheduler.due(start), vec![Job::Fast, Job::Slow]);
+        assert!(scheduler.time_until_next(start) <= Duration::from_millis(50));
+        assert!(scheduler.due(start).is_empty());
+
+        // 阻塞了170ms：快任务只执行一次，跳过错过的周期
+        let late = start + Duration::from_millis(220);
+        assert_eq!(scheduler.due(late), vec![Job::Fast]);
+        assert!(scheduler.due(late).is_empty());
+
+        let jitter = scheduler.take_jitter();
+        let (_, fast, skipped) = jitter[1];
+        assert_eq!(fast.runs, 2);
+        assert_eq!(skipped, 3);
+        assert!(fast.max_lateness >= Duration::from_millis(170));
+        assert_eq!(scheduler.take_jitter()[1].1.runs, 0);
+    }
+
+    #[test]
+    fn test_set_period() {
+        let mut scheduler = Scheduler::new();
+        scheduler.add(Job::Fast, Duration::from_millis(200), 0);
+        let start = Instant::now();
+        assert_eq!(scheduler.due(start), vec![Job::Fast]);
+
+        // 缩短周期后按新周期到期
+        scheduler.set_period(Job::Fast, Duration::from_millis(50), start);
+        assert_eq!(scheduler.time_until_next(start), Duration::from_millis(50));
+        let next = start + Duration::from_millis(50);
+        assert_eq!(scheduler.due(next), vec![Job::Fast]);
+        assert_eq!(scheduler.time_until_next(next), Duration::from_millis(50));
+    }
+}
@@ -0,0 +1,200 @@
+// src/app/selftest.rs
+//! 硬件自检
+//!
+//! 依次检查I2C设备、LCD、麦克风、扬声器和WiFi，每项的结果显示在自检界面上。
+//! 具体的检测动作由App逐帧执行（见`App::poll_self_test`），这里只包含检测项、
+//! 结果和判定逻辑。
+
+use std::time::{Duration, Instant};
+
+use crate::peripherals::{qmi8658::QMI8658_ADDRESS_HIGH, tca9554::TCA9554_ADDRESS};
+
+/// 总线上应当存在的I2C设备
+pub const EXPECTED_I2C_DEVICES: &[(u8, &str)] =
+    &[(QMI8658_ADDRESS_HIGH, "IMU"), (TCA9554_ADDRESS, "IO扩展")];
+
+/// 麦克风均方根幅度下限，低于该值认为没有信号（麦克风未接或数据线断开）
+pub const MIC_MIN_RMS: u16 = 2;
+/// 麦克风均方根幅度上限，高于该值认为信号饱和
+pub const MIC_MAX_RMS: u16 = 20000;
+
+/// 检测项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfTestStep {
+    I2c,
+    Lcd,
+    Microphone,
+    Speaker,
+    Wifi,
+}
+
+impl SelfTestStep {
+    /// 按执行顺序排列的全部检测项
+    pub const ALL: [SelfTestStep; 5] = [
+        SelfTestStep::I2c,
+        SelfTestStep::Lcd,
+        SelfTestStep::Microphone,
+        SelfTestStep::Speaker,
+        SelfTestStep::Wifi,
+    ];
+
+    /// 显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            SelfTestStep::I2c => "I2C设备",
+            SelfTestStep::Lcd => "屏幕",
+            SelfTestStep::Microphone => "麦克风",
+            SelfTestStep::Speaker => "扬声器",
+            SelfTestStep::Wifi => "WiFi",
+        }
+    }
+
+    /// 等待结果的最长时间，超时判定为失败
+    pub fn timeout(&self) -> Duration {
+        match self {
+            SelfTestStep::Wifi => Duration::from_secs(10),
+            _ => Duration::from_secs(3),
+        }
+    }
+}
+
+/// 单项结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestOutcome {
+    Pending,
+    Running,
+    Pass(String),
+    Fail(String),
+}
+
+impl TestOutcome {
+    /// 是否已得出结论
+    pub fn is_done(&self) -> bool {
+        matches!(self, TestOutcome::Pass(_) | TestOutcome::Fail(_))
+    }
+}
+
+/// 自检进度与结果
+#[derive(Debug, Clone)]
+pub struct SelfTestReport {
+    pub results: Vec<(SelfTestStep, TestOutcome)>,
+    /// 当前检测项的序号，全部完成后等于检测项数量
+    current: usize,
+    /// 当前检测项开始的时间
+    step_started: Instant,
+}
+
+impl Default for SelfTestReport {
+    fn default() -> Self {
+        Self {
+            results: SelfTestStep::ALL
+                .iter()
+                .map(|step| (*step, TestOutcome::Pending))
+                .collect(),
+            current: 0,
+            step_started: Instant::now(),
+        }
+    }
+}
+
+impl SelfTestReport {
+    /// 当前检测项，全部完成后为None
+    pub fn current(&self) -> Option<SelfTestStep> {
+        self.results.get(self.current).map(|(step, _)| *step)
+    }
+
+    /// 当前检测项的结果
+    pub fn current_outcome(&self) -> Option<&TestOutcome> {
+        self.results.get(self.current).map(|(_, outcome)| outcome)
+    }
+
+    /// 当前检测项已进行的时间
+    pub fn step_elapsed(&self) -> Duration {
+        self.step_started.elapsed()
+    }
+
+    /// 记录当前检测项的结果，得出结论后进入下一项
+    pub fn set_outcome(&mut self, outcome: TestOutcome) {
+        let done = outcome.is_done();
+        if let Some((step, slot)) = self.results.get_mut(self.current) {
+            if done {
+                log::info!("自检 {}: {:?}", step.name(), outcome);
+            }
+            *slot = outcome;
+        }
+        if done {
+            self.current += 1;
+            self.step_started = Instant::now();
+        }
+    }
+
+    /// 是否全部完成
+    pub fn is_finished(&self) -> bool {
+        self.current >= self.results.len()
+    }
+
+    /// 失败的检测项数量
+    pub fn failures(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, TestOutcome::Fail(_)))
+            .count()
+    }
+}
+
+/// 根据扫描到的地址判定I2C检测结果
+///
+/// # 参数
+/// * `found` - 有应答的地址
+pub fn check_i2c(found: &[u8]) -> TestOutcome {
+    let missing: Vec<&str> = EXPECTED_I2C_DEVICES
+        .iter()
+        .filter(|(address, _)| !found.contains(address))
+        .map(|(_, name)| *name)
+        .collect();
+    if missing.is_empty() {
+        TestOutcome::Pass(format!("{}个设备", found.len()))
+    } else {
+        TestOutcome::Fail(format!("缺少{}", missing.join("、")))
+    }
+}
+
+/// 根据环境噪声的均方根幅度判定麦克风检测结果
+pub fn check_mic_rms(rms: u16) -> TestOutcome {
+    if rms < MIC_MIN_RMS {
+        TestOutcome::Fail("无信号".to_string())
+    } else if rms > MIC_MAX_RMS {
+        TestOutcome::Fail(format!("饱和 {}", rms))
+    } else {
+        TestOutcome::Pass(format!("RMS {}", rms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checks_and_progress() {
+        assert!(matches!(
+            check_i2c(&[QMI8658_ADDRESS_HIGH, TCA9554_ADDRESS]),
+            TestOutcome::Pass(_)
+        ));
+        assert_eq!(
+            check_i2c(&[TCA9554_ADDRESS]),
+            TestOutcome::Fail("缺少IMU".to_string())
+        );
+        assert!(matches!(check_mic_rms(0), TestOutcome::Fail(_)));
+        assert!(matches!(check_mic_rms(120), TestOutcome::Pass(_)));
+
+        let mut report = SelfTestReport::default();
+        assert_eq!(report.current(), Some(SelfTestStep::I2c));
+        report.set_outcome(TestOutcome::Running);
+        assert_eq!(report.current(), Some(SelfTestStep::I2c));
+        for _ in SelfTestStep::ALL {
+            report.set_outcome(TestOutcome::Fail(String::new()));
+        }
+        assert!(report.is_finished());
+        assert_eq!(report.failures(), SelfTestStep::ALL.len());
+    }
+}
@@ -0,0 +1,170 @@
+// src/app/url_editor.rs
+//! 地址输入
+//!
+//! 设备上没有键盘，用旋转手势在字符表中选择，单击按键输入选中的字符。
+//! 字符表开头是"保存"和"删除"两个操作，之后按网址中的常用程度排列字符。
+//! 编辑从当前地址开始，通常只需要修改末尾的几个字符。
+
+use crate::api::endpoints::{self, EndpointField, MAX_URL_LEN};
+
+/// 可输入的字符，网址中常用的排在前面
+const CHARSET: &str = ".:/0123456789abcdefghijklmnopqrstuvwxyz-_ABCDEFGHIJKLMNOPQRSTUVWXYZ?=&%~[]";
+
+/// 字符表中的一项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickerEntry {
+    /// 检查并保存地址
+    Save,
+    /// 删除最后一个字符
+    Delete,
+    /// 输入字符
+    Char(char),
+}
+
+impl PickerEntry {
+    /// 字符表中的第`index`项
+    fn at(index: usize) -> Self {
+        match index {
+            0 => Self::Save,
+            1 => Self::Delete,
+            i => Self::Char(CHARSET.as_bytes()[i - 2] as char),
+        }
+    }
+
+    /// 界面上显示的文字
+    pub fn label(self) -> String {
+        match self {
+            Self::Save => "保存".to_string(),
+            Self::Delete => "删除".to_string(),
+            Self::Char(c) => c.to_string(),
+        }
+    }
+}
+
+/// 单击后的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditorAction {
+    /// 继续编辑
+    Edited,
+    /// 地址检查通过，可以保存
+    Save(String),
+}
+
+/// 地址输入状态
+#[derive(Debug, Clone)]
+pub struct UrlEditor {
+    field: EndpointField,
+    text: String,
+    /// 字符表中选中的项
+    selected: usize,
+    /// 上次保存失败的原因
+    error: Option<String>,
+}
+
+impl UrlEditor {
+    /// # 参数
+    /// * `field` - 要修改的地址
+    /// * `current` - 当前地址，作为编辑的起点
+    pub fn new(field: EndpointField, current: &str) -> Self {
+        Self {
+            field,
+            text: current.to_string(),
+            selected: 0,
+            error: None,
+        }
+    }
+
+    pub fn field(&self) -> EndpointField {
+        self.field
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// 在字符表中移动，两端循环
+    pub fn rotate(&mut self, delta: i32) {
+        let total = (CHARSET.len() + 2) as i32;
+        self.selected = (self.selected as i32 + delta).rem_euclid(total) as usize;
+    }
+
+    /// 选中项及前后各`radius`项，用于界面上显示转盘
+    pub fn window(&self, radius: usize) -> Vec<PickerEntry> {
+        let total = CHARSET.len() + 2;
+        (0..radius * 2 + 1)
+            .map(|offset| PickerEntry::at((self.selected + total + offset - radius) % total))
+            .collect()
+    }
+
+    /// 执行选中项
+    pub fn confirm(&mut self) -> EditorAction {
+        self.error = None;
+        match PickerEntry::at(self.selected) {
+            PickerEntry::Save => match endpoints::validate_url(&self.text) {
+                Ok(url) => return EditorAction::Save(url),
+                Err(e) => self.error = Some(e.to_string()),
+            },
+            PickerEntry::Delete => {
+                self.text.pop();
+            }
+            PickerEntry::Char(c) => {
+                if self.text.len() < MAX_URL_LEN {
+                    self.text.push(c);
+                }
+            }
+        }
+        EditorAction::Edited
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_and_save() {
+        let mut editor = UrlEditor::new(EndpointField::BaseUrl, "http://10.0.0.2/ap");
+        // 删除最后一个字符
+        editor.rotate(1);
+        assert_eq!(editor.confirm(), EditorAction::Edited);
+        assert_eq!(editor.text(), "http://10.0.0.2/a");
+
+        // 逆时针转回"保存"之前的最后一项
+        editor.rotate(-2);
+        assert_eq!(editor.window(0), vec![PickerEntry::Char(']')]);
+        editor.confirm();
+        assert_eq!(editor.text(), "http://10.0.0.2/a]");
+
+        editor.rotate(1);
+        assert_eq!(
+            editor.confirm(),
+            EditorAction::Save("http://10.0.0.2/a]".to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_url_is_not_saved() {
+        let mut editor = UrlEditor::new(EndpointField::PcmUrl, "http://");
+        assert_eq!(editor.confirm(), EditorAction::Edited);
+        assert!(editor.error().is_some());
+
+        // 继续编辑后清除错误提示
+        editor.rotate(2);
+        editor.confirm();
+        assert_eq!(editor.text(), "http://.");
+        assert_eq!(editor.error(), None);
+    }
+
+    #[test]
+    fn test_window_wraps() {
+        let editor = UrlEditor::new(EndpointField::BaseUrl, "");
+        let window = editor.window(1);
+        assert_eq!(window[0], PickerEntry::Char(']'));
+        assert_eq!(window[1], PickerEntry::Save);
+        assert_eq!(window[2], PickerEntry::Delete);
+    }
+}
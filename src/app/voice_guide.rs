@@ -0,0 +1,200 @@
+// src/app/voice_guide.rs
+//! 语音导航
+//!
+//! 为视力不好的用户朗读高亮的列表项（模型、地址输入的字符、对讲设备）。
+//! 朗读使用预先合成的语音片段：16kHz、16位小端、单声道PCM，放在存储的`voice/`下
+//! （有SD卡时优先读SD卡），文件名见[`Announcement::clip_name`]。没有对应片段时播放一声短提示音。
+//!
+//! 旋转手势连续切换时只朗读停下来的那一项；片段按界面帧分段播放，
+//! 每帧只阻塞一小段时间，不超出主循环的阻塞预算。
+
+use std::time::{Duration, Instant};
+
+use super::url_editor::PickerEntry;
+
+/// 高亮项停留多久后开始朗读
+pub const SETTLE_DELAY: Duration = Duration::from_millis(250);
+
+/// 语音片段最长时长（毫秒），更长的部分不播放
+pub const MAX_CLIP_MS: u32 = 2000;
+
+/// 片段文件名中名称部分的最大长度，SPIFFS文件名（含开头的`/`和目录）不能超过31个字符
+const MAX_KEY_LEN: usize = 14;
+
+/// 要朗读的列表项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Announcement {
+    /// 模型选择界面中的模型，None为服务端默认模型
+    Model(Option<String>),
+    /// 地址输入界面中的字符或操作
+    Picker(PickerEntry),
+    /// 对讲界面中的设备名称
+    Peer(String),
+}
+
+impl Announcement {
+    /// 语音片段的文件名（相对于存储挂载点）
+    ///
+    /// 例如`voice/model_default.pcm`、`voice/save.pcm`、`voice/char_2f.pcm`（字符按Unicode编码）。
+    pub fn clip_name(&self) -> String {
+        let key = match self {
+            Announcement::Model(None) => "model_default".to_string(),
+            Announcement::Model(Some(id)) => format!("model_{}", sanitize(id)),
+            Announcement::Picker(PickerEntry::Save) => "save".to_string(),
+            Announcement::Picker(PickerEntry::Delete) => "delete".to_string(),
+            Announcement::Picker(PickerEntry::Char(c)) => format!("char_{:02x}", *c as u32),
+            Announcement::Peer(name) => format!("peer_{}", sanitize(name)),
+        };
+        format!("voice/{}.pcm", key)
+    }
+}
+
+/// 名称转换为文件名：ASCII字母数字转小写，其他字符替换为`_`，过长时截断
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .take(MAX_KEY_LEN)
+        .collect()
+}
+
+/// 朗读状态
+#[derive(Debug, Default)]
+pub struct VoiceGuide {
+    /// 等待高亮停下来的项与最后一次切换的时间
+    pending: Option<(Announcement, Instant)>,
+    /// 正在播放的片段
+    clip: Vec<i16>,
+    /// 已播放的样本数
+    position: usize,
+}
+
+impl VoiceGuide {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 高亮项变化：停止正在朗读的内容，高亮停留`SETTLE_DELAY`后朗读新项
+    pub fn highlight(&mut self, item: Announcement, now: Instant) {
+        self.stop();
+        self.pending = Some((item, now));
+    }
+
+    /// 取出已停留足够时间、应开始朗读的项
+    pub fn take_due(&mut self, now: Instant) -> Option<Announcement> {
+        let (_, since) = self.pending.as_ref()?;
+        if now.saturating_duration_since(*since) < SETTLE_DELAY {
+            return None;
+        }
+        self.pending.take().map(|(item, _)| item)
+    }
+
+    /// 开始播放片段
+    pub fn start(&mut self, clip: Vec<i16>) {
+        self.clip = clip;
+        self.position = 0;
+    }
+
+    /// 本帧要播放的一段，播放完后返回None
+    ///
+    /// # 参数
+    /// * `max_samples` - 每帧最多播放的样本数
+    pub fn next_slice(&mut self, max_samples: usize) -> Option<&[i16]> {
+        if self.position >= self.clip.len() {
+            return None;
+        }
+        let start = self.position;
+        self.position = (start + max_samples).min(self.clip.len());
+        Some(&self.clip[start..self.position])
+    }
+
+    /// 停止朗读并放弃等待中的项
+    pub fn stop(&mut self) {
+        self.pending = None;
+        self.clip.clear();
+        self.position = 0;
+    }
+}
+
+/// 解码语音片段文件，超出`MAX_CLIP_MS`的部分丢弃
+///
+/// # 参数
+/// * `bytes` - 文件内容（16位小端PCM）
+/// * `sample_rate` - 片段的采样率(Hz)
+pub fn decode_clip(bytes: &[u8], sample_rate: u32) -> Vec<i16> {
+    let max_samples = (sample_rate * MAX_CLIP_MS / 1000) as usize;
+    bytes
+        .chunks_exact(2)
+        .take(max_samples)
+        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clip_name() {
+        assert_eq!(
+            Announcement::Model(None).clip_name(),
+            "voice/model_default.pcm"
+        );
+        assert_eq!(
+            Announcement::Model(Some("Qwen-Plus".to_string())).clip_name(),
+            "voice/model_qwen_plus.pcm"
+        );
+        assert_eq!(
+            Announcement::Picker(PickerEntry::Char('/')).clip_name(),
+            "voice/char_2f.pcm"
+        );
+        assert_eq!(
+            Announcement::Picker(PickerEntry::Delete).clip_name(),
+            "voice/delete.pcm"
+        );
+        let long = Announcement::Peer("客厅的音箱-esp32-aichat-0123456789".to_string());
+        assert!(long.clip_name().len() < 31);
+    }
+
+    #[test]
+    fn test_settle_before_speaking() {
+        let now = Instant::now();
+        let mut guide = VoiceGuide::new();
+        guide.highlight(Announcement::Model(None), now);
+        guide.highlight(Announcement::Peer("a".to_string()), now + SETTLE_DELAY / 2);
+        assert_eq!(guide.take_due(now + SETTLE_DELAY), None);
+        assert_eq!(
+            guide.take_due(now + SETTLE_DELAY * 2),
+            Some(Announcement::Peer("a".to_string()))
+        );
+        assert_eq!(guide.take_due(now + SETTLE_DELAY * 3), None);
+    }
+
+    #[test]
+    fn test_slices_and_stop() {
+        let mut guide = VoiceGuide::new();
+        guide.start(vec![1; 10]);
+        assert_eq!(guide.next_slice(4).map(<[i16]>::len), Some(4));
+        assert_eq!(guide.next_slice(4).map(<[i16]>::len), Some(4));
+        assert_eq!(guide.next_slice(4).map(<[i16]>::len), Some(2));
+        assert_eq!(guide.next_slice(4), None);
+
+        // 高亮变化时立即停止
+        guide.start(vec![1; 10]);
+        guide.next_slice(4);
+        guide.highlight(Announcement::Model(None), Instant::now());
+        assert_eq!(guide.next_slice(4), None);
+    }
+
+    #[test]
+    fn test_decode_clip() {
+        assert_eq!(decode_clip(&[0x01, 0x00, 0xff, 0xff, 0x7f], 16000), [1, -1]);
+        let long = vec![0u8; 16000 * 2 * 3];
+        assert_eq!(decode_clip(&long, 16000).len(), 16000 * 2);
+    }
+}
@@ -0,0 +1,105 @@
+// src/blocking.rs
+//! 阻塞调用预算
+//!
+//! 主循环以~20fps运行（50ms延迟），任何在主循环中执行的长时间阻塞调用都会推迟
+//! 事件处理与UI刷新。这里集中定义各类阻塞调用允许的最长时间，并提供检查工具：
+//!
+//! - 超出预算时输出警告日志，并计入`metrics::BLOCKING_OVERRUNS`，遥测中可以看到随功能增加
+//!   悄悄变慢的调用。网络差时HTTP请求超出预算是正常现象，因此只记录不中断
+//! - 主线程上的调用在启动完成后（见[`finish_startup`]）超出预算时，debug构建下通过
+//!   `debug_assert!`直接暴露问题，避免随功能增加悄悄拖慢主循环。首次连接WiFi时创建AFE、
+//!   加载唤醒词模型等一次性的启动工作不受此限制
+//!
+//! 耗时以秒计的调用（WiFi连接、HTTP请求）只允许在各自的actor线程中执行，
+//! 可以通过[`assert_off_main_thread`]在调用点声明这一约束。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::metrics;
+
+/// 主循环单次迭代（事件处理 + 显示更新）的预算
+pub const MAIN_LOOP_BUDGET: Duration = Duration::from_millis(200);
+
+/// LCD面板复位与初始化的预算（包含120ms复位等待和50ms显示切换等待）
+pub const LCD_INIT_BUDGET: Duration = Duration::from_millis(500);
+
+/// WiFi连接（关联 + DHCP）的预算，只允许在WiFi actor线程中执行
+pub const WIFI_CONNECT_BUDGET: Duration = Duration::from_secs(30);
+
+/// 单次HTTP请求（连接 + 发送 + 读取响应）的额外预算，实际预算为请求超时加上该值
+pub const HTTP_REQUEST_SLACK: Duration = Duration::from_secs(2);
+
+/// 一次性的启动工作已完成，之后主线程上的超时在debug构建下中断
+static STARTUP_FINISHED: AtomicBool = AtomicBool::new(false);
+
+/// 声明一次性的启动工作已完成
+///
+/// 之后主线程上的阻塞调用超出预算时，debug构建下直接panic。
+pub fn finish_startup() {
+    STARTUP_FINISHED.store(true, Ordering::Relaxed);
+}
+
+/// 检查一次阻塞调用是否超出预算
+///
+/// 启动完成后主线程上的调用超出预算时，debug构建下触发`debug_assert!`；
+/// actor线程中的调用（例如网络差时的HTTP请求）只记录不中断。
+///
+/// # 参数
+/// * `name` - 调用名称，用于日志
+/// * `budget` - 允许的最长时间
+/// * `elapsed` - 实际耗时
+pub fn check_budget(name: &str, budget: Duration, elapsed: Duration) {
+    if elapsed > budget {
+        log::warn!(
+            "阻塞调用超出预算: {} 耗时 {}ms，预算 {}ms",
+            name,
+            elapsed.as_millis(),
+            budget.as_millis()
+        );
+        metrics::increment(metrics::BLOCKING_OVERRUNS, 1);
+        debug_assert!(
+            !STARTUP_FINISHED.load(Ordering::Relaxed)
+                || std::thread::current().name() != Some("main"),
+            "blocking call `{}` took {:?}, budget {:?}",
+            name,
+            elapsed,
+            budget
+        );
+    }
+}
+
+/// 在预算内执行一次阻塞调用
+///
+/// # 参数
+/// * `name` - 调用名称，用于日志
+/// * `budget` - 允许的最长时间
+/// * `f` - 要执行的阻塞调用
+///
+/// # 返回值
+/// 返回`f`的执行结果
+///
+/// # 示例
+/// ```rust,no_run
+/// let controller = blocking::with_budget("lcd_init", LCD_INIT_BUDGET, || {
+///     controller.start_display()
+/// })?;
+/// ```
+pub fn with_budget<T>(name: &str, budget: Duration, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+    check_budget(name, budget, start.elapsed());
+    result
+}
+
+/// 声明当前调用不允许在主线程（主循环）中执行
+///
+/// 仅在debug构建下检查，release构建中为空操作。
+pub fn assert_off_main_thread(name: &str) {
+    debug_assert_ne!(
+        std::thread::current().name(),
+        Some("main"),
+        "blocking call `{}` must not run on the main loop",
+        name
+    );
+}
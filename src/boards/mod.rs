@@ -0,0 +1,104 @@
+//! 板级支持
+//!
+//! 外设驱动只接收抽象的引脚类型，具体接到哪个GPIO、屏幕多大、有没有触摸和电池检测
+//! 由这里按硬件版本决定。板子通过Cargo特性选择，换板子时只需要新增一个板子模块，
+//! 不需要改动驱动代码和main.rs。
+
+#[cfg(feature = "board-waveshare-185")]
+mod waveshare_lcd_185;
+#[cfg(feature = "board-waveshare-185")]
+use waveshare_lcd_185 as current;
+
+#[cfg(not(feature = "board-waveshare-185"))]
+compile_error!("必须通过Cargo特性选择一个板子，例如`board-waveshare-185`");
+
+use std::fmt;
+
+use crate::graphics::framebuffer::ColorDepth;
+#[cfg(target_os = "espidf")]
+use crate::peripherals::st77916::lcd::LcdConfig;
+
+// 引脚与LCD驱动参数只在ESP-IDF目标上编译，屏幕尺寸等参数在主机上运行测试时也会用到
+#[cfg(target_os = "espidf")]
+mod pins;
+#[cfg(target_os = "espidf")]
+pub use pins::*;
+
+/// 当前板子的硬件描述
+pub const SPEC: BoardSpec = current::SPEC;
+
+/// 功放类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Amplifier {
+    /// I2S DAC（PCM5101），内部PLL，不需要MCLK
+    Pcm5101,
+    /// I2S数字功放（NS4168等）
+    I2sClassD,
+}
+
+/// 麦克风接口
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MicInterface {
+    /// 标准I2S（INMP441等），需要WS、SCK、SD三根线
+    I2sStd,
+    /// PDM（MSM261等），只有时钟和数据两根线
+    Pdm,
+}
+
+/// 电池电压检测
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatterySense {
+    /// ADC引脚编号
+    pub adc_gpio: u8,
+    /// 分压比，ADC读数乘以该值得到电池电压
+    pub divider: f32,
+}
+
+/// 板子硬件描述
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoardSpec {
+    /// 板子名称
+    pub name: &'static str,
+    /// 屏幕物理分辨率
+    pub display_width: i32,
+    pub display_height: i32,
+    /// 是否带触摸屏
+    pub has_touch: bool,
+    /// 功放类型
+    pub amplifier: Amplifier,
+    /// 麦克风接口
+    pub microphone: MicInterface,
+    /// 电池电压检测，没有电池的板子为None
+    pub battery: Option<BatterySense>,
+    /// 帧缓冲区颜色深度，取决于板子是否带PSRAM
+    pub framebuffer_depth: ColorDepth,
+    /// 屏幕的QSPI总线参数
+    #[cfg(target_os = "espidf")]
+    pub lcd: LcdConfig,
+    /// 外接WS2812状态灯环的灯珠数量，没有灯环时为None
+    pub status_ring_leds: Option<u8>,
+}
+
+impl fmt::Display for BoardSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}x{}, 触摸: {}, 功放: {:?}, 麦克风: {:?}, 电池检测: {}, 帧缓冲: {:?}, 灯环: {})",
+            self.name,
+            self.display_width,
+            self.display_height,
+            if self.has_touch { "有" } else { "无" },
+            self.amplifier,
+            self.microphone,
+            match self.battery {
+                Some(battery) => format!("GPIO{} x{}", battery.adc_gpio, battery.divider),
+                None => "无".to_string(),
+            },
+            self.framebuffer_depth,
+            match self.status_ring_leds {
+                Some(count) => format!("{}颗", count),
+                None => "无".to_string(),
+            },
+        )
+    }
+}
@@ -0,0 +1,66 @@
+//! 板级引脚分配
+
+use esp_idf_hal::gpio::{AnyIOPin, AnyInputPin, AnyOutputPin, Pins};
+
+use super::current;
+
+/// I2C总线引脚
+pub struct I2cPins {
+    pub sda: AnyIOPin,
+    pub scl: AnyIOPin,
+}
+
+/// 麦克风引脚
+pub struct MicrophonePins {
+    /// 字时钟，PDM麦克风接在这里作为PDM时钟
+    pub ws: AnyIOPin,
+    /// 串行时钟，PDM麦克风没有该引脚
+    pub sck: Option<AnyIOPin>,
+    /// 串行数据
+    pub sd: AnyInputPin,
+}
+
+/// I2S功放引脚
+pub struct SpeakerPins {
+    /// 位时钟
+    pub bclk: AnyIOPin,
+    /// 数据输出
+    pub dout: AnyOutputPin,
+    /// 字时钟
+    pub ws: AnyIOPin,
+}
+
+/// SD卡（SDMMC 1-bit）引脚
+pub struct SdCardPins {
+    pub clk: AnyOutputPin,
+    pub cmd: AnyIOPin,
+    pub d0: AnyIOPin,
+}
+
+/// LCD控制引脚（QSPI数据线由LCD驱动直接配置）
+pub struct LcdPins {
+    /// 背光
+    pub backlight: AnyOutputPin,
+    /// TE信号输入
+    pub te: AnyInputPin,
+}
+
+/// 整板引脚分配
+pub struct BoardPins {
+    pub i2c: I2cPins,
+    pub microphone: MicrophonePins,
+    pub speaker: SpeakerPins,
+    pub sd_card: SdCardPins,
+    pub lcd: LcdPins,
+    /// BOOT按键
+    pub boot_button: AnyIOPin,
+    /// 状态灯环数据引脚，没有灯环时为None
+    pub status_ring: Option<AnyOutputPin>,
+}
+
+impl BoardPins {
+    /// 按当前板子从芯片引脚中取出使用的引脚
+    pub fn take(pins: Pins) -> Self {
+        current::pins(pins)
+    }
+}
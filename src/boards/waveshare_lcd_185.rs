@@ -0,0 +1,68 @@
+//! 微雪 ESP32-S3-Touch-LCD-1.85 引脚分配
+
+#[cfg(target_os = "espidf")]
+use esp_idf_hal::gpio::{IOPin, InputPin, OutputPin, Pins};
+
+use super::{Amplifier, BatterySense, BoardSpec, MicInterface};
+#[cfg(target_os = "espidf")]
+use super::{BoardPins, I2cPins, LcdPins, MicrophonePins, SdCardPins, SpeakerPins};
+use crate::graphics::framebuffer::ColorDepth;
+#[cfg(target_os = "espidf")]
+use crate::peripherals::st77916::lcd::LcdConfig;
+
+pub const SPEC: BoardSpec = BoardSpec {
+    name: "Waveshare ESP32-S3-Touch-LCD-1.85",
+    display_width: 360,
+    display_height: 360,
+    has_touch: true,
+    amplifier: Amplifier::Pcm5101,
+    microphone: MicInterface::I2sStd,
+    battery: Some(BatterySense {
+        adc_gpio: 8,
+        divider: 3.0,
+    }),
+    framebuffer_depth: ColorDepth::Rgb565,
+    #[cfg(target_os = "espidf")]
+    lcd: LcdConfig::DEFAULT,
+    // 灯环是外接配件，通过`status-ring`特性启用
+    #[cfg(feature = "status-ring")]
+    status_ring_leds: Some(16),
+    #[cfg(not(feature = "status-ring"))]
+    status_ring_leds: None,
+};
+
+/// 从芯片引脚中取出本板使用的引脚
+#[cfg(target_os = "espidf")]
+pub fn pins(pins: Pins) -> BoardPins {
+    BoardPins {
+        i2c: I2cPins {
+            sda: pins.gpio11.downgrade(),
+            scl: pins.gpio10.downgrade(),
+        },
+        microphone: MicrophonePins {
+            ws: pins.gpio2.downgrade(),
+            sck: Some(pins.gpio15.downgrade()),
+            sd: pins.gpio39.downgrade_input(),
+        },
+        speaker: SpeakerPins {
+            bclk: pins.gpio48.downgrade(),
+            dout: pins.gpio47.downgrade_output(),
+            ws: pins.gpio38.downgrade(),
+        },
+        sd_card: SdCardPins {
+            clk: pins.gpio14.downgrade_output(),
+            cmd: pins.gpio17.downgrade(),
+            d0: pins.gpio16.downgrade(),
+        },
+        lcd: LcdPins {
+            backlight: pins.gpio5.downgrade_output(),
+            te: pins.gpio18.downgrade_input(),
+        },
+        boot_button: pins.gpio0.downgrade(),
+        // 灯环数据线接在扩展排针的GPIO3
+        #[cfg(feature = "status-ring")]
+        status_ring: Some(pins.gpio3.downgrade_output()),
+        #[cfg(not(feature = "status-ring"))]
+        status_ring: None,
+    }
+}
@@ -0,0 +1,127 @@
+// src/clock.rs
+//! 墙上时钟
+//!
+//! WiFi连接后通过SNTP同步系统时间，未同步前`now()`返回None，
+//! 界面据此决定是否显示时间。设备固定使用北京时间（UTC+8）。
+//!
+//! 装有实时时钟芯片时，启动时先用芯片中的时间设置系统时间，没有WiFi也能显示时间、
+//! 触发闹钟；之后每次SNTP同步完成都把系统时间写回芯片。
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// SNTP与实时时钟同步依赖ESP-IDF，`LocalTime`的换算在主机上也可以测试
+#[cfg(target_os = "espidf")]
+mod sync;
+#[cfg(target_os = "espidf")]
+pub use sync::{restore_from_rtc, save_to_rtc_if_synced, start_sntp};
+
+/// 本地时区相对UTC的偏移（秒）
+pub const UTC_OFFSET_SECS: i64 = 8 * 3600;
+
+/// 早于该时间（2024-01-01 UTC）的系统时间视为尚未同步
+const MIN_SYNCED_UNIX_SECS: u64 = 1_704_067_200;
+
+/// 本地日期时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalTime {
+    pub year: i32,
+    /// 1-12
+    pub month: u8,
+    /// 1-31
+    pub day: u8,
+    /// 0为周日
+    pub weekday: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl LocalTime {
+    /// 从本地时间的秒数（已加上时区偏移的Unix时间）换算日期时间
+    pub fn from_local_secs(secs: i64) -> Self {
+        let days = secs.div_euclid(86400);
+        let secs_of_day = secs.rem_euclid(86400);
+
+        // 按公历换算年月日（以3月1日为一年的开始，闰日落在年末）
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        Self {
+            year: year as i32,
+            month: month as u8,
+            day: day as u8,
+            // 1970-01-01是周四
+            weekday: (days + 4).rem_euclid(7) as u8,
+            hour: (secs_of_day / 3600) as u8,
+            minute: (secs_of_day % 3600 / 60) as u8,
+            second: (secs_of_day % 60) as u8,
+        }
+    }
+
+    /// 换算回本地时间的秒数，`from_local_secs`的逆运算（忽略`weekday`）
+    pub fn to_local_secs(&self) -> i64 {
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let month = self.month as i64;
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let mp = if month > 2 { month - 3 } else { month + 9 };
+        let doy = (153 * mp + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146_097 + doe - 719_468;
+
+        days * 86400 + self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64
+    }
+
+    /// 星期的中文名称
+    pub fn weekday_name(&self) -> &'static str {
+        const NAMES: [&str; 7] = ["周日", "周一", "周二", "周三", "周四", "周五", "周六"];
+        NAMES[self.weekday as usize % 7]
+    }
+}
+
+/// 当前本地时间
+///
+/// # 返回值
+/// 系统时间尚未通过SNTP或实时时钟设置时返回None
+pub fn now() -> Option<LocalTime> {
+    let unix = unix_now()?;
+    Some(LocalTime::from_local_secs(unix as i64 + UTC_OFFSET_SECS))
+}
+
+/// 当前Unix时间（秒），系统时间尚未同步时返回None
+fn unix_now() -> Option<u64> {
+    let unix = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    (unix >= MIN_SYNCED_UNIX_SECS).then_some(unix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_time_from_secs() {
+        // 2023-11-14 22:13:20 UTC，周二
+        let time = LocalTime::from_local_secs(1_700_000_000);
+        assert_eq!((time.year, time.month, time.day), (2023, 11, 14));
+        assert_eq!((time.hour, time.minute, time.second), (22, 13, 20));
+        assert_eq!(time.weekday, 2);
+
+        // 闰日
+        let time = LocalTime::from_local_secs(1_709_164_800);
+        assert_eq!((time.year, time.month, time.day), (2024, 2, 29));
+    }
+
+    #[test]
+    fn test_local_time_round_trip() {
+        for secs in [0, 951_782_400, 1_700_000_000, 1_709_164_800, 4_102_444_799] {
+            assert_eq!(LocalTime::from_local_secs(secs).to_local_secs(), secs);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! SNTP时间同步与实时时钟的读写
+
+use anyhow::Result;
+use esp_idf_svc::sntp::{EspSntp, SyncStatus};
+
+use super::{unix_now, LocalTime, MIN_SYNCED_UNIX_SECS, UTC_OFFSET_SECS};
+use crate::hal::RealTimeClock;
+
+/// 用实时时钟芯片中的时间设置系统时间
+///
+/// 芯片掉电丢失时间或读数明显不对时保持系统时间不变，等待SNTP同步。
+pub fn restore_from_rtc(rtc: &mut dyn RealTimeClock) -> Result<()> {
+    let Some(time) = rtc.read_time()? else {
+        log::warn!("实时时钟未设置或曾经掉电，等待SNTP同步");
+        return Ok(());
+    };
+    let unix = time.to_local_secs() - UTC_OFFSET_SECS;
+    if unix < MIN_SYNCED_UNIX_SECS as i64 {
+        log::warn!("实时时钟时间无效: {:?}", time);
+        return Ok(());
+    }
+
+    let tv = esp_idf_sys::timeval {
+        tv_sec: unix as _,
+        tv_usec: 0,
+    };
+    if unsafe { esp_idf_sys::settimeofday(&tv, std::ptr::null()) } != 0 {
+        anyhow::bail!("设置系统时间失败");
+    }
+    log::info!(
+        "已从实时时钟恢复时间: {}-{:02}-{:02} {:02}:{:02}:{:02}",
+        time.year,
+        time.month,
+        time.day,
+        time.hour,
+        time.minute,
+        time.second
+    );
+    Ok(())
+}
+
+/// SNTP完成一次同步后把系统时间写入实时时钟
+///
+/// SNTP的同步状态读取一次后即复位，每次同步只会写入一次。
+///
+/// # 返回值
+/// 本次是否写入了实时时钟
+pub fn save_to_rtc_if_synced(sntp: &EspSntp<'static>, rtc: &mut dyn RealTimeClock) -> Result<bool> {
+    if sntp.get_sync_status() != SyncStatus::Completed {
+        return Ok(false);
+    }
+    let Some(unix) = unix_now() else {
+        return Ok(false);
+    };
+    rtc.set_time(&LocalTime::from_local_secs(unix as i64 + UTC_OFFSET_SECS))?;
+    log::info!("SNTP同步完成，已写入实时时钟");
+    Ok(true)
+}
+
+/// 启动SNTP时间同步
+///
+/// 返回的句柄需要一直持有，释放后停止同步。
+pub fn start_sntp() -> Result<EspSntp<'static>> {
+    let sntp = EspSntp::new_default()?;
+    log::info!("SNTP时间同步已启动");
+    Ok(sntp)
+}
@@ -0,0 +1,172 @@
+// src/config.rs
+//! 设备配置
+//!
+//! 用户可调整的设置统一保存在NVS中（JSON格式），重启后保持。
+//! 新增字段需提供默认值，保证旧版本保存的配置仍能正常加载。
+
+use anyhow::Result;
+use esp_idf_svc::nvs::{EspDefaultNvsPartition, EspNvs, NvsDefault};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    actors::wakeword::WakeWordConfig,
+    api::{
+        endpoints::EndpointConfig, imu_stream::ImuStreamConfig, persona::Persona,
+        weather::WeatherConfig,
+    },
+    app::{frame_pacing::DEFAULT_UI_FPS, kids::KidsModeConfig},
+    graphics::{burnin::BurnInConfig, theme::ThemeConfig},
+    peripherals::{
+        backlight::DEFAULT_BRIGHTNESS, microphone::dsp::MicDspSettings,
+        qmi8658::motion_detector::MotionThresholds,
+    },
+};
+
+/// NVS命名空间
+const CONFIG_NAMESPACE: &str = "config";
+/// 配置JSON在NVS中的键
+const CONFIG_KEY: &str = "device";
+
+/// 设备配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviceConfig {
+    /// 音量（0-100）
+    pub volume: u8,
+    /// 是否静音
+    pub muted: bool,
+    /// 免打扰：忽略唤醒词、扬声器静音、灯环调暗
+    pub do_not_disturb: bool,
+    /// 首选对话模型，None表示使用服务端默认模型
+    pub model: Option<String>,
+    /// 对话角色（儿童模式开启时不生效）
+    pub persona: Persona,
+    /// 儿童模式：限制每天的互动时长，角色固定为儿童角色
+    pub kids_mode: KidsModeConfig,
+    /// 界面主题
+    pub theme: ThemeConfig,
+    /// 防烧屏：静态画面的像素位移与定时扫屏
+    pub burn_in: BurnInConfig,
+    /// 天气服务，未配置API密钥时不显示天气
+    pub weather: WeatherConfig,
+    /// 运动检测阈值（可自动校准）
+    pub motion: MotionThresholds,
+    /// 调试用IMU原始数据流
+    pub imu_stream: ImuStreamConfig,
+    /// 录音使用AFE降噪后的音频，修改后下次启动采集时生效
+    pub noise_suppression: bool,
+    /// 麦克风增益与噪声门，启动时创建麦克风后应用
+    pub mic_dsp: MicDspSettings,
+    /// 唤醒词模型与检测阈值，修改后立即生效
+    pub wake_word: WakeWordConfig,
+    /// 界面目标帧率（5-30）
+    pub ui_fps: u8,
+    /// 待机时无人（没有动作、按键、唤醒词和明显声音）多少分钟后关闭屏幕，0表示不关闭
+    pub display_sleep_minutes: u16,
+    /// 手动设置的背光亮度（5-100），关闭自动亮度或没有环境光传感器时使用
+    pub brightness: u8,
+    /// 按环境光自动调节亮度（需要环境光传感器）
+    pub auto_brightness: bool,
+    /// 服务器地址，未修改时使用编译时的默认地址
+    pub endpoints: EndpointConfig,
+    /// 语音导航：朗读高亮的列表项（需要存储中的语音片段）
+    pub voice_guide: bool,
+}
+
+impl Default for DeviceConfig {
+    fn default() -> Self {
+        Self {
+            volume: 60,
+            muted: false,
+            do_not_disturb: false,
+            model: None,
+            persona: Persona::default(),
+            kids_mode: KidsModeConfig::default(),
+            theme: ThemeConfig::default(),
+            burn_in: BurnInConfig::default(),
+            weather: WeatherConfig::default(),
+            motion: MotionThresholds::default(),
+            imu_stream: ImuStreamConfig::default(),
+            noise_suppression: false,
+            mic_dsp: MicDspSettings::default(),
+            wake_word: WakeWordConfig::default(),
+            ui_fps: DEFAULT_UI_FPS,
+            display_sleep_minutes: 10,
+            brightness: DEFAULT_BRIGHTNESS,
+            auto_brightness: true,
+            endpoints: EndpointConfig::default(),
+            voice_guide: false,
+        }
+    }
+}
+
+impl DeviceConfig {
+    /// 实际使用的对话角色，儿童模式下固定为儿童角色
+    pub fn active_persona(&self) -> Persona {
+        if self.kids_mode.enabled {
+            Persona::Kids
+        } else {
+            self.persona
+        }
+    }
+}
+
+/// 设备配置存储
+pub struct ConfigStore {
+    nvs: EspNvs<NvsDefault>,
+    config: DeviceConfig,
+}
+
+impl ConfigStore {
+    /// 从NVS加载配置
+    ///
+    /// 没有保存过或解析失败时使用默认配置。
+    ///
+    /// # 参数
+    /// * `partition` - 默认NVS分区
+    pub fn new(partition: EspDefaultNvsPartition) -> Result<Self> {
+        let nvs = EspNvs::new(partition, CONFIG_NAMESPACE, true)?;
+
+        let config = match nvs.str_len(CONFIG_KEY)? {
+            Some(len) => {
+                let mut buf = vec![0u8; len];
+                match nvs.get_str(CONFIG_KEY, &mut buf)? {
+                    Some(json) => serde_json::from_str(json).unwrap_or_else(|e| {
+                        log::warn!("设备配置解析失败，使用默认配置: {}", e);
+                        DeviceConfig::default()
+                    }),
+                    None => DeviceConfig::default(),
+                }
+            }
+            None => DeviceConfig::default(),
+        };
+
+        Ok(Self { nvs, config })
+    }
+
+    /// 获取当前配置
+    pub fn config(&self) -> &DeviceConfig {
+        &self.config
+    }
+
+    /// 修改配置并写回NVS
+    ///
+    /// 配置没有变化时不写入，减少Flash擦写。
+    ///
+    /// # 示例
+    /// ```rust,no_run
+    /// store.update(|config| config.volume = 80)?;
+    /// ```
+    pub fn update(&mut self, f: impl FnOnce(&mut DeviceConfig)) -> Result<()> {
+        let mut config = self.config.clone();
+        f(&mut config);
+        if config == self.config {
+            return Ok(());
+        }
+
+        let json = serde_json::to_string(&config)?;
+        self.nvs.set_str(CONFIG_KEY, &json)?;
+        self.config = config;
+        Ok(())
+    }
+}
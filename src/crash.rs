@@ -0,0 +1,187 @@
+// src/crash.rs
+//! 崩溃记录
+//!
+//! 运行时把当前界面、运行时间和剩余内存写入RTC慢速内存（`.rtc_noinit`段，
+//! 软件复位和看门狗复位后内容保留），panic时额外记录panic信息。
+//! 下次启动时若复位原因为异常复位，由`StatsStore`读取这些信息生成崩溃记录并保存到NVS。
+
+use std::ptr::addr_of_mut;
+
+use esp_idf_sys::{esp_timer_get_time, heap_caps_get_free_size, MALLOC_CAP_8BIT};
+use serde::{Deserialize, Serialize};
+
+/// 最多保存的崩溃记录数量，超出后丢弃最旧的记录
+pub const MAX_CRASH_RECORDS: usize = 8;
+
+/// RTC内存中数据有效的标志
+const BREADCRUMB_MAGIC: u32 = 0xC4A5_11ED;
+
+const STATE_LEN: usize = 32;
+const MESSAGE_LEN: usize = 160;
+
+/// 保存在RTC内存中的运行痕迹
+#[repr(C)]
+struct Breadcrumbs {
+    magic: u32,
+    uptime_secs: u32,
+    free_heap: u32,
+    state_len: u8,
+    state: [u8; STATE_LEN],
+    message_len: u8,
+    message: [u8; MESSAGE_LEN],
+}
+
+#[link_section = ".rtc_noinit"]
+static mut BREADCRUMBS: Breadcrumbs = Breadcrumbs {
+    magic: 0,
+    uptime_secs: 0,
+    free_heap: 0,
+    state_len: 0,
+    state: [0; STATE_LEN],
+    message_len: 0,
+    message: [0; MESSAGE_LEN],
+};
+
+/// 一次崩溃的记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrashRecord {
+    /// 复位原因，例如"panic"、"task_wdt"
+    pub reason: String,
+    /// 崩溃前的剩余内存（字节）
+    pub free_heap: u32,
+    /// 崩溃前已运行时间（秒）
+    pub uptime_secs: u32,
+    /// 崩溃前所在的界面
+    pub app_state: String,
+    /// panic信息，其他原因的复位为空
+    #[serde(default)]
+    pub message: String,
+}
+
+/// 把记录加入历史，超出`MAX_CRASH_RECORDS`时丢弃最旧的记录
+pub fn push_record(history: &mut Vec<CrashRecord>, record: CrashRecord) {
+    history.push(record);
+    if history.len() > MAX_CRASH_RECORDS {
+        let excess = history.len() - MAX_CRASH_RECORDS;
+        history.drain(..excess);
+    }
+}
+
+/// 截断到不超过`max`字节的字符边界处
+fn truncate_utf8(text: &str, max: usize) -> &str {
+    let mut end = text.len().min(max);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// 访问RTC内存中的运行痕迹，首次访问时初始化
+fn with_breadcrumbs<T>(f: impl FnOnce(&mut Breadcrumbs) -> T) -> T {
+    // 只有主线程写入界面与运行时间，panic钩子写入时该线程已不会继续运行
+    let crumbs = unsafe { &mut *addr_of_mut!(BREADCRUMBS) };
+    if crumbs.magic != BREADCRUMB_MAGIC {
+        crumbs.magic = BREADCRUMB_MAGIC;
+        crumbs.state_len = 0;
+        crumbs.message_len = 0;
+    }
+    f(crumbs)
+}
+
+/// 更新运行时间与剩余内存
+fn record_vitals(crumbs: &mut Breadcrumbs) {
+    crumbs.uptime_secs = (unsafe { esp_timer_get_time() } / 1_000_000) as u32;
+    crumbs.free_heap = unsafe { heap_caps_get_free_size(MALLOC_CAP_8BIT) } as u32;
+}
+
+/// 安装panic钩子，panic时记录信息后交给默认钩子打印
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info.to_string();
+        with_breadcrumbs(|crumbs| {
+            record_vitals(crumbs);
+            let message = truncate_utf8(&message, MESSAGE_LEN);
+            crumbs.message[..message.len()].copy_from_slice(message.as_bytes());
+            crumbs.message_len = message.len() as u8;
+        });
+        default_hook(info);
+    }));
+}
+
+/// 记录当前界面，界面切换时调用
+pub fn set_app_state(state: &str) {
+    let state = truncate_utf8(state, STATE_LEN);
+    with_breadcrumbs(|crumbs| {
+        crumbs.state[..state.len()].copy_from_slice(state.as_bytes());
+        crumbs.state_len = state.len() as u8;
+    });
+}
+
+/// 定期调用，更新运行时间与剩余内存
+pub fn heartbeat() {
+    with_breadcrumbs(record_vitals);
+}
+
+/// 读取上次运行留下的痕迹生成崩溃记录，并清空痕迹
+///
+/// 启动时调用，只应在复位原因为异常复位时使用返回的记录。
+///
+/// # 参数
+/// * `reason` - 复位原因
+pub fn take_record(reason: &str) -> CrashRecord {
+    let crumbs = unsafe { &mut *addr_of_mut!(BREADCRUMBS) };
+    let valid = crumbs.magic == BREADCRUMB_MAGIC;
+    let text = |bytes: &[u8], len: u8| {
+        let len = (len as usize).min(bytes.len());
+        String::from_utf8_lossy(&bytes[..len]).into_owned()
+    };
+
+    let record = CrashRecord {
+        reason: reason.to_string(),
+        free_heap: if valid { crumbs.free_heap } else { 0 },
+        uptime_secs: if valid { crumbs.uptime_secs } else { 0 },
+        app_state: if valid {
+            text(&crumbs.state, crumbs.state_len)
+        } else {
+            String::new()
+        },
+        message: if valid {
+            text(&crumbs.message, crumbs.message_len)
+        } else {
+            String::new()
+        },
+    };
+
+    // 清空痕迹，保留界面由本次运行重新写入
+    crumbs.magic = 0;
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_is_capped_and_truncation_keeps_chars() {
+        let mut history = Vec::new();
+        for uptime_secs in 0..10 {
+            push_record(
+                &mut history,
+                CrashRecord {
+                    reason: "panic".to_string(),
+                    free_heap: 0,
+                    uptime_secs,
+                    app_state: String::new(),
+                    message: String::new(),
+                },
+            );
+        }
+        assert_eq!(history.len(), MAX_CRASH_RECORDS);
+        assert_eq!(history[0].uptime_secs, 2);
+
+        // "思考"每个字3字节，截断到4字节只保留第一个字
+        assert_eq!(truncate_utf8("思考", 4), "思");
+        assert_eq!(truncate_utf8("Main", 32), "Main");
+    }
+}
@@ -1,14 +1,74 @@
+use std::time::Duration;
+
 use anyhow::Result;
 
 use crate::{
+    actors::{motion::CALIBRATION_DURATION, wakeword::WakeWordConfig},
+    api::{
+        persona::Persona,
+        request::CHAT_REQUEST_TIMEOUT,
+        types::{ChatStage, ModelInfo},
+        weather::Weather,
+    },
+    app::{
+        frame_pacing::{FrameStats, DEFAULT_UI_FPS},
+        kids::KidsModeConfig,
+        selftest::SelfTestReport,
+    },
+    clock, crash,
     graphics::{
-        colors::BLACK,
+        animation::EspInstant,
+        burnin::{BurnInAction, BurnInConfig, BurnInGuard, SWEEP_BAND_WIDTH},
+        layout::{scaled, ScreenRect, SCREEN_HEIGHT, SCREEN_WIDTH},
         primitives::GraphicsPrimitives,
-        screens::{dizziness, error, home, settings, thinking, tilting, welcome},
+        screens::{
+            about::{self, AboutInfo},
+            alarm, calibration,
+            conversation::ConversationView,
+            dizziness,
+            endpoint::{self, EndpointEditView},
+            error, home,
+            intercom::{self, IntercomView},
+            kids,
+            listening::{self, LevelMeter},
+            models, ouch, pairing, reply, selftest,
+            settings::{
+                self, BrightnessSetting, LogUploadStatus, SettingAction, SettingsMenu,
+                SettingsValues,
+            },
+            spectrum::{self, SpectrumView},
+            standby::{StandbyFace, StandbyInfo},
+            stats, test_pattern, thinking, tilting, volume, welcome,
+        },
+        theme::{self, ThemeConfig},
+        ui::{
+            progress_ring::ProgressRing, qrcode::QrCode, statusbar::StatusBar,
+            traits::CachedUIComponent,
+        },
     },
-    peripherals::qmi8658::motion_detector::MotionState,
+    peripherals::{
+        battery::BatteryLevel,
+        qmi8658::motion_detector::{MotionState, MotionThresholds},
+        speaker::volume::Volume,
+        st77916::orientation::DisplayOrientation,
+        storage::StorageSpace,
+    },
+    stats::ReliabilityStats,
 };
 
+/// 错误界面自动返回欢迎界面的时间
+const ERROR_TIMEOUT: Duration = Duration::from_millis(7500);
+/// 可重试的错误停留更久，留出按键重试的时间
+const ERROR_RETRY_TIMEOUT: Duration = Duration::from_secs(30);
+/// 停止调节音量后返回主界面的时间
+const VOLUME_TIMEOUT: Duration = Duration::from_secs(2);
+/// 跌落表情的显示时间
+const OUCH_DURATION: Duration = Duration::from_secs(3);
+/// 回复界面自动返回主界面的时间
+const REPLY_TIMEOUT: Duration = Duration::from_secs(30);
+/// 晃动状态的最短持续时间，避免过于频繁的状态切换
+const MIN_DIZZINESS_DURATION: Duration = Duration::from_secs(3);
+
 /// 应用状态枚举
 #[derive(Debug, Clone, PartialEq)]
 pub enum DisplayState {
@@ -16,12 +76,37 @@ pub enum DisplayState {
     Welcome,
     /// 主界面
     Main,
+    /// 对话界面，会话中有消息时代替空白的主界面
+    Conversation,
     /// 设置界面
     Settings,
+    /// 运行统计界面
+    Stats,
+    /// 关于界面：固件版本与设备信息
+    About,
+    /// 局域网对讲
+    Intercom,
+    /// 麦克风频谱
+    Spectrum,
+    /// 修改服务器地址
+    EndpointEdit,
+    /// 模型选择界面
+    ModelSelect,
+    /// 调节音量时短暂显示的音量界面
+    Volume,
+
+    /// 按键说话录音中
+    Listening,
+
+    /// 显示配网或配对二维码，附带说明文字
+    Pairing(String),
 
     /// 思考中状态可以用于模拟AI处理请求的过程
     Thinking,
 
+    /// 显示助手的回复
+    Reply(String),
+
     /// 当设备被摇晃时
     Dizziness,
 
@@ -30,6 +115,43 @@ pub enum DisplayState {
 
     /// 错误界面
     Error(String),
+
+    /// 一段时间无操作后显示的待机表盘
+    Standby,
+
+    /// 闹钟或倒计时到期的全屏提醒，附带标签
+    Alarm(String),
+
+    /// 运动阈值自动校准中
+    Calibrating,
+
+    /// 跌落后短暂显示的"好痛"表情
+    Ouch,
+
+    /// 硬件自检
+    SelfTest,
+
+    /// 屏幕测试图：三原色与灰阶
+    TestPattern,
+
+    /// 儿童模式当天时间用完，显示"休息一下"
+    Break,
+
+    /// 儿童模式下进入设置前的家长手势验证
+    KidsUnlock,
+}
+
+impl DisplayState {
+    /// 是否为长时间停留的静态画面（需要防烧屏处理）
+    pub fn is_static(&self) -> bool {
+        matches!(
+            self,
+            DisplayState::Welcome
+                | DisplayState::Main
+                | DisplayState::Conversation
+                | DisplayState::Standby
+        )
+    }
 }
 
 /// 主应用结构
@@ -38,10 +160,94 @@ pub struct Display<'a> {
     state: DisplayState,
     /// 图形绘制接口
     graphics: GraphicsPrimitives<'a>,
-    /// 状态切换计时器（用于自动切换）
-    state_timer: u32,
-    /// 晃动状态开始时间
-    dizziness_start_time: u32,
+    /// 进入当前状态的时间（用于自动切换与动画）
+    state_since: EspInstant,
+    /// 顶部状态栏
+    status_bar: StatusBar,
+    /// 防烧屏调度器
+    burn_in: BurnInGuard,
+    /// 运行统计界面显示的数据
+    reliability_stats: ReliabilityStats,
+    /// 运行统计界面显示的界面帧率
+    frame_stats: FrameStats,
+    /// 运行统计界面显示的存储空间
+    storage_spaces: Vec<StorageSpace>,
+    /// 关于界面显示的设备信息
+    about: AboutInfo,
+    /// 对讲界面显示的内容
+    intercom: IntercomView,
+    /// 地址输入界面显示的内容
+    endpoint_edit: EndpointEditView,
+    /// 设置界面显示的音量
+    volume: Volume,
+    /// 当前错误是否可以重试
+    retry_available: bool,
+    /// 可用模型列表，None表示正在加载
+    models: Option<Vec<ModelInfo>>,
+    /// 模型选择界面高亮的列表项（0为默认模型）
+    model_index: usize,
+    /// 正在使用的模型ID
+    current_model: Option<String>,
+    /// 设置界面显示的对话角色
+    persona: Persona,
+    /// 服务端推送的当前处理阶段
+    chat_stage: Option<ChatStage>,
+    /// 进入思考界面的时间，用于显示已等待时长
+    thinking_since: EspInstant,
+    /// 语音上传进度，None表示没有上传（文字提问）
+    upload_progress: Option<u8>,
+    /// 思考界面边缘的进度环：上传语音时显示上传进度，之后显示请求已用时间占超时的比例
+    progress_ring: ProgressRing,
+    /// 配对界面显示的二维码
+    pairing_qr: Option<QrCode>,
+    /// 设置界面显示的主题选择
+    theme_config: ThemeConfig,
+    /// 待机表盘（记录已绘制的内容，只重绘变化部分）
+    standby_face: StandbyFace,
+    /// 待机表盘显示的WiFi信号格数
+    wifi_level: Option<u8>,
+    /// 待机表盘显示的电池电量
+    battery: Option<BatteryLevel>,
+    /// 待机表盘显示的天气
+    weather: Option<Weather>,
+    /// 聆听界面的麦克风电平波形
+    level_meter: LevelMeter,
+    /// 频谱界面的环形频谱
+    spectrum: SpectrumView,
+    /// 设置界面显示的运动检测阈值
+    motion_thresholds: MotionThresholds,
+    /// 待机表盘显示的今日步数
+    steps: Option<u32>,
+    /// 自检界面显示的进度与结果
+    self_test: SelfTestReport,
+    /// 当前会话的对话记录
+    conversation: ConversationView,
+    /// 免打扰是否开启
+    do_not_disturb: bool,
+    /// 语音导航是否开启
+    voice_guide: bool,
+    /// 儿童模式配置
+    kids_mode: KidsModeConfig,
+    /// 家长验证界面已输入的手势步数
+    unlock_entered: usize,
+    /// 设置界面显示的唤醒词设置
+    wake_word: WakeWordConfig,
+    /// 设置界面中可选的唤醒词模型
+    wake_word_models: Vec<String>,
+    /// 屏幕是否已关闭（无人时休眠）
+    asleep: bool,
+    /// 设置界面显示的亮度设置
+    brightness_setting: BrightnessSetting,
+    /// 设置界面的多页菜单，进入设置界面时重新生成
+    settings_menu: SettingsMenu,
+    /// 设置界面显示的上传日志进度
+    log_upload: LogUploadStatus,
+    /// 麦克风调试录音是否正在进行
+    debug_recording: bool,
+    /// 设置界面显示的界面目标帧率
+    ui_fps: u8,
+    /// 设置界面显示的无人关屏等待时间（分钟）
+    sleep_minutes: u16,
 }
 
 impl<'a> Display<'a> {
@@ -50,36 +256,278 @@ impl<'a> Display<'a> {
         Display {
             state: DisplayState::Main,
             graphics,
-            state_timer: 0,
-            dizziness_start_time: 0,
+            state_since: EspInstant::now(),
+            status_bar: StatusBar::default(),
+            burn_in: BurnInGuard::new(BurnInConfig::default()),
+            reliability_stats: ReliabilityStats::default(),
+            frame_stats: FrameStats::default(),
+            storage_spaces: Vec::new(),
+            about: AboutInfo::default(),
+            intercom: IntercomView::default(),
+            endpoint_edit: EndpointEditView::default(),
+            volume: Volume::default(),
+            retry_available: false,
+            models: None,
+            model_index: 0,
+            current_model: None,
+            persona: Persona::default(),
+            chat_stage: None,
+            thinking_since: EspInstant::now(),
+            upload_progress: None,
+            progress_ring: ProgressRing::new(),
+            pairing_qr: None,
+            theme_config: ThemeConfig::default(),
+            standby_face: StandbyFace::default(),
+            wifi_level: None,
+            battery: None,
+            weather: None,
+            level_meter: LevelMeter::default(),
+            spectrum: SpectrumView::default(),
+            motion_thresholds: MotionThresholds::default(),
+            steps: None,
+            self_test: SelfTestReport::default(),
+            conversation: ConversationView::default(),
+            do_not_disturb: false,
+            voice_guide: false,
+            kids_mode: KidsModeConfig::default(),
+            unlock_entered: 0,
+            wake_word: WakeWordConfig::default(),
+            wake_word_models: Vec::new(),
+            asleep: false,
+            brightness_setting: BrightnessSetting::default(),
+            settings_menu: SettingsMenu::default(),
+            log_upload: LogUploadStatus::default(),
+            debug_recording: false,
+            ui_fps: DEFAULT_UI_FPS,
+            sleep_minutes: 0,
         }
     }
 
-    /// 主更新循环
+    /// 主更新循环，屏幕关闭时不绘制
     pub fn update(&mut self) -> Result<()> {
-        // 增加计时器
-        self.state_timer += 1;
+        if self.asleep {
+            return Ok(());
+        }
+        let elapsed = self.state_since.elapsed();
 
         // 根据当前状态执行相应逻辑
         match &self.state {
             DisplayState::Welcome => welcome::draw(&mut self.graphics)?,
-            DisplayState::Main => home::draw(&mut self.graphics)?,
-            DisplayState::Settings => settings::draw(&mut self.graphics)?,
+            DisplayState::Main => {
+                home::draw(&mut self.graphics)?;
+                self.graphics.draw_component(&self.status_bar)?;
+            }
+            DisplayState::Conversation => {
+                self.conversation.draw(&mut self.graphics)?;
+                self.graphics.draw_component(&self.status_bar)?;
+            }
+            DisplayState::Settings => settings::draw(&mut self.graphics, &self.settings_menu)?,
+            DisplayState::ModelSelect => models::draw(
+                &mut self.graphics,
+                self.models.as_deref(),
+                self.model_index,
+                self.current_model.as_deref(),
+            )?,
+            DisplayState::Stats => stats::draw(
+                &mut self.graphics,
+                &self.reliability_stats,
+                &self.storage_spaces,
+                &self.frame_stats,
+            )?,
+            DisplayState::About => about::draw(&mut self.graphics, &self.about)?,
+            DisplayState::Intercom => intercom::draw(&mut self.graphics, &self.intercom)?,
+            DisplayState::Spectrum => spectrum::draw(&mut self.graphics, &mut self.spectrum)?,
+            DisplayState::EndpointEdit => endpoint::draw(&mut self.graphics, &self.endpoint_edit)?,
             DisplayState::Error(msg) => {
-                error::draw(&mut self.graphics, msg)?;
-                // 3秒后自动返回欢迎界面
-                if self.state_timer > 150 {
+                error::draw(&mut self.graphics, msg, self.retry_available)?;
+                let timeout = if self.retry_available {
+                    ERROR_RETRY_TIMEOUT
+                } else {
+                    ERROR_TIMEOUT
+                };
+                if elapsed > timeout {
                     self.enter_welcome()?;
                 }
             }
-            DisplayState::Thinking => thinking::draw(&mut self.graphics, self.state_timer)?,
-            DisplayState::Dizziness => dizziness::draw(&mut self.graphics, self.state_timer)?,
+            DisplayState::Volume => {
+                volume::draw(&mut self.graphics, &self.volume)?;
+                if elapsed > VOLUME_TIMEOUT {
+                    self.enter_main()?;
+                }
+            }
+            DisplayState::SelfTest => selftest::draw(&mut self.graphics, &self.self_test)?,
+            DisplayState::TestPattern => test_pattern::draw(&mut self.graphics)?,
+            DisplayState::Ouch => {
+                ouch::draw(&mut self.graphics, elapsed)?;
+                if elapsed > OUCH_DURATION {
+                    self.enter_main()?;
+                }
+            }
+            DisplayState::Listening => {
+                listening::draw(&mut self.graphics, elapsed, &mut self.level_meter)?
+            }
+            DisplayState::Pairing(caption) => {
+                if let Some(qr) = &self.pairing_qr {
+                    pairing::draw(&mut self.graphics, qr, caption)?;
+                }
+            }
+            DisplayState::Reply(text) => {
+                reply::draw(&mut self.graphics, text)?;
+                if elapsed > REPLY_TIMEOUT {
+                    self.enter_main()?;
+                }
+            }
+            DisplayState::Thinking => {
+                let waited = self.thinking_since.elapsed();
+                let uploading = self.upload_progress.filter(|percent| *percent < 100);
+                thinking::draw(
+                    &mut self.graphics,
+                    self.current_model.as_deref(),
+                    self.chat_stage,
+                    uploading.is_some(),
+                    waited,
+                )?;
+                let percent = uploading.unwrap_or_else(|| {
+                    (waited.as_millis() * 100 / CHAT_REQUEST_TIMEOUT.as_millis()).min(100) as u8
+                });
+                self.progress_ring.set_percent(percent);
+                self.graphics.draw_component(&self.progress_ring)?;
+            }
+            DisplayState::Dizziness => dizziness::draw(&mut self.graphics, elapsed)?,
             DisplayState::Tilting => tilting::draw(&mut self.graphics)?,
+            DisplayState::Standby => {
+                let info = StandbyInfo {
+                    time: clock::now(),
+                    wifi_level: self.wifi_level,
+                    battery: self.battery,
+                    weather: self.weather.as_ref(),
+                    steps: self.steps,
+                };
+                self.standby_face.draw(&mut self.graphics, &info)?;
+            }
+            DisplayState::Calibrating => {
+                calibration::draw(&mut self.graphics, elapsed, CALIBRATION_DURATION)?
+            }
+            DisplayState::Alarm(label) => {
+                alarm::draw(&mut self.graphics, elapsed, label, clock::now())?
+            }
+            DisplayState::Break => kids::draw_break(
+                &mut self.graphics,
+                elapsed,
+                self.kids_mode.daily_limit_minutes,
+            )?,
+            DisplayState::KidsUnlock => kids::draw_unlock(
+                &mut self.graphics,
+                self.unlock_entered,
+                self.kids_mode.unlock_code.len(),
+            )?,
+        }
+
+        if self.state.is_static() {
+            self.apply_burn_in()?;
+        }
+
+        self.graphics.flush()?;
+
+        Ok(())
+    }
+
+    /// 执行防烧屏动作（像素位移与扫屏）
+    ///
+    /// 在当前画面绘制完成后调用，扫屏条带会覆盖在画面之上。
+    fn apply_burn_in(&mut self) -> Result<()> {
+        match self.burn_in.poll(SCREEN_WIDTH) {
+            BurnInAction::None => {}
+            BurnInAction::Shift(dx, dy) => {
+                // 清屏后以新偏移重绘，避免旧位置残留
+                self.clear_screen()?;
+                self.graphics.set_offset(dx, dy);
+            }
+            BurnInAction::Sweep(x) => {
+                // 擦除上一帧的条带，再绘制新条带
+                let step = self.burn_in.config().sweep_step;
+                let previous = ScreenRect::new(x - step, 0, step, SCREEN_HEIGHT);
+                self.graphics
+                    .fill_rect(&previous, theme::current().background)?;
+                let band = ScreenRect::new(x, 0, SWEEP_BAND_WIDTH, SCREEN_HEIGHT);
+                self.graphics
+                    .fill_rect(&band, theme::current().foreground)?;
+                // 待机表盘与对话界面只重绘变化部分，条带扫过后需要整屏重绘
+                self.standby_face.invalidate();
+                self.conversation.invalidate();
+            }
+            BurnInAction::SweepDone => {
+                self.clear_screen()?;
+            }
         }
 
         Ok(())
     }
 
+    /// 更新防烧屏配置
+    ///
+    /// 禁用时立即恢复零偏移。
+    pub fn set_burn_in_config(&mut self, config: BurnInConfig) -> Result<()> {
+        self.burn_in.set_config(config);
+        let (dx, dy) = self.burn_in.offset();
+        if self.graphics.offset() != (dx, dy) {
+            self.clear_screen()?;
+            self.graphics.set_offset(dx, dy);
+        }
+        Ok(())
+    }
+
+    /// 运行时修改显示方向，并清屏重绘当前界面
+    pub fn set_orientation(&mut self, orientation: DisplayOrientation) -> Result<()> {
+        self.graphics.set_orientation(orientation)?;
+        self.clear_screen()
+    }
+
+    /// 用主题背景色清屏
+    fn clear_screen(&mut self) -> Result<()> {
+        self.standby_face.invalidate();
+        self.conversation.invalidate();
+        self.progress_ring.clear_cache();
+        self.graphics.fill_screen(theme::current().background)
+    }
+
+    /// 切换主题，清屏后下一帧按新配色重绘
+    pub fn set_theme(&mut self, config: ThemeConfig) -> Result<()> {
+        theme::apply(&config);
+        self.theme_config = config;
+        self.status_bar.apply_theme(&theme::current());
+        self.clear_screen()
+    }
+
+    /// 截屏并保存为BMP文件
+    ///
+    /// # 参数
+    /// * `path` - 文件路径
+    pub fn capture_screenshot(&self, path: &str) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        self.graphics.capture_bmp(&mut writer)
+    }
+
+    /// 显示线程是否仍在发送上一帧，界面刷新据此跳帧
+    pub fn is_flush_pending(&self) -> bool {
+        self.graphics.is_flush_pending()
+    }
+
+    /// 取出因显示线程忙而跳过的刷新次数并清零
+    pub fn take_skipped_frames(&self) -> u32 {
+        self.graphics.take_skipped_frames()
+    }
+
+    /// 检查LCD面板是否多次重新初始化仍无法传输，见`GraphicsPrimitives::check_panel`
+    pub fn check_panel(&self) -> Result<()> {
+        self.graphics.check_panel()
+    }
+
+    /// 获取防烧屏配置
+    pub fn burn_in_config(&self) -> &BurnInConfig {
+        self.burn_in.config()
+    }
+
     /// 处理用户输入
     pub fn back(&mut self) -> Result<()> {
         match &self.state {
@@ -98,6 +546,17 @@ impl<'a> Display<'a> {
                 self.enter_main()?;
             }
 
+            // 待机表盘、闹钟提醒：任意按键回到主界面
+            DisplayState::Standby | DisplayState::Alarm(_) | DisplayState::Ouch => {
+                self.enter_main()?;
+            }
+
+            // 家长验证：返回主界面
+            DisplayState::KidsUnlock => {
+                self.enter_main()?;
+            }
+
+            // 设置的子界面由App经过儿童模式检查后返回设置界面（见`is_settings_subscreen`）
             // 其他输入忽略
             _ => {}
         }
@@ -112,15 +571,213 @@ impl<'a> Display<'a> {
             return Ok(());
         }
 
+        crash::set_app_state(&format!("{:?}", new_state));
         self.state = new_state;
-        self.state_timer = 0; // 重置计时器
+        self.state_since = EspInstant::now(); // 重新计时
+        self.retry_available = false;
+
+        // 非静态画面不需要位移，切换时恢复零偏移并重新计时
+        if !self.state.is_static() {
+            self.graphics.set_offset(0, 0);
+            self.burn_in.reset_timers();
+        } else {
+            let (dx, dy) = self.burn_in.offset();
+            self.graphics.set_offset(dx, dy);
+        }
 
         // 清屏准备绘制新状态
-        self.graphics.fill_screen(BLACK)?;
+        self.clear_screen()?;
 
         Ok(())
     }
 
+    /// 更新聆听界面的麦克风电平，每帧调用一次
+    ///
+    /// # 参数
+    /// * `rms` - 最近采集样本的均方根幅度
+    pub fn push_mic_level(&mut self, rms: u16) {
+        self.level_meter.push(rms);
+    }
+
+    /// 更新频谱界面的各频段电平，每帧调用一次
+    ///
+    /// # 参数
+    /// * `levels` - 各频段电平（0-100），低频在前
+    pub fn push_spectrum(&mut self, levels: &[u8]) {
+        self.spectrum.push(levels);
+    }
+
+    /// 更新状态栏中的WiFi信号格数
+    ///
+    /// # 参数
+    /// * `level` - 信号格数（0-4），None表示WiFi未连接
+    pub fn set_wifi_level(&mut self, level: Option<u8>) {
+        self.wifi_level = level;
+        self.status_bar.set_wifi_level(level);
+    }
+
+    /// 更新待机表盘显示的电池电量
+    pub fn set_battery_level(&mut self, level: Option<BatteryLevel>) {
+        self.battery = level;
+    }
+
+    /// 更新待机表盘显示的天气
+    pub fn set_weather(&mut self, weather: Weather) {
+        self.weather = Some(weather);
+    }
+
+    /// 更新运行统计界面显示的数据
+    pub fn set_reliability_stats(&mut self, stats: ReliabilityStats) {
+        self.reliability_stats = stats;
+    }
+
+    /// 更新运行统计界面显示的实际帧率与目标帧率
+    pub fn set_frame_stats(&mut self, stats: FrameStats) {
+        self.frame_stats = stats;
+    }
+
+    /// 更新关于界面显示的设备信息
+    pub fn set_about_info(&mut self, info: AboutInfo) {
+        self.about = info;
+    }
+
+    /// 更新对讲界面，内容变化时清屏重绘
+    pub fn set_intercom_view(&mut self, view: IntercomView) -> Result<()> {
+        if view == self.intercom {
+            return Ok(());
+        }
+        self.intercom = view;
+        if self.state == DisplayState::Intercom {
+            self.clear_screen()?;
+        }
+        Ok(())
+    }
+
+    /// 更新地址输入界面，内容变化时清屏重绘
+    pub fn set_endpoint_edit_view(&mut self, view: EndpointEditView) -> Result<()> {
+        if view == self.endpoint_edit {
+            return Ok(());
+        }
+        self.endpoint_edit = view;
+        if self.state == DisplayState::EndpointEdit {
+            self.clear_screen()?;
+        }
+        Ok(())
+    }
+
+    /// 更新设置界面显示的音量
+    pub fn set_volume(&mut self, volume: Volume) {
+        self.volume = volume;
+        self.refresh_settings_menu();
+    }
+
+    /// 在主界面调节音量时显示音量界面，其他界面只更新数值
+    pub fn show_volume(&mut self) -> Result<()> {
+        match self.state {
+            DisplayState::Main | DisplayState::Conversation => {
+                self.transition_to(DisplayState::Volume)
+            }
+            DisplayState::Volume => {
+                // 持续调节时重新计时
+                self.state_since = EspInstant::now();
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 更新运行统计界面显示的存储空间
+    pub fn set_storage_spaces(&mut self, spaces: Vec<StorageSpace>) {
+        self.storage_spaces = spaces;
+    }
+
+    /// 语音上传跟不上实时速度时状态栏的WiFi图标改用警告色
+    pub fn set_network_degraded(&mut self, degraded: bool) {
+        self.status_bar.set_network_degraded(degraded);
+    }
+
+    /// 更新免打扰状态，状态栏显示月亮图标
+    pub fn set_do_not_disturb(&mut self, enabled: bool) {
+        self.do_not_disturb = enabled;
+        self.status_bar.set_do_not_disturb(enabled);
+        self.refresh_settings_menu();
+    }
+
+    /// 更新设置界面显示的语音导航开关
+    pub fn set_voice_guide(&mut self, enabled: bool) {
+        self.voice_guide = enabled;
+        self.refresh_settings_menu();
+    }
+
+    /// 更新儿童模式配置，设置界面与"休息一下"界面使用
+    pub fn set_kids_mode(&mut self, config: KidsModeConfig) {
+        self.kids_mode = config;
+        self.refresh_settings_menu();
+    }
+
+    /// 更新设置界面显示的唤醒词设置
+    pub fn set_wake_word(&mut self, config: WakeWordConfig) {
+        self.wake_word = config;
+        self.refresh_settings_menu();
+    }
+
+    /// 更新设置界面中可选的唤醒词模型
+    pub fn set_wake_word_models(&mut self, models: Vec<String>) {
+        self.wake_word_models = models;
+        self.refresh_settings_menu();
+    }
+
+    /// 更新设置界面显示的对话角色
+    pub fn set_persona(&mut self, persona: Persona) {
+        self.persona = persona;
+        self.refresh_settings_menu();
+    }
+
+    /// 设置正在使用的模型
+    pub fn set_current_model(&mut self, model: Option<String>) {
+        self.current_model = model;
+    }
+
+    /// 更新模型选择界面的模型列表，并高亮正在使用的模型
+    pub fn set_models(&mut self, models: Vec<ModelInfo>) -> Result<()> {
+        self.model_index = self
+            .current_model
+            .as_ref()
+            .and_then(|current| models.iter().position(|m| &m.id == current))
+            .map_or(0, |i| i + 1);
+        self.models = Some(models);
+        self.redraw_model_select()
+    }
+
+    /// 在模型选择界面移动高亮项
+    ///
+    /// # 参数
+    /// * `delta` - 移动步数，正数向下，负数向上，到达两端后循环
+    ///
+    /// # 返回值
+    /// 新高亮项对应的模型ID，默认模型为`Some(None)`；列表未加载时返回None
+    pub fn move_model_selection(&mut self, delta: i32) -> Result<Option<Option<String>>> {
+        let Some(models) = &self.models else {
+            return Ok(None);
+        };
+        let total = models.len() as i32 + 1;
+        self.model_index = (self.model_index as i32 + delta).rem_euclid(total) as usize;
+        let model = match self.model_index {
+            0 => None,
+            i => Some(models[i - 1].id.clone()),
+        };
+        self.redraw_model_select()?;
+        Ok(Some(model))
+    }
+
+    /// 列表内容变化后清屏，避免残留较长的旧文字
+    fn redraw_model_select(&mut self) -> Result<()> {
+        if self.state == DisplayState::ModelSelect {
+            self.clear_screen()?;
+        }
+        Ok(())
+    }
+
     /// 获取当前状态
     pub fn get_state(&self) -> &DisplayState {
         &self.state
@@ -131,37 +788,382 @@ impl<'a> Display<'a> {
         self.transition_to(DisplayState::Welcome)
     }
 
+    /// 返回主界面，会话中有消息时显示对话界面
     pub fn enter_main(&mut self) -> Result<()> {
-        self.transition_to(DisplayState::Main)
+        if self.conversation.is_empty() {
+            self.transition_to(DisplayState::Main)
+        } else {
+            self.transition_to(DisplayState::Conversation)
+        }
+    }
+
+    /// 把一轮问答加入对话记录
+    ///
+    /// # 参数
+    /// * `prompt` - 用户的提问
+    /// * `reply` - 助手的回复
+    pub fn push_exchange(&mut self, prompt: &str, reply: &str) {
+        self.conversation.push_exchange(prompt, reply);
+    }
+
+    /// 追加流式回复的一段文字
+    ///
+    /// 收到第一段时把提问和空的回复气泡加入对话记录，并从思考界面切换到对话界面，
+    /// 之后每段只重绘回复气泡中变化的行。
+    ///
+    /// # 参数
+    /// * `prompt` - 用户的提问
+    /// * `delta` - 新收到的回复文字
+    pub fn append_reply(&mut self, prompt: &str, delta: &str) -> Result<()> {
+        if !self.conversation.is_streaming() {
+            self.conversation.begin_stream(prompt);
+            if self.state == DisplayState::Thinking {
+                self.transition_to(DisplayState::Conversation)?;
+            }
+        }
+        self.conversation.append_stream(delta);
+        Ok(())
     }
 
+    /// 结束流式回复
+    pub fn end_reply_stream(&mut self) {
+        self.conversation.end_stream();
+    }
+
+    /// 是否正在显示流式回复
+    pub fn is_streaming_reply(&self) -> bool {
+        self.conversation.is_streaming()
+    }
+
+    /// 清空对话记录，正在显示对话界面时回到空白主界面
+    pub fn clear_conversation(&mut self) -> Result<()> {
+        self.conversation.clear();
+        if self.state == DisplayState::Conversation {
+            self.transition_to(DisplayState::Main)?;
+        }
+        Ok(())
+    }
+
+    /// 滚动对话界面
+    ///
+    /// # 参数
+    /// * `delta` - 正数显示更新的消息，负数显示更早的消息
+    pub fn scroll_conversation(&mut self, delta: i32) -> Result<()> {
+        if self.conversation.scroll(delta) && self.state == DisplayState::Conversation {
+            // 气泡位置整体变化，清屏后重绘
+            self.clear_screen()?;
+        }
+        Ok(())
+    }
+
+    /// 进入待机表盘
+    pub fn enter_standby(&mut self) -> Result<()> {
+        self.transition_to(DisplayState::Standby)
+    }
+
+    /// 关闭屏幕（面板与背光），界面状态不变
+    pub fn sleep(&mut self) -> Result<()> {
+        if self.asleep {
+            return Ok(());
+        }
+        self.graphics.set_power(false)?;
+        self.asleep = true;
+        Ok(())
+    }
+
+    /// 打开屏幕
+    ///
+    /// 先按当前状态重绘整屏再打开，避免露出休眠前的旧画面。
+    pub fn wake(&mut self) -> Result<()> {
+        if !self.asleep {
+            return Ok(());
+        }
+        self.asleep = false;
+        self.clear_screen()?;
+        self.update()?;
+        self.graphics.set_power(true)
+    }
+
+    /// 设置背光亮度（0-100），屏幕关闭时在下次打开后生效
+    pub fn set_brightness(&mut self, percent: u8) -> Result<()> {
+        self.graphics.set_brightness(percent)
+    }
+
+    /// 更新设置界面显示的亮度设置
+    pub fn set_brightness_setting(&mut self, setting: BrightnessSetting) {
+        self.brightness_setting = setting;
+        self.refresh_settings_menu();
+    }
+
+    /// 更新设置界面显示的上传日志进度
+    pub fn set_log_upload(&mut self, status: LogUploadStatus) {
+        self.log_upload = status;
+        self.refresh_settings_menu();
+    }
+
+    /// 更新设置界面显示的界面目标帧率
+    pub fn set_ui_fps(&mut self, fps: u8) {
+        self.ui_fps = fps;
+        self.refresh_settings_menu();
+    }
+
+    /// 更新设置界面显示的无人关屏等待时间
+    pub fn set_sleep_minutes(&mut self, minutes: u16) {
+        self.sleep_minutes = minutes;
+        self.refresh_settings_menu();
+    }
+
+    /// 设置界面显示的调试录音状态
+    pub fn debug_recording(&self) -> bool {
+        self.debug_recording
+    }
+
+    /// 更新设置界面显示的调试录音状态，没有变化时不重新生成菜单
+    pub fn set_debug_recording(&mut self, recording: bool) {
+        if self.debug_recording != recording {
+            self.debug_recording = recording;
+            self.refresh_settings_menu();
+        }
+    }
+
+    /// 屏幕是否已关闭
+    pub fn is_asleep(&self) -> bool {
+        self.asleep
+    }
+
+    /// 显示闹钟提醒
+    ///
+    /// # 参数
+    /// * `label` - 提醒界面显示的标签
+    pub fn enter_alarm(&mut self, label: String) -> Result<()> {
+        self.transition_to(DisplayState::Alarm(label))
+    }
+
+    /// 进入运动校准界面
+    pub fn enter_calibrating(&mut self) -> Result<()> {
+        self.transition_to(DisplayState::Calibrating)
+    }
+
+    /// 显示跌落后的"好痛"表情，3秒后自动返回主界面
+    pub fn enter_ouch(&mut self) -> Result<()> {
+        self.transition_to(DisplayState::Ouch)
+    }
+
+    /// 进入硬件自检界面
+    pub fn enter_self_test(&mut self) -> Result<()> {
+        self.transition_to(DisplayState::SelfTest)
+    }
+
+    /// 更新自检界面显示的进度与结果
+    pub fn set_self_test_report(&mut self, report: SelfTestReport) {
+        self.self_test = report;
+    }
+
+    /// 更新待机表盘显示的今日步数
+    pub fn set_steps(&mut self, steps: Option<u32>) {
+        self.steps = steps;
+    }
+
+    /// 更新设置界面显示的运动检测阈值
+    pub fn set_motion_thresholds(&mut self, thresholds: MotionThresholds) {
+        self.motion_thresholds = thresholds;
+        self.refresh_settings_menu();
+    }
+
+    /// 进入儿童模式的"休息一下"界面
+    pub fn enter_break(&mut self) -> Result<()> {
+        self.transition_to(DisplayState::Break)
+    }
+
+    /// 进入家长手势验证界面
+    pub fn enter_kids_unlock(&mut self) -> Result<()> {
+        self.unlock_entered = 0;
+        self.transition_to(DisplayState::KidsUnlock)
+    }
+
+    /// 更新家长验证界面已输入的手势步数
+    pub fn set_unlock_progress(&mut self, entered: usize) {
+        self.unlock_entered = entered;
+    }
+
+    /// 进入设置界面
+    ///
+    /// 从设置的子界面返回时保留焦点所在的页面与设置项，只刷新取值。
     pub fn enter_settings(&mut self) -> Result<()> {
+        if self.is_settings_subscreen() {
+            let values = self.settings_values();
+            self.settings_menu.refresh(&values);
+        } else if self.state != DisplayState::Settings {
+            self.settings_menu = SettingsMenu::new(&self.settings_values());
+        }
         self.transition_to(DisplayState::Settings)
     }
 
+    /// 当前是否为从设置界面打开的子界面（运行统计、关于、对讲、频谱、地址输入、
+    /// 模型选择、自检、测试图、运动校准、二维码），返回时回到设置界面
+    pub fn is_settings_subscreen(&self) -> bool {
+        matches!(
+            self.state,
+            DisplayState::Stats
+                | DisplayState::About
+                | DisplayState::TestPattern
+                | DisplayState::Intercom
+                | DisplayState::Spectrum
+                | DisplayState::EndpointEdit
+                | DisplayState::ModelSelect
+                | DisplayState::SelfTest
+                | DisplayState::Calibrating
+                | DisplayState::Pairing(_)
+        )
+    }
+
+    /// 设置界面中旋转：编辑时调节取值，否则移动焦点，翻页时清屏
+    ///
+    /// # 返回值
+    /// 取值变化时返回App要执行的操作
+    pub fn settings_rotate(&mut self, delta: i32) -> Result<Option<SettingAction>> {
+        let page = self.settings_menu.page();
+        let action = self.settings_menu.rotate(delta);
+        if self.settings_menu.page() != page {
+            self.clear_screen()?;
+        }
+        Ok(action)
+    }
+
+    /// 设置界面中单击获得焦点的控件
+    pub fn settings_activate(&mut self) -> Option<SettingAction> {
+        self.settings_menu.activate()
+    }
+
+    /// 设置界面中返回：正在编辑时结束编辑
+    ///
+    /// # 返回值
+    /// 是否处理了返回，false时按其他界面的返回处理
+    pub fn settings_back(&mut self) -> bool {
+        self.settings_menu.back()
+    }
+
+    /// 生成设置菜单所需的当前设置
+    fn settings_values(&self) -> SettingsValues {
+        SettingsValues {
+            volume: self.volume,
+            do_not_disturb: self.do_not_disturb,
+            voice_guide: self.voice_guide,
+            brightness: self.brightness_setting,
+            motion: self.motion_thresholds,
+            wake_word: self.wake_word.clone(),
+            wake_word_models: self.wake_word_models.clone(),
+            persona: self.persona,
+            model: self.current_model.clone(),
+            theme_config: self.theme_config,
+            kids_mode: self.kids_mode.clone(),
+            burn_in_enabled: self.burn_in.config().enabled,
+            log_upload: self.log_upload,
+            debug_recording: self.debug_recording,
+            ui_fps: self.ui_fps,
+            sleep_minutes: self.sleep_minutes,
+        }
+    }
+
+    /// 设置界面中设置变化后同步到菜单
+    fn refresh_settings_menu(&mut self) {
+        if self.state == DisplayState::Settings {
+            let values = self.settings_values();
+            self.settings_menu.refresh(&values);
+        }
+    }
+
+    pub fn enter_stats(&mut self) -> Result<()> {
+        self.transition_to(DisplayState::Stats)
+    }
+
+    pub fn enter_about(&mut self) -> Result<()> {
+        self.transition_to(DisplayState::About)
+    }
+
+    pub fn enter_test_pattern(&mut self) -> Result<()> {
+        self.transition_to(DisplayState::TestPattern)
+    }
+
+    pub fn enter_intercom(&mut self) -> Result<()> {
+        self.transition_to(DisplayState::Intercom)
+    }
+
+    pub fn enter_endpoint_edit(&mut self) -> Result<()> {
+        self.transition_to(DisplayState::EndpointEdit)
+    }
+
+    pub fn enter_spectrum(&mut self) -> Result<()> {
+        self.spectrum.reset();
+        self.transition_to(DisplayState::Spectrum)
+    }
+
+    /// 进入模型选择界面，模型列表由`set_models`异步填充
+    pub fn enter_model_select(&mut self) -> Result<()> {
+        self.models = None;
+        self.transition_to(DisplayState::ModelSelect)
+    }
+
+    pub fn enter_listening(&mut self) -> Result<()> {
+        self.level_meter.reset();
+        self.transition_to(DisplayState::Listening)
+    }
+
+    /// 显示二维码
+    ///
+    /// # 参数
+    /// * `payload` - 二维码内容，例如`wifi_payload`生成的配网信息或配对网址
+    /// * `caption` - 二维码下方的说明文字
+    pub fn enter_pairing(&mut self, payload: &str, caption: String) -> Result<()> {
+        self.pairing_qr = Some(QrCode::new(
+            payload,
+            SCREEN_WIDTH / 2,
+            scaled(170),
+            scaled(220),
+        )?);
+        self.transition_to(DisplayState::Pairing(caption))
+    }
+
     pub fn enter_thinking(&mut self) -> Result<()> {
+        self.chat_stage = None;
+        self.upload_progress = None;
+        self.thinking_since = EspInstant::now();
         self.transition_to(DisplayState::Thinking)
     }
 
+    /// 更新思考界面显示的处理阶段
+    pub fn set_chat_stage(&mut self, stage: ChatStage) {
+        self.chat_stage = Some(stage);
+    }
+
+    /// 更新思考界面显示的语音上传进度
+    pub fn set_upload_progress(&mut self, percent: u8) {
+        self.upload_progress = Some(percent);
+    }
+
+    /// 显示助手的回复
+    ///
+    /// # 参数
+    /// * `text` - 回复文本
+    pub fn enter_reply(&mut self, text: String) -> Result<()> {
+        self.transition_to(DisplayState::Reply(text))
+    }
+
     /// 进入摇晃状态
     ///
     /// 当检测到设备摇晃时调用，显示眩晕效果界面。
-    /// 记录进入时间以控制最小持续时间。
+    /// 进入时间即状态计时的起点，用于控制最小持续时间。
     ///
     /// # 返回值
     /// * `Result<()>` - 状态切换结果
     ///
     /// # 特殊逻辑
-    /// - 如果已经在摇晃状态，直接返回
-    /// - 记录进入摇晃状态的精确时间戳（微秒级）
-    /// - 用于后续的最小持续时间控制
+    /// - 如果已经在摇晃状态，直接返回，不重新计时
     pub fn enter_dizziness(&mut self) -> Result<()> {
         if self.state == DisplayState::Dizziness {
             return Ok(()); // 已经在晃动状态，直接返回
         }
 
-        // 记录进入晃动状态的绝对时间戳（微秒）
-        self.dizziness_start_time = unsafe { esp_idf_sys::esp_timer_get_time() } as u32;
         self.transition_to(DisplayState::Dizziness)?;
         Ok(())
     }
@@ -199,6 +1201,23 @@ impl<'a> Display<'a> {
         self.transition_to(DisplayState::Error(error_msg))
     }
 
+    /// 进入可重试的错误状态
+    ///
+    /// 与`enter_error`相同，但界面提示按键重试，并停留30秒后才返回欢迎界面。
+    ///
+    /// # 参数
+    /// * `error_msg` - 错误消息字符串
+    pub fn enter_error_with_retry(&mut self, error_msg: String) -> Result<()> {
+        self.transition_to(DisplayState::Error(error_msg))?;
+        self.retry_available = true;
+        Ok(())
+    }
+
+    /// 当前错误界面是否提供重试
+    pub fn retry_available(&self) -> bool {
+        matches!(self.state, DisplayState::Error(_)) && self.retry_available
+    }
+
     /// 检查是否可以退出摇晃状态
     ///
     /// 确保摇晃状态至少持续3秒，避免过于频繁的状态切换。
@@ -209,22 +1228,10 @@ impl<'a> Display<'a> {
     /// # 检查逻辑
     /// 1. 确认当前确实在摇晃状态
     /// 2. 计算已经持续的时间
-    /// 3. 判断是否达到最小持续时间（3秒）
-    ///
-    /// # 时间处理
-    /// 使用wrapping_sub()处理时间戳溢出情况
+    /// 3. 判断是否达到最小持续时间（`MIN_DIZZINESS_DURATION`）
     pub fn can_exit_dizziness(&self) -> bool {
-        if self.state != DisplayState::Dizziness {
-            return false;
-        }
-
-        // 晃动状态至少持续3秒（3,000,000微秒）
-        const MIN_DIZZINESS_DURATION_US: u32 = 3_000_000;
-        let current_time = unsafe { esp_idf_sys::esp_timer_get_time() } as u32;
-        let elapsed_time = current_time.wrapping_sub(self.dizziness_start_time);
-        let can_exit = elapsed_time >= MIN_DIZZINESS_DURATION_US;
-
-        can_exit
+        self.state == DisplayState::Dizziness
+            && self.state_since.elapsed() >= MIN_DIZZINESS_DURATION
     }
 
     /// 尝试退出摇晃状态
@@ -280,6 +1287,8 @@ impl<'a> Display<'a> {
             MotionState::Tilting => {
                 self.enter_tilting()?;
             }
+            // 旋转手势用于调节音量，由App处理，不切换界面
+            MotionState::RotatingClockwise | MotionState::RotatingCounterClockwise => {}
         }
 
         Ok(())
@@ -0,0 +1,242 @@
+// src/error.rs
+//! 统一错误类型
+//!
+//! 跨线程交给App的错误（对话失败、校准失败等）统一转换为[`Error`]：App据此显示
+//! 本地化的错误提示，遥测按[`ErrorKind`]分类计数，不再直接显示或统计格式化后的错误字符串。
+//!
+//! 只用于线程边界：api、peripherals与actors的函数继续返回`anyhow::Result`，
+//! 这里也不提供crate级的`Result`别名；在边界处用[`Error::classify`]或`From`转换分类。
+
+use esp_idf_svc::io::EspIOError;
+use esp_idf_svc::sys::EspError;
+use serde::{Deserialize, Serialize};
+
+use crate::api::types::ApiError;
+
+/// 错误类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Display,
+    Sensor,
+    Network,
+    Api,
+    Audio,
+    Config,
+}
+
+impl ErrorKind {
+    /// 全部类别，顺序与计数下标一致
+    pub const ALL: [ErrorKind; 6] = [
+        ErrorKind::Display,
+        ErrorKind::Sensor,
+        ErrorKind::Network,
+        ErrorKind::Api,
+        ErrorKind::Audio,
+        ErrorKind::Config,
+    ];
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// 应用错误
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Error {
+    /// 屏幕或绘制错误
+    Display(String),
+    /// 传感器读取、校准错误
+    Sensor(String),
+    /// 网络不可用、连接或传输失败
+    Network(String),
+    /// 服务端返回错误或响应无法解析
+    Api {
+        /// HTTP状态码，响应无法解析时为None
+        status: Option<u16>,
+        message: String,
+    },
+    /// 麦克风、扬声器错误
+    Audio(String),
+    /// 配置无效或无法读写
+    Config(String),
+}
+
+impl Error {
+    /// 错误类别
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Display(_) => ErrorKind::Display,
+            Error::Sensor(_) => ErrorKind::Sensor,
+            Error::Network(_) => ErrorKind::Network,
+            Error::Api { .. } => ErrorKind::Api,
+            Error::Audio(_) => ErrorKind::Audio,
+            Error::Config(_) => ErrorKind::Config,
+        }
+    }
+
+    /// 按类别构造错误
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        let message = message.into();
+        match kind {
+            ErrorKind::Display => Error::Display(message),
+            ErrorKind::Sensor => Error::Sensor(message),
+            ErrorKind::Network => Error::Network(message),
+            ErrorKind::Api => Error::Api {
+                status: None,
+                message,
+            },
+            ErrorKind::Audio => Error::Audio(message),
+            ErrorKind::Config => Error::Config(message),
+        }
+    }
+
+    /// 把anyhow错误归类
+    ///
+    /// 能识别来源的错误（API错误、ESP-IDF网络错误）按来源分类，其余归为`fallback`。
+    ///
+    /// # 参数
+    /// * `error` - 原始错误
+    /// * `fallback` - 无法识别来源时使用的类别
+    pub fn classify(error: &anyhow::Error, fallback: ErrorKind) -> Self {
+        if let Some(error) = error.downcast_ref::<Error>() {
+            return error.clone();
+        }
+        if let Some(error) = error.downcast_ref::<ApiError>() {
+            return error.into();
+        }
+        if error.downcast_ref::<EspError>().is_some()
+            || error.downcast_ref::<EspIOError>().is_some()
+        {
+            return Error::Network(error.to_string());
+        }
+        Error::new(fallback, error.to_string())
+    }
+
+    /// 显示给用户的提示
+    ///
+    /// 网络与服务端错误的原始信息是英文技术细节，只显示概括性的提示；
+    /// 其余类别的信息由本项目编写，附在类别提示之后。
+    pub fn user_message(&self) -> String {
+        match self {
+            Error::Display(message) => format!("屏幕异常: {}", message),
+            Error::Sensor(message) => format!("传感器异常: {}", message),
+            Error::Network(_) => "网络连接失败，请检查WiFi".to_string(),
+            Error::Api { status, .. } => match status {
+                Some(401) | Some(403) => "服务认证失败".to_string(),
+                Some(404) => "服务地址无效".to_string(),
+                Some(429) => "请求过于频繁，请稍后再试".to_string(),
+                Some(status) if *status >= 500 => "服务器繁忙，请稍后再试".to_string(),
+                Some(status) => format!("服务返回错误 {}", status),
+                None => "服务响应无效".to_string(),
+            },
+            Error::Audio(message) => format!("音频异常: {}", message),
+            Error::Config(message) => format!("配置无效: {}", message),
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Display(message) => write!(f, "display error: {}", message),
+            Error::Sensor(message) => write!(f, "sensor error: {}", message),
+            Error::Network(message) => write!(f, "network error: {}", message),
+            Error::Api {
+                status: Some(status),
+                message,
+            } => write!(f, "API error {}: {}", status, message),
+            Error::Api {
+                status: None,
+                message,
+            } => write!(f, "API error: {}", message),
+            Error::Audio(message) => write!(f, "audio error: {}", message),
+            Error::Config(message) => write!(f, "config error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<&ApiError> for Error {
+    fn from(error: &ApiError) -> Self {
+        match error {
+            ApiError::Http(_) | ApiError::Timeout | ApiError::Cancelled => {
+                Error::Network(error.to_string())
+            }
+            ApiError::Api { status, message } => Error::Api {
+                status: Some(*status),
+                message: message.clone(),
+            },
+            ApiError::SessionNotFound => Error::Api {
+                status: Some(404),
+                message: error.to_string(),
+            },
+            ApiError::InvalidFingerprint => Error::Config(error.to_string()),
+            ApiError::TlsHandshake => Error::Config("客户端证书握手失败".to_string()),
+            ApiError::Json(_) | ApiError::Utf8(_) | ApiError::ResponseTooLarge { .. } => {
+                Error::Api {
+                    status: None,
+                    message: error.to_string(),
+                }
+            }
+        }
+    }
+}
+
+impl From<EspError> for Error {
+    fn from(error: EspError) -> Self {
+        Error::Network(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::Config(error.to_string())
+    }
+}
+
+/// 按类别统计的错误次数
+#[derive(Debug, Clone, Default)]
+pub struct ErrorCounts([u32; ErrorKind::ALL.len()]);
+
+impl ErrorCounts {
+    /// 记录一次错误
+    pub fn record(&mut self, kind: ErrorKind) {
+        self.0[kind.index()] += 1;
+    }
+
+    /// 取出非零的计数并清零
+    pub fn take(&mut self) -> Vec<(ErrorKind, u32)> {
+        let counts = std::mem::take(&mut self.0);
+        ErrorKind::ALL
+            .iter()
+            .map(|kind| (*kind, counts[kind.index()]))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_and_messages() {
+        let error = anyhow::Error::from(ApiError::Api {
+            status: 503,
+            message: "busy".to_string(),
+        });
+        let error = Error::classify(&error, ErrorKind::Audio);
+        assert_eq!(error.kind(), ErrorKind::Api);
+        assert_eq!(error.user_message(), "服务器繁忙，请稍后再试");
+
+        let error = Error::classify(&anyhow::anyhow!("校准时请保持设备静止"), ErrorKind::Sensor);
+        assert_eq!(error, Error::Sensor("校准时请保持设备静止".to_string()));
+
+        let mut counts = ErrorCounts::default();
+        counts.record(ErrorKind::Network);
+        counts.record(ErrorKind::Network);
+        assert_eq!(counts.take(), vec![(ErrorKind::Network, 2)]);
+        assert!(counts.take().is_empty());
+    }
+}
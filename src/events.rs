@@ -1,9 +1,22 @@
 // src/events.rs
-use crate::{actors::wifi::WifiEvent, peripherals::qmi8658::motion_detector::MotionState};
+use crate::{
+    actors::{
+        chat::ChatEvent, intercom::IntercomEvent, motion::MotionCalibrationEvent, wifi::WifiEvent,
+    },
+    api::{types::ChatStage, weather::Weather},
+    app::alarms::Alarm,
+    error::Error,
+    peripherals::button::ButtonId,
+    peripherals::qmi8658::motion_detector::MotionState,
+};
+use serde::{Deserialize, Serialize};
 use std::sync::mpsc;
+use std::time::{Duration, Instant};
 
 /// 应用事件枚举，用于统一处理来自各个子线程的消息
-#[derive(Debug, Clone)]
+///
+/// 可序列化，用于事件记录与回放（见`trace`）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AppEvent {
     /// 运动传感器事件
     Motion(MotionState),
@@ -13,17 +26,75 @@ pub enum AppEvent {
 
     /// 系统事件
     System(SystemEvent),
+
+    /// 对话事件
+    Chat(ChatEvent),
+
+    /// 服务端推送的对话处理进度
+    ChatProgress(ChatStage),
+
+    /// 用户输入事件
+    Input(UserInputEvent),
+
+    /// 天气更新
+    Weather(Weather),
+
+    /// 闹钟或倒计时到期
+    AlarmFired(Alarm),
+
+    /// 运动阈值自动校准结果
+    MotionCalibration(MotionCalibrationEvent),
+
+    /// 设备跌落（失重后撞击），参数为失重持续时间（毫秒）
+    Dropped(u64),
+
+    /// 屏幕朝下扣放（true）或翻回（false）
+    FaceDown(bool),
+
+    /// 扬声器播放期间检测到唤醒词，播放已被打断（barge-in）
+    BargeIn,
+
+    /// 没有播放时检测到唤醒词，在空闲界面上开始聆听
+    WakeWord,
+
+    /// 语音上传进度（百分比）
+    UploadProgress(u8),
+
+    /// 语音上传跟不上实时速度（true）或已恢复（false）
+    NetworkDegraded(bool),
+
+    /// 局域网对讲：发现设备或收到语音
+    Intercom(IntercomEvent),
+}
+
+/// 用户输入事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UserInputEvent {
+    /// 按键按下
+    ButtonPress(ButtonId),
+    /// 按键松开
+    ButtonRelease(ButtonId),
+    /// 单击
+    Click(ButtonId),
+    /// 双击
+    DoubleClick(ButtonId),
+    /// 长按
+    LongPress(ButtonId),
+    /// 确认（任意按键单击）
+    Confirm,
+    /// 返回（任意按键长按）
+    Back,
 }
 
 /// 系统事件
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SystemEvent {
     /// 低电量警告
     LowBattery,
     /// 内存不足
     LowMemory,
     /// 硬件错误
-    HardwareError(String),
+    HardwareError(Error),
     /// 应用退出
     Shutdown,
 }
@@ -35,18 +106,26 @@ pub type EventSender = mpsc::Sender<AppEvent>;
 pub type EventReceiver = mpsc::Receiver<AppEvent>;
 
 /// 事件总线管理器
+///
+/// 运动状态与WiFi状态这类高频事件经过`EventThrottle`限流与去重后才交给主循环。
 pub struct EventBus {
     /// 事件发送器
     sender: EventSender,
     /// 事件接收器
     receiver: EventReceiver,
+    /// 高频事件的限流与去重
+    throttle: EventThrottle,
 }
 
 impl EventBus {
     /// 创建新的事件总线
     pub fn new() -> Self {
         let (sender, receiver) = mpsc::channel();
-        Self { sender, receiver }
+        Self {
+            sender,
+            receiver,
+            throttle: EventThrottle::new(Instant::now()),
+        }
     }
 
     /// 获取事件发送器的克隆
@@ -54,19 +133,202 @@ impl EventBus {
         self.sender.clone()
     }
 
-    /// 获取事件接收器的可变引用
+    /// 获取事件接收器的可变引用（绕过限流）
     pub fn get_receiver(&mut self) -> &mut EventReceiver {
         &mut self.receiver
     }
 
     /// 尝试接收事件（非阻塞）
-    pub fn try_recv(&self) -> Result<AppEvent, mpsc::TryRecvError> {
-        self.receiver.try_recv()
+    ///
+    /// 被限流暂存的事件在队列取空后、速率允许时返回。
+    pub fn try_recv(&mut self) -> Result<AppEvent, mpsc::TryRecvError> {
+        let now = Instant::now();
+        loop {
+            match self.receiver.try_recv() {
+                Ok(event) => {
+                    if let Some(event) = self.throttle.admit(event, now) {
+                        return Ok(event);
+                    }
+                }
+                Err(e) => return self.throttle.take_due(now).ok_or(e),
+            }
+        }
     }
 
     /// 接收事件（阻塞）
-    pub fn recv(&self) -> Result<AppEvent, mpsc::RecvError> {
-        self.receiver.recv()
+    pub fn recv(&mut self) -> Result<AppEvent, mpsc::RecvError> {
+        loop {
+            let event = self.receiver.recv()?;
+            if let Some(event) = self.throttle.admit(event, Instant::now()) {
+                return Ok(event);
+            }
+        }
+    }
+}
+
+/// 参与限流与去重的事件主题
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Topic {
+    /// 运动状态（包括每5秒的心跳）
+    Motion,
+    /// WiFi状态更新（信号强度）
+    WifiStatus,
+}
+
+impl Topic {
+    const ALL: [Topic; 2] = [Topic::Motion, Topic::WifiStatus];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// 允许连续发出的事件数（令牌桶容量）
+    fn burst(self) -> u32 {
+        match self {
+            // 旋转手势是"倾斜→旋转→静止"几次状态变化，需要一次全部送达
+            Topic::Motion => 4,
+            Topic::WifiStatus => 2,
+        }
+    }
+
+    /// 补充一个令牌的间隔
+    fn refill_interval(self) -> Duration {
+        match self {
+            Topic::Motion => Duration::from_millis(100),
+            Topic::WifiStatus => Duration::from_secs(1),
+        }
+    }
+}
+
+impl AppEvent {
+    /// 事件所属的限流主题，其他事件不限流
+    fn topic(&self) -> Option<Topic> {
+        match self {
+            AppEvent::Motion(_) => Some(Topic::Motion),
+            AppEvent::Wifi(WifiEvent::StatusUpdate(_)) => Some(Topic::WifiStatus),
+            _ => None,
+        }
+    }
+
+    /// 与同一主题上一次送达的事件相同且重复没有作用
+    ///
+    /// 静止心跳和未变化的WiFi状态可以丢弃；重复的晃动、旋转仍表示有人在操作（保持亮屏、
+    /// 继续滚动），照常送达。
+    fn is_redundant_after(&self, last: &AppEvent) -> bool {
+        match (self, last) {
+            (AppEvent::Motion(state), AppEvent::Motion(last)) => {
+                state == last && *state == MotionState::Still
+            }
+            (
+                AppEvent::Wifi(WifiEvent::StatusUpdate(status)),
+                AppEvent::Wifi(WifiEvent::StatusUpdate(last)),
+            ) => status == last,
+            _ => false,
+        }
+    }
+}
+
+/// 单个主题的限流状态
+#[derive(Debug)]
+struct TopicState {
+    /// 剩余令牌
+    tokens: u32,
+    /// 上次补充令牌的时间
+    refilled_at: Instant,
+    /// 上一次送达的事件
+    last: Option<AppEvent>,
+    /// 超过速率时暂存的最新事件
+    pending: Option<AppEvent>,
+}
+
+/// 高频事件的限流与去重
+///
+/// 每个主题一个令牌桶：超过速率的事件不会丢失状态，只保留最新的一个，令牌补充后送达；
+/// 与上一次送达的事件相同且没有作用的重复事件直接丢弃。
+#[derive(Debug)]
+struct EventThrottle {
+    topics: Vec<TopicState>,
+}
+
+impl EventThrottle {
+    fn new(now: Instant) -> Self {
+        Self {
+            topics: Topic::ALL
+                .iter()
+                .map(|topic| TopicState {
+                    tokens: topic.burst(),
+                    refilled_at: now,
+                    last: None,
+                    pending: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// 处理收到的事件
+    ///
+    /// # 返回值
+    /// 应立即送达的事件，被暂存或丢弃时返回None
+    fn admit(&mut self, event: AppEvent, now: Instant) -> Option<AppEvent> {
+        let Some(topic) = event.topic() else {
+            return Some(event);
+        };
+        let state = &mut self.topics[topic.index()];
+        Self::refill(topic, state, now);
+
+        // 已有暂存事件时新事件只替换它，保证送达顺序
+        if state.pending.is_some() || state.tokens == 0 {
+            state.pending = Some(event);
+            return None;
+        }
+        Self::deliver(state, event)
+    }
+
+    /// 取出速率已允许送达的暂存事件
+    fn take_due(&mut self, now: Instant) -> Option<AppEvent> {
+        for topic in Topic::ALL {
+            let state = &mut self.topics[topic.index()];
+            Self::refill(topic, state, now);
+            if state.tokens == 0 {
+                continue;
+            }
+            if let Some(event) = state.pending.take() {
+                if let Some(event) = Self::deliver(state, event) {
+                    return Some(event);
+                }
+            }
+        }
+        None
+    }
+
+    /// 按经过的时间补充令牌
+    fn refill(topic: Topic, state: &mut TopicState, now: Instant) {
+        let interval = topic.refill_interval();
+        let elapsed = now.saturating_duration_since(state.refilled_at);
+        let refills = (elapsed.as_millis() / interval.as_millis()) as u32;
+        if refills == 0 {
+            return;
+        }
+        state.tokens = (state.tokens + refills).min(topic.burst());
+        state.refilled_at = if state.tokens == topic.burst() {
+            now
+        } else {
+            state.refilled_at + interval * refills
+        };
+    }
+
+    /// 去重后消耗一个令牌送达事件
+    fn deliver(state: &mut TopicState, event: AppEvent) -> Option<AppEvent> {
+        if state
+            .last
+            .as_ref()
+            .is_some_and(|last| event.is_redundant_after(last))
+        {
+            return None;
+        }
+        state.tokens -= 1;
+        state.last = Some(event.clone());
+        Some(event)
     }
 }
 
@@ -97,3 +359,179 @@ pub fn send_system_event(
 ) -> Result<(), mpsc::SendError<AppEvent>> {
     sender.send(AppEvent::System(system_event))
 }
+
+pub fn send_input_event(
+    sender: &EventSender,
+    input_event: UserInputEvent,
+) -> Result<(), mpsc::SendError<AppEvent>> {
+    sender.send(AppEvent::Input(input_event))
+}
+
+pub fn send_chat_event(
+    sender: &EventSender,
+    chat_event: ChatEvent,
+) -> Result<(), mpsc::SendError<AppEvent>> {
+    sender.send(AppEvent::Chat(chat_event))
+}
+
+pub fn send_chat_progress_event(
+    sender: &EventSender,
+    stage: ChatStage,
+) -> Result<(), mpsc::SendError<AppEvent>> {
+    sender.send(AppEvent::ChatProgress(stage))
+}
+
+pub fn send_upload_progress_event(
+    sender: &EventSender,
+    percent: u8,
+) -> Result<(), mpsc::SendError<AppEvent>> {
+    sender.send(AppEvent::UploadProgress(percent))
+}
+
+pub fn send_weather_event(
+    sender: &EventSender,
+    weather: Weather,
+) -> Result<(), mpsc::SendError<AppEvent>> {
+    sender.send(AppEvent::Weather(weather))
+}
+
+pub fn send_alarm_event(
+    sender: &EventSender,
+    alarm: Alarm,
+) -> Result<(), mpsc::SendError<AppEvent>> {
+    sender.send(AppEvent::AlarmFired(alarm))
+}
+
+pub fn send_motion_calibration_event(
+    sender: &EventSender,
+    event: MotionCalibrationEvent,
+) -> Result<(), mpsc::SendError<AppEvent>> {
+    sender.send(AppEvent::MotionCalibration(event))
+}
+
+pub fn send_dropped_event(
+    sender: &EventSender,
+    fall_ms: u64,
+) -> Result<(), mpsc::SendError<AppEvent>> {
+    sender.send(AppEvent::Dropped(fall_ms))
+}
+
+pub fn send_face_down_event(
+    sender: &EventSender,
+    face_down: bool,
+) -> Result<(), mpsc::SendError<AppEvent>> {
+    sender.send(AppEvent::FaceDown(face_down))
+}
+
+pub fn send_barge_in_event(sender: &EventSender) -> Result<(), mpsc::SendError<AppEvent>> {
+    sender.send(AppEvent::BargeIn)
+}
+
+pub fn send_wake_word_event(sender: &EventSender) -> Result<(), mpsc::SendError<AppEvent>> {
+    sender.send(AppEvent::WakeWord)
+}
+
+pub fn send_intercom_event(
+    sender: &EventSender,
+    event: IntercomEvent,
+) -> Result<(), mpsc::SendError<AppEvent>> {
+    sender.send(AppEvent::Intercom(event))
+}
+
+pub fn send_network_degraded_event(
+    sender: &EventSender,
+    degraded: bool,
+) -> Result<(), mpsc::SendError<AppEvent>> {
+    sender.send(AppEvent::NetworkDegraded(degraded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::wifi::WifiStatus;
+
+    fn motion(state: MotionState) -> AppEvent {
+        AppEvent::Motion(state)
+    }
+
+    fn wifi(rssi: i8) -> AppEvent {
+        AppEvent::Wifi(WifiEvent::StatusUpdate(WifiStatus::Connected {
+            rssi: Some(rssi),
+        }))
+    }
+
+    fn is_motion(event: Option<AppEvent>, expected: MotionState) -> bool {
+        matches!(event, Some(AppEvent::Motion(state)) if state == expected)
+    }
+
+    #[test]
+    fn test_collapse_redundant_repeats() {
+        let now = Instant::now();
+        let mut throttle = EventThrottle::new(now);
+        assert!(throttle.admit(motion(MotionState::Still), now).is_some());
+        // 静止心跳丢弃，重复的旋转照常送达
+        let later = now + Duration::from_secs(5);
+        assert!(throttle.admit(motion(MotionState::Still), later).is_none());
+        assert!(throttle
+            .admit(motion(MotionState::RotatingClockwise), later)
+            .is_some());
+        let later = later + Duration::from_secs(5);
+        assert!(throttle
+            .admit(motion(MotionState::RotatingClockwise), later)
+            .is_some());
+
+        assert!(throttle.admit(wifi(-60), now).is_some());
+        assert!(throttle.admit(wifi(-60), later).is_none());
+        assert!(throttle.admit(wifi(-70), later).is_some());
+
+        // 其他事件不受影响
+        for _ in 0..10 {
+            assert!(throttle.admit(AppEvent::WakeWord, now).is_some());
+        }
+    }
+
+    #[test]
+    fn test_rate_limit_keeps_latest() {
+        let now = Instant::now();
+        let mut throttle = EventThrottle::new(now);
+        let states = [
+            MotionState::Tilting,
+            MotionState::Shaking,
+            MotionState::Tilting,
+            MotionState::Shaking,
+        ];
+        for state in states {
+            assert!(throttle.admit(motion(state), now).is_some());
+        }
+
+        // 令牌用完后只保留最新的事件
+        assert!(throttle.admit(motion(MotionState::Tilting), now).is_none());
+        assert!(throttle
+            .admit(motion(MotionState::RotatingClockwise), now)
+            .is_none());
+        assert!(throttle.take_due(now).is_none());
+
+        let later = now + Topic::Motion.refill_interval();
+        // 有暂存事件时新事件替换它
+        assert!(throttle
+            .admit(motion(MotionState::Shaking), later)
+            .is_none());
+        assert!(is_motion(throttle.take_due(later), MotionState::Shaking));
+        assert!(throttle.take_due(later).is_none());
+    }
+
+    #[test]
+    fn test_pending_duplicate_is_dropped() {
+        let now = Instant::now();
+        let mut throttle = EventThrottle::new(now);
+        assert!(throttle.admit(wifi(-60), now).is_some());
+        assert!(throttle.admit(wifi(-70), now).is_some());
+        assert!(throttle.admit(wifi(-80), now).is_none());
+        assert!(throttle.admit(wifi(-70), now).is_none());
+
+        // 暂存的事件与上一次送达的相同，补充令牌后也不再送达
+        let later = now + Duration::from_secs(2);
+        assert!(throttle.take_due(later).is_none());
+        assert!(throttle.admit(wifi(-80), later).is_some());
+    }
+}
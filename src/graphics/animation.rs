@@ -1,6 +1,12 @@
+use std::time::Duration;
+
 use esp_idf_sys as _;
 
-#[derive(Debug, Clone, Copy)]
+/// 基于esp_timer的单调时间点
+///
+/// esp_timer从启动开始按微秒计数（64位，不会回绕），不受SNTP校时影响。
+/// 界面的超时、闪烁与动画都用它计算，主循环变慢或跳帧时节奏不变。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct EspInstant {
     micros: i64,
 }
@@ -12,6 +18,16 @@ impl EspInstant {
         }
     }
 
+    /// 从该时间点到现在经过的时间
+    pub fn elapsed(&self) -> Duration {
+        Self::now().duration_since(*self)
+    }
+
+    /// 从`earlier`到该时间点经过的时间，`earlier`更晚时为零
+    pub fn duration_since(&self, earlier: EspInstant) -> Duration {
+        Duration::from_micros(self.micros.saturating_sub(earlier.micros).max(0) as u64)
+    }
+
     pub fn elapsed_us(&self) -> i64 {
         let current = unsafe { esp_idf_sys::esp_timer_get_time() };
         current - self.micros
@@ -22,6 +38,20 @@ impl EspInstant {
     }
 }
 
+/// 按固定步长循环的动画相位
+///
+/// # 参数
+/// * `elapsed` - 动画开始后经过的时间
+/// * `step` - 每个相位持续的时间
+/// * `count` - 相位数
+///
+/// # 返回值
+/// 当前相位（0..count）
+pub fn cycle_phase(elapsed: Duration, step: Duration, count: u32) -> u32 {
+    let steps = elapsed.as_micros() / step.as_micros().max(1);
+    (steps % count.max(1) as u128) as u32
+}
+
 pub struct FrameAnimation {
     frames: Vec<&'static [u8]>,
     current_frame: usize,
@@ -121,3 +151,26 @@ impl FrameAnimation {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_esp_instant_duration_since() {
+        let earlier = EspInstant { micros: 1_000_000 };
+        let later = EspInstant { micros: 3_500_000 };
+        assert_eq!(later.duration_since(earlier), Duration::from_millis(2500));
+        assert_eq!(earlier.duration_since(later), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_cycle_phase() {
+        let half = Duration::from_millis(500);
+        assert_eq!(cycle_phase(Duration::ZERO, half, 2), 0);
+        assert_eq!(cycle_phase(Duration::from_millis(499), half, 2), 0);
+        assert_eq!(cycle_phase(Duration::from_millis(500), half, 2), 1);
+        assert_eq!(cycle_phase(Duration::from_millis(1750), half, 4), 3);
+        assert_eq!(cycle_phase(Duration::from_secs(2), half, 4), 0);
+    }
+}
@@ -0,0 +1,159 @@
+// 防烧屏：静态画面的周期性像素位移与定时扫屏
+
+use serde::{Deserialize, Serialize};
+
+use crate::graphics::animation::EspInstant;
+
+/// 像素位移轨迹（单位偏移，会乘以`max_shift`）
+///
+/// 绕中心点走一圈，保证长期来看每个方向的位移量相同。
+const SHIFT_PATTERN: [(i32, i32); 9] = [
+    (0, 0),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+/// 扫屏条带宽度（像素）
+pub const SWEEP_BAND_WIDTH: i32 = 24;
+
+/// 防烧屏配置
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BurnInConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 像素位移间隔（秒）
+    pub shift_interval_secs: u32,
+    /// 最大位移量（像素）
+    pub max_shift: i32,
+    /// 扫屏间隔（秒），0表示不扫屏
+    pub sweep_interval_secs: u32,
+    /// 扫屏条带每帧移动的像素数
+    pub sweep_step: i32,
+}
+
+impl Default for BurnInConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            shift_interval_secs: 60,
+            max_shift: 2,
+            sweep_interval_secs: 30 * 60,
+            sweep_step: 12,
+        }
+    }
+}
+
+/// 防烧屏动作，由Display层执行实际绘制
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BurnInAction {
+    /// 无需处理
+    None,
+    /// 切换到新的像素偏移，需要清屏后以新偏移重绘
+    Shift(i32, i32),
+    /// 绘制扫屏条带，参数为条带左边界X坐标
+    Sweep(i32),
+    /// 扫屏结束，需要清屏重绘
+    SweepDone,
+}
+
+/// 防烧屏调度器
+///
+/// 只负责计时与计算偏移/扫屏位置，不直接绘制。
+pub struct BurnInGuard {
+    config: BurnInConfig,
+    shift_index: usize,
+    last_shift: EspInstant,
+    last_sweep: EspInstant,
+    /// 正在进行的扫屏条带位置
+    sweep_x: Option<i32>,
+}
+
+impl BurnInGuard {
+    /// 创建新的防烧屏调度器
+    pub fn new(config: BurnInConfig) -> Self {
+        Self {
+            config,
+            shift_index: 0,
+            last_shift: EspInstant::now(),
+            last_sweep: EspInstant::now(),
+            sweep_x: None,
+        }
+    }
+
+    /// 获取当前配置
+    pub fn config(&self) -> &BurnInConfig {
+        &self.config
+    }
+
+    /// 更新配置，禁用时立即恢复零偏移
+    pub fn set_config(&mut self, config: BurnInConfig) {
+        self.config = config;
+        if !config.enabled {
+            self.shift_index = 0;
+            self.sweep_x = None;
+        }
+    }
+
+    /// 获取当前像素偏移
+    pub fn offset(&self) -> (i32, i32) {
+        let (dx, dy) = SHIFT_PATTERN[self.shift_index];
+        (dx * self.config.max_shift, dy * self.config.max_shift)
+    }
+
+    /// 是否正在扫屏
+    pub fn is_sweeping(&self) -> bool {
+        self.sweep_x.is_some()
+    }
+
+    /// 重置计时（例如离开静态画面时）
+    pub fn reset_timers(&mut self) {
+        self.last_shift = EspInstant::now();
+        self.last_sweep = EspInstant::now();
+        self.sweep_x = None;
+    }
+
+    /// 推进调度器，返回本帧需要执行的动作
+    ///
+    /// # 参数
+    /// * `screen_width` - 屏幕宽度，用于判断扫屏结束
+    pub fn poll(&mut self, screen_width: i32) -> BurnInAction {
+        if !self.config.enabled {
+            return BurnInAction::None;
+        }
+
+        // 扫屏进行中：推进条带
+        if let Some(x) = self.sweep_x {
+            let next = x + self.config.sweep_step;
+            if next >= screen_width {
+                self.sweep_x = None;
+                self.last_sweep = EspInstant::now();
+                return BurnInAction::SweepDone;
+            }
+            self.sweep_x = Some(next);
+            return BurnInAction::Sweep(next);
+        }
+
+        if self.config.sweep_interval_secs > 0
+            && self.last_sweep.elapsed_ms() >= self.config.sweep_interval_secs * 1000
+        {
+            self.sweep_x = Some(-SWEEP_BAND_WIDTH);
+            return BurnInAction::Sweep(-SWEEP_BAND_WIDTH);
+        }
+
+        if self.last_shift.elapsed_ms() >= self.config.shift_interval_secs * 1000 {
+            self.last_shift = EspInstant::now();
+            self.shift_index = (self.shift_index + 1) % SHIFT_PATTERN.len();
+            let (dx, dy) = self.offset();
+            return BurnInAction::Shift(dx, dy);
+        }
+
+        BurnInAction::None
+    }
+}
@@ -0,0 +1,483 @@
+// 帧缓冲区：所有绘制先写入内存，再由显示线程按脏区域分块刷新到LCD
+//
+// 支持两种颜色深度：
+// - RGB565：每像素2字节，360x360约253KB，需要PSRAM
+// - RGB332：每像素1字节，内存减半，刷新时展开为RGB565，适合没有PSRAM的板子
+
+use std::io::Write;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use anyhow::Result;
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    pixelcolor::{Rgb565, RgbColor},
+    primitives::Rectangle,
+    Pixel,
+};
+
+/// 帧缓冲区颜色深度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// 16位RGB565，与面板原生格式一致
+    Rgb565,
+    /// 8位RGB332，刷新时展开为RGB565
+    Rgb332,
+}
+
+impl ColorDepth {
+    /// 每像素占用的字节数
+    pub fn bytes_per_pixel(&self) -> usize {
+        match self {
+            ColorDepth::Rgb565 => 2,
+            ColorDepth::Rgb332 => 1,
+        }
+    }
+}
+
+/// 默认帧缓冲区颜色深度，由板子决定（没有PSRAM的板子使用RGB332），
+/// 启用`fb-rgb332`特性时强制使用RGB332
+#[cfg(not(feature = "fb-rgb332"))]
+pub const FRAMEBUFFER_COLOR_DEPTH: ColorDepth = crate::boards::SPEC.framebuffer_depth;
+#[cfg(feature = "fb-rgb332")]
+pub const FRAMEBUFFER_COLOR_DEPTH: ColorDepth = ColorDepth::Rgb332;
+
+/// 将RGB565颜色转换为面板要求的大端序u16
+#[inline(always)]
+fn rgb565_to_panel(color: Rgb565) -> u16 {
+    let raw = ((color.r() as u16) << 11) | ((color.g() as u16) << 5) | (color.b() as u16);
+    raw.swap_bytes()
+}
+
+/// 将RGB565颜色压缩为RGB332
+#[inline(always)]
+fn rgb565_to_rgb332(color: Rgb565) -> u8 {
+    ((color.r() >> 2) << 5) | ((color.g() >> 3) << 2) | (color.b() >> 3)
+}
+
+/// 将RGB332展开为RGB565（高位复制到低位，保证纯白/纯黑无损）
+const fn rgb332_to_rgb565_raw(value: u8) -> u16 {
+    let r3 = (value >> 5) as u16 & 0x07;
+    let g3 = (value >> 2) as u16 & 0x07;
+    let b2 = value as u16 & 0x03;
+
+    let r5 = (r3 << 2) | (r3 >> 1);
+    let g6 = (g3 << 3) | g3;
+    let b5 = (b2 << 3) | (b2 << 1) | (b2 >> 1);
+
+    (r5 << 11) | (g6 << 5) | b5
+}
+
+/// RGB332到面板格式（大端序RGB565）的查找表
+const RGB332_TO_PANEL: [u16; 256] = {
+    let mut table = [0u16; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = rgb332_to_rgb565_raw(i as u8).swap_bytes();
+        i += 1;
+    }
+    table
+};
+
+/// 像素存储
+enum FrameStorage {
+    /// 已经是面板格式（大端序RGB565）
+    Rgb565(Vec<u16>),
+    Rgb332(Vec<u8>),
+}
+
+/// 脏区域（闭区间）
+#[derive(Debug, Clone, Copy)]
+struct DirtyRect {
+    min_x: i32,
+    min_y: i32,
+    max_x: i32,
+    max_y: i32,
+}
+
+/// 帧缓冲区
+///
+/// 实现了embedded-graphics的DrawTarget，对GraphicsPrimitives透明。
+/// 绘制只修改内存并记录脏区域，显示线程取出脏区域（`take_dirty`）后才真正写入LCD。
+pub struct FrameBuffer {
+    width: i32,
+    height: i32,
+    depth: ColorDepth,
+    storage: FrameStorage,
+    dirty: Option<DirtyRect>,
+}
+
+impl FrameBuffer {
+    /// 创建新的帧缓冲区
+    ///
+    /// # 参数
+    /// * `width` - 宽度（像素）
+    /// * `height` - 高度（像素）
+    /// * `depth` - 颜色深度
+    pub fn new(width: i32, height: i32, depth: ColorDepth) -> Result<Self> {
+        let pixel_count = (width * height) as usize;
+        let storage = match depth {
+            ColorDepth::Rgb565 => FrameStorage::Rgb565(vec![0u16; pixel_count]),
+            ColorDepth::Rgb332 => FrameStorage::Rgb332(vec![0u8; pixel_count]),
+        };
+
+        Ok(Self {
+            width,
+            height,
+            depth,
+            storage,
+            // 初始内容未知，首次刷新需要写满整个屏幕
+            dirty: Some(DirtyRect {
+                min_x: 0,
+                min_y: 0,
+                max_x: width - 1,
+                max_y: height - 1,
+            }),
+        })
+    }
+
+    /// 获取颜色深度
+    pub fn depth(&self) -> ColorDepth {
+        self.depth
+    }
+
+    /// 帧缓冲区占用的内存（字节）
+    pub fn memory_usage(&self) -> usize {
+        (self.width * self.height) as usize * self.depth.bytes_per_pixel()
+    }
+
+    /// 是否有尚未刷新的修改
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.is_some()
+    }
+
+    /// 标记整个屏幕需要刷新
+    pub fn invalidate(&mut self) {
+        self.mark_dirty(0, 0, self.width - 1, self.height - 1);
+    }
+
+    /// 读取指定像素的颜色（面板格式的大端序RGB565）
+    pub fn get_panel_pixel(&self, x: i32, y: i32) -> Option<u16> {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return None;
+        }
+        let index = (y * self.width + x) as usize;
+        Some(match &self.storage {
+            FrameStorage::Rgb565(buffer) => buffer[index],
+            FrameStorage::Rgb332(buffer) => RGB332_TO_PANEL[buffer[index] as usize],
+        })
+    }
+
+    /// 将当前帧保存为24位BMP图片
+    ///
+    /// 保存的是帧缓冲区中的内容，可能包含尚未刷新到屏幕的绘制。
+    pub fn write_bmp<W: Write>(&self, writer: &mut W) -> Result<()> {
+        const HEADER_LEN: u32 = 14 + 40;
+        // BMP每行按4字节对齐
+        let row_len = (self.width as u32 * 3).div_ceil(4) * 4;
+        let image_len = row_len * self.height as u32;
+
+        // 文件头
+        writer.write_all(b"BM")?;
+        writer.write_all(&(HEADER_LEN + image_len).to_le_bytes())?;
+        writer.write_all(&0u32.to_le_bytes())?;
+        writer.write_all(&HEADER_LEN.to_le_bytes())?;
+
+        // BITMAPINFOHEADER，高度为正表示从下到上存放
+        writer.write_all(&40u32.to_le_bytes())?;
+        writer.write_all(&self.width.to_le_bytes())?;
+        writer.write_all(&self.height.to_le_bytes())?;
+        writer.write_all(&1u16.to_le_bytes())?; // 平面数
+        writer.write_all(&24u16.to_le_bytes())?; // 每像素位数
+        writer.write_all(&0u32.to_le_bytes())?; // 不压缩
+        writer.write_all(&image_len.to_le_bytes())?;
+        writer.write_all(&2835i32.to_le_bytes())?; // 72 DPI
+        writer.write_all(&2835i32.to_le_bytes())?;
+        writer.write_all(&0u32.to_le_bytes())?;
+        writer.write_all(&0u32.to_le_bytes())?;
+
+        let mut row = vec![0u8; row_len as usize];
+        for y in (0..self.height).rev() {
+            for x in 0..self.width {
+                let raw = self.get_panel_pixel(x, y).unwrap_or(0).swap_bytes();
+                let r = ((raw >> 11) & 0x1F) as u8;
+                let g = ((raw >> 5) & 0x3F) as u8;
+                let b = (raw & 0x1F) as u8;
+                // 像素顺序为BGR，低位用高位填充以保证白色为0xFF
+                let offset = x as usize * 3;
+                row[offset] = (b << 3) | (b >> 2);
+                row[offset + 1] = (g << 2) | (g >> 4);
+                row[offset + 2] = (r << 3) | (r >> 2);
+            }
+            writer.write_all(&row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// 把当前帧缩小后按行程编码（RLE）写入`out`，用于屏幕镜像
+    ///
+    /// 格式：宽、高各一个小端序u16，之后为若干段`[重复次数u8][像素u16]`，
+    /// 像素为小端序RGB565，从左到右、从上到下排列。缩小时取每个`scale`x`scale`块左上角的像素。
+    ///
+    /// # 参数
+    /// * `scale` - 缩小倍数，1为原尺寸
+    /// * `out` - 输出，写入前清空
+    pub fn encode_rle(&self, scale: i32, out: &mut Vec<u8>) {
+        let scale = scale.max(1);
+        let width = self.width / scale;
+        let height = self.height / scale;
+        out.clear();
+        out.extend_from_slice(&(width as u16).to_le_bytes());
+        out.extend_from_slice(&(height as u16).to_le_bytes());
+
+        let mut run: Option<(u16, u8)> = None;
+        for y in 0..height {
+            for x in 0..width {
+                let pixel = self
+                    .get_panel_pixel(x * scale, y * scale)
+                    .unwrap_or(0)
+                    .swap_bytes();
+                run = match run {
+                    Some((color, count)) if color == pixel && count < u8::MAX => {
+                        Some((color, count + 1))
+                    }
+                    Some((color, count)) => {
+                        out.push(count);
+                        out.extend_from_slice(&color.to_le_bytes());
+                        Some((pixel, 1))
+                    }
+                    None => Some((pixel, 1)),
+                };
+            }
+        }
+        if let Some((color, count)) = run {
+            out.push(count);
+            out.extend_from_slice(&color.to_le_bytes());
+        }
+    }
+
+    fn mark_dirty(&mut self, min_x: i32, min_y: i32, max_x: i32, max_y: i32) {
+        self.dirty = Some(match self.dirty {
+            Some(d) => DirtyRect {
+                min_x: d.min_x.min(min_x),
+                min_y: d.min_y.min(min_y),
+                max_x: d.max_x.max(max_x),
+                max_y: d.max_y.max(max_y),
+            },
+            None => DirtyRect {
+                min_x,
+                min_y,
+                max_x,
+                max_y,
+            },
+        });
+    }
+
+    #[inline(always)]
+    fn set_pixel(&mut self, index: usize, color: Rgb565) {
+        match &mut self.storage {
+            FrameStorage::Rgb565(buffer) => buffer[index] = rgb565_to_panel(color),
+            FrameStorage::Rgb332(buffer) => buffer[index] = rgb565_to_rgb332(color),
+        }
+    }
+
+    /// 取出脏区域并清除脏标记，没有修改时返回None
+    pub fn take_dirty(&mut self) -> Option<Rectangle> {
+        self.dirty.take().map(|dirty| {
+            Rectangle::with_corners(
+                Point::new(dirty.min_x, dirty.min_y),
+                Point::new(dirty.max_x, dirty.max_y),
+            )
+        })
+    }
+
+    /// 把区域中的若干行按面板格式复制到`out`
+    ///
+    /// # 参数
+    /// * `area` - 区域，必须在帧缓冲区范围内
+    /// * `first_row` - 起始行（屏幕坐标）
+    /// * `out` - 输出，长度为区域宽度的整数倍，决定复制的行数
+    pub fn copy_rows(&self, area: &Rectangle, first_row: i32, out: &mut [u16]) {
+        let region_width = area.size.width as usize;
+        for (row, dst) in out.chunks_exact_mut(region_width).enumerate() {
+            let src_start = ((first_row + row as i32) * self.width + area.top_left.x) as usize;
+            match &self.storage {
+                FrameStorage::Rgb565(buffer) => {
+                    dst.copy_from_slice(&buffer[src_start..src_start + region_width]);
+                }
+                FrameStorage::Rgb332(buffer) => {
+                    for (out, value) in dst
+                        .iter_mut()
+                        .zip(&buffer[src_start..src_start + region_width])
+                    {
+                        *out = RGB332_TO_PANEL[*value as usize];
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl DrawTarget for FrameBuffer {
+    type Color = Rgb565;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let mut min_x = i32::MAX;
+        let mut min_y = i32::MAX;
+        let mut max_x = i32::MIN;
+        let mut max_y = i32::MIN;
+
+        for Pixel(coord, color) in pixels {
+            if coord.x < 0 || coord.y < 0 || coord.x >= self.width || coord.y >= self.height {
+                continue;
+            }
+
+            let index = (coord.y * self.width + coord.x) as usize;
+            self.set_pixel(index, color);
+
+            min_x = min_x.min(coord.x);
+            min_y = min_y.min(coord.y);
+            max_x = max_x.max(coord.x);
+            max_y = max_y.max(coord.y);
+        }
+
+        if min_x <= max_x {
+            self.mark_dirty(min_x, min_y, max_x, max_y);
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let screen = Rectangle::new(Point::zero(), self.size());
+        let area = area.intersection(&screen);
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+
+        let start_x = area.top_left.x as usize;
+        let end_x = bottom_right.x as usize + 1;
+        let width = self.width as usize;
+
+        for y in area.top_left.y..=bottom_right.y {
+            let row = y as usize * width;
+            match &mut self.storage {
+                FrameStorage::Rgb565(buffer) => {
+                    buffer[row + start_x..row + end_x].fill(rgb565_to_panel(color));
+                }
+                FrameStorage::Rgb332(buffer) => {
+                    buffer[row + start_x..row + end_x].fill(rgb565_to_rgb332(color));
+                }
+            }
+        }
+
+        self.mark_dirty(
+            area.top_left.x,
+            area.top_left.y,
+            bottom_right.x,
+            bottom_right.y,
+        );
+        Ok(())
+    }
+}
+
+impl OriginDimensions for FrameBuffer {
+    fn size(&self) -> Size {
+        Size::new(self.width as u32, self.height as u32)
+    }
+}
+
+/// 主线程与显示线程共享的帧缓冲区
+///
+/// 主线程每次绘制调用只短暂加锁，显示线程每复制一块数据加锁一次，
+/// 双方都不会在锁内等待SPI传输。
+#[derive(Clone)]
+pub struct SharedFrameBuffer(Arc<Mutex<FrameBuffer>>);
+
+impl SharedFrameBuffer {
+    pub fn new(framebuffer: FrameBuffer) -> Self {
+        Self(Arc::new(Mutex::new(framebuffer)))
+    }
+
+    /// 加锁访问帧缓冲区
+    ///
+    /// 另一方panic后锁会损坏，但像素数据仍然可用，因此忽略损坏标记。
+    pub fn lock(&self) -> MutexGuard<'_, FrameBuffer> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl DrawTarget for SharedFrameBuffer {
+    type Color = Rgb565;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.lock().draw_iter(pixels)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.lock().fill_solid(area, color)
+    }
+}
+
+impl OriginDimensions for SharedFrameBuffer {
+    fn size(&self) -> Size {
+        self.lock().size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 解码`encode_rle`的输出，返回宽、高与像素
+    fn decode_rle(data: &[u8]) -> (u16, u16, Vec<u16>) {
+        let width = u16::from_le_bytes([data[0], data[1]]);
+        let height = u16::from_le_bytes([data[2], data[3]]);
+        let mut pixels = Vec::new();
+        for run in data[4..].chunks_exact(3) {
+            let color = u16::from_le_bytes([run[1], run[2]]);
+            pixels.resize(pixels.len() + run[0] as usize, color);
+        }
+        (width, height, pixels)
+    }
+
+    #[test]
+    fn test_encode_rle_downscaled() {
+        for depth in [ColorDepth::Rgb565, ColorDepth::Rgb332] {
+            let mut framebuffer = FrameBuffer::new(40, 20, depth).unwrap();
+            framebuffer
+                .fill_solid(
+                    &Rectangle::new(Point::new(20, 0), Size::new(20, 20)),
+                    Rgb565::WHITE,
+                )
+                .unwrap();
+
+            let mut out = Vec::new();
+            framebuffer.encode_rle(2, &mut out);
+            let (width, height, pixels) = decode_rle(&out);
+            assert_eq!((width, height), (20, 10));
+            assert_eq!(pixels.len(), 200);
+            assert_eq!(pixels[9], 0);
+            assert_eq!(pixels[10], 0xFFFF);
+            // 每行一段黑、一段白
+            assert_eq!(out.len(), 4 + 10 * 2 * 3);
+        }
+    }
+
+    #[test]
+    fn test_encode_rle_splits_long_runs() {
+        let framebuffer = FrameBuffer::new(300, 1, ColorDepth::Rgb565).unwrap();
+        let mut out = Vec::new();
+        framebuffer.encode_rle(1, &mut out);
+        assert_eq!(&out[4..], &[255, 0, 0, 45, 0, 0]);
+    }
+}
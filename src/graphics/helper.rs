@@ -215,19 +215,19 @@ pub fn map_range(value: f32, from_min: f32, from_max: f32, to_min: f32, to_max:
 #[macro_export]
 macro_rules! draw_debug_grid {
     ($graphics:expr, $color:expr) => {
-        use crate::graphics::layout::GRID_SIZE;
+        use crate::graphics::layout::{GRID_SIZE, SCREEN_HEIGHT, SCREEN_WIDTH};
 
         // 绘制垂直线
         for i in 0..4 {
             let x = i * GRID_SIZE;
-            let rect = crate::graphics::layout::ScreenRect::new(x, 0, 1, 360);
+            let rect = crate::graphics::layout::ScreenRect::new(x, 0, 1, SCREEN_HEIGHT);
             $graphics.fill_rect(&rect, $color)?;
         }
 
         // 绘制水平线
         for i in 0..4 {
             let y = i * GRID_SIZE;
-            let rect = crate::graphics::layout::ScreenRect::new(0, y, 360, 1);
+            let rect = crate::graphics::layout::ScreenRect::new(0, y, SCREEN_WIDTH, 1);
             $graphics.fill_rect(&rect, $color)?;
         }
     };
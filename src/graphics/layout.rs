@@ -1,12 +1,32 @@
-// 360x360 屏幕布局常量和位置定义
+// 屏幕布局常量和位置定义
+//
+// 尺寸取自当前板子的屏幕分辨率（360x360的ST77916或240x240的GC9A01）。
+// 界面按360x360设计，坐标、半径等尺寸通过`scaled`换算到实际分辨率。
+
+use crate::boards::SPEC;
 
 /// 屏幕尺寸常量
-pub const SCREEN_WIDTH: i32 = 360;
-pub const SCREEN_HEIGHT: i32 = 360;
+pub const SCREEN_WIDTH: i32 = SPEC.display_width;
+pub const SCREEN_HEIGHT: i32 = SPEC.display_height;
 
 /// 屏幕中心点
-pub const SCREEN_CENTER_X: i32 = SCREEN_WIDTH / 2; // 180
-pub const SCREEN_CENTER_Y: i32 = SCREEN_HEIGHT / 2; // 180
+pub const SCREEN_CENTER_X: i32 = SCREEN_WIDTH / 2;
+pub const SCREEN_CENTER_Y: i32 = SCREEN_HEIGHT / 2;
+
+/// 界面设计稿的尺寸
+pub const DESIGN_SIZE: i32 = 360;
+
+/// 把设计稿（360x360）中的坐标或长度换算到当前屏幕
+///
+/// 按短边等比缩放，360x360的屏幕上原样返回。
+pub const fn scaled(value: i32) -> i32 {
+    let size = if SCREEN_WIDTH < SCREEN_HEIGHT {
+        SCREEN_WIDTH
+    } else {
+        SCREEN_HEIGHT
+    };
+    value * size / DESIGN_SIZE
+}
 
 /// 四个角的坐标
 pub const TOP_LEFT: (i32, i32) = (0, 0);
@@ -20,8 +40,8 @@ pub const BOTTOM_CENTER: (i32, i32) = (SCREEN_CENTER_X, SCREEN_HEIGHT - 1);
 pub const LEFT_CENTER: (i32, i32) = (0, SCREEN_CENTER_Y);
 pub const RIGHT_CENTER: (i32, i32) = (SCREEN_WIDTH - 1, SCREEN_CENTER_Y);
 
-/// 九宫格布局坐标 (每个区域120x120像素)
-pub const GRID_SIZE: i32 = 120;
+/// 九宫格布局坐标 (每个区域为屏幕的三分之一)
+pub const GRID_SIZE: i32 = SCREEN_WIDTH / 3;
 
 // 九宫格左上角坐标
 pub const GRID_TOP_LEFT: (i32, i32) = (0, 0);
@@ -50,9 +70,9 @@ pub const GRID_CENTER_BOTTOM_RIGHT: (i32, i32) =
     (GRID_SIZE * 2 + GRID_SIZE / 2, GRID_SIZE * 2 + GRID_SIZE / 2);
 
 /// 常用边距
-pub const MARGIN_SMALL: i32 = 10;
-pub const MARGIN_MEDIUM: i32 = 20;
-pub const MARGIN_LARGE: i32 = 30;
+pub const MARGIN_SMALL: i32 = scaled(10);
+pub const MARGIN_MEDIUM: i32 = scaled(20);
+pub const MARGIN_LARGE: i32 = scaled(30);
 
 /// 内容区域（带边距）
 pub const CONTENT_AREA_START_X: i32 = MARGIN_MEDIUM;
@@ -63,10 +83,10 @@ pub const CONTENT_AREA_WIDTH: i32 = CONTENT_AREA_END_X - CONTENT_AREA_START_X;
 pub const CONTENT_AREA_HEIGHT: i32 = CONTENT_AREA_END_Y - CONTENT_AREA_START_Y;
 
 /// 圆形区域相关常量
-pub const CIRCLE_RADIUS_SMALL: i32 = 20;
-pub const CIRCLE_RADIUS_MEDIUM: i32 = 40;
-pub const CIRCLE_RADIUS_LARGE: i32 = 60;
-pub const CIRCLE_RADIUS_EXTRA_LARGE: i32 = 80;
+pub const CIRCLE_RADIUS_SMALL: i32 = scaled(20);
+pub const CIRCLE_RADIUS_MEDIUM: i32 = scaled(40);
+pub const CIRCLE_RADIUS_LARGE: i32 = scaled(60);
+pub const CIRCLE_RADIUS_EXTRA_LARGE: i32 = scaled(80);
 
 /// 文字相关常量
 pub const TEXT_LINE_HEIGHT: i32 = 22; // 基于10x20字体
@@ -118,6 +138,108 @@ impl GridPosition {
     }
 }
 
+/// 圆形屏幕布局
+///
+/// 圆形面板的四角在物理上不可见，方形布局常量放在角落附近的内容会被切掉。
+/// 这里按圆的几何关系计算每一行的可见宽度、内切安全区和极坐标位置。
+/// 角度以12点钟方向为0度，顺时针增加，与`draw_arc`一致。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircularLayout {
+    pub center_x: i32,
+    pub center_y: i32,
+    pub radius: i32,
+}
+
+/// 整个屏幕对应的圆
+pub const SCREEN_CIRCLE: CircularLayout =
+    CircularLayout::new(SCREEN_CENTER_X, SCREEN_CENTER_Y, SCREEN_WIDTH / 2);
+
+impl CircularLayout {
+    pub const fn new(center_x: i32, center_y: i32, radius: i32) -> Self {
+        Self {
+            center_x,
+            center_y,
+            radius,
+        }
+    }
+
+    /// 某一行的可见宽度（弦长），圆外的行为0
+    pub fn chord_width(&self, y: i32) -> i32 {
+        let dy = (y - self.center_y) as f32;
+        let r = self.radius as f32;
+        if dy.abs() >= r {
+            return 0;
+        }
+        (2.0 * (r * r - dy * dy).sqrt()) as i32
+    }
+
+    /// 某一行内缩`inset`后的可见范围
+    ///
+    /// # 返回值
+    /// (起始x, 结束x)，结束x不含；可见宽度不足时返回None
+    pub fn chord_span(&self, y: i32, inset: i32) -> Option<(i32, i32)> {
+        let half = self.chord_width(y) / 2 - inset;
+        if half <= 0 {
+            return None;
+        }
+        Some((self.center_x - half, self.center_x + half))
+    }
+
+    /// 一段水平带状区域内每一行都可见的矩形
+    ///
+    /// 可见宽度取带内离圆心最远的一行。
+    ///
+    /// # 参数
+    /// * `y` - 带状区域顶部
+    /// * `height` - 带状区域高度
+    /// * `inset` - 左右额外留白
+    pub fn safe_band(&self, y: i32, height: i32, inset: i32) -> Option<ScreenRect> {
+        let top = y - self.center_y;
+        let bottom = y + height - 1 - self.center_y;
+        let farthest = if top.abs() > bottom.abs() {
+            y
+        } else {
+            y + height - 1
+        };
+        let (start, end) = self.chord_span(farthest, inset)?;
+        Some(ScreenRect::new(start, y, end - start, height))
+    }
+
+    /// 内切正方形安全区
+    ///
+    /// # 参数
+    /// * `inset` - 在圆边缘向内额外留出的距离
+    pub fn safe_area(&self, inset: i32) -> ScreenRect {
+        let half = ((self.radius - inset).max(0) as f32 / std::f32::consts::SQRT_2) as i32;
+        ScreenRect::new(
+            self.center_x - half,
+            self.center_y - half,
+            half * 2,
+            half * 2,
+        )
+    }
+
+    /// 极坐标转屏幕坐标
+    ///
+    /// # 参数
+    /// * `angle_deg` - 角度，12点钟方向为0度，顺时针增加
+    /// * `radius` - 到圆心的距离
+    pub fn polar(&self, angle_deg: f32, radius: i32) -> (i32, i32) {
+        let (sin, cos) = angle_deg.to_radians().sin_cos();
+        (
+            self.center_x + (radius as f32 * sin).round() as i32,
+            self.center_y - (radius as f32 * cos).round() as i32,
+        )
+    }
+
+    /// 点是否在可见圆内
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        let dx = x - self.center_x;
+        let dy = y - self.center_y;
+        dx * dx + dy * dy <= self.radius * self.radius
+    }
+}
+
 /// 屏幕区域定义
 #[derive(Debug, Clone, Copy)]
 pub struct ScreenRect {
@@ -170,3 +292,68 @@ pub const STATUS_BAR: ScreenRect = ScreenRect {
     width: SCREEN_WIDTH,
     height: 30,
 };
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::{
+        geometry::Point,
+        mono_font::{ascii::FONT_10X20, MonoTextStyleBuilder},
+        pixelcolor::{Rgb565, RgbColor},
+        text::Text,
+        Drawable,
+    };
+
+    use super::*;
+    use crate::testing::MockDrawTarget;
+
+    #[test]
+    fn test_circular_layout_geometry() {
+        let layout = SCREEN_CIRCLE;
+        assert_eq!(layout.chord_width(SCREEN_CENTER_Y), SCREEN_WIDTH);
+        assert_eq!(layout.chord_width(0), 0);
+        assert_eq!(
+            layout.polar(0.0, 100),
+            (SCREEN_CENTER_X, SCREEN_CENTER_Y - 100)
+        );
+        assert_eq!(
+            layout.polar(90.0, 100),
+            (SCREEN_CENTER_X + 100, SCREEN_CENTER_Y)
+        );
+        assert_eq!(scaled(DESIGN_SIZE), SCREEN_WIDTH.min(SCREEN_HEIGHT));
+
+        let safe = layout.safe_area(0);
+        assert!(layout.contains(safe.x, safe.y));
+        let (x, y) = safe.bottom_right();
+        assert!(layout.contains(x, y));
+
+        let band = layout.safe_band(30, 30, 0).unwrap();
+        assert!(layout.contains(band.x, band.y));
+        assert!(layout.contains(band.x + band.width - 1, band.y));
+    }
+
+    #[test]
+    fn test_text_metrics_match_font() {
+        let style = MonoTextStyleBuilder::new()
+            .font(&FONT_10X20)
+            .text_color(Rgb565::WHITE)
+            .background_color(Rgb565::BLACK)
+            .build();
+        let mut target = MockDrawTarget::screen();
+        Text::new("Hello", Point::new(20, 40), style)
+            .draw(&mut target)
+            .unwrap();
+
+        // 文字坐标为基线，每个字符占TEXT_CHAR_WIDTH，行高容纳一个字符单元
+        let bounds = target.drawn_bounds().unwrap();
+        assert_eq!(
+            bounds.top_left,
+            Point::new(20, 40 - FONT_10X20.baseline as i32)
+        );
+        assert_eq!(bounds.size.width as i32, 5 * TEXT_CHAR_WIDTH);
+        assert!(bounds.size.height as i32 <= TEXT_LINE_HEIGHT);
+        // 字形落在字符单元内
+        let ink = target.bounds_of(Rgb565::WHITE).unwrap();
+        assert!(bounds.contains(ink.top_left));
+        assert!(bounds.contains(ink.bottom_right().unwrap()));
+    }
+}
@@ -1,7 +1,11 @@
 pub mod animation;
+pub mod burnin;
 pub mod colors;
+pub mod framebuffer;
 pub mod helper;
 pub mod layout;
+pub mod panel_recovery;
 pub mod primitives;
 pub mod screens;
+pub mod theme;
 pub mod ui;
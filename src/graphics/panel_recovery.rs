@@ -0,0 +1,110 @@
+// 面板故障恢复：位图传输持续失败时重新初始化面板，多次无效后上报硬件错误
+//
+// QSPI线路受到干扰时面板可能停在错误状态，之后每次刷新都失败，屏幕一直显示花屏。
+// 显示线程每次刷新后把传输端口的连续失败次数交给`PanelRecovery`，由它决定是否重新初始化面板。
+// 失败次数只在传输成功时清零（重新初始化不清零），之后又新增`LCD_ERROR_THRESHOLD`次失败才再次尝试，
+// 连续`MAX_REINIT_ATTEMPTS`次都没有恢复时上报一次，之后不再尝试，直到传输重新成功。
+
+/// 触发重新初始化的连续失败次数
+pub const LCD_ERROR_THRESHOLD: u32 = 3;
+
+/// 上报硬件错误前最多重新初始化的次数
+pub const MAX_REINIT_ATTEMPTS: u32 = 3;
+
+/// 检查后应执行的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryAction {
+    /// 传输正常或失败次数未到阈值
+    None,
+    /// 重新初始化面板并重绘整屏
+    Reinit,
+    /// 多次重新初始化仍未恢复，上报硬件错误
+    Report,
+}
+
+/// 面板故障恢复状态
+#[derive(Debug, Default)]
+pub struct PanelRecovery {
+    /// 上一次处理时的连续失败次数，之后新增的失败才计入下一次尝试
+    handled_errors: u32,
+    /// 传输恢复前已经重新初始化的次数
+    attempts: u32,
+    /// 本轮故障是否已经上报
+    reported: bool,
+}
+
+impl PanelRecovery {
+    /// 根据连续失败次数决定下一步操作
+    ///
+    /// # 参数
+    /// * `errors` - 传输端口当前的连续失败次数，见`BitmapSink::transfer_errors`
+    pub fn check(&mut self, errors: u32) -> RecoveryAction {
+        // 计数变小说明中间有传输成功，面板已经恢复
+        if errors < self.handled_errors {
+            *self = Self::default();
+        }
+        if self.reported || errors - self.handled_errors < LCD_ERROR_THRESHOLD {
+            return RecoveryAction::None;
+        }
+
+        self.handled_errors = errors;
+        if self.attempts < MAX_REINIT_ATTEMPTS {
+            self.attempts += 1;
+            RecoveryAction::Reinit
+        } else {
+            self.reported = true;
+            RecoveryAction::Report
+        }
+    }
+
+    /// 当前故障中已经重新初始化的次数
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reinit_after_threshold() {
+        let mut recovery = PanelRecovery::default();
+        assert_eq!(recovery.check(0), RecoveryAction::None);
+        assert_eq!(
+            recovery.check(LCD_ERROR_THRESHOLD - 1),
+            RecoveryAction::None
+        );
+        assert_eq!(recovery.check(LCD_ERROR_THRESHOLD), RecoveryAction::Reinit);
+        assert_eq!(recovery.attempts(), 1);
+
+        // 没有新增失败时不再重复初始化
+        assert_eq!(recovery.check(LCD_ERROR_THRESHOLD), RecoveryAction::None);
+
+        // 传输成功后计数清零，下一次故障重新计算尝试次数
+        assert_eq!(recovery.check(0), RecoveryAction::None);
+        assert_eq!(recovery.attempts(), 0);
+        assert_eq!(recovery.check(LCD_ERROR_THRESHOLD), RecoveryAction::Reinit);
+        assert_eq!(recovery.attempts(), 1);
+    }
+
+    #[test]
+    fn test_report_once_when_reinit_does_not_help() {
+        let mut recovery = PanelRecovery::default();
+        let mut errors = 0;
+        for _ in 0..MAX_REINIT_ATTEMPTS {
+            errors += LCD_ERROR_THRESHOLD;
+            assert_eq!(recovery.check(errors), RecoveryAction::Reinit);
+        }
+
+        errors += LCD_ERROR_THRESHOLD;
+        assert_eq!(recovery.check(errors), RecoveryAction::Report);
+        // 上报后继续失败也不再尝试
+        errors += LCD_ERROR_THRESHOLD * 10;
+        assert_eq!(recovery.check(errors), RecoveryAction::None);
+
+        // 传输恢复后下一次故障重新开始
+        assert_eq!(recovery.check(1), RecoveryAction::None);
+        assert_eq!(recovery.check(LCD_ERROR_THRESHOLD), RecoveryAction::Reinit);
+    }
+}
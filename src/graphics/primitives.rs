@@ -1,29 +1,68 @@
 use anyhow::Result;
 use embedded_graphics::{
-    geometry::{Dimensions, Point, Size},
+    draw_target::{DrawTarget, DrawTargetExt, Translated},
+    geometry::{AngleUnit, Dimensions, OriginDimensions, Point, Size},
     image::Image,
     mono_font::{jis_x0201::FONT_10X20, MonoTextStyle},
     pixelcolor::Rgb565,
-    primitives::{Circle, PrimitiveStyle, Rectangle, Styled},
+    primitives::{
+        Arc, Circle, CornerRadii, Line, PrimitiveStyle, Rectangle, RoundedRectangle, Styled,
+    },
     text::{renderer::CharacterStyle, Text, TextStyleBuilder},
-    Drawable,
+    Drawable, Pixel,
 };
 use tinybmp::Bmp;
 
 use crate::{
     graphics::{
+        framebuffer::{FrameBuffer, SharedFrameBuffer, FRAMEBUFFER_COLOR_DEPTH},
         layout::{GridPosition, ScreenRect},
-        ui::traits::UIComponent,
+        panel_recovery::MAX_REINIT_ATTEMPTS,
+        ui::{
+            emoji::{self, Emoji, TextRun, EMOJI_SIZE},
+            traits::UIComponent,
+        },
     },
-    peripherals::st77916::lcd::{LcdController, LCD_HEIGHT, LCD_WIDTH},
+    hal::DisplayDevice,
+    peripherals::st77916::orientation::DisplayOrientation,
 };
 
+/// 帧缓冲区的刷新通道
+///
+/// 把帧缓冲区中修改过的区域发送到屏幕，当前实现是显示线程（`DisplayActorManager`）。
+pub trait FrameFlusher {
+    /// 请求刷新，立即返回
+    ///
+    /// 仍在发送上一帧时跳过本次请求，脏区域留到下一次刷新。
+    fn request_flush(&self) -> Result<()>;
+
+    /// 等待已请求的刷新发送完成
+    ///
+    /// 发送面板命令（如修改方向）前调用，避免命令插在像素传输中间。
+    fn wait_idle(&self) -> Result<()>;
+
+    /// 是否仍在发送上一帧
+    fn is_busy(&self) -> bool;
+
+    /// 取出跳过的刷新次数并清零
+    fn take_skipped_frames(&self) -> u32;
+
+    /// 多次重新初始化面板后传输是否仍然失败，取出后清除，同一次故障只返回一次true
+    fn take_panel_failure(&self) -> bool;
+}
+
 /// 图形基元绘制器
 ///
 /// 提供基于embedded-graphics库的图形绘制功能，包括图像、圆形、文本等基本图形的绘制。
-/// 所有绘制操作都通过内部的LCD控制器来执行。
+/// 所有绘制操作先写入内部帧缓冲区，调用`flush`后由显示线程刷新到LCD。
+/// 帧缓冲区的颜色深度由`FRAMEBUFFER_COLOR_DEPTH`决定，对调用方透明。
 pub struct GraphicsPrimitives<'a> {
-    lcd: &'a mut LcdController,
+    lcd: &'a mut dyn DisplayDevice,
+    framebuffer: SharedFrameBuffer,
+    /// 显示线程，负责把帧缓冲区传输到LCD
+    display: Box<dyn FrameFlusher>,
+    /// 全局绘制偏移，用于防烧屏像素位移（fill_screen不受影响）
+    offset: Point,
 }
 
 impl<'a> GraphicsPrimitives<'a> {
@@ -31,23 +70,150 @@ impl<'a> GraphicsPrimitives<'a> {
     ///
     /// # 参数
     ///
-    /// * `lcd` - LCD控制器的可变引用，用于执行实际的绘制操作
+    /// * `lcd` - 屏幕的可变引用，用于修改显示方向
+    /// * `start_display` - 用屏幕与新建的帧缓冲区启动刷新通道
     ///
     /// # 返回值
     ///
-    /// 返回绑定到指定LCD控制器的GraphicsPrimitives实例
+    /// 返回绑定到指定屏幕的GraphicsPrimitives实例
     ///
     /// # 示例
     ///
     /// ```rust,no_run
+    /// use crate::boards::SPEC;
     /// use crate::graphics::primitives::GraphicsPrimitives;
-    /// use crate::lcd::LcdController;
+    /// use crate::peripherals::st77916::{lcd::LcdController, orientation::DisplayOrientation};
     ///
-    /// let mut lcd = LcdController::new(/* 参数 */);
-    /// let mut graphics = GraphicsPrimitives::new(&mut lcd);
+    /// let mut lcd = LcdController::new(backlight, DisplayOrientation::default(), SPEC.lcd, None)?;
+    /// let mut graphics = GraphicsPrimitives::new(&mut lcd, |lcd, framebuffer| {
+    ///     Ok(Box::new(DisplayActorManager::new(lcd, framebuffer, None)?))
+    /// })?;
     /// ```
-    pub fn new(lcd: &'a mut LcdController) -> Self {
-        Self { lcd }
+    pub fn new(
+        lcd: &'a mut dyn DisplayDevice,
+        start_display: impl FnOnce(
+            &dyn DisplayDevice,
+            SharedFrameBuffer,
+        ) -> Result<Box<dyn FrameFlusher>>,
+    ) -> Result<Self> {
+        let framebuffer = FrameBuffer::new(lcd.width(), lcd.height(), FRAMEBUFFER_COLOR_DEPTH)?;
+        log::info!(
+            "帧缓冲区: {:?}, 占用 {} 字节",
+            framebuffer.depth(),
+            framebuffer.memory_usage()
+        );
+        let framebuffer = SharedFrameBuffer::new(framebuffer);
+        let display = start_display(lcd, framebuffer.clone())?;
+
+        Ok(Self {
+            lcd,
+            framebuffer,
+            display,
+            offset: Point::zero(),
+        })
+    }
+
+    /// 请求把帧缓冲区中的修改刷新到LCD，不等待传输
+    ///
+    /// 只传输自上次刷新以来被修改的区域，没有修改时不产生任何传输。
+    /// 显示线程仍在发送上一帧时跳过本次刷新，修改留到下一次刷新。
+    pub fn flush(&mut self) -> Result<()> {
+        if !self.framebuffer.lock().is_dirty() {
+            return Ok(());
+        }
+        self.display.request_flush()
+    }
+
+    /// 显示线程是否仍在发送上一次刷新的数据
+    pub fn is_flush_pending(&self) -> bool {
+        self.display.is_busy()
+    }
+
+    /// 取出因显示线程忙而跳过的刷新次数并清零
+    pub fn take_skipped_frames(&self) -> u32 {
+        self.display.take_skipped_frames()
+    }
+
+    /// 检查显示线程是否多次重新初始化面板仍无法传输
+    ///
+    /// 面板恢复由显示线程完成（见`DisplayActor`），这里只取出结果，可以在主线程中定期调用。
+    ///
+    /// # 返回值
+    /// 多次重新初始化仍未恢复时返回错误，同一次故障只返回一次
+    pub fn check_panel(&self) -> Result<()> {
+        if self.display.take_panel_failure() {
+            anyhow::bail!("LCD重新初始化{}次后仍无法传输", MAX_REINIT_ATTEMPTS);
+        }
+        Ok(())
+    }
+
+    /// 修改显示方向
+    ///
+    /// 逻辑分辨率变化时重新分配帧缓冲区，否则标记整屏重绘。
+    /// 之后所有绘制坐标都基于新方向的左上角。
+    pub fn set_orientation(&mut self, orientation: DisplayOrientation) -> Result<()> {
+        // 方向命令不能插在像素传输中间
+        self.display.wait_idle()?;
+        self.lcd.set_orientation(orientation)?;
+
+        let mut framebuffer = self.framebuffer.lock();
+        let size = framebuffer.size();
+        if size.width as i32 != self.lcd.width() || size.height as i32 != self.lcd.height() {
+            *framebuffer =
+                FrameBuffer::new(self.lcd.width(), self.lcd.height(), FRAMEBUFFER_COLOR_DEPTH)?;
+        }
+        framebuffer.invalidate();
+        Ok(())
+    }
+
+    /// 打开或关闭屏幕，帧缓冲区内容不变
+    pub fn set_power(&mut self, on: bool) -> Result<()> {
+        // 面板命令不能插在像素传输中间
+        self.display.wait_idle()?;
+        self.lcd.set_power(on)
+    }
+
+    /// 设置背光亮度（0-100）
+    pub fn set_brightness(&mut self, percent: u8) -> Result<()> {
+        self.lcd.set_brightness(percent)
+    }
+
+    /// 截屏：将当前帧以BMP格式写入
+    pub fn capture_bmp<W: std::io::Write>(&self, writer: &mut W) -> Result<()> {
+        self.framebuffer.lock().write_bmp(writer)
+    }
+
+    /// 与显示线程共享的帧缓冲区，供屏幕镜像读取
+    pub fn shared_framebuffer(&self) -> SharedFrameBuffer {
+        self.framebuffer.clone()
+    }
+
+    /// 当前显示方向
+    pub fn orientation(&self) -> DisplayOrientation {
+        self.lcd.orientation()
+    }
+
+    /// 设置全局绘制偏移
+    ///
+    /// 之后的所有绘制操作（fill_screen除外）都会整体平移指定像素，
+    /// 调用方无需关心偏移的存在。
+    ///
+    /// # 参数
+    ///
+    /// * `dx` - X方向偏移
+    /// * `dy` - Y方向偏移
+    pub fn set_offset(&mut self, dx: i32, dy: i32) {
+        self.offset = Point::new(dx, dy);
+    }
+
+    /// 获取当前全局绘制偏移
+    pub fn offset(&self) -> (i32, i32) {
+        (self.offset.x, self.offset.y)
+    }
+
+    /// 获取应用了全局偏移的绘制目标
+    fn target(&mut self) -> Translated<'_, SharedFrameBuffer> {
+        self.framebuffer.translated(self.offset)
     }
 
     /// 绘制RGB565格式的BMP图片
@@ -76,7 +242,7 @@ impl<'a> GraphicsPrimitives<'a> {
     /// graphics.draw_image(&bmp, 10, 20)?;
     /// ```
     pub fn draw_image(&mut self, image: &Bmp<Rgb565>, x: i32, y: i32) -> Result<()> {
-        Image::new(image, Point::new(x, y)).draw(self.lcd)?;
+        Image::new(image, Point::new(x, y)).draw(&mut self.target())?;
         Ok(())
     }
 
@@ -130,7 +296,7 @@ impl<'a> GraphicsPrimitives<'a> {
 
         let style = PrimitiveStyle::with_fill(color);
         let styled_circle = Styled::new(circle, style);
-        styled_circle.draw(self.lcd)?;
+        styled_circle.draw(&mut self.target())?;
 
         Ok(())
     }
@@ -154,6 +320,7 @@ impl<'a> GraphicsPrimitives<'a> {
     /// # 字体信息
     ///
     /// 使用的字体是FONT_10X20，每个字符的尺寸为10x20像素。
+    /// 常用emoji替换为20x20的表情图片，占两个字符宽度，见`ui::emoji`。
     ///
     /// # 示例
     ///
@@ -177,8 +344,56 @@ impl<'a> GraphicsPrimitives<'a> {
 
         let text_style = TextStyleBuilder::new().build();
 
-        let text_obj = Text::with_text_style(text, Point::new(x, y), character_style, text_style);
-        text_obj.draw(self.lcd)?;
+        if emoji::is_plain(text) {
+            let text_obj =
+                Text::with_text_style(text, Point::new(x, y), character_style, text_style);
+            text_obj.draw(&mut self.target())?;
+            return Ok(());
+        }
+
+        // 文字与表情图片依次排列，图片与字符单元上下对齐
+        let mut position = Point::new(x, y);
+        for run in emoji::shape(text) {
+            match run {
+                TextRun::Text(text) => {
+                    let text_obj =
+                        Text::with_text_style(text, position, character_style, text_style);
+                    position = text_obj.draw(&mut self.target())?;
+                }
+                TextRun::Emoji(image) => {
+                    let top = position.y - FONT_10X20.baseline as i32;
+                    self.draw_emoji(image, position.x, top, color, background_color)?;
+                    position.x += EMOJI_SIZE;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 绘制表情图片
+    ///
+    /// # 参数
+    ///
+    /// * `image` - 表情图片
+    /// * `x` - 图片左上角X坐标
+    /// * `y` - 图片左上角Y坐标
+    /// * `color` - 文字颜色，图片中跟随文字颜色的像素使用
+    /// * `background_color` - 可选背景色，填充透明像素
+    pub fn draw_emoji(
+        &mut self,
+        image: &Emoji,
+        x: i32,
+        y: i32,
+        color: Rgb565,
+        background_color: Option<Rgb565>,
+    ) -> Result<()> {
+        if let Some(background) = background_color {
+            self.fill_rect(&ScreenRect::new(x, y, EMOJI_SIZE, EMOJI_SIZE), background)?;
+        }
+        let pixels = image
+            .pixels(color)
+            .map(|(col, row, c)| Pixel(Point::new(x + col, y + row), c));
+        self.target().draw_iter(pixels)?;
         Ok(())
     }
 
@@ -212,11 +427,10 @@ impl<'a> GraphicsPrimitives<'a> {
     /// graphics.fill_screen(RED)?;
     /// ```
     pub fn fill_screen(&mut self, color: Rgb565) -> Result<()> {
-        let screen_size = Size::new(LCD_WIDTH as u32, LCD_HEIGHT as u32);
-        let rectangle = Rectangle::new(Point::zero(), screen_size);
+        let rectangle = Rectangle::new(Point::zero(), self.framebuffer.size());
         let style = PrimitiveStyle::with_fill(color);
         let styled_rectangle = Styled::new(rectangle, style);
-        styled_rectangle.draw(self.lcd)?;
+        styled_rectangle.draw(&mut self.framebuffer)?;
         Ok(())
     }
 
@@ -349,7 +563,7 @@ impl<'a> GraphicsPrimitives<'a> {
         );
         let style = PrimitiveStyle::with_fill(color);
         let styled_rectangle = Styled::new(rectangle, style);
-        styled_rectangle.draw(self.lcd)?;
+        styled_rectangle.draw(&mut self.target())?;
         Ok(())
     }
 
@@ -384,7 +598,7 @@ impl<'a> GraphicsPrimitives<'a> {
         );
         let style = PrimitiveStyle::with_stroke(color, thickness);
         let styled_rectangle = Styled::new(rectangle, style);
-        styled_rectangle.draw(self.lcd)?;
+        styled_rectangle.draw(&mut self.target())?;
         Ok(())
     }
 
@@ -433,10 +647,10 @@ impl<'a> GraphicsPrimitives<'a> {
         color: Rgb565,
         background_color: Option<Rgb565>,
     ) -> Result<()> {
-        use crate::graphics::layout::{SCREEN_CENTER_X, SCREEN_CENTER_Y};
+        use crate::graphics::layout::{SCREEN_CENTER_X, SCREEN_CENTER_Y, TEXT_CHAR_WIDTH};
 
         // 计算文本尺寸并调整位置使其居中
-        let text_width = text.len() as i32 * 10; // 每个字符10像素宽
+        let text_width = emoji::text_cells(text) as i32 * TEXT_CHAR_WIDTH;
         let text_height = 20; // 字体高度20像素
 
         let text_x = SCREEN_CENTER_X - text_width / 2;
@@ -520,11 +734,170 @@ impl<'a> GraphicsPrimitives<'a> {
 
         let style = PrimitiveStyle::with_stroke(color, thickness);
         let styled_circle = Styled::new(circle, style);
-        styled_circle.draw(self.lcd)?;
+        styled_circle.draw(&mut self.target())?;
 
         Ok(())
     }
 
+    /// 绘制圆弧
+    ///
+    /// 角度以12点钟方向为0度，顺时针增加。
+    ///
+    /// # 参数
+    ///
+    /// * `center_x` - 圆心X坐标
+    /// * `center_y` - 圆心Y坐标
+    /// * `radius` - 圆弧半径（到线条中心）
+    /// * `start_deg` - 起始角度
+    /// * `sweep_deg` - 扫过的角度，负数为逆时针
+    /// * `color` - 线条颜色
+    /// * `thickness` - 线条粗细
+    ///
+    /// # 示例
+    ///
+    /// ```rust,no_run
+    /// use crate::graphics::colors::GREEN;
+    ///
+    /// // 绘制右上四分之一圆弧
+    /// graphics.draw_arc(180, 180, 100, 0.0, 90.0, GREEN, 8)?;
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_arc(
+        &mut self,
+        center_x: i32,
+        center_y: i32,
+        radius: i32,
+        start_deg: f32,
+        sweep_deg: f32,
+        color: Rgb565,
+        thickness: u32,
+    ) -> Result<()> {
+        if radius <= 0 {
+            anyhow::bail!("半径必须为正数，当前为 {}", radius);
+        }
+        if sweep_deg == 0.0 {
+            return Ok(());
+        }
+
+        // embedded-graphics以3点钟方向为0度
+        let arc = Arc::with_center(
+            Point::new(center_x, center_y),
+            (radius * 2) as u32,
+            (start_deg - 90.0).deg(),
+            sweep_deg.deg(),
+        );
+        let style = PrimitiveStyle::with_stroke(color, thickness);
+        Styled::new(arc, style).draw(&mut self.target())?;
+
+        Ok(())
+    }
+
+    /// 绘制直线
+    ///
+    /// # 参数
+    ///
+    /// * `start` - 起点坐标(x, y)
+    /// * `end` - 终点坐标(x, y)
+    /// * `color` - 线条颜色
+    /// * `thickness` - 线条粗细
+    ///
+    /// # 示例
+    ///
+    /// ```rust,no_run
+    /// use crate::graphics::colors::RED;
+    ///
+    /// // 从屏幕中心向右上方画一条线
+    /// graphics.draw_line((180, 180), (280, 80), RED, 3)?;
+    /// ```
+    pub fn draw_line(
+        &mut self,
+        start: (i32, i32),
+        end: (i32, i32),
+        color: Rgb565,
+        thickness: u32,
+    ) -> Result<()> {
+        let line = Line::new(Point::new(start.0, start.1), Point::new(end.0, end.1));
+        let style = PrimitiveStyle::with_stroke(color, thickness);
+        Styled::new(line, style).draw(&mut self.target())?;
+
+        Ok(())
+    }
+
+    /// 绘制圆角矩形（填充）
+    ///
+    /// # 参数
+    ///
+    /// * `rect` - 矩形区域
+    /// * `corner_radius` - 圆角半径，等于高度一半时为胶囊形按钮
+    /// * `color` - 填充颜色
+    ///
+    /// # 示例
+    ///
+    /// ```rust,no_run
+    /// use crate::graphics::{colors::BLUE, layout::ScreenRect};
+    ///
+    /// // 绘制胶囊形按钮
+    /// graphics.fill_rounded_rect(&ScreenRect::new(120, 280, 120, 40), 20, BLUE)?;
+    /// ```
+    pub fn fill_rounded_rect(
+        &mut self,
+        rect: &ScreenRect,
+        corner_radius: u32,
+        color: Rgb565,
+    ) -> Result<()> {
+        let rectangle = Rectangle::new(
+            Point::new(rect.x, rect.y),
+            Size::new(rect.width as u32, rect.height as u32),
+        );
+        let rounded = RoundedRectangle::new(
+            rectangle,
+            CornerRadii::new(Size::new(corner_radius, corner_radius)),
+        );
+        let style = PrimitiveStyle::with_fill(color);
+        Styled::new(rounded, style).draw(&mut self.target())?;
+
+        Ok(())
+    }
+
+    /// 绘制进度环
+    ///
+    /// 先画完整的底环，再从12点钟方向顺时针画出已完成的部分。
+    ///
+    /// # 参数
+    ///
+    /// * `center_x` - 圆心X坐标
+    /// * `center_y` - 圆心Y坐标
+    /// * `radius` - 环半径（到线条中心）
+    /// * `percent_completed` - 完成百分比（0-100，超出范围会被截断）
+    /// * `color` - 已完成部分的颜色
+    /// * `track_color` - 底环颜色
+    /// * `thickness` - 环的粗细
+    ///
+    /// # 示例
+    ///
+    /// ```rust,no_run
+    /// use crate::graphics::colors::{GRAY, GREEN};
+    ///
+    /// // 在屏幕中心绘制完成75%的进度环
+    /// graphics.draw_progress_ring(180, 180, 150, 75, GREEN, GRAY, 12)?;
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_progress_ring(
+        &mut self,
+        center_x: i32,
+        center_y: i32,
+        radius: i32,
+        percent_completed: u8,
+        color: Rgb565,
+        track_color: Rgb565,
+        thickness: u32,
+    ) -> Result<()> {
+        self.draw_circle_border(center_x, center_y, radius, track_color, thickness)?;
+
+        let sweep = percent_completed.min(100) as f32 * 3.6;
+        self.draw_arc(center_x, center_y, radius, 0.0, sweep, color, thickness)
+    }
+
     /// 清除九宫格指定区域
     ///
     /// 用指定颜色清除九宫格的指定区域。
@@ -551,6 +924,53 @@ impl<'a> GraphicsPrimitives<'a> {
         self.fill_rect(&rect, color)
     }
 
+    /// 绘制单色位图图标
+    ///
+    /// 按行绘制位图，每行一个u16，最高位对应最左侧像素。置位的像素使用前景色，
+    /// 未置位的像素使用背景色（背景色为None时保持透明）。
+    ///
+    /// # 参数
+    ///
+    /// * `rows` - 位图行数据，宽度最多16像素
+    /// * `width` - 图标宽度（像素，1-16）
+    /// * `x` - 图标左上角X坐标
+    /// * `y` - 图标左上角Y坐标
+    /// * `color` - 前景色
+    /// * `background_color` - 可选背景色
+    ///
+    /// # 示例
+    ///
+    /// ```rust,no_run
+    /// use crate::graphics::ui::icons::{WIFI_ICONS, WIFI_ICON_WIDTH};
+    /// use crate::graphics::colors::{BLACK, WHITE};
+    ///
+    /// graphics.draw_glyph(&WIFI_ICONS[3], WIFI_ICON_WIDTH, 200, 10, BLACK, Some(WHITE))?;
+    /// ```
+    pub fn draw_glyph(
+        &mut self,
+        rows: &[u16],
+        width: i32,
+        x: i32,
+        y: i32,
+        color: Rgb565,
+        background_color: Option<Rgb565>,
+    ) -> Result<()> {
+        if width <= 0 || width > 16 {
+            anyhow::bail!("图标宽度必须在1-16之间，当前为 {}", width);
+        }
+
+        let pixels = rows.iter().enumerate().flat_map(|(row, bits)| {
+            (0..width).filter_map(move |col| {
+                let lit = bits & (0x8000 >> col) != 0;
+                let pixel_color = if lit { Some(color) } else { background_color };
+                pixel_color.map(|c| Pixel(Point::new(x + col, y + row as i32), c))
+            })
+        });
+
+        self.target().draw_iter(pixels)?;
+        Ok(())
+    }
+
     /// 绘制UI组件
     ///
     /// 使用UI组件的render方法来绘制组件。
@@ -0,0 +1,84 @@
+use crate::{
+    graphics::{
+        layout::{scaled, SCREEN_CENTER_X},
+        primitives::GraphicsPrimitives,
+        theme,
+    },
+    peripherals::storage::format_bytes,
+};
+
+/// 固件版本
+pub const FIRMWARE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 构建时的git提交（由build.rs注入），不在git仓库中构建时为"unknown"
+pub const GIT_HASH: &str = match option_env!("GIT_HASH") {
+    Some(hash) => hash,
+    None => "unknown",
+};
+
+/// 关于界面显示的设备信息
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AboutInfo {
+    /// ESP-IDF版本
+    pub idf_version: String,
+    /// WiFi STA的MAC地址，读取失败时为None
+    pub mac: Option<[u8; 6]>,
+    /// 当前IP地址，未连接时为None
+    pub ip: Option<String>,
+    /// 可用内存（字节）
+    pub free_heap: usize,
+}
+
+/// 将MAC地址格式化为"AA:BB:CC:DD:EE:FF"
+fn format_mac(mac: &[u8; 6]) -> String {
+    mac.iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// 更新关于界面
+///
+/// # 参数
+/// * `info` - 设备信息
+pub fn draw(graphics: &mut GraphicsPrimitives, info: &AboutInfo) -> anyhow::Result<()> {
+    let theme = theme::current();
+    graphics.draw_text(
+        "关于",
+        SCREEN_CENTER_X,
+        scaled(50),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+
+    let lines = [
+        format!("固件: v{} ({})", FIRMWARE_VERSION, GIT_HASH),
+        format!("ESP-IDF: {}", info.idf_version),
+        format!(
+            "MAC: {}",
+            info.mac.as_ref().map_or("未知".to_string(), format_mac)
+        ),
+        format!("IP: {}", info.ip.as_deref().unwrap_or("未连接")),
+        format!("可用内存: {}", format_bytes(info.free_heap as u64)),
+    ];
+    for (index, line) in lines.iter().enumerate() {
+        graphics.draw_text(
+            line,
+            scaled(60),
+            scaled(110) + index as i32 * scaled(40),
+            theme.foreground,
+            Some(theme.background),
+        )?;
+    }
+
+    // 操作提示
+    graphics.draw_text(
+        "按 B 键返回",
+        SCREEN_CENTER_X,
+        scaled(330),
+        theme.accent,
+        Some(theme.background),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use crate::{
+    clock::LocalTime,
+    graphics::{
+        animation::cycle_phase,
+        layout::{scaled, SCREEN_CENTER_X, SCREEN_CENTER_Y},
+        primitives::GraphicsPrimitives,
+        theme,
+    },
+};
+
+/// 更新闹钟提醒界面：外圈每半秒闪烁一次，中间显示标签与当前时间
+///
+/// # 参数
+/// * `elapsed` - 进入界面后经过的时间
+/// * `label` - 闹钟标签
+/// * `time` - 当前时间，尚未同步时为None
+pub fn draw(
+    graphics: &mut GraphicsPrimitives,
+    elapsed: Duration,
+    label: &str,
+    time: Option<LocalTime>,
+) -> anyhow::Result<()> {
+    let theme = theme::current();
+
+    let ring_color = if cycle_phase(elapsed, Duration::from_millis(500), 2) == 0 {
+        theme.warning
+    } else {
+        theme.background
+    };
+    graphics.draw_circle_border(
+        SCREEN_CENTER_X,
+        SCREEN_CENTER_Y,
+        scaled(160),
+        ring_color,
+        scaled(12),
+    )?;
+
+    if let Some(time) = time {
+        graphics.draw_text(
+            &format!("{:02}:{:02}", time.hour, time.minute),
+            SCREEN_CENTER_X,
+            scaled(130),
+            theme.foreground,
+            Some(theme.background),
+        )?;
+    }
+    graphics.draw_text(
+        label,
+        SCREEN_CENTER_X,
+        SCREEN_CENTER_Y,
+        theme.accent,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        "按键关闭",
+        SCREEN_CENTER_X,
+        scaled(250),
+        theme.muted,
+        Some(theme.background),
+    )?;
+
+    Ok(())
+}
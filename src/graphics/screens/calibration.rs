@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use crate::graphics::{
+    layout::{scaled, SCREEN_CENTER_X, SCREEN_CENTER_Y},
+    primitives::GraphicsPrimitives,
+    theme,
+};
+
+/// 更新运动校准界面：进度环显示采样进度
+///
+/// # 参数
+/// * `elapsed` - 进入界面后经过的时间
+/// * `duration` - 采样总时长
+pub fn draw(
+    graphics: &mut GraphicsPrimitives,
+    elapsed: Duration,
+    duration: Duration,
+) -> anyhow::Result<()> {
+    let theme = theme::current();
+    let percent = (elapsed.as_millis() * 100 / duration.as_millis().max(1)).min(100) as u8;
+    graphics.draw_progress_ring(
+        SCREEN_CENTER_X,
+        SCREEN_CENTER_Y,
+        scaled(140),
+        percent,
+        theme.accent,
+        theme.surface,
+        scaled(12),
+    )?;
+
+    graphics.draw_text(
+        "动作校准",
+        SCREEN_CENTER_X,
+        scaled(160),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        "请保持静止",
+        SCREEN_CENTER_X,
+        scaled(200),
+        theme.muted,
+        Some(theme.background),
+    )?;
+
+    Ok(())
+}
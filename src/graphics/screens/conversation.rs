@@ -0,0 +1,350 @@
+use crate::graphics::{
+    layout::{scaled, SCREEN_CENTER_X, TEXT_CHAR_WIDTH, TEXT_LINE_HEIGHT},
+    primitives::GraphicsPrimitives,
+    theme,
+    ui::chat_bubble::{ChatBubble, ChatRole, BUBBLE_MAX_WIDTH},
+};
+
+/// 列表可见区域顶部
+const VIEW_TOP: i32 = scaled(60);
+/// 列表可见区域底部
+const VIEW_BOTTOM: i32 = scaled(300);
+/// 列表左右边界，圆屏上下两端较窄，宽度比气泡多出一段用于区分左右对齐
+const VIEW_LEFT: i32 = SCREEN_CENTER_X - scaled(130);
+const VIEW_RIGHT: i32 = SCREEN_CENTER_X + scaled(130);
+/// 气泡之间的间距
+const BUBBLE_GAP: i32 = scaled(8);
+
+/// 单个气泡最多显示的行数，留一行给气泡内边距，保证任何气泡都能完整放进可见区域
+const BUBBLE_MAX_LINES: usize = ((VIEW_BOTTOM - VIEW_TOP) / TEXT_LINE_HEIGHT - 1) as usize;
+
+/// 最多保留的消息数，超出后丢弃最早的消息
+const MAX_MESSAGES: usize = 20;
+
+const _: () = assert!(BUBBLE_MAX_WIDTH < VIEW_RIGHT - VIEW_LEFT);
+
+/// 对话界面
+///
+/// 保存当前会话的消息气泡。默认显示最新的消息，向上滚动查看更早的消息，
+/// 滚动以整条消息为单位。
+///
+/// 只在内容变化后绘制：消息增减、滚动或清屏后整体重绘；
+/// 流式回复追加文字时只重绘最后一个气泡中变化的行。
+pub struct ConversationView {
+    bubbles: Vec<ChatBubble>,
+    /// 底部隐藏的消息数，0表示显示到最新一条
+    scroll: usize,
+    /// 下一帧需要整体重绘
+    needs_redraw: bool,
+    /// 最后一个气泡正在接收流式回复
+    streaming: bool,
+    /// 流式气泡中需要重绘的第一行
+    stream_damage: Option<usize>,
+    /// 流式气泡超出底部后固定在顶部显示，之后增长不再需要整体重绘
+    pin_stream_top: bool,
+    /// 上次整体重绘时可见的消息下标
+    visible: Vec<usize>,
+}
+
+impl Default for ConversationView {
+    fn default() -> Self {
+        Self {
+            bubbles: Vec::new(),
+            scroll: 0,
+            needs_redraw: true,
+            streaming: false,
+            stream_damage: None,
+            pin_stream_top: false,
+            visible: Vec::new(),
+        }
+    }
+}
+
+impl ConversationView {
+    /// 追加一轮问答，并滚动到最新消息
+    ///
+    /// # 参数
+    /// * `prompt` - 用户的提问
+    /// * `reply` - 助手的回复
+    pub fn push_exchange(&mut self, prompt: &str, reply: &str) {
+        self.push(
+            ChatBubble::new(ChatRole::User, prompt, BUBBLE_MAX_LINES),
+            ChatBubble::new(ChatRole::Assistant, reply, BUBBLE_MAX_LINES),
+        );
+    }
+
+    /// 开始一轮流式回复：追加提问和一个空的回复气泡
+    ///
+    /// # 参数
+    /// * `prompt` - 用户的提问
+    pub fn begin_stream(&mut self, prompt: &str) {
+        self.push(
+            ChatBubble::new(ChatRole::User, prompt, BUBBLE_MAX_LINES),
+            ChatBubble::streaming(ChatRole::Assistant, BUBBLE_MAX_LINES),
+        );
+        self.streaming = true;
+    }
+
+    /// 向正在接收的回复追加文字
+    ///
+    /// 气泡向下增长，超出可见区域底部时整体重绘一次，把气泡移到顶部。
+    pub fn append_stream(&mut self, text: &str) {
+        if !self.streaming {
+            return;
+        }
+        let index = self.bubbles.len() - 1;
+        let Some(line) = self.bubbles[index].append(text) else {
+            return;
+        };
+
+        // 滚动到更早的消息时流式气泡不可见，回到底部时会整体重绘
+        if self.needs_redraw || !self.visible.contains(&index) {
+            return;
+        }
+        let bubble = &self.bubbles[index];
+        if bubble.top() + bubble.height() > VIEW_BOTTOM {
+            self.pin_stream_top = true;
+            self.needs_redraw = true;
+        } else {
+            self.stream_damage = Some(self.stream_damage.map_or(line, |d| d.min(line)));
+        }
+    }
+
+    /// 结束流式回复，已显示的内容保持不变
+    pub fn end_stream(&mut self) {
+        self.streaming = false;
+    }
+
+    /// 是否正在接收流式回复
+    pub fn is_streaming(&self) -> bool {
+        self.streaming
+    }
+
+    /// 追加一轮消息，丢弃超出数量的旧消息并滚动到底部
+    fn push(&mut self, prompt: ChatBubble, reply: ChatBubble) {
+        self.streaming = false;
+        self.bubbles.push(prompt);
+        self.bubbles.push(reply);
+        if self.bubbles.len() > MAX_MESSAGES {
+            let excess = self.bubbles.len() - MAX_MESSAGES;
+            self.bubbles.drain(..excess);
+        }
+        self.scroll = 0;
+        self.pin_stream_top = false;
+        self.needs_redraw = true;
+    }
+
+    /// 清空对话
+    pub fn clear(&mut self) {
+        self.bubbles.clear();
+        self.scroll = 0;
+        self.streaming = false;
+        self.pin_stream_top = false;
+        self.needs_redraw = true;
+    }
+
+    /// 屏幕已被清空，下一帧整体重绘
+    pub fn invalidate(&mut self) {
+        self.needs_redraw = true;
+    }
+
+    /// 是否没有任何消息
+    pub fn is_empty(&self) -> bool {
+        self.bubbles.is_empty()
+    }
+
+    /// 滚动列表
+    ///
+    /// # 参数
+    /// * `delta` - 正数向下（更新的消息），负数向上（更早的消息）
+    ///
+    /// # 返回值
+    /// 位置是否变化
+    pub fn scroll(&mut self, delta: i32) -> bool {
+        let max = self.bubbles.len().saturating_sub(1) as i32;
+        let scroll = (self.scroll as i32 - delta).clamp(0, max) as usize;
+        let changed = scroll != self.scroll;
+        if changed {
+            self.scroll = scroll;
+            self.pin_stream_top = false;
+            self.needs_redraw = true;
+        }
+        changed
+    }
+
+    /// 绘制对话界面，内容没有变化时不绘制
+    ///
+    /// 整体重绘不清除背景，调用方需要在此之前清屏。
+    pub fn draw(&mut self, graphics: &mut GraphicsPrimitives) -> anyhow::Result<()> {
+        if !self.needs_redraw {
+            if let (Some(line), Some(bubble)) = (self.stream_damage.take(), self.bubbles.last()) {
+                bubble.render_from(graphics, line)?;
+            }
+            return Ok(());
+        }
+        self.needs_redraw = false;
+        self.stream_damage = None;
+
+        let theme = theme::current();
+        let visible = if self.pin_stream_top && self.scroll == 0 && !self.bubbles.is_empty() {
+            vec![(self.bubbles.len() - 1, VIEW_TOP)]
+        } else {
+            let heights: Vec<i32> = self.bubbles.iter().map(ChatBubble::height).collect();
+            stack_from_bottom(&heights, self.scroll, VIEW_TOP, VIEW_BOTTOM, BUBBLE_GAP)
+        };
+        self.visible = visible.iter().map(|&(index, _)| index).collect();
+
+        for &(index, top) in &visible {
+            let bubble = &mut self.bubbles[index];
+            bubble.set_position(VIEW_LEFT, VIEW_RIGHT, top);
+            graphics.draw_component(bubble)?;
+        }
+
+        // 上下还有消息时显示箭头提示
+        let indicator_x = SCREEN_CENTER_X - TEXT_CHAR_WIDTH / 2;
+        if visible.first().is_some_and(|&(index, _)| index > 0) {
+            graphics.draw_text(
+                "^",
+                indicator_x,
+                VIEW_TOP - scaled(10),
+                theme.muted,
+                Some(theme.background),
+            )?;
+        }
+        if self.scroll > 0 {
+            graphics.draw_text(
+                "v",
+                indicator_x,
+                VIEW_BOTTOM + scaled(25),
+                theme.muted,
+                Some(theme.background),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 从底部向上依次排列，直到放不下为止
+///
+/// # 参数
+/// * `heights` - 各条消息的高度，按时间顺序
+/// * `scroll` - 底部隐藏的消息数
+/// * `top`, `bottom` - 可见区域的上下边界
+/// * `gap` - 消息之间的间距
+///
+/// # 返回值
+/// 可见消息的(下标, 顶部y)，按时间顺序
+fn stack_from_bottom(
+    heights: &[i32],
+    scroll: usize,
+    top: i32,
+    bottom: i32,
+    gap: i32,
+) -> Vec<(usize, i32)> {
+    let Some(last) = heights.len().checked_sub(scroll + 1) else {
+        return Vec::new();
+    };
+
+    let mut visible = Vec::new();
+    let mut next_bottom = bottom;
+    for index in (0..=last).rev() {
+        let y = next_bottom - heights[index];
+        // 第一条总是显示，即使高度超过可见区域
+        if y < top && !visible.is_empty() {
+            break;
+        }
+        visible.push((index, y.max(top)));
+        next_bottom = y - gap;
+    }
+    visible.reverse();
+    visible
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stack_from_bottom() {
+        assert!(stack_from_bottom(&[], 0, 0, 100, 5).is_empty());
+
+        // 只放得下最后两条
+        let heights = [30, 40, 40];
+        assert_eq!(
+            stack_from_bottom(&heights, 0, 0, 100, 5),
+            vec![(1, 15), (2, 60)]
+        );
+        // 向上滚动一条
+        assert_eq!(
+            stack_from_bottom(&heights, 1, 0, 100, 5),
+            vec![(0, 25), (1, 60)]
+        );
+        assert!(stack_from_bottom(&heights, 3, 0, 100, 5).is_empty());
+    }
+
+    #[test]
+    fn test_scroll_is_clamped() {
+        let mut view = ConversationView::default();
+        assert!(!view.scroll(-1));
+
+        view.push_exchange("hello", "hi");
+        assert!(view.scroll(-1));
+        assert!(!view.scroll(-1));
+        assert!(view.scroll(5));
+        assert_eq!(view.scroll, 0);
+    }
+
+    #[test]
+    fn test_old_messages_dropped() {
+        let mut view = ConversationView::default();
+        for _ in 0..MAX_MESSAGES {
+            view.push_exchange("q", "a");
+        }
+        assert_eq!(view.bubbles.len(), MAX_MESSAGES);
+        assert_eq!(view.bubbles[0].role(), ChatRole::User);
+    }
+
+    /// 模拟一次整体重绘后的布局
+    fn drawn_stream(reply_top: i32) -> ConversationView {
+        let mut view = ConversationView::default();
+        view.begin_stream("q");
+        view.needs_redraw = false;
+        view.visible = vec![0, 1];
+        view.bubbles[1].set_position(VIEW_LEFT, VIEW_RIGHT, reply_top);
+        view
+    }
+
+    #[test]
+    fn test_stream_damage_only_touches_changed_lines() {
+        let mut view = drawn_stream(VIEW_TOP);
+        assert!(view.is_streaming());
+
+        view.append_stream("hello");
+        assert_eq!(view.stream_damage, Some(0));
+        assert!(!view.needs_redraw);
+
+        view.stream_damage = None;
+        view.append_stream(&"x".repeat(40));
+        view.stream_damage = None;
+        view.append_stream("more");
+        assert!(view.stream_damage.is_some_and(|line| line > 0));
+
+        view.end_stream();
+        view.stream_damage = None;
+        view.append_stream("ignored");
+        assert_eq!(view.stream_damage, None);
+    }
+
+    #[test]
+    fn test_stream_overflow_pins_bubble_to_top() {
+        let mut view = drawn_stream(VIEW_BOTTOM - TEXT_LINE_HEIGHT * 2);
+        view.append_stream(&"x".repeat(80));
+        assert!(view.pin_stream_top);
+        assert!(view.needs_redraw);
+
+        // 滚动后恢复正常布局
+        view.scroll(-1);
+        assert!(!view.pin_stream_top);
+    }
+}
@@ -1,27 +1,57 @@
+use std::time::Duration;
+
 use crate::graphics::{
-    colors::{BLACK, BLUE, GREEN, RED, WHITE},
+    animation::cycle_phase,
+    layout::{scaled, SCREEN_CENTER_X},
     primitives::GraphicsPrimitives,
+    theme,
 };
 
 /// 更新晃动状态
-pub fn draw(graphics: &mut GraphicsPrimitives, state_timer: u32) -> anyhow::Result<()> {
+pub fn draw(graphics: &mut GraphicsPrimitives, elapsed: Duration) -> anyhow::Result<()> {
+    let theme = theme::current();
+
     // Draw dizziness screen
-    graphics.draw_text("Ah! So dizzy!", 180, 120, RED, Some(BLACK))?;
+    graphics.draw_text(
+        "Ah! So dizzy!",
+        SCREEN_CENTER_X,
+        scaled(120),
+        theme.error,
+        Some(theme.background),
+    )?;
 
     // Draw shaking effect text
-    let shake_text = match (state_timer / 5) % 3 {
+    let shake_text = match cycle_phase(elapsed, Duration::from_millis(250), 3) {
         0 => "Shaking...",
         1 => "Spinning...",
         2 => "Feeling dizzy...",
         _ => "Shaking...",
     };
-    graphics.draw_text(shake_text, 180, 160, WHITE, Some(BLACK))?;
+    graphics.draw_text(
+        shake_text,
+        SCREEN_CENTER_X,
+        scaled(160),
+        theme.foreground,
+        Some(theme.background),
+    )?;
 
     // Draw prompt message
-    graphics.draw_text("Please stop shaking", 180, 200, BLUE, Some(BLACK))?;
+    graphics.draw_text(
+        "Please stop shaking",
+        SCREEN_CENTER_X,
+        scaled(200),
+        theme.muted,
+        Some(theme.background),
+    )?;
 
     // Draw return hint
-    graphics.draw_text("Will return when stable", 180, 240, GREEN, Some(BLACK))?;
+    graphics.draw_text(
+        "Will return when stable",
+        SCREEN_CENTER_X,
+        scaled(240),
+        theme.accent,
+        Some(theme.background),
+    )?;
 
     Ok(())
 }
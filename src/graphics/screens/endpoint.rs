@@ -0,0 +1,92 @@
+use crate::graphics::{
+    layout::{scaled, SCREEN_CENTER_X},
+    primitives::GraphicsPrimitives,
+    theme,
+};
+
+/// 地址每行最多显示的字符数
+const CHARS_PER_LINE: usize = 28;
+
+/// 地址第一行的Y坐标
+const FIRST_LINE_Y: i32 = scaled(100);
+
+/// 地址行间距
+const LINE_SPACING: i32 = 26;
+
+/// 字符转盘的Y坐标
+const PICKER_Y: i32 = scaled(220);
+
+/// 字符转盘中相邻两项的间距
+const PICKER_SPACING: i32 = scaled(56);
+
+/// 地址输入界面显示的内容
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EndpointEditView {
+    /// 正在修改的地址名称
+    pub title: String,
+    /// 已输入的地址
+    pub text: String,
+    /// 字符转盘，选中项在正中间
+    pub picker: Vec<String>,
+    /// 保存失败的原因
+    pub error: Option<String>,
+}
+
+/// 更新地址输入界面
+///
+/// # 参数
+/// * `view` - 界面内容
+pub fn draw(graphics: &mut GraphicsPrimitives, view: &EndpointEditView) -> anyhow::Result<()> {
+    let theme = theme::current();
+    graphics.draw_text(
+        &view.title,
+        SCREEN_CENTER_X,
+        scaled(50),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+
+    // 地址按固定字符数换行，末尾显示光标
+    let text: Vec<char> = view.text.chars().chain(['_']).collect();
+    for (index, line) in text.chunks(CHARS_PER_LINE).enumerate() {
+        graphics.draw_text(
+            &line.iter().collect::<String>(),
+            SCREEN_CENTER_X,
+            FIRST_LINE_Y + index as i32 * LINE_SPACING,
+            theme.foreground,
+            Some(theme.background),
+        )?;
+    }
+
+    let middle = view.picker.len() / 2;
+    for (index, entry) in view.picker.iter().enumerate() {
+        let color = if index == middle {
+            theme.accent
+        } else {
+            theme.muted
+        };
+        let x = SCREEN_CENTER_X + (index as i32 - middle as i32) * PICKER_SPACING;
+        graphics.draw_text(entry, x, PICKER_Y, color, Some(theme.background))?;
+    }
+
+    if let Some(error) = &view.error {
+        graphics.draw_text(
+            error,
+            SCREEN_CENTER_X,
+            scaled(270),
+            theme.error,
+            Some(theme.background),
+        )?;
+    }
+
+    // 操作提示
+    graphics.draw_text(
+        "旋转选择 单击输入",
+        SCREEN_CENTER_X,
+        scaled(310),
+        theme.accent,
+        Some(theme.background),
+    )?;
+
+    Ok(())
+}
@@ -1,14 +1,47 @@
 use crate::graphics::{
-    colors::{BLACK, BLUE, RED, WHITE},
+    layout::{scaled, SCREEN_CENTER_X},
     primitives::GraphicsPrimitives,
+    theme,
 };
 
 /// 更新错误界面
-pub fn draw(graphics: &mut GraphicsPrimitives, error_msg: &str) -> anyhow::Result<()> {
+///
+/// # 参数
+/// * `retry_available` - 为true时提示按键重试
+pub fn draw(
+    graphics: &mut GraphicsPrimitives,
+    error_msg: &str,
+    retry_available: bool,
+) -> anyhow::Result<()> {
+    let theme = theme::current();
+
     // 绘制错误界面
-    graphics.draw_text("错误", 180, 100, RED, Some(BLACK))?;
-    graphics.draw_text(error_msg, 180, 140, WHITE, Some(BLACK))?;
-    graphics.draw_text("按任意键继续", 180, 220, BLUE, Some(BLACK))?;
+    graphics.draw_text(
+        "错误",
+        SCREEN_CENTER_X,
+        scaled(100),
+        theme.error,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        error_msg,
+        SCREEN_CENTER_X,
+        scaled(140),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+    let hint = if retry_available {
+        "按键重试"
+    } else {
+        "按任意键继续"
+    };
+    graphics.draw_text(
+        hint,
+        SCREEN_CENTER_X,
+        scaled(220),
+        theme.muted,
+        Some(theme.background),
+    )?;
 
     Ok(())
 }
@@ -1,8 +1,9 @@
-use crate::graphics::{colors::WHITE, primitives::GraphicsPrimitives};
+use crate::graphics::{primitives::GraphicsPrimitives, theme};
 
 /// 更新主界面
 pub fn draw(graphics: &mut GraphicsPrimitives) -> anyhow::Result<()> {
-    graphics.fill_screen(WHITE)?;
+    let theme = theme::current();
+    graphics.fill_screen(theme.background)?;
 
     Ok(())
 }
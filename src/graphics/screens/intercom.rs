@@ -0,0 +1,100 @@
+use crate::graphics::{
+    layout::{scaled, SCREEN_CENTER_X, SCREEN_CENTER_Y},
+    primitives::GraphicsPrimitives,
+    theme,
+};
+
+/// 第一行的Y坐标
+const FIRST_ROW_Y: i32 = scaled(100);
+
+/// 行间距
+const ROW_SPACING: i32 = 30;
+
+/// 一屏最多显示的设备数，列表不超过状态文字
+const VISIBLE_ITEMS: usize = ((scaled(250) - FIRST_ROW_Y) / ROW_SPACING) as usize;
+
+/// 对讲界面显示的内容
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IntercomView {
+    /// 局域网中的其他设备名称
+    pub peers: Vec<String>,
+    /// 当前选中的设备
+    pub selected: usize,
+    /// 本机正在说话
+    pub talking: bool,
+    /// 正在播放对方的语音
+    pub receiving: bool,
+}
+
+/// 更新对讲界面
+///
+/// # 参数
+/// * `view` - 界面内容
+pub fn draw(graphics: &mut GraphicsPrimitives, view: &IntercomView) -> anyhow::Result<()> {
+    let theme = theme::current();
+    graphics.draw_text(
+        "对讲",
+        SCREEN_CENTER_X,
+        scaled(50),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+
+    if view.peers.is_empty() {
+        graphics.draw_text(
+            "正在查找其他设备...",
+            SCREEN_CENTER_X,
+            SCREEN_CENTER_Y,
+            theme.warning,
+            Some(theme.background),
+        )?;
+    }
+
+    // 选中项超出一屏时整体滚动
+    let first = view.selected.saturating_sub(VISIBLE_ITEMS - 1);
+    let visible = first..view.peers.len().min(first + VISIBLE_ITEMS);
+    for (row, index) in visible.enumerate() {
+        let selected = index == view.selected;
+        let (marker, color) = if selected {
+            ("●", theme.accent)
+        } else {
+            ("○", theme.foreground)
+        };
+        graphics.draw_text(
+            &format!("{} {}", marker, view.peers[index]),
+            scaled(80),
+            FIRST_ROW_Y + row as i32 * ROW_SPACING,
+            color,
+            Some(theme.background),
+        )?;
+    }
+
+    let status = if view.talking {
+        Some(("正在说话...", theme.accent))
+    } else if view.receiving {
+        Some(("对方正在说话", theme.warning))
+    } else if !view.peers.is_empty() {
+        Some(("按住 BOOT 键说话", theme.foreground))
+    } else {
+        None
+    };
+    if let Some((status, color)) = status {
+        graphics.draw_text(
+            status,
+            SCREEN_CENTER_X,
+            scaled(280),
+            color,
+            Some(theme.background),
+        )?;
+    }
+
+    graphics.draw_text(
+        "旋转选择  按 B 键返回",
+        SCREEN_CENTER_X,
+        scaled(330),
+        theme.accent,
+        Some(theme.background),
+    )?;
+
+    Ok(())
+}
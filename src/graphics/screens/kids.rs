@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use crate::graphics::{
+    animation::cycle_phase,
+    layout::{scaled, ScreenRect, SCREEN_CENTER_X},
+    primitives::GraphicsPrimitives,
+    theme,
+};
+
+/// 手势密码进度点的间距
+const DOT_SPACING: i32 = scaled(30);
+/// 手势密码进度点的半径
+const DOT_RADIUS: i32 = scaled(8);
+
+/// 儿童模式当天时间用完后的"休息一下"界面
+///
+/// 闭着眼睛微笑的表情，右上角的"z"随计时交替出现。
+///
+/// # 参数
+/// * `elapsed` - 进入界面后经过的时间
+/// * `limit_minutes` - 每天允许的互动时长（分钟）
+pub fn draw_break(
+    graphics: &mut GraphicsPrimitives,
+    elapsed: Duration,
+    limit_minutes: u16,
+) -> anyhow::Result<()> {
+    let theme = theme::current();
+
+    // 闭眼：两条向下弯的弧线
+    graphics.draw_arc(
+        scaled(130),
+        scaled(130),
+        scaled(24),
+        20.0,
+        140.0,
+        theme.foreground,
+        scaled(6),
+    )?;
+    graphics.draw_arc(
+        scaled(230),
+        scaled(130),
+        scaled(24),
+        20.0,
+        140.0,
+        theme.foreground,
+        scaled(6),
+    )?;
+
+    // 微笑
+    graphics.draw_arc(
+        SCREEN_CENTER_X,
+        scaled(170),
+        scaled(36),
+        30.0,
+        120.0,
+        theme.accent,
+        scaled(6),
+    )?;
+
+    // 每秒多显示一个"z"，三个之后重新开始
+    graphics.fill_rect(
+        &ScreenRect::new(scaled(250), scaled(60), scaled(60), scaled(40)),
+        theme.background,
+    )?;
+    let count = cycle_phase(elapsed, Duration::from_secs(1), 4) as usize;
+    graphics.draw_text(
+        &"z".repeat(count),
+        scaled(260),
+        scaled(90),
+        theme.muted,
+        Some(theme.background),
+    )?;
+
+    graphics.draw_text(
+        "该休息一下啦",
+        SCREEN_CENTER_X,
+        scaled(240),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        &format!("今天的{}分钟用完了", limit_minutes),
+        SCREEN_CENTER_X,
+        scaled(270),
+        theme.muted,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        "明天再来玩吧",
+        SCREEN_CENTER_X,
+        scaled(295),
+        theme.muted,
+        Some(theme.background),
+    )?;
+
+    Ok(())
+}
+
+/// 儿童模式下进入设置前的家长验证界面
+///
+/// # 参数
+/// * `entered` - 已输入的手势步数
+/// * `code_len` - 手势密码的步数
+pub fn draw_unlock(
+    graphics: &mut GraphicsPrimitives,
+    entered: usize,
+    code_len: usize,
+) -> anyhow::Result<()> {
+    let theme = theme::current();
+
+    graphics.draw_text(
+        "家长验证",
+        SCREEN_CENTER_X,
+        scaled(120),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        "旋转设备输入手势密码",
+        SCREEN_CENTER_X,
+        scaled(150),
+        theme.muted,
+        Some(theme.background),
+    )?;
+
+    // 每一步一个圆点，已输入的点亮
+    let left = SCREEN_CENTER_X - (code_len.saturating_sub(1) as i32 * DOT_SPACING) / 2;
+    for index in 0..code_len {
+        let color = if index < entered {
+            theme.accent
+        } else {
+            theme.surface
+        };
+        graphics.draw_filled_circle(
+            left + index as i32 * DOT_SPACING,
+            scaled(200),
+            DOT_RADIUS,
+            color,
+        )?;
+    }
+
+    graphics.draw_text(
+        "按 B 键返回",
+        SCREEN_CENTER_X,
+        scaled(260),
+        theme.accent,
+        Some(theme.background),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::graphics::{
+    animation::cycle_phase,
+    layout::{scaled, ScreenRect, SCREEN_CENTER_X},
+    primitives::GraphicsPrimitives,
+    theme,
+};
+
+/// 波形柱数量
+const METER_COLUMNS: usize = 30;
+/// 每根柱宽度与间距
+const COLUMN_WIDTH: i32 = 4;
+const COLUMN_GAP: i32 = 2;
+/// 波形中心线Y坐标
+const METER_CENTER_Y: i32 = scaled(255);
+/// 柱在中心线上下各自的最大高度
+const METER_HALF_HEIGHT: i32 = scaled(30);
+/// 电平显示范围的下限（dBFS），低于该值显示为最短的柱
+const METER_FLOOR_DB: f32 = -60.0;
+
+/// 麦克风电平波形
+///
+/// 每帧压入一个电平值，从右向左滚动显示最近`METER_COLUMNS`帧。
+/// 记录每根柱上次绘制的高度，只重绘高度变化的柱。
+#[derive(Debug, Default)]
+pub struct LevelMeter {
+    /// 最近的柱高度（像素），最新的在末尾
+    history: VecDeque<i32>,
+    /// 上次绘制的柱高度
+    drawn: Vec<i32>,
+}
+
+impl LevelMeter {
+    /// 清空历史，下一帧重绘整个波形
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.drawn.clear();
+    }
+
+    /// 压入一帧电平
+    ///
+    /// # 参数
+    /// * `rms` - 麦克风样本的均方根幅度
+    pub fn push(&mut self, rms: u16) {
+        if self.history.len() == METER_COLUMNS {
+            self.history.pop_front();
+        }
+        self.history.push_back(level_height(rms));
+    }
+
+    fn draw(&mut self, graphics: &mut GraphicsPrimitives) -> anyhow::Result<()> {
+        let theme = theme::current();
+        let total_width = METER_COLUMNS as i32 * (COLUMN_WIDTH + COLUMN_GAP) - COLUMN_GAP;
+        let left = SCREEN_CENTER_X - total_width / 2;
+
+        if self.drawn.len() != METER_COLUMNS {
+            self.drawn = vec![-1; METER_COLUMNS];
+        }
+
+        // 历史不足时左侧补最短的柱
+        let padding = METER_COLUMNS - self.history.len();
+        for column in 0..METER_COLUMNS {
+            let height = if column < padding {
+                1
+            } else {
+                self.history[column - padding]
+            };
+            if self.drawn[column] == height {
+                continue;
+            }
+
+            let x = left + column as i32 * (COLUMN_WIDTH + COLUMN_GAP);
+            let area = ScreenRect::new(
+                x,
+                METER_CENTER_Y - METER_HALF_HEIGHT,
+                COLUMN_WIDTH,
+                METER_HALF_HEIGHT * 2,
+            );
+            graphics.fill_rect(&area, theme.background)?;
+            let bar = ScreenRect::new(x, METER_CENTER_Y - height, COLUMN_WIDTH, height * 2);
+            graphics.fill_rect(&bar, theme.accent)?;
+            self.drawn[column] = height;
+        }
+
+        Ok(())
+    }
+}
+
+/// 把均方根幅度按对数刻度换算为柱高度（像素）
+fn level_height(rms: u16) -> i32 {
+    if rms == 0 {
+        return 1;
+    }
+    let db = 20.0 * (rms as f32 / i16::MAX as f32).log10();
+    let ratio = ((db - METER_FLOOR_DB) / -METER_FLOOR_DB).clamp(0.0, 1.0);
+    ((ratio * METER_HALF_HEIGHT as f32) as i32).max(1)
+}
+
+/// 更新聆听界面（按键说话录音中）
+///
+/// # 参数
+/// * `elapsed` - 进入界面后经过的时间，用于显示录音时长和闪烁提示
+/// * `meter` - 麦克风电平波形
+pub fn draw(
+    graphics: &mut GraphicsPrimitives,
+    elapsed: Duration,
+    meter: &mut LevelMeter,
+) -> anyhow::Result<()> {
+    let theme = theme::current();
+    graphics.draw_text(
+        "聆听中...",
+        SCREEN_CENTER_X,
+        scaled(150),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+
+    let seconds = elapsed.as_secs();
+    graphics.draw_text(
+        &format!("{:>2}s", seconds),
+        SCREEN_CENTER_X,
+        scaled(200),
+        theme.accent,
+        Some(theme.background),
+    )?;
+
+    // 录音指示点每半秒闪烁一次
+    let dot_color = if cycle_phase(elapsed, Duration::from_millis(500), 2) == 0 {
+        theme.error
+    } else {
+        theme.background
+    };
+    graphics.draw_filled_circle(scaled(150), scaled(195), scaled(6), dot_color)?;
+
+    meter.draw(graphics)?;
+
+    graphics.draw_text(
+        "松开结束",
+        SCREEN_CENTER_X,
+        scaled(310),
+        theme.accent,
+        Some(theme.background),
+    )?;
+
+    Ok(())
+}
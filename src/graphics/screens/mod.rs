@@ -1,7 +1,25 @@
+pub mod about;
+pub mod alarm;
+pub mod calibration;
+pub mod conversation;
 pub mod dizziness;
+pub mod endpoint;
 pub mod error;
 pub mod home;
+pub mod intercom;
+pub mod kids;
+pub mod listening;
+pub mod models;
+pub mod ouch;
+pub mod pairing;
+pub mod reply;
+pub mod selftest;
 pub mod settings;
+pub mod spectrum;
+pub mod standby;
+pub mod stats;
+pub mod test_pattern;
 pub mod thinking;
 pub mod tilting;
+pub mod volume;
 pub mod welcome;
@@ -0,0 +1,88 @@
+use crate::{
+    api::types::ModelInfo,
+    graphics::{
+        layout::{scaled, SCREEN_CENTER_X, SCREEN_CENTER_Y},
+        primitives::GraphicsPrimitives,
+        theme,
+    },
+};
+
+/// 第一行的Y坐标
+const FIRST_ROW_Y: i32 = scaled(90);
+
+/// 行间距
+const ROW_SPACING: i32 = 30;
+
+/// 一屏最多显示的模型数，列表不超过底部提示文字
+const VISIBLE_ITEMS: usize = ((scaled(300) - FIRST_ROW_Y) / ROW_SPACING) as usize;
+
+/// 更新模型选择界面
+///
+/// 列表第一项为服务端默认模型，其后为`models`中的模型。
+///
+/// # 参数
+/// * `models` - 可用模型列表，None表示正在加载
+/// * `selected` - 当前高亮的列表项
+/// * `current` - 正在使用的模型ID
+pub fn draw(
+    graphics: &mut GraphicsPrimitives,
+    models: Option<&[ModelInfo]>,
+    selected: usize,
+    current: Option<&str>,
+) -> anyhow::Result<()> {
+    let theme = theme::current();
+    graphics.draw_text(
+        "选择模型",
+        SCREEN_CENTER_X,
+        scaled(50),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+
+    let Some(models) = models else {
+        graphics.draw_text(
+            "加载中...",
+            SCREEN_CENTER_X,
+            SCREEN_CENTER_Y,
+            theme.warning,
+            Some(theme.background),
+        )?;
+        return Ok(());
+    };
+
+    // 选中项超出一屏时整体滚动
+    let total = models.len() + 1;
+    let first = selected.saturating_sub(VISIBLE_ITEMS - 1);
+    for (row, index) in (first..total.min(first + VISIBLE_ITEMS)).enumerate() {
+        let (id, name) = match index {
+            0 => (None, "默认"),
+            i => {
+                let model = &models[i - 1];
+                (Some(model.id.as_str()), model.display_name())
+            }
+        };
+        let marker = if id == current { "●" } else { "○" };
+        let color = if index == selected {
+            theme.accent
+        } else {
+            theme.foreground
+        };
+        graphics.draw_text(
+            &format!("{} {}", marker, name),
+            scaled(80),
+            FIRST_ROW_Y + row as i32 * ROW_SPACING,
+            color,
+            Some(theme.background),
+        )?;
+    }
+
+    graphics.draw_text(
+        "旋转选择",
+        SCREEN_CENTER_X,
+        scaled(330),
+        theme.accent,
+        Some(theme.background),
+    )?;
+
+    Ok(())
+}
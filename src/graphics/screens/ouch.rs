@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use crate::graphics::{
+    animation::cycle_phase,
+    layout::{scaled, ScreenRect, SCREEN_CENTER_X},
+    primitives::GraphicsPrimitives,
+    theme,
+};
+
+/// 跌落后显示的"好痛"表情
+///
+/// 眯起的眼睛和向下的嘴巴，眼睛随计时轻微抖动。
+pub fn draw(graphics: &mut GraphicsPrimitives, elapsed: Duration) -> anyhow::Result<()> {
+    let theme = theme::current();
+
+    // 抖动时先擦除眼睛区域
+    let shake = if cycle_phase(elapsed, Duration::from_millis(100), 2) == 0 {
+        -3
+    } else {
+        3
+    };
+    graphics.fill_rect(
+        &ScreenRect::new(scaled(80), scaled(110), scaled(200), scaled(80)),
+        theme.background,
+    )?;
+
+    // 眯眼：两条向上拱起的弧线
+    graphics.draw_arc(
+        scaled(125) + shake,
+        scaled(160),
+        scaled(28),
+        -60.0,
+        120.0,
+        theme.foreground,
+        scaled(6),
+    )?;
+    graphics.draw_arc(
+        scaled(235) + shake,
+        scaled(160),
+        scaled(28),
+        -60.0,
+        120.0,
+        theme.foreground,
+        scaled(6),
+    )?;
+
+    // 嘴巴：向下弯的弧线
+    graphics.draw_arc(
+        SCREEN_CENTER_X,
+        scaled(270),
+        scaled(40),
+        -50.0,
+        100.0,
+        theme.error,
+        scaled(6),
+    )?;
+
+    graphics.draw_text(
+        "好痛!",
+        SCREEN_CENTER_X,
+        scaled(300),
+        theme.error,
+        Some(theme.background),
+    )?;
+
+    Ok(())
+}
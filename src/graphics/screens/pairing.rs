@@ -0,0 +1,38 @@
+use crate::graphics::{
+    layout::{scaled, SCREEN_CENTER_X},
+    primitives::GraphicsPrimitives,
+    theme,
+    ui::qrcode::QrCode,
+};
+
+/// 更新配对界面：居中显示二维码，下方显示说明文字
+///
+/// # 参数
+/// * `qr` - 已编码的二维码
+/// * `caption` - 说明文字，例如热点名称或配对网址
+pub fn draw(graphics: &mut GraphicsPrimitives, qr: &QrCode, caption: &str) -> anyhow::Result<()> {
+    let theme = theme::current();
+    graphics.draw_text(
+        "扫码连接",
+        SCREEN_CENTER_X,
+        scaled(40),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+    graphics.draw_component(qr)?;
+    graphics.draw_text(
+        caption,
+        SCREEN_CENTER_X,
+        scaled(300),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        "按 B 键返回",
+        SCREEN_CENTER_X,
+        scaled(330),
+        theme.accent,
+        Some(theme.background),
+    )?;
+    Ok(())
+}
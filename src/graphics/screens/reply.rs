@@ -0,0 +1,45 @@
+use crate::graphics::{
+    layout::{scaled, SCREEN_CENTER_X, TEXT_CHAR_WIDTH, TEXT_LINE_HEIGHT},
+    primitives::GraphicsPrimitives,
+    theme,
+    ui::chat_bubble::wrap_text,
+};
+
+/// 每行最多显示的字符数
+const LINE_CHARS: usize = (scaled(240) / TEXT_CHAR_WIDTH) as usize;
+
+/// 最多显示的行数
+const MAX_LINES: usize = (scaled(220) / TEXT_LINE_HEIGHT) as usize;
+
+/// 更新回复界面
+///
+/// # 参数
+/// * `reply` - 助手的回复文本，超出显示区域的部分以省略号结尾
+pub fn draw(graphics: &mut GraphicsPrimitives, reply: &str) -> anyhow::Result<()> {
+    let theme = theme::current();
+    let mut lines = wrap_text(reply, LINE_CHARS);
+    if lines.len() > MAX_LINES {
+        lines.truncate(MAX_LINES);
+        if let Some(last) = lines.last_mut() {
+            last.push_str("...");
+        }
+    }
+
+    let lines: Vec<&str> = lines.iter().map(|line| line.as_str()).collect();
+    graphics.draw_multiline_text(
+        &lines,
+        scaled(60),
+        scaled(70),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        "按键继续",
+        SCREEN_CENTER_X,
+        scaled(330),
+        theme.accent,
+        Some(theme.background),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use embedded_graphics::pixelcolor::Rgb565;
+
+use crate::{
+    app::selftest::{SelfTestReport, SelfTestStep, TestOutcome},
+    graphics::{
+        colors,
+        layout::{scaled, ScreenRect, SCREEN_CENTER_X},
+        primitives::GraphicsPrimitives,
+        theme,
+    },
+};
+
+/// 第一行的Y坐标
+const FIRST_ROW_Y: i32 = scaled(100);
+/// 行距
+const ROW_SPACING: i32 = scaled(36);
+/// 屏幕检测时显示的色条
+const TEST_PATTERN: [Rgb565; 8] = [
+    colors::WHITE,
+    colors::YELLOW,
+    colors::CYAN,
+    colors::GREEN,
+    colors::MAGENTA,
+    colors::RED,
+    colors::BLUE,
+    colors::BLACK,
+];
+
+/// 更新自检界面：每项一行，底部在屏幕检测时显示色条，完成后显示结论
+pub fn draw(graphics: &mut GraphicsPrimitives, report: &SelfTestReport) -> anyhow::Result<()> {
+    let theme = theme::current();
+    graphics.draw_text(
+        "硬件自检",
+        SCREEN_CENTER_X,
+        scaled(50),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+
+    for (index, (step, outcome)) in report.results.iter().enumerate() {
+        let y = FIRST_ROW_Y + index as i32 * ROW_SPACING;
+        let (text, color) = match outcome {
+            TestOutcome::Pending => ("等待".to_string(), theme.muted),
+            TestOutcome::Running => ("检测中...".to_string(), theme.accent),
+            TestOutcome::Pass(detail) => (format!("通过 {}", detail), theme.foreground),
+            TestOutcome::Fail(detail) => (format!("失败 {}", detail), theme.error),
+        };
+        // 结果长度会变化，先擦除整行
+        graphics.fill_rect(
+            &ScreenRect::new(scaled(60), y - 18, scaled(260), 24),
+            theme.background,
+        )?;
+        graphics.draw_text(
+            step.name(),
+            scaled(70),
+            y,
+            theme.foreground,
+            Some(theme.background),
+        )?;
+        graphics.draw_text(&text, scaled(160), y, color, Some(theme.background))?;
+    }
+
+    // 底部色条或结论所在区域
+    let footer = ScreenRect::new(scaled(60), scaled(285), scaled(240), scaled(40));
+    graphics.fill_rect(&footer, theme.background)?;
+    if report.current() == Some(SelfTestStep::Lcd) {
+        let width = footer.width / TEST_PATTERN.len() as i32;
+        for (index, color) in TEST_PATTERN.iter().enumerate() {
+            let bar = ScreenRect::new(
+                footer.x + index as i32 * width,
+                footer.y,
+                width,
+                footer.height,
+            );
+            graphics.fill_rect(&bar, *color)?;
+        }
+    } else if report.is_finished() {
+        let (text, color) = match report.failures() {
+            0 => ("全部通过".to_string(), theme.accent),
+            n => (format!("{}项失败", n), theme.error),
+        };
+        graphics.draw_text(
+            &text,
+            SCREEN_CENTER_X,
+            scaled(305),
+            color,
+            Some(theme.background),
+        )?;
+        graphics.draw_text(
+            "按 B 键返回",
+            SCREEN_CENTER_X,
+            scaled(335),
+            theme.muted,
+            Some(theme.background),
+        )?;
+    }
+
+    Ok(())
+}
@@ -1,21 +1,679 @@
-use crate::graphics::{
-    colors::{BLACK, GREEN, WHITE},
-    primitives::GraphicsPrimitives,
+use crate::{
+    actors::wakeword::{self, WakeWordConfig, DEFAULT_WAKE_THRESHOLD},
+    api::{endpoints::EndpointField, persona::Persona},
+    app::{
+        frame_pacing::{MAX_UI_FPS, MIN_UI_FPS},
+        kids::KidsModeConfig,
+    },
+    graphics::{
+        layout::{scaled, SCREEN_CENTER_X},
+        primitives::GraphicsPrimitives,
+        theme::{self, ThemeConfig},
+        ui::widgets::{Button, ListPicker, Slider, Toggle, Widget, ROW_HEIGHT},
+    },
+    peripherals::{
+        backlight::MIN_BRIGHTNESS,
+        qmi8658::motion_detector::{MotionSensitivity, MotionThresholds},
+        speaker::volume::{Volume, MAX_VOLUME, VOLUME_STEP},
+    },
 };
 
+/// 第一行控件的Y坐标
+const FIRST_ROW_Y: i32 = scaled(110);
+/// 控件下方说明文字的行距
+const NOTE_SPACING: i32 = 22;
+/// 页码指示点的Y坐标
+const PAGE_DOTS_Y: i32 = scaled(300);
+/// 相邻页码指示点的间距
+const PAGE_DOT_SPACING: i32 = 16;
+/// 亮度滑块的步长
+const BRIGHTNESS_STEP: i32 = 5;
+/// 唤醒词阈值滑块的范围（百分之一）与步长
+const WAKE_THRESHOLD_RANGE: (i32, i32, i32) = (40, 95, 5);
+/// 帧率滑块的步长
+const UI_FPS_STEP: i32 = 5;
+/// 关屏等待时间滑块的范围（分钟）与步长
+const SLEEP_MINUTES_RANGE: (i32, i32, i32) = (0, 60, 5);
+/// 儿童模式每天互动时长的可选值（分钟），0表示不限制
+const KIDS_DAILY_LIMITS: [u16; 7] = [0, 15, 30, 45, 60, 90, 120];
+
+/// 设置项修改后App要执行的操作
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettingAction {
+    /// 音量（0-100）
+    Volume(u8),
+    Muted(bool),
+    DoNotDisturb(bool),
+    VoiceGuide(bool),
+    /// 手动亮度（百分比）
+    Brightness(u8),
+    AutoBrightness(bool),
+    Theme(ThemeConfig),
+    /// 打开屏幕测试图
+    TestPattern,
+    /// 防烧屏开关
+    BurnIn(bool),
+    /// 界面目标帧率
+    UiFps(u8),
+    /// 无人时关闭屏幕的等待时间（分钟），0表示不关闭
+    SleepMinutes(u16),
+    MotionSensitivity(MotionSensitivity),
+    /// 开始运动阈值自动校准
+    CalibrateMotion,
+    /// 唤醒词检测阈值
+    WakeThreshold(f32),
+    /// 唤醒词模型，`wakeword::model_choices`中的序号
+    WakeWordModel(usize),
+    Persona(Persona),
+    /// 打开模型选择界面
+    ModelSelect,
+    /// 上传日志
+    UploadLogs,
+    /// 开始或结束麦克风调试录音
+    DebugRecording,
+    /// 打开对讲界面
+    Intercom,
+    /// 打开频谱界面
+    Spectrum,
+    /// 打开运行统计界面
+    Stats,
+    /// 打开关于界面
+    About,
+    /// 打开地址输入界面
+    EditEndpoint(EndpointField),
+    /// 显示屏幕镜像页面的二维码
+    #[cfg(feature = "display-mirror")]
+    MirrorQr,
+    KidsMode(bool),
+    /// 儿童模式每天允许的互动时长（分钟），0表示不限制
+    KidsDailyLimit(u16),
+}
+
+/// 上传日志的进度，显示在设置界面的按钮旁
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogUploadStatus {
+    #[default]
+    Idle,
+    Uploading,
+    Uploaded,
+    Failed,
+}
+
+impl LogUploadStatus {
+    fn text(self) -> &'static str {
+        match self {
+            LogUploadStatus::Idle => "",
+            LogUploadStatus::Uploading => "上传中...",
+            LogUploadStatus::Uploaded => "已上传",
+            LogUploadStatus::Failed => "失败",
+        }
+    }
+}
+
+/// 设置界面显示的亮度设置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BrightnessSetting {
+    /// 手动设置的亮度
+    pub percent: u8,
+    /// 是否使用自动亮度，None表示没有环境光传感器
+    pub auto: Option<bool>,
+}
+
+/// 生成设置菜单所需的当前设置
+#[derive(Debug, Clone, Default)]
+pub struct SettingsValues {
+    pub volume: Volume,
+    pub do_not_disturb: bool,
+    pub voice_guide: bool,
+    pub brightness: BrightnessSetting,
+    pub motion: MotionThresholds,
+    pub wake_word: WakeWordConfig,
+    /// 模型分区中的唤醒词模型
+    pub wake_word_models: Vec<String>,
+    pub persona: Persona,
+    /// 当前对话模型，None表示服务端默认模型
+    pub model: Option<String>,
+    pub theme_config: ThemeConfig,
+    pub kids_mode: KidsModeConfig,
+    /// 防烧屏是否启用
+    pub burn_in_enabled: bool,
+    pub log_upload: LogUploadStatus,
+    /// 麦克风调试录音是否正在进行
+    pub debug_recording: bool,
+    /// 界面目标帧率
+    pub ui_fps: u8,
+    /// 无人时关闭屏幕的等待时间（分钟），0表示不关闭
+    pub sleep_minutes: u16,
+}
+
+/// 设置菜单中的一页
+struct SettingsPage {
+    title: &'static str,
+    widgets: Vec<Box<dyn Widget<SettingAction>>>,
+    /// 控件下方只显示不可修改的设置
+    notes: Vec<String>,
+}
+
+/// 多页设置菜单
+///
+/// 旋转手势在控件之间移动焦点，越过一页的首尾时翻页；单击操作获得焦点的控件。
+#[derive(Default)]
+pub struct SettingsMenu {
+    pages: Vec<SettingsPage>,
+    /// 当前页
+    page: usize,
+    /// 当前页中获得焦点的控件
+    focus: usize,
+}
+
+impl SettingsMenu {
+    /// 根据当前设置生成菜单，焦点在第一页第一项
+    pub fn new(values: &SettingsValues) -> Self {
+        let mut menu = Self {
+            pages: build_pages(values),
+            page: 0,
+            focus: 0,
+        };
+        menu.set_focus(0, 0);
+        menu
+    }
+
+    /// 设置在菜单外被修改（或修改后被App调整）时重新生成，保留焦点与编辑状态
+    pub fn refresh(&mut self, values: &SettingsValues) {
+        let editing = self.is_editing();
+        let (page, focus) = (self.page, self.focus);
+        self.pages = build_pages(values);
+        self.set_focus(page, focus);
+        if let Some(widget) = self.focused_mut() {
+            widget.set_editing(editing);
+        }
+    }
+
+    /// 当前页序号，翻页后需要清屏
+    pub fn page(&self) -> usize {
+        self.page
+    }
+
+    /// 获得焦点的控件是否正在编辑
+    pub fn is_editing(&self) -> bool {
+        self.focused().is_some_and(|widget| widget.is_editing())
+    }
+
+    /// 旋转：编辑时调节取值，否则移动焦点
+    ///
+    /// # 参数
+    /// * `delta` - 旋转方向与步数，正数为顺时针
+    pub fn rotate(&mut self, delta: i32) -> Option<SettingAction> {
+        if self.is_editing() {
+            return self.focused_mut()?.rotate(delta);
+        }
+
+        // 所有页的控件连成一串循环移动
+        let total: usize = self.pages.iter().map(|page| page.widgets.len()).sum();
+        if total == 0 {
+            return None;
+        }
+        let before: usize = self.pages[..self.page]
+            .iter()
+            .map(|page| page.widgets.len())
+            .sum();
+        let mut index =
+            (before as i32 + self.focus as i32 + delta).rem_euclid(total as i32) as usize;
+        for (page, content) in self.pages.iter().enumerate() {
+            if index < content.widgets.len() {
+                self.set_focus(page, index);
+                break;
+            }
+            index -= content.widgets.len();
+        }
+        None
+    }
+
+    /// 单击获得焦点的控件
+    pub fn activate(&mut self) -> Option<SettingAction> {
+        self.focused_mut()?.activate()
+    }
+
+    /// 返回：正在编辑时结束编辑
+    ///
+    /// # 返回值
+    /// 是否处理了返回，false时应离开设置界面
+    pub fn back(&mut self) -> bool {
+        if !self.is_editing() {
+            return false;
+        }
+        if let Some(widget) = self.focused_mut() {
+            widget.set_editing(false);
+        }
+        true
+    }
+
+    fn set_focus(&mut self, page: usize, focus: usize) {
+        if let Some(widget) = self.focused_mut() {
+            widget.set_focused(false);
+        }
+        self.page = page.min(self.pages.len().saturating_sub(1));
+        self.focus = self
+            .pages
+            .get(self.page)
+            .map_or(0, |page| focus.min(page.widgets.len().saturating_sub(1)));
+        if let Some(widget) = self.focused_mut() {
+            widget.set_focused(true);
+        }
+    }
+
+    fn focused(&self) -> Option<&dyn Widget<SettingAction>> {
+        let widget = self.pages.get(self.page)?.widgets.get(self.focus)?;
+        Some(widget.as_ref())
+    }
+
+    fn focused_mut(&mut self) -> Option<&mut Box<dyn Widget<SettingAction>>> {
+        self.pages.get_mut(self.page)?.widgets.get_mut(self.focus)
+    }
+}
+
+/// 生成各页控件
+fn build_pages(values: &SettingsValues) -> Vec<SettingsPage> {
+    let sound: Vec<Box<dyn Widget<SettingAction>>> = vec![
+        Box::new(Slider::new(
+            "音量",
+            values.volume.level() as i32,
+            (0, MAX_VOLUME as i32, VOLUME_STEP as i32),
+            |level| format!("{}%", level),
+            |level| SettingAction::Volume(level as u8),
+        )),
+        Box::new(Toggle::new(
+            "静音",
+            values.volume.is_muted(),
+            SettingAction::Muted,
+        )),
+        Box::new(Toggle::new(
+            "免打扰",
+            values.do_not_disturb,
+            SettingAction::DoNotDisturb,
+        )),
+        Box::new(Toggle::new(
+            "语音导航",
+            values.voice_guide,
+            SettingAction::VoiceGuide,
+        )),
+    ];
+
+    let mut display: Vec<Box<dyn Widget<SettingAction>>> = vec![Box::new(Slider::new(
+        "亮度",
+        values.brightness.percent as i32,
+        (MIN_BRIGHTNESS as i32, 100, BRIGHTNESS_STEP),
+        |percent| format!("{}%", percent),
+        |percent| SettingAction::Brightness(percent as u8),
+    ))];
+    // 没有环境光传感器时不显示自动亮度
+    if let Some(auto) = values.brightness.auto {
+        display.push(Box::new(Toggle::new(
+            "自动亮度",
+            auto,
+            SettingAction::AutoBrightness,
+        )));
+    }
+    let themes = ThemeConfig::all();
+    display.push(Box::new(ListPicker::new(
+        "主题",
+        themes.iter().map(ThemeConfig::name).collect(),
+        themes
+            .iter()
+            .position(|theme| *theme == values.theme_config)
+            .unwrap_or(0),
+        |index| SettingAction::Theme(ThemeConfig::all()[index]),
+    )));
+    display.push(Box::new(Button::new("测试图", "", || {
+        SettingAction::TestPattern
+    })));
+
+    let screen: Vec<Box<dyn Widget<SettingAction>>> = vec![
+        Box::new(Toggle::new(
+            "防烧屏",
+            values.burn_in_enabled,
+            SettingAction::BurnIn,
+        )),
+        Box::new(Slider::new(
+            "帧率",
+            values.ui_fps as i32,
+            (MIN_UI_FPS as i32, MAX_UI_FPS as i32, UI_FPS_STEP),
+            |fps| format!("{}fps", fps),
+            |fps| SettingAction::UiFps(fps as u8),
+        )),
+        Box::new(Slider::new(
+            "自动关屏",
+            values.sleep_minutes as i32,
+            SLEEP_MINUTES_RANGE,
+            |minutes| match minutes {
+                0 => "关".to_string(),
+                minutes => format!("{}分钟", minutes),
+            },
+            |minutes| SettingAction::SleepMinutes(minutes as u16),
+        )),
+    ];
+
+    let threshold = values.wake_word.threshold.unwrap_or(DEFAULT_WAKE_THRESHOLD);
+    let wake_word_choices = wakeword::model_choices(&values.wake_word_models);
+    let sensitivity: Vec<Box<dyn Widget<SettingAction>>> = vec![
+        Box::new(ListPicker::new(
+            "动作灵敏度",
+            MotionSensitivity::ALL
+                .iter()
+                .map(|s| s.name().to_string())
+                .collect(),
+            MotionSensitivity::ALL
+                .iter()
+                .position(|s| *s == values.motion.sensitivity())
+                .unwrap_or(0),
+            |index| SettingAction::MotionSensitivity(MotionSensitivity::ALL[index]),
+        )),
+        Box::new(Button::new("校准动作", "", || {
+            SettingAction::CalibrateMotion
+        })),
+        Box::new(Slider::new(
+            "唤醒阈值",
+            (threshold * 100.0).round() as i32,
+            WAKE_THRESHOLD_RANGE,
+            |percent| format!("{:.2}", percent as f32 / 100.0),
+            |percent| SettingAction::WakeThreshold(percent as f32 / 100.0),
+        )),
+        Box::new(ListPicker::new(
+            "唤醒词",
+            wake_word_choices
+                .iter()
+                .map(|model| {
+                    model
+                        .as_deref()
+                        .map_or("默认", wakeword::keyword_name)
+                        .to_string()
+                })
+                .collect(),
+            wake_word_choices
+                .iter()
+                .position(|model| *model == values.wake_word.model)
+                .unwrap_or(0),
+            SettingAction::WakeWordModel,
+        )),
+    ];
+
+    let other: Vec<Box<dyn Widget<SettingAction>>> = vec![
+        Box::new(ListPicker::new(
+            "角色",
+            Persona::ALL.iter().map(|p| p.name().to_string()).collect(),
+            Persona::ALL
+                .iter()
+                .position(|p| *p == values.persona)
+                .unwrap_or(0),
+            |index| SettingAction::Persona(Persona::ALL[index]),
+        )),
+        Box::new(Button::new(
+            "模型",
+            values.model.as_deref().unwrap_or("默认"),
+            || SettingAction::ModelSelect,
+        )),
+        Box::new(Button::new(
+            "上传日志",
+            values.log_upload.text(),
+            || SettingAction::UploadLogs,
+        )),
+        Box::new(Button::new(
+            "调试录音",
+            if values.debug_recording {
+                "录音中..."
+            } else {
+                ""
+            },
+            || SettingAction::DebugRecording,
+        )),
+    ];
+
+    let kids: Vec<Box<dyn Widget<SettingAction>>> = vec![
+        Box::new(Toggle::new(
+            "儿童模式",
+            values.kids_mode.enabled,
+            SettingAction::KidsMode,
+        )),
+        Box::new(ListPicker::new(
+            "每天时长",
+            KIDS_DAILY_LIMITS
+                .iter()
+                .map(|minutes| match minutes {
+                    0 => "不限".to_string(),
+                    minutes => format!("{}分钟", minutes),
+                })
+                .collect(),
+            KIDS_DAILY_LIMITS
+                .iter()
+                .position(|minutes| *minutes == values.kids_mode.daily_limit_minutes)
+                .unwrap_or(0),
+            |index| SettingAction::KidsDailyLimit(KIDS_DAILY_LIMITS[index]),
+        )),
+    ];
+
+    let network: Vec<Box<dyn Widget<SettingAction>>> = vec![
+        Box::new(Button::new("对话地址", "", || {
+            SettingAction::EditEndpoint(EndpointField::BaseUrl)
+        })),
+        Box::new(Button::new("上传地址", "", || {
+            SettingAction::EditEndpoint(EndpointField::PcmUrl)
+        })),
+        #[cfg(feature = "display-mirror")]
+        Box::new(Button::new("镜像", "", || SettingAction::MirrorQr)),
+    ];
+
+    let tools: Vec<Box<dyn Widget<SettingAction>>> = vec![
+        Box::new(Button::new("对讲", "", || SettingAction::Intercom)),
+        Box::new(Button::new("频谱", "", || SettingAction::Spectrum)),
+        Box::new(Button::new("统计", "", || SettingAction::Stats)),
+        Box::new(Button::new("关于", "", || SettingAction::About)),
+    ];
+
+    vec![
+        page("声音", sound, Vec::new()),
+        page("显示", display, Vec::new()),
+        page("屏幕", screen, Vec::new()),
+        page("灵敏度", sensitivity, Vec::new()),
+        page("其他", other, Vec::new()),
+        page("儿童", kids, Vec::new()),
+        page("网络", network, Vec::new()),
+        page("工具", tools, Vec::new()),
+    ]
+}
+
+/// 生成一页，控件从`FIRST_ROW_Y`开始逐行排列
+fn page(
+    title: &'static str,
+    mut widgets: Vec<Box<dyn Widget<SettingAction>>>,
+    notes: Vec<String>,
+) -> SettingsPage {
+    for (index, widget) in widgets.iter_mut().enumerate() {
+        widget.set_row(FIRST_ROW_Y + index as i32 * ROW_HEIGHT);
+    }
+    SettingsPage {
+        title,
+        widgets,
+        notes,
+    }
+}
+
 /// 更新设置界面
-pub fn draw(graphics: &mut GraphicsPrimitives) -> anyhow::Result<()> {
-    // 绘制设置界面
-    graphics.draw_text("设置", 180, 50, WHITE, Some(BLACK))?;
+///
+/// # 参数
+/// * `menu` - 设置菜单
+pub fn draw(graphics: &mut GraphicsPrimitives, menu: &SettingsMenu) -> anyhow::Result<()> {
+    let theme = theme::current();
+    let Some(page) = menu.pages.get(menu.page) else {
+        return Ok(());
+    };
 
-    // 设置选项
-    graphics.draw_text("● 主题设置", 80, 120, WHITE, Some(BLACK))?;
-    graphics.draw_text("● 网络设置", 80, 160, WHITE, Some(BLACK))?;
-    graphics.draw_text("● 语言设置", 80, 200, WHITE, Some(BLACK))?;
-    graphics.draw_text("● 关于", 80, 240, WHITE, Some(BLACK))?;
+    graphics.draw_text(
+        &format!("设置 - {}", page.title),
+        SCREEN_CENTER_X,
+        scaled(50),
+        theme.foreground,
+        Some(theme.background),
+    )?;
 
-    // 操作提示
-    graphics.draw_text("按 B 键返回", 180, 320, GREEN, Some(BLACK))?;
+    for widget in &page.widgets {
+        widget.render(graphics)?;
+    }
+    let notes_y = FIRST_ROW_Y + page.widgets.len() as i32 * ROW_HEIGHT;
+    for (index, note) in page.notes.iter().enumerate() {
+        graphics.draw_text(
+            note,
+            scaled(60),
+            notes_y + index as i32 * NOTE_SPACING,
+            theme.muted,
+            Some(theme.background),
+        )?;
+    }
+
+    // 页码指示点
+    let count = menu.pages.len() as i32;
+    let first_x = SCREEN_CENTER_X - (count - 1) * PAGE_DOT_SPACING / 2;
+    for index in 0..count {
+        let color = if index as usize == menu.page {
+            theme.accent
+        } else {
+            theme.muted
+        };
+        graphics.draw_filled_circle(first_x + index * PAGE_DOT_SPACING, PAGE_DOTS_Y, 4, color)?;
+    }
+
+    // 操作提示，两种提示等宽，切换时不需要清除
+    let hint = if menu.is_editing() {
+        "旋转调节 单击完成"
+    } else {
+        "旋转选择 单击修改"
+    };
+    graphics.draw_text(
+        hint,
+        SCREEN_CENTER_X,
+        scaled(330),
+        theme.accent,
+        Some(theme.background),
+    )?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(auto_brightness: Option<bool>) -> SettingsValues {
+        SettingsValues {
+            volume: Volume::new(50, false),
+            brightness: BrightnessSetting {
+                percent: 80,
+                auto: auto_brightness,
+            },
+            ..SettingsValues::default()
+        }
+    }
+
+    #[test]
+    fn test_focus_crosses_pages() {
+        let mut menu = SettingsMenu::new(&values(None));
+        // 声音页4项，第5项为显示页的亮度
+        for _ in 0..4 {
+            assert_eq!(menu.rotate(1), None);
+        }
+        assert_eq!(menu.page(), 1);
+        // 没有环境光传感器时显示页只有亮度、主题和测试图，再下一项在屏幕页
+        menu.rotate(1);
+        assert_eq!(menu.activate(), None);
+        assert_eq!(
+            menu.rotate(1),
+            Some(SettingAction::Theme(ThemeConfig {
+                mode: theme::ThemeMode::Dark,
+                accent: theme::Accent::Blue,
+            }))
+        );
+        assert!(menu.back());
+        menu.rotate(1);
+        assert_eq!(menu.activate(), Some(SettingAction::TestPattern));
+        menu.rotate(1);
+        assert_eq!(menu.page(), 2);
+        assert_eq!(menu.activate(), Some(SettingAction::BurnIn(true)));
+        menu.rotate(3);
+        assert_eq!(menu.page(), 3);
+        // 逆时针越过第一项回到最后一页，最后一项为关于
+        menu.rotate(-11);
+        assert_eq!(menu.page(), 7);
+        assert_eq!(menu.activate(), Some(SettingAction::About));
+        menu.rotate(-1);
+        assert_eq!(menu.activate(), Some(SettingAction::Stats));
+        menu.rotate(-1);
+        assert_eq!(menu.activate(), Some(SettingAction::Spectrum));
+        menu.rotate(-1);
+        assert_eq!(menu.activate(), Some(SettingAction::Intercom));
+        // 网络页最后一项为语音上传地址
+        menu.rotate(-1);
+        assert_eq!(menu.page(), 6);
+        assert_eq!(
+            menu.activate(),
+            Some(SettingAction::EditEndpoint(EndpointField::PcmUrl))
+        );
+        // 儿童页最后一项为每天时长
+        menu.rotate(-2);
+        assert_eq!(menu.page(), 5);
+        assert_eq!(menu.activate(), None);
+        assert_eq!(menu.rotate(1), Some(SettingAction::KidsDailyLimit(15)));
+        assert!(menu.back());
+        // 其他页最后三项为模型选择、上传日志和调试录音按钮
+        menu.rotate(-4);
+        assert_eq!(menu.page(), 4);
+        assert_eq!(menu.activate(), Some(SettingAction::ModelSelect));
+        menu.rotate(1);
+        assert_eq!(menu.activate(), Some(SettingAction::UploadLogs));
+        menu.rotate(1);
+        assert_eq!(menu.activate(), Some(SettingAction::DebugRecording));
+    }
+
+    #[test]
+    fn test_opening_click_does_not_edit() {
+        // 主界面的单击由`Confirm`打开设置，同一次单击不会再激活音量滑块
+        let mut menu = SettingsMenu::new(&values(None));
+        assert_eq!(menu.page(), 0);
+        assert!(!menu.is_editing());
+        assert_eq!(menu.rotate(1), None);
+        assert!(!menu.back());
+
+        // 下一次单击才进入编辑
+        menu.rotate(-1);
+        assert_eq!(menu.activate(), None);
+        assert!(menu.is_editing());
+    }
+
+    #[test]
+    fn test_edit_slider_and_toggle() {
+        let mut menu = SettingsMenu::new(&values(Some(true)));
+        assert_eq!(menu.activate(), None);
+        assert!(menu.is_editing());
+        assert_eq!(menu.rotate(1), Some(SettingAction::Volume(60)));
+        assert!(menu.back());
+        assert!(!menu.back());
+
+        menu.rotate(1);
+        assert_eq!(menu.activate(), Some(SettingAction::Muted(true)));
+        assert!(!menu.is_editing());
+    }
+
+    #[test]
+    fn test_refresh_keeps_focus() {
+        let mut menu = SettingsMenu::new(&values(Some(true)));
+        menu.rotate(4);
+        menu.activate();
+        assert_eq!(menu.rotate(-1), Some(SettingAction::Brightness(75)));
+
+        // 修改亮度后App关闭了自动亮度
+        let mut changed = values(Some(false));
+        changed.brightness.percent = 75;
+        menu.refresh(&changed);
+        assert_eq!(menu.page(), 1);
+        assert!(menu.is_editing());
+        assert_eq!(menu.rotate(-1), Some(SettingAction::Brightness(70)));
+    }
+}
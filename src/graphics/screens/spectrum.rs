@@ -0,0 +1,120 @@
+use crate::graphics::{
+    layout::{scaled, SCREEN_CENTER_X, SCREEN_CENTER_Y},
+    primitives::GraphicsPrimitives,
+    theme,
+};
+
+/// 频段数，即环上的柱数
+pub const SPECTRUM_BANDS: usize = 48;
+/// 柱根部到屏幕中心的距离
+const INNER_RADIUS: i32 = scaled(80);
+/// 柱的最大长度
+const MAX_BAR_LENGTH: i32 = scaled(85);
+/// 柱的粗细
+const BAR_THICKNESS: u32 = if scaled(4) > 2 { scaled(4) as u32 } else { 2 };
+/// 电平下降时每帧最多回落的量，上升时立即跟随
+const FALL_PER_FRAME: u8 = 4;
+/// 电平超过该值时用警告色显示，提示麦克风接近削波
+const HOT_LEVEL: u8 = 90;
+
+/// 环形频谱
+///
+/// 各频段的柱从12点钟方向开始顺时针排列，低频在前。
+/// 记录每根柱上次绘制的长度，只重绘变化的柱。
+#[derive(Debug, Default)]
+pub struct SpectrumView {
+    /// 各频段当前显示的电平（0-100）
+    levels: Vec<u8>,
+    /// 上次绘制的柱长度（像素）
+    drawn: Vec<i32>,
+}
+
+impl SpectrumView {
+    /// 清空电平，下一帧重绘所有柱
+    pub fn reset(&mut self) {
+        self.levels.clear();
+        self.drawn.clear();
+    }
+
+    /// 压入一帧各频段电平
+    ///
+    /// # 参数
+    /// * `levels` - 各频段电平（0-100），数量不足`SPECTRUM_BANDS`时其余视为0
+    pub fn push(&mut self, levels: &[u8]) {
+        self.levels.resize(SPECTRUM_BANDS, 0);
+        for (band, shown) in self.levels.iter_mut().enumerate() {
+            let level = levels.get(band).copied().unwrap_or(0).min(100);
+            *shown = level.max(shown.saturating_sub(FALL_PER_FRAME));
+        }
+    }
+
+    fn draw(&mut self, graphics: &mut GraphicsPrimitives) -> anyhow::Result<()> {
+        let theme = theme::current();
+        if self.drawn.len() != SPECTRUM_BANDS {
+            self.drawn = vec![-1; SPECTRUM_BANDS];
+        }
+
+        for band in 0..SPECTRUM_BANDS {
+            let level = self.levels.get(band).copied().unwrap_or(0);
+            let length = (MAX_BAR_LENGTH * level as i32 / 100).max(1);
+            if self.drawn[band] == length {
+                continue;
+            }
+
+            // 以12点钟方向为0度顺时针排列
+            let angle = (band as f32 / SPECTRUM_BANDS as f32) * std::f32::consts::TAU;
+            let (sin, cos) = angle.sin_cos();
+            let point = |radius: i32| {
+                (
+                    SCREEN_CENTER_X + (radius as f32 * sin).round() as i32,
+                    SCREEN_CENTER_Y - (radius as f32 * cos).round() as i32,
+                )
+            };
+
+            let start = point(INNER_RADIUS);
+            graphics.draw_line(
+                start,
+                point(INNER_RADIUS + MAX_BAR_LENGTH),
+                theme.background,
+                BAR_THICKNESS,
+            )?;
+            let color = if level >= HOT_LEVEL {
+                theme.warning
+            } else {
+                theme.accent
+            };
+            graphics.draw_line(start, point(INNER_RADIUS + length), color, BAR_THICKNESS)?;
+            self.drawn[band] = length;
+        }
+
+        Ok(())
+    }
+}
+
+/// 更新频谱界面
+///
+/// # 参数
+/// * `spectrum` - 环形频谱
+pub fn draw(graphics: &mut GraphicsPrimitives, spectrum: &mut SpectrumView) -> anyhow::Result<()> {
+    let theme = theme::current();
+    graphics.draw_text(
+        "频谱",
+        SCREEN_CENTER_X,
+        SCREEN_CENTER_Y - scaled(20),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+
+    spectrum.draw(graphics)?;
+
+    // 操作提示
+    graphics.draw_text(
+        "按 B 键返回",
+        SCREEN_CENTER_X,
+        SCREEN_CENTER_Y + scaled(20),
+        theme.accent,
+        Some(theme.background),
+    )?;
+
+    Ok(())
+}
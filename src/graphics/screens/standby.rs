@@ -0,0 +1,305 @@
+use crate::{
+    api::weather::Weather,
+    clock::LocalTime,
+    graphics::{
+        layout::{scaled, ScreenRect, SCREEN_CENTER_X},
+        primitives::GraphicsPrimitives,
+        theme,
+        ui::icons::{WIFI_ICONS, WIFI_ICON_HEIGHT, WIFI_ICON_WIDTH},
+    },
+    peripherals::battery::BatteryLevel,
+};
+
+/// 数码管数字的宽、高与笔画粗细
+const DIGIT_WIDTH: i32 = scaled(44);
+const DIGIT_HEIGHT: i32 = scaled(80);
+const SEGMENT_THICKNESS: i32 = scaled(8);
+/// 同一组（时或分）两个数字之间的间距
+const DIGIT_GAP: i32 = scaled(10);
+/// 冒号所占宽度
+const COLON_WIDTH: i32 = scaled(24);
+/// 时间顶部Y坐标
+const CLOCK_TOP: i32 = scaled(110);
+/// 天气卡片顶部Y坐标
+const WEATHER_TOP: i32 = scaled(60);
+/// 日期行顶部Y坐标
+const DATE_TOP: i32 = scaled(220);
+/// 状态行顶部Y坐标
+const STATUS_TOP: i32 = scaled(280);
+/// 步数行顶部Y坐标
+const STEPS_TOP: i32 = scaled(296);
+/// 状态行与步数行的宽度，行内图标按像素排列，不随屏幕缩放
+const ROW_WIDTH: i32 = 140;
+
+/// 各数字点亮的笔画，位0-6依次为a(上) b(右上) c(右下) d(下) e(左下) f(左上) g(中)
+const DIGIT_SEGMENTS: [u8; 10] = [
+    0b011_1111, 0b000_0110, 0b101_1011, 0b100_1111, 0b110_0110, 0b110_1101, 0b111_1101, 0b000_0111,
+    0b111_1111, 0b110_1111,
+];
+
+/// 只点亮中间一划，时间未同步时显示"--:--"
+const SEGMENT_DASH: u8 = 0b100_0000;
+
+/// 待机表盘显示的内容
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StandbyInfo<'a> {
+    /// 当前时间，None表示尚未同步
+    pub time: Option<LocalTime>,
+    /// WiFi信号格数，None表示未连接
+    pub wifi_level: Option<u8>,
+    /// 电池电量，没有电池检测时为None
+    pub battery: Option<BatteryLevel>,
+    /// 天气，未配置天气服务或尚未获取时为None
+    pub weather: Option<&'a Weather>,
+    /// 今日步数，没有计步数据时为None
+    pub steps: Option<u32>,
+}
+
+/// 待机表盘
+///
+/// 记录上次绘制的内容，每帧只重绘发生变化的部分（大部分时间只有冒号在闪烁），
+/// 减少帧缓冲区的脏区域和LCD传输量。清屏后需调用`invalidate`强制整屏重绘。
+#[derive(Debug, Default)]
+pub struct StandbyFace {
+    digits: Option<[u8; 4]>,
+    colon_on: Option<bool>,
+    date: Option<String>,
+    weather: Option<String>,
+    status: Option<(Option<u8>, Option<u8>)>,
+    steps: Option<String>,
+}
+
+impl StandbyFace {
+    /// 清除绘制记录，下一帧整屏重绘
+    pub fn invalidate(&mut self) {
+        *self = Self::default();
+    }
+
+    /// 绘制待机表盘
+    ///
+    /// # 参数
+    /// * `info` - 时间、天气、WiFi与电池状态
+    pub fn draw(
+        &mut self,
+        graphics: &mut GraphicsPrimitives,
+        info: &StandbyInfo,
+    ) -> anyhow::Result<()> {
+        let theme = theme::current();
+
+        // 天气卡片：时间上方一行，显示描述、气温与湿度
+        let weather = info
+            .weather
+            .map(|w| format!("{} {:.0}C {}%", w.description, w.temperature, w.humidity))
+            .unwrap_or_default();
+        if self.weather.as_deref() != Some(weather.as_str()) {
+            let card = ScreenRect::new(SCREEN_CENTER_X - 110, WEATHER_TOP, 220, 34);
+            graphics.fill_rect(&card, theme.background)?;
+            if !weather.is_empty() {
+                graphics.fill_rounded_rect(&card, 17, theme.surface)?;
+                graphics.draw_text(
+                    &weather,
+                    card.x + 30,
+                    card.y + 23,
+                    theme.foreground,
+                    Some(theme.surface),
+                )?;
+            }
+            self.weather = Some(weather);
+        }
+
+        // 时间：HH:MM，未同步时显示横线
+        let digits = match info.time {
+            Some(time) => [
+                DIGIT_SEGMENTS[(time.hour / 10) as usize],
+                DIGIT_SEGMENTS[(time.hour % 10) as usize],
+                DIGIT_SEGMENTS[(time.minute / 10) as usize],
+                DIGIT_SEGMENTS[(time.minute % 10) as usize],
+            ],
+            None => [SEGMENT_DASH; 4],
+        };
+        let previous = self.digits.unwrap_or([u8::MAX; 4]);
+        for (index, segments) in digits.iter().enumerate() {
+            if previous[index] != *segments {
+                draw_digit(graphics, digit_x(index), CLOCK_TOP, *segments)?;
+            }
+        }
+        self.digits = Some(digits);
+
+        // 冒号每秒闪烁一次
+        let colon_on = info.time.map_or(true, |time| time.second % 2 == 0);
+        if self.colon_on != Some(colon_on) {
+            let color = if colon_on {
+                theme.accent
+            } else {
+                theme.background
+            };
+            let x = SCREEN_CENTER_X - SEGMENT_THICKNESS / 2;
+            for y in [
+                CLOCK_TOP + scaled(22),
+                CLOCK_TOP + DIGIT_HEIGHT - scaled(30),
+            ] {
+                let dot = ScreenRect::new(x, y, SEGMENT_THICKNESS, SEGMENT_THICKNESS);
+                graphics.fill_rounded_rect(&dot, 2, color)?;
+            }
+            self.colon_on = Some(colon_on);
+        }
+
+        // 日期与星期
+        let date = match info.time {
+            Some(time) => format!(
+                "{:04}-{:02}-{:02} {}",
+                time.year,
+                time.month,
+                time.day,
+                time.weekday_name()
+            ),
+            None => "等待时间同步".to_string(),
+        };
+        if self.date.as_deref() != Some(date.as_str()) {
+            graphics.fill_rect(
+                &ScreenRect::new(SCREEN_CENTER_X - 120, DATE_TOP, 240, 28),
+                theme.background,
+            )?;
+            graphics.draw_text(
+                &date,
+                SCREEN_CENTER_X - 60,
+                DATE_TOP + 20,
+                theme.muted,
+                Some(theme.background),
+            )?;
+            self.date = Some(date);
+        }
+
+        // WiFi信号与电池电量
+        let status = (info.wifi_level, info.battery.map(|b| b.percent));
+        if self.status != Some(status) {
+            draw_status(graphics, status.0, status.1)?;
+            self.status = Some(status);
+        }
+
+        // 今日步数
+        let steps = info
+            .steps
+            .map(|steps| format!("{}步", steps))
+            .unwrap_or_default();
+        if self.steps.as_deref() != Some(steps.as_str()) {
+            graphics.fill_rect(
+                &ScreenRect::new(SCREEN_CENTER_X - ROW_WIDTH / 2, STEPS_TOP, ROW_WIDTH, 24),
+                theme.background,
+            )?;
+            if !steps.is_empty() {
+                let x = SCREEN_CENTER_X - steps.chars().count() as i32 * 5;
+                graphics.draw_text(
+                    &steps,
+                    x,
+                    STEPS_TOP + 18,
+                    theme.muted,
+                    Some(theme.background),
+                )?;
+            }
+            self.steps = Some(steps);
+        }
+
+        Ok(())
+    }
+}
+
+/// 第`index`个数字左上角的X坐标
+fn digit_x(index: usize) -> i32 {
+    let total = DIGIT_WIDTH * 4 + DIGIT_GAP * 2 + COLON_WIDTH;
+    let left = SCREEN_CENTER_X - total / 2;
+    match index {
+        0 => left,
+        1 => left + DIGIT_WIDTH + DIGIT_GAP,
+        2 => SCREEN_CENTER_X + COLON_WIDTH / 2,
+        _ => SCREEN_CENTER_X + COLON_WIDTH / 2 + DIGIT_WIDTH + DIGIT_GAP,
+    }
+}
+
+/// 绘制一个数码管数字，先清除整个数字区域
+fn draw_digit(
+    graphics: &mut GraphicsPrimitives,
+    x: i32,
+    y: i32,
+    segments: u8,
+) -> anyhow::Result<()> {
+    let theme = theme::current();
+    let (w, h, t) = (DIGIT_WIDTH, DIGIT_HEIGHT, SEGMENT_THICKNESS);
+    let half = h / 2;
+    // 竖划长度：半个数字高度减去上下横划
+    let vertical = half - t - t / 2;
+
+    graphics.fill_rect(&ScreenRect::new(x, y, w, h), theme.background)?;
+
+    // 笔画区域，顺序与DIGIT_SEGMENTS的位一致
+    let rects = [
+        ScreenRect::new(x + t, y, w - 2 * t, t),
+        ScreenRect::new(x + w - t, y + t, t, vertical),
+        ScreenRect::new(x + w - t, y + half + t / 2, t, vertical),
+        ScreenRect::new(x + t, y + h - t, w - 2 * t, t),
+        ScreenRect::new(x, y + half + t / 2, t, vertical),
+        ScreenRect::new(x, y + t, t, vertical),
+        ScreenRect::new(x + t, y + half - t / 2, w - 2 * t, t),
+    ];
+    for (bit, rect) in rects.iter().enumerate() {
+        if segments & (1 << bit) != 0 {
+            graphics.fill_rounded_rect(rect, 3, theme.foreground)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 绘制底部状态行：WiFi图标、电池图标与电量百分比
+fn draw_status(
+    graphics: &mut GraphicsPrimitives,
+    wifi_level: Option<u8>,
+    battery_percent: Option<u8>,
+) -> anyhow::Result<()> {
+    let theme = theme::current();
+    let top = STATUS_TOP;
+    let left = SCREEN_CENTER_X - ROW_WIDTH / 2;
+    graphics.fill_rect(&ScreenRect::new(left, top, ROW_WIDTH, 24), theme.background)?;
+
+    if let Some(level) = wifi_level {
+        graphics.draw_glyph(
+            &WIFI_ICONS[level.min(4) as usize],
+            WIFI_ICON_WIDTH,
+            left + 14,
+            top + (20 - WIFI_ICON_HEIGHT) / 2,
+            theme.foreground,
+            None,
+        )?;
+    }
+
+    if let Some(percent) = battery_percent {
+        // 电池外框与正极
+        let body = ScreenRect::new(left + 46, top + 3, 28, 14);
+        graphics.draw_rect_border(&body, theme.foreground, 2)?;
+        graphics.fill_rect(&ScreenRect::new(left + 74, top + 7, 3, 6), theme.foreground)?;
+
+        // 电量低于20%时用警告色
+        let fill_color = if percent < 20 {
+            theme.warning
+        } else {
+            theme.accent
+        };
+        let fill_width = 24 * percent.min(100) as i32 / 100;
+        if fill_width > 0 {
+            graphics.fill_rect(
+                &ScreenRect::new(left + 48, top + 5, fill_width, 10),
+                fill_color,
+            )?;
+        }
+
+        graphics.draw_text(
+            &format!("{}%", percent),
+            left + 84,
+            top + 16,
+            theme.foreground,
+            Some(theme.background),
+        )?;
+    }
+
+    Ok(())
+}
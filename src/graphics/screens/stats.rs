@@ -0,0 +1,140 @@
+use crate::{
+    app::frame_pacing::FrameStats,
+    graphics::{
+        layout::{scaled, SCREEN_CENTER_X},
+        primitives::GraphicsPrimitives,
+        theme,
+    },
+    peripherals::storage::{format_bytes, StorageSpace},
+    stats::{format_duration, ReliabilityStats},
+};
+
+/// 更新运行统计界面
+///
+/// # 参数
+/// * `stats` - 可靠性统计快照
+/// * `storage` - 已挂载存储的空间使用情况
+/// * `frames` - 界面实际帧率与目标帧率
+pub fn draw(
+    graphics: &mut GraphicsPrimitives,
+    stats: &ReliabilityStats,
+    storage: &[StorageSpace],
+    frames: &FrameStats,
+) -> anyhow::Result<()> {
+    let theme = theme::current();
+    graphics.draw_text(
+        "运行统计",
+        SCREEN_CENTER_X,
+        scaled(50),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+
+    // 实际帧率明显低于目标时用黄色提示
+    let fps_color = if frames.measured_fps < frames.target_fps as f32 * 0.8 {
+        theme.warning
+    } else {
+        theme.muted
+    };
+    graphics.draw_text(
+        &format!(
+            "帧率: {:.1}/{}fps 跳帧: {}",
+            frames.measured_fps, frames.target_fps, frames.skipped
+        ),
+        scaled(60),
+        scaled(80),
+        fps_color,
+        Some(theme.background),
+    )?;
+
+    graphics.draw_text(
+        &format!("本次运行: {}", format_duration(stats.session_uptime_secs)),
+        scaled(60),
+        scaled(110),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        &format!("累计运行: {}", format_duration(stats.total_uptime_secs)),
+        scaled(60),
+        scaled(140),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        &format!("启动次数: {}", stats.boot_count),
+        scaled(60),
+        scaled(170),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+
+    // 有崩溃记录时用黄色提示
+    let crash_color = if stats.crash_count > 0 {
+        theme.warning
+    } else {
+        theme.foreground
+    };
+    graphics.draw_text(
+        &format!("崩溃次数: {}", stats.crash_count),
+        scaled(60),
+        scaled(200),
+        crash_color,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        &format!(
+            "WiFi断开: {}  跌落: {}",
+            stats.wifi_disconnects, stats.drops
+        ),
+        scaled(60),
+        scaled(230),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        &format!(
+            "对话: {}次  说话: {}",
+            stats.conversations,
+            format_duration(stats.speaking_ms / 1000)
+        ),
+        scaled(60),
+        scaled(260),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+
+    let storage_text = storage
+        .iter()
+        .map(|space| {
+            format!(
+                "{} {}",
+                space.location.name(),
+                format_bytes(space.free_bytes())
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" / ");
+    graphics.draw_text(
+        &format!("可用空间: {}", storage_text),
+        scaled(60),
+        scaled(290),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+
+    // 有崩溃记录时显示最近一次崩溃的原因和所在界面
+    let footer = match stats.crashes.last() {
+        Some(crash) => format!("上次崩溃: {} @ {}", crash.reason, crash.app_state),
+        None => format!("复位原因: {}", stats.last_reset_reason),
+    };
+    graphics.draw_text(
+        &footer,
+        SCREEN_CENTER_X,
+        scaled(330),
+        theme.accent,
+        Some(theme.background),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use embedded_graphics::pixelcolor::Rgb565;
+
+use crate::graphics::{
+    colors,
+    layout::{scaled, ScreenRect, SCREEN_CENTER_X, SCREEN_CIRCLE, TEXT_CHAR_WIDTH},
+    primitives::GraphicsPrimitives,
+};
+
+/// 三原色条与标注：颜色与字母不一致说明颜色分量顺序不对，整体反相说明反色设置不对
+const PRIMARIES: [(Rgb565, &str); 3] = [
+    (colors::RED, "R"),
+    (colors::GREEN, "G"),
+    (colors::BLUE, "B"),
+];
+/// 灰阶级数：相邻两级应当都能分辨，暗部连成一片或亮部发白说明伽马曲线不合适
+const GRAY_STEPS: i32 = 16;
+
+/// 更新屏幕测试图，用于检查`LcdConfig`中的颜色顺序、反色与伽马设置
+pub fn draw(graphics: &mut GraphicsPrimitives) -> anyhow::Result<()> {
+    let area = SCREEN_CIRCLE.safe_area(scaled(10));
+    let band_height = area.height / 3;
+
+    let bar_width = area.width / PRIMARIES.len() as i32;
+    for (index, (color, label)) in PRIMARIES.iter().enumerate() {
+        let x = area.x + index as i32 * bar_width;
+        graphics.fill_rect(&ScreenRect::new(x, area.y, bar_width, band_height), *color)?;
+        graphics.draw_text(
+            label,
+            x + (bar_width - TEXT_CHAR_WIDTH) / 2,
+            area.y + band_height / 2 + 6,
+            colors::WHITE,
+            None,
+        )?;
+    }
+
+    let step_width = area.width / GRAY_STEPS;
+    let gray_y = area.y + band_height;
+    for step in 0..GRAY_STEPS {
+        graphics.fill_rect(
+            &ScreenRect::new(area.x + step * step_width, gray_y, step_width, band_height),
+            gray(step),
+        )?;
+    }
+
+    let footer_y = gray_y + band_height;
+    graphics.fill_rect(
+        &ScreenRect::new(area.x, footer_y, area.width, band_height),
+        colors::BLACK,
+    )?;
+    graphics.draw_text(
+        "屏幕测试图",
+        SCREEN_CENTER_X,
+        footer_y + scaled(30),
+        colors::WHITE,
+        Some(colors::BLACK),
+    )?;
+    graphics.draw_text(
+        "按 B 键返回",
+        SCREEN_CENTER_X,
+        footer_y + scaled(60),
+        colors::GRAY,
+        Some(colors::BLACK),
+    )?;
+    Ok(())
+}
+
+/// 第`step`级灰阶，从黑到白等分
+fn gray(step: i32) -> Rgb565 {
+    let level = (step * 255 / (GRAY_STEPS - 1)) as u8;
+    Rgb565::new(level >> 3, level >> 2, level >> 3)
+}
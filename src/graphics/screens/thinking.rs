@@ -1,22 +1,80 @@
-use crate::graphics::{
-    colors::{BLACK, GREEN, WHITE},
-    primitives::GraphicsPrimitives,
+use std::time::Duration;
+
+use crate::{
+    api::types::ChatStage,
+    graphics::{
+        animation::cycle_phase,
+        layout::{scaled, SCREEN_CENTER_X},
+        primitives::GraphicsPrimitives,
+        theme,
+    },
 };
 
+/// 处理阶段的显示文字
+fn stage_text(stage: Option<ChatStage>, uploading: bool) -> &'static str {
+    match stage {
+        None if uploading => "上传语音...",
+        None => "思考中...",
+        Some(ChatStage::Transcribing) => "识别语音...",
+        Some(ChatStage::Generating) => "生成回复...",
+        Some(ChatStage::Speaking) => "合成语音...",
+    }
+}
+
 /// 更新思考状态
-pub fn draw(graphics: &mut GraphicsPrimitives, state_timer: u32) -> anyhow::Result<()> {
-    // 绘制思考界面
-    graphics.draw_text("思考中...", 180, 150, WHITE, Some(BLACK))?;
+///
+/// # 参数
+/// * `model` - 正在使用的模型名称，None表示服务端默认模型
+/// * `stage` - 服务端推送的处理阶段，尚未收到时为None
+/// * `uploading` - 是否正在上传语音
+/// * `elapsed` - 已等待的时间，用于显示秒数和加载动画
+pub fn draw(
+    graphics: &mut GraphicsPrimitives,
+    model: Option<&str>,
+    stage: Option<ChatStage>,
+    uploading: bool,
+    elapsed: Duration,
+) -> anyhow::Result<()> {
+    let theme = theme::current();
+
+    // 绘制思考界面，文字后补空格覆盖上一阶段较长的文字
+    graphics.draw_text(
+        &format!("{:<8}", stage_text(stage, uploading)),
+        SCREEN_CENTER_X,
+        scaled(150),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        model.unwrap_or("默认模型"),
+        SCREEN_CENTER_X,
+        scaled(250),
+        theme.muted,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        &format!("{:>3}秒", elapsed.as_secs()),
+        SCREEN_CENTER_X,
+        scaled(280),
+        theme.muted,
+        Some(theme.background),
+    )?;
 
     // 绘制简单的加载动画
-    let dots = match (state_timer / 10) % 4 {
+    let dots = match cycle_phase(elapsed, Duration::from_millis(500), 4) {
         0 => "   ",
         1 => ".  ",
         2 => ".. ",
         3 => "...",
         _ => "   ",
     };
-    graphics.draw_text(dots, 180, 200, GREEN, Some(BLACK))?;
+    graphics.draw_text(
+        dots,
+        SCREEN_CENTER_X,
+        scaled(200),
+        theme.accent,
+        Some(theme.background),
+    )?;
 
     Ok(())
 }
@@ -1,13 +1,28 @@
 use crate::graphics::{
-    colors::{BLACK, WHITE, YELLOW},
+    layout::{scaled, SCREEN_CENTER_X},
     primitives::GraphicsPrimitives,
+    theme,
 };
 
 /// 更新倾斜状态
 pub fn draw(graphics: &mut GraphicsPrimitives) -> anyhow::Result<()> {
+    let theme = theme::current();
+
     // 绘制倾斜状态
-    graphics.draw_text("Device Is Tilting", 180, 150, YELLOW, Some(BLACK))?;
-    graphics.draw_text("Please Keep The Device Level", 180, 200, WHITE, Some(BLACK))?;
+    graphics.draw_text(
+        "Device Is Tilting",
+        SCREEN_CENTER_X,
+        scaled(150),
+        theme.warning,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        "Please Keep The Device Level",
+        SCREEN_CENTER_X,
+        scaled(200),
+        theme.foreground,
+        Some(theme.background),
+    )?;
 
     Ok(())
 }
@@ -0,0 +1,43 @@
+use crate::{
+    graphics::{
+        layout::{scaled, SCREEN_CENTER_X, SCREEN_CENTER_Y},
+        primitives::GraphicsPrimitives,
+        theme,
+    },
+    peripherals::speaker::volume::Volume,
+};
+
+/// 更新音量界面：进度环显示音量大小，中间显示数值
+///
+/// # 参数
+/// * `volume` - 当前音量
+pub fn draw(graphics: &mut GraphicsPrimitives, volume: &Volume) -> anyhow::Result<()> {
+    let theme = theme::current();
+    let level = if volume.is_muted() { 0 } else { volume.level() };
+    graphics.draw_progress_ring(
+        SCREEN_CENTER_X,
+        SCREEN_CENTER_Y,
+        scaled(140),
+        level,
+        theme.accent,
+        theme.surface,
+        scaled(16),
+    )?;
+
+    graphics.draw_text(
+        "音量",
+        SCREEN_CENTER_X,
+        scaled(140),
+        theme.foreground,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        &format!("{:^6}", volume.label()),
+        SCREEN_CENTER_X,
+        SCREEN_CENTER_Y,
+        theme.foreground,
+        Some(theme.background),
+    )?;
+
+    Ok(())
+}
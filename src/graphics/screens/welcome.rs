@@ -1,16 +1,37 @@
 use crate::graphics::{
-    colors::{BLACK, BLUE, GREEN, WHITE},
+    layout::{SCREEN_CENTER_X, SCREEN_CENTER_Y},
     primitives::GraphicsPrimitives,
+    theme,
 };
 
 /// 更新欢迎界面
 pub fn draw(graphics: &mut GraphicsPrimitives) -> anyhow::Result<()> {
+    let theme = theme::current();
+
     // 绘制欢迎界面 - 垂直居中显示
-    let center_y = 180; // 屏幕中心Y坐标
+    let center_y = SCREEN_CENTER_Y;
 
-    graphics.draw_text("AI Chat", 180, center_y - 40, WHITE, Some(BLACK))?;
-    graphics.draw_text("ESP32-S3", 180, center_y, GREEN, Some(BLACK))?;
-    graphics.draw_text("Click Any Key", 180, center_y + 40, BLUE, Some(BLACK))?;
+    graphics.draw_text(
+        "AI Chat",
+        SCREEN_CENTER_X,
+        center_y - 40,
+        theme.foreground,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        "ESP32-S3",
+        SCREEN_CENTER_X,
+        center_y,
+        theme.accent,
+        Some(theme.background),
+    )?;
+    graphics.draw_text(
+        "Click Any Key",
+        SCREEN_CENTER_X,
+        center_y + 40,
+        theme.muted,
+        Some(theme.background),
+    )?;
 
     Ok(())
 }
@@ -0,0 +1,171 @@
+// 界面主题：深色/浅色模式与强调色
+//
+// 各界面不直接使用固定颜色，而是通过`theme::current()`取得当前主题的配色，
+// 切换主题后下一帧即按新配色绘制。
+
+use std::sync::RwLock;
+
+use embedded_graphics::pixelcolor::Rgb565;
+use serde::{Deserialize, Serialize};
+
+use crate::graphics::colors::{
+    BLACK, DARK_GRAY, GRAY, GREEN, LIGHT_GRAY, ORANGE, PINK, RED, WHITE, YELLOW,
+};
+
+/// 明暗模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeMode {
+    /// 深色背景，适合圆形屏幕和夜间使用
+    #[default]
+    Dark,
+    /// 浅色背景
+    Light,
+}
+
+impl ThemeMode {
+    /// 所有模式，按设置界面中的顺序排列
+    pub const ALL: [ThemeMode; 2] = [ThemeMode::Dark, ThemeMode::Light];
+
+    /// 界面显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThemeMode::Dark => "深色",
+            ThemeMode::Light => "浅色",
+        }
+    }
+}
+
+/// 强调色，用于操作提示、选中项和进度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Accent {
+    #[default]
+    Green,
+    Blue,
+    Orange,
+    Pink,
+}
+
+impl Accent {
+    /// 所有强调色，按设置界面中的顺序排列
+    pub const ALL: [Accent; 4] = [Accent::Green, Accent::Blue, Accent::Orange, Accent::Pink];
+
+    /// 界面显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Accent::Green => "绿",
+            Accent::Blue => "蓝",
+            Accent::Orange => "橙",
+            Accent::Pink => "粉",
+        }
+    }
+
+    /// 强调色的RGB565值
+    pub fn color(&self) -> Rgb565 {
+        match self {
+            Accent::Green => GREEN,
+            // 纯蓝在深色背景上太暗，使用偏亮的蓝色
+            Accent::Blue => Rgb565::new(4, 32, 31),
+            Accent::Orange => ORANGE,
+            Accent::Pink => PINK,
+        }
+    }
+}
+
+/// 保存在设备配置中的主题选择
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub mode: ThemeMode,
+    pub accent: Accent,
+}
+
+impl ThemeConfig {
+    /// 设置界面中可选的全部主题：先深色后浅色，每种模式依次为各强调色
+    pub fn all() -> Vec<ThemeConfig> {
+        ThemeMode::ALL
+            .iter()
+            .flat_map(|&mode| {
+                Accent::ALL
+                    .iter()
+                    .map(move |&accent| ThemeConfig { mode, accent })
+            })
+            .collect()
+    }
+
+    /// 界面显示名称，例如"深色/绿"
+    pub fn name(&self) -> String {
+        format!("{}/{}", self.mode.name(), self.accent.name())
+    }
+}
+
+/// 界面配色
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// 背景色
+    pub background: Rgb565,
+    /// 进度环轨道等次级填充色
+    pub surface: Rgb565,
+    /// 正文文字
+    pub foreground: Rgb565,
+    /// 次要文字（模型名、耗时等）
+    pub muted: Rgb565,
+    /// 强调色（操作提示、选中项）
+    pub accent: Rgb565,
+    /// 警告
+    pub warning: Rgb565,
+    /// 错误
+    pub error: Rgb565,
+}
+
+impl Theme {
+    /// 默认的深色绿主题
+    pub const DARK: Theme = Theme {
+        background: BLACK,
+        surface: DARK_GRAY,
+        foreground: WHITE,
+        muted: GRAY,
+        accent: GREEN,
+        warning: YELLOW,
+        error: RED,
+    };
+
+    /// 浅色绿主题
+    pub const LIGHT: Theme = Theme {
+        background: WHITE,
+        surface: LIGHT_GRAY,
+        foreground: BLACK,
+        muted: DARK_GRAY,
+        accent: GREEN,
+        warning: ORANGE,
+        error: RED,
+    };
+
+    /// 根据主题选择生成配色
+    pub fn from_config(config: &ThemeConfig) -> Theme {
+        let base = match config.mode {
+            ThemeMode::Dark => Theme::DARK,
+            ThemeMode::Light => Theme::LIGHT,
+        };
+        Theme {
+            accent: config.accent.color(),
+            ..base
+        }
+    }
+}
+
+/// 当前主题，由主循环设置，各界面绘制时读取
+static CURRENT: RwLock<Theme> = RwLock::new(Theme::DARK);
+
+/// 获取当前主题
+pub fn current() -> Theme {
+    *CURRENT.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 应用主题选择
+///
+/// 只修改后续绘制使用的配色，调用方需自行清屏重绘。
+pub fn apply(config: &ThemeConfig) {
+    *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = Theme::from_config(config);
+}
@@ -0,0 +1,319 @@
+use anyhow::Result;
+use embedded_graphics::pixelcolor::Rgb565;
+
+use super::emoji;
+use super::traits::UIComponent;
+use crate::graphics::layout::{scaled, ScreenRect, TEXT_CHAR_WIDTH, TEXT_LINE_HEIGHT};
+use crate::graphics::primitives::GraphicsPrimitives;
+use crate::graphics::theme;
+
+/// 气泡最大宽度
+pub const BUBBLE_MAX_WIDTH: i32 = scaled(210);
+
+/// 文字与气泡边缘的水平间距
+const PADDING_X: i32 = scaled(8);
+/// 文字与气泡边缘的垂直间距
+const PADDING_Y: i32 = 4;
+/// 圆角半径
+const CORNER_RADIUS: u32 = scaled(12) as u32;
+/// 字体基线到字符顶部的距离（FONT_10X20）
+const TEXT_BASELINE: i32 = 16;
+
+/// 每行最多显示的字符数
+const LINE_CHARS: usize = ((BUBBLE_MAX_WIDTH - PADDING_X * 2) / TEXT_CHAR_WIDTH) as usize;
+
+/// 消息发送方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatRole {
+    /// 用户，气泡靠右
+    User,
+    /// 助手，气泡靠左
+    Assistant,
+}
+
+/// 对话气泡
+///
+/// 创建时完成折行并确定尺寸，绘制前用`set_position`放到列表中的位置。
+/// 流式回复使用`streaming`创建固定宽度的空气泡，再用`append`逐段追加文字。
+#[derive(Debug, Clone)]
+pub struct ChatBubble {
+    role: ChatRole,
+    lines: Vec<String>,
+    rect: ScreenRect,
+    /// 最多显示的行数
+    max_lines: usize,
+    /// 是否已经因超出行数而截断，之后追加的文字被丢弃
+    truncated: bool,
+}
+
+impl ChatBubble {
+    /// 创建对话气泡
+    ///
+    /// # 参数
+    /// * `role` - 消息发送方
+    /// * `text` - 消息文本
+    /// * `max_lines` - 最多显示的行数，超出部分以省略号结尾
+    pub fn new(role: ChatRole, text: &str, max_lines: usize) -> Self {
+        let max_lines = max_lines.max(1);
+        let mut lines = wrap_text(text, LINE_CHARS);
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        let truncated = lines.len() > max_lines;
+        if truncated {
+            lines.truncate(max_lines);
+            if let Some(last) = lines.last_mut() {
+                last.push_str("...");
+            }
+        }
+
+        let widest = lines
+            .iter()
+            .map(|line| emoji::text_cells(line).min(LINE_CHARS) as i32)
+            .max()
+            .unwrap_or(0);
+        let width = widest * TEXT_CHAR_WIDTH + PADDING_X * 2;
+        let height = lines.len() as i32 * TEXT_LINE_HEIGHT + PADDING_Y * 2;
+
+        Self {
+            role,
+            lines,
+            rect: ScreenRect::new(0, 0, width, height),
+            max_lines,
+            truncated,
+        }
+    }
+
+    /// 创建用于流式回复的空气泡
+    ///
+    /// 宽度固定为最大宽度，追加文字时只有高度变化，已绘制的行不需要重绘。
+    ///
+    /// # 参数
+    /// * `role` - 消息发送方
+    /// * `max_lines` - 最多显示的行数，超出部分以省略号结尾
+    pub fn streaming(role: ChatRole, max_lines: usize) -> Self {
+        let mut bubble = Self::new(role, "", max_lines);
+        bubble.rect.width = BUBBLE_MAX_WIDTH;
+        bubble
+    }
+
+    /// 追加文字
+    ///
+    /// # 返回值
+    /// 内容变化时返回需要重绘的第一行
+    pub fn append(&mut self, text: &str) -> Option<usize> {
+        if self.truncated || text.is_empty() {
+            return None;
+        }
+
+        let first_changed = self.lines.len() - 1;
+        for c in text.chars() {
+            let width = emoji::char_cells(c);
+            let line_full = self.lines.last().is_some_and(|line| {
+                !line.is_empty() && emoji::text_cells(line) + width > LINE_CHARS
+            });
+            if c == '\n' || line_full {
+                if self.lines.len() >= self.max_lines {
+                    if let Some(last) = self.lines.last_mut() {
+                        last.push_str("...");
+                    }
+                    self.truncated = true;
+                    break;
+                }
+                self.lines.push(String::new());
+            }
+            if c != '\n' {
+                if let Some(last) = self.lines.last_mut() {
+                    last.push(c);
+                }
+            }
+        }
+
+        self.rect.height = self.lines.len() as i32 * TEXT_LINE_HEIGHT + PADDING_Y * 2;
+        Some(first_changed)
+    }
+
+    /// 消息发送方
+    pub fn role(&self) -> ChatRole {
+        self.role
+    }
+
+    /// 气泡高度
+    pub fn height(&self) -> i32 {
+        self.rect.height
+    }
+
+    /// 气泡顶部
+    pub fn top(&self) -> i32 {
+        self.rect.y
+    }
+
+    /// 设置气泡位置
+    ///
+    /// # 参数
+    /// * `left` - 列表左边界，助手气泡贴齐
+    /// * `right` - 列表右边界，用户气泡贴齐
+    /// * `top` - 气泡顶部
+    pub fn set_position(&mut self, left: i32, right: i32, top: i32) {
+        self.rect.x = match self.role {
+            ChatRole::User => right - self.rect.width,
+            ChatRole::Assistant => left,
+        };
+        self.rect.y = top;
+    }
+
+    /// 从指定行开始重绘，之前的行保持不变
+    ///
+    /// 只重绘该行及以下的背景（包括底部圆角）和文字，用于流式追加后的局部刷新。
+    pub fn render_from(&self, graphics: &mut GraphicsPrimitives, first_line: usize) -> Result<()> {
+        if first_line == 0 {
+            return self.render(graphics);
+        }
+
+        let fill = self.colors().0;
+        let line_top = self.rect.y + PADDING_Y + first_line as i32 * TEXT_LINE_HEIGHT;
+        let bottom = self.rect.y + self.rect.height;
+        let radius = CORNER_RADIUS as i32;
+
+        // 先画带圆角的底部，再用直角矩形盖住它上方的圆角
+        let cap_top = (bottom - radius * 2).max(line_top);
+        graphics.fill_rounded_rect(
+            &ScreenRect::new(self.rect.x, cap_top, self.rect.width, bottom - cap_top),
+            CORNER_RADIUS,
+            fill,
+        )?;
+        let body_bottom = (bottom - radius).max(line_top);
+        graphics.fill_rect(
+            &ScreenRect::new(
+                self.rect.x,
+                line_top,
+                self.rect.width,
+                body_bottom - line_top,
+            ),
+            fill,
+        )?;
+        self.draw_lines(graphics, first_line)
+    }
+
+    /// (背景色, 文字颜色)
+    fn colors(&self) -> (Rgb565, Rgb565) {
+        let theme = theme::current();
+        match self.role {
+            ChatRole::User => (theme.accent, theme.background),
+            ChatRole::Assistant => (theme.surface, theme.foreground),
+        }
+    }
+
+    fn draw_lines(&self, graphics: &mut GraphicsPrimitives, first_line: usize) -> Result<()> {
+        let (fill, text_color) = self.colors();
+        let lines: Vec<&str> = self.lines[first_line..]
+            .iter()
+            .map(|line| line.as_str())
+            .collect();
+        graphics.draw_multiline_text(
+            &lines,
+            self.rect.x + PADDING_X,
+            self.rect.y + PADDING_Y + TEXT_BASELINE + first_line as i32 * TEXT_LINE_HEIGHT,
+            text_color,
+            Some(fill),
+        )
+    }
+}
+
+impl UIComponent for ChatBubble {
+    fn render(&self, graphics: &mut GraphicsPrimitives) -> Result<()> {
+        graphics.fill_rounded_rect(&self.rect, CORNER_RADIUS, self.colors().0)?;
+        self.draw_lines(graphics, 0)
+    }
+
+    fn get_bounds(&self) -> (i32, i32, i32, i32) {
+        (self.rect.x, self.rect.y, self.rect.width, self.rect.height)
+    }
+}
+
+/// 按显示宽度折行，遇到换行符时强制换行
+///
+/// 表情图片占两个字符宽度，不会被拆到两行。
+pub fn wrap_text(text: &str, line_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut cells = 0;
+        for c in paragraph.chars() {
+            let width = emoji::char_cells(c);
+            if !line.is_empty() && cells + width > line_chars {
+                lines.push(std::mem::take(&mut line));
+                cells = 0;
+            }
+            line.push(c);
+            cells += width;
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_text() {
+        assert_eq!(wrap_text("abcdef", 4), vec!["abcd", "ef"]);
+        assert_eq!(wrap_text("ab\n\ncd", 4), vec!["ab", "", "cd"]);
+        // 表情占两个字符宽度，放不下时整体换行
+        assert_eq!(wrap_text("abc\u{1F600}d", 4), vec!["abc", "\u{1F600}d"]);
+    }
+
+    #[test]
+    fn test_bubble_truncates_and_aligns() {
+        let text = "x".repeat(LINE_CHARS * 5);
+        let mut bubble = ChatBubble::new(ChatRole::User, &text, 3);
+        assert_eq!(bubble.lines.len(), 3);
+        assert!(bubble.lines[2].ends_with("..."));
+        assert_eq!(bubble.height(), 3 * TEXT_LINE_HEIGHT + PADDING_Y * 2);
+
+        bubble.set_position(10, 300, 50);
+        let (x, y, width, _) = bubble.get_bounds();
+        assert_eq!(x + width, 300);
+        assert_eq!(y, 50);
+
+        let mut reply = ChatBubble::new(ChatRole::Assistant, "hi", 3);
+        reply.set_position(10, 300, 50);
+        assert_eq!(reply.get_bounds().0, 10);
+        assert_eq!(reply.get_bounds().2, 2 * TEXT_CHAR_WIDTH + PADDING_X * 2);
+    }
+
+    #[test]
+    fn test_streaming_append() {
+        let mut bubble = ChatBubble::streaming(ChatRole::Assistant, 2);
+        assert_eq!(bubble.get_bounds().2, BUBBLE_MAX_WIDTH);
+        assert_eq!(bubble.append(""), None);
+        assert_eq!(bubble.append("ab"), Some(0));
+        assert_eq!(bubble.lines, vec!["ab"]);
+
+        // 写满一行后换行，只需从原来的最后一行开始重绘
+        let rest = "x".repeat(LINE_CHARS);
+        assert_eq!(bubble.append(&rest), Some(0));
+        assert_eq!(bubble.lines.len(), 2);
+        assert_eq!(bubble.append("\ny"), Some(1));
+        assert!(bubble.truncated);
+        assert!(bubble.lines[1].ends_with("..."));
+        assert_eq!(bubble.append("z"), None);
+        assert_eq!(bubble.height(), 2 * TEXT_LINE_HEIGHT + PADDING_Y * 2);
+        assert_eq!(bubble.get_bounds().2, BUBBLE_MAX_WIDTH);
+    }
+
+    #[test]
+    fn test_streaming_matches_wrapped_text() {
+        let text = "hello world, this is a streamed reply \u{1F600}\u{1F44D}\nsecond paragraph";
+        let mut bubble = ChatBubble::streaming(ChatRole::Assistant, 10);
+        for token in text.split_inclusive(' ') {
+            bubble.append(token);
+        }
+        assert_eq!(
+            bubble.lines,
+            ChatBubble::new(ChatRole::Assistant, text, 10).lines
+        );
+    }
+}
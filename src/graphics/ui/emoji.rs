@@ -0,0 +1,629 @@
+// 聊天文字中的表情图片
+//
+// FONT_10X20只包含ASCII与半角片假名，回复里的emoji会显示成乱码。
+// 这里收录常用emoji的20x20小图，文字排版时把对应码位替换为图片，
+// 每个图片占两个字符宽度。没有收录的emoji统一显示为方框。
+//
+// 图片按行存储为字符串，每个字符对应一个像素，颜色见`palette`。
+
+use embedded_graphics::pixelcolor::Rgb565;
+
+use crate::graphics::{colors::WHITE, layout::TEXT_CHAR_WIDTH};
+
+/// 表情图片边长（像素）
+pub const EMOJI_SIZE: i32 = 20;
+/// 表情图片占用的字符宽度
+pub const EMOJI_CELLS: usize = 2;
+
+const _: () = assert!(EMOJI_SIZE == EMOJI_CELLS as i32 * TEXT_CHAR_WIDTH);
+
+/// 表情图片
+#[derive(Debug, PartialEq, Eq)]
+pub struct Emoji {
+    rows: [&'static str; EMOJI_SIZE as usize],
+}
+
+impl Emoji {
+    /// 遍历图片中不透明的像素
+    ///
+    /// # 参数
+    /// * `foreground` - 文字颜色，用于跟随文字颜色的像素
+    ///
+    /// # 返回值
+    /// (列, 行, 颜色)
+    pub fn pixels(&self, foreground: Rgb565) -> impl Iterator<Item = (i32, i32, Rgb565)> + '_ {
+        self.rows.iter().enumerate().flat_map(move |(row, line)| {
+            line.bytes().enumerate().filter_map(move |(col, pixel)| {
+                palette(pixel, foreground).map(|color| (col as i32, row as i32, color))
+            })
+        })
+    }
+}
+
+/// 像素字符对应的颜色，'.'为透明
+fn palette(pixel: u8, foreground: Rgb565) -> Option<Rgb565> {
+    match pixel {
+        b'Y' => Some(Rgb565::new(31, 54, 4)),
+        b'O' => Some(Rgb565::new(29, 38, 0)),
+        b'K' => Some(Rgb565::new(9, 12, 2)),
+        b'W' => Some(WHITE),
+        b'R' => Some(Rgb565::new(30, 8, 6)),
+        b'B' => Some(Rgb565::new(8, 42, 31)),
+        b'G' => Some(Rgb565::new(6, 48, 10)),
+        b'P' => Some(Rgb565::new(31, 38, 20)),
+        b'F' => Some(foreground),
+        _ => None,
+    }
+}
+
+/// 排版后的一段文字
+#[derive(Debug, PartialEq, Eq)]
+pub enum TextRun<'a> {
+    /// 直接用字体绘制的文字
+    Text(&'a str),
+    /// 表情图片
+    Emoji(&'static Emoji),
+}
+
+/// 把文字拆分为普通文字与表情图片
+///
+/// 变体选择符、零宽连接符和肤色修饰符不占位置，直接丢弃，
+/// 因此组合emoji会显示为几个独立的图片。
+pub fn shape(text: &str) -> Vec<TextRun<'_>> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        if !is_special(c) {
+            continue;
+        }
+        if start < index {
+            runs.push(TextRun::Text(&text[start..index]));
+        }
+        if let Some(emoji) = lookup(c) {
+            runs.push(TextRun::Emoji(emoji));
+        }
+        start = index + c.len_utf8();
+    }
+    if start < text.len() {
+        runs.push(TextRun::Text(&text[start..]));
+    }
+    runs
+}
+
+/// 文字中是否没有需要替换的字符
+pub fn is_plain(text: &str) -> bool {
+    !text.chars().any(is_special)
+}
+
+/// 字符占用的字符宽度
+pub fn char_cells(c: char) -> usize {
+    if !is_special(c) {
+        1
+    } else if lookup(c).is_some() {
+        EMOJI_CELLS
+    } else {
+        0
+    }
+}
+
+/// 文字占用的字符宽度
+pub fn text_cells(text: &str) -> usize {
+    text.chars().map(char_cells).sum()
+}
+
+/// 是否需要在排版时替换或丢弃
+fn is_special(c: char) -> bool {
+    !c.is_ascii() && (is_pictograph(c) || is_ignorable(c))
+}
+
+/// 查找字符对应的表情图片，没有收录的emoji返回方框
+fn lookup(c: char) -> Option<&'static Emoji> {
+    if !is_pictograph(c) {
+        return None;
+    }
+    Some(match c {
+        '\u{1F600}' | '\u{1F601}' | '\u{1F603}' | '\u{1F604}' | '\u{1F606}' => &GRINNING,
+        '\u{1F602}' | '\u{1F923}' => &JOY,
+        '\u{1F60A}' | '\u{1F642}' | '\u{263A}' => &SMILE,
+        '\u{1F609}' => &WINK,
+        '\u{1F622}' | '\u{1F62D}' | '\u{1F61E}' | '\u{1F641}' | '\u{2639}' => &CRY,
+        '\u{1F610}' | '\u{1F611}' | '\u{1F914}' => &NEUTRAL,
+        '\u{1F62E}' | '\u{1F62F}' | '\u{1F632}' => &SURPRISED,
+        '\u{1F60E}' => &COOL,
+        '\u{2764}' | '\u{2665}' | '\u{1F493}' | '\u{1F495}' | '\u{1F496}' | '\u{1F497}' => &HEART,
+        '\u{2B50}' | '\u{1F31F}' | '\u{2728}' => &STAR,
+        '\u{2705}' | '\u{2714}' | '\u{2611}' => &CHECK,
+        '\u{274C}' | '\u{2716}' => &CROSS,
+        '\u{1F44D}' => &THUMBS_UP,
+        '\u{2600}' | '\u{1F31E}' => &SUN,
+        '\u{1F319}' => &MOON,
+        _ => &FALLBACK,
+    })
+}
+
+/// 显示为表情图片的码位范围
+fn is_pictograph(c: char) -> bool {
+    matches!(
+        c,
+        '\u{2600}'..='\u{27BF}' | '\u{2B50}'..='\u{2B55}' | '\u{1F000}'..='\u{1FAFF}'
+    ) && !is_ignorable(c)
+}
+
+/// 不占位置的修饰字符
+fn is_ignorable(c: char) -> bool {
+    matches!(
+        c,
+        '\u{200D}'
+            | '\u{20E3}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{1F3FB}'..='\u{1F3FF}'
+            | '\u{E0020}'..='\u{E007F}'
+    )
+}
+
+/// 咧嘴笑
+const GRINNING: Emoji = Emoji {
+    rows: [
+        ".........OO.........",
+        "......OOOYYOOO......",
+        "....OOYYYYYYYYOO....",
+        "...OYYYYYYYYYYYYO...",
+        "..OYYYYYYYYYYYYYYO..",
+        "..OYYYYYYYYYYYYYYO..",
+        ".OYYYYKKYYYYKKYYYYO.",
+        ".OYYYYKKYYYYKKYYYYO.",
+        ".OYYYYKKYYYYKKYYYYO.",
+        "OYYYYYYYYYYYYYYYYYYO",
+        "OYYYYYYYYYYYYYYYYYYO",
+        ".OYYYKWWWWWWWWKYYYO.",
+        ".OYYYKKKKKKKKKKYYYO.",
+        ".OYYYYKKKKKKKKYYYYO.",
+        "..OYYYYKKKKKKYYYYO..",
+        "..OYYYYYYYYYYYYYYO..",
+        "...OYYYYYYYYYYYYO...",
+        "....OOYYYYYYYYOO....",
+        "......OOOYYOOO......",
+        ".........OO.........",
+    ],
+};
+
+/// 笑出眼泪
+const JOY: Emoji = Emoji {
+    rows: [
+        ".........OO.........",
+        "......OOOYYOOO......",
+        "....OOYYYYYYYYOO....",
+        "...OYYYYYYYYYYYYO...",
+        "..OYYYYYYYYYYYYYYO..",
+        "..OYYYYYYYYYYYYYYO..",
+        ".OYYYYYYYYYYYYYYYYO.",
+        ".OYYYYKKYYYYYKKYYYO.",
+        ".OYYYKYYKYYYKYYKYYO.",
+        "OYBYYYYYYYYYYYYYYBYO",
+        "OYBYYYYYYYYYYYYYYBYO",
+        ".BBYYKWWWWWWWWKYYBB.",
+        ".BBYYKKKKKKKKKKYYBB.",
+        ".OYYYYKKKKKKKKYYYYO.",
+        "..OYYYYKKKKKKYYYYO..",
+        "..OYYYYYYYYYYYYYYO..",
+        "...OYYYYYYYYYYYYO...",
+        "....OOYYYYYYYYOO....",
+        "......OOOYYOOO......",
+        ".........OO.........",
+    ],
+};
+
+/// 微笑
+const SMILE: Emoji = Emoji {
+    rows: [
+        ".........OO.........",
+        "......OOOYYOOO......",
+        "....OOYYYYYYYYOO....",
+        "...OYYYYYYYYYYYYO...",
+        "..OYYYYYYYYYYYYYYO..",
+        "..OYYYYYYYYYYYYYYO..",
+        ".OYYYYYYYYYYYYYYYYO.",
+        ".OYYYYKKYYYYYKKYYYO.",
+        ".OYYYKYYKYYYKYYKYYO.",
+        "OYYYYYYYYYYYYYYYYYYO",
+        "OYYYYYYYYYYYYYYYYYYO",
+        ".OYYPPYYYYYYYYPPYYO.",
+        ".OYYYKYYYYYYYYKYYYO.",
+        ".OYYYYKYYYYYYKYYYYO.",
+        "..OYYYYKKKKKKYYYYO..",
+        "..OYYYYYYYYYYYYYYO..",
+        "...OYYYYYYYYYYYYO...",
+        "....OOYYYYYYYYOO....",
+        "......OOOYYOOO......",
+        ".........OO.........",
+    ],
+};
+
+/// 眨眼
+const WINK: Emoji = Emoji {
+    rows: [
+        ".........OO.........",
+        "......OOOYYOOO......",
+        "....OOYYYYYYYYOO....",
+        "...OYYYYYYYYYYYYO...",
+        "..OYYYYYYYYYYYYYYO..",
+        "..OYYYYYYYYYYYYYYO..",
+        ".OYYYYKKYYYYYYYYYYO.",
+        ".OYYYYKKYYYYKKYYYYO.",
+        ".OYYYYKKYYYKYYKYYYO.",
+        "OYYYYYYYYYYYYYYYYYYO",
+        "OYYYYYYYYYYYYYYYYYYO",
+        ".OYYYYYYYYYYYYYYYYO.",
+        ".OYYYKYYYYYYYYKYYYO.",
+        ".OYYYYKYYYYYYKYYYYO.",
+        "..OYYYYKKKKKKYYYYO..",
+        "..OYYYYYYYYYYYYYYO..",
+        "...OYYYYYYYYYYYYO...",
+        "....OOYYYYYYYYOO....",
+        "......OOOYYOOO......",
+        ".........OO.........",
+    ],
+};
+
+/// 哭泣
+const CRY: Emoji = Emoji {
+    rows: [
+        ".........OO.........",
+        "......OOOYYOOO......",
+        "....OOYYYYYYYYOO....",
+        "...OYYYYYYYYYYYYO...",
+        "..OYYYYYYYYYYYYYYO..",
+        "..OYYKKYYYYYYKKYYO..",
+        ".OYYYYYYYYYYYYYYYYO.",
+        ".OYYYYKKYYYYKKYYYYO.",
+        ".OYYYYKKYYYYKKYYYYO.",
+        "OYYYYYYYYYYYYYYYYYYO",
+        "OYYYYYBYYYYYYYYYYYYO",
+        ".OYYYYBYYYYYYYYYYYO.",
+        ".OYYYBBYYYYYYYYYYYO.",
+        ".OYYYBBYKKKKYYYYYYO.",
+        "..OYYYYKYYYYKYYYYO..",
+        "..OYYYYYYYYYYYYYYO..",
+        "...OYYYYYYYYYYYYO...",
+        "....OOYYYYYYYYOO....",
+        "......OOOYYOOO......",
+        ".........OO.........",
+    ],
+};
+
+/// 面无表情
+const NEUTRAL: Emoji = Emoji {
+    rows: [
+        ".........OO.........",
+        "......OOOYYOOO......",
+        "....OOYYYYYYYYOO....",
+        "...OYYYYYYYYYYYYO...",
+        "..OYYYYYYYYYYYYYYO..",
+        "..OYYYYYYYYYYYYYYO..",
+        ".OYYYYKKYYYYKKYYYYO.",
+        ".OYYYYKKYYYYKKYYYYO.",
+        ".OYYYYKKYYYYKKYYYYO.",
+        "OYYYYYYYYYYYYYYYYYYO",
+        "OYYYYYYYYYYYYYYYYYYO",
+        ".OYYYYYYYYYYYYYYYYO.",
+        ".OYYYYYYYYYYYYYYYYO.",
+        ".OYYYYKKKKKKKKYYYYO.",
+        "..OYYYYYYYYYYYYYYO..",
+        "..OYYYYYYYYYYYYYYO..",
+        "...OYYYYYYYYYYYYO...",
+        "....OOYYYYYYYYOO....",
+        "......OOOYYOOO......",
+        ".........OO.........",
+    ],
+};
+
+/// 惊讶
+const SURPRISED: Emoji = Emoji {
+    rows: [
+        ".........OO.........",
+        "......OOOYYOOO......",
+        "....OOYYYYYYYYOO....",
+        "...OYYYYYYYYYYYYO...",
+        "..OYYYYYYYYYYYYYYO..",
+        "..OYYYKKYYYYKKYYYO..",
+        ".OYYYYKKYYYYKKYYYYO.",
+        ".OYYYYKKYYYYKKYYYYO.",
+        ".OYYYYYYYYYYYYYYYYO.",
+        "OYYYYYYYYYYYYYYYYYYO",
+        "OYYYYYYYYYYYYYYYYYYO",
+        ".OYYYYYYKKKKYYYYYYO.",
+        ".OYYYYYYKKKKYYYYYYO.",
+        ".OYYYYYYKKKKYYYYYYO.",
+        "..OYYYYYKKKKYYYYYO..",
+        "..OYYYYYKKKKYYYYYO..",
+        "...OYYYYYYYYYYYYO...",
+        "....OOYYYYYYYYOO....",
+        "......OOOYYOOO......",
+        ".........OO.........",
+    ],
+};
+
+/// 墨镜
+const COOL: Emoji = Emoji {
+    rows: [
+        ".........OO.........",
+        "......OOOYYOOO......",
+        "....OOYYYYYYYYOO....",
+        "...OYYYYYYYYYYYYO...",
+        "..OYYYYYYYYYYYYYYO..",
+        "..OYYYYYYYYYYYYYYO..",
+        ".OYKKKKKKKKKKKKKKYO.",
+        ".OYYKWKKKYYKWKKKYYO.",
+        ".OYYKKKKKYYKKKKKYYO.",
+        "OYYYKKKKKYYKKKKKYYYO",
+        "OYYYYYYYYYYYYYYYYYYO",
+        ".OYYYYYYYYYYYYYYYYO.",
+        ".OYYYYYYYYYYYYYYYYO.",
+        ".OYYYKYYYYYYYYKYYYO.",
+        "..OYYYKYYYYYYKYYYO..",
+        "..OYYYYKKKKKKYYYYO..",
+        "...OYYYYYYYYYYYYO...",
+        "....OOYYYYYYYYOO....",
+        "......OOOYYOOO......",
+        ".........OO.........",
+    ],
+};
+
+/// 红心
+const HEART: Emoji = Emoji {
+    rows: [
+        "....................",
+        "...RRRRRR..RRRRRR...",
+        "..RRRRRRRRRRRRRRRR..",
+        ".RRRRRRRRRRRRRRRRRR.",
+        ".RRRRRRRRRRRRRRRRRR.",
+        "RRRRRWWRRRRRRRRRRRRR",
+        "RRRRWRRRRRRRRRRRRRRR",
+        "RRRRRRRRRRRRRRRRRRRR",
+        ".RRRRRRRRRRRRRRRRRR.",
+        ".RRRRRRRRRRRRRRRRRR.",
+        ".RRRRRRRRRRRRRRRRRR.",
+        "..RRRRRRRRRRRRRRRR..",
+        "..RRRRRRRRRRRRRRRR..",
+        "...RRRRRRRRRRRRRR...",
+        "....RRRRRRRRRRRR....",
+        ".....RRRRRRRRRR.....",
+        "......RRRRRRRR......",
+        ".......RRRRRR.......",
+        ".........RR.........",
+        "....................",
+    ],
+};
+
+/// 星星
+const STAR: Emoji = Emoji {
+    rows: [
+        "....................",
+        "....................",
+        "....................",
+        ".........YY.........",
+        ".........YY.........",
+        "........YYYY........",
+        "........YYYY........",
+        "........YYYY........",
+        "..YYYYYYYYYYYYYYYY..",
+        "...YYYYYYYYYYYYYY...",
+        "....YYYYYYYYYYYY....",
+        "......YYYYYYYY......",
+        "......YYYYYYYY......",
+        "......YYYYYYYY......",
+        "......YYYYYYYY......",
+        ".....YYYY..YYYY.....",
+        ".....YY......YY.....",
+        ".....Y........Y.....",
+        "....................",
+        "....................",
+    ],
+};
+
+/// 对勾
+const CHECK: Emoji = Emoji {
+    rows: [
+        "....................",
+        ".GGGGGGGGGGGGGGGGGG.",
+        ".GGGGGGGGGGGGGGGGGG.",
+        ".GGGGGGGGGGGGGGGGGG.",
+        ".GGGGGGGGGGGGGGGGGG.",
+        ".GGGGGGGGGGGGGGGGGG.",
+        ".GGGGGGGGGGGGGGWWGG.",
+        ".GGGGGGGGGGGGGGWWGG.",
+        ".GGGGGGGGGGGGGWWWGG.",
+        ".GGGGGGGGGGGGWWWGGG.",
+        ".GGGWWGGGGGGWWWGGGG.",
+        ".GGGWWWGGGGWWWGGGGG.",
+        ".GGGGWWWGGWWWGGGGGG.",
+        ".GGGGGWWWWWWGGGGGGG.",
+        ".GGGGGGWWWWGGGGGGGG.",
+        ".GGGGGGGWWGGGGGGGGG.",
+        ".GGGGGGGGGGGGGGGGGG.",
+        ".GGGGGGGGGGGGGGGGGG.",
+        ".GGGGGGGGGGGGGGGGGG.",
+        "....................",
+    ],
+};
+
+/// 叉号
+const CROSS: Emoji = Emoji {
+    rows: [
+        "....................",
+        "....................",
+        "..RR...........RR...",
+        "..RRR.........RRR...",
+        "...RRR.......RRR....",
+        "....RRR.....RRR.....",
+        ".....RRR...RRR......",
+        "......RRR.RRR.......",
+        ".......RRRRR........",
+        "........RRR.........",
+        ".......RRRRR........",
+        "......RRR.RRR.......",
+        ".....RRR...RRR......",
+        "....RRR.....RRR.....",
+        "...RRR.......RRR....",
+        "..RRR.........RRR...",
+        "..RR...........RR...",
+        "....................",
+        "....................",
+        "....................",
+    ],
+};
+
+/// 点赞
+const THUMBS_UP: Emoji = Emoji {
+    rows: [
+        "....................",
+        ".........OO.........",
+        "........OOOO........",
+        "........OOOO........",
+        ".......OOOOO........",
+        ".......OOOOO........",
+        "......OOOOOO........",
+        ".....OOOOOOOOOOOOO..",
+        ".BBB.OOOOOOOOOOOOOO.",
+        ".BBB.OOOOOOOKKKKKKO.",
+        ".BBB.OOOOOOOOOOOOOO.",
+        ".BBB.OOOOOOOOOOOOO..",
+        ".BBB.OOOOOOOKKKKKK..",
+        ".BBB.OOOOOOOOOOOOO..",
+        ".BBB.OOOOOOOOOOOOO..",
+        ".BBB.OOOOOOOKKKKK...",
+        ".BBB.OOOOOOOOOOOO...",
+        ".BBB..OOOOOOOOOOO...",
+        "....................",
+        "....................",
+    ],
+};
+
+/// 太阳
+const SUN: Emoji = Emoji {
+    rows: [
+        ".........OO.........",
+        ".........OO.........",
+        "...O.....OO.....O...",
+        "...OO..........OO...",
+        "....OO........OO....",
+        ".......YYYYYY.......",
+        "......YYYYYYYY......",
+        ".....YYYYYYYYYY.....",
+        ".....YYYYYYYYYY.....",
+        "OOO..YYYYYYYYYY..OOO",
+        "OOO..YYYYYYYYYY..OOO",
+        ".....YYYYYYYYYY.....",
+        ".....YYYYYYYYYY.....",
+        "......YYYYYYYY......",
+        ".......YYYYYY.......",
+        "....OO........OO....",
+        "...OO..........OO...",
+        "...O.....OO.....O...",
+        ".........OO.........",
+        ".........OO.........",
+    ],
+};
+
+/// 月亮
+const MOON: Emoji = Emoji {
+    rows: [
+        "....................",
+        "....................",
+        "......YYY...........",
+        ".....YYY............",
+        "....YYY.............",
+        "...YYYY.............",
+        "..YYYYY.............",
+        "..YYYYY.............",
+        "..YYYYY.............",
+        "..YYYYY.............",
+        "..YYYYYY............",
+        "..YYYYYYY...........",
+        "..YYYYYYYY..........",
+        "..YYYYYYYYY......Y..",
+        "...YYYYYYYYYYYYYY...",
+        "....YYYYYYYYYYYY....",
+        ".....YYYYYYYYYY.....",
+        "......YYYYYYYY......",
+        "....................",
+        "....................",
+    ],
+};
+
+/// 没有收录的表情，用文字颜色画一个方框
+const FALLBACK: Emoji = Emoji {
+    rows: [
+        "....................",
+        "....................",
+        "...FFFFFFFFFFFFFF...",
+        "...F............F...",
+        "...F............F...",
+        "...F............F...",
+        "...F............F...",
+        "...F............F...",
+        "...F............F...",
+        "...F............F...",
+        "...F............F...",
+        "...F............F...",
+        "...F............F...",
+        "...F............F...",
+        "...F............F...",
+        "...F............F...",
+        "...F............F...",
+        "...FFFFFFFFFFFFFF...",
+        "....................",
+        "....................",
+    ],
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [&Emoji; 16] = [
+        &GRINNING, &JOY, &SMILE, &WINK, &CRY, &NEUTRAL, &SURPRISED, &COOL, &HEART, &STAR, &CHECK,
+        &CROSS, &THUMBS_UP, &SUN, &MOON, &FALLBACK,
+    ];
+
+    #[test]
+    fn test_bitmaps_are_square_and_use_palette() {
+        for emoji in ALL {
+            for row in emoji.rows {
+                assert_eq!(row.len(), EMOJI_SIZE as usize, "{:?}", row);
+                assert!(row
+                    .bytes()
+                    .all(|pixel| pixel == b'.' || palette(pixel, WHITE).is_some()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_shape_splits_text_and_emoji() {
+        assert_eq!(shape("hello"), vec![TextRun::Text("hello")]);
+        assert_eq!(
+            shape("ok\u{1F44D}!"),
+            vec![
+                TextRun::Text("ok"),
+                TextRun::Emoji(&THUMBS_UP),
+                TextRun::Text("!")
+            ]
+        );
+        // 变体选择符被丢弃
+        assert_eq!(
+            shape("\u{2764}\u{FE0F}\u{2764}"),
+            vec![TextRun::Emoji(&HEART), TextRun::Emoji(&HEART)]
+        );
+        // 没有收录的emoji显示为方框，肤色修饰符被丢弃
+        assert_eq!(shape("\u{1F44B}\u{1F3FD}"), vec![TextRun::Emoji(&FALLBACK)]);
+    }
+
+    #[test]
+    fn test_cells() {
+        assert!(is_plain("abc 你好"));
+        assert!(!is_plain("\u{1F600}"));
+        assert_eq!(text_cells("abc"), 3);
+        assert_eq!(text_cells("a\u{1F600}\u{FE0F}b"), 4);
+        assert_eq!(char_cells('\u{200D}'), 0);
+    }
+}
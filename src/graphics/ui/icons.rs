@@ -0,0 +1,49 @@
+// 状态栏使用的小尺寸单色位图图标
+//
+// 每个图标按行存储，每行一个u16，最高位对应最左侧像素。
+
+/// WiFi信号图标宽度（像素）
+pub const WIFI_ICON_WIDTH: i32 = 16;
+/// WiFi信号图标高度（像素）
+pub const WIFI_ICON_HEIGHT: i32 = 12;
+
+/// WiFi信号强度图标，下标即信号格数（0-4）
+///
+/// 未点亮的信号格只保留底部一行，便于用户看出总格数。
+pub const WIFI_ICONS: [[u16; WIFI_ICON_HEIGHT as usize]; 5] = [
+    // 0格
+    [
+        0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+        0xEEEE,
+    ],
+    // 1格
+    [
+        0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0xE000, 0xE000,
+        0xEEEE,
+    ],
+    // 2格
+    [
+        0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0E00, 0x0E00, 0x0E00, 0xEE00, 0xEE00,
+        0xEEEE,
+    ],
+    // 3格
+    [
+        0x0000, 0x0000, 0x0000, 0x00E0, 0x00E0, 0x00E0, 0x0EE0, 0x0EE0, 0x0EE0, 0xEEE0, 0xEEE0,
+        0xEEEE,
+    ],
+    // 4格
+    [
+        0x000E, 0x000E, 0x000E, 0x00EE, 0x00EE, 0x00EE, 0x0EEE, 0x0EEE, 0x0EEE, 0xEEEE, 0xEEEE,
+        0xEEEE,
+    ],
+];
+
+/// 免打扰图标宽度（像素）
+pub const MOON_ICON_WIDTH: i32 = 12;
+/// 免打扰图标高度（像素）
+pub const MOON_ICON_HEIGHT: i32 = 12;
+
+/// 免打扰图标（月牙）
+pub const MOON_ICON: [u16; MOON_ICON_HEIGHT as usize] = [
+    0x0E00, 0x3800, 0x7000, 0x6000, 0xE000, 0xE000, 0xE000, 0xF000, 0x7820, 0x7FE0, 0x3FC0, 0x0F00,
+];
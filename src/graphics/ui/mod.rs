@@ -1,2 +1,8 @@
+pub mod chat_bubble;
+pub mod emoji;
+pub mod icons;
+pub mod progress_ring;
+pub mod qrcode;
 pub mod statusbar;
 pub mod traits;
+pub mod widgets;
@@ -0,0 +1,199 @@
+use std::cell::Cell;
+
+use anyhow::Result;
+use embedded_graphics::pixelcolor::Rgb565;
+
+use super::traits::{CachedUIComponent, UIComponent};
+use crate::graphics::{
+    layout::{scaled, SCREEN_CIRCLE},
+    primitives::GraphicsPrimitives,
+    theme,
+};
+
+/// 环的粗细
+const RING_THICKNESS: i32 = scaled(8);
+/// 环外侧与屏幕边缘的距离
+const RING_MARGIN: i32 = scaled(4);
+
+/// 需要重绘的弧段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Redraw {
+    /// 完整重绘底环与已完成部分（清屏后）
+    Full,
+    /// 进度增加：用进度颜色画出from到to之间的弧
+    Advance { from: u8, to: u8 },
+    /// 进度减少：用底环颜色覆盖from到to之间的弧
+    Retreat { from: u8, to: u8 },
+}
+
+/// 环形进度条
+///
+/// 沿圆形屏幕边缘从12点钟方向顺时针显示百分比，用于语音上传、较长的API请求等。
+/// 记录屏幕上已绘制的进度，之后只重绘变化的那段弧，不影响环内的其他内容。
+/// 清屏后需调用`clear_cache`强制完整重绘。
+#[derive(Debug)]
+pub struct ProgressRing {
+    center_x: i32,
+    center_y: i32,
+    /// 环半径（到线条中心）
+    radius: i32,
+    thickness: u32,
+    /// 当前进度（0-100）
+    percent: u8,
+    /// 屏幕上已绘制的进度，None表示需要完整重绘
+    drawn: Cell<Option<u8>>,
+}
+
+impl Default for ProgressRing {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressRing {
+    /// 创建贴着圆形屏幕边缘的进度环
+    pub fn new() -> Self {
+        Self {
+            center_x: SCREEN_CIRCLE.center_x,
+            center_y: SCREEN_CIRCLE.center_y,
+            radius: SCREEN_CIRCLE.radius - RING_MARGIN - RING_THICKNESS / 2,
+            thickness: RING_THICKNESS as u32,
+            percent: 0,
+            drawn: Cell::new(None),
+        }
+    }
+
+    /// 设置进度
+    ///
+    /// # 参数
+    /// * `percent` - 完成百分比，超过100按100处理
+    pub fn set_percent(&mut self, percent: u8) {
+        self.percent = percent.min(100);
+    }
+
+    pub fn percent(&self) -> u8 {
+        self.percent
+    }
+
+    /// 与已绘制内容相比需要重绘的弧段
+    fn redraw(&self) -> Option<Redraw> {
+        match self.drawn.get() {
+            None => Some(Redraw::Full),
+            Some(drawn) if self.percent > drawn => Some(Redraw::Advance {
+                from: drawn,
+                to: self.percent,
+            }),
+            Some(drawn) if self.percent < drawn => Some(Redraw::Retreat {
+                from: self.percent,
+                to: drawn,
+            }),
+            Some(_) => None,
+        }
+    }
+
+    /// 绘制from到to之间的弧（百分比）
+    fn draw_span(
+        &self,
+        graphics: &mut GraphicsPrimitives,
+        from: u8,
+        to: u8,
+        color: Rgb565,
+    ) -> Result<()> {
+        graphics.draw_arc(
+            self.center_x,
+            self.center_y,
+            self.radius,
+            from as f32 * 3.6,
+            (to - from) as f32 * 3.6,
+            color,
+            self.thickness,
+        )
+    }
+}
+
+impl UIComponent for ProgressRing {
+    fn render(&self, graphics: &mut GraphicsPrimitives) -> Result<()> {
+        let theme = theme::current();
+        match self.redraw() {
+            None => return Ok(()),
+            Some(Redraw::Full) => {
+                graphics.draw_circle_border(
+                    self.center_x,
+                    self.center_y,
+                    self.radius,
+                    theme.surface,
+                    self.thickness,
+                )?;
+                self.draw_span(graphics, 0, self.percent, theme.accent)?;
+            }
+            Some(Redraw::Advance { from, to }) => {
+                self.draw_span(graphics, from, to, theme.accent)?;
+            }
+            Some(Redraw::Retreat { from, to }) => {
+                self.draw_span(graphics, from, to, theme.surface)?;
+            }
+        }
+        self.drawn.set(Some(self.percent));
+        Ok(())
+    }
+
+    fn get_bounds(&self) -> (i32, i32, i32, i32) {
+        let outer = self.radius + self.thickness as i32 / 2;
+        (
+            self.center_x - outer,
+            self.center_y - outer,
+            outer * 2,
+            outer * 2,
+        )
+    }
+
+    fn needs_redraw(&self) -> bool {
+        self.redraw().is_some()
+    }
+}
+
+impl CachedUIComponent for ProgressRing {
+    fn clear_cache(&mut self) {
+        self.drawn.set(None);
+    }
+
+    fn mark_dirty(&mut self) {
+        self.drawn.set(None);
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.needs_redraw()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redraw_only_changed_span() {
+        let mut ring = ProgressRing::new();
+        ring.set_percent(30);
+        assert_eq!(ring.redraw(), Some(Redraw::Full));
+
+        ring.drawn.set(Some(30));
+        assert_eq!(ring.redraw(), None);
+        ring.set_percent(45);
+        assert_eq!(ring.redraw(), Some(Redraw::Advance { from: 30, to: 45 }));
+        ring.set_percent(10);
+        assert_eq!(ring.redraw(), Some(Redraw::Retreat { from: 10, to: 30 }));
+
+        ring.clear_cache();
+        assert_eq!(ring.redraw(), Some(Redraw::Full));
+    }
+
+    #[test]
+    fn test_fits_round_screen() {
+        let mut ring = ProgressRing::new();
+        ring.set_percent(150);
+        assert_eq!(ring.percent(), 100);
+        let (x, y, width, height) = ring.get_bounds();
+        assert!(x >= 0 && y >= 0);
+        assert!(width <= SCREEN_CIRCLE.radius * 2 && width == height);
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use embedded_graphics::pixelcolor::Rgb565;
+use qrcodegen::{QrCode as Encoder, QrCodeEcc};
+
+use super::traits::UIComponent;
+use crate::graphics::{
+    colors::{BLACK, WHITE},
+    layout::ScreenRect,
+    primitives::GraphicsPrimitives,
+};
+
+/// 二维码四周的空白（模块数），扫码器需要至少4个模块的静区
+const QUIET_ZONE: i32 = 4;
+
+/// 二维码组件
+///
+/// 创建时完成编码，之后每帧只按模块绘制方块。模块大小按给定的边长自动取整，
+/// 二维码在该区域内居中。
+#[derive(Debug, Clone, PartialEq)]
+pub struct QrCode {
+    /// 每边的模块数
+    size: i32,
+    /// 按行存放的模块，true为深色
+    modules: Vec<bool>,
+    /// 区域中心
+    center_x: i32,
+    center_y: i32,
+    /// 区域边长（像素）
+    max_side: i32,
+    pub dark_color: Rgb565,
+    pub light_color: Rgb565,
+}
+
+impl QrCode {
+    /// 编码文本
+    ///
+    /// # 参数
+    /// * `text` - 二维码内容
+    /// * `center_x`, `center_y` - 二维码中心位置
+    /// * `max_side` - 二维码（含静区）最大边长
+    ///
+    /// # 返回值
+    /// 内容过长无法编码时返回错误
+    pub fn new(text: &str, center_x: i32, center_y: i32, max_side: i32) -> Result<Self> {
+        let encoded = Encoder::encode_text(text, QrCodeEcc::Medium)
+            .map_err(|_| anyhow::anyhow!("二维码内容过长: {} 字节", text.len()))?;
+
+        let size = encoded.size();
+        let modules = (0..size)
+            .flat_map(|y| (0..size).map(move |x| (x, y)))
+            .map(|(x, y)| encoded.get_module(x, y))
+            .collect();
+
+        Ok(Self {
+            size,
+            modules,
+            center_x,
+            center_y,
+            max_side,
+            dark_color: BLACK,
+            light_color: WHITE,
+        })
+    }
+
+    /// 每个模块的像素大小
+    fn module_px(&self) -> i32 {
+        (self.max_side / (self.size + QUIET_ZONE * 2)).max(1)
+    }
+}
+
+/// 生成WiFi配网二维码内容（手机相机扫码即可连接）
+///
+/// # 参数
+/// * `ssid` - 热点名称
+/// * `password` - 密码，为空时生成开放网络
+pub fn wifi_payload(ssid: &str, password: &str) -> String {
+    fn escape(value: &str) -> String {
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            if matches!(c, '\\' | ';' | ',' | ':' | '"') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    if password.is_empty() {
+        format!("WIFI:T:nopass;S:{};;", escape(ssid))
+    } else {
+        format!("WIFI:T:WPA;S:{};P:{};;", escape(ssid), escape(password))
+    }
+}
+
+impl UIComponent for QrCode {
+    fn render(&self, graphics: &mut GraphicsPrimitives) -> Result<()> {
+        let (x, y, side, _) = self.get_bounds();
+        let module_px = self.module_px();
+
+        // 先画浅色底（含静区），再逐个画深色模块
+        graphics.fill_rect(&ScreenRect::new(x, y, side, side), self.light_color)?;
+
+        let origin_x = x + QUIET_ZONE * module_px;
+        let origin_y = y + QUIET_ZONE * module_px;
+        for row in 0..self.size {
+            // 同一行连续的深色模块合并成一个矩形
+            let mut col = 0;
+            while col < self.size {
+                if !self.modules[(row * self.size + col) as usize] {
+                    col += 1;
+                    continue;
+                }
+                let start = col;
+                while col < self.size && self.modules[(row * self.size + col) as usize] {
+                    col += 1;
+                }
+                let rect = ScreenRect::new(
+                    origin_x + start * module_px,
+                    origin_y + row * module_px,
+                    (col - start) * module_px,
+                    module_px,
+                );
+                graphics.fill_rect(&rect, self.dark_color)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_bounds(&self) -> (i32, i32, i32, i32) {
+        let side = (self.size + QUIET_ZONE * 2) * self.module_px();
+        (
+            self.center_x - side / 2,
+            self.center_y - side / 2,
+            side,
+            side,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wifi_payload_escapes_special_chars() {
+        assert_eq!(
+            wifi_payload("my;ap", r"p:w\d"),
+            r"WIFI:T:WPA;S:my\;ap;P:p\:w\\d;;"
+        );
+        assert_eq!(wifi_payload("open", ""), "WIFI:T:nopass;S:open;;");
+    }
+}
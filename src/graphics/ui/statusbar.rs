@@ -1,6 +1,12 @@
+use super::icons::{
+    MOON_ICON, MOON_ICON_HEIGHT, MOON_ICON_WIDTH, WIFI_ICONS, WIFI_ICON_HEIGHT, WIFI_ICON_WIDTH,
+};
 use super::traits::UIComponent;
-use crate::graphics::layout::{SCREEN_WIDTH, STATUS_BAR, TEXT_CHAR_WIDTH};
+use crate::graphics::layout::{
+    SCREEN_CENTER_X, SCREEN_CIRCLE, SCREEN_WIDTH, STATUS_BAR, TEXT_CHAR_WIDTH,
+};
 use crate::graphics::primitives::GraphicsPrimitives;
+use crate::graphics::theme::{self, Theme};
 use anyhow::Result;
 use embedded_graphics::pixelcolor::Rgb565;
 
@@ -33,6 +39,14 @@ pub struct StatusBar {
     pub text_items: Vec<StatusBarText>,
     /// 状态栏高度
     pub height: i32,
+    /// WiFi信号格数（0-4），None表示未连接，不显示图标
+    pub wifi_level: Option<u8>,
+    /// 是否显示免打扰图标
+    pub do_not_disturb: bool,
+    /// 网络变差时WiFi图标使用警告色
+    pub network_degraded: bool,
+    /// 图标颜色
+    pub icon_color: Rgb565,
 }
 
 impl StatusBar {
@@ -50,6 +64,10 @@ impl StatusBar {
             background_color,
             text_items: Vec::new(),
             height: STATUS_BAR.height,
+            wifi_level: None,
+            do_not_disturb: false,
+            network_degraded: false,
+            icon_color: crate::graphics::colors::BLACK,
         }
     }
 
@@ -85,6 +103,65 @@ impl StatusBar {
         self.background_color = color;
     }
 
+    /// 按主题更新背景色、图标颜色和所有文本项的颜色
+    ///
+    /// # 参数
+    ///
+    /// * `theme` - 新主题
+    pub fn apply_theme(&mut self, theme: &Theme) {
+        self.background_color = theme.background;
+        self.icon_color = theme.foreground;
+        for item in &mut self.text_items {
+            item.color = theme.foreground;
+            if item.background_color.is_some() {
+                item.background_color = Some(theme.background);
+            }
+        }
+    }
+
+    /// 设置WiFi信号格数
+    ///
+    /// # 参数
+    ///
+    /// * `level` - 信号格数（0-4），超出范围按4处理；None表示隐藏WiFi图标
+    pub fn set_wifi_level(&mut self, level: Option<u8>) {
+        self.wifi_level = level.map(|l| l.min(4));
+    }
+
+    /// 设置是否显示免打扰图标
+    pub fn set_do_not_disturb(&mut self, enabled: bool) {
+        self.do_not_disturb = enabled;
+    }
+
+    /// 设置网络是否变差
+    pub fn set_network_degraded(&mut self, degraded: bool) {
+        self.network_degraded = degraded;
+    }
+
+    /// 计算免打扰图标的绘制位置，与WiFi图标对称放在中心偏左
+    ///
+    /// # 返回值
+    ///
+    /// 返回图标左上角的(x, y)坐标
+    pub fn calculate_moon_icon_position(&self) -> (i32, i32) {
+        let x = SCREEN_CENTER_X - 36 - MOON_ICON_WIDTH;
+        let y = STATUS_BAR.y + (self.height - MOON_ICON_HEIGHT) / 2;
+        (x, y)
+    }
+
+    /// 计算WiFi图标的绘制位置
+    ///
+    /// 圆形屏幕的顶部两角不可见，因此图标放在中心偏右的位置。
+    ///
+    /// # 返回值
+    ///
+    /// 返回图标左上角的(x, y)坐标
+    pub fn calculate_wifi_icon_position(&self) -> (i32, i32) {
+        let x = SCREEN_CENTER_X + 36;
+        let y = STATUS_BAR.y + (self.height - WIFI_ICON_HEIGHT) / 2;
+        (x, y)
+    }
+
     /// 计算文本的绘制位置
     ///
     /// # 参数
@@ -102,11 +179,14 @@ impl StatusBar {
         // embedded-graphics的文本绘制是基于基线的，FONT_10X20的字体高度是20，基线大约在距离顶部16的位置
         let y = STATUS_BAR.y + (self.height + 16) / 2; // 基线位置，让文字在状态栏中垂直居中
 
-        // 水平位置计算
+        // 水平位置计算：圆形屏幕上左右两侧只能使用状态栏底边处的可见弦长，边距10px
+        let (start, end) = SCREEN_CIRCLE
+            .chord_span(STATUS_BAR.y + self.height, 10)
+            .unwrap_or((STATUS_BAR.x, STATUS_BAR.x + SCREEN_WIDTH));
         let x = match position {
-            StatusBarPosition::Left => STATUS_BAR.x + 10, // 左边距10px
+            StatusBarPosition::Left => start,
             StatusBarPosition::Center => STATUS_BAR.x + (SCREEN_WIDTH - text_width) / 2,
-            StatusBarPosition::Right => STATUS_BAR.x + SCREEN_WIDTH - text_width - 10, // 右边距10px
+            StatusBarPosition::Right => end - text_width,
         };
 
         (x, y)
@@ -129,9 +209,11 @@ impl StatusBar {
 }
 
 impl Default for StatusBar {
+    /// 按当前主题创建状态栏
     fn default() -> Self {
-        use crate::graphics::colors::WHITE;
-        Self::new(WHITE)
+        let mut status_bar = Self::new(theme::current().background);
+        status_bar.apply_theme(&theme::current());
+        status_bar
     }
 }
 
@@ -152,6 +234,37 @@ impl UIComponent for StatusBar {
             graphics.draw_text(&item.text, x, y, item.color, item.background_color)?;
         }
 
+        // 绘制WiFi信号图标
+        if let Some(level) = self.wifi_level {
+            let (x, y) = self.calculate_wifi_icon_position();
+            let color = if self.network_degraded {
+                theme::current().warning
+            } else {
+                self.icon_color
+            };
+            graphics.draw_glyph(
+                &WIFI_ICONS[level as usize],
+                WIFI_ICON_WIDTH,
+                x,
+                y,
+                color,
+                Some(self.background_color),
+            )?;
+        }
+
+        // 绘制免打扰图标
+        if self.do_not_disturb {
+            let (x, y) = self.calculate_moon_icon_position();
+            graphics.draw_glyph(
+                &MOON_ICON,
+                MOON_ICON_WIDTH,
+                x,
+                y,
+                self.icon_color,
+                Some(self.background_color),
+            )?;
+        }
+
         Ok(())
     }
 
@@ -159,3 +272,73 @@ impl UIComponent for StatusBar {
         (STATUS_BAR.x, STATUS_BAR.y, STATUS_BAR.width, self.height)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::{
+        geometry::Point,
+        mono_font::{ascii::FONT_10X20, MonoTextStyleBuilder},
+        pixelcolor::RgbColor,
+        text::Text,
+        Drawable,
+    };
+
+    use super::*;
+    use crate::testing::{DrawCall, MockDrawTarget};
+
+    #[test]
+    fn test_text_fits_round_screen() {
+        let status_bar = StatusBar::new(Rgb565::BLACK);
+        let style = MonoTextStyleBuilder::new()
+            .font(&FONT_10X20)
+            .text_color(Rgb565::WHITE)
+            .background_color(Rgb565::BLACK)
+            .build();
+        let mut cells = Vec::new();
+        for (text, position) in [
+            ("12:30", StatusBarPosition::Left),
+            ("AI", StatusBarPosition::Center),
+            ("85%", StatusBarPosition::Right),
+        ] {
+            let mut target = MockDrawTarget::screen();
+            let (x, y) = status_bar.calculate_text_position(text, position);
+            Text::new(text, Point::new(x, y), style)
+                .draw(&mut target)
+                .unwrap();
+            assert_eq!(target.clipped(), 0);
+            // 等宽字体逐个字符绘制
+            assert_eq!(target.calls().len(), text.len());
+            assert!(target
+                .calls()
+                .iter()
+                .all(|call| matches!(call, DrawCall::Pixels { .. })));
+
+            // 文字在状态栏内，底边两角在圆形屏幕的可见区域内
+            let cell = target.drawn_bounds().unwrap();
+            let bottom_right = cell.bottom_right().unwrap();
+            assert!(cell.top_left.y >= STATUS_BAR.y);
+            assert!(bottom_right.y < STATUS_BAR.y + status_bar.height);
+            assert!(SCREEN_CIRCLE.contains(cell.top_left.x, bottom_right.y));
+            assert!(SCREEN_CIRCLE.contains(bottom_right.x, bottom_right.y));
+            cells.push(cell);
+        }
+        // 左、中、右依次排列且不重叠
+        for pair in cells.windows(2) {
+            assert!(pair[0].bottom_right().unwrap().x < pair[1].top_left.x);
+        }
+    }
+
+    #[test]
+    fn test_icons_symmetric() {
+        let status_bar = StatusBar::new(Rgb565::BLACK);
+        let (moon_x, moon_y) = status_bar.calculate_moon_icon_position();
+        let (wifi_x, wifi_y) = status_bar.calculate_wifi_icon_position();
+        assert_eq!(
+            SCREEN_CENTER_X - (moon_x + MOON_ICON_WIDTH),
+            wifi_x - SCREEN_CENTER_X
+        );
+        assert!(moon_y >= STATUS_BAR.y && wifi_y >= STATUS_BAR.y);
+        assert!(moon_y + MOON_ICON_HEIGHT <= STATUS_BAR.y + status_bar.height);
+        assert!(wifi_y + WIFI_ICON_HEIGHT <= STATUS_BAR.y + status_bar.height);
+    }
+}
@@ -0,0 +1,69 @@
+use anyhow::Result;
+
+use super::{draw_label, row_bounds, Widget, VALUE_X};
+use crate::graphics::{primitives::GraphicsPrimitives, theme, ui::traits::UIComponent};
+
+/// 按钮：单击执行一次操作，取值区域显示操作状态
+pub struct Button<A> {
+    label: String,
+    /// 取值区域显示的状态文字
+    status: String,
+    /// 单击时生成操作
+    on_press: fn() -> A,
+    y: i32,
+    focused: bool,
+}
+
+impl<A> Button<A> {
+    /// 创建按钮
+    ///
+    /// # 参数
+    /// * `label` - 标签
+    /// * `status` - 状态文字，可以为空
+    /// * `on_press` - 单击时生成操作
+    pub fn new(label: &str, status: &str, on_press: fn() -> A) -> Self {
+        Self {
+            label: label.to_string(),
+            status: status.to_string(),
+            on_press,
+            y: 0,
+            focused: false,
+        }
+    }
+}
+
+impl<A> UIComponent for Button<A> {
+    fn render(&self, graphics: &mut GraphicsPrimitives) -> Result<()> {
+        let theme = theme::current();
+        draw_label(graphics, &self.label, self.y, self.focused)?;
+        graphics.draw_text(
+            &self.status,
+            VALUE_X,
+            self.y,
+            theme.muted,
+            Some(theme.background),
+        )
+    }
+
+    fn get_bounds(&self) -> (i32, i32, i32, i32) {
+        row_bounds(self.y)
+    }
+}
+
+impl<A> Widget<A> for Button<A> {
+    fn set_row(&mut self, y: i32) {
+        self.y = y;
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn activate(&mut self) -> Option<A> {
+        Some((self.on_press)())
+    }
+
+    fn rotate(&mut self, _delta: i32) -> Option<A> {
+        None
+    }
+}
@@ -0,0 +1,129 @@
+use anyhow::Result;
+
+use super::{draw_label, row_bounds, Widget, VALUE_X};
+use crate::graphics::{
+    layout::TEXT_CHAR_WIDTH, primitives::GraphicsPrimitives, theme, ui::traits::UIComponent,
+};
+
+/// 列表选择器：在若干选项中循环选择
+pub struct ListPicker<A> {
+    label: String,
+    options: Vec<String>,
+    index: usize,
+    /// 选中项变化时生成操作，参数为选项序号
+    on_change: fn(usize) -> A,
+    y: i32,
+    focused: bool,
+    editing: bool,
+}
+
+impl<A> ListPicker<A> {
+    /// 创建列表选择器
+    ///
+    /// # 参数
+    /// * `label` - 标签
+    /// * `options` - 选项的显示文字
+    /// * `index` - 当前选中项的序号
+    /// * `on_change` - 选中项变化时生成操作
+    pub fn new(label: &str, options: Vec<String>, index: usize, on_change: fn(usize) -> A) -> Self {
+        Self {
+            label: label.to_string(),
+            index: index.min(options.len().saturating_sub(1)),
+            options,
+            on_change,
+            y: 0,
+            focused: false,
+            editing: false,
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<A> UIComponent for ListPicker<A> {
+    fn render(&self, graphics: &mut GraphicsPrimitives) -> Result<()> {
+        let theme = theme::current();
+        draw_label(graphics, &self.label, self.y, self.focused)?;
+
+        let option = self.options.get(self.index).map_or("", String::as_str);
+        if self.editing {
+            // 编辑时两侧显示箭头，提示旋转切换
+            let text = format!("<{}>", option);
+            graphics.draw_text(
+                &text,
+                VALUE_X - TEXT_CHAR_WIDTH,
+                self.y,
+                theme.accent,
+                Some(theme.background),
+            )
+        } else {
+            graphics.draw_text(
+                option,
+                VALUE_X,
+                self.y,
+                theme.foreground,
+                Some(theme.background),
+            )
+        }
+    }
+
+    fn get_bounds(&self) -> (i32, i32, i32, i32) {
+        row_bounds(self.y)
+    }
+}
+
+impl<A> Widget<A> for ListPicker<A> {
+    fn set_row(&mut self, y: i32) {
+        self.y = y;
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        if !focused {
+            self.editing = false;
+        }
+    }
+
+    fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    fn set_editing(&mut self, editing: bool) {
+        self.editing = editing;
+    }
+
+    fn activate(&mut self) -> Option<A> {
+        self.editing = !self.editing;
+        None
+    }
+
+    fn rotate(&mut self, delta: i32) -> Option<A> {
+        if !self.editing || self.options.len() < 2 {
+            return None;
+        }
+        let count = self.options.len() as i32;
+        self.index = (self.index as i32 + delta).rem_euclid(count) as usize;
+        Some((self.on_change)(self.index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotate_wraps() {
+        let options = vec!["低".to_string(), "中".to_string(), "高".to_string()];
+        let mut picker = ListPicker::new("动作", options, 2, |index| index);
+        assert_eq!(picker.rotate(1), None);
+
+        picker.activate();
+        assert_eq!(picker.rotate(1), Some(0));
+        assert_eq!(picker.rotate(-1), Some(2));
+        picker.activate();
+        assert!(!picker.is_editing());
+        assert_eq!(picker.index(), 2);
+    }
+}
@@ -0,0 +1,107 @@
+//! 可聚焦的设置控件
+//!
+//! 控件按行排列：左侧为标签，右侧为取值区域。旋转手势在控件之间移动焦点，
+//! 单击操作获得焦点的控件；滑块和列表选择器单击后进入编辑，编辑时旋转调节取值，
+//! 再次单击或返回结束编辑；按钮单击即执行。取值变化时通过回调生成调用方定义的操作`A`。
+
+pub mod button;
+pub mod list_picker;
+pub mod slider;
+pub mod toggle;
+
+pub use button::Button;
+pub use list_picker::ListPicker;
+pub use slider::Slider;
+pub use toggle::Toggle;
+
+use anyhow::Result;
+
+use super::traits::UIComponent;
+use crate::graphics::{
+    layout::{scaled, ScreenRect},
+    primitives::GraphicsPrimitives,
+    theme,
+};
+
+/// 标签左边缘的X坐标
+pub const LABEL_X: i32 = scaled(60);
+/// 取值区域左边缘的X坐标
+pub const VALUE_X: i32 = scaled(170);
+/// 取值区域宽度
+pub const VALUE_WIDTH: i32 = scaled(130);
+/// 行高
+pub const ROW_HEIGHT: i32 = scaled(44);
+/// 文字基线以上的高度（FONT_10X20）
+const TEXT_ASCENT: i32 = 16;
+
+/// 可聚焦的控件
+pub trait Widget<A>: UIComponent {
+    /// 设置所在行文字基线的Y坐标
+    fn set_row(&mut self, y: i32);
+
+    /// 获得或失去焦点，失去焦点时结束编辑
+    fn set_focused(&mut self, focused: bool);
+
+    /// 是否正在编辑（编辑时旋转调节取值而不是移动焦点）
+    fn is_editing(&self) -> bool {
+        false
+    }
+
+    /// 进入或结束编辑，不支持编辑的控件忽略
+    fn set_editing(&mut self, _editing: bool) {}
+
+    /// 单击获得焦点的控件
+    ///
+    /// # 返回值
+    /// 取值变化时返回回调生成的操作
+    fn activate(&mut self) -> Option<A>;
+
+    /// 编辑时旋转调节取值
+    ///
+    /// # 参数
+    /// * `delta` - 旋转方向与步数，正数为顺时针
+    ///
+    /// # 返回值
+    /// 取值变化时返回回调生成的操作
+    fn rotate(&mut self, delta: i32) -> Option<A>;
+}
+
+/// 焦点指示点与标签的距离
+const FOCUS_DOT_OFFSET: i32 = 12;
+
+/// 控件所在行的区域（含焦点指示点）
+fn row_rect(y: i32) -> ScreenRect {
+    let x = LABEL_X - FOCUS_DOT_OFFSET * 2;
+    ScreenRect::new(
+        x,
+        y - TEXT_ASCENT - 4,
+        VALUE_X + VALUE_WIDTH - x,
+        TEXT_ASCENT + 10,
+    )
+}
+
+/// 控件边界框(x, y, width, height)
+fn row_bounds(y: i32) -> (i32, i32, i32, i32) {
+    let rect = row_rect(y);
+    (rect.x, rect.y, rect.width, rect.height)
+}
+
+/// 清除所在行并绘制标签，获得焦点时使用强调色并在左侧显示指示点
+fn draw_label(graphics: &mut GraphicsPrimitives, label: &str, y: i32, focused: bool) -> Result<()> {
+    let theme = theme::current();
+    graphics.fill_rect(&row_rect(y), theme.background)?;
+    if focused {
+        graphics.draw_filled_circle(
+            LABEL_X - FOCUS_DOT_OFFSET,
+            y - TEXT_ASCENT / 2,
+            4,
+            theme.accent,
+        )?;
+    }
+    let color = if focused {
+        theme.accent
+    } else {
+        theme.foreground
+    };
+    graphics.draw_text(label, LABEL_X, y, color, Some(theme.background))
+}
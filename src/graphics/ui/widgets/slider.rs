@@ -0,0 +1,165 @@
+use anyhow::Result;
+
+use super::{draw_label, row_bounds, Widget, VALUE_WIDTH, VALUE_X};
+use crate::graphics::{primitives::GraphicsPrimitives, theme, ui::traits::UIComponent};
+
+/// 取值文字占用的宽度，轨道画在文字右侧
+const TEXT_WIDTH: i32 = 50;
+/// 滑块半径
+const KNOB_RADIUS: i32 = 6;
+
+/// 滑块：在范围内按步长调节整数取值
+pub struct Slider<A> {
+    label: String,
+    value: i32,
+    min: i32,
+    max: i32,
+    step: i32,
+    /// 取值的显示文字
+    format: fn(i32) -> String,
+    /// 取值变化时生成操作
+    on_change: fn(i32) -> A,
+    y: i32,
+    focused: bool,
+    editing: bool,
+}
+
+impl<A> Slider<A> {
+    /// 创建滑块
+    ///
+    /// # 参数
+    /// * `label` - 标签
+    /// * `value` - 当前取值，限制在`min`与`max`之间
+    /// * `range` - 取值范围(min, max)与步长
+    /// * `format` - 取值的显示文字
+    /// * `on_change` - 取值变化时生成操作
+    pub fn new(
+        label: &str,
+        value: i32,
+        (min, max, step): (i32, i32, i32),
+        format: fn(i32) -> String,
+        on_change: fn(i32) -> A,
+    ) -> Self {
+        Self {
+            label: label.to_string(),
+            value: value.clamp(min, max),
+            min,
+            max,
+            step: step.max(1),
+            format,
+            on_change,
+            y: 0,
+            focused: false,
+            editing: false,
+        }
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+}
+
+impl<A> UIComponent for Slider<A> {
+    fn render(&self, graphics: &mut GraphicsPrimitives) -> Result<()> {
+        let theme = theme::current();
+        draw_label(graphics, &self.label, self.y, self.focused)?;
+
+        let value_color = if self.editing {
+            theme.accent
+        } else {
+            theme.foreground
+        };
+        graphics.draw_text(
+            &(self.format)(self.value),
+            VALUE_X,
+            self.y,
+            value_color,
+            Some(theme.background),
+        )?;
+
+        // 轨道：已选部分使用强调色
+        let track_y = self.y - 6;
+        let start = VALUE_X + TEXT_WIDTH;
+        let end = VALUE_X + VALUE_WIDTH - KNOB_RADIUS;
+        let span = (self.max - self.min).max(1);
+        let knob_x = start + (end - start) * (self.value - self.min) / span;
+        graphics.draw_line((start, track_y), (end, track_y), theme.muted, 2)?;
+        graphics.draw_line((start, track_y), (knob_x, track_y), theme.accent, 4)?;
+        let knob_color = if self.editing {
+            theme.accent
+        } else {
+            theme.foreground
+        };
+        graphics.draw_filled_circle(knob_x, track_y, KNOB_RADIUS, knob_color)
+    }
+
+    fn get_bounds(&self) -> (i32, i32, i32, i32) {
+        row_bounds(self.y)
+    }
+}
+
+impl<A> Widget<A> for Slider<A> {
+    fn set_row(&mut self, y: i32) {
+        self.y = y;
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        if !focused {
+            self.editing = false;
+        }
+    }
+
+    fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    fn set_editing(&mut self, editing: bool) {
+        self.editing = editing;
+    }
+
+    fn activate(&mut self) -> Option<A> {
+        self.editing = !self.editing;
+        None
+    }
+
+    fn rotate(&mut self, delta: i32) -> Option<A> {
+        if !self.editing {
+            return None;
+        }
+        let value = (self.value + delta * self.step).clamp(self.min, self.max);
+        if value == self.value {
+            return None;
+        }
+        self.value = value;
+        Some((self.on_change)(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotate_while_editing() {
+        let mut slider = Slider::new("音量", 90, (0, 100, 10), |v| v.to_string(), |v| v);
+        assert_eq!(slider.rotate(1), None);
+
+        slider.activate();
+        assert!(slider.is_editing());
+        assert_eq!(slider.rotate(1), Some(100));
+        // 到达上限后不再产生操作
+        assert_eq!(slider.rotate(1), None);
+        assert_eq!(slider.rotate(-3), Some(70));
+
+        slider.set_focused(false);
+        assert!(!slider.is_editing());
+        assert_eq!(slider.value(), 70);
+    }
+
+    #[test]
+    fn test_value_clamped() {
+        let slider = Slider::new("亮度", 1, (5, 100, 5), |v| v.to_string(), |v| v);
+        assert_eq!(slider.value(), 5);
+    }
+}
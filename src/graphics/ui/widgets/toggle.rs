@@ -0,0 +1,109 @@
+use anyhow::Result;
+
+use super::{draw_label, row_bounds, Widget, VALUE_X};
+use crate::graphics::{
+    layout::ScreenRect, primitives::GraphicsPrimitives, theme, ui::traits::UIComponent,
+};
+
+/// 开关的宽度
+const TRACK_WIDTH: i32 = 44;
+/// 开关的高度
+const TRACK_HEIGHT: i32 = 20;
+
+/// 开关：单击切换开/关
+pub struct Toggle<A> {
+    label: String,
+    value: bool,
+    /// 取值变化时生成操作
+    on_change: fn(bool) -> A,
+    y: i32,
+    focused: bool,
+}
+
+impl<A> Toggle<A> {
+    /// 创建开关
+    ///
+    /// # 参数
+    /// * `label` - 标签
+    /// * `value` - 当前取值
+    /// * `on_change` - 取值变化时生成操作
+    pub fn new(label: &str, value: bool, on_change: fn(bool) -> A) -> Self {
+        Self {
+            label: label.to_string(),
+            value,
+            on_change,
+            y: 0,
+            focused: false,
+        }
+    }
+
+    pub fn value(&self) -> bool {
+        self.value
+    }
+}
+
+impl<A> UIComponent for Toggle<A> {
+    fn render(&self, graphics: &mut GraphicsPrimitives) -> Result<()> {
+        let theme = theme::current();
+        draw_label(graphics, &self.label, self.y, self.focused)?;
+
+        let track = ScreenRect::new(
+            VALUE_X,
+            self.y - TRACK_HEIGHT + 4,
+            TRACK_WIDTH,
+            TRACK_HEIGHT,
+        );
+        let track_color = if self.value {
+            theme.accent
+        } else {
+            theme.muted
+        };
+        graphics.fill_rect(&track, track_color)?;
+
+        // 圆形滑块：开在右侧，关在左侧
+        let radius = TRACK_HEIGHT / 2 - 2;
+        let knob_x = if self.value {
+            track.x + track.width - TRACK_HEIGHT / 2
+        } else {
+            track.x + TRACK_HEIGHT / 2
+        };
+        graphics.draw_filled_circle(knob_x, track.y + TRACK_HEIGHT / 2, radius, theme.foreground)
+    }
+
+    fn get_bounds(&self) -> (i32, i32, i32, i32) {
+        row_bounds(self.y)
+    }
+}
+
+impl<A> Widget<A> for Toggle<A> {
+    fn set_row(&mut self, y: i32) {
+        self.y = y;
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn activate(&mut self) -> Option<A> {
+        self.value = !self.value;
+        Some((self.on_change)(self.value))
+    }
+
+    fn rotate(&mut self, _delta: i32) -> Option<A> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activate_flips() {
+        let mut toggle = Toggle::new("静音", false, |muted| muted);
+        assert_eq!(toggle.activate(), Some(true));
+        assert_eq!(toggle.activate(), Some(false));
+        assert_eq!(toggle.rotate(1), None);
+        assert!(!toggle.is_editing());
+    }
+}
@@ -0,0 +1,176 @@
+// src/hal.rs
+//! 硬件抽象
+//!
+//! 界面、音频采集与运动检测只通过这里的trait访问硬件：
+//! - [`DisplayDevice`]：屏幕的尺寸、方向，以及交给显示线程的位图传输端口[`BitmapSink`]
+//!   与面板重新初始化句柄[`PanelReinit`]
+//! - [`AudioInput`]：单声道16位PCM输入
+//! - [`MotionSensor`]：六轴运动传感器
+//! - [`AmbientLightSensor`]：环境光传感器（可选）
+//! - [`RealTimeClock`]：带备用电池的实时时钟（可选）
+//! - [`I2cTransport`]：I2C总线上某个设备的读写，驱动通过它访问寄存器
+//!
+//! 当前实现分别是ST77916、I2S麦克风与QMI8658。更换面板（如GC9A01）或传感器（如MPU6050）
+//! 时只需新增实现，调用方不变；测试中也可以用模拟设备替换。
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::clock::LocalTime;
+use crate::peripherals::st77916::orientation::DisplayOrientation;
+
+/// 异步位图传输的凭据，用于等待该次传输完成
+///
+/// 序号由[`BitmapSink`]的实现分配，调用方只需原样传回。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransferTicket(pub u32);
+
+/// 屏幕
+pub trait DisplayDevice {
+    /// 旋转后的逻辑宽度
+    fn width(&self) -> i32;
+
+    /// 旋转后的逻辑高度
+    fn height(&self) -> i32;
+
+    /// 当前显示方向
+    fn orientation(&self) -> DisplayOrientation;
+
+    /// 修改显示方向，面板中已有的内容需要调用方重绘
+    ///
+    /// 调用前需保证位图传输端口上没有正在进行的传输。
+    fn set_orientation(&mut self, orientation: DisplayOrientation) -> Result<()>;
+
+    /// 打开或关闭面板显示与背光，关闭期间面板保留显示内容
+    ///
+    /// 调用前需保证位图传输端口上没有正在进行的传输。
+    fn set_power(&mut self, on: bool) -> Result<()>;
+
+    /// 设置背光亮度（0-100），屏幕关闭时在下次打开后生效
+    fn set_brightness(&mut self, percent: u8) -> Result<()>;
+
+    /// 面板重新初始化句柄，交给显示线程在位图传输持续失败时使用
+    fn panel_reinit(&self) -> Box<dyn PanelReinit>;
+
+    /// 位图传输端口，交给显示线程使用
+    fn bitmap_sink(&self) -> Box<dyn BitmapSink>;
+}
+
+/// 位图传输端口
+///
+/// 只负责把像素发送到屏幕，可以在显示线程中使用。
+pub trait BitmapSink: Send {
+    /// 把位图传输放入队列后立即返回
+    ///
+    /// 调用方必须保证`color_data`在传输完成（`wait_transfer`返回）之前不被修改或释放。
+    ///
+    /// # 参数
+    /// * `x_start`, `y_start` - 区域左上角（包含）
+    /// * `x_end`, `y_end` - 区域右下角（不包含）
+    /// * `color_data` - RGB565像素，按行排列
+    ///
+    /// # 返回值
+    /// 本次传输的凭据
+    fn draw_bitmap_async(
+        &self,
+        x_start: i32,
+        y_start: i32,
+        x_end: i32,
+        y_end: i32,
+        color_data: &[u16],
+    ) -> Result<TransferTicket>;
+
+    /// 等待指定传输完成
+    fn wait_transfer(&self, ticket: TransferTicket) -> Result<()>;
+
+    /// 等待所有已排队的传输完成
+    fn wait_idle(&self) -> Result<()>;
+
+    /// 连续失败（排队出错或等待超时）的传输次数，传输成功完成时清零
+    fn transfer_errors(&self) -> u32;
+}
+
+/// 面板重新初始化
+///
+/// 包含复位等待，只在显示线程中调用。
+pub trait PanelReinit: Send {
+    /// 重新执行面板复位与初始化，恢复当前方向，面板中的内容需要调用方重绘
+    ///
+    /// 调用前需保证没有其他线程在排队传输。
+    fn reinit(&mut self) -> Result<()>;
+}
+
+/// 音频输入
+///
+/// 采集线程独占实例，停止采集后再交还给主线程。
+pub trait AudioInput: Send {
+    /// 开始采集
+    fn start_recording(&mut self) -> Result<()>;
+
+    /// 停止采集
+    fn stop_recording(&mut self) -> Result<()>;
+
+    /// 读取样本，阻塞到有数据可读
+    ///
+    /// # 返回值
+    /// 实际读取的样本数
+    fn read_samples(&mut self, buffer: &mut [i16]) -> Result<usize>;
+}
+
+/// 运动传感器的一次采样
+///
+/// 包含从传感器读取的完整数据，包括3轴加速度、3轴角速度、温度和时间戳
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SensorData {
+    /// X轴加速度值 (单位根据配置：mg或m/s²)
+    pub accel_x: f32,
+    /// Y轴加速度值 (单位根据配置：mg或m/s²)
+    pub accel_y: f32,
+    /// Z轴加速度值 (单位根据配置：mg或m/s²)
+    pub accel_z: f32,
+    /// X轴角速度值 (单位根据配置：dps或rad/s)
+    pub gyro_x: f32,
+    /// Y轴角速度值 (单位根据配置：dps或rad/s)
+    pub gyro_y: f32,
+    /// Z轴角速度值 (单位根据配置：dps或rad/s)
+    pub gyro_z: f32,
+    /// 芯片温度值 (°C)
+    pub temperature: f32,
+    /// 时间戳 (传感器内部计数器)
+    pub timestamp: u32,
+}
+
+/// 运动传感器
+pub trait MotionSensor: Send {
+    /// 读取一次加速度、角速度与温度
+    fn read_sensor_data(&mut self) -> Result<SensorData>;
+}
+
+/// 环境光传感器
+pub trait AmbientLightSensor: Send {
+    /// 读取最近一次测量的照度(lux)
+    fn read_lux(&mut self) -> Result<f32>;
+}
+
+/// 带备用电池的实时时钟，保存本地时间（北京时间）
+pub trait RealTimeClock: Send {
+    /// 读取当前时间
+    ///
+    /// # 返回值
+    /// 芯片曾经掉电（振荡器停止）或尚未设置时间时返回None
+    fn read_time(&mut self) -> Result<Option<LocalTime>>;
+
+    /// 设置时间，同时清除掉电标志
+    fn set_time(&mut self, time: &LocalTime) -> Result<()>;
+}
+
+/// I2C总线上某个设备的读写
+///
+/// 实现为共享总线上的设备句柄`I2cDevice`；测试中用按脚本应答的模拟设备代替，检查驱动发出的传输。
+pub trait I2cTransport: Send {
+    /// 写入数据
+    fn write(&self, bytes: &[u8]) -> Result<()>;
+
+    /// 先写后读（通常是写寄存器地址再读数据），期间不会被其他设备打断
+    fn write_read(&self, bytes: &[u8], buffer: &mut [u8]) -> Result<()>;
+}
@@ -0,0 +1,82 @@
+// src/lib.rs
+//! 可在主机上编译的模块子集，供`cargo test --lib`运行单元测试
+//!
+//! 模块路径与`main.rs`中的模块树一致；依赖ESP-IDF的模块只由二进制目标编译
+pub mod api {
+    pub mod cache;
+    pub mod endpoints;
+    pub mod intercom;
+    pub mod pacing;
+    pub mod persona;
+    pub mod request;
+    pub mod sse;
+    pub mod types;
+}
+pub mod app {
+    pub mod chat_request;
+    pub mod frame_pacing;
+    pub mod intercom_playback;
+    pub mod push_to_talk;
+    pub mod scheduler;
+    pub mod url_editor;
+    pub mod voice_guide;
+}
+pub mod blocking;
+pub mod boards;
+pub mod clock;
+pub mod graphics {
+    pub mod colors;
+    pub mod framebuffer;
+    pub mod layout;
+    pub mod panel_recovery;
+    pub mod primitives;
+    pub mod theme;
+    pub mod ui {
+        pub mod chat_bubble;
+        pub mod emoji;
+        pub mod icons;
+        pub mod progress_ring;
+        pub mod qrcode;
+        pub mod statusbar;
+        pub mod traits;
+        pub mod widgets;
+    }
+}
+pub mod hal;
+pub mod metrics;
+pub mod peripherals {
+    pub mod button {
+        pub mod classifier;
+    }
+    pub mod microphone {
+        pub mod activity;
+        pub mod dsp;
+        pub mod fft;
+        pub mod preroll;
+        pub mod recorder;
+        pub mod tap;
+        pub mod utterance;
+    }
+    pub mod neopixel {
+        pub mod effects;
+    }
+    pub mod qmi8658 {
+        pub mod calibration;
+        pub mod motion_detector;
+        pub mod pedometer;
+    }
+    pub mod resample;
+    pub mod speaker {
+        pub mod earcon;
+        pub mod tone;
+        pub mod volume;
+    }
+    pub mod st77916 {
+        pub mod orientation;
+    }
+    pub mod wifi {
+        pub mod config;
+    }
+}
+#[cfg(test)]
+pub mod testing;
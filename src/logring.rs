@@ -0,0 +1,157 @@
+// src/logring.rs
+//! 日志环形缓冲
+//!
+//! 安装后`log`宏的输出照常交给`EspLogger`打印到串口，同时按行保存在内存中，
+//! 超过`LOG_RING_BYTES`时丢弃最旧的行。远程支持时由[`snapshot`]取出，
+//! 经`ApiClient::upload_logs`压缩后上传。`println!`的输出不经过`log`，不会被记录。
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, PoisonError};
+
+use esp_idf_svc::log::EspLogger;
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// 缓冲中保存的日志总字节数上限
+pub const LOG_RING_BYTES: usize = 32 * 1024;
+
+/// 单行日志的最大字节数，更长的部分截断
+const MAX_LINE_BYTES: usize = 512;
+
+/// 按行保存的日志，总长度超出上限时丢弃最旧的行
+#[derive(Debug)]
+struct LogRing {
+    lines: VecDeque<String>,
+    /// 所有行的字节数（含换行）
+    bytes: usize,
+    capacity: usize,
+}
+
+impl LogRing {
+    const fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            bytes: 0,
+            capacity,
+        }
+    }
+
+    fn push(&mut self, mut line: String) {
+        if line.len() > MAX_LINE_BYTES {
+            let mut end = MAX_LINE_BYTES;
+            while !line.is_char_boundary(end) {
+                end -= 1;
+            }
+            line.truncate(end);
+        }
+        self.bytes += line.len() + 1;
+        self.lines.push_back(line);
+        while self.bytes > self.capacity {
+            match self.lines.pop_front() {
+                Some(old) => self.bytes -= old.len() + 1,
+                None => break,
+            }
+        }
+    }
+
+    /// 按时间顺序拼接所有行
+    fn snapshot(&self) -> Vec<u8> {
+        let mut text = Vec::with_capacity(self.bytes);
+        for line in &self.lines {
+            text.extend_from_slice(line.as_bytes());
+            text.push(b'\n');
+        }
+        text
+    }
+}
+
+static RING: Mutex<LogRing> = Mutex::new(LogRing::new(LOG_RING_BYTES));
+
+/// 打印到串口并写入环形缓冲的日志器
+struct RingLogger {
+    console: EspLogger,
+}
+
+impl Log for RingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.console.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        self.console.log(record);
+
+        let uptime_ms = unsafe { esp_idf_sys::esp_timer_get_time() } / 1000;
+        let line = format!(
+            "[{}] {} {}: {}",
+            uptime_ms,
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        RING.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(line);
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+    }
+}
+
+/// 安装日志器，启动时尽早调用
+pub fn install() -> anyhow::Result<()> {
+    log::set_boxed_logger(Box::new(RingLogger {
+        console: EspLogger::new(),
+    }))
+    .map_err(|e| anyhow::anyhow!("安装日志器失败: {}", e))?;
+    log::set_max_level(LevelFilter::Info);
+    Ok(())
+}
+
+/// 取出缓冲中的全部日志（UTF-8文本，每行一条）
+pub fn snapshot() -> Vec<u8> {
+    RING.lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .snapshot()
+}
+
+/// zlib压缩日志文本，上传时配合`Content-Encoding: deflate`
+pub fn compress(text: &[u8]) -> Vec<u8> {
+    miniz_oxide::deflate::compress_to_vec_zlib(text, 6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drops_oldest_lines() {
+        let mut ring = LogRing::new(16);
+        ring.push("first".to_string());
+        ring.push("second".to_string());
+        assert_eq!(ring.snapshot(), b"first\nsecond\n");
+
+        ring.push("third".to_string());
+        assert_eq!(ring.snapshot(), b"second\nthird\n");
+        assert_eq!(ring.bytes, 13);
+    }
+
+    #[test]
+    fn test_truncates_long_line() {
+        let mut ring = LogRing::new(LOG_RING_BYTES);
+        ring.push("日".repeat(MAX_LINE_BYTES));
+        assert!(ring.snapshot().len() <= MAX_LINE_BYTES + 1);
+        assert!(std::str::from_utf8(&ring.snapshot()).is_ok());
+    }
+
+    #[test]
+    fn test_compress_round_trip() {
+        let text = "[1200] INFO wifi: connected\n".repeat(100);
+        let compressed = compress(text.as_bytes());
+        assert!(compressed.len() < text.len() / 4);
+        let restored = miniz_oxide::inflate::decompress_to_vec_zlib(&compressed).unwrap();
+        assert_eq!(restored, text.as_bytes());
+    }
+}
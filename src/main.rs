@@ -2,7 +2,12 @@ use std::time::Duration;
 
 // src/main.rs
 use anyhow::Result;
-use esp_idf_hal::{delay::FreeRtos, peripherals::Peripherals};
+use esp_idf_hal::{
+    delay::FreeRtos,
+    gpio::{PinDriver, Pull},
+    peripherals::Peripherals,
+    prelude::*,
+};
 use esp_idf_svc::{eventloop::EspSystemEventLoop, nvs::EspDefaultNvsPartition};
 use esp_idf_sys::{
     esp_timer_get_time, heap_caps_get_free_size, heap_caps_get_largest_free_block,
@@ -16,88 +21,384 @@ use esp_idf_sys::{
 mod actors;
 mod api;
 mod app;
+mod blocking;
+mod boards;
+mod clock;
+mod config;
+mod crash;
 mod display;
+mod error;
 mod events;
 mod graphics;
+mod hal;
+mod logring;
+mod metrics;
+#[cfg(feature = "display-mirror")]
+mod mirror;
 mod peripherals;
+mod stats;
+#[cfg(test)]
+mod testing;
+#[cfg(feature = "event-trace")]
+mod trace;
 
 use crate::{
-    actors::{motion::MotionActorManager, wifi::WifiActorManager},
+    actors::{
+        chat::ChatActorManager, display::DisplayActorManager, motion::MotionActorManager,
+        weather::WeatherActorManager, wifi::WifiActorManager,
+    },
     api::{
         client::ApiClient,
         pcm_client::{PcmClient, PcmClientConfig},
+        signing, tls, ApiConfig,
     },
-    app::App,
+    app::{alarms::AlarmManager, kids::KidsUsageStore, App},
+    boards::{BoardPins, MicInterface},
+    clock,
+    config::ConfigStore,
     display::Display,
     events::{EventBus, EventHandler},
     graphics::primitives::GraphicsPrimitives,
-    peripherals::{microphone, st77916::lcd::LcdController, wifi::WifiConfig},
+    hal::AudioInput,
+    peripherals::{
+        als,
+        backlight::Backlight,
+        battery::BatteryMonitor,
+        button::{ButtonActorManager, ButtonConfig, BOOT_BUTTON},
+        i2c_bus::SharedI2cBus,
+        microphone,
+        neopixel::{NeoPixelRing, StatusRingManager},
+        qmi8658::{QMI8658Driver, QMI8658_ADDRESS_HIGH},
+        rtc, speaker,
+        st77916::{lcd::LcdController, orientation::DisplayOrientation},
+        storage::Storage,
+        tca9554::{Tca9554, EXIO_LCD_RST, EXIO_TOUCH_RST, TCA9554_ADDRESS},
+        wifi,
+    },
+    stats::StatsStore,
 };
 
 fn main() -> Result<()> {
     // 必须先调用，打补丁
     esp_idf_sys::link_patches();
 
+    // 尽早安装，启动过程中的panic也能留下记录
+    crash::install_panic_hook();
+    // log宏的输出同时保存到内存，供上传日志
+    logring::install()?;
+
     println!("=== ESP32 AI 聊天助手 ===");
 
-    // 取得外设
+    // 取得外设，引脚按板子分配（通过Cargo特性选择板子）
     let p = Peripherals::take().unwrap();
+    let pins = BoardPins::take(p.pins);
+    println!("板子: {}", boards::SPEC);
+
+    // I2C总线：IMU、IO扩展芯片共用
+    let i2c_bus = SharedI2cBus::new(p.i2c0, pins.i2c.sda, pins.i2c.scl, 400.kHz().into())?;
 
-    // 传感器gpio
-    let sda = p.pins.gpio11;
-    let scl = p.pins.gpio10;
-    let i2c = p.i2c0;
+    // LCD与触摸屏的复位脚接在TCA9554上，先复位再初始化LCD
+    let mut expander = Tca9554::new(i2c_bus.device(TCA9554_ADDRESS))?;
+    if boards::SPEC.has_touch {
+        expander.pulse_reset(
+            EXIO_TOUCH_RST,
+            Duration::from_millis(10),
+            Duration::from_millis(50),
+        )?;
+    }
+    let backlight = Backlight::new(p.ledc.timer0, p.ledc.channel0, pins.lcd.backlight)?;
+    let mut lcd = LcdController::new(
+        backlight,
+        DisplayOrientation::default(),
+        boards::SPEC.lcd,
+        Some(&mut expander.reset_line(EXIO_LCD_RST)),
+    )?;
+    #[cfg(feature = "lcd-benchmark")]
+    match lcd.benchmark() {
+        Ok(result) => println!("LCD基准测试: {}", result),
+        Err(e) => println!("LCD基准测试失败: {}", e),
+    }
 
     // 创建事件总线
-    let event_bus = EventBus::new();
+    let mut event_bus = EventBus::new();
     let event_sender = event_bus.get_sender();
 
     // 初始化运动检测actor（自动启动后台线程）
     println!("正在初始化运动检测器...");
-    let _motion_actor = MotionActorManager::new(i2c, sda, scl, event_sender.clone())?;
+    let imu = QMI8658Driver::new(&i2c_bus, QMI8658_ADDRESS_HIGH)?;
+    let motion_actor = MotionActorManager::new(Box::new(imu), event_sender.clone())?;
 
     // 然后初始化WiFi系统
     let sys_loop = EspSystemEventLoop::take()?;
     let nvs = EspDefaultNvsPartition::take()?;
 
+    // 记录本次启动（启动次数、异常复位）
+    let stats = StatsStore::new(nvs.clone())?;
+    let config = ConfigStore::new(nvs.clone())?;
+
+    // 闹钟与倒计时保存在NVS中，后台线程检查到期
+    let alarms = AlarmManager::new(nvs.clone(), event_sender.clone())?;
+
+    // 儿童模式当天已用的互动时长
+    let kids_usage = KidsUsageStore::new(nvs.clone())?;
+
+    // 客户端证书可能保存在SPIFFS中，挂载文件系统后再加载
+    let tls_nvs = nvs.clone();
+    let auth_nvs = nvs.clone();
+
+    // WiFi凭据（包括企业网络的认证信息与CA证书）保存在NVS中
+    let wifi_config = wifi::store::load(nvs.clone())?;
+
     println!("正在初始化WiFi...");
     let wifi_actor = WifiActorManager::new(p.modem, sys_loop, Some(nvs), event_sender.clone())?;
 
-    let wifi_config = WifiConfig::new("fushangyun", "fsy@666888");
+    // 没有WiFi凭据时不会进入首次连接的启动流程，主循环从一开始就按预算检查
+    if let Some(wifi_config) = wifi_config {
+        wifi_actor.connect(wifi_config)?;
+    } else {
+        blocking::finish_startup();
+    }
+
+    // 麦克风，增益与噪声门来自设备配置
+    let mic_pins = pins.microphone;
+    let dsp_config = config.config().mic_dsp.dsp_config();
+    let mic: Box<dyn AudioInput> = match boards::SPEC.microphone {
+        MicInterface::I2sStd => {
+            let sck = mic_pins
+                .sck
+                .ok_or_else(|| anyhow::anyhow!("标准I2S麦克风需要SCK引脚"))?;
+            let mut mic = microphone::i2s_microphone::I2sMicrophone::new(
+                p.i2s0,
+                mic_pins.ws,
+                sck,
+                mic_pins.sd,
+                16000,
+            )?;
+            mic.set_dsp_config(dsp_config);
+            Box::new(mic)
+        }
+        MicInterface::Pdm => {
+            let mut mic = microphone::pdm_microphone::PdmMicrophone::new(
+                p.i2s0,
+                mic_pins.ws,
+                mic_pins.sd,
+                16000,
+            )?;
+            mic.set_dsp_config(dsp_config);
+            Box::new(mic)
+        }
+    };
+
+    // 扬声器（采样率与麦克风一致，便于回声消除）
+    let speaker_pins = pins.speaker;
+    let speaker = speaker::i2s_speaker::I2sSpeaker::new(
+        p.i2s1,
+        speaker_pins.bclk,
+        speaker_pins.dout,
+        speaker_pins.ws,
+        16000,
+    )?;
+
+    // 启动过程中一直按住BOOT键则进入硬件自检
+    let mut boot_button = pins.boot_button;
+    let self_test_requested = {
+        let mut button = PinDriver::input(&mut boot_button)?;
+        button.set_pull(Pull::Up)?;
+        button.is_low()
+    };
+
+    // 按键说话：BOOT键，按下开始录音，松开上传
+    let buttons = vec![ButtonConfig::active_low(BOOT_BUTTON, boot_button)];
+    let _button_actor =
+        ButtonActorManager::new(buttons, event_sender.clone(), Some(speaker.reference()))?;
+
+    // 文件系统：SPIFFS必须可用，SD卡可选
+    let mut storage = Storage::mount()?;
+    let sd_pins = pins.sd_card;
+    if let Err(e) = storage.mount_sd_card(p.sdmmc1, sd_pins.clk, sd_pins.cmd, sd_pins.d0) {
+        println!("未检测到SD卡: {}", e);
+    }
+
+    // 事件记录：有SD卡时写SD卡
+    #[cfg(feature = "event-trace")]
+    let trace_dir = storage.bulk_location().mount_point();
+    #[cfg(feature = "event-trace")]
+    let mut recorder = match trace::EventRecorder::open(trace_dir) {
+        Ok(recorder) => Some(recorder),
+        Err(e) => {
+            println!("启动事件记录失败: {}", e);
+            None
+        }
+    };
+
+    // 双向TLS：配置了客户端证书时，对话与语音上传请求出示该证书
+    if let Err(e) = tls::install(tls_nvs) {
+        println!("加载客户端证书失败: {}", e);
+    }
 
-    wifi_actor.connect(wifi_config)?;
+    // 配置了设备密钥时，对话与语音上传请求附带HMAC签名
+    if let Err(e) = signing::install(auth_nvs) {
+        println!("加载设备密钥失败: {}", e);
+    }
 
-    // mic gpio
-    let i2s = p.i2s0;
-    let ws = p.pins.gpio2;
-    let sck = p.pins.gpio15;
-    let sd = p.pins.gpio39;
-    let mic = microphone::i2s_microphone::I2sMicrophone::new(i2s, ws, sck, sd, 16000)?;
+    // 刷新在独立的显示线程中进行，并与面板TE信号同步，避免撕裂
+    let te_pin = match lcd.enable_te_output() {
+        Ok(()) => Some(pins.lcd.te),
+        Err(e) => {
+            println!("LCD TE同步启用失败: {}", e);
+            None
+        }
+    };
+    let graphics = GraphicsPrimitives::new(&mut lcd, |lcd, framebuffer| {
+        Ok(Box::new(DisplayActorManager::new(
+            lcd,
+            framebuffer,
+            te_pin,
+        )?))
+    })?;
 
-    // lcd背光控制gpio - 先初始化显示系统
-    let bl_io = p.pins.gpio5;
-    // let app = DisplayActorManager::new(bl_io);
-    let mut lcd = LcdController::new(bl_io).unwrap();
-    let graphics = GraphicsPrimitives::new(&mut lcd);
+    // 屏幕镜像：服务器在WiFi连接后才能访问，先启动即可，变量需保持到程序结束
+    #[cfg(feature = "display-mirror")]
+    let _mirror = match mirror::MirrorServer::start(graphics.shared_framebuffer()) {
+        Ok(server) => Some(server),
+        Err(e) => {
+            println!("启动屏幕镜像失败: {}", e);
+            None
+        }
+    };
     let display = Display::new(graphics);
 
-    let mut app = App::new(display, mic);
+    // 对话请求在独立线程中执行，截止时间由ChatActor控制
+    let chat = ChatActorManager::new(
+        ApiConfig {
+            base_url: config.config().endpoints.base_url().to_string(),
+            pcm_url: config.config().endpoints.pcm_url.clone(),
+            fingerprint: "esp32".to_string(),
+            ..ApiConfig::default()
+        },
+        config.config().model.clone(),
+        config.config().active_persona(),
+        event_sender.clone(),
+    )?;
+
+    // 天气（配置了API密钥时启用，WiFi连接后开始获取）
+    let weather = if config.config().weather.is_enabled() {
+        Some(WeatherActorManager::new(
+            config.config().weather.clone(),
+            event_sender.clone(),
+        )?)
+    } else {
+        None
+    };
+
+    // 电池电压检测（板子支持时）
+    let battery = boards::SPEC
+        .battery
+        .and_then(|sense| match BatteryMonitor::new(sense) {
+            Ok(monitor) => Some(monitor),
+            Err(e) => {
+                println!("电池检测初始化失败: {}", e);
+                None
+            }
+        });
+
+    // 实时时钟（外接配件），没有WiFi时也能恢复时间
+    let mut rtc = rtc::probe(&i2c_bus);
+    if let Some(rtc) = rtc.as_mut() {
+        if let Err(e) = clock::restore_from_rtc(rtc.as_mut()) {
+            println!("从实时时钟恢复时间失败: {}", e);
+        }
+    }
+
+    // 环境光传感器（外接配件，探测到时启用自动亮度）
+    let ambient_light = als::probe(&i2c_bus);
+
+    // 状态灯环（外接配件，板子分配了引脚时启用）
+    let status_ring = match (boards::SPEC.status_ring_leds, pins.status_ring) {
+        (Some(count), Some(pin)) => {
+            match NeoPixelRing::new(p.rmt.channel0, pin, count as usize)
+                .and_then(StatusRingManager::new)
+            {
+                Ok(ring) => Some(ring),
+                Err(e) => {
+                    println!("状态灯环初始化失败: {}", e);
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    let mut app = App::new(
+        display,
+        mic,
+        speaker,
+        stats,
+        storage,
+        config,
+        chat,
+        battery,
+        weather,
+        alarms,
+        kids_usage,
+        status_ring,
+        ambient_light,
+        rtc,
+        motion_actor,
+        wifi_actor,
+        i2c_bus,
+        event_sender.clone(),
+    );
+
+    if self_test_requested {
+        println!("检测到BOOT键按下，开始硬件自检");
+        if let Err(e) = app.start_self_test() {
+            println!("启动硬件自检失败: {}", e);
+        }
+    }
+
+    // 回放在进入主循环前开始，保持记录时的时间间隔
+    #[cfg(feature = "event-trace")]
+    if let Err(e) = trace::start_replay(trace_dir, event_sender.clone()) {
+        println!("启动事件回放失败: {}", e);
+    }
 
     println!("应用启动成功，进入主循环...");
 
     loop {
+        let loop_start = std::time::Instant::now();
+
         // 处理事件
         while let Ok(event) = event_bus.try_recv() {
+            #[cfg(feature = "event-trace")]
+            if let Some(recorder) = recorder.as_mut() {
+                recorder.record(&event);
+            }
             if let Err(e) = app.handle_event(event) {
                 eprintln!("处理事件失败: {}", e);
             }
         }
+        #[cfg(feature = "event-trace")]
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.flush();
+        }
+
+        // 执行到期的周期任务（界面刷新、状态栏、遥测）
+        let wait = app.tick();
 
-        // 定期更新显示（用于动画和UI刷新，但时间计算不再依赖此频率）
-        if let Err(e) = app.update() {
-            eprintln!("显示更新失败: {}", e);
+        // 主循环中不允许出现长时间阻塞调用
+        blocking::check_budget(
+            "main_loop",
+            blocking::MAIN_LOOP_BUDGET,
+            loop_start.elapsed(),
+        );
+        // 启动工作在本次迭代中完成，之后的迭代超出预算时debug构建直接中断
+        if app.startup_finished() {
+            blocking::finish_startup();
         }
 
-        FreeRtos::delay_ms(50);
+        // 休眠到下一个任务到期，最长一个界面刷新周期，保证事件及时处理
+        let wait = wait.min(app::UI_REFRESH_PERIOD).as_millis().max(1);
+        FreeRtos::delay_ms(wait as u32);
     }
 }
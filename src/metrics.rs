@@ -0,0 +1,165 @@
+// src/metrics.rs
+//! 运行指标
+//!
+//! 全局的轻量指标注册表，任意线程都可以直接记录：
+//! - 计数器：只增不减，例如I2C错误次数、上传字节数
+//! - 仪表：最近一次的值，例如上传速率
+//! - 直方图：按固定分桶统计分布，例如每帧绘制耗时、HTTP延迟
+//!
+//! 指标名在首次记录时注册。[`take_snapshot`]生成可序列化的快照，遥测任务定期以JSON输出。
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// 阻塞调用超出预算的次数（见`blocking::check_budget`）
+pub const BLOCKING_OVERRUNS: &str = "blocking_overruns";
+/// 每帧界面绘制耗时（毫秒）
+pub const FRAME_RENDER_MS: &str = "frame_render_ms";
+/// 最近一秒实际绘制的界面帧率
+pub const UI_FPS: &str = "ui_fps";
+/// HTTP请求耗时（毫秒，从建立连接到读完响应）
+pub const HTTP_LATENCY_MS: &str = "http_latency_ms";
+/// 服务端返回错误的次数（非2xx响应）
+pub const HTTP_ERRORS: &str = "http_errors";
+/// GET请求命中响应缓存的次数
+pub const HTTP_CACHE_HITS: &str = "http_cache_hits";
+/// 已上传的音频字节数
+pub const AUDIO_UPLOAD_BYTES: &str = "audio_upload_bytes";
+/// 最近一次音频上传的速率（KB/s）
+pub const AUDIO_UPLOAD_KBPS: &str = "audio_upload_kbps";
+/// I2C读写失败次数
+pub const I2C_ERRORS: &str = "i2c_errors";
+/// LCD位图传输失败次数（排队出错或等待超时）
+pub const LCD_TRANSFER_ERRORS: &str = "lcd_transfer_errors";
+/// 传输持续失败后重新初始化LCD面板的次数
+pub const LCD_REINITS: &str = "lcd_reinits";
+/// 原始麦克风信号电平（最近一秒的均方根幅度）
+pub const MIC_RMS_RAW: &str = "mic_rms_raw";
+/// AFE处理（回声消除、降噪）后的电平
+pub const MIC_RMS_PROCESSED: &str = "mic_rms_processed";
+/// AFE处理后电平下降的分贝数
+pub const MIC_REDUCTION_DB: &str = "mic_reduction_db";
+
+/// 直方图分桶上界，覆盖毫秒级到秒级的耗时
+const HISTOGRAM_BOUNDS: [f64; 10] = [
+    1.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 5000.0,
+];
+
+/// 直方图
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Histogram {
+    pub count: u64,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+    /// 各分桶的样本数，最后一项为超出最大上界的样本
+    pub buckets: [u64; HISTOGRAM_BOUNDS.len() + 1],
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count += 1;
+        self.sum += value;
+        let bucket = HISTOGRAM_BOUNDS
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(HISTOGRAM_BOUNDS.len());
+        self.buckets[bucket] += 1;
+    }
+}
+
+/// 指标快照
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MetricsSnapshot {
+    pub counters: BTreeMap<&'static str, u64>,
+    pub gauges: BTreeMap<&'static str, f64>,
+    pub histograms: BTreeMap<&'static str, Histogram>,
+}
+
+static REGISTRY: Mutex<MetricsSnapshot> = Mutex::new(MetricsSnapshot {
+    counters: BTreeMap::new(),
+    gauges: BTreeMap::new(),
+    histograms: BTreeMap::new(),
+});
+
+/// 访问注册表，记录指标的线程panic后仍继续使用已有数据
+fn with_registry<T>(f: impl FnOnce(&mut MetricsSnapshot) -> T) -> T {
+    f(&mut REGISTRY.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// 计数器加`by`
+pub fn increment(name: &'static str, by: u64) {
+    with_registry(|registry| *registry.counters.entry(name).or_default() += by);
+}
+
+/// 设置仪表的值
+pub fn set_gauge(name: &'static str, value: f64) {
+    with_registry(|registry| {
+        registry.gauges.insert(name, value);
+    });
+}
+
+/// 向直方图添加一个样本
+pub fn observe(name: &'static str, value: f64) {
+    with_registry(|registry| registry.histograms.entry(name).or_default().observe(value));
+}
+
+/// 向直方图添加一个耗时样本（毫秒）
+pub fn observe_duration(name: &'static str, duration: Duration) {
+    observe(name, duration.as_secs_f64() * 1000.0);
+}
+
+/// 记录一次音频上传：累计字节数并更新上传速率
+///
+/// # 参数
+/// * `bytes` - 上传的字节数
+/// * `elapsed` - 上传耗时
+pub fn record_audio_upload(bytes: usize, elapsed: Duration) {
+    increment(AUDIO_UPLOAD_BYTES, bytes as u64);
+    let secs = elapsed.as_secs_f64();
+    if secs > 0.0 {
+        set_gauge(AUDIO_UPLOAD_KBPS, bytes as f64 / 1024.0 / secs);
+    }
+}
+
+/// 快照并清空直方图，计数器与仪表保持累计值
+///
+/// 遥测每个周期调用一次，直方图反映的是该周期内的分布。
+pub fn take_snapshot() -> MetricsSnapshot {
+    with_registry(|registry| {
+        let snapshot = registry.clone();
+        registry.histograms.clear();
+        snapshot
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets() {
+        let mut histogram = Histogram::default();
+        for value in [0.5, 3.0, 3.0, 250.0, 9000.0] {
+            histogram.observe(value);
+        }
+        assert_eq!(histogram.count, 5);
+        assert_eq!(histogram.min, 0.5);
+        assert_eq!(histogram.max, 9000.0);
+        assert_eq!(histogram.buckets[0], 1);
+        assert_eq!(histogram.buckets[1], 2);
+        assert_eq!(histogram.buckets[7], 1);
+        assert_eq!(histogram.buckets[HISTOGRAM_BOUNDS.len()], 1);
+        assert_eq!(histogram.sum, 9256.5);
+    }
+}
@@ -0,0 +1,152 @@
+// src/mirror.rs
+//! 屏幕镜像（调试用）
+//!
+//! 启用`display-mirror`特性后启动一个简单的诊断HTTP服务器：浏览器打开`http://<设备IP>/`，
+//! 页面通过`/ws`的WebSocket每秒接收`MIRROR_FPS`帧缩小后的帧缓冲区快照（180x180，
+//! 格式见`FrameBuffer::encode_rle`）并绘制到画布上，用于远程观察界面状态。
+//! 没有浏览器连接时不编码也不发送。需要在sdkconfig中开启`CONFIG_HTTPD_WS_SUPPORT`。
+
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use embedded_svc::{http::Method, io::Write};
+use esp_idf_svc::http::server::{
+    ws::{EspHttpWsConnection, EspHttpWsDetachedSender},
+    Configuration, EspHttpServer,
+};
+use esp_idf_svc::ws::FrameType;
+use log::{info, warn};
+
+use crate::actors::spawn;
+use crate::graphics::framebuffer::SharedFrameBuffer;
+
+/// HTTP端口
+const MIRROR_PORT: u16 = 80;
+/// 每秒发送的帧数
+const MIRROR_FPS: u64 = 2;
+/// 缩小倍数，360x360缩小为180x180
+const MIRROR_SCALE: i32 = 2;
+/// 最多同时连接的浏览器
+const MAX_VIEWERS: usize = 2;
+
+/// 镜像页面：解码行程编码的RGB565帧并绘制到圆形画布上
+const MIRROR_PAGE: &str = r#"<!DOCTYPE html>
+<html><head><meta charset="utf-8"><title>屏幕镜像</title>
+<style>body{background:#222;color:#ccc;font-family:sans-serif;text-align:center}
+canvas{width:360px;height:360px;border-radius:50%;image-rendering:pixelated;background:#000}</style>
+</head><body><h3>屏幕镜像</h3><canvas id="c"></canvas><p id="s">连接中...</p>
+<script>
+const c=document.getElementById('c'),s=document.getElementById('s'),g=c.getContext('2d');
+function connect(){
+  const ws=new WebSocket(`ws://${location.host}/ws`);ws.binaryType='arraybuffer';
+  ws.onopen=()=>s.textContent='已连接';
+  ws.onclose=()=>{s.textContent='已断开，重连中...';setTimeout(connect,2000)};
+  ws.onmessage=e=>{
+    const d=new DataView(e.data),w=d.getUint16(0,true),h=d.getUint16(2,true);
+    if(c.width!==w||c.height!==h){c.width=w;c.height=h}
+    const img=g.createImageData(w,h),p=img.data;let o=0;
+    for(let i=4;i+2<d.byteLength;i+=3){
+      const n=d.getUint8(i),v=d.getUint16(i+1,true);
+      const r=(v>>11)*255/31,gr=((v>>5)&63)*255/63,b=(v&31)*255/31;
+      for(let k=0;k<n;k++){p[o]=r;p[o+1]=gr;p[o+2]=b;p[o+3]=255;o+=4}
+    }
+    g.putImageData(img,0,0);
+  };
+}
+connect();
+</script></body></html>"#;
+
+/// 镜像页面的地址，显示为二维码供手机扫码打开
+///
+/// # 参数
+/// * `ip` - 设备当前的IP地址
+pub fn page_url(ip: &str) -> String {
+    format!("http://{}:{}/", ip, MIRROR_PORT)
+}
+
+/// 已连接的浏览器
+type Viewers = Arc<Mutex<Vec<EspHttpWsDetachedSender>>>;
+
+/// 屏幕镜像服务器，drop后停止HTTP服务
+pub struct MirrorServer {
+    _server: EspHttpServer<'static>,
+}
+
+impl MirrorServer {
+    /// 启动HTTP服务器与发送线程
+    ///
+    /// # 参数
+    /// * `framebuffer` - 与显示线程共享的帧缓冲区
+    pub fn start(framebuffer: SharedFrameBuffer) -> Result<Self> {
+        let viewers: Viewers = Arc::new(Mutex::new(Vec::new()));
+
+        let mut server = EspHttpServer::new(&Configuration {
+            http_port: MIRROR_PORT,
+            ..Default::default()
+        })?;
+        server.fn_handler("/", Method::Get, |request| -> Result<()> {
+            request
+                .into_response(200, None, &[("Content-Type", "text/html; charset=utf-8")])?
+                .write_all(MIRROR_PAGE.as_bytes())?;
+            Ok(())
+        })?;
+
+        let handler_viewers = viewers.clone();
+        server.ws_handler("/ws", move |ws: &mut EspHttpWsConnection| -> Result<()> {
+            if ws.is_new() {
+                let mut viewers = handler_viewers
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner);
+                if viewers.len() >= MAX_VIEWERS {
+                    anyhow::bail!("屏幕镜像连接数已满");
+                }
+                info!("屏幕镜像: 浏览器已连接 (session {})", ws.session());
+                viewers.push(ws.create_detached_sender()?);
+            } else if !ws.is_closed() {
+                // 页面不发送数据，收到的内容直接丢弃
+                let mut buf = [0u8; 64];
+                ws.recv(&mut buf)?;
+            }
+            Ok(())
+        })?;
+
+        spawn::DISPLAY_MIRROR.spawn(move || stream_frames(framebuffer, viewers))?;
+        info!("屏幕镜像已启动: http://<设备IP>:{}/", MIRROR_PORT);
+        Ok(Self { _server: server })
+    }
+}
+
+/// 按固定帧率编码当前帧并发送给所有浏览器，发送失败的连接移除
+fn stream_frames(framebuffer: SharedFrameBuffer, viewers: Viewers) {
+    let interval = Duration::from_millis(1000 / MIRROR_FPS);
+    let mut frame = Vec::new();
+    loop {
+        thread::sleep(interval);
+
+        // 发送要等httpd任务完成，而httpd任务处理新连接时会锁住列表，因此发送时不持有列表的锁
+        let mut sending =
+            std::mem::take(&mut *viewers.lock().unwrap_or_else(PoisonError::into_inner));
+        sending.retain(|viewer| !viewer.is_closed());
+        if sending.is_empty() {
+            continue;
+        }
+
+        // 只在编码时持有帧缓冲区的锁，发送期间主线程可以继续绘制
+        framebuffer.lock().encode_rle(MIRROR_SCALE, &mut frame);
+        sending.retain_mut(
+            |viewer| match viewer.send(FrameType::Binary(false), &frame) {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!("屏幕镜像: 发送失败，断开连接: {}", e);
+                    false
+                }
+            },
+        );
+        viewers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .append(&mut sending);
+    }
+}
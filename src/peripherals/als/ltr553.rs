@@ -0,0 +1,116 @@
+//! LTR-553ALS环境光与接近传感器驱动（只使用环境光部分）
+//!
+//! 传感器有两个通道：CH0对可见光与红外都敏感，CH1主要对红外敏感。
+//! 按两者的比例选择数据手册中的换算系数，得到照度(lux)。
+
+use anyhow::Result;
+
+use crate::hal::AmbientLightSensor;
+use crate::peripherals::i2c_bus::I2cDevice;
+
+/// LTR-553固定I2C地址
+pub const LTR553_ADDRESS: u8 = 0x23;
+
+/// 器件ID寄存器的值（高4位为器件号，低4位为版本）
+const PART_ID: u8 = 0x92;
+
+/// 寄存器地址
+#[derive(Debug, Clone, Copy)]
+#[repr(u8)]
+enum Register {
+    /// 工作模式与增益
+    AlsControl = 0x80,
+    /// 积分时间与测量周期
+    AlsMeasRate = 0x85,
+    /// 器件ID
+    PartId = 0x86,
+    /// 测量数据，依次为CH1低、CH1高、CH0低、CH0高字节
+    AlsDataCh1Low = 0x88,
+}
+
+/// 激活环境光测量，增益1倍（量程1-64k lux）
+const ALS_CONTROL_ACTIVE: u8 = 0x01;
+/// 积分时间100ms，每500ms测量一次
+const ALS_MEAS_RATE_100MS_500MS: u8 = 0x03;
+
+/// 由两个通道的读数计算照度（增益1倍、积分时间100ms）
+///
+/// # 参数
+/// * `ch0` - 可见光+红外通道
+/// * `ch1` - 红外通道
+pub fn lux_from_channels(ch0: u16, ch1: u16) -> f32 {
+    let (ch0, ch1) = (ch0 as f32, ch1 as f32);
+    if ch0 + ch1 == 0.0 {
+        return 0.0;
+    }
+    let ratio = ch1 / (ch0 + ch1);
+    let lux = if ratio < 0.45 {
+        1.7743 * ch0 + 1.1059 * ch1
+    } else if ratio < 0.64 {
+        4.2785 * ch0 - 1.9548 * ch1
+    } else if ratio < 0.85 {
+        0.5926 * ch0 + 0.1185 * ch1
+    } else {
+        0.0
+    };
+    lux.max(0.0)
+}
+
+/// LTR-553驱动
+pub struct Ltr553 {
+    i2c: I2cDevice,
+}
+
+impl Ltr553 {
+    /// 校验器件ID并开始连续测量
+    ///
+    /// # 参数
+    /// * `i2c` - 共享总线上的设备句柄，地址为`LTR553_ADDRESS`
+    pub fn new(i2c: I2cDevice) -> Result<Self> {
+        let sensor = Self { i2c };
+        let part_id = sensor.read_register(Register::PartId)?;
+        if part_id != PART_ID {
+            anyhow::bail!("LTR-553器件ID不匹配: {:#04x}", part_id);
+        }
+        sensor.write_register(Register::AlsMeasRate, ALS_MEAS_RATE_100MS_500MS)?;
+        sensor.write_register(Register::AlsControl, ALS_CONTROL_ACTIVE)?;
+        log::info!("环境光传感器: LTR-553");
+        Ok(sensor)
+    }
+
+    fn read_register(&self, reg: Register) -> Result<u8> {
+        let mut value = [0u8];
+        self.i2c.write_read(&[reg as u8], &mut value)?;
+        Ok(value[0])
+    }
+
+    fn write_register(&self, reg: Register, value: u8) -> Result<()> {
+        self.i2c.write(&[reg as u8, value])
+    }
+}
+
+impl AmbientLightSensor for Ltr553 {
+    fn read_lux(&mut self) -> Result<f32> {
+        // 必须从CH1低字节开始连续读取4字节，传感器才会锁存同一次测量的数据
+        let mut data = [0u8; 4];
+        self.i2c
+            .write_read(&[Register::AlsDataCh1Low as u8], &mut data)?;
+        let ch1 = u16::from_le_bytes([data[0], data[1]]);
+        let ch0 = u16::from_le_bytes([data[2], data[3]]);
+        Ok(lux_from_channels(ch0, ch1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lux_from_channels() {
+        assert_eq!(lux_from_channels(0, 0), 0.0);
+        // 红外占比低（日光灯）
+        assert!((lux_from_channels(1000, 100) - 1884.89).abs() < 0.1);
+        // 红外占比极高时视为无可见光
+        assert_eq!(lux_from_channels(10, 1000), 0.0);
+    }
+}
@@ -0,0 +1,26 @@
+//! 环境光传感器
+//!
+//! 可选外设：启动时在共享I2C总线上探测，没有传感器时屏幕亮度保持设置值。
+//! 当前支持LTR-553ALS（I2C地址0x23）。
+
+pub mod ltr553;
+
+use crate::hal::AmbientLightSensor;
+use crate::peripherals::i2c_bus::SharedI2cBus;
+
+/// 探测总线上的环境光传感器
+///
+/// # 返回值
+/// 找到并初始化成功时返回传感器，否则返回None
+pub fn probe(bus: &SharedI2cBus) -> Option<Box<dyn AmbientLightSensor>> {
+    if !bus.probe(ltr553::LTR553_ADDRESS) {
+        return None;
+    }
+    match ltr553::Ltr553::new(bus.device(ltr553::LTR553_ADDRESS)) {
+        Ok(sensor) => Some(Box::new(sensor)),
+        Err(e) => {
+            log::warn!("环境光传感器初始化失败: {}", e);
+            None
+        }
+    }
+}
@@ -0,0 +1,105 @@
+// PWM背光
+//
+// 背光引脚由LEDC输出PWM，亮度按百分比设置。
+// 人眼对亮度的感知接近对数关系，占空比按平方曲线换算，低亮度区间调节更细。
+// 开关与亮度分开保存：关闭屏幕后再打开时恢复原来的亮度。
+
+use anyhow::Result;
+use esp_idf_hal::gpio::AnyOutputPin;
+use esp_idf_hal::ledc::{
+    config::TimerConfig, LedcDriver, LedcTimerDriver, Resolution, CHANNEL0, TIMER0,
+};
+use esp_idf_hal::units::FromValueType;
+
+/// PWM频率，高于可闻范围，避免背光驱动发出啸叫
+const PWM_FREQUENCY_KHZ: u32 = 25;
+
+/// 最低亮度，低于该值时部分面板的背光会闪烁
+pub const MIN_BRIGHTNESS: u8 = 5;
+/// 默认亮度
+pub const DEFAULT_BRIGHTNESS: u8 = 80;
+
+/// 亮度百分比换算为占空比
+///
+/// # 参数
+/// * `percent` - 亮度（0-100），大于0时不低于`MIN_BRIGHTNESS`
+/// * `max_duty` - 满占空比
+pub fn duty_for(percent: u8, max_duty: u32) -> u32 {
+    if percent == 0 {
+        return 0;
+    }
+    let level = percent.clamp(MIN_BRIGHTNESS, 100) as u64;
+    ((max_duty as u64 * level * level) / (100 * 100)).max(1) as u32
+}
+
+/// PWM背光
+pub struct Backlight {
+    driver: LedcDriver<'static>,
+    on: bool,
+    brightness: u8,
+}
+
+impl Backlight {
+    /// 初始化背光PWM，默认以`DEFAULT_BRIGHTNESS`点亮
+    ///
+    /// # 参数
+    /// * `timer` - LEDC定时器
+    /// * `channel` - LEDC通道
+    /// * `pin` - 背光控制引脚
+    pub fn new(timer: TIMER0, channel: CHANNEL0, pin: AnyOutputPin) -> Result<Self> {
+        let timer = LedcTimerDriver::new(
+            timer,
+            &TimerConfig::default()
+                .frequency(PWM_FREQUENCY_KHZ.kHz().into())
+                .resolution(Resolution::Bits10),
+        )?;
+        let driver = LedcDriver::new(channel, timer, pin)?;
+        let mut backlight = Self {
+            driver,
+            on: true,
+            brightness: DEFAULT_BRIGHTNESS,
+        };
+        backlight.apply()?;
+        Ok(backlight)
+    }
+
+    /// 打开或关闭背光，亮度设置保持不变
+    pub fn set_on(&mut self, on: bool) -> Result<()> {
+        self.on = on;
+        self.apply()
+    }
+
+    /// 设置亮度，背光关闭时在下次打开后生效
+    ///
+    /// # 参数
+    /// * `percent` - 亮度（0-100）
+    pub fn set_brightness(&mut self, percent: u8) -> Result<()> {
+        self.brightness = percent.min(100);
+        self.apply()
+    }
+
+    fn apply(&mut self) -> Result<()> {
+        let duty = if self.on {
+            duty_for(self.brightness, self.driver.get_max_duty())
+        } else {
+            0
+        };
+        self.driver.set_duty(duty)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duty_curve() {
+        assert_eq!(duty_for(0, 1023), 0);
+        assert_eq!(duty_for(100, 1023), 1023);
+        assert_eq!(duty_for(50, 1000), 250);
+        // 低于最低亮度时按最低亮度输出
+        assert_eq!(duty_for(1, 10000), duty_for(MIN_BRIGHTNESS, 10000));
+        assert!(duty_for(MIN_BRIGHTNESS, 1023) >= 1);
+    }
+}
@@ -0,0 +1,147 @@
+// 电池电压检测
+//
+// 电池电压经电阻分压后接到ADC引脚，使用ADC单次采样驱动读取，
+// 并用芯片出厂校准数据把原始读数换算成毫伏。
+
+use anyhow::Result;
+use esp_idf_sys::{
+    adc_atten_t_ADC_ATTEN_DB_12, adc_bitwidth_t_ADC_BITWIDTH_DEFAULT,
+    adc_cali_create_scheme_curve_fitting, adc_cali_curve_fitting_config_t,
+    adc_cali_delete_scheme_curve_fitting, adc_cali_handle_t, adc_cali_raw_to_voltage,
+    adc_channel_t, adc_oneshot_chan_cfg_t, adc_oneshot_config_channel, adc_oneshot_del_unit,
+    adc_oneshot_io_to_channel, adc_oneshot_new_unit, adc_oneshot_read, adc_oneshot_unit_handle_t,
+    adc_oneshot_unit_init_cfg_t, adc_unit_t, esp,
+};
+
+use crate::boards::BatterySense;
+
+/// 每次读取的采样次数，取平均值降低噪声
+const SAMPLES_PER_READ: u32 = 8;
+
+/// 锂电池电压与电量的对应关系（毫伏, 百分比），按电压降序排列
+const DISCHARGE_CURVE: [(u32, u8); 11] = [
+    (4200, 100),
+    (4100, 90),
+    (4000, 80),
+    (3900, 68),
+    (3850, 58),
+    (3800, 48),
+    (3750, 38),
+    (3700, 26),
+    (3600, 12),
+    (3500, 5),
+    (3300, 0),
+];
+
+/// 电池电量读数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatteryLevel {
+    /// 电池电压（毫伏）
+    pub millivolts: u32,
+    /// 估算电量（0-100）
+    pub percent: u8,
+}
+
+/// 电池电压检测器
+pub struct BatteryMonitor {
+    unit: adc_oneshot_unit_handle_t,
+    cali: adc_cali_handle_t,
+    channel: adc_channel_t,
+    divider: f32,
+}
+
+// ADC句柄只在持有者线程中使用
+unsafe impl Send for BatteryMonitor {}
+
+impl BatteryMonitor {
+    /// 按板子的电池检测配置初始化ADC
+    ///
+    /// # 参数
+    /// * `sense` - 电池检测引脚与分压比
+    pub fn new(sense: BatterySense) -> Result<Self> {
+        let mut unit_id: adc_unit_t = 0;
+        let mut channel: adc_channel_t = 0;
+        esp!(unsafe {
+            adc_oneshot_io_to_channel(sense.adc_gpio as i32, &mut unit_id, &mut channel)
+        })?;
+
+        let mut unit: adc_oneshot_unit_handle_t = std::ptr::null_mut();
+        let unit_config = adc_oneshot_unit_init_cfg_t {
+            unit_id,
+            ..Default::default()
+        };
+        esp!(unsafe { adc_oneshot_new_unit(&unit_config, &mut unit) })?;
+
+        let chan_config = adc_oneshot_chan_cfg_t {
+            atten: adc_atten_t_ADC_ATTEN_DB_12,
+            bitwidth: adc_bitwidth_t_ADC_BITWIDTH_DEFAULT,
+        };
+        let cali_config = adc_cali_curve_fitting_config_t {
+            unit_id,
+            chan: channel,
+            atten: adc_atten_t_ADC_ATTEN_DB_12,
+            bitwidth: adc_bitwidth_t_ADC_BITWIDTH_DEFAULT,
+        };
+        let mut cali: adc_cali_handle_t = std::ptr::null_mut();
+        let result = esp!(unsafe { adc_oneshot_config_channel(unit, channel, &chan_config) })
+            .and_then(|_| {
+                esp!(unsafe { adc_cali_create_scheme_curve_fitting(&cali_config, &mut cali) })
+            });
+        if let Err(e) = result {
+            unsafe { adc_oneshot_del_unit(unit) };
+            return Err(e.into());
+        }
+
+        log::info!("电池检测已启用: GPIO{}", sense.adc_gpio);
+        Ok(Self {
+            unit,
+            cali,
+            channel,
+            divider: sense.divider,
+        })
+    }
+
+    /// 读取电池电压并估算电量
+    pub fn read(&mut self) -> Result<BatteryLevel> {
+        let mut total = 0u32;
+        for _ in 0..SAMPLES_PER_READ {
+            let mut raw = 0i32;
+            let mut adc_mv = 0i32;
+            esp!(unsafe { adc_oneshot_read(self.unit, self.channel, &mut raw) })?;
+            esp!(unsafe { adc_cali_raw_to_voltage(self.cali, raw, &mut adc_mv) })?;
+            total += adc_mv.max(0) as u32;
+        }
+
+        let millivolts = (total as f32 / SAMPLES_PER_READ as f32 * self.divider) as u32;
+        Ok(BatteryLevel {
+            millivolts,
+            percent: percent_from_millivolts(millivolts),
+        })
+    }
+}
+
+impl Drop for BatteryMonitor {
+    fn drop(&mut self) {
+        unsafe {
+            adc_cali_delete_scheme_curve_fitting(self.cali);
+            adc_oneshot_del_unit(self.unit);
+        }
+    }
+}
+
+/// 按放电曲线把电池电压换算为电量百分比，曲线点之间线性插值
+pub fn percent_from_millivolts(millivolts: u32) -> u8 {
+    let (top_mv, top_percent) = DISCHARGE_CURVE[0];
+    if millivolts >= top_mv {
+        return top_percent;
+    }
+    for pair in DISCHARGE_CURVE.windows(2) {
+        let (high_mv, high_percent) = pair[0];
+        let (low_mv, low_percent) = pair[1];
+        if millivolts >= low_mv {
+            let span = (high_percent - low_percent) as u32;
+            return low_percent + ((millivolts - low_mv) * span / (high_mv - low_mv)) as u8;
+        }
+    }
+    0
+}
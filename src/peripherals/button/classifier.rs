@@ -0,0 +1,162 @@
+// 按键手势识别：把消抖后的按下/松开序列识别为单击、双击和长按
+
+use std::time::{Duration, Instant};
+
+/// 长按判定时间
+pub const LONG_PRESS_TIME: Duration = Duration::from_millis(800);
+
+/// 双击判定窗口：第一次松开后在此时间内再次松开视为双击
+pub const DOUBLE_CLICK_WINDOW: Duration = Duration::from_millis(300);
+
+/// 按键手势
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonGesture {
+    /// 按下（消抖后立即上报）
+    Press,
+    /// 松开（消抖后立即上报）
+    Release,
+    /// 单击，在双击窗口结束后上报
+    Click,
+    /// 双击
+    DoubleClick,
+    /// 长按，按住达到`LONG_PRESS_TIME`时上报，不等待松开
+    LongPress,
+}
+
+/// 单个按键的手势识别器
+#[derive(Debug, Default)]
+pub struct GestureClassifier {
+    /// 当前是否按下
+    pressed: bool,
+    /// 本次按下的时间
+    pressed_at: Option<Instant>,
+    /// 本次按下是否已经上报过长按
+    long_press_fired: bool,
+    /// 等待判定是否为双击的第一次松开时间
+    pending_click: Option<Instant>,
+}
+
+impl GestureClassifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 是否有未完成的识别（按住中或等待双击），需要继续调用`poll`
+    pub fn is_active(&self) -> bool {
+        self.pressed || self.pending_click.is_some()
+    }
+
+    /// 处理消抖后的电平变化
+    ///
+    /// # 参数
+    /// * `pressed` - 新的按键状态
+    /// * `now` - 当前时间
+    /// * `emit` - 手势回调
+    pub fn on_change(&mut self, pressed: bool, now: Instant, mut emit: impl FnMut(ButtonGesture)) {
+        if pressed == self.pressed {
+            return;
+        }
+        self.pressed = pressed;
+
+        if pressed {
+            self.pressed_at = Some(now);
+            self.long_press_fired = false;
+            emit(ButtonGesture::Press);
+            return;
+        }
+
+        emit(ButtonGesture::Release);
+        if self.long_press_fired {
+            return;
+        }
+        match self.pending_click.take() {
+            Some(first) if now.duration_since(first) <= DOUBLE_CLICK_WINDOW => {
+                emit(ButtonGesture::DoubleClick);
+            }
+            Some(_) => {
+                // 距离上次松开已超过双击窗口，补发上一次的单击
+                emit(ButtonGesture::Click);
+                self.pending_click = Some(now);
+            }
+            None => self.pending_click = Some(now),
+        }
+    }
+
+    /// 处理与时间相关的判定（长按、单击确认）
+    ///
+    /// 识别器处于活动状态时应周期性调用。
+    pub fn poll(&mut self, now: Instant, mut emit: impl FnMut(ButtonGesture)) {
+        if self.pressed && !self.long_press_fired {
+            if let Some(pressed_at) = self.pressed_at {
+                if now.duration_since(pressed_at) >= LONG_PRESS_TIME {
+                    // 长按前的单击先上报，保持事件顺序
+                    if self.pending_click.take().is_some() {
+                        emit(ButtonGesture::Click);
+                    }
+                    self.long_press_fired = true;
+                    emit(ButtonGesture::LongPress);
+                }
+            }
+        }
+
+        if !self.pressed {
+            if let Some(released_at) = self.pending_click {
+                if now.duration_since(released_at) > DOUBLE_CLICK_WINDOW {
+                    self.pending_click = None;
+                    emit(ButtonGesture::Click);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(steps: &[(u64, Option<bool>)]) -> Vec<ButtonGesture> {
+        let start = Instant::now();
+        let mut classifier = GestureClassifier::new();
+        let mut gestures = Vec::new();
+        for &(ms, change) in steps {
+            let now = start + Duration::from_millis(ms);
+            match change {
+                Some(pressed) => classifier.on_change(pressed, now, |g| gestures.push(g)),
+                None => classifier.poll(now, |g| gestures.push(g)),
+            }
+        }
+        gestures
+            .into_iter()
+            .filter(|g| !matches!(g, ButtonGesture::Press | ButtonGesture::Release))
+            .collect()
+    }
+
+    #[test]
+    fn test_click_double_click_and_long_press() {
+        use ButtonGesture::*;
+
+        assert_eq!(
+            run(&[(0, Some(true)), (100, Some(false)), (500, None)]),
+            [Click]
+        );
+        assert_eq!(
+            run(&[
+                (0, Some(true)),
+                (100, Some(false)),
+                (200, Some(true)),
+                (300, Some(false)),
+                (800, None),
+            ]),
+            [DoubleClick]
+        );
+        assert_eq!(
+            run(&[
+                (0, Some(true)),
+                (500, None),
+                (900, None),
+                (1200, Some(false))
+            ]),
+            [LongPress]
+        );
+    }
+}
@@ -0,0 +1,249 @@
+// 按键输入
+//
+// GPIO中断检测电平变化，按键线程在中断通知后延时消抖，再交给手势识别器
+// 识别单击、双击和长按，结果通过`UserInputEvent`发送到主事件总线。
+// 按下时直接在按键线程中打断扬声器播放，不等待主循环（播放期间主循环被阻塞）。
+
+pub mod classifier;
+
+use std::num::NonZeroU32;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use esp_idf_hal::delay::{TickType, BLOCK};
+use esp_idf_hal::gpio::{AnyIOPin, Input, InterruptType, PinDriver, Pull};
+use esp_idf_hal::task::notification::Notification;
+use log::{info, warn};
+
+use crate::actors::spawn;
+use crate::events::UserInputEvent;
+use crate::peripherals::speaker::i2s_speaker::PlaybackReference;
+
+use classifier::{ButtonGesture, GestureClassifier};
+
+/// 按键编号
+pub type ButtonId = u8;
+
+/// BOOT键（GPIO0），用作按键说话
+pub const BOOT_BUTTON: ButtonId = 0;
+
+/// 最多支持的按键数（每个按键占用通知值的一位）
+const MAX_BUTTONS: usize = 32;
+
+/// 电平变化后等待稳定的时间（软件消抖）
+const DEBOUNCE_TIME: Duration = Duration::from_millis(20);
+
+/// 有未完成识别时的轮询间隔
+const ACTIVE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// 按键配置
+pub struct ButtonConfig {
+    /// 按键编号，随事件一起上报
+    pub id: ButtonId,
+    /// 按键所在的GPIO
+    pub pin: AnyIOPin,
+    /// 是否低电平有效（按下时接地，使用内部上拉）
+    pub active_low: bool,
+}
+
+impl ButtonConfig {
+    /// 创建低电平有效的按键配置
+    pub fn active_low(id: ButtonId, pin: AnyIOPin) -> Self {
+        Self {
+            id,
+            pin,
+            active_low: true,
+        }
+    }
+}
+
+/// 单个按键的运行状态
+struct Button {
+    id: ButtonId,
+    driver: PinDriver<'static, AnyIOPin, Input>,
+    active_low: bool,
+    /// 消抖后的稳定状态
+    pressed: bool,
+    /// 最近一次电平变化中断的时间，消抖结束前不读取电平
+    settle_at: Option<Instant>,
+    classifier: GestureClassifier,
+}
+
+impl Button {
+    fn read_pressed(&self) -> bool {
+        self.driver.is_low() == self.active_low
+    }
+
+    fn is_active(&self) -> bool {
+        self.settle_at.is_some() || self.classifier.is_active()
+    }
+}
+
+/// 把手势转换为用户输入事件
+///
+/// 单击同时发送`Confirm`，长按同时发送`Back`，界面逻辑只需处理语义事件。
+fn publish(sender: &crate::events::EventSender, id: ButtonId, gesture: ButtonGesture) {
+    let events: &[UserInputEvent] = match gesture {
+        ButtonGesture::Press => &[UserInputEvent::ButtonPress(id)],
+        ButtonGesture::Release => &[UserInputEvent::ButtonRelease(id)],
+        ButtonGesture::Click => &[UserInputEvent::Click(id), UserInputEvent::Confirm],
+        ButtonGesture::DoubleClick => &[UserInputEvent::DoubleClick(id)],
+        ButtonGesture::LongPress => &[UserInputEvent::LongPress(id), UserInputEvent::Back],
+    };
+    for &event in events {
+        if let Err(e) = crate::events::send_input_event(sender, event) {
+            warn!("Failed to send input event: {}", e);
+        }
+    }
+}
+
+/// 按键Actor
+///
+/// 在独立线程中等待GPIO中断通知，没有按键活动时线程一直阻塞，不占用CPU。
+struct ButtonActor {
+    buttons: Vec<Button>,
+    notification: Notification,
+    app_event_sender: crate::events::EventSender,
+    /// 扬声器播放句柄，按下任意按键时打断播放
+    playback: Option<PlaybackReference>,
+}
+
+impl ButtonActor {
+    /// 初始化所有按键并注册中断
+    ///
+    /// 必须在按键线程中调用，中断通知会发送给创建`Notification`的任务。
+    fn new(
+        configs: Vec<ButtonConfig>,
+        app_event_sender: crate::events::EventSender,
+        playback: Option<PlaybackReference>,
+    ) -> Result<Self> {
+        if configs.len() > MAX_BUTTONS {
+            anyhow::bail!("按键数量超过上限: {}", configs.len());
+        }
+
+        let notification = Notification::new();
+        let mut buttons = Vec::with_capacity(configs.len());
+
+        for (index, config) in configs.into_iter().enumerate() {
+            let mut driver = PinDriver::input(config.pin)?;
+            driver.set_pull(if config.active_low {
+                Pull::Up
+            } else {
+                Pull::Down
+            })?;
+            driver.set_interrupt_type(InterruptType::AnyEdge)?;
+
+            let notifier = notification.notifier();
+            let bit = NonZeroU32::new(1 << index).unwrap();
+            // 中断回调中只发送任务通知，不做任何阻塞操作
+            unsafe {
+                driver.subscribe(move || {
+                    notifier.notify_and_yield(bit);
+                })?;
+            }
+            driver.enable_interrupt()?;
+
+            let mut button = Button {
+                id: config.id,
+                driver,
+                active_low: config.active_low,
+                pressed: false,
+                settle_at: None,
+                classifier: GestureClassifier::new(),
+            };
+            button.pressed = button.read_pressed();
+            buttons.push(button);
+        }
+
+        Ok(Self {
+            buttons,
+            notification,
+            app_event_sender,
+            playback,
+        })
+    }
+
+    fn run(&mut self) {
+        info!("Button actor started: {} buttons", self.buttons.len());
+
+        loop {
+            let timeout = if self.buttons.iter().any(Button::is_active) {
+                TickType::from(ACTIVE_POLL_INTERVAL).ticks()
+            } else {
+                BLOCK
+            };
+
+            let now = Instant::now();
+            if let Some(bits) = self.notification.wait(timeout) {
+                for (index, button) in self.buttons.iter_mut().enumerate() {
+                    if bits.get() & (1 << index) != 0 {
+                        button.settle_at = Some(now + DEBOUNCE_TIME);
+                        // 中断触发后会被自动关闭，需要重新使能
+                        if let Err(e) = button.driver.enable_interrupt() {
+                            warn!("Failed to re-enable button interrupt: {}", e);
+                        }
+                    }
+                }
+            }
+
+            let now = Instant::now();
+            for button in &mut self.buttons {
+                let id = button.id;
+                let sender = &self.app_event_sender;
+
+                if button.settle_at.is_some_and(|t| now >= t) {
+                    button.settle_at = None;
+                    let pressed = button.read_pressed();
+                    if pressed != button.pressed {
+                        button.pressed = pressed;
+                        if pressed {
+                            if let Some(playback) = &self.playback {
+                                playback.request_interrupt();
+                            }
+                        }
+                        button
+                            .classifier
+                            .on_change(pressed, now, |g| publish(sender, id, g));
+                    }
+                }
+
+                button.classifier.poll(now, |g| publish(sender, id, g));
+            }
+        }
+    }
+}
+
+/// 按键Actor管理器
+///
+/// 创建时启动后台线程，按键事件通过主事件总线发送。
+pub struct ButtonActorManager {}
+
+impl ButtonActorManager {
+    /// 启动按键线程
+    ///
+    /// # 参数
+    /// * `buttons` - 按键配置，所有权转移到后台线程
+    /// * `app_event_sender` - 应用事件发送器
+    /// * `playback` - 扬声器播放句柄，按键按下时打断播放
+    ///
+    /// # 示例
+    /// ```rust,no_run
+    /// let buttons = vec![ButtonConfig::active_low(BOOT_BUTTON, p.pins.gpio0.downgrade())];
+    /// let _button_actor =
+    ///     ButtonActorManager::new(buttons, event_sender.clone(), Some(speaker.reference()))?;
+    /// ```
+    pub fn new(
+        buttons: Vec<ButtonConfig>,
+        app_event_sender: crate::events::EventSender,
+        playback: Option<PlaybackReference>,
+    ) -> Result<Self> {
+        spawn::BUTTON.spawn(move || {
+            match ButtonActor::new(buttons, app_event_sender, playback) {
+                Ok(mut actor) => actor.run(),
+                Err(e) => warn!("Failed to create button actor: {}", e),
+            }
+        })?;
+
+        Ok(Self {})
+    }
+}
@@ -0,0 +1,301 @@
+use anyhow::Result;
+use esp_idf_hal::gpio::{AnyOutputPin, Output, Pin, PinDriver};
+use esp_idf_sys::gc9a01::esp_lcd_new_panel_gc9a01;
+use esp_idf_sys::*;
+use std::ptr;
+use std::sync::{Arc, Mutex};
+
+use crate::blocking::{self, LCD_INIT_BUDGET};
+use crate::hal::{BitmapSink, DisplayDevice, PanelReinit};
+use crate::peripherals::st77916::{
+    lcd::{on_color_trans_done, LcdBitmapPort, TransferState},
+    orientation::DisplayOrientation,
+};
+
+// ===================== 常量区 =====================
+// 面板物理分辨率
+pub const GC9A01_WIDTH: i32 = 240;
+pub const GC9A01_HEIGHT: i32 = 240;
+pub const GC9A01_BIT_PER_PIXEL: u8 = 16; // RGB565
+
+const GC9A01_HOST: spi_host_device_t = spi_host_device_t_SPI2_HOST;
+
+/// SPI时钟，GC9A01手册标称上限为80MHz
+const GC9A01_PCLK_HZ: u32 = 80 * 1000 * 1000;
+
+/// 单次SPI传输的最大行数，与显示线程的分块大小一致
+const MAX_TRANSFER_ROWS: i32 = 20;
+
+// =================================================
+
+/// GC9A01引脚
+pub struct Gc9a01Pins {
+    /// SPI时钟
+    pub sclk: AnyOutputPin,
+    /// SPI数据输出
+    pub mosi: AnyOutputPin,
+    /// 片选
+    pub cs: AnyOutputPin,
+    /// 数据/命令选择
+    pub dc: AnyOutputPin,
+    /// 复位，没有接出时为None（只发送软件复位命令）
+    pub reset: Option<AnyOutputPin>,
+    /// 背光
+    pub backlight: AnyOutputPin,
+}
+
+/// 面板初始化状态，控制器与显示线程的[`Gc9a01PanelReinit`]共享
+struct PanelSetup {
+    panel: esp_lcd_panel_handle_t,
+    orientation: DisplayOrientation,
+}
+
+// 面板句柄在Gc9a01Controller生命周期内有效，面板命令可以在任意任务中发送
+unsafe impl Send for PanelSetup {}
+
+impl PanelSetup {
+    /// 启动显示器
+    fn start_display(&self) -> Result<()> {
+        unsafe {
+            esp!(esp_lcd_panel_reset(self.panel))?;
+            esp!(esp_lcd_panel_init(self.panel))?;
+            // GC9A01模块的像素数据是反相的
+            esp!(esp_lcd_panel_invert_color(self.panel, true))?;
+        }
+
+        self.apply_orientation()?;
+
+        unsafe {
+            esp!(esp_lcd_panel_disp_on_off(self.panel, true))?;
+        }
+
+        Ok(())
+    }
+
+    /// 把当前显示方向写入面板
+    fn apply_orientation(&self) -> Result<()> {
+        let (swap_xy, mirror_x, mirror_y) = self.orientation.panel_transform();
+        unsafe {
+            esp!(esp_lcd_panel_swap_xy(self.panel, swap_xy))?;
+            esp!(esp_lcd_panel_mirror(self.panel, mirror_x, mirror_y))?;
+        }
+        Ok(())
+    }
+}
+
+/// 面板重新初始化句柄，交给显示线程使用
+pub struct Gc9a01PanelReinit {
+    port: LcdBitmapPort,
+    setup: Arc<Mutex<PanelSetup>>,
+}
+
+impl PanelReinit for Gc9a01PanelReinit {
+    fn reinit(&mut self) -> Result<()> {
+        self.port.drain_transfers();
+        let setup = self.setup.lock().unwrap();
+        blocking::with_budget("gc9a01_reinit", LCD_INIT_BUDGET, || setup.start_display())
+    }
+}
+
+pub struct Gc9a01Controller {
+    panel: esp_lcd_panel_handle_t,
+    io_handle: esp_lcd_panel_io_handle_t,
+    backlight: PinDriver<'static, AnyOutputPin, Output>,
+    orientation: DisplayOrientation,
+    /// 面板初始化状态，与显示线程共享
+    setup: Arc<Mutex<PanelSetup>>,
+    /// 位图传输端口，持有传输计数
+    port: LcdBitmapPort,
+}
+
+impl Gc9a01Controller {
+    /// 创建新的GC9A01控制器实例
+    ///
+    /// # 参数
+    /// * `pins` - 面板引脚
+    /// * `orientation` - 显示方向
+    pub fn new(pins: Gc9a01Pins, orientation: DisplayOrientation) -> Result<Self> {
+        // 步骤1：初始化SPI总线与面板IO
+        let transfers = TransferState::new()?;
+        let io_handle = Self::init_spi_bus(&pins, &transfers)?;
+
+        // 步骤2：创建LCD面板
+        let reset_gpio = pins.reset.as_ref().map_or(-1, |pin| pin.pin());
+        let panel = Self::create_panel(io_handle, reset_gpio)?;
+
+        // 步骤3：初始化背光控制
+        let mut backlight = PinDriver::output(pins.backlight)?;
+        backlight.set_high()?;
+
+        let controller = Self {
+            panel,
+            io_handle,
+            backlight,
+            orientation,
+            setup: Arc::new(Mutex::new(PanelSetup { panel, orientation })),
+            port: LcdBitmapPort::new(panel, transfers),
+        };
+
+        blocking::with_budget("gc9a01_start_display", LCD_INIT_BUDGET, || {
+            controller.setup.lock().unwrap().start_display()
+        })?;
+
+        Ok(controller)
+    }
+
+    /// 初始化四线SPI总线并创建面板IO
+    fn init_spi_bus(
+        pins: &Gc9a01Pins,
+        transfers: &Arc<TransferState>,
+    ) -> Result<esp_lcd_panel_io_handle_t> {
+        let bus_config = spi_bus_config_t {
+            sclk_io_num: pins.sclk.pin(),
+            __bindgen_anon_1: spi_bus_config_t__bindgen_ty_1 {
+                mosi_io_num: pins.mosi.pin(),
+            },
+            __bindgen_anon_2: spi_bus_config_t__bindgen_ty_2 { miso_io_num: -1 },
+            __bindgen_anon_3: spi_bus_config_t__bindgen_ty_3 { quadwp_io_num: -1 },
+            __bindgen_anon_4: spi_bus_config_t__bindgen_ty_4 { quadhd_io_num: -1 },
+            max_transfer_sz: GC9A01_WIDTH * MAX_TRANSFER_ROWS * 2,
+            ..Default::default()
+        };
+
+        unsafe {
+            esp!(spi_bus_initialize(
+                GC9A01_HOST,
+                &bus_config,
+                spi_common_dma_t_SPI_DMA_CH_AUTO
+            ))?;
+        }
+
+        let mut io_handle: esp_lcd_panel_io_handle_t = ptr::null_mut();
+        let io_config = esp_lcd_panel_io_spi_config_t {
+            cs_gpio_num: pins.cs.pin(),
+            dc_gpio_num: pins.dc.pin(),
+            spi_mode: 0,
+            pclk_hz: GC9A01_PCLK_HZ,
+            trans_queue_depth: 10,
+            on_color_trans_done: Some(on_color_trans_done),
+            user_ctx: transfers.callback_context(),
+            lcd_cmd_bits: 8,
+            lcd_param_bits: 8,
+            flags: esp_lcd_panel_io_spi_config_t__bindgen_ty_1::default(),
+        };
+
+        unsafe {
+            esp!(esp_lcd_new_panel_io_spi(
+                GC9A01_HOST as _,
+                &io_config,
+                &mut io_handle
+            ))?;
+        }
+
+        Ok(io_handle)
+    }
+
+    /// 创建LCD面板，使用组件自带的初始化序列
+    fn create_panel(
+        io_handle: esp_lcd_panel_io_handle_t,
+        reset_gpio: i32,
+    ) -> Result<esp_lcd_panel_handle_t> {
+        let mut panel: esp_lcd_panel_handle_t = ptr::null_mut();
+
+        let panel_config = esp_lcd_panel_dev_config_t {
+            reset_gpio_num: reset_gpio,
+            __bindgen_anon_1: esp_lcd_panel_dev_config_t__bindgen_ty_1 {
+                rgb_ele_order: lcd_rgb_element_order_t_LCD_RGB_ELEMENT_ORDER_BGR,
+            },
+            data_endian: lcd_rgb_data_endian_t_LCD_RGB_DATA_ENDIAN_BIG,
+            bits_per_pixel: GC9A01_BIT_PER_PIXEL as u32,
+            flags: esp_lcd_panel_dev_config_t__bindgen_ty_2::default(),
+            vendor_config: ptr::null_mut(),
+        };
+
+        unsafe {
+            esp!(esp_lcd_new_panel_gc9a01(
+                io_handle as *mut esp_idf_sys::gc9a01::esp_lcd_panel_io_t,
+                &panel_config as *const esp_lcd_panel_dev_config_t
+                    as *const esp_idf_sys::gc9a01::esp_lcd_panel_dev_config_t,
+                &mut panel as *mut esp_lcd_panel_handle_t
+                    as *mut *mut esp_idf_sys::gc9a01::esp_lcd_panel_t
+            ))?;
+        }
+
+        Ok(panel)
+    }
+
+    /// 设置背光状态
+    pub fn set_backlight(&mut self, on: bool) -> Result<()> {
+        if on {
+            self.backlight.set_high()?;
+        } else {
+            self.backlight.set_low()?;
+        }
+        Ok(())
+    }
+}
+
+impl DisplayDevice for Gc9a01Controller {
+    fn width(&self) -> i32 {
+        self.orientation.logical_size(GC9A01_WIDTH, GC9A01_HEIGHT).0
+    }
+
+    fn height(&self) -> i32 {
+        self.orientation.logical_size(GC9A01_WIDTH, GC9A01_HEIGHT).1
+    }
+
+    fn orientation(&self) -> DisplayOrientation {
+        self.orientation
+    }
+
+    fn set_orientation(&mut self, orientation: DisplayOrientation) -> Result<()> {
+        self.orientation = orientation;
+        let mut setup = self.setup.lock().unwrap();
+        setup.orientation = orientation;
+        setup.apply_orientation()
+    }
+
+    fn set_power(&mut self, on: bool) -> Result<()> {
+        if on {
+            unsafe { esp!(esp_lcd_panel_disp_on_off(self.panel, true))? };
+            self.set_backlight(true)
+        } else {
+            self.set_backlight(false)?;
+            unsafe { esp!(esp_lcd_panel_disp_on_off(self.panel, false))? };
+            Ok(())
+        }
+    }
+
+    fn set_brightness(&mut self, _percent: u8) -> Result<()> {
+        // 该模块的背光引脚只做开关控制，不支持调节亮度
+        Ok(())
+    }
+
+    fn panel_reinit(&self) -> Box<dyn PanelReinit> {
+        Box::new(Gc9a01PanelReinit {
+            port: self.port.clone(),
+            setup: self.setup.clone(),
+        })
+    }
+
+    fn bitmap_sink(&self) -> Box<dyn BitmapSink> {
+        Box::new(self.port.clone())
+    }
+}
+
+impl Drop for Gc9a01Controller {
+    fn drop(&mut self) {
+        // 回调上下文随控制器释放，删除面板前必须等待传输结束
+        let _ = self.port.wait_idle();
+
+        unsafe {
+            if !self.panel.is_null() {
+                esp_lcd_panel_del(self.panel);
+            }
+            if !self.io_handle.is_null() {
+                esp_lcd_panel_io_del(self.io_handle);
+            }
+            spi_bus_free(GC9A01_HOST);
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! GC9A01 圆形LCD驱动
+//!
+//! 1.28寸240x240圆屏，四线SPI（带DC引脚）。位图传输、DMA缓冲区与显示方向
+//! 复用`st77916`模块的实现，界面通过`hal::DisplayDevice`使用，与ST77916面板可以互换。
+
+pub mod lcd;
@@ -0,0 +1,131 @@
+//! 共享I2C总线
+//!
+//! IMU、IO扩展芯片、触摸屏等设备挂在同一条I2C总线上，
+//! 总线驱动放在互斥锁中，每个设备持有一个带地址的句柄，每次传输单独加锁。
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use esp_idf_hal::gpio::{InputPin, OutputPin};
+use esp_idf_hal::i2c::{I2c, I2cConfig, I2cDriver};
+use esp_idf_hal::peripheral::Peripheral;
+use esp_idf_hal::units::Hertz;
+use esp_idf_sys::EspError;
+
+use crate::hal::I2cTransport;
+use crate::metrics;
+
+/// 单次传输的超时（tick）
+const I2C_TIMEOUT: u32 = 1000;
+
+/// 共享I2C总线，可以克隆后交给不同线程
+#[derive(Clone)]
+pub struct SharedI2cBus {
+    driver: Arc<Mutex<I2cDriver<'static>>>,
+}
+
+impl SharedI2cBus {
+    /// 创建I2C总线
+    ///
+    /// # 参数
+    /// * `i2c` - I2C外设
+    /// * `sda` - 数据线引脚
+    /// * `scl` - 时钟线引脚
+    /// * `baudrate` - 总线频率
+    pub fn new<I: I2c>(
+        i2c: impl Peripheral<P = I> + 'static,
+        sda: impl Peripheral<P = impl InputPin + OutputPin> + 'static,
+        scl: impl Peripheral<P = impl InputPin + OutputPin> + 'static,
+        baudrate: Hertz,
+    ) -> Result<Self> {
+        let config = I2cConfig::new().baudrate(baudrate);
+        let driver = I2cDriver::new(i2c, sda, scl, &config)?;
+        Ok(Self {
+            driver: Arc::new(Mutex::new(driver)),
+        })
+    }
+
+    /// 取得指定地址设备的句柄
+    pub fn device(&self, address: u8) -> I2cDevice {
+        I2cDevice {
+            bus: self.clone(),
+            address,
+        }
+    }
+
+    /// 检测地址上是否有设备应答
+    ///
+    /// 没有应答是预期结果，不计入I2C错误。
+    pub fn probe(&self, address: u8) -> bool {
+        self.lock()
+            .map(|mut driver| driver.write(address, &[0x00], I2C_TIMEOUT).is_ok())
+            .unwrap_or(false)
+    }
+
+    /// 扫描总线，返回所有有应答的地址
+    pub fn scan(&self) -> Vec<u8> {
+        (0x08..=0x77)
+            .filter(|address| self.probe(*address))
+            .collect()
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, I2cDriver<'static>>> {
+        self.driver
+            .lock()
+            .map_err(|_| anyhow::anyhow!("I2C总线锁已损坏"))
+    }
+}
+
+/// 总线上某个设备的句柄
+#[derive(Clone)]
+pub struct I2cDevice {
+    bus: SharedI2cBus,
+    address: u8,
+}
+
+impl I2cDevice {
+    /// 设备地址
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// 写入数据
+    pub fn write(&self, bytes: &[u8]) -> Result<()> {
+        counted(self.bus.lock()?.write(self.address, bytes, I2C_TIMEOUT))?;
+        Ok(())
+    }
+
+    /// 读取数据
+    pub fn read(&self, buffer: &mut [u8]) -> Result<()> {
+        counted(self.bus.lock()?.read(self.address, buffer, I2C_TIMEOUT))?;
+        Ok(())
+    }
+
+    /// 先写后读（通常是写寄存器地址再读数据），期间不会被其他设备打断
+    pub fn write_read(&self, bytes: &[u8], buffer: &mut [u8]) -> Result<()> {
+        counted(
+            self.bus
+                .lock()?
+                .write_read(self.address, bytes, buffer, I2C_TIMEOUT),
+        )?;
+        Ok(())
+    }
+}
+
+impl I2cTransport for I2cDevice {
+    fn write(&self, bytes: &[u8]) -> Result<()> {
+        I2cDevice::write(self, bytes)
+    }
+
+    fn write_read(&self, bytes: &[u8], buffer: &mut [u8]) -> Result<()> {
+        I2cDevice::write_read(self, bytes, buffer)
+    }
+}
+
+/// 失败时计入I2C错误指标，结果原样返回
+fn counted(result: Result<(), EspError>) -> Result<(), EspError> {
+    if result.is_err() {
+        metrics::increment(metrics::I2C_ERRORS, 1);
+    }
+    result
+}
@@ -0,0 +1,119 @@
+// 环境声音检测：判断附近是否有人说话或活动，用于在无人时关闭屏幕
+//
+// 噪声底跟踪每块样本的均方根幅度：电平低于噪声底时快速下降，高于时缓慢上升，
+// 风扇、空调等持续的噪声会在几十秒内被吸收进噪声底，不会一直被当作有人。
+// 明显高于噪声底的块记为听到声音，由消费者线程写入、控制端定期取出。
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// 电平低于噪声底时的跟踪系数
+const FLOOR_FALL: f32 = 0.2;
+/// 电平高于噪声底时的跟踪系数（每块约32毫秒，时间常数约16秒）
+const FLOOR_RISE: f32 = 0.002;
+/// 超过噪声底该倍数（约6dB）才算听到声音
+const SOUND_MARGIN: f32 = 2.0;
+/// 听到声音的最低电平，安静环境中的细小波动不算
+const MIN_SOUND_RMS: u16 = 300;
+
+/// 噪声底跟踪
+#[derive(Debug, Clone, Default)]
+pub struct NoiseFloor {
+    /// 当前噪声底，尚无数据时为None
+    floor: Option<f32>,
+}
+
+impl NoiseFloor {
+    /// 当前噪声底
+    pub fn level(&self) -> Option<f32> {
+        self.floor
+    }
+
+    /// 输入一块样本的均方根幅度
+    ///
+    /// # 返回值
+    /// 这块明显高于噪声底时返回true
+    pub fn update(&mut self, rms: u16) -> bool {
+        let level = rms as f32;
+        let Some(floor) = self.floor else {
+            self.floor = Some(level);
+            return false;
+        };
+
+        let loud = rms >= MIN_SOUND_RMS && level > floor * SOUND_MARGIN;
+        let rate = if level < floor {
+            FLOOR_FALL
+        } else {
+            FLOOR_RISE
+        };
+        self.floor = Some(floor + (level - floor) * rate);
+        loud
+    }
+}
+
+/// 是否听到声音的标志，可在线程间共享
+#[derive(Clone, Default)]
+pub struct SoundActivity {
+    heard: Arc<AtomicBool>,
+}
+
+impl SoundActivity {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录听到声音，由消费者线程调用
+    pub fn report(&self) {
+        self.heard.store(true, Ordering::Relaxed);
+    }
+
+    /// 取出上次调用以来是否听到过声音并清除标志
+    pub fn take_heard(&self) -> bool {
+        self.heard.swap(false, Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speech_above_floor_is_heard() {
+        let mut floor = NoiseFloor::default();
+        assert!(!floor.update(100));
+        for _ in 0..50 {
+            assert!(!floor.update(120));
+        }
+        assert!(floor.update(2000));
+        // 安静环境中的小幅波动不算
+        assert!(!floor.update(250));
+    }
+
+    #[test]
+    fn test_steady_noise_is_absorbed() {
+        let mut floor = NoiseFloor::default();
+        floor.update(100);
+        // 风扇启动后的前几秒算作声音，之后被噪声底吸收
+        assert!(floor.update(1000));
+        let absorbed = (0..3000).position(|_| !floor.update(1000));
+        assert!(absorbed.is_some_and(|chunks| chunks < 1000));
+        assert!(floor.level().is_some_and(|level| level > 500.0));
+
+        // 噪声停止后噪声底很快回落
+        for _ in 0..30 {
+            floor.update(100);
+        }
+        assert!(floor.level().is_some_and(|level| level < 110.0));
+    }
+
+    #[test]
+    fn test_take_heard_clears_flag() {
+        let activity = SoundActivity::new();
+        assert!(!activity.take_heard());
+        activity.clone().report();
+        assert!(activity.take_heard());
+        assert!(!activity.take_heard());
+    }
+}
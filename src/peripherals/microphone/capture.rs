@@ -0,0 +1,102 @@
+// 独立的高优先级音频采集任务
+//
+// 采集线程只做一件事：从I2S读取样本写入环形缓冲区。
+// 上传、唤醒词检测等耗时处理在消费者线程中进行，网络卡顿时不会再导致I2S溢出。
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::thread::JoinHandle;
+
+use anyhow::Result;
+use log::{info, warn};
+
+use crate::actors::spawn;
+use crate::hal::AudioInput;
+
+use super::ring_buffer::{audio_ring_buffer, RingConsumer};
+
+/// 采集线程每次从I2S读取的样本数
+const CAPTURE_CHUNK_SAMPLES: usize = 256;
+
+/// 默认环形缓冲区容量（样本数），16kHz下约1秒
+pub const DEFAULT_CAPTURE_BUFFER_SAMPLES: usize = 16000;
+
+/// 音频采集任务
+///
+/// 持有采集线程，停止后可以取回麦克风实例。
+pub struct CaptureTask {
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<Box<dyn AudioInput>>>,
+}
+
+impl CaptureTask {
+    /// 启动采集任务
+    ///
+    /// # 参数
+    /// * `micphone` - 麦克风实例，所有权转移到采集线程
+    /// * `capacity` - 环形缓冲区容量（样本数）
+    ///
+    /// # 返回值
+    /// 返回采集任务与环形缓冲区消费者端
+    pub fn spawn(
+        mut micphone: Box<dyn AudioInput>,
+        capacity: usize,
+    ) -> Result<(Self, RingConsumer)> {
+        let (mut producer, consumer) = audio_ring_buffer(capacity)?;
+        let running = Arc::new(AtomicBool::new(true));
+        let thread_running = running.clone();
+
+        micphone.start_recording()?;
+
+        // 采集线程在核心1上以最高优先级运行，见`spawn::AUDIO_CAPTURE`
+        let handle = spawn::AUDIO_CAPTURE.spawn(move || {
+            let mut chunk = [0i16; CAPTURE_CHUNK_SAMPLES];
+            let mut reported_drops = 0;
+
+            while thread_running.load(Ordering::Relaxed) {
+                match micphone.read_samples(&mut chunk) {
+                    Ok(read) if read > 0 => {
+                        if producer.write(&chunk[..read]) < read {
+                            reported_drops += 1;
+                            // 每累计100次溢出报告一次，避免刷屏
+                            if reported_drops % 100 == 1 {
+                                warn!("采集缓冲区已满，消费者处理过慢");
+                            }
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("麦克风读取失败: {}", e),
+                }
+            }
+
+            let _ = micphone.stop_recording();
+            info!("音频采集任务已停止");
+            micphone
+        })?;
+
+        Ok((
+            Self {
+                running,
+                handle: Some(handle),
+            },
+            consumer,
+        ))
+    }
+
+    /// 停止采集任务并取回麦克风
+    pub fn stop(mut self) -> Result<Box<dyn AudioInput>> {
+        self.running.store(false, Ordering::Relaxed);
+        let handle = self.handle.take().expect("采集线程句柄已被取走");
+        handle
+            .join()
+            .map_err(|_| anyhow::anyhow!("音频采集线程异常退出"))
+    }
+}
+
+impl Drop for CaptureTask {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
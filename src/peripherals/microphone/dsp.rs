@@ -0,0 +1,327 @@
+// 麦克风音频预处理：去直流、数字增益、噪声门
+//
+// 处理顺序：去直流 → 增益（饱和截断）→ 噪声门
+
+use serde::{Deserialize, Serialize};
+
+/// 去直流高通滤波器系数，16kHz下截止频率约13Hz
+const DC_FILTER_POLE: f32 = 0.995;
+
+/// 音频预处理配置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DspConfig {
+    /// 是否去除直流偏置
+    pub dc_removal: bool,
+    /// 数字增益（线性倍数），1.0表示不放大
+    pub gain: f32,
+    /// 噪声门阈值（样本幅度），None表示不启用噪声门
+    pub noise_gate_threshold: Option<i16>,
+    /// 噪声门保持时间（样本数），信号低于阈值超过该时间才关门
+    pub noise_gate_hold_samples: u32,
+}
+
+impl Default for DspConfig {
+    fn default() -> Self {
+        Self {
+            dc_removal: true,
+            gain: 1.0,
+            noise_gate_threshold: None,
+            noise_gate_hold_samples: 1600, // 16kHz下100ms
+        }
+    }
+}
+
+impl DspConfig {
+    /// 不做任何处理的配置
+    pub fn passthrough() -> Self {
+        Self {
+            dc_removal: false,
+            gain: 1.0,
+            noise_gate_threshold: None,
+            noise_gate_hold_samples: 0,
+        }
+    }
+
+    /// 设置增益（分贝）
+    ///
+    /// # 参数
+    /// * `db` - 增益分贝数，例如6.0约为2倍
+    pub fn with_gain_db(mut self, db: f32) -> Self {
+        self.gain = 10f32.powf(db / 20.0);
+        self
+    }
+
+    /// 启用噪声门
+    ///
+    /// # 参数
+    /// * `threshold` - 阈值（样本幅度）
+    pub fn with_noise_gate(mut self, threshold: i16) -> Self {
+        self.noise_gate_threshold = Some(threshold);
+        self
+    }
+}
+
+/// 保存在设备配置中的麦克风预处理设置，创建麦克风时转换为`DspConfig`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MicDspSettings {
+    /// 数字增益（分贝），0表示不放大
+    pub gain_db: f32,
+    /// 噪声门阈值（样本幅度），None表示不启用噪声门
+    pub noise_gate_threshold: Option<i16>,
+}
+
+impl Default for MicDspSettings {
+    fn default() -> Self {
+        Self {
+            gain_db: 0.0,
+            noise_gate_threshold: None,
+        }
+    }
+}
+
+impl MicDspSettings {
+    /// 对应的预处理配置，去直流始终开启
+    pub fn dsp_config(&self) -> DspConfig {
+        let config = DspConfig::default().with_gain_db(self.gain_db);
+        match self.noise_gate_threshold {
+            Some(threshold) => config.with_noise_gate(threshold),
+            None => config,
+        }
+    }
+}
+
+/// 音频预处理器
+///
+/// 保存滤波器与噪声门的状态，需要对同一路音频流连续调用`process`。
+pub struct AudioProcessor {
+    config: DspConfig,
+    /// 去直流滤波器上一个输入
+    prev_input: f32,
+    /// 去直流滤波器上一个输出
+    prev_output: f32,
+    /// 噪声门包络
+    envelope: f32,
+    /// 连续低于阈值的样本数
+    below_threshold: u32,
+    /// 噪声门当前增益（0.0 ~ 1.0），平滑变化避免爆音
+    gate_gain: f32,
+}
+
+impl AudioProcessor {
+    /// 创建新的音频预处理器
+    pub fn new(config: DspConfig) -> Self {
+        Self {
+            config,
+            prev_input: 0.0,
+            prev_output: 0.0,
+            envelope: 0.0,
+            below_threshold: 0,
+            gate_gain: 1.0,
+        }
+    }
+
+    /// 获取当前配置
+    pub fn config(&self) -> &DspConfig {
+        &self.config
+    }
+
+    /// 更新配置并重置内部状态
+    pub fn set_config(&mut self, config: DspConfig) {
+        self.config = config;
+        self.reset();
+    }
+
+    /// 重置内部状态（例如开始新的录音时）
+    pub fn reset(&mut self) {
+        self.prev_input = 0.0;
+        self.prev_output = 0.0;
+        self.envelope = 0.0;
+        self.below_threshold = 0;
+        self.gate_gain = 1.0;
+    }
+
+    /// 原地处理一段音频样本
+    ///
+    /// # 参数
+    /// * `samples` - 16位PCM样本，处理结果直接写回
+    pub fn process(&mut self, samples: &mut [i16]) {
+        let config = self.config;
+        if config == DspConfig::passthrough() {
+            return;
+        }
+
+        for sample in samples.iter_mut() {
+            let mut value = *sample as f32;
+
+            if config.dc_removal {
+                let output = value - self.prev_input + DC_FILTER_POLE * self.prev_output;
+                self.prev_input = value;
+                self.prev_output = output;
+                value = output;
+            }
+
+            value *= config.gain;
+
+            if let Some(threshold) = config.noise_gate_threshold {
+                value *= self.update_gate(value.abs(), threshold as f32);
+            }
+
+            // 饱和截断，防止增益后溢出回绕
+            *sample = value.clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+        }
+    }
+
+    /// 更新噪声门状态，返回当前样本应乘的增益
+    fn update_gate(&mut self, level: f32, threshold: f32) -> f32 {
+        // 包络：快速上升，缓慢下降
+        if level > self.envelope {
+            self.envelope = level;
+        } else {
+            self.envelope *= 0.999;
+        }
+
+        if self.envelope >= threshold {
+            self.below_threshold = 0;
+            self.gate_gain = (self.gate_gain + 0.01).min(1.0);
+        } else {
+            self.below_threshold = self.below_threshold.saturating_add(1);
+            if self.below_threshold > self.config.noise_gate_hold_samples {
+                self.gate_gain = (self.gate_gain - 0.001).max(0.0);
+            }
+        }
+
+        self.gate_gain
+    }
+}
+
+/// 计算一段样本的均方根幅度，用于电平显示
+pub fn rms(samples: &[i16]) -> u16 {
+    if samples.is_empty() {
+        return 0;
+    }
+    let sum: f64 = samples.iter().map(|&s| (s as f64) * (s as f64)).sum();
+    (sum / samples.len() as f64).sqrt().min(i16::MAX as f64) as u16
+}
+
+/// 一个窗口内处理前后的电平
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RmsReport {
+    /// 原始麦克风信号的均方根幅度
+    pub raw: u16,
+    /// AFE处理后的均方根幅度
+    pub processed: u16,
+}
+
+impl RmsReport {
+    /// 处理后电平下降的分贝数，负值表示电平反而升高
+    pub fn reduction_db(&self) -> f32 {
+        20.0 * (self.raw.max(1) as f32 / self.processed.max(1) as f32).log10()
+    }
+}
+
+/// 降噪前后的电平对比
+///
+/// 分别累计原始与处理后样本的平方和，每满一个窗口给出一次结果，
+/// 用于在诊断信息中确认降噪在当前环境下是否有效。
+pub struct RmsComparison {
+    window_samples: usize,
+    count: usize,
+    raw_sum: f64,
+    processed_sum: f64,
+}
+
+impl RmsComparison {
+    /// # 参数
+    /// * `window_samples` - 每个窗口的样本数
+    pub fn new(window_samples: usize) -> Self {
+        Self {
+            window_samples: window_samples.max(1),
+            count: 0,
+            raw_sum: 0.0,
+            processed_sum: 0.0,
+        }
+    }
+
+    /// 写入同一段音频处理前后的样本
+    ///
+    /// # 返回值
+    /// 窗口已满时返回该窗口的电平并开始下一个窗口
+    pub fn feed(&mut self, raw: &[i16], processed: &[i16]) -> Option<RmsReport> {
+        let square = |&s: &i16| (s as f64) * (s as f64);
+        self.raw_sum += raw.iter().map(square).sum::<f64>();
+        self.processed_sum += processed.iter().map(square).sum::<f64>();
+        self.count += raw.len();
+        if self.count < self.window_samples {
+            return None;
+        }
+
+        let level = |sum: f64| (sum / self.count as f64).sqrt().min(i16::MAX as f64) as u16;
+        let report = RmsReport {
+            raw: level(self.raw_sum),
+            processed: level(self.processed_sum),
+        };
+        self.count = 0;
+        self.raw_sum = 0.0;
+        self.processed_sum = 0.0;
+        Some(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gain_saturates_instead_of_wrapping() {
+        let mut processor = AudioProcessor::new(DspConfig {
+            dc_removal: false,
+            gain: 4.0,
+            noise_gate_threshold: None,
+            noise_gate_hold_samples: 0,
+        });
+        let mut samples = [20000i16, -20000, 100];
+        processor.process(&mut samples);
+        assert_eq!(samples, [i16::MAX, i16::MIN, 400]);
+    }
+
+    #[test]
+    fn test_settings_to_dsp_config() {
+        assert_eq!(MicDspSettings::default().dsp_config(), DspConfig::default());
+
+        let config = MicDspSettings {
+            gain_db: 6.0,
+            noise_gate_threshold: Some(300),
+        }
+        .dsp_config();
+        assert!((config.gain - 2.0).abs() < 0.01);
+        assert_eq!(config.noise_gate_threshold, Some(300));
+        assert!(config.dc_removal);
+    }
+
+    #[test]
+    fn test_dc_offset_removed() {
+        let mut processor = AudioProcessor::new(DspConfig::default());
+        let mut samples = [1000i16; 4000];
+        processor.process(&mut samples);
+        assert!(samples[3999].abs() < 10);
+    }
+
+    #[test]
+    fn test_rms() {
+        assert_eq!(rms(&[]), 0);
+        assert_eq!(rms(&[1000, -1000, 1000, -1000]), 1000);
+        assert_eq!(rms(&[i16::MIN; 4]), i16::MAX as u16);
+    }
+
+    #[test]
+    fn test_rms_comparison_reports_per_window() {
+        let mut comparison = RmsComparison::new(8);
+        assert_eq!(comparison.feed(&[1000; 4], &[100; 4]), None);
+        let report = comparison.feed(&[-1000; 4], &[-100; 4]).unwrap();
+        assert_eq!(report.raw, 1000);
+        assert_eq!(report.processed, 100);
+        assert!((report.reduction_db() - 20.0).abs() < 0.01);
+        assert_eq!(comparison.feed(&[0; 4], &[0; 4]), None);
+    }
+}
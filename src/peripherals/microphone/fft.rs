@@ -0,0 +1,260 @@
+// 定点FFT：把一段音频换算为各频段的电平，用于频谱显示
+//
+// 样本按Q15处理，基2蝶形运算每一级右移1位防止溢出，结果整体缩小为1/N。
+// 旋转因子、Hann窗与位反转表在创建时计算一次，变换过程只用整数运算。
+// 幅度用|max| + 3/8·|min|近似，误差约±7%，对电平显示足够。
+
+/// 默认变换点数，16kHz下每个频点约62.5Hz、窗口长16ms
+pub const DEFAULT_FFT_SIZE: usize = 256;
+
+/// 满幅正弦波经Hann窗（相干增益1/2）与1/N缩放后在对应频点的幅度
+const FULL_SCALE_MAGNITUDE: f32 = i16::MAX as f32 / 4.0;
+
+/// 电平显示范围的下限（dBFS），低于该值显示为0
+const FLOOR_DB: f32 = -60.0;
+
+/// 频段划分的最低频率(Hz)，更低的频点只有直流偏置与工频干扰
+const MIN_BAND_HZ: f32 = 100.0;
+
+/// Q15定点FFT
+pub struct Fft {
+    size: usize,
+    /// 旋转因子exp(-2πik/N)的实部与虚部，k取0..N/2
+    twiddles: Vec<(i32, i32)>,
+    /// Hann窗系数（Q15）
+    window: Vec<i32>,
+    /// 位反转后的下标
+    bit_reverse: Vec<usize>,
+}
+
+impl Fft {
+    /// 创建FFT
+    ///
+    /// # 参数
+    /// * `size` - 变换点数，必须是2的幂且不小于4
+    pub fn new(size: usize) -> Self {
+        assert!(size >= 4 && size.is_power_of_two(), "FFT点数必须是2的幂");
+        let q15 = |value: f32| (value * i16::MAX as f32).round() as i32;
+
+        let twiddles = (0..size / 2)
+            .map(|k| {
+                let angle = -2.0 * std::f32::consts::PI * k as f32 / size as f32;
+                (q15(angle.cos()), q15(angle.sin()))
+            })
+            .collect();
+        let window = (0..size)
+            .map(|n| {
+                let phase = 2.0 * std::f32::consts::PI * n as f32 / (size - 1) as f32;
+                q15(0.5 - 0.5 * phase.cos())
+            })
+            .collect();
+        let bits = size.trailing_zeros();
+        let bit_reverse = (0..size)
+            .map(|index| index.reverse_bits() >> (usize::BITS - bits))
+            .collect();
+
+        Self {
+            size,
+            twiddles,
+            window,
+            bit_reverse,
+        }
+    }
+
+    /// 变换点数
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// 计算幅度谱
+    ///
+    /// # 参数
+    /// * `samples` - 最近的样本，不足`size`时前面补0，多出时只取最后`size`个
+    ///
+    /// # 返回值
+    /// 0到N/2-1各频点的幅度，第k个频点的频率为k·采样率/N
+    pub fn magnitudes(&self, samples: &[i16]) -> Vec<u16> {
+        let n = self.size;
+        let mut re = vec![0i32; n];
+        let mut im = vec![0i32; n];
+
+        let samples = &samples[samples.len().saturating_sub(n)..];
+        let offset = n - samples.len();
+        for (index, &sample) in samples.iter().enumerate() {
+            let position = offset + index;
+            re[self.bit_reverse[position]] = (sample as i32 * self.window[position]) >> 15;
+        }
+
+        let mut len = 2;
+        while len <= n {
+            let half = len / 2;
+            let step = n / len;
+            for start in (0..n).step_by(len) {
+                for k in 0..half {
+                    let (wr, wi) = self.twiddles[k * step];
+                    let (a, b) = (start + k, start + k + half);
+                    let tr = (wr * re[b] - wi * im[b]) >> 15;
+                    let ti = (wr * im[b] + wi * re[b]) >> 15;
+                    re[b] = (re[a] - tr) >> 1;
+                    im[b] = (im[a] - ti) >> 1;
+                    re[a] = (re[a] + tr) >> 1;
+                    im[a] = (im[a] + ti) >> 1;
+                }
+            }
+            len *= 2;
+        }
+
+        re.iter()
+            .zip(&im)
+            .take(n / 2)
+            .map(|(&re, &im)| {
+                let (re, im) = (re.unsigned_abs(), im.unsigned_abs());
+                let (max, min) = if re > im { (re, im) } else { (im, re) };
+                (max + min * 3 / 8).min(u16::MAX as u32) as u16
+            })
+            .collect()
+    }
+}
+
+/// 把幅度谱按对数频率分段，取每段的最大幅度换算为电平
+///
+/// 频段从`MIN_BAND_HZ`到奈奎斯特频率按对数等分，与人耳对音高的感知一致；
+/// 低频段窄于一个频点时与相邻频段共用同一个频点。
+///
+/// # 参数
+/// * `magnitudes` - `Fft::magnitudes`的结果
+/// * `bands` - 频段数
+/// * `sample_rate` - 采样率(Hz)
+///
+/// # 返回值
+/// 各频段的电平（0-100，按dB刻度），低频在前
+pub fn band_levels(magnitudes: &[u16], bands: usize, sample_rate: u32) -> Vec<u8> {
+    if magnitudes.len() < 2 || bands == 0 {
+        return vec![0; bands];
+    }
+    let bin_hz = sample_rate as f32 / (magnitudes.len() * 2) as f32;
+    let ratio = (sample_rate as f32 / 2.0 / MIN_BAND_HZ).ln() / bands as f32;
+    let bin_at = |band: usize| {
+        let hz = MIN_BAND_HZ * (ratio * band as f32).exp();
+        ((hz / bin_hz) as usize).clamp(1, magnitudes.len() - 1)
+    };
+
+    (0..bands)
+        .map(|band| {
+            let low = bin_at(band);
+            let high = bin_at(band + 1).max(low + 1).min(magnitudes.len());
+            let peak = magnitudes[low..high].iter().copied().max().unwrap_or(0);
+            level_for_magnitude(peak)
+        })
+        .collect()
+}
+
+/// 把频点幅度按dB刻度换算为0-100的电平
+fn level_for_magnitude(magnitude: u16) -> u8 {
+    if magnitude == 0 {
+        return 0;
+    }
+    let db = 20.0 * (magnitude as f32 / FULL_SCALE_MAGNITUDE).log10();
+    (((db - FLOOR_DB) / -FLOOR_DB).clamp(0.0, 1.0) * 100.0).round() as u8
+}
+
+/// 频谱分析器：保留最近`size`个样本，需要时计算各频段电平
+pub struct SpectrumAnalyzer {
+    fft: Fft,
+    recent: Vec<i16>,
+}
+
+impl SpectrumAnalyzer {
+    /// # 参数
+    /// * `size` - FFT点数，见`Fft::new`
+    pub fn new(size: usize) -> Self {
+        Self {
+            fft: Fft::new(size),
+            recent: Vec::with_capacity(size * 2),
+        }
+    }
+
+    /// 追加样本，只保留最近`size`个
+    pub fn push(&mut self, samples: &[i16]) {
+        self.recent.extend_from_slice(samples);
+        let excess = self.recent.len().saturating_sub(self.fft.size());
+        self.recent.drain(..excess);
+    }
+
+    /// 丢弃缓存的样本
+    pub fn clear(&mut self) {
+        self.recent.clear();
+    }
+
+    /// 最近样本的各频段电平，见`band_levels`
+    pub fn levels(&self, bands: usize, sample_rate: u32) -> Vec<u8> {
+        band_levels(&self.fft.magnitudes(&self.recent), bands, sample_rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(bin: usize, amplitude: f32, size: usize) -> Vec<i16> {
+        (0..size)
+            .map(|n| {
+                let phase = 2.0 * std::f32::consts::PI * (bin * n) as f32 / size as f32;
+                (amplitude * phase.sin()) as i16
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_silence_is_zero() {
+        let fft = Fft::new(64);
+        assert!(fft.magnitudes(&[0; 64]).iter().all(|&m| m == 0));
+        assert!(fft.magnitudes(&[]).iter().all(|&m| m == 0));
+    }
+
+    #[test]
+    fn test_sine_peaks_at_its_bin() {
+        let fft = Fft::new(DEFAULT_FFT_SIZE);
+        let magnitudes = fft.magnitudes(&sine(16, i16::MAX as f32, DEFAULT_FFT_SIZE));
+        assert_eq!(magnitudes.len(), DEFAULT_FFT_SIZE / 2);
+        let peak = (0..magnitudes.len())
+            .max_by_key(|&k| magnitudes[k])
+            .unwrap();
+        assert_eq!(peak, 16);
+        // 满幅正弦的峰值接近满刻度
+        let error = (magnitudes[16] as f32 - FULL_SCALE_MAGNITUDE).abs();
+        assert!(error < FULL_SCALE_MAGNITUDE * 0.1);
+        // 远离峰值的频点几乎没有能量
+        assert!(magnitudes[64] < 16);
+    }
+
+    #[test]
+    fn test_band_levels() {
+        let fft = Fft::new(DEFAULT_FFT_SIZE);
+        assert_eq!(band_levels(&fft.magnitudes(&[]), 8, 16000), vec![0; 8]);
+
+        // 16kHz下第32个频点为2kHz
+        let levels = band_levels(
+            &fft.magnitudes(&sine(32, 8000.0, DEFAULT_FFT_SIZE)),
+            8,
+            16000,
+        );
+        let loudest = (0..levels.len()).max_by_key(|&band| levels[band]).unwrap();
+        // 100Hz-8kHz对数等分为8段，2kHz落在第6段（下标5）
+        assert_eq!(loudest, 5);
+        // 8000约为-12dBFS
+        assert!(levels[loudest] > 70);
+        assert!(levels[0] < 20);
+    }
+
+    #[test]
+    fn test_analyzer_keeps_latest_samples() {
+        let mut analyzer = SpectrumAnalyzer::new(64);
+        analyzer.push(&sine(8, 10000.0, 64));
+        assert!(analyzer.levels(4, 16000).iter().any(|&level| level > 50));
+        analyzer.push(&[0; 64]);
+        assert_eq!(analyzer.levels(4, 16000), vec![0; 4]);
+        analyzer.clear();
+        assert_eq!(analyzer.levels(4, 16000), vec![0; 4]);
+    }
+}
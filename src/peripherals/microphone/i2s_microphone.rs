@@ -1,35 +1,43 @@
 use anyhow::Result;
-use esp_idf_hal::gpio::{Gpio15, Gpio2, Gpio39};
+use esp_idf_hal::gpio::{AnyIOPin, InputPin, OutputPin};
 use esp_idf_hal::i2s::{
     config::{
         Config, DataBitWidth, SlotMode, StdClkConfig, StdConfig, StdGpioConfig, StdSlotConfig,
     },
-    I2sDriver, I2sRx, I2S0,
+    I2s, I2sDriver, I2sRx,
 };
+use esp_idf_hal::peripheral::Peripheral;
+
+use super::dsp::{AudioProcessor, DspConfig};
+use crate::hal::AudioInput;
 
 pub struct I2sMicrophone {
     i2s_driver: I2sDriver<'static, I2sRx>,
     sample_rate: u32,
     is_recording: bool,
+    /// 音频预处理器，读取到的样本在返回前经过处理
+    processor: AudioProcessor,
 }
 
 impl I2sMicrophone {
     /// 创建新的I2S麦克风实例
     ///
     /// # 参数
-    /// * `i2s_peripheral` - I2S0外设实例
-    /// * `ws_pin` - 字时钟引脚(GPIO2)
-    /// * `sck_pin` - 串行时钟引脚(GPIO15)
-    /// * `sd_pin` - 串行数据引脚(GPIO39)
+    /// * `i2s_peripheral` - I2S外设实例
+    /// * `ws_pin` - 字时钟引脚
+    /// * `sck_pin` - 串行时钟引脚
+    /// * `sd_pin` - 串行数据引脚
+    ///
+    /// 引脚分配见`boards`模块。
     /// * `sample_rate` - 采样率(Hz)
     ///
     /// # 返回
     /// 返回配置好的I2S麦克风实例或错误
     pub fn new(
-        i2s_peripheral: I2S0,
-        ws_pin: Gpio2,
-        sck_pin: Gpio15,
-        sd_pin: Gpio39,
+        i2s_peripheral: impl Peripheral<P = impl I2s> + 'static,
+        ws_pin: impl Peripheral<P = impl InputPin + OutputPin> + 'static,
+        sck_pin: impl Peripheral<P = impl InputPin + OutputPin> + 'static,
+        sd_pin: impl Peripheral<P = impl InputPin> + 'static,
         sample_rate: u32,
     ) -> Result<Self> {
         let std_cfg = StdConfig::new(
@@ -42,17 +50,25 @@ impl I2sMicrophone {
         let driver = I2sDriver::new_std_rx(
             i2s_peripheral,
             &std_cfg,
-            sck_pin,       // 串行时钟
-            sd_pin,        // 串行数据
-            None::<Gpio2>, // mclk (主时钟，通常不需要)
-            ws_pin,        // 字选择/帧同步
+            sck_pin,          // 串行时钟
+            sd_pin,           // 串行数据
+            None::<AnyIOPin>, // mclk (主时钟，通常不需要)
+            ws_pin,           // 字选择/帧同步
         )?;
 
-        Ok(Self {
+        Ok(Self::from_driver(driver, sample_rate))
+    }
+
+    /// 用已配置好的接收驱动创建麦克风
+    ///
+    /// 驱动输出16位单声道PCM即可，标准模式与PDM模式共用之后的读取和预处理。
+    pub(super) fn from_driver(driver: I2sDriver<'static, I2sRx>, sample_rate: u32) -> Self {
+        Self {
             i2s_driver: driver,
             sample_rate,
             is_recording: false,
-        })
+            processor: AudioProcessor::new(DspConfig::default()),
+        }
     }
 
     /// 获取当前采样率
@@ -68,6 +84,7 @@ impl I2sMicrophone {
     /// 启用I2S通道并设置麦克风为录音状态
     pub fn start_recording(&mut self) -> Result<()> {
         self.i2s_driver.rx_enable()?;
+        self.processor.reset();
         self.is_recording = true;
         Ok(())
     }
@@ -104,6 +121,19 @@ impl I2sMicrophone {
         Ok(())
     }
 
+    /// 设置音频预处理配置
+    ///
+    /// # 参数
+    /// * `config` - 预处理配置，使用`DspConfig::passthrough()`可关闭所有处理
+    pub fn set_dsp_config(&mut self, config: DspConfig) {
+        self.processor.set_config(config);
+    }
+
+    /// 获取当前音频预处理配置
+    pub fn dsp_config(&self) -> &DspConfig {
+        self.processor.config()
+    }
+
     /// 读取音频样本数据
     ///
     /// # 参数
@@ -116,26 +146,18 @@ impl I2sMicrophone {
             anyhow::bail!("麦克风未在录音状态");
         }
 
-        // 将i16缓冲区转换为u8缓冲区进行I2S读取
-        let byte_len = buffer.len() * 2; // 每个i16样本需要2个字节
-        let mut byte_buffer = vec![0u8; byte_len];
+        // 直接读入i16缓冲区（I2S数据为小端序，与ESP32字节序一致），不再每次分配临时Vec
+        let byte_buffer: &mut [u8] = bytemuck::cast_slice_mut(buffer);
 
         // 从I2S驱动读取原始字节数据，使用超时
         let timeout = esp_idf_hal::delay::TickType::new_millis(100);
-        let bytes_read = self.i2s_driver.read(&mut byte_buffer, timeout.into())?;
+        let bytes_read = self.i2s_driver.read(byte_buffer, timeout.into())?;
 
         // 将读取的字节数转换为样本数
-        let samples_read = bytes_read / 2;
-
-        // 将字节数据转换为i16样本（小端序）
-        for i in 0..samples_read.min(buffer.len()) {
-            let byte_idx = i * 2;
-            if byte_idx + 1 < byte_buffer.len() {
-                buffer[i] = i16::from_le_bytes([byte_buffer[byte_idx], byte_buffer[byte_idx + 1]]);
-            }
-        }
+        let samples_read = (bytes_read / 2).min(buffer.len());
+        self.processor.process(&mut buffer[..samples_read]);
 
-        Ok(samples_read.min(buffer.len()))
+        Ok(samples_read)
     }
 
     /// 录制指定时长的音频，返回包含音频数据的缓冲区
@@ -229,6 +251,20 @@ impl I2sMicrophone {
     }
 }
 
+impl AudioInput for I2sMicrophone {
+    fn start_recording(&mut self) -> Result<()> {
+        I2sMicrophone::start_recording(self)
+    }
+
+    fn stop_recording(&mut self) -> Result<()> {
+        I2sMicrophone::stop_recording(self)
+    }
+
+    fn read_samples(&mut self, buffer: &mut [i16]) -> Result<usize> {
+        I2sMicrophone::read_samples(self, buffer)
+    }
+}
+
 impl Drop for I2sMicrophone {
     fn drop(&mut self) {
         let _ = self.stop_recording();
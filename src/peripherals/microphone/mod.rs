@@ -1 +1,11 @@
+pub mod activity;
+pub mod capture;
+pub mod dsp;
+pub mod fft;
 pub mod i2s_microphone;
+pub mod pdm_microphone;
+pub mod preroll;
+pub mod recorder;
+pub mod ring_buffer;
+pub mod tap;
+pub mod utterance;
@@ -0,0 +1,77 @@
+use anyhow::Result;
+use esp_idf_hal::gpio::{InputPin, OutputPin};
+use esp_idf_hal::i2s::{
+    config::{
+        Config, DataBitWidth, PdmRxClkConfig, PdmRxConfig, PdmRxGpioConfig, PdmRxSlotConfig,
+        SlotMode,
+    },
+    I2s, I2sDriver,
+};
+use esp_idf_hal::peripheral::Peripheral;
+
+use super::dsp::DspConfig;
+use super::i2s_microphone::I2sMicrophone;
+use crate::hal::AudioInput;
+
+/// PDM麦克风
+///
+/// 使用I2S外设的PDM接收模式，由硬件把PDM比特流抽取为16位PCM。
+/// 读出的样本与标准I2S麦克风格式相同，读取与预处理复用`I2sMicrophone`，
+/// 交给采集任务后的环形缓冲区、上传等流程完全一致。
+pub struct PdmMicrophone {
+    inner: I2sMicrophone,
+}
+
+impl PdmMicrophone {
+    /// 创建新的PDM麦克风实例
+    ///
+    /// # 参数
+    /// * `i2s_peripheral` - I2S外设实例，ESP32-S3只有I2S0支持PDM接收
+    /// * `clk_pin` - PDM时钟输出引脚
+    /// * `din_pin` - PDM数据输入引脚
+    /// * `sample_rate` - 抽取后的采样率(Hz)
+    ///
+    /// # 返回
+    /// 返回配置好的PDM麦克风实例或错误
+    pub fn new(
+        i2s_peripheral: impl Peripheral<P = impl I2s> + 'static,
+        clk_pin: impl Peripheral<P = impl OutputPin> + 'static,
+        din_pin: impl Peripheral<P = impl InputPin> + 'static,
+        sample_rate: u32,
+    ) -> Result<Self> {
+        let pdm_cfg = PdmRxConfig::new(
+            Config::new().auto_clear(true),
+            PdmRxClkConfig::from_sample_rate_hz(sample_rate),
+            PdmRxSlotConfig::from_bits_per_sample_and_slot_mode(
+                DataBitWidth::Bits16,
+                SlotMode::Mono,
+            ),
+            PdmRxGpioConfig::new(false), // 时钟不反相
+        );
+
+        let driver = I2sDriver::new_pdm_rx(i2s_peripheral, &pdm_cfg, clk_pin, din_pin)?;
+
+        Ok(Self {
+            inner: I2sMicrophone::from_driver(driver, sample_rate),
+        })
+    }
+
+    /// 设置音频预处理配置，见`I2sMicrophone::set_dsp_config`
+    pub fn set_dsp_config(&mut self, config: DspConfig) {
+        self.inner.set_dsp_config(config);
+    }
+}
+
+impl AudioInput for PdmMicrophone {
+    fn start_recording(&mut self) -> Result<()> {
+        self.inner.start_recording()
+    }
+
+    fn stop_recording(&mut self) -> Result<()> {
+        self.inner.stop_recording()
+    }
+
+    fn read_samples(&mut self, buffer: &mut [i16]) -> Result<usize> {
+        self.inner.read_samples(buffer)
+    }
+}
@@ -0,0 +1,84 @@
+// 唤醒词预录缓冲：持续保存最近一段麦克风数据
+//
+// 唤醒词在说完之后才会被检测到，再经过事件传递和提示音，开始收集语音时
+// 用户往往已经说出了指令的前几个字。检测到唤醒词时把预录的数据交给语音缓冲，
+// 上传的语音就能包含这部分内容。
+//
+// 缓冲区大于CONFIG_SPIRAM_MALLOC_ALWAYSINTERNAL，由分配器放在PSRAM中，不占用内部RAM；
+// 每块样本只做一次拷贝，不影响唤醒词检测的实时性。
+
+/// 固定容量的滚动缓冲区，写满后覆盖最早的样本
+pub struct PreRoll {
+    samples: Vec<i16>,
+    /// 下一个写入位置
+    write: usize,
+    /// 是否已经写满过一轮
+    wrapped: bool,
+}
+
+impl PreRoll {
+    /// # 参数
+    /// * `capacity` - 保存的样本数
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: vec![0; capacity.max(1)],
+            write: 0,
+            wrapped: false,
+        }
+    }
+
+    /// 写入样本，超过容量的部分覆盖最早的数据
+    pub fn push(&mut self, samples: &[i16]) {
+        let capacity = self.samples.len();
+        // 一次写入超过容量时只有最后capacity个样本有意义
+        let samples = &samples[samples.len().saturating_sub(capacity)..];
+        let first = samples.len().min(capacity - self.write);
+        self.samples[self.write..self.write + first].copy_from_slice(&samples[..first]);
+        self.samples[..samples.len() - first].copy_from_slice(&samples[first..]);
+
+        let end = self.write + samples.len();
+        self.wrapped |= end >= capacity;
+        self.write = end % capacity;
+    }
+
+    /// 按时间顺序取出保存的样本
+    pub fn snapshot(&self) -> Vec<i16> {
+        if !self.wrapped {
+            return self.samples[..self.write].to_vec();
+        }
+        let mut samples = Vec::with_capacity(self.samples.len());
+        samples.extend_from_slice(&self.samples[self.write..]);
+        samples.extend_from_slice(&self.samples[..self.write]);
+        samples
+    }
+
+    /// 丢弃保存的样本
+    pub fn clear(&mut self) {
+        self.write = 0;
+        self.wrapped = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_most_recent_samples_in_order() {
+        let mut pre_roll = PreRoll::new(5);
+        assert!(pre_roll.snapshot().is_empty());
+
+        pre_roll.push(&[1, 2, 3]);
+        assert_eq!(pre_roll.snapshot(), vec![1, 2, 3]);
+        pre_roll.push(&[4, 5, 6, 7]);
+        assert_eq!(pre_roll.snapshot(), vec![3, 4, 5, 6, 7]);
+
+        // 一次写入超过容量
+        pre_roll.push(&[10, 11, 12, 13, 14, 15, 16]);
+        assert_eq!(pre_roll.snapshot(), vec![12, 13, 14, 15, 16]);
+
+        pre_roll.clear();
+        pre_roll.push(&[8]);
+        assert_eq!(pre_roll.snapshot(), vec![8]);
+    }
+}
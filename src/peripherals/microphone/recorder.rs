@@ -0,0 +1,208 @@
+// WAV录音：把采集到的PCM数据写入SPIFFS或SD卡上的WAV文件
+//
+// 用于在没有服务器的情况下检查麦克风接线与音量。
+
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use log::{info, warn};
+
+/// WAV文件头长度（字节）
+const WAV_HEADER_LEN: u32 = 44;
+
+/// 16位单声道PCM的WAV写入器
+///
+/// 创建时先写入数据长度为0的文件头，`finish`时回填实际长度。
+pub struct WavWriter<W: Write + Seek> {
+    writer: W,
+    sample_rate: u32,
+    data_bytes: u32,
+}
+
+impl<W: Write + Seek> WavWriter<W> {
+    /// 创建WAV写入器并写入文件头
+    ///
+    /// # 参数
+    /// * `writer` - 输出目标
+    /// * `sample_rate` - 采样率(Hz)
+    pub fn new(mut writer: W, sample_rate: u32) -> Result<Self> {
+        write_header(&mut writer, sample_rate, 0)?;
+        Ok(Self {
+            writer,
+            sample_rate,
+            data_bytes: 0,
+        })
+    }
+
+    /// 写入样本
+    pub fn write_samples(&mut self, samples: &[i16]) -> Result<()> {
+        for sample in samples {
+            self.writer.write_all(&sample.to_le_bytes())?;
+        }
+        self.data_bytes += (samples.len() * 2) as u32;
+        Ok(())
+    }
+
+    /// 已写入的样本数
+    pub fn samples_written(&self) -> u32 {
+        self.data_bytes / 2
+    }
+
+    /// 回填文件头中的长度字段并返回底层写入器
+    pub fn finish(mut self) -> Result<W> {
+        self.writer.seek(SeekFrom::Start(0))?;
+        write_header(&mut self.writer, self.sample_rate, self.data_bytes)?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// 写入44字节的标准WAV文件头（PCM，单声道，16位）
+fn write_header<W: Write>(writer: &mut W, sample_rate: u32, data_bytes: u32) -> Result<()> {
+    let channels: u16 = 1;
+    let bits_per_sample: u16 = 16;
+    let block_align = channels * bits_per_sample / 8;
+    let byte_rate = sample_rate * block_align as u32;
+
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&(WAV_HEADER_LEN - 8 + data_bytes).to_le_bytes())?;
+    writer.write_all(b"WAVE")?;
+
+    writer.write_all(b"fmt ")?;
+    writer.write_all(&16u32.to_le_bytes())?; // fmt块长度
+    writer.write_all(&1u16.to_le_bytes())?; // PCM格式
+    writer.write_all(&channels.to_le_bytes())?;
+    writer.write_all(&sample_rate.to_le_bytes())?;
+    writer.write_all(&byte_rate.to_le_bytes())?;
+    writer.write_all(&block_align.to_le_bytes())?;
+    writer.write_all(&bits_per_sample.to_le_bytes())?;
+
+    writer.write_all(b"data")?;
+    writer.write_all(&data_bytes.to_le_bytes())?;
+    Ok(())
+}
+
+/// 正在进行的录音
+struct ActiveRecording {
+    path: String,
+    writer: WavWriter<BufWriter<File>>,
+    max_samples: u32,
+}
+
+/// WAV调试录音器
+///
+/// 可在多个线程间共享：控制端（设置界面、HTTP接口）调用`start`/`stop`，
+/// 采集数据的消费者线程调用`feed`写入样本。未在录音时`feed`几乎没有开销。
+#[derive(Clone, Default)]
+pub struct AudioRecorder {
+    active: Arc<Mutex<Option<ActiveRecording>>>,
+}
+
+impl AudioRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 开始录音
+    ///
+    /// # 参数
+    /// * `path` - WAV文件路径，例如`/spiffs/debug.wav`或`/sdcard/debug.wav`
+    /// * `sample_rate` - 采样率(Hz)
+    /// * `max_seconds` - 最长录音时间，到达后自动结束
+    pub fn start(&self, path: &str, sample_rate: u32, max_seconds: u32) -> Result<()> {
+        let mut active = self
+            .active
+            .lock()
+            .map_err(|_| anyhow::anyhow!("录音器锁已损坏"))?;
+        if active.is_some() {
+            anyhow::bail!("已经在录音中");
+        }
+
+        let file = File::create(path)?;
+        let writer = WavWriter::new(BufWriter::new(file), sample_rate)?;
+        *active = Some(ActiveRecording {
+            path: path.to_string(),
+            writer,
+            max_samples: sample_rate * max_seconds,
+        });
+
+        info!("开始录音: {} ({}秒)", path, max_seconds);
+        Ok(())
+    }
+
+    /// 结束录音并写入文件头
+    ///
+    /// # 返回值
+    /// 返回录音文件路径，没有进行中的录音时返回None
+    pub fn stop(&self) -> Result<Option<String>> {
+        let recording = match self.active.lock() {
+            Ok(mut active) => active.take(),
+            Err(_) => anyhow::bail!("录音器锁已损坏"),
+        };
+
+        match recording {
+            Some(recording) => {
+                let samples = recording.writer.samples_written();
+                recording.writer.finish()?;
+                info!("录音结束: {} ({} 样本)", recording.path, samples);
+                Ok(Some(recording.path))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// 是否正在录音
+    pub fn is_recording(&self) -> bool {
+        self.active
+            .lock()
+            .map(|active| active.is_some())
+            .unwrap_or(false)
+    }
+
+    /// 写入采集到的样本，到达最长时间后自动结束录音
+    pub fn feed(&self, samples: &[i16]) {
+        let reached_limit = {
+            let Ok(mut active) = self.active.lock() else {
+                return;
+            };
+            let Some(recording) = active.as_mut() else {
+                return;
+            };
+
+            if let Err(e) = recording.writer.write_samples(samples) {
+                warn!("写入录音文件失败: {}", e);
+                true
+            } else {
+                recording.writer.samples_written() >= recording.max_samples
+            }
+        };
+
+        if reached_limit {
+            if let Err(e) = self.stop() {
+                warn!("结束录音失败: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_wav_header_sizes_patched_on_finish() {
+        let mut writer = WavWriter::new(Cursor::new(Vec::new()), 16000).unwrap();
+        writer.write_samples(&[1, -1, 2]).unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+
+        assert_eq!(bytes.len(), 44 + 6);
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes(bytes[4..8].try_into().unwrap()), 36 + 6);
+        assert_eq!(u32::from_le_bytes(bytes[24..28].try_into().unwrap()), 16000);
+        assert_eq!(u32::from_le_bytes(bytes[40..44].try_into().unwrap()), 6);
+    }
+}
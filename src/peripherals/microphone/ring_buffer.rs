@@ -0,0 +1,178 @@
+// 单生产者单消费者（SPSC）无锁环形缓冲区
+//
+// 采集线程作为唯一的生产者写入，处理线程作为唯一的消费者读取，
+// 读写索引通过原子变量同步，不需要加锁，采集线程不会因为消费者阻塞而错过I2S数据。
+
+use std::sync::{
+    atomic::{AtomicU32, AtomicUsize, Ordering},
+    Arc,
+};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use esp_idf_sys::{heap_caps_calloc, heap_caps_free, MALLOC_CAP_8BIT, MALLOC_CAP_INTERNAL};
+
+/// 环形缓冲区共享部分
+struct Inner {
+    /// 样本存储，位于内部RAM
+    data: *mut i16,
+    capacity: usize,
+    /// 下一个写入位置（只由生产者修改）
+    head: AtomicUsize,
+    /// 下一个读取位置（只由消费者修改）
+    tail: AtomicUsize,
+    /// 缓冲区已满时被丢弃的样本数
+    dropped: AtomicU32,
+}
+
+// 生产者只写head之前的空闲区域，消费者只读tail到head之间的数据，两者不会同时访问同一位置。
+// 读写方法都要求`&mut self`，两端又都不能克隆，因此同一时刻只有一个写入者和一个读取者
+unsafe impl Send for Inner {}
+unsafe impl Sync for Inner {}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        unsafe { heap_caps_free(self.data as *mut _) };
+    }
+}
+
+impl Inner {
+    fn available_read(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire);
+        let tail = self.tail.load(Ordering::Acquire);
+        (head + self.capacity - tail) % self.capacity
+    }
+}
+
+/// 创建SPSC环形缓冲区
+///
+/// # 参数
+/// * `capacity` - 缓冲区容量（样本数），实际可用容量为`capacity - 1`
+///
+/// # 返回值
+/// 返回生产者与消费者两端
+pub fn audio_ring_buffer(capacity: usize) -> Result<(RingProducer, RingConsumer)> {
+    if capacity < 2 {
+        anyhow::bail!("环形缓冲区容量至少为2");
+    }
+
+    // 强制放在内部RAM，避免PSRAM访问延迟影响采集
+    let data = unsafe {
+        heap_caps_calloc(
+            capacity,
+            std::mem::size_of::<i16>(),
+            MALLOC_CAP_INTERNAL | MALLOC_CAP_8BIT,
+        )
+    } as *mut i16;
+    if data.is_null() {
+        anyhow::bail!("环形缓冲区内存分配失败: {} 样本", capacity);
+    }
+
+    let inner = Arc::new(Inner {
+        data,
+        capacity,
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+        dropped: AtomicU32::new(0),
+    });
+
+    Ok((
+        RingProducer {
+            inner: inner.clone(),
+        },
+        RingConsumer { inner },
+    ))
+}
+
+/// 环形缓冲区生产者端（采集线程持有）
+pub struct RingProducer {
+    inner: Arc<Inner>,
+}
+
+impl RingProducer {
+    /// 写入样本
+    ///
+    /// 空间不足时丢弃放不下的样本并计入丢弃计数，不会阻塞。
+    ///
+    /// # 返回值
+    /// 实际写入的样本数
+    pub fn write(&mut self, samples: &[i16]) -> usize {
+        let inner = &*self.inner;
+        let free = inner.capacity - 1 - inner.available_read();
+        let count = samples.len().min(free);
+
+        let mut head = inner.head.load(Ordering::Relaxed);
+        for &sample in &samples[..count] {
+            unsafe { *inner.data.add(head) = sample };
+            head = (head + 1) % inner.capacity;
+        }
+        inner.head.store(head, Ordering::Release);
+
+        let dropped = samples.len() - count;
+        if dropped > 0 {
+            inner.dropped.fetch_add(dropped as u32, Ordering::Relaxed);
+        }
+        count
+    }
+}
+
+/// 环形缓冲区消费者端（处理线程持有）
+pub struct RingConsumer {
+    inner: Arc<Inner>,
+}
+
+impl RingConsumer {
+    /// 读取样本（非阻塞）
+    ///
+    /// # 返回值
+    /// 实际读取的样本数
+    pub fn read(&mut self, out: &mut [i16]) -> usize {
+        let inner = &*self.inner;
+        let count = out.len().min(inner.available_read());
+
+        let mut tail = inner.tail.load(Ordering::Relaxed);
+        for sample in &mut out[..count] {
+            *sample = unsafe { *inner.data.add(tail) };
+            tail = (tail + 1) % inner.capacity;
+        }
+        inner.tail.store(tail, Ordering::Release);
+
+        count
+    }
+
+    /// 读取样本直到填满输出缓冲区或超时
+    ///
+    /// # 参数
+    /// * `out` - 输出缓冲区
+    /// * `timeout` - 最长等待时间
+    ///
+    /// # 返回值
+    /// 实际读取的样本数，超时时可能小于`out.len()`
+    pub fn read_exact(&mut self, out: &mut [i16], timeout: Duration) -> usize {
+        let start = Instant::now();
+        let mut filled = self.read(out);
+
+        while filled < out.len() && start.elapsed() < timeout {
+            std::thread::sleep(Duration::from_millis(5));
+            filled += self.read(&mut out[filled..]);
+        }
+
+        filled
+    }
+
+    /// 当前可读取的样本数
+    pub fn available(&self) -> usize {
+        self.inner.available_read()
+    }
+
+    /// 丢弃所有未读取的样本
+    pub fn clear(&mut self) {
+        let head = self.inner.head.load(Ordering::Acquire);
+        self.inner.tail.store(head, Ordering::Release);
+    }
+
+    /// 缓冲区溢出累计丢弃的样本数
+    pub fn dropped_samples(&self) -> u32 {
+        self.inner.dropped.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,75 @@
+// 音频分流：把采集数据实时转发给其他线程（如对讲发送）
+//
+// 与调试录音器一样由消费者线程调用`feed`，未连接接收端时几乎没有开销。
+// 接收端处理不过来时丢弃新数据，不会阻塞唤醒词检测。
+
+use std::sync::{
+    mpsc::{self, Receiver, SyncSender, TrySendError},
+    Arc, Mutex,
+};
+
+/// 音频分流，可在多个线程间共享
+#[derive(Clone, Default)]
+pub struct AudioTap {
+    sender: Arc<Mutex<Option<SyncSender<Vec<i16>>>>>,
+}
+
+impl AudioTap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 开始转发，之前的接收端不再收到数据
+    ///
+    /// # 参数
+    /// * `capacity` - 最多缓存的样本块数
+    pub fn attach(&self, capacity: usize) -> Receiver<Vec<i16>> {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        if let Ok(mut current) = self.sender.lock() {
+            *current = Some(sender);
+        }
+        receiver
+    }
+
+    /// 停止转发
+    pub fn detach(&self) {
+        if let Ok(mut current) = self.sender.lock() {
+            *current = None;
+        }
+    }
+
+    /// 写入采集到的样本
+    pub fn feed(&self, samples: &[i16]) {
+        let Ok(mut current) = self.sender.lock() else {
+            return;
+        };
+        let Some(sender) = current.as_ref() else {
+            return;
+        };
+        if let Err(TrySendError::Disconnected(_)) = sender.try_send(samples.to_vec()) {
+            *current = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forwards_only_while_attached() {
+        let tap = AudioTap::new();
+        tap.feed(&[1]);
+
+        let receiver = tap.attach(1);
+        tap.feed(&[2, 3]);
+        // 接收端来不及处理时丢弃
+        tap.feed(&[4]);
+        assert_eq!(receiver.try_recv(), Ok(vec![2, 3]));
+        assert!(receiver.try_recv().is_err());
+
+        tap.detach();
+        tap.feed(&[5]);
+        assert!(receiver.try_recv().is_err());
+    }
+}
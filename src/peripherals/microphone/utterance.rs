@@ -0,0 +1,172 @@
+// 按键说话的语音缓冲：按下时开始收集采集数据，松开时取出整段语音
+//
+// 与调试录音器一样由消费者线程调用`feed`，控制端调用`start`/`finish`。
+// 检测到唤醒词时消费者线程调用`arm`交出预录音频，之后的样本先暂存，
+// 控制端随后调用`start`时这些样本成为语音的开头。
+
+use std::sync::{
+    atomic::{AtomicU16, Ordering},
+    Arc, Mutex,
+};
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+
+use super::dsp;
+
+/// 预录音频在这段时间内没有被`start`接管时丢弃
+const ARMED_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 正在收集的语音
+struct ActiveUtterance {
+    samples: Vec<i16>,
+    max_samples: usize,
+}
+
+/// 唤醒词触发后等待开始收集的语音
+struct ArmedUtterance {
+    /// 预录音频与之后暂存的样本
+    samples: Vec<i16>,
+    since: Instant,
+}
+
+#[derive(Default)]
+struct State {
+    active: Option<ActiveUtterance>,
+    armed: Option<ArmedUtterance>,
+}
+
+/// 语音片段缓冲区
+///
+/// 可在多个线程间共享，未在收集时`feed`几乎没有开销。
+#[derive(Clone, Default)]
+pub struct UtteranceBuffer {
+    state: Arc<Mutex<State>>,
+    /// 最近一块样本的均方根幅度，聆听界面显示电平用
+    level: Arc<AtomicU16>,
+}
+
+impl UtteranceBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 开始收集语音，丢弃之前未取出的数据
+    ///
+    /// 唤醒词刚触发过时，预录音频与之后暂存的样本作为语音的开头。
+    ///
+    /// # 参数
+    /// * `sample_rate` - 采样率(Hz)
+    /// * `max_seconds` - 最长收集时间，超过后的样本被丢弃
+    pub fn start(&self, sample_rate: u32, max_seconds: u32) {
+        let max_samples = (sample_rate * max_seconds) as usize;
+        if let Ok(mut state) = self.state.lock() {
+            let mut samples = match state.armed.take() {
+                Some(armed) if armed.since.elapsed() < ARMED_TIMEOUT => {
+                    info!("语音包含唤醒词前后的预录音频: {} 样本", armed.samples.len());
+                    armed.samples
+                }
+                _ => Vec::with_capacity(sample_rate as usize),
+            };
+            samples.truncate(max_samples);
+            state.active = Some(ActiveUtterance {
+                samples,
+                max_samples,
+            });
+            info!("开始收集语音 (最长{}秒)", max_seconds);
+        }
+        self.level.store(0, Ordering::Relaxed);
+    }
+
+    /// 唤醒词触发，交出预录音频并暂存之后的样本，等待控制端调用`start`
+    ///
+    /// 正在收集语音时忽略。
+    ///
+    /// # 参数
+    /// * `pre_roll` - 唤醒词之前的音频
+    pub fn arm(&self, pre_roll: Vec<i16>) {
+        if let Ok(mut state) = self.state.lock() {
+            if state.active.is_none() {
+                state.armed = Some(ArmedUtterance {
+                    samples: pre_roll,
+                    since: Instant::now(),
+                });
+            }
+        }
+    }
+
+    /// 结束收集并取出语音
+    ///
+    /// # 返回值
+    /// 收集到的样本，没有进行中的收集时返回None
+    pub fn finish(&self) -> Option<Vec<i16>> {
+        self.level.store(0, Ordering::Relaxed);
+        let utterance = self.state.lock().ok()?.active.take()?;
+        info!("语音收集结束: {} 样本", utterance.samples.len());
+        Some(utterance.samples)
+    }
+
+    /// 是否正在收集
+    pub fn is_active(&self) -> bool {
+        self.state
+            .lock()
+            .map(|state| state.active.is_some())
+            .unwrap_or(false)
+    }
+
+    /// 最近写入的一块样本的均方根幅度，未在收集时为0
+    pub fn level(&self) -> u16 {
+        self.level.load(Ordering::Relaxed)
+    }
+
+    /// 写入采集到的样本
+    pub fn feed(&self, samples: &[i16]) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let Some(utterance) = state.active.as_mut() else {
+            // 等待开始收集期间暂存样本，超时后丢弃
+            let expired = state
+                .armed
+                .as_ref()
+                .is_some_and(|armed| armed.since.elapsed() >= ARMED_TIMEOUT);
+            if expired {
+                state.armed = None;
+            } else if let Some(armed) = state.armed.as_mut() {
+                armed.samples.extend_from_slice(samples);
+            }
+            return;
+        };
+
+        self.level.store(dsp::rms(samples), Ordering::Relaxed);
+
+        let free = utterance.max_samples - utterance.samples.len();
+        if free < samples.len() && free > 0 {
+            warn!("语音超过最长时间，后续数据被丢弃");
+        }
+        utterance
+            .samples
+            .extend_from_slice(&samples[..samples.len().min(free)]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_armed_pre_roll_starts_utterance() {
+        let buffer = UtteranceBuffer::new();
+        // 未收集也未触发唤醒词时样本被忽略
+        buffer.feed(&[9, 9]);
+        buffer.arm(vec![1, 2]);
+        buffer.feed(&[3]);
+        buffer.start(16000, 1);
+        buffer.feed(&[4]);
+        assert_eq!(buffer.finish(), Some(vec![1, 2, 3, 4]));
+
+        // 预录音频只使用一次
+        buffer.start(16000, 1);
+        assert_eq!(buffer.finish(), Some(vec![]));
+    }
+}
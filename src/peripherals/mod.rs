@@ -1,4 +1,16 @@
+pub mod als;
+pub mod backlight;
+pub mod battery;
+pub mod button;
+pub mod gc9a01;
+pub mod i2c_bus;
 pub mod microphone;
+pub mod neopixel;
 pub mod qmi8658;
+pub mod resample;
+pub mod rtc;
+pub mod speaker;
 pub mod st77916;
+pub mod storage;
+pub mod tca9554;
 pub mod wifi;
@@ -0,0 +1,155 @@
+// 状态灯环效果
+//
+// 每种效果按帧号计算每颗灯珠的颜色，与驱动无关，便于在主机上测试。
+
+use std::f32::consts::PI;
+
+use embedded_graphics::pixelcolor::{Rgb565, Rgb888, RgbColor};
+
+/// 灯珠颜色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const OFF: Rgb = Rgb { r: 0, g: 0, b: 0 };
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// 按比例调整亮度
+    ///
+    /// # 参数
+    /// * `factor` - 亮度系数（0.0-1.0）
+    pub fn scale(self, factor: f32) -> Self {
+        let factor = factor.clamp(0.0, 1.0);
+        Self {
+            r: (self.r as f32 * factor) as u8,
+            g: (self.g as f32 * factor) as u8,
+            b: (self.b as f32 * factor) as u8,
+        }
+    }
+}
+
+/// 使用界面主题色
+impl From<Rgb565> for Rgb {
+    fn from(color: Rgb565) -> Self {
+        let color = Rgb888::from(color);
+        Self::new(color.r(), color.g(), color.b())
+    }
+}
+
+/// 灯环效果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RingEffect {
+    /// 全部熄灭
+    Off,
+    /// 常亮
+    Solid(Rgb),
+    /// 呼吸：整体亮度缓慢起伏，待机时使用
+    Breathing(Rgb),
+    /// 旋转：一个带拖尾的亮点绕环转动，思考中使用
+    Spinning(Rgb),
+    /// 脉冲：整体亮度快速跳动，录音和播放语音时使用
+    Pulse(Rgb),
+}
+
+/// 呼吸效果的周期（帧）
+const BREATHING_PERIOD: u32 = 90;
+/// 旋转效果转一圈的帧数
+const SPIN_PERIOD: u32 = 30;
+/// 旋转效果拖尾长度（灯珠数）
+const SPIN_TAIL: usize = 4;
+/// 脉冲效果的周期（帧）
+const PULSE_PERIOD: u32 = 10;
+
+impl RingEffect {
+    /// 计算一帧的灯珠颜色
+    ///
+    /// # 参数
+    /// * `frame` - 帧号，从效果开始时计数
+    /// * `pixels` - 输出，长度为灯珠数
+    pub fn render(&self, frame: u32, pixels: &mut [Rgb]) {
+        match *self {
+            RingEffect::Off => pixels.fill(Rgb::OFF),
+            RingEffect::Solid(color) => pixels.fill(color),
+            RingEffect::Breathing(color) => {
+                pixels.fill(color.scale(wave(frame, BREATHING_PERIOD, 0.05)));
+            }
+            RingEffect::Pulse(color) => {
+                pixels.fill(color.scale(wave(frame, PULSE_PERIOD, 0.3)));
+            }
+            RingEffect::Spinning(color) => {
+                let count = pixels.len();
+                if count == 0 {
+                    return;
+                }
+                let head = (frame % SPIN_PERIOD) as usize * count / SPIN_PERIOD as usize;
+                for (index, pixel) in pixels.iter_mut().enumerate() {
+                    // 亮点之后第几颗灯珠，越靠后越暗
+                    let behind = (head + count - index) % count;
+                    *pixel = if behind <= SPIN_TAIL {
+                        color.scale(1.0 - behind as f32 / (SPIN_TAIL + 1) as f32)
+                    } else {
+                        Rgb::OFF
+                    };
+                }
+            }
+        }
+    }
+}
+
+impl RingEffect {
+    /// 按比例降低整体亮度，效果形式不变
+    ///
+    /// # 参数
+    /// * `factor` - 亮度系数（0.0-1.0）
+    pub fn dimmed(self, factor: f32) -> Self {
+        match self {
+            RingEffect::Off => RingEffect::Off,
+            RingEffect::Solid(color) => RingEffect::Solid(color.scale(factor)),
+            RingEffect::Breathing(color) => RingEffect::Breathing(color.scale(factor)),
+            RingEffect::Spinning(color) => RingEffect::Spinning(color.scale(factor)),
+            RingEffect::Pulse(color) => RingEffect::Pulse(color.scale(factor)),
+        }
+    }
+}
+
+/// 在`floor`与1.0之间按正弦起伏的亮度
+fn wave(frame: u32, period: u32, floor: f32) -> f32 {
+    let phase = (frame % period) as f32 / period as f32;
+    let level = 0.5 - 0.5 * (2.0 * PI * phase).cos();
+    floor + (1.0 - floor) * level
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spinning_head_and_tail() {
+        let color = Rgb::new(0, 200, 0);
+        let mut pixels = [Rgb::OFF; 12];
+        RingEffect::Spinning(color).render(0, &mut pixels);
+
+        // 第0帧亮点在第0颗，拖尾在环的末尾
+        assert_eq!(pixels[0], color);
+        assert!(pixels[11].g > 0 && pixels[11].g < color.g);
+        assert_eq!(pixels[1], Rgb::OFF);
+        assert_eq!(pixels[6], Rgb::OFF);
+    }
+
+    #[test]
+    fn test_dimmed_keeps_effect() {
+        let color = Rgb::new(200, 100, 0);
+        assert_eq!(
+            RingEffect::Breathing(color).dimmed(0.1),
+            RingEffect::Breathing(Rgb::new(20, 10, 0))
+        );
+        assert_eq!(RingEffect::Off.dimmed(0.1), RingEffect::Off);
+    }
+}
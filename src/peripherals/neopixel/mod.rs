@@ -0,0 +1,193 @@
+// WS2812状态灯环
+//
+// 使用RMT外设产生WS2812时序，灯环线程按固定帧率刷新当前效果，
+// App根据界面状态切换效果（待机呼吸、思考旋转、说话脉冲）。
+
+pub mod effects;
+
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use esp_idf_hal::gpio::OutputPin;
+use esp_idf_hal::peripheral::Peripheral;
+use esp_idf_hal::rmt::{
+    config::TransmitConfig, PinState, Pulse, RmtChannel, TxRmtDriver, VariableLengthSignal,
+};
+use log::{info, warn};
+
+use effects::{Rgb, RingEffect};
+
+/// 刷新间隔（约30帧/秒）
+const FRAME_INTERVAL: Duration = Duration::from_millis(33);
+
+/// 最大亮度系数，限制整环电流并避免夜间刺眼
+const MAX_BRIGHTNESS: f32 = 0.3;
+
+/// WS2812位时序（纳秒）
+const T0H_NS: u64 = 350;
+const T0L_NS: u64 = 800;
+const T1H_NS: u64 = 700;
+const T1L_NS: u64 = 600;
+
+/// WS2812灯环驱动
+pub struct NeoPixelRing {
+    tx: TxRmtDriver<'static>,
+    led_count: usize,
+    /// 0、1两种位对应的高低电平脉冲
+    bit0: (Pulse, Pulse),
+    bit1: (Pulse, Pulse),
+}
+
+impl NeoPixelRing {
+    /// 初始化灯环
+    ///
+    /// # 参数
+    /// * `channel` - RMT通道
+    /// * `pin` - 数据引脚
+    /// * `led_count` - 灯珠数量
+    pub fn new(
+        channel: impl Peripheral<P = impl RmtChannel> + 'static,
+        pin: impl Peripheral<P = impl OutputPin> + 'static,
+        led_count: usize,
+    ) -> Result<Self> {
+        let config = TransmitConfig::new().clock_divider(1);
+        let tx = TxRmtDriver::new(channel, pin, &config)?;
+
+        let ticks_hz = tx.counter_clock()?;
+        let pulse =
+            |state, ns| Pulse::new_with_duration(ticks_hz, state, &Duration::from_nanos(ns));
+        let bit0 = (
+            pulse(PinState::High, T0H_NS)?,
+            pulse(PinState::Low, T0L_NS)?,
+        );
+        let bit1 = (
+            pulse(PinState::High, T1H_NS)?,
+            pulse(PinState::Low, T1L_NS)?,
+        );
+
+        Ok(Self {
+            tx,
+            led_count,
+            bit0,
+            bit1,
+        })
+    }
+
+    /// 灯珠数量
+    pub fn led_count(&self) -> usize {
+        self.led_count
+    }
+
+    /// 写入所有灯珠的颜色（按GRB顺序发送，阻塞直到发送完成）
+    pub fn write(&mut self, pixels: &[Rgb]) -> Result<()> {
+        let mut signal = VariableLengthSignal::with_capacity(pixels.len() * 24);
+        for pixel in pixels {
+            let grb = (pixel.g as u32) << 16 | (pixel.r as u32) << 8 | pixel.b as u32;
+            for bit in (0..24).rev() {
+                let (high, low) = if grb & (1 << bit) != 0 {
+                    &self.bit1
+                } else {
+                    &self.bit0
+                };
+                signal.push([high, low])?;
+            }
+        }
+        self.tx.start_blocking(&signal)?;
+        Ok(())
+    }
+}
+
+/// 灯环线程
+struct StatusRing {
+    ring: NeoPixelRing,
+    command_receiver: Receiver<RingEffect>,
+}
+
+impl StatusRing {
+    fn run(&mut self) {
+        info!("Status ring started ({} LEDs)", self.ring.led_count());
+
+        let mut effect = RingEffect::Off;
+        let mut frame = 0u32;
+        let mut pixels = vec![Rgb::OFF; self.ring.led_count()];
+        let mut next_frame = Instant::now();
+
+        loop {
+            match self
+                .command_receiver
+                .recv_timeout(next_frame.saturating_duration_since(Instant::now()))
+            {
+                Ok(new_effect) => {
+                    if new_effect != effect {
+                        effect = new_effect;
+                        frame = 0;
+                    }
+                    continue;
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            effect.render(frame, &mut pixels);
+            for pixel in pixels.iter_mut() {
+                *pixel = pixel.scale(MAX_BRIGHTNESS);
+            }
+            if let Err(e) = self.ring.write(&pixels) {
+                warn!("Status ring write failed: {}", e);
+            }
+
+            frame = frame.wrapping_add(1);
+            next_frame += FRAME_INTERVAL;
+            // 落后太多时不追帧
+            if next_frame < Instant::now() {
+                next_frame = Instant::now() + FRAME_INTERVAL;
+            }
+        }
+
+        // 退出时熄灭
+        pixels.fill(Rgb::OFF);
+        let _ = self.ring.write(&pixels);
+        info!("Status ring command channel disconnected, shutting down");
+    }
+}
+
+/// 灯环管理器，由App根据界面状态切换效果
+pub struct StatusRingManager {
+    command_sender: Sender<RingEffect>,
+    /// 最近一次设置的效果，相同效果不重复发送
+    current: RingEffect,
+}
+
+impl StatusRingManager {
+    /// 启动灯环线程
+    pub fn new(ring: NeoPixelRing) -> Result<Self> {
+        let (command_sender, command_receiver) = mpsc::channel::<RingEffect>();
+
+        thread::Builder::new()
+            .stack_size(4 * 1024)
+            .name("status_ring".to_string())
+            .spawn(move || {
+                StatusRing {
+                    ring,
+                    command_receiver,
+                }
+                .run();
+            })?;
+
+        Ok(Self {
+            command_sender,
+            current: RingEffect::Off,
+        })
+    }
+
+    /// 切换效果
+    pub fn set_effect(&mut self, effect: RingEffect) -> Result<()> {
+        if effect != self.current {
+            self.command_sender.send(effect)?;
+            self.current = effect;
+        }
+        Ok(())
+    }
+}
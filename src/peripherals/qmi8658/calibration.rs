@@ -0,0 +1,156 @@
+// 运动阈值自动校准
+//
+// 设备静止放置几秒，统计加速度变化、角速度和倾角的噪声，
+// 按噪声水平推算晃动、倾斜和旋转手势的阈值。环境振动越大阈值越高，
+// 安静环境下阈值更低、手势更灵敏。
+
+use anyhow::{bail, Result};
+
+use super::motion_detector::{MotionConfig, MotionThresholds};
+use crate::hal::SensorData;
+
+/// 校准至少需要的样本数
+pub const MIN_CALIBRATION_SAMPLES: u32 = 50;
+
+/// 噪声上界取均值加几倍标准差
+const NOISE_SIGMAS: f32 = 4.0;
+/// 晃动阈值相对噪声上界的倍数
+const SHAKE_MARGIN: f32 = 20.0;
+/// 旋转手势阈值相对噪声上界的倍数
+const ROTATE_MARGIN: f32 = 15.0;
+/// 倾斜阈值在静止倾角之上增加的角度
+const TILT_MARGIN_DEG: f32 = 30.0;
+/// 校准期间角速度超过该值视为设备被移动 (°/s)
+const MAX_STILL_GYRO: f32 = 30.0;
+
+/// 单个量的在线统计（Welford算法）
+#[derive(Debug, Clone, Copy, Default)]
+struct RunningStats {
+    count: u32,
+    mean: f32,
+    m2: f32,
+    max: f32,
+}
+
+impl RunningStats {
+    fn add(&mut self, value: f32) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f32;
+        self.m2 += delta * (value - self.mean);
+        self.max = self.max.max(value);
+    }
+
+    /// 噪声上界：均值加`NOISE_SIGMAS`倍标准差
+    fn noise_ceiling(&self) -> f32 {
+        let variance = if self.count > 1 {
+            self.m2 / (self.count - 1) as f32
+        } else {
+            0.0
+        };
+        self.mean + NOISE_SIGMAS * variance.sqrt()
+    }
+}
+
+/// 噪声采样器
+#[derive(Debug, Clone, Default)]
+pub struct NoiseCalibrator {
+    prev_accel_magnitude: Option<f32>,
+    accel_change: RunningStats,
+    gyro: RunningStats,
+    gyro_z: RunningStats,
+    tilt: RunningStats,
+}
+
+impl NoiseCalibrator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 加入一个静止时的传感器样本
+    pub fn add(&mut self, data: &SensorData) {
+        let accel_magnitude =
+            (data.accel_x.powi(2) + data.accel_y.powi(2) + data.accel_z.powi(2)).sqrt();
+        if let Some(prev) = self.prev_accel_magnitude {
+            self.accel_change.add((accel_magnitude - prev).abs());
+        }
+        self.prev_accel_magnitude = Some(accel_magnitude);
+
+        self.gyro
+            .add((data.gyro_x.powi(2) + data.gyro_y.powi(2) + data.gyro_z.powi(2)).sqrt());
+        self.gyro_z.add(data.gyro_z.abs());
+
+        if accel_magnitude > MotionConfig::MIN_VALID_ACCEL_THRESHOLD {
+            let cos_angle = (data.accel_z.abs() / accel_magnitude).clamp(0.0, 1.0);
+            self.tilt.add(cos_angle.acos().to_degrees());
+        }
+    }
+
+    /// 已采集的样本数
+    pub fn sample_count(&self) -> u32 {
+        self.gyro.count
+    }
+
+    /// 根据噪声推算阈值
+    ///
+    /// 样本不足或校准期间设备被移动时返回错误。
+    pub fn finish(&self) -> Result<MotionThresholds> {
+        if self.sample_count() < MIN_CALIBRATION_SAMPLES {
+            bail!("校准样本不足: {}", self.sample_count());
+        }
+        if self.gyro.max > MAX_STILL_GYRO {
+            bail!("校准时请保持设备静止");
+        }
+
+        let defaults = MotionThresholds::default();
+        let thresholds = MotionThresholds {
+            accel: (self.accel_change.noise_ceiling() * SHAKE_MARGIN)
+                .clamp(defaults.accel / 2.0, defaults.accel * 2.5),
+            gyro: (self.gyro.noise_ceiling() * SHAKE_MARGIN)
+                .clamp(defaults.gyro / 2.0, defaults.gyro * 2.5),
+            tilt: (self.tilt.mean + TILT_MARGIN_DEG).clamp(20.0, 80.0),
+            rotate: (self.gyro_z.noise_ceiling() * ROTATE_MARGIN)
+                .clamp(defaults.rotate / 2.0, defaults.rotate * 3.0),
+        };
+        thresholds.validate()?;
+        Ok(thresholds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(accel_z: f32, gyro_z: f32) -> SensorData {
+        SensorData {
+            accel_x: 0.0,
+            accel_y: 0.0,
+            accel_z,
+            gyro_x: 0.0,
+            gyro_y: 0.0,
+            gyro_z,
+            temperature: 25.0,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_calibration_scales_with_noise() {
+        let mut quiet = NoiseCalibrator::new();
+        let mut noisy = NoiseCalibrator::new();
+        for i in 0..100 {
+            let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+            quiet.add(&sample(1000.0 + sign * 2.0, sign * 0.5));
+            noisy.add(&sample(1000.0 + sign * 30.0, sign * 6.0));
+        }
+
+        let quiet = quiet.finish().unwrap();
+        let noisy = noisy.finish().unwrap();
+        assert!(noisy.accel > quiet.accel);
+        assert!(noisy.gyro > quiet.gyro);
+        assert!(quiet.tilt >= 20.0 && quiet.tilt <= 80.0);
+
+        // 样本不足
+        assert!(NoiseCalibrator::new().finish().is_err());
+    }
+}
@@ -16,16 +16,17 @@
 //! # 使用示例
 //!
 //! ```rust
-//! use esp_idf_hal::peripherals::Peripherals;
-//! use crate::peripherals::qmi8658::driver::QMI8658Driver;
+//! use esp_idf_hal::{peripherals::Peripherals, prelude::*};
+//! use crate::peripherals::{i2c_bus::SharedI2cBus, qmi8658::driver::QMI8658Driver};
 //!
 //! let peripherals = Peripherals::take().unwrap();
-//! let mut sensor = QMI8658Driver::new(
+//! let bus = SharedI2cBus::new(
 //!     peripherals.i2c0,
 //!     peripherals.pins.gpio11,
 //!     peripherals.pins.gpio10,
-//!     0x6A
+//!     400.kHz().into(),
 //! )?;
+//! let mut sensor = QMI8658Driver::new(&bus, 0x6A)?;
 //!
 //! let sensor_data = sensor.read_sensor_data()?;
 //! println!("Accel: {:.2}, {:.2}, {:.2}",
@@ -33,12 +34,12 @@
 //! ```
 
 use anyhow::Result;
-use esp_idf_hal::gpio::{Gpio10, Gpio11};
-use esp_idf_hal::i2c::{I2cConfig, I2cDriver, I2C0};
-use esp_idf_hal::prelude::*;
 use log::{error, info};
 use std::f32::consts::PI;
 
+use crate::hal::{MotionSensor, SensorData};
+use crate::peripherals::i2c_bus::{I2cDevice, SharedI2cBus};
+
 /// QMI8658 I2C地址(当SA0引脚接地时)
 pub const QMI8658_ADDRESS_LOW: u8 = 0x6A;
 /// QMI8658 I2C地址(当SA0引脚接高电平时)
@@ -189,32 +190,8 @@ pub enum Precision {
     Six = 6,
 }
 
-/// QMI8658传感器数据结构
-///
-/// 包含从QMI8658传感器读取的完整传感器数据，包括3轴加速度、3轴角速度、温度和时间戳
-#[derive(Debug, Clone, Copy)]
-pub struct SensorData {
-    /// X轴加速度值 (单位根据配置：mg或m/s²)
-    pub accel_x: f32,
-    /// Y轴加速度值 (单位根据配置：mg或m/s²)
-    pub accel_y: f32,
-    /// Z轴加速度值 (单位根据配置：mg或m/s²)
-    pub accel_z: f32,
-    /// X轴角速度值 (单位根据配置：dps或rad/s)
-    pub gyro_x: f32,
-    /// Y轴角速度值 (单位根据配置：dps或rad/s)
-    pub gyro_y: f32,
-    /// Z轴角速度值 (单位根据配置：dps或rad/s)
-    pub gyro_z: f32,
-    /// 芯片温度值 (°C)
-    pub temperature: f32,
-    /// 时间戳 (传感器内部计数器)
-    pub timestamp: u32,
-}
-
-pub struct QMI8658Driver<'a> {
-    i2c: I2cDriver<'a>,
-    address: u8,
+pub struct QMI8658Driver {
+    i2c: I2cDevice,
     accel_lsb_div: u16,
     gyro_lsb_div: u16,
     accel_unit_mps2: bool,
@@ -223,10 +200,10 @@ pub struct QMI8658Driver<'a> {
     timestamp: u32,
 }
 
-impl<'a> std::fmt::Debug for QMI8658Driver<'a> {
+impl std::fmt::Debug for QMI8658Driver {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("QMI8658Driver")
-            .field("address", &self.address)
+            .field("address", &self.i2c.address())
             .field("accel_lsb_div", &self.accel_lsb_div)
             .field("gyro_lsb_div", &self.gyro_lsb_div)
             .field("accel_unit_mps2", &self.accel_unit_mps2)
@@ -237,26 +214,20 @@ impl<'a> std::fmt::Debug for QMI8658Driver<'a> {
     }
 }
 
-impl<'a> QMI8658Driver<'a> {
+impl QMI8658Driver {
     /// 创建新的QMI8658驱动器实例
     ///
     /// # 参数
     ///
-    /// * `i2c0` - I2C外设实例
-    /// * `sda` - SDA引脚(GPIO11)
-    /// * `scl` - SCL引脚(GPIO10)
+    /// * `bus` - 共享I2C总线
     /// * `address` - 设备I2C地址
     ///
     /// # 返回
     ///
     /// 返回配置好的驱动器实例或错误
-    pub fn new(i2c0: I2C0, sda: Gpio11, scl: Gpio10, address: u8) -> Result<Self> {
-        let config = I2cConfig::new().baudrate(400.kHz().into());
-        let i2c = I2cDriver::new(i2c0, sda, scl, &config)?;
-
+    pub fn new(bus: &SharedI2cBus, address: u8) -> Result<Self> {
         let mut driver = QMI8658Driver {
-            i2c,
-            address,
+            i2c: bus.device(address),
             accel_lsb_div: 4096,
             gyro_lsb_div: 64,
             accel_unit_mps2: false,
@@ -265,11 +236,6 @@ impl<'a> QMI8658Driver<'a> {
             timestamp: 0,
         };
 
-        for addr in 0x08..=0x77 {
-            // todo: 这里可能会有问题
-            let _ = driver.i2c.write(addr, &[0x00], 100);
-        }
-
         driver.init()?;
         Ok(driver)
     }
@@ -304,8 +270,7 @@ impl<'a> QMI8658Driver<'a> {
     /// * `value` - 要写入的值
     fn write_register(&mut self, reg: QMI8658Register, value: u8) -> Result<()> {
         let data = [reg as u8, value];
-        self.i2c.write(self.address, &data, 1000)?;
-        Ok(())
+        self.i2c.write(&data)
     }
 
     /// 读取寄存器
@@ -316,8 +281,7 @@ impl<'a> QMI8658Driver<'a> {
     /// * `buffer` - 存储读取数据的缓冲区
     fn read_register(&mut self, reg: QMI8658Register, buffer: &mut [u8]) -> Result<()> {
         let reg_addr = [reg as u8];
-        self.i2c.write_read(self.address, &reg_addr, buffer, 1000)?;
-        Ok(())
+        self.i2c.write_read(&reg_addr, buffer)
     }
 
     /// 获取设备ID
@@ -779,3 +743,9 @@ impl<'a> QMI8658Driver<'a> {
         result
     }
 }
+
+impl MotionSensor for QMI8658Driver {
+    fn read_sensor_data(&mut self) -> Result<SensorData> {
+        QMI8658Driver::read_sensor_data(self)
+    }
+}
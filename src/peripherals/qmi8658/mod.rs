@@ -1,33 +1,11 @@
+//! QMI8658六轴IMU
+//!
+//! 寄存器访问与数据读取只在`driver`中实现，运动状态判断、阈值校准与计步分别在
+//! `motion_detector`、`calibration`与`pedometer`中基于`SensorData`完成，与驱动无关。
+
+pub mod calibration;
 pub mod driver;
 pub mod motion_detector;
+pub mod pedometer;
 
 pub use driver::*;
-
-use anyhow::Result;
-use driver::{QMI8658Driver, SensorData};
-
-#[derive(Debug)]
-pub struct QMI8658<'a> {
-    driver: QMI8658Driver<'a>,
-}
-
-impl<'a> QMI8658<'a> {
-    pub fn new(
-        i2c0: esp_idf_hal::i2c::I2C0,
-        sda: esp_idf_hal::gpio::Gpio11,
-        scl: esp_idf_hal::gpio::Gpio10,
-        address: u8,
-    ) -> Result<Self> {
-        let driver = QMI8658Driver::new(i2c0, sda, scl, address)?;
-
-        Ok(Self { driver })
-    }
-
-    pub fn read_sensor_data(&mut self) -> Result<SensorData> {
-        self.driver.read_sensor_data()
-    }
-
-    pub fn driver(&mut self) -> &mut QMI8658Driver<'a> {
-        &mut self.driver
-    }
-}
@@ -1,13 +1,16 @@
-use super::driver::SensorData;
+use crate::hal::SensorData;
 use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
 use std::f32::consts::PI;
 
 /// 运动状态枚举
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MotionState {
-    Still,   // 静止
-    Shaking, // 晃动
-    Tilting, // 倾斜
+    Still,                    // 静止
+    Shaking,                  // 晃动
+    Tilting,                  // 倾斜
+    RotatingClockwise,        // 屏幕朝向用户时顺时针旋转
+    RotatingCounterClockwise, // 屏幕朝向用户时逆时针旋转
 }
 
 /// 运动检测配置常量
@@ -30,6 +33,130 @@ impl MotionConfig {
     pub const MIN_VALID_ACCEL_THRESHOLD: f32 = 10.0;
     /// 最大有效倾斜角度
     pub const MAX_TILT_ANGLE: f32 = 90.0;
+    /// 默认旋转手势阈值 (°/s) - 绕屏幕法线(Z轴)的角速度
+    pub const DEFAULT_ROTATE_THRESHOLD: f32 = 60.0;
+    /// 失重阈值 (mg) - 加速度矢量低于该值视为自由落体
+    pub const FREE_FALL_THRESHOLD: f32 = 300.0;
+    /// 失重持续超过该时间才算跌落 (ms)，约5cm落差
+    pub const MIN_FREE_FALL_MS: u64 = 100;
+    /// 撞击阈值 (mg)
+    pub const IMPACT_THRESHOLD: f32 = 2500.0;
+    /// 失重结束后等待撞击的时间 (ms)
+    pub const IMPACT_WINDOW_MS: u64 = 500;
+    /// 失重超过该时间时，即使没采到撞击峰值也算跌落 (ms)
+    ///
+    /// 撞击峰值通常只有几毫秒，50ms采样间隔可能错过。
+    pub const LONG_FREE_FALL_MS: u64 = 200;
+    /// 屏幕朝下阈值 (mg) - Z轴加速度低于该值视为扣放
+    pub const FACE_DOWN_THRESHOLD: f32 = -850.0;
+    /// 扣放后Z轴加速度高于该值视为翻回 (mg)，与扣放阈值之间留出回差
+    pub const FACE_UP_THRESHOLD: f32 = -500.0;
+    /// 屏幕朝下持续超过该时间才算扣放 (ms)，避免翻转过程中误触发
+    pub const FACE_DOWN_HOLD_MS: u64 = 1500;
+}
+
+/// 运动检测阈值，可自动校准并保存在设备配置中
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MotionThresholds {
+    /// 加速度变化阈值 (mg)
+    pub accel: f32,
+    /// 陀螺仪阈值 (°/s)
+    pub gyro: f32,
+    /// 倾斜角度阈值 (度)
+    pub tilt: f32,
+    /// 旋转手势阈值 (°/s)
+    pub rotate: f32,
+}
+
+impl Default for MotionThresholds {
+    fn default() -> Self {
+        Self {
+            accel: MotionConfig::DEFAULT_ACCEL_THRESHOLD,
+            gyro: MotionConfig::DEFAULT_GYRO_THRESHOLD,
+            tilt: MotionConfig::DEFAULT_TILT_THRESHOLD,
+            rotate: MotionConfig::DEFAULT_ROTATE_THRESHOLD,
+        }
+    }
+}
+
+impl MotionThresholds {
+    /// 检查阈值是否有效
+    pub fn validate(&self) -> Result<()> {
+        if self.accel < MotionConfig::MIN_VALID_ACCEL_THRESHOLD {
+            bail!("加速度阈值过小: {}", self.accel);
+        }
+        if self.gyro <= 0.0 {
+            bail!("陀螺仪阈值必须大于0: {}", self.gyro);
+        }
+        if self.tilt <= 0.0 || self.tilt > MotionConfig::MAX_TILT_ANGLE {
+            bail!("倾斜角度阈值无效: {}", self.tilt);
+        }
+        if self.rotate <= 0.0 {
+            bail!("旋转手势阈值必须大于0: {}", self.rotate);
+        }
+        Ok(())
+    }
+
+    /// 灵敏度档位对应的阈值：默认阈值按档位缩放，倾斜阈值不变
+    pub fn preset(sensitivity: MotionSensitivity) -> Self {
+        let scale = sensitivity.threshold_scale();
+        Self {
+            accel: MotionConfig::DEFAULT_ACCEL_THRESHOLD * scale,
+            gyro: MotionConfig::DEFAULT_GYRO_THRESHOLD * scale,
+            tilt: MotionConfig::DEFAULT_TILT_THRESHOLD,
+            rotate: MotionConfig::DEFAULT_ROTATE_THRESHOLD * scale,
+        }
+    }
+
+    /// 与当前阈值最接近的灵敏度档位（校准后的阈值不一定正好落在某一档）
+    pub fn sensitivity(&self) -> MotionSensitivity {
+        let scale = self.accel / MotionConfig::DEFAULT_ACCEL_THRESHOLD;
+        MotionSensitivity::ALL
+            .into_iter()
+            .min_by(|a, b| {
+                let da = (a.threshold_scale() - scale).abs();
+                let db = (b.threshold_scale() - scale).abs();
+                da.total_cmp(&db)
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// 设置界面中的动作灵敏度档位
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MotionSensitivity {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl MotionSensitivity {
+    /// 所有档位，按设置界面中的顺序排列
+    pub const ALL: [MotionSensitivity; 3] = [
+        MotionSensitivity::Low,
+        MotionSensitivity::Medium,
+        MotionSensitivity::High,
+    ];
+
+    /// 界面显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            MotionSensitivity::Low => "低",
+            MotionSensitivity::Medium => "中",
+            MotionSensitivity::High => "高",
+        }
+    }
+
+    /// 相对默认阈值的倍数，灵敏度越高阈值越低
+    fn threshold_scale(&self) -> f32 {
+        match self {
+            MotionSensitivity::Low => 1.5,
+            MotionSensitivity::Medium => 1.0,
+            MotionSensitivity::High => 0.7,
+        }
+    }
 }
 
 /// 缓存的检测结果，避免重复计算
@@ -39,17 +166,16 @@ struct CachedDetectionResult {
     accel_magnitude: f32,
     gyro_magnitude: f32,
     tilt_angle: f32,
-    is_shaking: bool,
-    is_tilting: bool,
 }
 
 /// 运动检测器主结构体
 #[derive(Debug, Clone, Copy)]
 pub struct MotionDetector {
     // 公开配置参数
-    pub accel_threshold: f32, // 加速度变化阈值 (mg)
-    pub gyro_threshold: f32,  // 陀螺仪阈值 (°/s)
-    pub tilt_threshold: f32,  // 倾斜角度阈值 (度)
+    pub accel_threshold: f32,  // 加速度变化阈值 (mg)
+    pub gyro_threshold: f32,   // 陀螺仪阈值 (°/s)
+    pub tilt_threshold: f32,   // 倾斜角度阈值 (度)
+    pub rotate_threshold: f32, // 旋转手势阈值 (°/s)
 
     // 内部状态
     prev_accel_magnitude: f32,
@@ -59,6 +185,14 @@ pub struct MotionDetector {
     // 缓存结果
     cached_result: Option<CachedDetectionResult>,
     last_sensor_data_hash: u64, // 简单的数据指纹，用于检测数据是否变化
+
+    // 跌落检测
+    free_fall_since_ms: Option<u64>, // 失重开始时间
+    fall_ended: Option<(u64, u64)>,  // 失重结束时间与持续时间，等待撞击
+
+    // 扣放检测
+    face_down_since_ms: Option<u64>, // 屏幕朝下开始时间
+    face_down: bool,                 // 已判定为扣放
 }
 
 impl MotionDetector {
@@ -68,11 +202,16 @@ impl MotionDetector {
             accel_threshold: MotionConfig::DEFAULT_ACCEL_THRESHOLD,
             gyro_threshold: MotionConfig::DEFAULT_GYRO_THRESHOLD,
             tilt_threshold: MotionConfig::DEFAULT_TILT_THRESHOLD,
+            rotate_threshold: MotionConfig::DEFAULT_ROTATE_THRESHOLD,
             prev_accel_magnitude: 0.0,
             shake_count: 0,
             stable_count: 0,
             cached_result: None,
             last_sensor_data_hash: 0,
+            free_fall_since_ms: None,
+            fall_ended: None,
+            face_down_since_ms: None,
+            face_down: false,
         }
     }
 
@@ -96,11 +235,16 @@ impl MotionDetector {
             accel_threshold,
             gyro_threshold,
             tilt_threshold,
+            rotate_threshold: MotionConfig::DEFAULT_ROTATE_THRESHOLD,
             prev_accel_magnitude: 0.0,
             shake_count: 0,
             stable_count: 0,
             cached_result: None,
             last_sensor_data_hash: 0,
+            free_fall_since_ms: None,
+            fall_ended: None,
+            face_down_since_ms: None,
+            face_down: false,
         })
     }
 
@@ -125,6 +269,77 @@ impl MotionDetector {
         result.motion_state
     }
 
+    /// 检测跌落：先失重（加速度接近0），随后出现撞击峰值
+    ///
+    /// 需要以较高频率（20Hz以上）对每个样本调用，与`detect_motion`相互独立。
+    ///
+    /// # 参数
+    /// * `data` - 传感器数据
+    /// * `timestamp_ms` - 采样时间（毫秒，单调递增）
+    ///
+    /// # 返回值
+    /// 检测到跌落时返回失重持续时间（毫秒）
+    pub fn detect_drop(&mut self, data: &SensorData, timestamp_ms: u64) -> Option<u64> {
+        let magnitude = Self::calculate_magnitude(data.accel_x, data.accel_y, data.accel_z);
+
+        if magnitude < MotionConfig::FREE_FALL_THRESHOLD {
+            self.free_fall_since_ms.get_or_insert(timestamp_ms);
+            return None;
+        }
+
+        if let Some(start) = self.free_fall_since_ms.take() {
+            let duration = timestamp_ms - start;
+            if duration >= MotionConfig::MIN_FREE_FALL_MS {
+                self.fall_ended = Some((timestamp_ms, duration));
+            }
+        }
+
+        let (ended, duration) = self.fall_ended?;
+        if magnitude > MotionConfig::IMPACT_THRESHOLD {
+            self.fall_ended = None;
+            return Some(duration);
+        }
+        if timestamp_ms - ended > MotionConfig::IMPACT_WINDOW_MS {
+            self.fall_ended = None;
+            if duration >= MotionConfig::LONG_FREE_FALL_MS {
+                return Some(duration);
+            }
+        }
+        None
+    }
+
+    /// 检测扣放：屏幕朝下静置超过`FACE_DOWN_HOLD_MS`
+    ///
+    /// 与`detect_drop`一样需要对每个样本调用。
+    ///
+    /// # 参数
+    /// * `data` - 传感器数据
+    /// * `timestamp_ms` - 采样时间（毫秒，单调递增）
+    ///
+    /// # 返回值
+    /// 状态变化时返回是否扣放：扣放后返回`Some(true)`，翻回后返回`Some(false)`
+    pub fn detect_face_down(&mut self, data: &SensorData, timestamp_ms: u64) -> Option<bool> {
+        if self.face_down {
+            if data.accel_z > MotionConfig::FACE_UP_THRESHOLD {
+                self.face_down = false;
+                self.face_down_since_ms = None;
+                return Some(false);
+            }
+            return None;
+        }
+
+        if data.accel_z >= MotionConfig::FACE_DOWN_THRESHOLD {
+            self.face_down_since_ms = None;
+            return None;
+        }
+        let since = *self.face_down_since_ms.get_or_insert(timestamp_ms);
+        if timestamp_ms - since >= MotionConfig::FACE_DOWN_HOLD_MS {
+            self.face_down = true;
+            return Some(true);
+        }
+        None
+    }
+
     /// 计算传感器数据的简单哈希值（用于检测数据变化）
     fn calculate_data_hash(&self, data: &SensorData) -> u64 {
         // 使用简单的位运算组合数据，足以检测数据变化
@@ -163,24 +378,51 @@ impl MotionDetector {
         let tilt_angle = Self::calculate_tilt_angle(data.accel_x, data.accel_y, data.accel_z);
         let is_tilting = tilt_angle > self.tilt_threshold;
 
+        // 检测旋转手势：屏幕保持水平朝上/朝向用户，主要绕Z轴转动
+        let rotation = self.detect_rotation(data, is_shaking || is_tilting);
+
         // 更新历史状态
         self.prev_accel_magnitude = accel_magnitude;
 
         // 状态机逻辑：需要连续检测来避免噪声
-        let motion_state = self.update_state_machine(is_shaking, is_tilting);
+        let motion_state = self.update_state_machine(is_shaking, is_tilting, rotation);
 
         CachedDetectionResult {
             motion_state,
             accel_magnitude,
             gyro_magnitude,
             tilt_angle,
-            is_shaking,
-            is_tilting,
+        }
+    }
+
+    /// 检测绕Z轴的旋转手势
+    ///
+    /// Z轴垂直屏幕指向用户，按右手定则逆时针角速度为正。
+    fn detect_rotation(&self, data: &SensorData, other_motion: bool) -> Option<MotionState> {
+        if other_motion || data.gyro_z.abs() < self.rotate_threshold {
+            return None;
+        }
+
+        // X/Y轴角速度明显小于Z轴才算旋转，避免翻转设备时误触发
+        let off_axis = Self::calculate_magnitude(data.gyro_x, data.gyro_y, 0.0);
+        if off_axis > data.gyro_z.abs() * 0.5 {
+            return None;
+        }
+
+        if data.gyro_z > 0.0 {
+            Some(MotionState::RotatingCounterClockwise)
+        } else {
+            Some(MotionState::RotatingClockwise)
         }
     }
 
     /// 状态机更新逻辑
-    fn update_state_machine(&mut self, is_shaking: bool, is_tilting: bool) -> MotionState {
+    fn update_state_machine(
+        &mut self,
+        is_shaking: bool,
+        is_tilting: bool,
+        rotation: Option<MotionState>,
+    ) -> MotionState {
         if is_shaking {
             self.shake_count += 1;
             self.stable_count = 0;
@@ -194,7 +436,9 @@ impl MotionDetector {
             }
         }
 
-        if is_tilting {
+        if let Some(rotation) = rotation {
+            rotation
+        } else if is_tilting {
             MotionState::Tilting
         } else {
             MotionState::Still
@@ -234,6 +478,27 @@ impl MotionDetector {
         Ok(())
     }
 
+    /// 一次设置全部阈值（带验证）
+    pub fn set_all_thresholds(&mut self, thresholds: MotionThresholds) -> Result<()> {
+        thresholds.validate()?;
+        self.accel_threshold = thresholds.accel;
+        self.gyro_threshold = thresholds.gyro;
+        self.tilt_threshold = thresholds.tilt;
+        self.rotate_threshold = thresholds.rotate;
+        self.invalidate_cache();
+        Ok(())
+    }
+
+    /// 当前全部阈值
+    pub fn thresholds(&self) -> MotionThresholds {
+        MotionThresholds {
+            accel: self.accel_threshold,
+            gyro: self.gyro_threshold,
+            tilt: self.tilt_threshold,
+            rotate: self.rotate_threshold,
+        }
+    }
+
     /// 设置倾斜角度阈值（带验证）
     pub fn set_tilt_threshold(&mut self, tilt_threshold: f32) -> Result<()> {
         if tilt_threshold <= 0.0 || tilt_threshold > MotionConfig::MAX_TILT_ANGLE {
@@ -328,3 +593,168 @@ impl Default for MotionDetector {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::hal::MotionSensor;
+    use crate::testing::{sensor_sample, FakeClock, ScriptedImu};
+
+    #[test]
+    fn test_sensitivity_presets() {
+        for sensitivity in MotionSensitivity::ALL {
+            let thresholds = MotionThresholds::preset(sensitivity);
+            assert!(thresholds.validate().is_ok());
+            assert_eq!(thresholds.sensitivity(), sensitivity);
+        }
+        assert_eq!(
+            MotionThresholds::default().sensitivity(),
+            MotionSensitivity::Medium
+        );
+        let calibrated = MotionThresholds {
+            accel: 1100.0,
+            ..MotionThresholds::default()
+        };
+        assert_eq!(calibrated.sensitivity(), MotionSensitivity::Low);
+    }
+
+    fn sample(accel_z: f32) -> SensorData {
+        SensorData {
+            accel_x: 0.0,
+            accel_y: 0.0,
+            accel_z,
+            gyro_x: 0.0,
+            gyro_y: 0.0,
+            gyro_z: 0.0,
+            temperature: 25.0,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_drop_requires_free_fall_then_impact() {
+        let mut detector = MotionDetector::new();
+
+        // 只有撞击没有失重：例如敲桌子
+        assert_eq!(detector.detect_drop(&sample(3000.0), 0), None);
+
+        // 失重150ms后撞击
+        assert_eq!(detector.detect_drop(&sample(1000.0), 50), None);
+        for t in [100, 150, 200, 250] {
+            assert_eq!(detector.detect_drop(&sample(50.0), t), None);
+        }
+        assert_eq!(detector.detect_drop(&sample(3500.0), 300), Some(200));
+
+        // 短暂失重（小于MIN_FREE_FALL_MS）不算
+        assert_eq!(detector.detect_drop(&sample(50.0), 1000), None);
+        assert_eq!(detector.detect_drop(&sample(3500.0), 1050), None);
+    }
+
+    #[test]
+    fn test_face_down_requires_hold() {
+        let mut detector = MotionDetector::new();
+
+        // 翻转过程中短暂朝下不算
+        assert_eq!(detector.detect_face_down(&sample(-1000.0), 0), None);
+        assert_eq!(detector.detect_face_down(&sample(1000.0), 500), None);
+
+        for t in (1000..2500).step_by(50) {
+            assert_eq!(detector.detect_face_down(&sample(-1000.0), t), None);
+        }
+        assert_eq!(
+            detector.detect_face_down(&sample(-1000.0), 2500),
+            Some(true)
+        );
+        assert_eq!(detector.detect_face_down(&sample(-1000.0), 2550), None);
+
+        // 回差范围内保持扣放，翻回后报告一次
+        assert_eq!(detector.detect_face_down(&sample(-700.0), 2600), None);
+        assert_eq!(
+            detector.detect_face_down(&sample(1000.0), 2650),
+            Some(false)
+        );
+        assert_eq!(detector.detect_face_down(&sample(1000.0), 2700), None);
+    }
+
+    /// 依次检测脚本中的全部样本
+    fn run(detector: &mut MotionDetector, imu: &mut ScriptedImu) -> Vec<MotionState> {
+        let mut states = Vec::new();
+        while let Ok(data) = imu.read_sensor_data() {
+            states.push(detector.detect_motion(&data));
+        }
+        states
+    }
+
+    #[test]
+    fn test_shake_needs_consecutive_samples() {
+        let mut detector = MotionDetector::new();
+        // 相同的数据直接返回缓存结果，静止样本之间加入微小变化
+        let still = |z| sensor_sample((0.0, 0.0, z), (0.0, 0.0, 0.0));
+        let shake = |z| sensor_sample((0.0, 0.0, z), (200.0, 0.0, 0.0));
+
+        let mut imu = ScriptedImu::new([still(1000.0), shake(2000.0), shake(500.0)]);
+        assert_eq!(
+            run(&mut detector, &mut imu),
+            [MotionState::Still, MotionState::Still, MotionState::Shaking]
+        );
+
+        // 只隔一个稳定样本时晃动计数不清零，再晃一次立即报告
+        let mut imu = ScriptedImu::new([still(1000.0), shake(2000.0)]);
+        assert_eq!(
+            run(&mut detector, &mut imu),
+            [MotionState::Still, MotionState::Shaking]
+        );
+
+        // 连续两个稳定样本后重新计数
+        let mut imu = ScriptedImu::new([still(1000.0), still(1001.0), shake(2000.0)]);
+        assert_eq!(
+            run(&mut detector, &mut imu),
+            [MotionState::Still, MotionState::Still, MotionState::Still]
+        );
+    }
+
+    #[test]
+    fn test_rotation_and_tilt() {
+        let mut detector = MotionDetector::new();
+        let mut imu = ScriptedImu::new([
+            sensor_sample((0.0, 0.0, 1000.0), (0.0, 0.0, 90.0)),
+            sensor_sample((0.0, 0.0, 1000.0), (0.0, 0.0, -90.0)),
+            // 主要绕X/Y轴转动（翻转设备）不算旋转手势
+            sensor_sample((0.0, 0.0, 1000.0), (60.0, 60.0, 90.0)),
+            sensor_sample((800.0, 0.0, 600.0), (0.0, 0.0, 0.0)),
+            // 倾斜时不报告旋转
+            sensor_sample((800.0, 0.0, 600.0), (0.0, 0.0, 90.0)),
+        ]);
+        assert_eq!(
+            run(&mut detector, &mut imu),
+            [
+                MotionState::RotatingCounterClockwise,
+                MotionState::RotatingClockwise,
+                MotionState::Still,
+                MotionState::Tilting,
+                MotionState::Tilting,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_drop_at_sample_rate() {
+        let mut detector = MotionDetector::new();
+        let mut clock = FakeClock::new();
+        let mut imu = ScriptedImu::default()
+            .repeat(sample(1000.0), 2)
+            .repeat(sample(50.0), 4)
+            .repeat(sample(3500.0), 1);
+
+        let mut drops = Vec::new();
+        while let Ok(data) = imu.read_sensor_data() {
+            if let Some(fall_ms) = detector.detect_drop(&data, clock.millis()) {
+                drops.push((clock.millis(), fall_ms));
+            }
+            clock.advance(Duration::from_millis(50));
+        }
+        assert_eq!(drops, [(300, 200)]);
+    }
+}
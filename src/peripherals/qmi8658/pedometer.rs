@@ -0,0 +1,138 @@
+// 计步器
+//
+// 对加速度矢量大小做低通滤波并减去缓慢变化的基线（重力），
+// 在动态分量上做带迟滞的峰值检测。步与步之间的间隔需在合理范围内，
+// 连续出现`CONFIRM_STEPS`步后才开始计数，过滤拿起、放下设备等偶发冲击。
+
+/// 低通滤波系数（20Hz采样）
+const SMOOTHING: f32 = 0.3;
+/// 基线跟踪系数，越小基线越稳定
+const BASELINE_ALPHA: f32 = 0.02;
+/// 峰值阈值 (mg)，动态分量超过该值视为一次迈步
+const PEAK_THRESHOLD: f32 = 80.0;
+/// 动态分量回落到该值以下才允许检测下一个峰 (mg)
+const RESET_THRESHOLD: f32 = 20.0;
+/// 两步之间的最短、最长间隔（毫秒）
+const MIN_STEP_INTERVAL_MS: u64 = 250;
+const MAX_STEP_INTERVAL_MS: u64 = 2000;
+/// 连续多少步后确认正在走路
+const CONFIRM_STEPS: u32 = 4;
+
+/// 计步器
+#[derive(Debug, Clone, Default)]
+pub struct Pedometer {
+    smoothed: Option<f32>,
+    baseline: f32,
+    /// 已越过峰值阈值，等待回落
+    above_peak: bool,
+    last_step_ms: Option<u64>,
+    /// 尚未确认的连续步数
+    pending: u32,
+    /// 已确认正在走路
+    walking: bool,
+    steps: u32,
+}
+
+impl Pedometer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 输入一个加速度样本
+    ///
+    /// # 参数
+    /// * `accel_magnitude` - 加速度矢量大小 (mg)
+    /// * `timestamp_ms` - 采样时间（毫秒，单调递增）
+    ///
+    /// # 返回值
+    /// 本次是否计入了新的步数
+    pub fn update(&mut self, accel_magnitude: f32, timestamp_ms: u64) -> bool {
+        let smoothed = match self.smoothed {
+            Some(previous) => previous + SMOOTHING * (accel_magnitude - previous),
+            None => {
+                self.baseline = accel_magnitude;
+                accel_magnitude
+            }
+        };
+        self.smoothed = Some(smoothed);
+        self.baseline += BASELINE_ALPHA * (smoothed - self.baseline);
+        let dynamic = smoothed - self.baseline;
+
+        if self.above_peak {
+            if dynamic < RESET_THRESHOLD {
+                self.above_peak = false;
+            }
+            return false;
+        }
+        if dynamic < PEAK_THRESHOLD {
+            // 超过最长间隔没有新的一步，视为停止走路
+            if self
+                .last_step_ms
+                .is_some_and(|last| timestamp_ms - last > MAX_STEP_INTERVAL_MS)
+            {
+                self.pending = 0;
+                self.walking = false;
+                self.last_step_ms = None;
+            }
+            return false;
+        }
+
+        self.above_peak = true;
+        if let Some(last) = self.last_step_ms {
+            if timestamp_ms - last < MIN_STEP_INTERVAL_MS {
+                // 同一步内的抖动
+                return false;
+            }
+        }
+        self.last_step_ms = Some(timestamp_ms);
+
+        if self.walking {
+            self.steps += 1;
+            return true;
+        }
+        self.pending += 1;
+        if self.pending >= CONFIRM_STEPS {
+            // 确认走路后把之前等待确认的步数一起计入
+            self.walking = true;
+            self.steps += self.pending;
+            self.pending = 0;
+            return true;
+        }
+        false
+    }
+
+    /// 累计步数
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+
+    /// 清零步数（每天零点）
+    pub fn reset_steps(&mut self) {
+        self.steps = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_walking_ignores_still() {
+        // 20Hz采样，2Hz步频，幅度300mg，持续10秒
+        let mut pedometer = Pedometer::new();
+        for i in 0..200u64 {
+            let t = i as f32 * 0.05;
+            let magnitude = 1000.0 + 300.0 * (2.0 * std::f32::consts::PI * 2.0 * t).sin();
+            pedometer.update(magnitude, i * 50);
+        }
+        let steps = pedometer.steps();
+        assert!((18..=21).contains(&steps), "steps = {}", steps);
+
+        let mut still = Pedometer::new();
+        for i in 0..200u64 {
+            let noise = if i % 2 == 0 { 5.0 } else { -5.0 };
+            still.update(1000.0 + noise, i * 50);
+        }
+        assert_eq!(still.steps(), 0);
+    }
+}
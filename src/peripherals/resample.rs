@@ -0,0 +1,134 @@
+// 音频重采样
+//
+// 麦克风与扬声器固定运行在16kHz，部分服务端要求8kHz或24kHz。
+// 这里用整数线性插值在两者之间转换，上传前和播放前各用一次。
+// 线性插值对语音足够，降采样时不做额外的抗混叠滤波。
+
+/// 流式重采样器
+///
+/// 位置以输出采样率的倒数为单位计数，没有累积误差。
+/// 保存上一块的最后一个样本与插值位置，分块处理与一次处理整段的结果相同。
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    from_rate: u32,
+    to_rate: u32,
+    /// 下一个输出样本的位置（单位为1/to_rate个输入样本），0对应`prev`
+    position: u64,
+    /// 上一块的最后一个样本
+    prev: i16,
+}
+
+impl Resampler {
+    /// 创建重采样器
+    ///
+    /// # 参数
+    /// * `from_rate` - 输入采样率(Hz)
+    /// * `to_rate` - 输出采样率(Hz)
+    pub fn new(from_rate: u32, to_rate: u32) -> Self {
+        let from_rate = from_rate.max(1);
+        let to_rate = to_rate.max(1);
+        Self {
+            from_rate,
+            to_rate,
+            // 第一个输出样本对齐第一个输入样本
+            position: to_rate as u64,
+            prev: 0,
+        }
+    }
+
+    /// 输入与输出采样率相同，不需要转换
+    pub fn is_passthrough(&self) -> bool {
+        self.from_rate == self.to_rate
+    }
+
+    /// 处理一块输入，结果追加到`output`
+    ///
+    /// # 参数
+    /// * `input` - 输入样本
+    /// * `output` - 输出缓冲区
+    pub fn process(&mut self, input: &[i16], output: &mut Vec<i16>) {
+        if self.is_passthrough() {
+            output.extend_from_slice(input);
+            return;
+        }
+        if input.is_empty() {
+            return;
+        }
+
+        let len = input.len() as u64;
+        let to_rate = self.to_rate as u64;
+        output.reserve((len * to_rate / self.from_rate as u64) as usize + 1);
+
+        // 位置0对应`prev`，位置k对应`input[k - 1]`，插值需要位置右侧的样本
+        while self.position / to_rate < len {
+            let index = (self.position / to_rate) as usize;
+            let a = if index == 0 {
+                self.prev
+            } else {
+                input[index - 1]
+            } as i64;
+            let b = input[index] as i64;
+            let frac = (self.position % to_rate) as i64;
+            output.push((a + (b - a) * frac / to_rate as i64) as i16);
+            self.position += self.from_rate as u64;
+        }
+
+        self.position -= len * to_rate;
+        self.prev = input[input.len() - 1];
+    }
+}
+
+/// 一次性重采样整段音频
+///
+/// # 参数
+/// * `input` - 输入样本
+/// * `from_rate` - 输入采样率(Hz)
+/// * `to_rate` - 输出采样率(Hz)
+pub fn resample(input: &[i16], from_rate: u32, to_rate: u32) -> Vec<i16> {
+    let mut output = Vec::new();
+    Resampler::new(from_rate, to_rate).process(input, &mut output);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_rate_is_passthrough() {
+        let input = [1, -2, 3, i16::MAX, i16::MIN];
+        assert_eq!(resample(&input, 16000, 16000), input);
+    }
+
+    #[test]
+    fn test_downsample_by_two_takes_every_other_sample() {
+        let input: Vec<i16> = (0..16).map(|i| i * 100).collect();
+        let output = resample(&input, 16000, 8000);
+        assert_eq!(output, vec![0, 200, 400, 600, 800, 1000, 1200, 1400]);
+    }
+
+    #[test]
+    fn test_upsample_interpolates() {
+        let output = resample(&[0, 300, 600, 900], 8000, 24000);
+        assert_eq!(output, vec![0, 100, 200, 300, 400, 500, 600, 700, 800]);
+    }
+
+    #[test]
+    fn test_extreme_values_do_not_overflow() {
+        let output = resample(&[i16::MIN, i16::MAX, i16::MIN], 16000, 24000);
+        assert_eq!(output, vec![i16::MIN, 10922, 10922]);
+    }
+
+    #[test]
+    fn test_chunked_matches_whole() {
+        let input: Vec<i16> = (0..1000).map(|i| ((i * 37) % 2000 - 1000) as i16).collect();
+        let whole = resample(&input, 16000, 24000);
+
+        let mut resampler = Resampler::new(16000, 24000);
+        let mut chunked = Vec::new();
+        for chunk in input.chunks(77) {
+            resampler.process(chunk, &mut chunked);
+        }
+        assert_eq!(chunked, whole);
+    }
+}
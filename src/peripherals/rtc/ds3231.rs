@@ -0,0 +1,151 @@
+//! DS3231实时时钟驱动
+//!
+//! 时间寄存器从0x00开始依次为秒、分、时、星期、日、月、年，均为BCD编码，
+//! 使用24小时制。状态寄存器的OSF位在芯片掉电后置位，写入时间后需要手动清除。
+
+use anyhow::Result;
+
+use super::{from_bcd, to_bcd, BASE_YEAR};
+use crate::clock::LocalTime;
+use crate::hal::{I2cTransport, RealTimeClock};
+
+/// DS3231固定I2C地址
+pub const DS3231_ADDRESS: u8 = 0x68;
+
+/// 秒寄存器地址，其后依次为分、时、星期、日、月、年
+const REG_SECONDS: u8 = 0x00;
+/// 状态寄存器
+const REG_STATUS: u8 = 0x0F;
+/// 状态寄存器中的振荡器停止标志
+const STATUS_OSF: u8 = 0x80;
+/// 小时寄存器中的12小时制标志
+const HOUR_12H: u8 = 0x40;
+
+/// 解析时间寄存器
+///
+/// # 返回值
+/// 数值超出范围时返回None
+fn decode(regs: &[u8; 7]) -> Option<LocalTime> {
+    let hour = if regs[2] & HOUR_12H != 0 {
+        // 12小时制：bit5为下午，12点记为12
+        let hour = from_bcd(regs[2] & 0x1F) % 12;
+        if regs[2] & 0x20 != 0 {
+            hour + 12
+        } else {
+            hour
+        }
+    } else {
+        from_bcd(regs[2] & 0x3F)
+    };
+    let time = LocalTime {
+        second: from_bcd(regs[0] & 0x7F),
+        minute: from_bcd(regs[1] & 0x7F),
+        hour,
+        // 芯片中星期为1-7
+        weekday: (regs[3] & 0x07).saturating_sub(1),
+        day: from_bcd(regs[4] & 0x3F),
+        month: from_bcd(regs[5] & 0x1F),
+        year: BASE_YEAR + from_bcd(regs[6]) as i32,
+    };
+    let valid = time.second < 60
+        && time.minute < 60
+        && time.hour < 24
+        && (1..=31).contains(&time.day)
+        && (1..=12).contains(&time.month);
+    valid.then_some(time)
+}
+
+/// 生成时间寄存器的值（24小时制）
+fn encode(time: &LocalTime) -> [u8; 7] {
+    [
+        to_bcd(time.second),
+        to_bcd(time.minute),
+        to_bcd(time.hour),
+        time.weekday + 1,
+        to_bcd(time.day),
+        to_bcd(time.month),
+        to_bcd((time.year - BASE_YEAR).clamp(0, 99) as u8),
+    ]
+}
+
+/// DS3231驱动
+pub struct Ds3231<I> {
+    i2c: I,
+}
+
+impl<I: I2cTransport> Ds3231<I> {
+    /// # 参数
+    /// * `i2c` - 共享总线上的设备句柄，地址为`DS3231_ADDRESS`
+    pub fn new(i2c: I) -> Self {
+        log::info!("实时时钟: DS3231");
+        Self { i2c }
+    }
+
+    fn read_register(&self, reg: u8) -> Result<u8> {
+        let mut value = [0u8];
+        self.i2c.write_read(&[reg], &mut value)?;
+        Ok(value[0])
+    }
+}
+
+impl<I: I2cTransport> RealTimeClock for Ds3231<I> {
+    fn read_time(&mut self) -> Result<Option<LocalTime>> {
+        if self.read_register(REG_STATUS)? & STATUS_OSF != 0 {
+            return Ok(None);
+        }
+        let mut regs = [0u8; 7];
+        self.i2c.write_read(&[REG_SECONDS], &mut regs)?;
+        Ok(decode(&regs))
+    }
+
+    fn set_time(&mut self, time: &LocalTime) -> Result<()> {
+        let mut data = [0u8; 8];
+        data[0] = REG_SECONDS;
+        data[1..].copy_from_slice(&encode(time));
+        self.i2c.write(&data)?;
+
+        let status = self.read_register(REG_STATUS)?;
+        self.i2c.write(&[REG_STATUS, status & !STATUS_OSF])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{I2cTransaction, MockI2c};
+
+    #[test]
+    fn test_encode_decode() {
+        let time = LocalTime::from_local_secs(1_709_164_800 + 23 * 3600 + 5 * 60 + 9);
+        let regs = encode(&time);
+        assert_eq!(regs, [0x09, 0x05, 0x23, 5, 0x29, 0x02, 0x24]);
+        assert_eq!(decode(&regs), Some(time));
+
+        // 12小时制：下午11点
+        let mut pm = regs;
+        pm[2] = HOUR_12H | 0x20 | 0x11;
+        assert_eq!(decode(&pm).map(|t| t.hour), Some(23));
+    }
+
+    #[test]
+    fn test_read_and_set_time_over_i2c() {
+        let time = LocalTime::from_local_secs(1_709_164_800 + 8 * 3600);
+        let regs = encode(&time);
+        let i2c = MockI2c::new([
+            I2cTransaction::WriteRead(vec![REG_STATUS], vec![0x00]),
+            I2cTransaction::WriteRead(vec![REG_SECONDS], regs.to_vec()),
+            // 掉电后不读取时间
+            I2cTransaction::WriteRead(vec![REG_STATUS], vec![STATUS_OSF]),
+            // 写入时间后清除OSF，保留其他状态位
+            I2cTransaction::Write([&[REG_SECONDS][..], &regs].concat()),
+            I2cTransaction::WriteRead(vec![REG_STATUS], vec![STATUS_OSF | 0x08]),
+            I2cTransaction::Write(vec![REG_STATUS, 0x08]),
+        ]);
+
+        let mut rtc = Ds3231::new(i2c.clone());
+        assert_eq!(rtc.read_time().unwrap(), Some(time));
+        assert_eq!(rtc.read_time().unwrap(), None);
+        rtc.set_time(&time).unwrap();
+        i2c.done();
+    }
+}
@@ -0,0 +1,54 @@
+//! 实时时钟
+//!
+//! 可选外设：启动时在共享I2C总线上探测，找到后即使没有WiFi也能在断电重启后恢复时间。
+//! 当前支持PCF85063（I2C地址0x51）与DS3231（I2C地址0x68），芯片中保存北京时间。
+
+pub mod ds3231;
+pub mod pcf85063;
+
+use crate::hal::RealTimeClock;
+use crate::peripherals::i2c_bus::SharedI2cBus;
+
+/// 探测总线上的实时时钟
+///
+/// # 返回值
+/// 找到时返回时钟，否则返回None
+pub fn probe(bus: &SharedI2cBus) -> Option<Box<dyn RealTimeClock>> {
+    let rtc: Box<dyn RealTimeClock> = if bus.probe(pcf85063::PCF85063_ADDRESS) {
+        Box::new(pcf85063::Pcf85063::new(
+            bus.device(pcf85063::PCF85063_ADDRESS),
+        ))
+    } else if bus.probe(ds3231::DS3231_ADDRESS) {
+        Box::new(ds3231::Ds3231::new(bus.device(ds3231::DS3231_ADDRESS)))
+    } else {
+        return None;
+    };
+    Some(rtc)
+}
+
+/// BCD编码转换为数值
+fn from_bcd(value: u8) -> u8 {
+    (value >> 4) * 10 + (value & 0x0F)
+}
+
+/// 数值转换为BCD编码
+fn to_bcd(value: u8) -> u8 {
+    ((value / 10) << 4) | (value % 10)
+}
+
+/// 芯片只保存两位年份，按2000-2099年解释
+const BASE_YEAR: i32 = 2000;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bcd() {
+        assert_eq!(from_bcd(0x59), 59);
+        assert_eq!(to_bcd(59), 0x59);
+        for value in 0..100 {
+            assert_eq!(from_bcd(to_bcd(value)), value);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! PCF85063实时时钟驱动
+//!
+//! 时间寄存器从0x04开始依次为秒、分、时、日、星期、月、年，均为BCD编码，
+//! 秒寄存器的最高位是振荡器停止标志，芯片掉电后置位，写入时间时清除。
+
+use anyhow::Result;
+
+use super::{from_bcd, to_bcd, BASE_YEAR};
+use crate::clock::LocalTime;
+use crate::hal::{I2cTransport, RealTimeClock};
+
+/// PCF85063固定I2C地址
+pub const PCF85063_ADDRESS: u8 = 0x51;
+
+/// 秒寄存器地址，其后依次为分、时、日、星期、月、年
+const REG_SECONDS: u8 = 0x04;
+/// 秒寄存器中的振荡器停止标志
+const OSCILLATOR_STOPPED: u8 = 0x80;
+
+/// 解析时间寄存器
+///
+/// # 返回值
+/// 振荡器曾经停止或数值超出范围时返回None
+fn decode(regs: &[u8; 7]) -> Option<LocalTime> {
+    if regs[0] & OSCILLATOR_STOPPED != 0 {
+        return None;
+    }
+    let time = LocalTime {
+        second: from_bcd(regs[0] & 0x7F),
+        minute: from_bcd(regs[1] & 0x7F),
+        hour: from_bcd(regs[2] & 0x3F),
+        day: from_bcd(regs[3] & 0x3F),
+        weekday: regs[4] & 0x07,
+        month: from_bcd(regs[5] & 0x1F),
+        year: BASE_YEAR + from_bcd(regs[6]) as i32,
+    };
+    let valid = time.second < 60
+        && time.minute < 60
+        && time.hour < 24
+        && (1..=31).contains(&time.day)
+        && (1..=12).contains(&time.month);
+    valid.then_some(time)
+}
+
+/// 生成时间寄存器的值
+fn encode(time: &LocalTime) -> [u8; 7] {
+    [
+        to_bcd(time.second),
+        to_bcd(time.minute),
+        to_bcd(time.hour),
+        to_bcd(time.day),
+        time.weekday,
+        to_bcd(time.month),
+        to_bcd((time.year - BASE_YEAR).clamp(0, 99) as u8),
+    ]
+}
+
+/// PCF85063驱动
+pub struct Pcf85063<I> {
+    i2c: I,
+}
+
+impl<I: I2cTransport> Pcf85063<I> {
+    /// # 参数
+    /// * `i2c` - 共享总线上的设备句柄，地址为`PCF85063_ADDRESS`
+    pub fn new(i2c: I) -> Self {
+        log::info!("实时时钟: PCF85063");
+        Self { i2c }
+    }
+}
+
+impl<I: I2cTransport> RealTimeClock for Pcf85063<I> {
+    fn read_time(&mut self) -> Result<Option<LocalTime>> {
+        let mut regs = [0u8; 7];
+        self.i2c.write_read(&[REG_SECONDS], &mut regs)?;
+        Ok(decode(&regs))
+    }
+
+    fn set_time(&mut self, time: &LocalTime) -> Result<()> {
+        let mut data = [0u8; 8];
+        data[0] = REG_SECONDS;
+        data[1..].copy_from_slice(&encode(time));
+        self.i2c.write(&data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let time = LocalTime::from_local_secs(1_709_164_800 + 12 * 3600 + 34 * 60 + 56);
+        let regs = encode(&time);
+        assert_eq!(regs, [0x56, 0x34, 0x12, 0x29, 4, 0x02, 0x24]);
+        assert_eq!(decode(&regs), Some(time));
+
+        // 掉电后时间无效
+        let mut stopped = regs;
+        stopped[0] |= OSCILLATOR_STOPPED;
+        assert_eq!(decode(&stopped), None);
+    }
+}
@@ -0,0 +1,36 @@
+// 语音交互提示音：开始聆听、结束聆听与错误
+//
+// PCM数据由`scripts/gen_earcons.py`生成（16kHz、16位小端、单声道），编译时嵌入固件。
+// 每段不超过150ms，在主循环中播放不会超出单次迭代的阻塞预算。
+
+/// 提示音
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Earcon {
+    /// 开始聆听，麦克风已在录音
+    ListenStart,
+    /// 结束聆听，语音已提交
+    ListenEnd,
+    /// 请求失败或超时
+    Error,
+}
+
+impl Earcon {
+    /// 嵌入的原始PCM字节
+    fn bytes(&self) -> &'static [u8] {
+        match self {
+            Earcon::ListenStart => include_bytes!("../../../assets/earcons/listen_start.pcm"),
+            Earcon::ListenEnd => include_bytes!("../../../assets/earcons/listen_end.pcm"),
+            Earcon::Error => include_bytes!("../../../assets/earcons/error.pcm"),
+        }
+    }
+
+    /// 解码为16位PCM样本
+    ///
+    /// `include_bytes!`的数据不保证2字节对齐，这里逐个样本转换而不是直接转换切片。
+    pub fn samples(&self) -> Vec<i16> {
+        self.bytes()
+            .chunks_exact(2)
+            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+            .collect()
+    }
+}
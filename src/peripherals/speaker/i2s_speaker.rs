@@ -0,0 +1,268 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+use anyhow::Result;
+use esp_idf_hal::gpio::{AnyIOPin, InputPin, OutputPin};
+use esp_idf_hal::i2s::{
+    config::{
+        Config, DataBitWidth, SlotMode, StdClkConfig, StdConfig, StdGpioConfig, StdSlotConfig,
+    },
+    I2s, I2sDriver, I2sTx,
+};
+use esp_idf_hal::peripheral::Peripheral;
+
+use super::volume::Volume;
+use crate::peripherals::microphone::i2s_microphone::AudioBuffer;
+use crate::peripherals::resample;
+
+/// 每次写入I2S的样本数，也是打断检查的粒度
+const PLAYBACK_CHUNK_SAMPLES: usize = 512;
+
+/// 参考信号缓冲区容量（样本数），16kHz下约500ms
+const REFERENCE_BUFFER_SAMPLES: usize = 8000;
+
+/// 被打断时的淡出时长，加上一个播放块的检查间隔不超过100ms
+const FADE_OUT_MS: u32 = 60;
+
+/// 播放参考信号
+///
+/// 扬声器每写出一段音频，都会把同样的样本放入参考缓冲区，
+/// 采集端按相同节奏取出，作为AFE回声消除（AEC）的参考通道。
+/// 同时提供播放打断（barge-in）标志，按键与唤醒词线程都可以打断播放。
+#[derive(Clone)]
+pub struct PlaybackReference {
+    buffer: Arc<Mutex<AudioBuffer>>,
+    playing: Arc<AtomicBool>,
+    interrupt: Arc<AtomicBool>,
+}
+
+impl PlaybackReference {
+    fn new() -> Self {
+        Self {
+            buffer: Arc::new(Mutex::new(AudioBuffer::new(REFERENCE_BUFFER_SAMPLES))),
+            playing: Arc::new(AtomicBool::new(false)),
+            interrupt: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// 扬声器是否正在播放
+    pub fn is_playing(&self) -> bool {
+        self.playing.load(Ordering::Relaxed)
+    }
+
+    /// 请求打断当前播放
+    pub fn request_interrupt(&self) {
+        if self.is_playing() {
+            self.interrupt.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// 读取与采集样本对齐的参考信号
+    ///
+    /// 参考数据不足时（未播放或播放刚结束）剩余部分填充静音。
+    ///
+    /// # 参数
+    /// * `out` - 输出缓冲区，长度与本次采集的样本数相同
+    pub fn read_into(&self, out: &mut [i16]) {
+        let read = match self.buffer.lock() {
+            Ok(mut buffer) => buffer.read(out),
+            Err(_) => 0,
+        };
+        out[read..].fill(0);
+    }
+
+    fn push(&self, samples: &[i16]) {
+        if let Ok(mut buffer) = self.buffer.lock() {
+            buffer.write(samples);
+        }
+    }
+
+    fn clear(&self) {
+        if let Ok(mut buffer) = self.buffer.lock() {
+            buffer.clear();
+        }
+    }
+}
+
+/// I2S扬声器（PCM5101 DAC）
+pub struct I2sSpeaker {
+    i2s_driver: I2sDriver<'static, I2sTx>,
+    sample_rate: u32,
+    reference: PlaybackReference,
+    /// 软件音量
+    volume: Volume,
+}
+
+impl I2sSpeaker {
+    /// 创建新的I2S扬声器实例
+    ///
+    /// # 参数
+    /// * `i2s_peripheral` - I2S外设实例
+    /// * `bclk_pin` - 位时钟引脚
+    /// * `dout_pin` - 数据输出引脚
+    /// * `ws_pin` - 字时钟引脚
+    /// * `sample_rate` - 采样率(Hz)，做回声消除时需与麦克风一致
+    ///
+    /// # 返回
+    /// 返回配置好的I2S扬声器实例或错误
+    pub fn new(
+        i2s_peripheral: impl Peripheral<P = impl I2s> + 'static,
+        bclk_pin: impl Peripheral<P = impl InputPin + OutputPin> + 'static,
+        dout_pin: impl Peripheral<P = impl OutputPin> + 'static,
+        ws_pin: impl Peripheral<P = impl InputPin + OutputPin> + 'static,
+        sample_rate: u32,
+    ) -> Result<Self> {
+        let std_cfg = StdConfig::new(
+            Config::new().auto_clear(true),
+            StdClkConfig::from_sample_rate_hz(sample_rate),
+            StdSlotConfig::philips_slot_default(DataBitWidth::Bits16, SlotMode::Mono),
+            StdGpioConfig::new(false, false, false),
+        );
+
+        let driver = I2sDriver::new_std_tx(
+            i2s_peripheral,
+            &std_cfg,
+            bclk_pin,
+            dout_pin,
+            None::<AnyIOPin>, // mclk (PCM5101使用内部PLL，不需要)
+            ws_pin,
+        )?;
+
+        Ok(Self {
+            i2s_driver: driver,
+            sample_rate,
+            reference: PlaybackReference::new(),
+            volume: Volume::default(),
+        })
+    }
+
+    /// 获取当前采样率
+    pub fn get_sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// 设置音量，从下一个播放块开始生效
+    pub fn set_volume(&mut self, volume: Volume) {
+        self.volume = volume;
+    }
+
+    /// 获取当前音量
+    pub fn volume(&self) -> Volume {
+        self.volume
+    }
+
+    /// 获取播放参考信号句柄，交给采集端用于回声消除
+    pub fn reference(&self) -> PlaybackReference {
+        self.reference.clone()
+    }
+
+    /// 播放一段PCM音频（阻塞直到写完或被打断）
+    ///
+    /// 被打断时把剩余音频淡出`FADE_OUT_MS`后停止，而不是直接截断。
+    ///
+    /// # 参数
+    /// * `samples` - 16位单声道PCM样本
+    ///
+    /// # 返回
+    /// 完整播放返回true，被打断返回false
+    pub fn play(&mut self, samples: &[i16]) -> Result<bool> {
+        self.reference.interrupt.store(false, Ordering::Relaxed);
+        self.reference.playing.store(true, Ordering::Relaxed);
+        self.i2s_driver.tx_enable()?;
+
+        let result = self.play_chunks(samples);
+
+        self.i2s_driver.tx_disable()?;
+        self.reference.playing.store(false, Ordering::Relaxed);
+        self.reference.clear();
+
+        result
+    }
+
+    /// 播放采样率与扬声器不同的PCM音频，先重采样到扬声器采样率
+    ///
+    /// # 参数
+    /// * `samples` - 16位单声道PCM样本
+    /// * `sample_rate` - 样本的采样率(Hz)
+    ///
+    /// # 返回
+    /// 完整播放返回true，被打断返回false
+    pub fn play_at_rate(&mut self, samples: &[i16], sample_rate: u32) -> Result<bool> {
+        if sample_rate == self.sample_rate {
+            return self.play(samples);
+        }
+        let resampled = resample::resample(samples, sample_rate, self.sample_rate);
+        self.play(&resampled)
+    }
+
+    fn play_chunks(&mut self, samples: &[i16]) -> Result<bool> {
+        let mut scratch = [0i16; PLAYBACK_CHUNK_SAMPLES];
+
+        for (index, chunk) in samples.chunks(PLAYBACK_CHUNK_SAMPLES).enumerate() {
+            if self.reference.interrupt.swap(false, Ordering::Relaxed) {
+                self.fade_out_from(&samples[index * PLAYBACK_CHUNK_SAMPLES..])?;
+                return Ok(false);
+            }
+
+            let scaled = &mut scratch[..chunk.len()];
+            self.volume.apply(chunk, scaled);
+            self.write_scaled(scaled)?;
+        }
+
+        Ok(true)
+    }
+
+    /// 把剩余音频的开头淡出后播放，避免突然截断产生爆音
+    fn fade_out_from(&mut self, remaining: &[i16]) -> Result<()> {
+        let len = (self.sample_rate * FADE_OUT_MS / 1000) as usize;
+        let head = &remaining[..len.min(remaining.len())];
+        let mut faded = vec![0i16; head.len()];
+        self.volume.apply(head, &mut faded);
+        fade_out(&mut faded);
+        for chunk in faded.chunks(PLAYBACK_CHUNK_SAMPLES) {
+            self.write_scaled(chunk)?;
+        }
+        Ok(())
+    }
+
+    /// 写入已经过增益的样本
+    fn write_scaled(&mut self, scaled: &[i16]) -> Result<()> {
+        let timeout = esp_idf_hal::delay::TickType::new_millis(1000);
+        // 参考信号使用增益后的样本，与扬声器实际输出一致
+        self.reference.push(scaled);
+        let bytes: &[u8] = bytemuck::cast_slice(scaled);
+        self.i2s_driver.write_all(bytes, timeout.into())?;
+        Ok(())
+    }
+}
+
+/// 线性淡出：第一个样本保持原幅度，之后逐渐减小，最后一个样本接近0
+pub fn fade_out(samples: &mut [i16]) {
+    let len = samples.len() as i32;
+    for (i, sample) in samples.iter_mut().enumerate() {
+        *sample = (*sample as i32 * (len - i as i32) / len) as i16;
+    }
+}
+
+impl Drop for I2sSpeaker {
+    fn drop(&mut self) {
+        let _ = self.i2s_driver.tx_disable();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fade_out() {
+        let mut samples = [i16::MAX, i16::MIN, 1000, 1000];
+        fade_out(&mut samples);
+        assert_eq!(samples, [i16::MAX, -24576, 500, 250]);
+
+        let mut empty: [i16; 0] = [];
+        fade_out(&mut empty);
+    }
+}
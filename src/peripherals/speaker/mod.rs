@@ -0,0 +1,4 @@
+pub mod earcon;
+pub mod i2s_speaker;
+pub mod tone;
+pub mod volume;
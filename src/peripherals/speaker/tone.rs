@@ -0,0 +1,46 @@
+// 提示音生成：闹钟、提醒等不需要从服务端下载的简单音调
+
+use std::f32::consts::PI;
+
+/// 音调首尾的淡入淡出时长（毫秒），避免起止处的爆音
+const FADE_MS: u32 = 5;
+
+/// 生成单一频率的正弦波提示音
+///
+/// # 参数
+/// * `frequency` - 频率(Hz)
+/// * `duration_ms` - 时长(毫秒)
+/// * `amplitude` - 峰值幅度（0-32767），最终音量仍受扬声器软件音量控制
+/// * `sample_rate` - 采样率(Hz)
+pub fn sine_tone(frequency: u32, duration_ms: u32, amplitude: i16, sample_rate: u32) -> Vec<i16> {
+    let total = (sample_rate * duration_ms / 1000) as usize;
+    let fade = ((sample_rate * FADE_MS / 1000) as usize)
+        .min(total / 2)
+        .max(1);
+    let step = 2.0 * PI * frequency as f32 / sample_rate as f32;
+
+    (0..total)
+        .map(|i| {
+            let edge = i.min(total - 1 - i);
+            let envelope = (edge as f32 / fade as f32).min(1.0);
+            ((i as f32 * step).sin() * amplitude as f32 * envelope) as i16
+        })
+        .collect()
+}
+
+/// 生成"嘀嘀"两声的闹钟提示音
+///
+/// 总长约170ms，在主循环中播放不会超出单次迭代的阻塞预算。
+///
+/// # 参数
+/// * `sample_rate` - 采样率(Hz)
+pub fn alarm_beep(sample_rate: u32) -> Vec<i16> {
+    let beep = sine_tone(2000, 60, 12000, sample_rate);
+    let gap = vec![0i16; (sample_rate * 50 / 1000) as usize];
+
+    let mut samples = Vec::with_capacity(beep.len() * 2 + gap.len());
+    samples.extend_from_slice(&beep);
+    samples.extend_from_slice(&gap);
+    samples.extend_from_slice(&beep);
+    samples
+}
@@ -0,0 +1,144 @@
+// 音量控制：在扬声器播放路径上做软件增益
+
+/// 手势/按键调节音量的步进
+pub const VOLUME_STEP: i8 = 10;
+
+/// 最大音量
+pub const MAX_VOLUME: u8 = 100;
+
+/// 音量设置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Volume {
+    /// 音量（0-100）
+    level: u8,
+    /// 是否静音（静音不改变音量值，取消静音后恢复）
+    muted: bool,
+}
+
+impl Default for Volume {
+    fn default() -> Self {
+        Self {
+            level: 60,
+            muted: false,
+        }
+    }
+}
+
+impl Volume {
+    /// 创建音量设置
+    ///
+    /// # 参数
+    /// * `level` - 音量（0-100），超出范围会被截断
+    /// * `muted` - 是否静音
+    pub fn new(level: u8, muted: bool) -> Self {
+        Self {
+            level: level.min(MAX_VOLUME),
+            muted,
+        }
+    }
+
+    /// 当前音量（0-100）
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    /// 是否静音
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    /// 设置音量
+    pub fn set_level(&mut self, level: u8) {
+        self.level = level.min(MAX_VOLUME);
+    }
+
+    /// 按增量调整音量，调大音量时自动取消静音
+    pub fn adjust(&mut self, delta: i8) {
+        let level = (self.level as i16 + delta as i16).clamp(0, MAX_VOLUME as i16);
+        self.level = level as u8;
+        if delta > 0 {
+            self.muted = false;
+        }
+    }
+
+    /// 设置静音
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+    }
+
+    /// 线性增益系数
+    ///
+    /// 人耳对响度的感知近似对数，使用平方曲线让低音量段调节更细腻。
+    pub fn gain(&self) -> f32 {
+        if self.muted {
+            return 0.0;
+        }
+        let ratio = self.level as f32 / MAX_VOLUME as f32;
+        ratio * ratio
+    }
+
+    /// 对样本应用音量增益
+    ///
+    /// # 参数
+    /// * `input` - 原始样本
+    /// * `output` - 输出缓冲区，长度需与`input`相同
+    pub fn apply(&self, input: &[i16], output: &mut [i16]) {
+        let gain = self.gain();
+        for (out, &sample) in output.iter_mut().zip(input) {
+            *out = (sample as f32 * gain) as i16;
+        }
+    }
+
+    /// 显示文本，例如"60%"或"静音"
+    pub fn label(&self) -> String {
+        if self.muted {
+            "静音".to_string()
+        } else {
+            format!("{}%", self.level)
+        }
+    }
+}
+
+/// 远程音量命令（来自服务端推送的`set_volume`设备命令）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeCommand {
+    /// 设置音量
+    Set(u8),
+    /// 调整音量
+    Adjust(i8),
+    /// 设置静音
+    Mute(bool),
+}
+
+impl VolumeCommand {
+    /// 从设备命令的字段创建音量命令
+    ///
+    /// # 参数
+    /// * `volume` - 音量（0-100）
+    /// * `delta` - 音量增量
+    /// * `mute` - 是否静音
+    ///
+    /// # 返回值
+    /// 三个字段必须且只能有一个，否则返回错误
+    pub fn from_fields(
+        volume: Option<u8>,
+        delta: Option<i8>,
+        mute: Option<bool>,
+    ) -> anyhow::Result<Self> {
+        match (volume, delta, mute) {
+            (Some(level), None, None) => Ok(VolumeCommand::Set(level)),
+            (None, Some(delta), None) => Ok(VolumeCommand::Adjust(delta)),
+            (None, None, Some(muted)) => Ok(VolumeCommand::Mute(muted)),
+            _ => anyhow::bail!("音量命令必须且只能包含volume、delta、mute之一"),
+        }
+    }
+
+    /// 将命令应用到音量设置
+    pub fn apply_to(&self, volume: &mut Volume) {
+        match *self {
+            VolumeCommand::Set(level) => volume.set_level(level),
+            VolumeCommand::Adjust(delta) => volume.adjust(delta),
+            VolumeCommand::Mute(muted) => volume.set_muted(muted),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+// DMA可访问的像素缓冲区
+//
+// SPI DMA只能访问内部RAM，普通Vec在启用PSRAM后可能被分配到PSRAM，
+// 因此传输用的缓冲区统一通过heap_caps_malloc在内部RAM中分配，创建后重复使用。
+
+use std::ops::{Deref, DerefMut};
+
+use anyhow::Result;
+use esp_idf_sys::{heap_caps_calloc, heap_caps_free, MALLOC_CAP_DMA, MALLOC_CAP_INTERNAL};
+
+/// DMA可访问的RGB565像素缓冲区
+pub struct DmaBuffer {
+    data: *mut u16,
+    len: usize,
+}
+
+// 缓冲区独占所有权，只通过&/&mut访问
+unsafe impl Send for DmaBuffer {}
+
+impl DmaBuffer {
+    /// 分配缓冲区
+    ///
+    /// # 参数
+    /// * `len` - 像素数
+    pub fn new(len: usize) -> Result<Self> {
+        let data = unsafe {
+            heap_caps_calloc(
+                len,
+                std::mem::size_of::<u16>(),
+                MALLOC_CAP_DMA | MALLOC_CAP_INTERNAL,
+            )
+        } as *mut u16;
+        if data.is_null() {
+            anyhow::bail!("DMA缓冲区分配失败: {} 像素", len);
+        }
+        Ok(Self { data, len })
+    }
+}
+
+impl Deref for DmaBuffer {
+    type Target = [u16];
+
+    fn deref(&self) -> &[u16] {
+        unsafe { std::slice::from_raw_parts(self.data, self.len) }
+    }
+}
+
+impl DerefMut for DmaBuffer {
+    fn deref_mut(&mut self) -> &mut [u16] {
+        unsafe { std::slice::from_raw_parts_mut(self.data, self.len) }
+    }
+}
+
+impl Drop for DmaBuffer {
+    fn drop(&mut self) {
+        unsafe { heap_caps_free(self.data as *mut _) };
+    }
+}
@@ -1,23 +1,42 @@
 use anyhow::Result;
-use esp_idf_hal::gpio::{Gpio5, PinDriver};
-use esp_idf_sys::st77916::{esp_lcd_new_panel_st77916, st77916_vendor_config_t};
+use esp_idf_hal::delay::TickType;
+use esp_idf_hal::gpio::{AnyInputPin, Input, InterruptType, PinDriver};
+use esp_idf_hal::task::notification::Notification;
+use esp_idf_sys::st77916::{
+    esp_lcd_new_panel_st77916, st77916_lcd_init_cmd_t, st77916_vendor_config_t,
+};
 use esp_idf_sys::*;
+use std::ffi::c_void;
+use std::fmt;
+use std::num::NonZeroU32;
 use std::ptr;
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc, Mutex, MutexGuard,
+};
+use std::time::{Duration, Instant};
 
-use super::lcd_cmds::get_vendor_specific_init_new;
+use super::dma_buffer::DmaBuffer;
+use super::lcd_cmds::InitSequence;
+use super::orientation::DisplayOrientation;
+use crate::blocking::{self, LCD_INIT_BUDGET};
+use crate::hal::{BitmapSink, DisplayDevice, PanelReinit, TransferTicket};
+use crate::metrics;
+use crate::peripherals::backlight::Backlight;
 
 // embedded-graphics相关导入
 use embedded_graphics::{
     draw_target::DrawTarget,
     geometry::{OriginDimensions, Size},
     pixelcolor::{Rgb565, RgbColor},
+    primitives::Rectangle,
     Pixel,
 };
 
 // ===================== 常量区 =====================
-// 分辨率 & 像素格式
-pub const LCD_WIDTH: i32 = 360;
-pub const LCD_HEIGHT: i32 = 360;
+// 分辨率 & 像素格式（面板物理分辨率，旋转后的逻辑分辨率见`LcdController::width/height`）
+pub const LCD_WIDTH: i32 = crate::boards::SPEC.display_width;
+pub const LCD_HEIGHT: i32 = crate::boards::SPEC.display_height;
 pub const LCD_BIT_PER_PIXEL: u8 = 16; // RGB565
 
 // QSPI 引脚映射（根据硬件连接）
@@ -30,42 +49,688 @@ pub const QSPI_PIN_NUM_LCD_SDA2: i32 = gpio_num_t_GPIO_NUM_42; // LCD_SDA2 (DATA
 pub const QSPI_PIN_NUM_LCD_SDA3: i32 = gpio_num_t_GPIO_NUM_41; // LCD_SDA3 (DATA3)
 pub const QSPI_PIN_NUM_LCD_TE: i32 = gpio_num_t_GPIO_NUM_18; // LCD_TE (Tearing Effect)
 pub const QSPI_PIN_NUM_LCD_BL: i32 = gpio_num_t_GPIO_NUM_5; // LCD_BL (背光)
-pub const QSPI_PIN_NUM_LCD_RST: i32 = gpio_num_t_GPIO_NUM_NC; // LCD_RST
+pub const QSPI_PIN_NUM_LCD_RST: i32 = gpio_num_t_GPIO_NUM_NC; // LCD_RST（接在TCA9554上，见`LcdResetLine`）
+
+// 硬件复位时序：复位保持时间与释放后的等待时间
+const LCD_RESET_HOLD: Duration = Duration::from_millis(10);
+const LCD_RESET_SETTLE: Duration = Duration::from_millis(120);
+
+/// 绘制用临时缓冲区的行数
+const SCRATCH_ROWS: usize = 20;
+
+// QSPI命令格式：写命令操作码放在最高字节，命令放在中间字节
+const LCD_OPCODE_WRITE_CMD: u32 = 0x02;
+
+// 面板命令
+const LCD_CMD_TEON: u8 = 0x35; // 打开TE输出
+const LCD_CMD_PAGE: u8 = 0xF0; // 切换命令页
+const LCD_CMD_PGAMMA: u8 = 0xE0; // 正极性伽马（第2页）
+const LCD_CMD_NGAMMA: u8 = 0xE1; // 负极性伽马（第2页）
+const LCD_PAGE_GAMMA: u8 = 0x02;
+const LCD_PAGE_USER: u8 = 0x00;
+
+/// 面板厂商初始化序列中的伽马曲线
+const VENDOR_GAMMA: GammaTable = GammaTable {
+    positive: [
+        0xF0, 0x0A, 0x10, 0x09, 0x09, 0x36, 0x35, 0x33, 0x4A, 0x29, 0x15, 0x15, 0x2E, 0x34,
+    ],
+    negative: [
+        0xF0, 0x0A, 0x0F, 0x08, 0x08, 0x05, 0x34, 0x33, 0x4A, 0x39, 0x15, 0x15, 0x2D, 0x33,
+    ],
+};
+
+/// 等待DMA传输完成的最长时间，远大于整屏传输时间，超时说明SPI出现异常
+const TRANSFER_WAIT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// 单次阻塞在完成信号量上的最长时间
+///
+/// 二值信号量一次只唤醒一个等待方，多个线程同时等待时其余线程最迟在这个时间后重新检查计数。
+const TRANSFER_SIGNAL_WAIT: Duration = Duration::from_millis(10);
+
+/// 等待TE信号的最长时间，面板刷新率约60Hz，超过两帧没有信号视为TE失效
+const TE_WAIT_TIMEOUT: Duration = Duration::from_millis(40);
+
+/// ESP32-S3的SPI控制器支持的最高时钟
+const MAX_PCLK_HZ: u32 = 80 * 1000 * 1000;
+
+/// 基准测试中每项操作重复的次数
+const BENCHMARK_ROUNDS: u32 = 10;
+/// 基准测试中位图的边长
+const BENCHMARK_BLIT_SIZE: i32 = 80;
 
 // =================================================
 
+/// 异步传输计数
+///
+/// `queued`在排队传输时递增，`done`在传输完成中断中递增，
+/// 两者比较即可知道某次传输是否完成，不需要加锁。
+/// 中断同时释放一个二值信号量，等待方阻塞在信号量上，不会空转占满CPU饿死IDLE任务。
+/// 其他esp_lcd面板驱动（如GC9A01）也用它配合`LcdBitmapPort`跟踪传输。
+pub struct TransferState {
+    queued: AtomicU32,
+    done: AtomicU32,
+    /// 连续失败（排队出错或等待超时）的次数，传输成功完成时清零
+    errors: AtomicU32,
+    /// 传输完成信号（二值信号量）
+    signal: QueueHandle_t,
+}
+
+// 信号量句柄可以在任意任务和中断中使用
+unsafe impl Send for TransferState {}
+unsafe impl Sync for TransferState {}
+
+impl TransferState {
+    /// 创建传输计数与完成信号量
+    pub fn new() -> Result<Arc<Self>> {
+        let signal = unsafe { xQueueGenericCreate(1, 0, queueQUEUE_TYPE_BINARY_SEMAPHORE as u8) };
+        if signal.is_null() {
+            anyhow::bail!("创建LCD传输信号量失败");
+        }
+
+        Ok(Arc::new(Self {
+            queued: AtomicU32::new(0),
+            done: AtomicU32::new(0),
+            errors: AtomicU32::new(0),
+            signal,
+        }))
+    }
+
+    /// 作为`on_color_trans_done`回调上下文的指针
+    ///
+    /// 指针在`Arc`释放前有效，调用方需保证面板IO在此之前删除。
+    pub fn callback_context(self: &Arc<Self>) -> *mut c_void {
+        Arc::as_ptr(self) as *mut c_void
+    }
+
+    /// 阻塞到下一次传输完成或超时
+    ///
+    /// 信号量可能是之前某次传输留下的，调用方需要在返回后重新检查计数。
+    fn wait_signal(&self, timeout: Duration) {
+        unsafe { xQueueSemaphoreTake(self.signal, TickType::from(timeout).ticks()) };
+    }
+}
+
+impl Drop for TransferState {
+    fn drop(&mut self) {
+        unsafe { vQueueDelete(self.signal) };
+    }
+}
+
+/// 颜色数据传输完成回调（在中断上下文中执行）
+///
+/// `user_ctx`必须是`TransferState::callback_context`返回的指针。
+pub unsafe extern "C" fn on_color_trans_done(
+    _panel_io: esp_lcd_panel_io_handle_t,
+    _edata: *mut esp_lcd_panel_io_event_data_t,
+    user_ctx: *mut c_void,
+) -> bool {
+    let state = &*(user_ctx as *const TransferState);
+    state.done.fetch_add(1, Ordering::Release);
+
+    let mut woken: BaseType_t = 0;
+    xQueueGiveFromISR(state.signal, &mut woken);
+    // 返回true时驱动在中断结束后切换到被唤醒的等待任务
+    woken != 0
+}
+
+/// 面板的颜色分量顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RgbOrder {
+    Rgb,
+    Bgr,
+}
+
+impl RgbOrder {
+    fn element_order(self) -> lcd_rgb_element_order_t {
+        match self {
+            RgbOrder::Rgb => lcd_rgb_element_order_t_LCD_RGB_ELEMENT_ORDER_RGB,
+            RgbOrder::Bgr => lcd_rgb_element_order_t_LCD_RGB_ELEMENT_ORDER_BGR,
+        }
+    }
+}
+
+/// ST77916伽马寄存器的内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GammaTable {
+    /// 正极性曲线（E0h）
+    pub positive: [u8; 14],
+    /// 负极性曲线（E1h）
+    pub negative: [u8; 14],
+}
+
+/// 伽马曲线
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GammaProfile {
+    /// 面板厂商初始化序列中的曲线
+    Vendor,
+    /// 不写伽马寄存器，使用芯片复位后的默认曲线
+    ChipDefault,
+    /// 其他批次面板的厂商曲线
+    Custom(GammaTable),
+}
+
+impl GammaProfile {
+    /// 要写入的曲线，为None时保持芯片默认值
+    fn table(&self) -> Option<&GammaTable> {
+        match self {
+            GammaProfile::Vendor => Some(&VENDOR_GAMMA),
+            GammaProfile::ChipDefault => None,
+            GammaProfile::Custom(table) => Some(table),
+        }
+    }
+}
+
+/// 面板参数：QSPI总线与颜色设置
+///
+/// 默认值与面板厂商的示例一致。调整总线参数后启用`lcd-benchmark`特性，启动时会运行
+/// [`LcdController::benchmark`]并打印结果，确认画面正常且速度确实有提升；
+/// 调整颜色设置后在测试图界面检查三原色与灰阶。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LcdConfig {
+    /// 像素时钟(Hz)，最高80MHz
+    pub pclk_hz: u32,
+    /// SPI驱动中最多同时排队的传输数，排满后`draw_bitmap_async`会阻塞
+    pub trans_queue_depth: usize,
+    /// 单次SPI传输的最大字节数，更大的位图由驱动拆分发送；0表示使用驱动默认值（4092字节）
+    pub max_transfer_sz: usize,
+    /// 颜色分量顺序，红色显示成蓝色时改为另一种
+    pub rgb_order: RgbOrder,
+    /// 是否反色显示，ST77916面板通常需要打开
+    pub invert_colors: bool,
+    /// 伽马曲线
+    pub gamma_profile: GammaProfile,
+}
+
+impl LcdConfig {
+    pub const DEFAULT: Self = Self {
+        pclk_hz: 80 * 1000 * 1000,
+        trans_queue_depth: 10,
+        max_transfer_sz: 0,
+        rgb_order: RgbOrder::Rgb,
+        invert_colors: true,
+        gamma_profile: GammaProfile::Vendor,
+    };
+
+    /// 检查参数是否在硬件支持的范围内
+    pub fn validate(&self) -> Result<()> {
+        if self.pclk_hz == 0 || self.pclk_hz > MAX_PCLK_HZ {
+            anyhow::bail!(
+                "LCD像素时钟{}Hz超出范围（最高{}Hz）",
+                self.pclk_hz,
+                MAX_PCLK_HZ
+            );
+        }
+        if self.trans_queue_depth == 0 {
+            anyhow::bail!("LCD传输队列深度不能为0");
+        }
+        if self.max_transfer_sz % 2 != 0 {
+            anyhow::bail!("LCD单次传输字节数必须是整像素（偶数）");
+        }
+        Ok(())
+    }
+}
+
+impl Default for LcdConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// [`LcdController::benchmark`]的结果，均为单次操作的平均耗时
+#[derive(Debug, Clone, Copy)]
+pub struct LcdBenchmark {
+    /// 整屏纯色填充
+    pub full_fill: Duration,
+    /// 80x80位图
+    pub blit: Duration,
+}
+
+impl fmt::Display for LcdBenchmark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let full_bytes = (LCD_WIDTH * LCD_HEIGHT * 2) as f64;
+        let blit_bytes = (BENCHMARK_BLIT_SIZE * BENCHMARK_BLIT_SIZE * 2) as f64;
+        // 字节数除以微秒即为MB/s
+        let throughput = |bytes: f64, time: Duration| bytes / time.as_micros().max(1) as f64;
+        write!(
+            f,
+            "整屏填充 {:.1}ms ({:.1}MB/s), {}x{}位图 {:.2}ms ({:.1}MB/s)",
+            self.full_fill.as_secs_f64() * 1000.0,
+            throughput(full_bytes, self.full_fill),
+            BENCHMARK_BLIT_SIZE,
+            BENCHMARK_BLIT_SIZE,
+            self.blit.as_secs_f64() * 1000.0,
+            throughput(blit_bytes, self.blit),
+        )
+    }
+}
+
+/// LCD硬件复位线
+///
+/// 复位脚不在ESP32上时（例如接在IO扩展芯片上），由实现方负责控制电平。
+pub trait LcdResetLine {
+    /// 设置复位状态，`asserted`为true时面板保持复位
+    fn set_reset(&mut self, asserted: bool) -> Result<()>;
+}
+
+/// 面板初始化状态
+///
+/// 控制器与显示线程的[`LcdPanelReinit`]共享。运行时修改的方向与TE开关记录在这里，
+/// 显示线程重新初始化面板后按当前状态恢复。
+struct PanelSetup {
+    panel: esp_lcd_panel_handle_t,
+    io_handle: esp_lcd_panel_io_handle_t,
+    config: LcdConfig,
+    orientation: DisplayOrientation,
+    /// TE输出是否已打开，重新初始化后需要再次打开
+    te_output: bool,
+}
+
+// 面板句柄在LcdController生命周期内有效，面板命令可以在任意任务中发送
+unsafe impl Send for PanelSetup {}
+
+impl PanelSetup {
+    /// 启动显示器
+    fn start_display(&self) -> Result<()> {
+        unsafe {
+            esp!(esp_lcd_panel_reset(self.panel))?;
+
+            // 等待重置完成
+            std::thread::sleep(std::time::Duration::from_millis(120));
+
+            // 步骤2：初始化面板
+            esp!(esp_lcd_panel_init(self.panel))?;
+            esp!(esp_lcd_panel_invert_color(
+                self.panel,
+                self.config.invert_colors
+            ))?;
+        }
+        self.apply_gamma()?;
+
+        // 步骤3：设置显示方向
+        self.apply_orientation()?;
+
+        unsafe {
+            // 步骤4：先关闭显示，清除GRAM，再开启显示
+            esp!(esp_lcd_panel_disp_on_off(self.panel, false))?;
+            std::thread::sleep(std::time::Duration::from_millis(50));
+
+            esp!(esp_lcd_panel_disp_on_off(self.panel, true))?;
+        }
+
+        Ok(())
+    }
+
+    /// 发送带参数的面板命令
+    fn tx_param(&self, cmd: u8, params: &[u8]) -> Result<()> {
+        let lcd_cmd = ((LCD_OPCODE_WRITE_CMD << 24) | ((cmd as u32) << 8)) as i32;
+        unsafe {
+            esp!(esp_lcd_panel_io_tx_param(
+                self.io_handle,
+                lcd_cmd,
+                params.as_ptr() as *const _,
+                params.len()
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// 写入配置的伽马曲线
+    fn apply_gamma(&self) -> Result<()> {
+        let Some(table) = self.config.gamma_profile.table() else {
+            return Ok(());
+        };
+        self.tx_param(LCD_CMD_PAGE, &[LCD_PAGE_GAMMA])?;
+        self.tx_param(LCD_CMD_PGAMMA, &table.positive)?;
+        self.tx_param(LCD_CMD_NGAMMA, &table.negative)?;
+        self.tx_param(LCD_CMD_PAGE, &[LCD_PAGE_USER])
+    }
+
+    /// 打开面板的TE输出
+    ///
+    /// 之后在刷新线程中用`TeSync`等待TE信号，等到垂直消隐期再开始传输。
+    fn enable_te_output(&mut self) -> Result<()> {
+        // 参数0：只在垂直消隐期输出TE
+        self.tx_param(LCD_CMD_TEON, &[0x00])?;
+        self.te_output = true;
+        log::info!("LCD TE输出已打开");
+        Ok(())
+    }
+
+    /// 把当前显示方向写入面板
+    fn apply_orientation(&self) -> Result<()> {
+        let (swap_xy, mirror_x, mirror_y) = self.orientation.panel_transform();
+        unsafe {
+            esp!(esp_lcd_panel_swap_xy(self.panel, swap_xy))?;
+            esp!(esp_lcd_panel_mirror(self.panel, mirror_x, mirror_y))?;
+        }
+        Ok(())
+    }
+}
+
+/// 面板重新初始化句柄，交给显示线程使用
+///
+/// QSPI线路受到干扰后面板可能停在错误状态，之后的传输持续失败。重新初始化包含
+/// 复位等待，在显示线程中执行，不占用主循环。
+pub struct LcdPanelReinit {
+    port: LcdBitmapPort,
+    setup: Arc<Mutex<PanelSetup>>,
+}
+
+impl PanelReinit for LcdPanelReinit {
+    /// 等待已排队的传输，重新执行复位与初始化序列，并恢复反色、伽马、方向与TE输出
+    fn reinit(&mut self) -> Result<()> {
+        self.port.drain_transfers();
+
+        let mut setup = self.setup.lock().unwrap();
+        blocking::with_budget("lcd_reinit", LCD_INIT_BUDGET, || setup.start_display())?;
+        if setup.te_output {
+            setup.enable_te_output()?;
+        }
+        log::info!("LCD面板已重新初始化");
+        Ok(())
+    }
+}
+
 pub struct LcdController {
     panel: esp_lcd_panel_handle_t,
     io_handle: esp_lcd_panel_io_handle_t,
-    backlight: PinDriver<'static, esp_idf_hal::gpio::Gpio5, esp_idf_hal::gpio::Output>,
+    backlight: Backlight,
+    orientation: DisplayOrientation,
+    /// 面板初始化状态，与显示线程的`LcdPanelReinit`共享，显示线程重新初始化面板时会持有锁，
+    /// 主线程只在发送面板命令时访问
+    setup: Arc<Mutex<PanelSetup>>,
+    /// 面板初始化命令，驱动只保存命令表的指针，命令表与其指向的参数必须与面板同时存在
+    _init_sequence: InitSequence,
+    _init_cmds: Vec<st77916_lcd_init_cmd_t>,
+    /// 位图传输端口，持有传输计数
+    port: LcdBitmapPort,
+    /// 直接绘制（填充、像素块）时重复使用的DMA缓冲区，避免每次绘制分配内存
+    scratch: DmaBuffer,
+}
+
+/// 位图传输端口
+///
+/// 只包含发送位图所需的面板句柄与传输计数，可以交给刷新线程使用。
+/// 面板命令（方向、TE开关等）仍由`LcdController`发送，调用方需要保证
+/// 发送命令时端口上没有正在进行的传输（见`wait_idle`）。
+#[derive(Clone)]
+pub struct LcdBitmapPort {
+    panel: esp_lcd_panel_handle_t,
+    /// 异步传输计数，地址作为回调上下文传给SPI驱动，必须在控制器生命周期内保持不变
+    transfers: Arc<TransferState>,
+}
+
+// 面板句柄在LcdController生命周期内有效，esp_lcd的位图传输可以在任意任务中调用
+unsafe impl Send for LcdBitmapPort {}
+
+impl LcdBitmapPort {
+    /// 创建传输端口
+    ///
+    /// # 参数
+    /// * `panel` - esp_lcd面板句柄，必须在端口使用期间有效
+    /// * `transfers` - 传输计数，其回调上下文已注册到面板IO
+    pub fn new(panel: esp_lcd_panel_handle_t, transfers: Arc<TransferState>) -> Self {
+        Self { panel, transfers }
+    }
+
+    /// 传输是否已经完成
+    pub fn is_transfer_done(&self, ticket: TransferTicket) -> bool {
+        let done = self.transfers.done.load(Ordering::Acquire);
+        // 计数会回绕，用差值判断先后
+        done.wrapping_sub(ticket.0) as i32 >= 0
+    }
+
+    /// 重新初始化面板前等待已排队的传输完成
+    ///
+    /// 超时后卡住的传输仍留在SPI驱动队列中，完成计数不做修改：驱动按顺序完成传输，
+    /// 迟到的完成回调照常计数，之后排队的传输只有在它们之后完成时才会被判定为完成，
+    /// 不会提前复用仍在发送的DMA缓冲区。
+    pub fn drain_transfers(&self) {
+        let last = self.transfers.queued.load(Ordering::Acquire);
+        if self.wait_done(TransferTicket(last)).is_err() {
+            let done = self.transfers.done.load(Ordering::Acquire);
+            log::warn!("LCD仍有{}个传输未完成", last.wrapping_sub(done));
+        }
+    }
+
+    /// 阻塞等待传输完成，超过`TRANSFER_WAIT_TIMEOUT`返回错误
+    fn wait_done(&self, ticket: TransferTicket) -> Result<()> {
+        let start = Instant::now();
+        while !self.is_transfer_done(ticket) {
+            let elapsed = start.elapsed();
+            if elapsed > TRANSFER_WAIT_TIMEOUT {
+                anyhow::bail!("等待LCD传输完成超时");
+            }
+            self.transfers
+                .wait_signal(TRANSFER_SIGNAL_WAIT.min(TRANSFER_WAIT_TIMEOUT - elapsed));
+        }
+        Ok(())
+    }
+
+    /// 记录一次失败的传输
+    fn record_error(&self) {
+        self.transfers.errors.fetch_add(1, Ordering::AcqRel);
+        metrics::increment(metrics::LCD_TRANSFER_ERRORS, 1);
+    }
+}
+
+impl BitmapSink for LcdBitmapPort {
+    /// 把位图传输放入DMA队列后立即返回
+    ///
+    /// 调用方必须保证`color_data`在传输完成（`wait_transfer`返回）之前不被修改或释放，
+    /// 通常用两个缓冲区交替：CPU填充一个的同时DMA发送另一个。
+    ///
+    /// # 返回值
+    /// 本次传输的凭据
+    fn draw_bitmap_async(
+        &self,
+        x_start: i32,
+        y_start: i32,
+        x_end: i32,
+        y_end: i32,
+        color_data: &[u16],
+    ) -> Result<TransferTicket> {
+        let expected_len = ((x_end - x_start) * (y_end - y_start)) as usize;
+        if color_data.len() != expected_len {
+            return Err(anyhow::anyhow!("颜色数据长度不匹配"));
+        }
+
+        let result = unsafe {
+            esp!(esp_lcd_panel_draw_bitmap(
+                self.panel,
+                x_start,
+                y_start,
+                x_end,
+                y_end,
+                color_data.as_ptr() as *const _
+            ))
+        };
+        if let Err(e) = result {
+            self.record_error();
+            return Err(e.into());
+        }
+
+        let ticket = self.transfers.queued.fetch_add(1, Ordering::AcqRel) + 1;
+        Ok(TransferTicket(ticket))
+    }
+
+    /// 等待指定传输完成
+    fn wait_transfer(&self, ticket: TransferTicket) -> Result<()> {
+        if let Err(e) = self.wait_done(ticket) {
+            self.record_error();
+            return Err(e);
+        }
+        self.transfers.errors.store(0, Ordering::Release);
+        Ok(())
+    }
+
+    /// 等待所有已排队的传输完成
+    fn wait_idle(&self) -> Result<()> {
+        let last = self.transfers.queued.load(Ordering::Acquire);
+        self.wait_transfer(TransferTicket(last))
+    }
+
+    fn transfer_errors(&self) -> u32 {
+        self.transfers.errors.load(Ordering::Acquire)
+    }
+}
+
+/// TE（Tearing Effect）同步
+///
+/// 面板在每帧刷新的垂直消隐期开始时拉高TE引脚，在上升沿之后开始传输可以避免撕裂。
+/// 中断只在等待时使能，平时不会每帧产生中断。
+pub struct TeSync {
+    pin: PinDriver<'static, AnyInputPin, Input>,
+    /// 中断通知，发送给创建它的任务，因此必须在执行刷新的线程中创建
+    notification: Notification,
+    /// 连续等待超时的次数，用于只在TE失效时打印一次警告
+    missed: u32,
+}
+
+impl TeSync {
+    /// 在TE引脚上注册上升沿中断
+    ///
+    /// 面板的TE输出需要先通过`LcdController::enable_te_output`打开。
+    ///
+    /// # 参数
+    /// * `te_pin` - 连接面板TE输出的引脚
+    pub fn new(te_pin: AnyInputPin) -> Result<Self> {
+        let mut pin = PinDriver::input(te_pin)?;
+        pin.set_interrupt_type(InterruptType::PosEdge)?;
+
+        let notification = Notification::new();
+        let notifier = notification.notifier();
+        // 中断回调中只发送任务通知
+        unsafe {
+            pin.subscribe(move || {
+                notifier.notify_and_yield(NonZeroU32::new(1).unwrap());
+            })?;
+        }
+
+        Ok(Self {
+            pin,
+            notification,
+            missed: 0,
+        })
+    }
+
+    /// 等待下一次TE信号
+    ///
+    /// TE信号超时时不报错，直接返回让刷新继续进行。
+    pub fn wait(&mut self) -> Result<()> {
+        // 清除之前残留的通知，保证等到的是下一次上升沿
+        self.notification.wait(0);
+        self.pin.enable_interrupt()?;
+
+        match self
+            .notification
+            .wait(TickType::from(TE_WAIT_TIMEOUT).ticks())
+        {
+            Some(_) => self.missed = 0,
+            None => {
+                self.pin.disable_interrupt()?;
+                if self.missed == 0 {
+                    log::warn!("等待LCD TE信号超时，检查TE接线");
+                }
+                self.missed = self.missed.saturating_add(1);
+            }
+        }
+        Ok(())
+    }
 }
 
 impl LcdController {
-    /// 创建新的LCD控制器实例
-    pub fn new(bl_io: Gpio5) -> Result<Self> {
+    /// 创建新的LCD控制器实例，使用面板厂商的初始化序列
+    ///
+    /// # 参数
+    /// * `backlight` - PWM背光
+    /// * `orientation` - 显示方向
+    /// * `config` - 面板参数
+    /// * `reset` - 硬件复位线，为None时只发送软件复位命令
+    pub fn new(
+        backlight: Backlight,
+        orientation: DisplayOrientation,
+        config: LcdConfig,
+        reset: Option<&mut dyn LcdResetLine>,
+    ) -> Result<Self> {
+        Self::with_init_sequence(
+            backlight,
+            orientation,
+            config,
+            InitSequence::vendor(),
+            reset,
+        )
+    }
+
+    /// 使用指定的初始化序列创建LCD控制器，用于其他批次的面板
+    ///
+    /// # 参数
+    /// * `init_sequence` - 面板初始化命令，见`InitSequenceBuilder`与`InitSequence::parse`
+    /// * 其余参数同`new`
+    pub fn with_init_sequence(
+        backlight: Backlight,
+        orientation: DisplayOrientation,
+        config: LcdConfig,
+        init_sequence: InitSequence,
+        reset: Option<&mut dyn LcdResetLine>,
+    ) -> Result<Self> {
+        config.validate()?;
+        anyhow::ensure!(!init_sequence.is_empty(), "LCD初始化序列为空");
+
+        // 步骤0：硬件复位，必须在发送任何命令之前完成
+        if let Some(reset) = reset {
+            Self::hardware_reset(reset)?;
+        }
+
         // 步骤1：初始化SPI总线
-        let io_handle = Self::init_spi_bus()?;
+        let transfers = TransferState::new()?;
+        let io_handle = Self::init_spi_bus(&config, &transfers)?;
 
         // 步骤2：创建LCD面板
-        let panel = Self::create_panel(io_handle)?;
-
-        // 步骤3：初始化背光控制
-        let backlight = Self::init_backlight(bl_io)?;
+        let init_cmds = init_sequence.to_raw();
+        let panel = Self::create_panel(io_handle, config.rgb_order, &init_cmds)?;
 
-        // 步骤4：启动显示器
+        // 步骤3：启动显示器
         let controller = LcdController {
             panel,
             io_handle,
             backlight,
+            orientation,
+            setup: Arc::new(Mutex::new(PanelSetup {
+                panel,
+                io_handle,
+                config,
+                orientation,
+                te_output: false,
+            })),
+            _init_sequence: init_sequence,
+            _init_cmds: init_cmds,
+            port: LcdBitmapPort::new(panel, transfers),
+            scratch: DmaBuffer::new(LCD_WIDTH.max(LCD_HEIGHT) as usize * SCRATCH_ROWS)?,
         };
 
-        controller.start_display()?;
+        // 面板复位与初始化包含固定等待，只在启动时执行一次
+        blocking::with_budget("lcd_start_display", LCD_INIT_BUDGET, || {
+            controller.setup().start_display()
+        })?;
 
         Ok(controller)
     }
 
+    /// 拉低复位线后释放，等待面板内部复位完成
+    fn hardware_reset(reset: &mut dyn LcdResetLine) -> Result<()> {
+        reset.set_reset(true)?;
+        std::thread::sleep(LCD_RESET_HOLD);
+        reset.set_reset(false)?;
+        std::thread::sleep(LCD_RESET_SETTLE);
+        Ok(())
+    }
+
     /// 初始化QSPI总线（使用官方推荐的配置）
-    fn init_spi_bus() -> Result<esp_lcd_panel_io_handle_t> {
+    fn init_spi_bus(
+        config: &LcdConfig,
+        transfers: &Arc<TransferState>,
+    ) -> Result<esp_lcd_panel_io_handle_t> {
         unsafe {
             // 步骤1：修复QSPI引脚映射（标准QSPI配置）
             let bus_config = spi_bus_config_t {
@@ -82,7 +747,7 @@ impl LcdController {
                 __bindgen_anon_4: spi_bus_config_t__bindgen_ty_4 {
                     data3_io_num: QSPI_PIN_NUM_LCD_SDA3,
                 },
-                // max_transfer_sz: LCD_WIDTH * 80 * 2,
+                max_transfer_sz: config.max_transfer_sz as i32,
                 ..Default::default()
             };
 
@@ -108,10 +773,10 @@ impl LcdController {
             cs_gpio_num: QSPI_PIN_NUM_LCD_CS,
             dc_gpio_num: -1, // QSPI模式不需要DC引脚
             spi_mode: 0,
-            pclk_hz: 80 * 1000 * 1000,
-            trans_queue_depth: 10,
-            on_color_trans_done: None,
-            user_ctx: ptr::null_mut(),
+            pclk_hz: config.pclk_hz,
+            trans_queue_depth: config.trans_queue_depth,
+            on_color_trans_done: Some(on_color_trans_done),
+            user_ctx: transfers.callback_context(),
             lcd_cmd_bits: 32,  // QSPI使用32位命令
             lcd_param_bits: 8, // 8位参数
             flags,
@@ -129,19 +794,22 @@ impl LcdController {
     }
 
     /// 创建LCD面板
-    fn create_panel(io_handle: esp_lcd_panel_io_handle_t) -> Result<esp_lcd_panel_handle_t> {
+    fn create_panel(
+        io_handle: esp_lcd_panel_io_handle_t,
+        rgb_order: RgbOrder,
+        init_cmds: &[st77916_lcd_init_cmd_t],
+    ) -> Result<esp_lcd_panel_handle_t> {
         let mut panel: esp_lcd_panel_handle_t = ptr::null_mut();
 
-        let st77916_init_cmds = get_vendor_specific_init_new();
         let mut vendor_config = st77916_vendor_config_t::default();
         vendor_config.flags.set_use_qspi_interface(1);
-        vendor_config.init_cmds = st77916_init_cmds.as_ptr() as *const _;
-        vendor_config.init_cmds_size = st77916_init_cmds.len() as u16;
+        vendor_config.init_cmds = init_cmds.as_ptr();
+        vendor_config.init_cmds_size = init_cmds.len() as u16;
 
         let panel_config = esp_lcd_panel_dev_config_t {
-            reset_gpio_num: QSPI_PIN_NUM_LCD_RST, // LCD_RST连接到TCA9554扩展IO，暂时不使用
+            reset_gpio_num: QSPI_PIN_NUM_LCD_RST, // LCD_RST连接到TCA9554扩展IO，由`LcdResetLine`控制
             __bindgen_anon_1: esp_lcd_panel_dev_config_t__bindgen_ty_1 {
-                rgb_ele_order: lcd_rgb_element_order_t_LCD_RGB_ELEMENT_ORDER_RGB,
+                rgb_ele_order: rgb_order.element_order(),
             },
             data_endian: lcd_rgb_data_endian_t_LCD_RGB_DATA_ENDIAN_BIG,
             bits_per_pixel: LCD_BIT_PER_PIXEL as u32,
@@ -162,41 +830,45 @@ impl LcdController {
         Ok(panel)
     }
 
-    /// 初始化背光控制
-    fn init_backlight(
-        bl_io: Gpio5,
-    ) -> Result<PinDriver<'static, esp_idf_hal::gpio::Gpio5, esp_idf_hal::gpio::Output>> {
-        let mut backlight = PinDriver::output(bl_io)?;
-        backlight.set_high()?; // 默认开启背光
-        Ok(backlight)
+    fn setup(&self) -> MutexGuard<'_, PanelSetup> {
+        self.setup.lock().unwrap()
     }
 
-    /// 启动显示器
-    fn start_display(&self) -> Result<()> {
-        unsafe {
-            esp!(esp_lcd_panel_reset(self.panel))?;
-
-            // 等待重置完成
-            std::thread::sleep(std::time::Duration::from_millis(120));
-
-            // 步骤2：初始化面板
-            esp!(esp_lcd_panel_init(self.panel))?;
+    /// 打开面板的TE输出
+    ///
+    /// 之后在刷新线程中用`TeSync`等待TE信号，等到垂直消隐期再开始传输。
+    pub fn enable_te_output(&mut self) -> Result<()> {
+        self.setup().enable_te_output()
+    }
 
-            // 步骤3：设置显示方向（尝试不同配置）
-            esp!(esp_lcd_panel_swap_xy(self.panel, false))?; // 不交换XY轴
-            esp!(esp_lcd_panel_mirror(self.panel, false, false))?; // 不镜像
+    /// 运行时修改显示方向
+    ///
+    /// 面板中已有的内容不会重新排列，调用方需要重绘整屏。
+    pub fn set_orientation(&mut self, orientation: DisplayOrientation) -> Result<()> {
+        self.orientation = orientation;
+        let mut setup = self.setup();
+        setup.orientation = orientation;
+        setup.apply_orientation()
+    }
 
-            // 步骤4：先关闭显示，清除GRAM，再开启显示
-            esp!(esp_lcd_panel_disp_on_off(self.panel, false))?;
-            std::thread::sleep(std::time::Duration::from_millis(50));
+    /// 当前显示方向
+    pub fn orientation(&self) -> DisplayOrientation {
+        self.orientation
+    }
 
-            esp!(esp_lcd_panel_disp_on_off(self.panel, true))?;
-        }
+    /// 旋转后的逻辑宽度
+    pub fn width(&self) -> i32 {
+        self.orientation.logical_size(LCD_WIDTH, LCD_HEIGHT).0
+    }
 
-        Ok(())
+    /// 旋转后的逻辑高度
+    pub fn height(&self) -> i32 {
+        self.orientation.logical_size(LCD_WIDTH, LCD_HEIGHT).1
     }
 
-    /// 绘制位图到指定区域
+    /// 绘制位图到指定区域，等待传输完成后返回
+    ///
+    /// 返回后`color_data`可以立即修改或释放。
     pub fn draw_bitmap(
         &self,
         x_start: i32,
@@ -205,42 +877,58 @@ impl LcdController {
         y_end: i32,
         color_data: &[u16],
     ) -> Result<()> {
-        // if x_start < 0 || y_start < 0 || x_end > LCD_WIDTH || y_end > LCD_HEIGHT {
-        //     return Err(anyhow::anyhow!("坐标超出屏幕范围"));
-        // }
+        let ticket = self.draw_bitmap_async(x_start, y_start, x_end, y_end, color_data)?;
+        self.wait_transfer(ticket)
+    }
 
-        let expected_len = ((x_end - x_start) * (y_end - y_start)) as usize;
-        if color_data.len() != expected_len {
-            return Err(anyhow::anyhow!("颜色数据长度不匹配"));
-        }
+    /// 把位图传输放入DMA队列后立即返回，约束见`LcdBitmapPort::draw_bitmap_async`
+    pub fn draw_bitmap_async(
+        &self,
+        x_start: i32,
+        y_start: i32,
+        x_end: i32,
+        y_end: i32,
+        color_data: &[u16],
+    ) -> Result<TransferTicket> {
+        self.port
+            .draw_bitmap_async(x_start, y_start, x_end, y_end, color_data)
+    }
 
-        unsafe {
-            esp!(esp_lcd_panel_draw_bitmap(
-                self.panel,
-                x_start,
-                y_start,
-                x_end,
-                y_end,
-                color_data.as_ptr() as *const _
-            ))?;
-        }
+    /// 等待指定传输完成
+    pub fn wait_transfer(&self, ticket: TransferTicket) -> Result<()> {
+        self.port.wait_transfer(ticket)
+    }
 
-        Ok(())
+    /// 等待所有已排队的传输完成
+    pub fn wait_idle(&self) -> Result<()> {
+        self.port.wait_idle()
     }
 
     /// 设置背光状态
     pub fn set_backlight(&mut self, on: bool) -> Result<()> {
+        self.backlight.set_on(on)
+    }
+
+    /// 设置背光亮度（0-100）
+    pub fn set_brightness(&mut self, percent: u8) -> Result<()> {
+        self.backlight.set_brightness(percent)
+    }
+
+    /// 打开或关闭显示：关闭时先关背光再关面板，打开时顺序相反，避免背光下看到面板切换
+    pub fn set_power(&mut self, on: bool) -> Result<()> {
         if on {
-            self.backlight.set_high()?;
+            unsafe { esp!(esp_lcd_panel_disp_on_off(self.panel, true))? };
+            self.set_backlight(true)
         } else {
-            self.backlight.set_low()?;
+            self.set_backlight(false)?;
+            unsafe { esp!(esp_lcd_panel_disp_on_off(self.panel, false))? };
+            Ok(())
         }
-        Ok(())
     }
 
     /// 绘制单个像素
     pub fn draw_pixel(&self, x: i32, y: i32, color: u16) -> Result<()> {
-        if x < 0 || y < 0 || x >= LCD_WIDTH || y >= LCD_HEIGHT {
+        if x < 0 || y < 0 || x >= self.width() || y >= self.height() {
             return Ok(()); // 超出边界直接返回
         }
 
@@ -254,6 +942,88 @@ impl LcdController {
         let raw = ((c.r() as u16) << 11) | ((c.g() as u16) << 5) | (c.b() as u16);
         raw.swap_bytes() // ST77916/ILI 等常见面板要求大端序
     }
+
+    /// 用纯色填充矩形区域（半开区间）
+    ///
+    /// 临时缓冲区只填充一次颜色，之后各分块重复发送同一块数据，不需要等待中间的传输。
+    pub fn fill_rect(
+        &mut self,
+        x_start: i32,
+        y_start: i32,
+        x_end: i32,
+        y_end: i32,
+        color: Rgb565,
+    ) -> Result<()> {
+        let x_start = x_start.max(0);
+        let y_start = y_start.max(0);
+        let x_end = x_end.min(self.width());
+        let y_end = y_end.min(self.height());
+        if x_start >= x_end || y_start >= y_end {
+            return Ok(());
+        }
+
+        // 上一次绘制可能仍在使用临时缓冲区
+        self.wait_idle()?;
+
+        let width = (x_end - x_start) as usize;
+        let rows_per_chunk = (self.scratch.len() / width).max(1);
+        let chunk_len = (rows_per_chunk * width).min(self.scratch.len());
+        self.scratch[..chunk_len].fill(Self::color_to_u16(color));
+
+        let mut y = y_start;
+        while y < y_end {
+            let rows = rows_per_chunk.min((y_end - y) as usize);
+            let data = &self.scratch[..rows * width];
+            self.draw_bitmap_async(x_start, y, x_end, y + rows as i32, data)?;
+            y += rows as i32;
+        }
+
+        self.wait_idle()
+    }
+
+    /// 测量整屏填充与80x80位图的传输耗时
+    ///
+    /// 用于调整[`LcdConfig`]后比较速度，会覆盖屏幕内容，调用后需要重绘整屏。
+    pub fn benchmark(&mut self) -> Result<LcdBenchmark> {
+        let (width, height) = (self.width(), self.height());
+        let start = Instant::now();
+        for round in 0..BENCHMARK_ROUNDS {
+            let color = if round % 2 == 0 {
+                Rgb565::RED
+            } else {
+                Rgb565::BLUE
+            };
+            self.fill_rect(0, 0, width, height, color)?;
+        }
+        let full_fill = start.elapsed() / BENCHMARK_ROUNDS;
+
+        // 渐变色块放在屏幕中央，每次都等待传输完成，与界面刷新的用法一致
+        let size = BENCHMARK_BLIT_SIZE;
+        let len = (size * size) as usize;
+        anyhow::ensure!(len <= self.scratch.len(), "临时缓冲区放不下基准测试位图");
+        self.wait_idle()?;
+        for (i, pixel) in self.scratch[..len].iter_mut().enumerate() {
+            let (x, y) = (i % size as usize, i / size as usize);
+            *pixel = Self::color_to_u16(Rgb565::new((x % 32) as u8, (y % 64) as u8, 16));
+        }
+        let (x, y) = ((width - size) / 2, (height - size) / 2);
+        let start = Instant::now();
+        for _ in 0..BENCHMARK_ROUNDS {
+            self.draw_bitmap(x, y, x + size, y + size, &self.scratch[..len])?;
+        }
+        let blit = start.elapsed() / BENCHMARK_ROUNDS;
+
+        Ok(LcdBenchmark { full_fill, blit })
+    }
+
+    /// 发送临时缓冲区中的一段水平像素
+    fn flush_run(&mut self, x: i32, y: i32, len: usize) -> Result<()> {
+        if len == 0 {
+            return Ok(());
+        }
+        let data = &self.scratch[..len];
+        self.draw_bitmap(x, y, x + len as i32, y + 1, data)
+    }
 }
 
 // 为LcdController实现embedded-graphics的DrawTarget trait
@@ -261,62 +1031,103 @@ impl DrawTarget for LcdController {
     type Color = Rgb565;
     type Error = anyhow::Error;
 
+    /// 逐像素绘制
+    ///
+    /// 同一行上连续的像素合并到临时缓冲区中一次发送，不分配内存。
     fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        // 收集所有像素并计算边界框
-        let mut min_x = i32::MAX;
-        let mut min_y = i32::MAX;
-        let mut max_x = i32::MIN;
-        let mut max_y = i32::MIN;
-        let mut pixel_data = Vec::new();
+        let (width, height) = (self.width(), self.height());
+        let mut run_x = 0;
+        let mut run_y = 0;
+        let mut run_len = 0usize;
 
+        self.wait_idle()?;
         for Pixel(coord, color) in pixels {
-            // 更新边界框
-            min_x = min_x.min(coord.x);
-            min_y = min_y.min(coord.y);
-            max_x = max_x.max(coord.x);
-            max_y = max_y.max(coord.y);
+            if coord.x < 0 || coord.y < 0 || coord.x >= width || coord.y >= height {
+                continue;
+            }
 
-            // 将Rgb565转换为RGB565格式的u16值，考虑大端序
-            let color_u16 = Self::color_to_u16(color);
+            let continues = run_len > 0
+                && coord.y == run_y
+                && coord.x == run_x + run_len as i32
+                && run_len < self.scratch.len();
+            if !continues {
+                self.flush_run(run_x, run_y, run_len)?;
+                run_x = coord.x;
+                run_y = coord.y;
+                run_len = 0;
+            }
 
-            pixel_data.push((coord, color_u16));
+            self.scratch[run_len] = Self::color_to_u16(color);
+            run_len += 1;
         }
+        self.flush_run(run_x, run_y, run_len)
+    }
 
-        // 如果没有像素，直接返回
-        if pixel_data.is_empty() {
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let Some(bottom_right) = area.bottom_right() else {
             return Ok(());
-        }
+        };
+        self.fill_rect(
+            area.top_left.x,
+            area.top_left.y,
+            bottom_right.x + 1,
+            bottom_right.y + 1,
+            color,
+        )
+    }
+}
 
-        // 创建边界框区域的帧缓冲区
-        let width = (max_x - min_x + 1) as usize;
-        let height = (max_y - min_y + 1) as usize;
-        let mut framebuffer = vec![0u16; width * height];
+impl OriginDimensions for LcdController {
+    fn size(&self) -> Size {
+        Size::new(self.width() as u32, self.height() as u32)
+    }
+}
 
-        // 将像素填入缓冲区
-        for (coord, color_u16) in pixel_data {
-            let x = (coord.x - min_x) as usize;
-            let y = (coord.y - min_y) as usize;
-            framebuffer[y * width + x] = color_u16;
-        }
+impl DisplayDevice for LcdController {
+    fn width(&self) -> i32 {
+        LcdController::width(self)
+    }
 
-        // 一次性绘制整个区域
-        self.draw_bitmap(min_x, min_y, max_x + 1, max_y + 1, &framebuffer)?;
+    fn height(&self) -> i32 {
+        LcdController::height(self)
+    }
 
-        Ok(())
+    fn orientation(&self) -> DisplayOrientation {
+        LcdController::orientation(self)
     }
-}
 
-impl OriginDimensions for LcdController {
-    fn size(&self) -> Size {
-        Size::new(LCD_WIDTH as u32, LCD_HEIGHT as u32)
+    fn set_orientation(&mut self, orientation: DisplayOrientation) -> Result<()> {
+        LcdController::set_orientation(self, orientation)
+    }
+
+    fn set_power(&mut self, on: bool) -> Result<()> {
+        LcdController::set_power(self, on)
+    }
+
+    fn set_brightness(&mut self, percent: u8) -> Result<()> {
+        LcdController::set_brightness(self, percent)
+    }
+
+    fn panel_reinit(&self) -> Box<dyn PanelReinit> {
+        Box::new(LcdPanelReinit {
+            port: self.port.clone(),
+            setup: self.setup.clone(),
+        })
+    }
+
+    fn bitmap_sink(&self) -> Box<dyn BitmapSink> {
+        Box::new(self.port.clone())
     }
 }
 
 impl Drop for LcdController {
     fn drop(&mut self) {
+        // 回调上下文随控制器释放，删除面板前必须等待传输结束
+        let _ = self.wait_idle();
+
         // 清理资源
         unsafe {
             if !self.panel.is_null() {
@@ -1,2 +1,4 @@
+pub mod dma_buffer;
 pub mod lcd;
 pub mod lcd_cmds;
+pub mod orientation;
@@ -0,0 +1,77 @@
+// 显示方向：旋转角度与镜像到面板交换/镜像设置的映射
+
+/// 顺时针旋转角度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rotation {
+    #[default]
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+/// 显示方向
+///
+/// 通过面板的XY交换和镜像实现，不需要软件变换像素，对绘制性能没有影响。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DisplayOrientation {
+    /// 旋转角度
+    pub rotation: Rotation,
+    /// 在旋转的基础上水平镜像
+    pub mirror: bool,
+}
+
+impl DisplayOrientation {
+    pub fn new(rotation: Rotation, mirror: bool) -> Self {
+        Self { rotation, mirror }
+    }
+
+    /// 转换为面板设置
+    ///
+    /// # 返回值
+    /// (swap_xy, mirror_x, mirror_y)，对应`esp_lcd_panel_swap_xy`和`esp_lcd_panel_mirror`的参数
+    pub fn panel_transform(&self) -> (bool, bool, bool) {
+        let (swap_xy, mirror_x, mirror_y) = match self.rotation {
+            Rotation::Deg0 => (false, false, false),
+            Rotation::Deg90 => (true, true, false),
+            Rotation::Deg180 => (false, true, true),
+            Rotation::Deg270 => (true, false, true),
+        };
+
+        // 交换XY后逻辑水平方向对应面板的Y方向
+        if !self.mirror {
+            (swap_xy, mirror_x, mirror_y)
+        } else if swap_xy {
+            (swap_xy, mirror_x, !mirror_y)
+        } else {
+            (swap_xy, !mirror_x, mirror_y)
+        }
+    }
+
+    /// 旋转后的逻辑分辨率
+    ///
+    /// # 参数
+    /// * `width` - 面板物理宽度
+    /// * `height` - 面板物理高度
+    pub fn logical_size(&self, width: i32, height: i32) -> (i32, i32) {
+        match self.rotation {
+            Rotation::Deg0 | Rotation::Deg180 => (width, height),
+            Rotation::Deg90 | Rotation::Deg270 => (height, width),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panel_transform() {
+        let rotated = DisplayOrientation::new(Rotation::Deg90, false);
+        assert_eq!(rotated.panel_transform(), (true, true, false));
+        assert_eq!(rotated.logical_size(320, 240), (240, 320));
+
+        let mirrored = DisplayOrientation::new(Rotation::Deg0, true);
+        assert_eq!(mirrored.panel_transform(), (false, true, false));
+    }
+}
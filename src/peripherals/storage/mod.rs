@@ -0,0 +1,294 @@
+// 文件系统存储
+//
+// - SPIFFS：挂载在`/spiffs`，使用分区表中的`storage`分区，保存资源、日志和小文件
+// - SD卡（可选）：SDMMC 1-bit模式挂载在`/sdcard`，FAT格式，适合录音和TTS缓存等大文件
+//
+// 挂载后可以直接使用std::fs访问，这里额外提供按存储位置读写和剩余空间查询的小型API。
+
+use std::fs;
+use std::io::Write;
+
+use anyhow::Result;
+use esp_idf_hal::{
+    gpio::{AnyIOPin, InputPin, OutputPin},
+    peripheral::Peripheral,
+    sd::{
+        mmc::{SdMmcHostConfiguration, SdMmcHostDriver, SDMMC1},
+        SdCardConfiguration, SdCardDriver,
+    },
+};
+use esp_idf_svc::fs::fatfs::{Fatfs, MountedFatfs};
+use esp_idf_sys::{
+    esp, esp_spiffs_info, esp_vfs_fat_info, esp_vfs_spiffs_conf_t, esp_vfs_spiffs_register,
+    esp_vfs_spiffs_unregister,
+};
+use log::{info, warn};
+
+/// SPIFFS挂载点
+pub const SPIFFS_MOUNT_POINT: &str = "/spiffs";
+/// SD卡挂载点
+pub const SD_MOUNT_POINT: &str = "/sdcard";
+
+/// SPIFFS分区标签（见custom_partitions.csv）
+const SPIFFS_PARTITION_LABEL: &core::ffi::CStr = c"storage";
+const SPIFFS_BASE_PATH: &core::ffi::CStr = c"/spiffs";
+const SD_BASE_PATH: &core::ffi::CStr = c"/sdcard";
+
+/// 同时打开的最大文件数
+const MAX_OPEN_FILES: usize = 5;
+
+type MountedSdCard = MountedFatfs<Fatfs<SdCardDriver<SdMmcHostDriver<'static>>>>;
+
+/// 存储位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageLocation {
+    /// 内部Flash（SPIFFS）
+    Internal,
+    /// SD卡
+    SdCard,
+}
+
+impl StorageLocation {
+    /// 挂载点路径
+    pub fn mount_point(&self) -> &'static str {
+        match self {
+            StorageLocation::Internal => SPIFFS_MOUNT_POINT,
+            StorageLocation::SdCard => SD_MOUNT_POINT,
+        }
+    }
+
+    /// 显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            StorageLocation::Internal => "内部",
+            StorageLocation::SdCard => "SD",
+        }
+    }
+}
+
+/// 存储空间信息
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StorageSpace {
+    pub location: StorageLocation,
+    /// 总容量（字节）
+    pub total_bytes: u64,
+    /// 已用空间（字节）
+    pub used_bytes: u64,
+}
+
+impl StorageSpace {
+    /// 剩余空间（字节）
+    pub fn free_bytes(&self) -> u64 {
+        self.total_bytes.saturating_sub(self.used_bytes)
+    }
+}
+
+/// 文件系统存储管理器
+pub struct Storage {
+    sd_card: Option<MountedSdCard>,
+}
+
+impl Storage {
+    /// 挂载SPIFFS
+    ///
+    /// 首次使用或分区损坏时会自动格式化。
+    pub fn mount() -> Result<Self> {
+        let conf = esp_vfs_spiffs_conf_t {
+            base_path: SPIFFS_BASE_PATH.as_ptr(),
+            partition_label: SPIFFS_PARTITION_LABEL.as_ptr(),
+            max_files: MAX_OPEN_FILES,
+            format_if_mount_failed: true,
+        };
+        unsafe { esp!(esp_vfs_spiffs_register(&conf))? };
+
+        info!("SPIFFS已挂载到 {}", SPIFFS_MOUNT_POINT);
+        Ok(Self { sd_card: None })
+    }
+
+    /// 挂载SD卡（SDMMC 1-bit模式）
+    ///
+    /// SD_D3接在TCA9554扩展IO上，1-bit模式下只需要CLK、CMD和D0。
+    ///
+    /// # 参数
+    /// * `sdmmc` - SDMMC1外设
+    /// * `clk` - 时钟引脚
+    /// * `cmd` - 命令引脚
+    /// * `d0` - 数据引脚
+    pub fn mount_sd_card(
+        &mut self,
+        sdmmc: SDMMC1,
+        clk: impl Peripheral<P = impl OutputPin> + 'static,
+        cmd: impl Peripheral<P = impl InputPin + OutputPin> + 'static,
+        d0: impl Peripheral<P = impl InputPin + OutputPin> + 'static,
+    ) -> Result<()> {
+        let host = SdMmcHostDriver::new_1bit(
+            sdmmc,
+            cmd,
+            clk,
+            d0,
+            None::<AnyIOPin>,
+            None::<AnyIOPin>,
+            &SdMmcHostConfiguration::new(),
+        )?;
+        let card = SdCardDriver::new_mmc(host, &SdCardConfiguration::new())?;
+        let fatfs = Fatfs::new_sdcard(0, card)?;
+        self.sd_card = Some(MountedFatfs::mount(fatfs, SD_MOUNT_POINT, MAX_OPEN_FILES)?);
+
+        info!("SD卡已挂载到 {}", SD_MOUNT_POINT);
+        Ok(())
+    }
+
+    /// SD卡是否已挂载
+    pub fn has_sd_card(&self) -> bool {
+        self.sd_card.is_some()
+    }
+
+    /// 适合存放大文件（录音、TTS缓存）的位置：有SD卡时优先使用SD卡
+    pub fn bulk_location(&self) -> StorageLocation {
+        if self.has_sd_card() {
+            StorageLocation::SdCard
+        } else {
+            StorageLocation::Internal
+        }
+    }
+
+    /// 拼接完整路径
+    ///
+    /// # 参数
+    /// * `location` - 存储位置
+    /// * `name` - 相对路径，例如`logs/boot.log`
+    pub fn path(&self, location: StorageLocation, name: &str) -> String {
+        format!(
+            "{}/{}",
+            location.mount_point(),
+            name.trim_start_matches('/')
+        )
+    }
+
+    fn check_mounted(&self, location: StorageLocation) -> Result<()> {
+        if location == StorageLocation::SdCard && !self.has_sd_card() {
+            anyhow::bail!("SD卡未挂载");
+        }
+        Ok(())
+    }
+
+    /// 读取整个文件
+    pub fn read(&self, location: StorageLocation, name: &str) -> Result<Vec<u8>> {
+        self.check_mounted(location)?;
+        Ok(fs::read(self.path(location, name))?)
+    }
+
+    /// 写入整个文件（覆盖）
+    pub fn write(&self, location: StorageLocation, name: &str, data: &[u8]) -> Result<()> {
+        self.check_mounted(location)?;
+        Ok(fs::write(self.path(location, name), data)?)
+    }
+
+    /// 追加写入（用于日志）
+    pub fn append(&self, location: StorageLocation, name: &str, data: &[u8]) -> Result<()> {
+        self.check_mounted(location)?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(location, name))?;
+        file.write_all(data)?;
+        Ok(())
+    }
+
+    /// 删除文件
+    pub fn remove(&self, location: StorageLocation, name: &str) -> Result<()> {
+        self.check_mounted(location)?;
+        Ok(fs::remove_file(self.path(location, name))?)
+    }
+
+    /// 文件是否存在
+    pub fn exists(&self, location: StorageLocation, name: &str) -> bool {
+        self.check_mounted(location).is_ok() && fs::metadata(self.path(location, name)).is_ok()
+    }
+
+    /// 列出目录下的文件名
+    ///
+    /// SPIFFS没有真正的目录，`dir`传空字符串列出全部文件。
+    pub fn list(&self, location: StorageLocation, dir: &str) -> Result<Vec<String>> {
+        self.check_mounted(location)?;
+        let mut names = Vec::new();
+        for entry in fs::read_dir(self.path(location, dir))? {
+            names.push(entry?.file_name().to_string_lossy().into_owned());
+        }
+        Ok(names)
+    }
+
+    /// 查询指定位置的空间使用情况
+    pub fn space(&self, location: StorageLocation) -> Result<StorageSpace> {
+        self.check_mounted(location)?;
+
+        let (total_bytes, used_bytes) = match location {
+            StorageLocation::Internal => {
+                let mut total: usize = 0;
+                let mut used: usize = 0;
+                unsafe {
+                    esp!(esp_spiffs_info(
+                        SPIFFS_PARTITION_LABEL.as_ptr(),
+                        &mut total,
+                        &mut used
+                    ))?
+                };
+                (total as u64, used as u64)
+            }
+            StorageLocation::SdCard => {
+                let mut total: u64 = 0;
+                let mut free: u64 = 0;
+                unsafe {
+                    esp!(esp_vfs_fat_info(
+                        SD_BASE_PATH.as_ptr(),
+                        &mut total,
+                        &mut free
+                    ))?
+                };
+                (total, total.saturating_sub(free))
+            }
+        };
+
+        Ok(StorageSpace {
+            location,
+            total_bytes,
+            used_bytes,
+        })
+    }
+
+    /// 所有已挂载位置的空间使用情况（用于诊断显示）
+    pub fn all_spaces(&self) -> Vec<StorageSpace> {
+        let mut locations = vec![StorageLocation::Internal];
+        if self.has_sd_card() {
+            locations.push(StorageLocation::SdCard);
+        }
+
+        locations
+            .into_iter()
+            .filter_map(|location| match self.space(location) {
+                Ok(space) => Some(space),
+                Err(e) => {
+                    warn!("查询{}存储空间失败: {}", location.name(), e);
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+impl Drop for Storage {
+    fn drop(&mut self) {
+        // SD卡由MountedFatfs在drop时卸载
+        self.sd_card.take();
+        unsafe { esp_vfs_spiffs_unregister(SPIFFS_PARTITION_LABEL.as_ptr()) };
+    }
+}
+
+/// 将字节数格式化为易读字符串
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1}MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{}KB", bytes / 1024)
+    }
+}
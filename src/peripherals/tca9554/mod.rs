@@ -0,0 +1,140 @@
+//! TCA9554 8位I2C IO扩展芯片驱动
+//!
+//! 板上LCD和触摸屏的复位脚没有直接接到ESP32，而是接在TCA9554的扩展IO上，
+//! 初始化这些外设之前需要先通过I2C控制对应的引脚。
+
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::peripherals::i2c_bus::I2cDevice;
+use crate::peripherals::st77916::lcd::LcdResetLine;
+
+/// TCA9554默认I2C地址（A0~A2接地）
+pub const TCA9554_ADDRESS: u8 = 0x20;
+
+/// 扩展IO编号（原理图中的EXIO1~EXIO8对应0~7）
+pub const EXIO_TOUCH_RST: u8 = 0; // EXIO1：触摸屏复位
+pub const EXIO_LCD_RST: u8 = 1; // EXIO2：LCD复位
+pub const EXIO_SD_CS: u8 = 2; // EXIO3：SD卡片选
+
+/// TCA9554寄存器地址
+#[derive(Debug, Clone, Copy)]
+#[repr(u8)]
+enum Register {
+    Input = 0x00,
+    Output = 0x01,
+    Polarity = 0x02,
+    Config = 0x03,
+}
+
+/// TCA9554驱动
+///
+/// 缓存输出和方向寄存器，修改单个引脚时只写一次寄存器。
+pub struct Tca9554 {
+    i2c: I2cDevice,
+    output: u8,
+    config: u8,
+}
+
+impl Tca9554 {
+    /// 创建驱动并读取当前寄存器状态
+    ///
+    /// # 参数
+    /// * `i2c` - 共享总线上的设备句柄，地址通常为`TCA9554_ADDRESS`
+    pub fn new(i2c: I2cDevice) -> Result<Self> {
+        let mut expander = Self {
+            i2c,
+            output: 0xFF,
+            config: 0xFF,
+        };
+
+        expander.write_register(Register::Polarity, 0x00)?;
+        expander.output = expander.read_register(Register::Output)?;
+        expander.config = expander.read_register(Register::Config)?;
+        Ok(expander)
+    }
+
+    /// 将引脚配置为输出并设置初始电平
+    ///
+    /// 先写输出寄存器再切换方向，避免切换瞬间输出错误电平。
+    pub fn set_output(&mut self, pin: u8, high: bool) -> Result<()> {
+        self.set_level(pin, high)?;
+        self.config &= !Self::mask(pin)?;
+        self.write_register(Register::Config, self.config)
+    }
+
+    /// 将引脚配置为输入
+    pub fn set_input(&mut self, pin: u8) -> Result<()> {
+        self.config |= Self::mask(pin)?;
+        self.write_register(Register::Config, self.config)
+    }
+
+    /// 设置输出电平
+    pub fn set_level(&mut self, pin: u8, high: bool) -> Result<()> {
+        let mask = Self::mask(pin)?;
+        if high {
+            self.output |= mask;
+        } else {
+            self.output &= !mask;
+        }
+        self.write_register(Register::Output, self.output)
+    }
+
+    /// 读取引脚电平
+    pub fn is_high(&mut self, pin: u8) -> Result<bool> {
+        let mask = Self::mask(pin)?;
+        Ok(self.read_register(Register::Input)? & mask != 0)
+    }
+
+    /// 输出一个低电平复位脉冲
+    ///
+    /// # 参数
+    /// * `pin` - 低电平有效的复位脚
+    /// * `hold` - 保持低电平的时间
+    /// * `settle` - 释放后等待芯片就绪的时间
+    pub fn pulse_reset(&mut self, pin: u8, hold: Duration, settle: Duration) -> Result<()> {
+        self.set_output(pin, false)?;
+        std::thread::sleep(hold);
+        self.set_level(pin, true)?;
+        std::thread::sleep(settle);
+        Ok(())
+    }
+
+    /// 取得某个引脚作为LCD复位线
+    pub fn reset_line(&mut self, pin: u8) -> ExpanderResetLine<'_> {
+        ExpanderResetLine {
+            expander: self,
+            pin,
+        }
+    }
+
+    fn mask(pin: u8) -> Result<u8> {
+        if pin > 7 {
+            anyhow::bail!("TCA9554引脚编号超出范围: {}", pin);
+        }
+        Ok(1 << pin)
+    }
+
+    fn write_register(&mut self, reg: Register, value: u8) -> Result<()> {
+        self.i2c.write(&[reg as u8, value])
+    }
+
+    fn read_register(&mut self, reg: Register) -> Result<u8> {
+        let mut buffer = [0u8];
+        self.i2c.write_read(&[reg as u8], &mut buffer)?;
+        Ok(buffer[0])
+    }
+}
+
+/// 接在扩展IO上的低电平有效复位线
+pub struct ExpanderResetLine<'a> {
+    expander: &'a mut Tca9554,
+    pin: u8,
+}
+
+impl LcdResetLine for ExpanderResetLine<'_> {
+    fn set_reset(&mut self, asserted: bool) -> Result<()> {
+        self.expander.set_output(self.pin, !asserted)
+    }
+}
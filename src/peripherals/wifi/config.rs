@@ -1,11 +1,83 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
+/// WPA2-Enterprise 外层认证方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EapMethod {
+    /// EAP-PEAP（内层MSCHAPv2）
+    Peap,
+    /// EAP-TTLS（内层MSCHAPv2）
+    Ttls,
+}
+
+impl EapMethod {
+    /// 按名称解析认证方式（"peap"或"ttls"，不区分大小写），无法识别时使用PEAP
+    pub fn from_name(name: &str) -> Self {
+        if name.eq_ignore_ascii_case("ttls") {
+            EapMethod::Ttls
+        } else {
+            EapMethod::Peap
+        }
+    }
+}
+
+/// WPA2-Enterprise 认证配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnterpriseConfig {
+    pub method: EapMethod,
+    /// 外层匿名身份（例如 anonymous@example.com）
+    pub identity: String,
+    pub username: String,
+    pub password: String,
+    /// 可选的CA证书（PEM格式），不提供时不校验服务器证书
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<String>,
+}
+
+impl EnterpriseConfig {
+    pub fn new(method: EapMethod, identity: &str, username: &str, password: &str) -> Self {
+        Self {
+            method,
+            identity: identity.to_string(),
+            username: username.to_string(),
+            password: password.to_string(),
+            ca_cert: None,
+        }
+    }
+
+    /// 设置用于校验认证服务器的CA证书（PEM格式）
+    pub fn with_ca_cert(mut self, ca_cert_pem: &str) -> Self {
+        self.ca_cert = Some(ca_cert_pem.to_string());
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.identity.is_empty() {
+            return Err(anyhow::anyhow!("EAP identity cannot be empty"));
+        }
+        if self.username.is_empty() {
+            return Err(anyhow::anyhow!("EAP username cannot be empty"));
+        }
+        if self.password.is_empty() {
+            return Err(anyhow::anyhow!("EAP password cannot be empty"));
+        }
+        if let Some(ca_cert) = &self.ca_cert {
+            if !ca_cert.contains("-----BEGIN CERTIFICATE-----") {
+                return Err(anyhow::anyhow!("CA certificate must be PEM encoded"));
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WifiConfig {
     pub ssid: String,
     pub password: String,
     pub auto_connect: bool,
+    /// WPA2-Enterprise 配置，为None时使用WPA2-Personal
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enterprise: Option<EnterpriseConfig>,
 }
 
 impl WifiConfig {
@@ -14,22 +86,49 @@ impl WifiConfig {
             ssid: ssid.to_string(),
             password: password.to_string(),
             auto_connect: true,
+            enterprise: None,
+        }
+    }
+
+    /// 创建WPA2-Enterprise网络配置
+    pub fn new_enterprise(ssid: &str, enterprise: EnterpriseConfig) -> Self {
+        Self {
+            ssid: ssid.to_string(),
+            password: String::new(),
+            auto_connect: true,
+            enterprise: Some(enterprise),
         }
     }
 
+    /// 使用编译时环境变量中的凭据（构建前`source wifi.env`，见`wifi.env.example`）
     pub fn from_env() -> Result<Self> {
-        let ssid = std::env::var("WIFI_SSID")
-            .map_err(|_| anyhow::anyhow!("WIFI_SSID environment variable not set"))?;
-        let password = std::env::var("WIFI_PASS")
-            .map_err(|_| anyhow::anyhow!("WIFI_PASS environment variable not set"))?;
+        let ssid = option_env!("WIFI_SSID")
+            .ok_or_else(|| anyhow::anyhow!("WIFI_SSID environment variable not set"))?;
 
-        Ok(Self::new(&ssid, &password))
+        // 设置了WIFI_EAP_USERNAME时按WPA2-Enterprise网络处理
+        if let Some(username) = option_env!("WIFI_EAP_USERNAME") {
+            let password = option_env!("WIFI_EAP_PASSWORD")
+                .ok_or_else(|| anyhow::anyhow!("WIFI_EAP_PASSWORD environment variable not set"))?;
+            let identity = option_env!("WIFI_EAP_IDENTITY").unwrap_or(username);
+            let method =
+                option_env!("WIFI_EAP_METHOD").map_or(EapMethod::Peap, EapMethod::from_name);
+            let enterprise = EnterpriseConfig::new(method, identity, username, password);
+            return Ok(Self::new_enterprise(ssid, enterprise));
+        }
+
+        let password = option_env!("WIFI_PASS")
+            .ok_or_else(|| anyhow::anyhow!("WIFI_PASS environment variable not set"))?;
+
+        Ok(Self::new(ssid, password))
     }
 
     pub fn validate(&self) -> Result<()> {
         if self.ssid.is_empty() {
             return Err(anyhow::anyhow!("SSID cannot be empty"));
         }
+        if let Some(enterprise) = &self.enterprise {
+            return enterprise.validate();
+        }
         if self.password.len() < 8 {
             return Err(anyhow::anyhow!("Password must be at least 8 characters"));
         }
@@ -41,6 +140,7 @@ impl WifiConfig {
 pub struct WifiCredentials {
     pub ssid: String,
     pub password: String,
+    pub enterprise: Option<EnterpriseConfig>,
 }
 
 impl WifiCredentials {
@@ -48,6 +148,7 @@ impl WifiCredentials {
         Self {
             ssid: ssid.to_string(),
             password: password.to_string(),
+            enterprise: None,
         }
     }
 }
@@ -57,6 +158,7 @@ impl From<WifiConfig> for WifiCredentials {
         Self {
             ssid: config.ssid,
             password: config.password,
+            enterprise: config.enterprise,
         }
     }
 }
@@ -1,16 +1,47 @@
 pub mod config;
+pub mod store;
 
 use anyhow::Result;
 use embedded_svc::wifi::{AuthMethod, ClientConfiguration, Configuration};
 use esp_idf_hal::modem::Modem;
 use esp_idf_svc::wifi::{BlockingWifi, EspWifi};
 use esp_idf_svc::{eventloop::EspSystemEventLoop, nvs::EspDefaultNvsPartition};
+use esp_idf_sys::{
+    esp, esp_eap_client_clear_ca_cert, esp_eap_client_clear_identity,
+    esp_eap_client_clear_password, esp_eap_client_clear_username, esp_eap_client_set_ca_cert,
+    esp_eap_client_set_identity, esp_eap_client_set_password,
+    esp_eap_client_set_ttls_phase2_method, esp_eap_client_set_username,
+    esp_eap_ttls_phase2_types_ESP_EAP_TTLS_PHASE2_MSCHAPV2, esp_wifi_sta_enterprise_disable,
+    esp_wifi_sta_enterprise_enable,
+};
 use log::info;
 
-pub use config::{WifiConfig, WifiCredentials};
+use crate::blocking::{self, WIFI_CONNECT_BUDGET};
+
+pub use config::{EapMethod, EnterpriseConfig, WifiConfig, WifiCredentials};
+
+/// 将RSSI（dBm）转换为0-4格的信号强度
+///
+/// 阈值参考常见手机的信号格划分：
+/// >= -55 为4格，>= -66 为3格，>= -77 为2格，>= -88 为1格，其余为0格
+pub fn rssi_to_level(rssi: i8) -> u8 {
+    match rssi {
+        r if r >= -55 => 4,
+        r if r >= -66 => 3,
+        r if r >= -77 => 2,
+        r if r >= -88 => 1,
+        _ => 0,
+    }
+}
 
 pub struct WifiManager {
     wifi: BlockingWifi<EspWifi<'static>>,
+    /// 企业认证CA证书缓冲区
+    ///
+    /// esp_eap_client只保存证书指针而不复制内容，因此缓冲区必须在连接期间保持有效
+    ca_cert: Option<Vec<u8>>,
+    /// 是否已启用WPA2-Enterprise
+    enterprise_enabled: bool,
 }
 
 impl WifiManager {
@@ -21,7 +52,11 @@ impl WifiManager {
     ) -> Result<Self> {
         let wifi = BlockingWifi::wrap(EspWifi::new(modem, sys_loop.clone(), nvs)?, sys_loop)?;
 
-        Ok(Self { wifi })
+        Ok(Self {
+            wifi,
+            ca_cert: None,
+            enterprise_enabled: false,
+        })
     }
 
     pub fn connect(&mut self, ssid: &str, password: &str) -> Result<()> {
@@ -29,7 +64,19 @@ impl WifiManager {
         self.connect_with_credentials(&credentials)
     }
 
+    /// 连接WiFi并等待网络接口就绪
+    ///
+    /// # 注意
+    /// 此方法会阻塞数秒（最长`WIFI_CONNECT_BUDGET`），只能在WiFi actor线程中调用
     pub fn connect_with_credentials(&mut self, credentials: &WifiCredentials) -> Result<()> {
+        blocking::assert_off_main_thread("wifi_connect");
+
+        // 企业网络的密码通过EAP客户端提交，不写入STA配置
+        let (auth_method, password) = match &credentials.enterprise {
+            Some(_) => (AuthMethod::WPA2Enterprise, ""),
+            None => (AuthMethod::WPA2Personal, credentials.password.as_str()),
+        };
+
         let wifi_configuration = Configuration::Client(ClientConfiguration {
             ssid: credentials
                 .ssid
@@ -37,26 +84,100 @@ impl WifiManager {
                 .try_into()
                 .map_err(|_| anyhow::anyhow!("Invalid SSID"))?,
             bssid: None,
-            auth_method: AuthMethod::WPA2Personal,
-            password: credentials
-                .password
-                .as_str()
+            auth_method,
+            password: password
                 .try_into()
                 .map_err(|_| anyhow::anyhow!("Invalid password"))?,
             channel: None,
             ..Default::default()
         });
 
-        self.wifi.set_configuration(&wifi_configuration)?;
-        self.wifi.start()?;
-        info!("WiFi started");
+        blocking::with_budget("wifi_connect", WIFI_CONNECT_BUDGET, || -> Result<()> {
+            self.wifi.set_configuration(&wifi_configuration)?;
+            match &credentials.enterprise {
+                Some(enterprise) => self.enable_enterprise(enterprise)?,
+                None => self.disable_enterprise()?,
+            }
+            self.wifi.start()?;
+            info!("WiFi started");
+
+            self.wifi.connect()?;
+            info!("WiFi connected");
 
-        self.wifi.connect()?;
-        info!("WiFi connected");
+            self.wifi.wait_netif_up()?;
+            info!("WiFi netif up");
 
-        self.wifi.wait_netif_up()?;
-        info!("WiFi netif up");
+            Ok(())
+        })
+    }
+
+    /// 配置并启用WPA2-Enterprise认证
+    ///
+    /// 必须在`set_configuration`之后、`start`之前调用。
+    fn enable_enterprise(&mut self, config: &EnterpriseConfig) -> Result<()> {
+        info!("Enabling WPA2-Enterprise ({:?})", config.method);
+
+        unsafe {
+            esp!(esp_eap_client_set_identity(
+                config.identity.as_ptr(),
+                config.identity.len() as i32
+            ))?;
+            esp!(esp_eap_client_set_username(
+                config.username.as_ptr(),
+                config.username.len() as i32
+            ))?;
+            esp!(esp_eap_client_set_password(
+                config.password.as_ptr(),
+                config.password.len() as i32
+            ))?;
+
+            match &config.ca_cert {
+                Some(pem) => {
+                    // PEM证书需要以'\0'结尾，长度包含结尾的'\0'
+                    let mut buffer = pem.as_bytes().to_vec();
+                    buffer.push(0);
+                    esp!(esp_eap_client_set_ca_cert(
+                        buffer.as_ptr(),
+                        buffer.len() as i32
+                    ))?;
+                    self.ca_cert = Some(buffer);
+                }
+                None => {
+                    esp_eap_client_clear_ca_cert();
+                    self.ca_cert = None;
+                }
+            }
+
+            // PEAP默认内层即为MSCHAPv2，TTLS需要显式指定
+            if config.method == EapMethod::Ttls {
+                esp!(esp_eap_client_set_ttls_phase2_method(
+                    esp_eap_ttls_phase2_types_ESP_EAP_TTLS_PHASE2_MSCHAPV2
+                ))?;
+            }
+
+            esp!(esp_wifi_sta_enterprise_enable())?;
+        }
+
+        self.enterprise_enabled = true;
+        Ok(())
+    }
 
+    /// 关闭WPA2-Enterprise认证并清除凭据
+    fn disable_enterprise(&mut self) -> Result<()> {
+        if !self.enterprise_enabled {
+            return Ok(());
+        }
+
+        unsafe {
+            esp!(esp_wifi_sta_enterprise_disable())?;
+            esp_eap_client_clear_identity();
+            esp_eap_client_clear_username();
+            esp_eap_client_clear_password();
+            esp_eap_client_clear_ca_cert();
+        }
+
+        self.ca_cert = None;
+        self.enterprise_enabled = false;
         Ok(())
     }
 
@@ -81,6 +202,13 @@ impl WifiManager {
         Ok(ip_info.ip)
     }
 
+    /// 获取当前连接AP的信号强度（dBm）
+    pub fn get_rssi(&self) -> Result<i8> {
+        let mut ap_info = esp_idf_sys::wifi_ap_record_t::default();
+        esp!(unsafe { esp_idf_sys::esp_wifi_sta_get_ap_info(&mut ap_info) })?;
+        Ok(ap_info.rssi)
+    }
+
     pub fn scan_networks(&mut self) -> Result<Vec<embedded_svc::wifi::AccessPointInfo>> {
         self.wifi
             .scan()
@@ -0,0 +1,94 @@
+// WiFi凭据存储
+//
+// 凭据保存在NVS（命名空间`wifi`），可以用`nvs_partition_gen.py`生成分区或由配网流程写入：
+// - `ssid`、`password`：WPA2-Personal网络
+// - `eap_user`、`eap_pass`：设置后按WPA2-Enterprise网络处理，`eap_method`（peap/ttls）与
+//   `eap_identity`可选，`eap_ca`（PEM格式的blob）用于校验认证服务器
+// NVS中没有SSID时使用编译时环境变量中的凭据（见`wifi.env.example`）。
+
+use anyhow::Result;
+use esp_idf_svc::nvs::{EspDefaultNvsPartition, EspNvs, NvsDefault};
+use log::{info, warn};
+
+use super::config::{EapMethod, EnterpriseConfig, WifiConfig};
+
+/// NVS命名空间
+const WIFI_NAMESPACE: &str = "wifi";
+const SSID_KEY: &str = "ssid";
+const PASSWORD_KEY: &str = "password";
+const EAP_METHOD_KEY: &str = "eap_method";
+const EAP_IDENTITY_KEY: &str = "eap_identity";
+const EAP_USERNAME_KEY: &str = "eap_user";
+const EAP_PASSWORD_KEY: &str = "eap_pass";
+/// 企业认证CA证书（PEM）的键
+const EAP_CA_KEY: &str = "eap_ca";
+
+/// 加载WiFi凭据
+///
+/// # 参数
+/// * `partition` - 默认NVS分区
+///
+/// # 返回值
+/// NVS和编译时环境变量中都没有配置时返回None
+pub fn load(partition: EspDefaultNvsPartition) -> Result<Option<WifiConfig>> {
+    let nvs = EspNvs::new(partition, WIFI_NAMESPACE, true)?;
+
+    let mut config = match read_str(&nvs, SSID_KEY)? {
+        Some(ssid) => {
+            info!("使用NVS中保存的WiFi凭据");
+            match read_str(&nvs, EAP_USERNAME_KEY)? {
+                Some(username) => {
+                    let password = read_str(&nvs, EAP_PASSWORD_KEY)?.unwrap_or_default();
+                    let identity =
+                        read_str(&nvs, EAP_IDENTITY_KEY)?.unwrap_or_else(|| username.clone());
+                    let method = read_str(&nvs, EAP_METHOD_KEY)?
+                        .map_or(EapMethod::Peap, |name| EapMethod::from_name(&name));
+                    let enterprise = EnterpriseConfig::new(method, &identity, &username, &password);
+                    WifiConfig::new_enterprise(&ssid, enterprise)
+                }
+                None => WifiConfig::new(&ssid, &read_str(&nvs, PASSWORD_KEY)?.unwrap_or_default()),
+            }
+        }
+        None => match WifiConfig::from_env() {
+            Ok(config) => config,
+            Err(e) => {
+                warn!("没有配置WiFi凭据: {}", e);
+                return Ok(None);
+            }
+        },
+    };
+
+    // 编译时配置的企业网络同样使用NVS中的CA证书
+    if let Some(enterprise) = config.enterprise.take() {
+        config.enterprise = Some(match read_ca_cert(&nvs)? {
+            Some(ca_cert) => enterprise.with_ca_cert(&ca_cert),
+            None => enterprise,
+        });
+    }
+    if let Err(e) = config.validate() {
+        warn!("WiFi凭据无效: {}", e);
+        return Ok(None);
+    }
+    Ok(Some(config))
+}
+
+/// 读取字符串，键不存在时返回None
+fn read_str(nvs: &EspNvs<NvsDefault>, key: &str) -> Result<Option<String>> {
+    let Some(len) = nvs.str_len(key)? else {
+        return Ok(None);
+    };
+    let mut buf = vec![0u8; len];
+    Ok(nvs.get_str(key, &mut buf)?.map(str::to_string))
+}
+
+/// 读取企业认证CA证书
+fn read_ca_cert(nvs: &EspNvs<NvsDefault>) -> Result<Option<String>> {
+    let Some(len) = nvs.blob_len(EAP_CA_KEY)? else {
+        return Ok(None);
+    };
+    let mut buf = vec![0u8; len];
+    match nvs.get_blob(EAP_CA_KEY, &mut buf)? {
+        Some(pem) => Ok(Some(String::from_utf8(pem.to_vec())?)),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,253 @@
+// src/stats.rs
+//! 可靠性统计
+//!
+//! 在NVS中记录累计运行时间、启动次数、崩溃次数和WiFi断开次数，
+//! 用于在现场评估设备的长期稳定性；同时累计对话次数与用户说话时长。
+//! 对话统计变化频繁，只在内存中累加，随运行时间定期写入NVS。
+//! 异常复位后根据`crash`模块留下的运行痕迹生成崩溃记录，保存最近几次。
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use esp_idf_svc::nvs::{EspDefaultNvsPartition, EspNvs, NvsDefault};
+use esp_idf_sys::{
+    esp_reset_reason, esp_reset_reason_t, esp_reset_reason_t_ESP_RST_BROWNOUT,
+    esp_reset_reason_t_ESP_RST_DEEPSLEEP, esp_reset_reason_t_ESP_RST_INT_WDT,
+    esp_reset_reason_t_ESP_RST_PANIC, esp_reset_reason_t_ESP_RST_POWERON,
+    esp_reset_reason_t_ESP_RST_SW, esp_reset_reason_t_ESP_RST_TASK_WDT,
+    esp_reset_reason_t_ESP_RST_WDT,
+};
+use serde::Serialize;
+
+use crate::crash::{self, CrashRecord};
+
+/// NVS命名空间
+const STATS_NAMESPACE: &str = "stats";
+
+const KEY_BOOT_COUNT: &str = "boot_count";
+const KEY_CRASH_COUNT: &str = "crash_count";
+const KEY_WIFI_DISCONNECTS: &str = "wifi_disc";
+const KEY_UPTIME_SECS: &str = "uptime_secs";
+const KEY_DROPS: &str = "drops";
+const KEY_CRASH_HISTORY: &str = "crashes";
+const KEY_CONVERSATIONS: &str = "conversations";
+const KEY_SPEAKING_MS: &str = "speaking_ms";
+
+/// 累计运行时间写入NVS的间隔，避免频繁擦写Flash
+const UPTIME_PERSIST_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// 可靠性统计快照
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ReliabilityStats {
+    /// 累计运行时间（秒，包含本次启动）
+    pub total_uptime_secs: u64,
+    /// 本次启动后的运行时间（秒）
+    pub session_uptime_secs: u64,
+    /// 启动次数
+    pub boot_count: u32,
+    /// 崩溃次数（panic、看门狗、掉电复位）
+    pub crash_count: u32,
+    /// WiFi断开次数
+    pub wifi_disconnects: u32,
+    /// 跌落次数
+    pub drops: u32,
+    /// 完成的对话轮数（收到完整回复）
+    pub conversations: u32,
+    /// 用户语音提问的累计时长（毫秒）
+    pub speaking_ms: u64,
+    /// 本次启动的复位原因
+    pub last_reset_reason: &'static str,
+    /// 最近几次崩溃记录，旧的在前
+    pub crashes: Vec<CrashRecord>,
+}
+
+/// 将复位原因转换为可读字符串
+fn reset_reason_name(reason: esp_reset_reason_t) -> &'static str {
+    match reason {
+        esp_reset_reason_t_ESP_RST_PANIC => "panic",
+        esp_reset_reason_t_ESP_RST_INT_WDT => "int_wdt",
+        esp_reset_reason_t_ESP_RST_TASK_WDT => "task_wdt",
+        esp_reset_reason_t_ESP_RST_WDT => "wdt",
+        esp_reset_reason_t_ESP_RST_BROWNOUT => "brownout",
+        esp_reset_reason_t_ESP_RST_POWERON => "power_on",
+        esp_reset_reason_t_ESP_RST_SW => "software",
+        esp_reset_reason_t_ESP_RST_DEEPSLEEP => "deep_sleep",
+        _ => "unknown",
+    }
+}
+
+/// 复位原因是否属于异常复位
+fn is_crash_reset(reason: esp_reset_reason_t) -> bool {
+    matches!(
+        reason,
+        esp_reset_reason_t_ESP_RST_PANIC
+            | esp_reset_reason_t_ESP_RST_INT_WDT
+            | esp_reset_reason_t_ESP_RST_TASK_WDT
+            | esp_reset_reason_t_ESP_RST_WDT
+            | esp_reset_reason_t_ESP_RST_BROWNOUT
+    )
+}
+
+/// 从NVS读取崩溃记录，解析失败时丢弃
+fn load_crash_history(nvs: &EspNvs<NvsDefault>) -> Result<Vec<CrashRecord>> {
+    let Some(len) = nvs.str_len(KEY_CRASH_HISTORY)? else {
+        return Ok(Vec::new());
+    };
+    let mut buf = vec![0u8; len];
+    Ok(match nvs.get_str(KEY_CRASH_HISTORY, &mut buf)? {
+        Some(json) => serde_json::from_str(json).unwrap_or_else(|e| {
+            log::warn!("崩溃记录解析失败，已清空: {}", e);
+            Vec::new()
+        }),
+        None => Vec::new(),
+    })
+}
+
+/// 可靠性统计存储
+pub struct StatsStore {
+    nvs: EspNvs<NvsDefault>,
+    stats: ReliabilityStats,
+    /// 本次启动前已累计的运行时间（秒）
+    uptime_base_secs: u64,
+    boot_time: Instant,
+    last_persist: Instant,
+    /// 本次启动是从崩溃中恢复，尚未提示用户
+    recovered_crash: Option<CrashRecord>,
+}
+
+impl StatsStore {
+    /// 加载统计数据并记录本次启动
+    ///
+    /// 启动次数加一，若上次是异常复位则崩溃次数加一，然后立即写回NVS。
+    ///
+    /// # 参数
+    /// * `partition` - 默认NVS分区
+    pub fn new(partition: EspDefaultNvsPartition) -> Result<Self> {
+        let mut nvs = EspNvs::new(partition, STATS_NAMESPACE, true)?;
+
+        let reason = unsafe { esp_reset_reason() };
+        let boot_count = nvs.get_u32(KEY_BOOT_COUNT)?.unwrap_or(0) + 1;
+        let mut crash_count = nvs.get_u32(KEY_CRASH_COUNT)?.unwrap_or(0);
+        let mut crash_history = load_crash_history(&nvs)?;
+        let breadcrumb = crash::take_record(reset_reason_name(reason));
+        let recovered_crash = if is_crash_reset(reason) {
+            crash_count += 1;
+            log::warn!("上次运行异常退出: {:?}", breadcrumb);
+            crash::push_record(&mut crash_history, breadcrumb.clone());
+            nvs.set_str(KEY_CRASH_HISTORY, &serde_json::to_string(&crash_history)?)?;
+            Some(breadcrumb)
+        } else {
+            None
+        };
+        let wifi_disconnects = nvs.get_u32(KEY_WIFI_DISCONNECTS)?.unwrap_or(0);
+        let drops = nvs.get_u32(KEY_DROPS)?.unwrap_or(0);
+        let conversations = nvs.get_u32(KEY_CONVERSATIONS)?.unwrap_or(0);
+        let speaking_ms = nvs.get_u64(KEY_SPEAKING_MS)?.unwrap_or(0);
+        let uptime_base_secs = nvs.get_u64(KEY_UPTIME_SECS)?.unwrap_or(0);
+
+        let mut store = Self {
+            nvs,
+            stats: ReliabilityStats {
+                total_uptime_secs: uptime_base_secs,
+                session_uptime_secs: 0,
+                boot_count,
+                crash_count,
+                wifi_disconnects,
+                drops,
+                conversations,
+                speaking_ms,
+                last_reset_reason: reset_reason_name(reason),
+                crashes: crash_history,
+            },
+            uptime_base_secs,
+            boot_time: Instant::now(),
+            last_persist: Instant::now(),
+            recovered_crash,
+        };
+        store.persist()?;
+
+        log::info!(
+            "启动统计: 第{}次启动, 崩溃{}次, 复位原因: {}",
+            boot_count,
+            crash_count,
+            store.stats.last_reset_reason
+        );
+
+        Ok(store)
+    }
+
+    /// 取出本次启动前发生的崩溃（只返回一次），用于提示用户
+    pub fn take_recovered_crash(&mut self) -> Option<CrashRecord> {
+        self.recovered_crash.take()
+    }
+
+    /// 记录一次WiFi断开
+    pub fn record_wifi_disconnect(&mut self) -> Result<()> {
+        self.stats.wifi_disconnects += 1;
+        self.nvs
+            .set_u32(KEY_WIFI_DISCONNECTS, self.stats.wifi_disconnects)?;
+        Ok(())
+    }
+
+    /// 记录一次跌落
+    pub fn record_drop(&mut self) -> Result<()> {
+        self.stats.drops += 1;
+        self.nvs.set_u32(KEY_DROPS, self.stats.drops)?;
+        Ok(())
+    }
+
+    /// 记录一轮完成的对话，随运行时间定期写入NVS
+    pub fn record_conversation(&mut self) {
+        self.stats.conversations += 1;
+    }
+
+    /// 记录一段用户语音的时长，随运行时间定期写入NVS
+    pub fn record_speech(&mut self, duration: Duration) {
+        self.stats.speaking_ms += duration.as_millis() as u64;
+    }
+
+    /// 定期调用，按`UPTIME_PERSIST_INTERVAL`间隔将累计运行时间与对话统计写入NVS
+    pub fn tick(&mut self) -> Result<()> {
+        if self.last_persist.elapsed() >= UPTIME_PERSIST_INTERVAL {
+            self.persist()?;
+        }
+        Ok(())
+    }
+
+    /// 获取当前统计快照（运行时间实时计算）
+    ///
+    /// 快照实现了`Serialize`，可直接放入遥测数据上报。
+    pub fn snapshot(&self) -> ReliabilityStats {
+        let session = self.boot_time.elapsed().as_secs();
+        ReliabilityStats {
+            total_uptime_secs: self.uptime_base_secs + session,
+            session_uptime_secs: session,
+            ..self.stats.clone()
+        }
+    }
+
+    fn persist(&mut self) -> Result<()> {
+        self.stats = self.snapshot();
+        self.nvs.set_u32(KEY_BOOT_COUNT, self.stats.boot_count)?;
+        self.nvs.set_u32(KEY_CRASH_COUNT, self.stats.crash_count)?;
+        self.nvs
+            .set_u32(KEY_WIFI_DISCONNECTS, self.stats.wifi_disconnects)?;
+        self.nvs.set_u32(KEY_DROPS, self.stats.drops)?;
+        self.nvs
+            .set_u32(KEY_CONVERSATIONS, self.stats.conversations)?;
+        self.nvs.set_u64(KEY_SPEAKING_MS, self.stats.speaking_ms)?;
+        self.nvs
+            .set_u64(KEY_UPTIME_SECS, self.stats.total_uptime_secs)?;
+        self.last_persist = Instant::now();
+        Ok(())
+    }
+}
+
+/// 将秒数格式化为"Xd HH:MM:SS"
+pub fn format_duration(secs: u64) -> String {
+    let days = secs / 86400;
+    let hours = (secs % 86400) / 3600;
+    let minutes = (secs % 3600) / 60;
+    let seconds = secs % 60;
+    format!("{}d {:02}:{:02}:{:02}", days, hours, minutes, seconds)
+}
@@ -0,0 +1,294 @@
+// src/testing.rs
+//! 测试工具，只在`cargo test`时编译
+//!
+//! - [`MockDrawTarget`]：记录绘制调用与像素的DrawTarget，检查界面组件的绘制位置
+//! - [`MockI2c`]：按脚本应答的I2C设备，检查驱动发出的传输
+//! - [`ScriptedImu`]：按顺序返回预设样本的运动传感器
+//! - [`FakeClock`]：手动推进的时钟，配合接受`now`参数的状态机使用
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    pixelcolor::Rgb565,
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+
+use crate::graphics::layout::{SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::hal::{I2cTransport, MotionSensor, SensorData};
+
+/// [`MockDrawTarget`]记录的一次绘制调用
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCall {
+    /// 逐像素绘制：像素个数与外接矩形（含屏幕外的像素）
+    Pixels { count: usize, bounds: Rectangle },
+    /// 填充矩形
+    FillSolid { area: Rectangle, color: Rgb565 },
+}
+
+/// 记录绘制调用与像素的DrawTarget
+///
+/// 屏幕外的像素不保存，只计入`clipped`，用于检查组件没有画出屏幕。
+pub struct MockDrawTarget {
+    size: Size,
+    pixels: Vec<Option<Rgb565>>,
+    calls: Vec<DrawCall>,
+    clipped: usize,
+}
+
+impl MockDrawTarget {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            size: Size::new(width, height),
+            pixels: vec![None; (width * height) as usize],
+            calls: Vec::new(),
+            clipped: 0,
+        }
+    }
+
+    /// 与屏幕同样大小
+    pub fn screen() -> Self {
+        Self::new(SCREEN_WIDTH as u32, SCREEN_HEIGHT as u32)
+    }
+
+    /// 按顺序记录的绘制调用
+    pub fn calls(&self) -> &[DrawCall] {
+        &self.calls
+    }
+
+    /// 画到屏幕外的像素个数
+    pub fn clipped(&self) -> usize {
+        self.clipped
+    }
+
+    /// 所有画过的像素的外接矩形
+    pub fn drawn_bounds(&self) -> Option<Rectangle> {
+        self.bounds_where(|_| true)
+    }
+
+    /// 指定颜色像素的外接矩形
+    pub fn bounds_of(&self, color: Rgb565) -> Option<Rectangle> {
+        self.bounds_where(|pixel| pixel == color)
+    }
+
+    fn bounds_where(&self, filter: impl Fn(Rgb565) -> bool) -> Option<Rectangle> {
+        let width = self.size.width as usize;
+        let points = self
+            .pixels
+            .iter()
+            .enumerate()
+            .filter(|(_, pixel)| pixel.is_some_and(&filter))
+            .map(|(index, _)| Point::new((index % width) as i32, (index / width) as i32));
+        bounding_box(points)
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        let (width, height) = (self.size.width as i32, self.size.height as i32);
+        if point.x < 0 || point.y < 0 || point.x >= width || point.y >= height {
+            return None;
+        }
+        Some((point.y * width + point.x) as usize)
+    }
+}
+
+/// 点集的外接矩形，点集为空时返回None
+fn bounding_box(points: impl IntoIterator<Item = Point>) -> Option<Rectangle> {
+    let mut points = points.into_iter();
+    let first = points.next()?;
+    let (min, max) = points.fold((first, first), |(min, max), point| {
+        (min.component_min(point), max.component_max(point))
+    });
+    Some(Rectangle::with_corners(min, max))
+}
+
+impl DrawTarget for MockDrawTarget {
+    type Color = Rgb565;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let mut drawn = Vec::new();
+        for Pixel(point, color) in pixels {
+            drawn.push(point);
+            match self.index(point) {
+                Some(index) => self.pixels[index] = Some(color),
+                None => self.clipped += 1,
+            }
+        }
+        if let Some(bounds) = bounding_box(drawn.iter().copied()) {
+            self.calls.push(DrawCall::Pixels {
+                count: drawn.len(),
+                bounds,
+            });
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.calls.push(DrawCall::FillSolid { area: *area, color });
+        for point in area.points() {
+            match self.index(point) {
+                Some(index) => self.pixels[index] = Some(color),
+                None => self.clipped += 1,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl OriginDimensions for MockDrawTarget {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+/// [`MockI2c`]脚本中的一次传输
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum I2cTransaction {
+    /// 期望写入的数据
+    Write(Vec<u8>),
+    /// 期望写入的数据与随后读取时返回的数据
+    WriteRead(Vec<u8>, Vec<u8>),
+}
+
+/// 按脚本应答的I2C设备
+///
+/// 驱动的每次传输必须与脚本中的下一项一致，否则panic。克隆后共享同一个脚本，
+/// 把一份交给驱动，测试结束时用另一份调用`done`检查脚本已全部执行。
+#[derive(Debug, Clone, Default)]
+pub struct MockI2c {
+    script: Arc<Mutex<VecDeque<I2cTransaction>>>,
+}
+
+impl MockI2c {
+    pub fn new(script: impl IntoIterator<Item = I2cTransaction>) -> Self {
+        Self {
+            script: Arc::new(Mutex::new(script.into_iter().collect())),
+        }
+    }
+
+    /// 检查脚本已全部执行
+    pub fn done(&self) {
+        let script = self.script.lock().unwrap_or_else(PoisonError::into_inner);
+        assert!(script.is_empty(), "I2C脚本未执行完: {:?}", script);
+    }
+
+    fn next(&self, actual: &str) -> I2cTransaction {
+        self.script
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front()
+            .unwrap_or_else(|| panic!("I2C脚本已执行完，驱动又发出了{}", actual))
+    }
+}
+
+impl I2cTransport for MockI2c {
+    fn write(&self, bytes: &[u8]) -> Result<()> {
+        match self.next("写入") {
+            I2cTransaction::Write(expected) => assert_eq!(bytes, expected.as_slice()),
+            other => panic!("期望{:?}，实际写入{:02X?}", other, bytes),
+        }
+        Ok(())
+    }
+
+    fn write_read(&self, bytes: &[u8], buffer: &mut [u8]) -> Result<()> {
+        match self.next("先写后读") {
+            I2cTransaction::WriteRead(expected, response) => {
+                assert_eq!(bytes, expected.as_slice());
+                buffer.copy_from_slice(&response);
+            }
+            other => panic!("期望{:?}，实际先写{:02X?}后读", other, bytes),
+        }
+        Ok(())
+    }
+}
+
+/// 按顺序返回预设样本的运动传感器，样本用完后返回错误
+#[derive(Debug, Default)]
+pub struct ScriptedImu {
+    samples: VecDeque<SensorData>,
+}
+
+impl ScriptedImu {
+    pub fn new(samples: impl IntoIterator<Item = SensorData>) -> Self {
+        Self {
+            samples: samples.into_iter().collect(),
+        }
+    }
+
+    /// 在末尾追加`count`个相同的样本
+    pub fn repeat(mut self, sample: SensorData, count: usize) -> Self {
+        self.samples.resize(self.samples.len() + count, sample);
+        self
+    }
+}
+
+impl MotionSensor for ScriptedImu {
+    fn read_sensor_data(&mut self) -> Result<SensorData> {
+        self.samples
+            .pop_front()
+            .ok_or_else(|| anyhow::anyhow!("脚本中的样本已用完"))
+    }
+}
+
+/// 生成传感器样本
+///
+/// # 参数
+/// * `accel` - 加速度(mg)
+/// * `gyro` - 角速度(°/s)
+pub fn sensor_sample(accel: (f32, f32, f32), gyro: (f32, f32, f32)) -> SensorData {
+    SensorData {
+        accel_x: accel.0,
+        accel_y: accel.1,
+        accel_z: accel.2,
+        gyro_x: gyro.0,
+        gyro_y: gyro.1,
+        gyro_z: gyro.2,
+        temperature: 25.0,
+        timestamp: 0,
+    }
+}
+
+/// 手动推进的时钟
+#[derive(Debug, Clone, Copy)]
+pub struct FakeClock {
+    start: Instant,
+    elapsed: Duration,
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// 当前时间
+    pub fn now(&self) -> Instant {
+        self.start + self.elapsed
+    }
+
+    /// 创建后经过的毫秒数，用作传感器样本的时间戳
+    pub fn millis(&self) -> u64 {
+        self.elapsed.as_millis() as u64
+    }
+
+    /// 推进时间
+    pub fn advance(&mut self, duration: Duration) {
+        self.elapsed += duration;
+    }
+}
@@ -0,0 +1,214 @@
+// src/trace.rs
+//! 事件记录与回放
+//!
+//! 启用`event-trace`特性后，主循环把交给App的每个事件连同启动后的毫秒数写入存储
+//! （有SD卡时写SD卡），每行一个JSON：`{"ms":1234,"event":{"Motion":"Shaking"}}`。
+//! 文件超过`MAX_TRACE_BYTES`后改名为`events.trace.1`并重新开始，最多保留两段。
+//!
+//! 回放：把记录文件改名为`replay.trace`放在同一位置，重启后按原来的时间间隔重新发送其中的事件，
+//! 回放开始前文件改名为`replay.trace.done`，避免每次启动都回放。Wokwi仿真同样适用。
+//! 回放期间真实的按键和传感器事件照常到达，复现问题时保持设备静止。
+
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::actors::{intercom::IntercomEvent, spawn};
+use crate::events::{AppEvent, EventSender};
+
+/// 记录文件名
+const TRACE_FILE: &str = "events.trace";
+/// 上一段记录的文件名
+const TRACE_OLD_FILE: &str = "events.trace.1";
+/// 待回放的文件名
+const REPLAY_FILE: &str = "replay.trace";
+/// 回放过的文件改为此名
+const REPLAY_DONE_FILE: &str = "replay.trace.done";
+
+/// 单个记录文件的最大字节数
+const MAX_TRACE_BYTES: usize = 256 * 1024;
+
+/// 记录文件中的一行
+#[derive(Debug, Serialize, Deserialize)]
+struct TraceRecord<E> {
+    /// 启动记录后的毫秒数
+    ms: u64,
+    event: E,
+}
+
+/// 事件记录器
+pub struct EventRecorder {
+    /// 存储目录（挂载点）
+    dir: String,
+    file: BufWriter<File>,
+    /// 当前文件已写入的字节数
+    bytes: usize,
+    start: Instant,
+    /// 有未写入存储的记录
+    dirty: bool,
+}
+
+impl EventRecorder {
+    /// 开始记录，之前的记录保留为上一段
+    ///
+    /// # 参数
+    /// * `dir` - 存储目录，例如`/sdcard`
+    pub fn open(dir: &str) -> Result<Self> {
+        let path = format!("{}/{}", dir, TRACE_FILE);
+        if fs::metadata(&path).is_ok() {
+            fs::rename(&path, format!("{}/{}", dir, TRACE_OLD_FILE))?;
+        }
+        info!("事件记录: {}", path);
+        Ok(Self {
+            dir: dir.to_string(),
+            file: BufWriter::new(File::create(path)?),
+            bytes: 0,
+            start: Instant::now(),
+            dirty: false,
+        })
+    }
+
+    /// 记录一个事件，写入在`flush`时进行
+    pub fn record(&mut self, event: &AppEvent) {
+        let Some(line) = encode(self.start.elapsed().as_millis() as u64, event) else {
+            return;
+        };
+        if self.bytes + line.len() > MAX_TRACE_BYTES {
+            if let Err(e) = self.rotate() {
+                warn!("事件记录换段失败: {}", e);
+                return;
+            }
+        }
+        match self.file.write_all(line.as_bytes()) {
+            Ok(()) => {
+                self.bytes += line.len();
+                self.dirty = true;
+            }
+            Err(e) => warn!("写入事件记录失败: {}", e),
+        }
+    }
+
+    /// 把本轮记录的事件写入存储，主循环每轮处理完事件后调用
+    pub fn flush(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        self.dirty = false;
+        if let Err(e) = self.file.flush() {
+            warn!("写入事件记录失败: {}", e);
+        }
+    }
+
+    /// 当前文件改为上一段，重新开始
+    fn rotate(&mut self) -> Result<()> {
+        self.file.flush()?;
+        let path = format!("{}/{}", self.dir, TRACE_FILE);
+        fs::rename(&path, format!("{}/{}", self.dir, TRACE_OLD_FILE))?;
+        self.file = BufWriter::new(File::create(path)?);
+        self.bytes = 0;
+        Ok(())
+    }
+}
+
+/// 存在待回放的文件时在后台线程中回放
+///
+/// # 参数
+/// * `dir` - 存储目录
+/// * `sender` - 事件发送器，回放的事件与真实事件走同一条总线
+///
+/// # 返回值
+/// 是否开始了回放
+pub fn start_replay(dir: &str, sender: EventSender) -> Result<bool> {
+    let path = format!("{}/{}", dir, REPLAY_FILE);
+    let file = match File::open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut records = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match decode(&line) {
+            Ok(record) => records.push(record),
+            Err(e) => warn!("回放文件第{}行无效: {}", index + 1, e),
+        }
+    }
+    fs::rename(&path, format!("{}/{}", dir, REPLAY_DONE_FILE))?;
+    info!("开始回放{}个事件", records.len());
+
+    spawn::EVENT_REPLAY.spawn(move || replay(records, sender))?;
+    Ok(true)
+}
+
+/// 按记录的时间间隔发送事件
+fn replay(records: Vec<(u64, AppEvent)>, sender: EventSender) {
+    let start = Instant::now();
+    let first = records.first().map_or(0, |(ms, _)| *ms);
+    for (ms, event) in records {
+        let due = start + Duration::from_millis(ms.saturating_sub(first));
+        if let Some(wait) = due.checked_duration_since(Instant::now()) {
+            std::thread::sleep(wait);
+        }
+        if sender.send(event).is_err() {
+            return;
+        }
+    }
+    info!("回放结束");
+}
+
+/// 编码为记录文件中的一行（含换行）
+///
+/// 对讲语音数据量大且与界面状态无关，不记录。
+fn encode(ms: u64, event: &AppEvent) -> Option<String> {
+    if matches!(event, AppEvent::Intercom(IntercomEvent::Audio(_))) {
+        return None;
+    }
+    let mut line = serde_json::to_string(&TraceRecord { ms, event }).ok()?;
+    line.push('\n');
+    Some(line)
+}
+
+/// 解析记录文件中的一行
+fn decode(line: &str) -> Result<(u64, AppEvent)> {
+    let record: TraceRecord<AppEvent> = serde_json::from_str(line)?;
+    Ok((record.ms, record.event))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::UserInputEvent;
+    use crate::peripherals::qmi8658::motion_detector::MotionState;
+
+    #[test]
+    fn test_encode_decode() {
+        let events = [
+            AppEvent::Motion(MotionState::RotatingClockwise),
+            AppEvent::Input(UserInputEvent::Click(0)),
+            AppEvent::Dropped(320),
+            AppEvent::WakeWord,
+        ];
+        for (ms, event) in events.iter().enumerate() {
+            let line = encode(ms as u64 * 100, event).unwrap();
+            assert!(line.ends_with('\n'));
+            let (decoded_ms, decoded) = decode(line.trim_end()).unwrap();
+            assert_eq!(decoded_ms, ms as u64 * 100);
+            assert_eq!(format!("{:?}", decoded), format!("{:?}", event));
+        }
+        assert!(decode("{\"ms\":1}").is_err());
+    }
+
+    #[test]
+    fn test_skip_intercom_audio() {
+        let event = AppEvent::Intercom(IntercomEvent::Audio(vec![0; 320]));
+        assert!(encode(0, &event).is_none());
+    }
+}